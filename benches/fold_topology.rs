@@ -0,0 +1,60 @@
+// Fold topology benchmark
+// Wall-clock cost of folding shard digests as a chain vs. a balanced tree.
+// Tree levels are folded in parallel (one thread per pair), which is where
+// its latency advantage comes from; the chain folds on one thread.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::thread;
+
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::{recursive::FoldTopology, utils::poseidon_hash};
+
+/// Fold one tree level with one thread per pair
+fn fold_level_parallel(level: &[Fr]) -> Vec<Fr> {
+    thread::scope(|scope| {
+        let handles: Vec<_> = level
+            .chunks(2)
+            .map(|pair| {
+                scope.spawn(move || match pair {
+                    [left, right] => poseidon_hash(&[*left, *right]),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+fn benchmark_fold_topology(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fold_topology");
+    for shards in [8usize, 64, 256] {
+        let digests: Vec<Fr> = (0..shards as u64).map(Fr::from).collect();
+
+        group.bench_with_input(BenchmarkId::new("sequential", shards), &digests, |b, d| {
+            b.iter(|| FoldTopology::Sequential.fold_digests(black_box(d)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("binary_tree", shards), &digests, |b, d| {
+            b.iter(|| {
+                let mut level = black_box(d).to_vec();
+                while level.len() > 1 {
+                    level = fold_level_parallel(&level);
+                }
+                level.pop()
+            })
+        });
+
+        let seq = FoldTopology::Sequential.cost(shards);
+        let tree = FoldTopology::BinaryTree.cost(shards);
+        println!(
+            "{} shards: sequential {} rounds / {} live, binary tree {} rounds / {} live",
+            shards, seq.rounds, seq.peak_live, tree.rounds, tree.peak_live
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_fold_topology);
+criterion_main!(benches);
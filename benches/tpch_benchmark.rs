@@ -2,14 +2,14 @@
 // Paper: TPCH benchmark queries for performance evaluation
 // Small, medium, large scale tests
 
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::collections::HashMap;
+use std::hint::black_box;
 
 use halo2_proofs::{circuit::Value, pasta::EqAffine, poly::commitment::Params};
 use pasta_curves::pallas::Base as Fr;
 use poneglyphdb::{
-    circuit::PoneglyphCircuit,
-    database::DatabaseCommitment,
+    circuit::{PoneglyphCircuit, TableScan, ThresholdMode},
     prover::{MockProverHelper, Prover, Verifier},
     sql::{SQLCompiler, SQLParser},
 };
@@ -25,6 +25,12 @@ pub struct TPCHBenchmark {
     pub large_scale: HashMap<String, HashMap<String, Vec<u64>>>,
 }
 
+impl Default for TPCHBenchmark {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TPCHBenchmark {
     /// Create new TPCH benchmark suite
     pub fn new() -> Self {
@@ -199,6 +205,19 @@ fn benchmark_sql_compilation(c: &mut Criterion) {
     group.finish();
 }
 
+/// Rows of `table`, columns in name order
+fn table_scan(table_data: &HashMap<String, HashMap<String, Vec<u64>>>, table: &str) -> TableScan {
+    let mut columns: Vec<(&String, &Vec<u64>)> = table_data[table].iter().collect();
+    columns.sort();
+    let rows = columns.first().map_or(0, |(_, values)| values.len());
+    TableScan::new(
+        columns.iter().map(|(name, _)| name.to_string()).collect(),
+        (0..rows)
+            .map(|row| columns.iter().map(|(_, values)| values[row]).collect())
+            .collect(),
+    )
+}
+
 /// Benchmark: Circuit Synthesis (Mock Prover)
 fn benchmark_circuit_synthesis(c: &mut Criterion) {
     let benchmark = TPCHBenchmark::new();
@@ -225,22 +244,20 @@ fn benchmark_circuit_synthesis(c: &mut Criterion) {
             let query = SQLParser::parse(&query_str).unwrap();
             let compiled = SQLCompiler::compile(&query, table_data).unwrap();
 
-            // Create database commitment
-            let db_data: Vec<(u64, u64)> = table_data
-                .values()
-                .flat_map(|t| {
-                    t.values()
-                        .flatten()
-                        .enumerate()
-                        .map(|(i, &v)| (i as u64, v))
-                        .collect::<Vec<_>>()
-                })
-                .collect();
-            let db_commitment = DatabaseCommitment::new(&db_data);
+            // Scanned table, hashed into the database commitment in-circuit
+            let scan = table_scan(table_data, &query.from);
+            let db_commitment = scan.commitment();
 
             let circuit = PoneglyphCircuit {
-                db_commitment: Value::known(db_commitment.commitment),
-                query_result: Value::unknown(),
+                scan,
+                query_result: Value::known(Fr::zero()),
+                nonce: Value::known(Fr::zero()),
+                expiry: Value::known(Fr::zero()),
+                query_hash: Fr::zero(),
+                result_commitment: Value::known(Fr::zero()),
+                result_columns: vec![],
+                result_predicate: None,
+                threshold_mode: ThresholdMode::Fixed,
                 range_checks: compiled.range_checks,
                 sorts: compiled.sorts,
                 group_bys: compiled.group_bys,
@@ -264,8 +281,8 @@ fn benchmark_circuit_synthesis(c: &mut Criterion) {
                         // Circuit has only 1 instance column
                         // Row 0: db_commitment, Row 1: query_result
                         let public_inputs = vec![vec![
-                            db_commitment.commitment, // Row 0
-                            Fr::zero(),               // Row 1: Placeholder query result
+                            db_commitment, // Row 0
+                            Fr::zero(),    // Row 1: Placeholder query result
                         ]];
                         black_box(
                             MockProverHelper::mock_prove_and_verify(circ, &public_inputs, k)
@@ -290,22 +307,20 @@ fn benchmark_proof_generation(c: &mut Criterion) {
     let query = SQLParser::parse(&query_str).unwrap();
     let compiled = SQLCompiler::compile(&query, table_data).unwrap();
 
-    // Create database commitment
-    let db_data: Vec<(u64, u64)> = table_data
-        .values()
-        .flat_map(|t| {
-            t.values()
-                .flatten()
-                .enumerate()
-                .map(|(i, &v)| (i as u64, v))
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let db_commitment = DatabaseCommitment::new(&db_data);
+    // Scanned table, hashed into the database commitment in-circuit
+    let scan = table_scan(table_data, &query.from);
+    let db_commitment = scan.commitment();
 
     let circuit = PoneglyphCircuit {
-        db_commitment: Value::known(db_commitment.commitment),
-        query_result: Value::unknown(),
+        scan,
+        query_result: Value::known(Fr::zero()),
+        nonce: Value::known(Fr::zero()),
+        expiry: Value::known(Fr::zero()),
+        query_hash: Fr::zero(),
+        result_commitment: Value::known(Fr::zero()),
+        result_columns: vec![],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
         range_checks: compiled.range_checks,
         sorts: compiled.sorts,
         group_bys: compiled.group_bys,
@@ -320,14 +335,14 @@ fn benchmark_proof_generation(c: &mut Criterion) {
     let verifier = Verifier::new(&params, &circuit).unwrap();
 
     let public_inputs = vec![
-        vec![db_commitment.commitment],
+        vec![db_commitment],
         vec![Fr::zero()], // Placeholder query result
     ];
 
     c.bench_function("proof_generation", |b| {
         b.iter(|| {
             let proof = black_box(prover.prove(&params, &circuit, &public_inputs).unwrap());
-            black_box(verifier.verify(&params, &proof, &public_inputs).unwrap());
+            verifier.verify(&params, &proof, &public_inputs).unwrap();
         });
     });
 }
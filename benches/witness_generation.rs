@@ -0,0 +1,106 @@
+// Witness generation benchmark
+// Host-side witness precomputation (`circuit::witness`) against a
+// single-threaded scalar reference loop over the same rows (one shift and
+// mask per chunk). Without the `parallel` feature
+// both run on one thread, and the difference is vectorization alone; with it
+// the library side also runs on rayon:
+//
+//   cargo bench --bench witness_generation
+//   cargo bench --bench witness_generation --features parallel
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+
+use poneglyphdb::circuit::{witness, AggregationType};
+
+/// Deterministic pseudo-random column
+fn column(rows: usize, modulus: u64) -> Vec<u64> {
+    (0..rows as u64)
+        .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) % modulus)
+        .collect()
+}
+
+fn benchmark_decomposition(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chunk_decomposition");
+    for rows in [10_000usize, 100_000, 1_000_000] {
+        let values = column(rows, u64::MAX);
+        group.bench_with_input(BenchmarkId::new("reference", rows), &values, |b, v| {
+            b.iter(|| {
+                black_box(v)
+                    .iter()
+                    .map(|&v| {
+                        let mut chunks = [0u64; 8];
+                        for (i, chunk) in chunks.iter_mut().enumerate() {
+                            *chunk = (v >> (i * 8)) & 0xFF;
+                        }
+                        chunks
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("witness", rows), &values, |b, v| {
+            b.iter(|| witness::decompose_rows(black_box(v)))
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_permutation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permutation_witness");
+    for rows in [10_000usize, 100_000, 1_000_000] {
+        let values = column(rows, 1 << 40);
+        group.bench_with_input(BenchmarkId::new("reference", rows), &values, |b, v| {
+            b.iter(|| {
+                let mut sorted = black_box(v).to_vec();
+                sorted.sort_unstable();
+                let diffs: Vec<u64> = sorted.windows(2).map(|w| w[1] - w[0]).collect();
+                (sorted, diffs)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("witness", rows), &values, |b, v| {
+            b.iter(|| {
+                let sorted = witness::sorted(black_box(v));
+                let diffs = witness::adjacent_diffs(&sorted);
+                (sorted, diffs)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_group_accumulators(c: &mut Criterion) {
+    let mut group = c.benchmark_group("group_accumulators");
+    for rows in [10_000usize, 100_000, 1_000_000] {
+        // ~100 rows per group
+        let keys: Vec<u64> = (0..rows as u64).map(|i| i / 100).collect();
+        let values = column(rows, 1 << 20);
+        group.bench_with_input(
+            BenchmarkId::new("reference", rows),
+            &(&keys, &values),
+            |b, (k, v)| {
+                b.iter(|| {
+                    let mut sums = Vec::with_capacity(v.len());
+                    for i in 0..v.len() {
+                        let continues = i > 0 && k[i] == k[i - 1];
+                        sums.push(if continues { sums[i - 1] + v[i] } else { v[i] });
+                    }
+                    black_box(sums)
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("witness", rows),
+            &(&keys, &values),
+            |b, (k, v)| b.iter(|| witness::group_accumulators(k, v, &AggregationType::Sum)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_decomposition,
+    benchmark_permutation,
+    benchmark_group_accumulators
+);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+// Print the constraint system documentation
+//
+//     cargo run --example constraint_docs --features constraint-docs [-- --json]
+
+use poneglyphdb::circuit::ConstraintDocs;
+
+fn main() {
+    let docs = ConstraintDocs::generate();
+    if std::env::args().any(|arg| arg == "--json") {
+        match docs.to_json() {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        print!("{}", docs.to_markdown());
+    }
+}
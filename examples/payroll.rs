@@ -0,0 +1,15 @@
+// Payroll audit, end to end: CSV -> commitment -> SQL -> proof -> verification
+//
+//     cargo run --release --example payroll
+
+use poneglyphdb::examples_lib::run_payroll_demo;
+
+fn main() {
+    match run_payroll_demo() {
+        Ok(report) => print!("{}", report),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
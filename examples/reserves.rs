@@ -0,0 +1,15 @@
+// Proof of reserves, end to end: JSON -> commitment -> SQL -> proof -> verification
+//
+//     cargo run --release --example reserves
+
+use poneglyphdb::examples_lib::run_reserves_demo;
+
+fn main() {
+    match run_reserves_demo() {
+        Ok(report) => print!("{}", report),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
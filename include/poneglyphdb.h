@@ -0,0 +1,94 @@
+#ifndef PONEGLYPHDB_H
+#define PONEGLYPHDB_H
+
+/* Generated with cbindgen:0.27.0 */
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The proof verifies
+ */
+#define PDB_VALID 0
+
+/**
+ * The proof does not verify against the instances
+ */
+#define PDB_INVALID_PROOF 1
+
+/**
+ * The instances decode, but not to what the verifier expects
+ */
+#define PDB_INSTANCE_MISMATCH 2
+
+/**
+ * The instances commit to another or an unanchored database
+ */
+#define PDB_COMMITMENT_MISMATCH 3
+
+/**
+ * The proof was made for another circuit than the verifying key
+ */
+#define PDB_WRONG_CIRCUIT_VERSION 4
+
+/**
+ * The proof, verifying key or instances can't be decoded
+ */
+#define PDB_MALFORMED 5
+
+/**
+ * A buffer is null but its length is not zero
+ */
+#define PDB_NULL_ARGUMENT -1
+
+/**
+ * Verification panicked (a bug; please report it with the inputs)
+ */
+#define PDB_PANIC -2
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Verify a proof
+ *
+ * `proof` and `vk` are the bytes of p.bin and vk.bin, `instances` the UTF-8
+ * text of i.json. Returns `PDB_VALID` or one of the `PDB_*` codes; the
+ * reason for a non-zero code is available from `pdb_last_error`.
+ *
+ * # Safety
+ *
+ * Each pointer must be valid for reads of its length in bytes, or null with
+ * a length of zero.
+ */
+int32_t pdb_verify(const uint8_t *proof_ptr,
+                   size_t proof_len,
+                   const uint8_t *vk_ptr,
+                   size_t vk_len,
+                   const uint8_t *instances_ptr,
+                   size_t instances_len);
+
+/**
+ * Reason for the last non-zero `pdb_verify` return on this thread
+ *
+ * Copies the message into `buf` as a NUL-terminated string, truncated to
+ * `buf_len - 1` bytes, and returns its full length without the NUL. Call with
+ * a null `buf` to size the buffer. The message is empty after `PDB_VALID`.
+ *
+ * # Safety
+ *
+ * `buf` must be valid for writes of `buf_len` bytes, or null.
+ */
+size_t pdb_last_error(char *buf, size_t buf_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PONEGLYPHDB_H */
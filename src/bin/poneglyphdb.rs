@@ -0,0 +1,697 @@
+// poneglyphdb command-line interface
+// Drives commit -> prove -> verify through files, so the engine can be used
+// without writing Rust:
+//
+//   poneglyphdb commit orders.csv                      -> orders.db
+//   poneglyphdb prove --query "SELECT SUM(price) FROM orders" --db orders.db
+//                                                      -> p.bin, vk.bin, i.json
+//   poneglyphdb verify --proof p.bin --vk vk.bin --instances i.json
+//   poneglyphdb evm --proof p.bin --vk vk.bin --instances i.json
+//                                                      -> calldata.bin, PoneglyphGate.sol
+//   poneglyphdb catalog --db orders.db                 -> JSON listing
+//   poneglyphdb serve --db orders.db --workers 4       -> HTTP proving service
+//   poneglyphdb repl --db orders.db                    -> interactive shell
+//
+// A committed database file is one `StoredTable` (the table with its
+// commitment). The verifying key is a `RawVerifyingKey` and the instances use
+// the test-vector encoding (hex field elements), so `verify` needs neither the
+// tables nor the query.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use poneglyphdb::circuit::PublicInputs;
+use poneglyphdb::database::{Database, StoredTable, TypeInference};
+use poneglyphdb::error::{PoneglyphError, PoneglyphResult};
+use poneglyphdb::prover::{
+    field_to_hex, instance_from_hex, instance_to_hex, solidity_gate, verify_raw, EvmProof, Proof,
+    ProofPreset, Session,
+};
+
+/// Circuit size the prover starts with (2^k rows)
+const DEFAULT_K: u32 = 12;
+
+/// Listening address and worker count of `serve`
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+const DEFAULT_WORKERS: u32 = 2;
+
+const USAGE: &str = "\
+Usage:
+  poneglyphdb commit <file.csv|file.json> [--table NAME] [--out FILE]
+  poneglyphdb prove --query SQL --db FILE [--db FILE ...] [--k K] [--max-k K] [--out-dir DIR]
+                    [--preset NAME]
+  poneglyphdb verify --proof FILE --vk FILE --instances FILE
+  poneglyphdb evm --proof FILE --vk FILE --instances FILE [--out-dir DIR]
+  poneglyphdb catalog --db FILE [--db FILE ...]
+  poneglyphdb serve [--addr ADDR] [--workers N] [--db FILE ...] [--k K] [--max-k K]
+                    [--admin-key KEY] [--preset NAME]
+  poneglyphdb repl [--db FILE ...] [--k K] [--max-k K] [--preset NAME]
+
+commit   Load a CSV or JSON file (column types are inferred), commit it and
+         write the committed table (default: <file stem>.db)
+prove    Prove a query over committed tables; writes p.bin, vk.bin and i.json
+verify   Verify a proof; exits with status 0 if it is valid
+evm      Verify a proof, then export it as EVM calldata (calldata.bin) with a
+         Solidity gate contract for its query (PoneglyphGate.sol)
+catalog  Print the tables, versions, commitments and column statistics of
+         committed tables as JSON
+serve    Run the HTTP proving service (feature `server`; default address
+         127.0.0.1:8080, 2 workers); with --admin-key, requests need an API key
+repl     Interactive SQL shell showing proving and verification times
+         (feature `cli`); \\? lists its commands
+
+--preset picks the proving settings together: fast-prove (shortest proving
+time), small-proof (fewest proof bytes) or cheap-verify (least verifier work)";
+
+/// Parsed command line
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Commit {
+        input: PathBuf,
+        table: Option<String>,
+        out: Option<PathBuf>,
+    },
+    Prove {
+        query: String,
+        databases: Vec<PathBuf>,
+        k: u32,
+        max_k: Option<u32>,
+        out_dir: PathBuf,
+        preset: Option<ProofPreset>,
+    },
+    Verify {
+        proof: PathBuf,
+        vk: PathBuf,
+        instances: PathBuf,
+    },
+    Evm {
+        proof: PathBuf,
+        vk: PathBuf,
+        instances: PathBuf,
+        out_dir: PathBuf,
+    },
+    Catalog {
+        databases: Vec<PathBuf>,
+    },
+    Serve {
+        address: String,
+        workers: u32,
+        databases: Vec<PathBuf>,
+        k: u32,
+        max_k: Option<u32>,
+        admin_key: Option<String>,
+        preset: Option<ProofPreset>,
+    },
+    Repl {
+        databases: Vec<PathBuf>,
+        k: u32,
+        max_k: Option<u32>,
+        preset: Option<ProofPreset>,
+    },
+    Help,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match run(command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (name, rest) = args.split_first().ok_or("missing command")?;
+    let mut positional = Vec::new();
+    let mut options: Vec<(&str, &str)> = Vec::new();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        if arg.starts_with("--") {
+            let value = rest
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))?;
+            options.push((arg.as_str(), value.as_str()));
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+    let known =
+        |allowed: &[&str]| match options.iter().find(|(option, _)| !allowed.contains(option)) {
+            Some((option, _)) => Err(format!("unknown option {} for {}", option, name)),
+            None => Ok(()),
+        };
+    let last = |option: &str| {
+        options
+            .iter()
+            .rev()
+            .find(|(o, _)| *o == option)
+            .map(|(_, value)| *value)
+    };
+    let required = |option: &str| last(option).ok_or_else(|| format!("{} is required", option));
+    let all_databases = || -> Vec<PathBuf> {
+        options
+            .iter()
+            .filter(|(option, _)| *option == "--db")
+            .map(|(_, path)| PathBuf::from(path))
+            .collect()
+    };
+    let databases = || {
+        let databases = all_databases();
+        if databases.is_empty() {
+            return Err("--db is required".to_string());
+        }
+        Ok(databases)
+    };
+    let size = |option: &str| {
+        last(option)
+            .map(|k| {
+                k.parse::<u32>()
+                    .map_err(|_| format!("{} must be a number, got {}", option, k))
+            })
+            .transpose()
+    };
+    let preset = || {
+        last("--preset")
+            .map(|name| {
+                ProofPreset::from_name(name).ok_or_else(|| format!("unknown preset {}", name))
+            })
+            .transpose()
+    };
+
+    match name.as_str() {
+        "commit" => {
+            known(&["--table", "--out"])?;
+            let input = match positional.as_slice() {
+                [input] => PathBuf::from(input),
+                _ => return Err("commit takes one input file".to_string()),
+            };
+            Ok(Command::Commit {
+                input,
+                table: last("--table").map(str::to_string),
+                out: last("--out").map(PathBuf::from),
+            })
+        }
+        "prove" => {
+            known(&["--query", "--db", "--k", "--max-k", "--out-dir", "--preset"])?;
+            if !positional.is_empty() {
+                return Err("prove takes no positional arguments".to_string());
+            }
+            Ok(Command::Prove {
+                query: required("--query")?.to_string(),
+                databases: databases()?,
+                k: size("--k")?.unwrap_or(DEFAULT_K),
+                max_k: size("--max-k")?,
+                out_dir: PathBuf::from(last("--out-dir").unwrap_or(".")),
+                preset: preset()?,
+            })
+        }
+        "verify" => {
+            known(&["--proof", "--vk", "--instances"])?;
+            if !positional.is_empty() {
+                return Err("verify takes no positional arguments".to_string());
+            }
+            Ok(Command::Verify {
+                proof: PathBuf::from(required("--proof")?),
+                vk: PathBuf::from(required("--vk")?),
+                instances: PathBuf::from(required("--instances")?),
+            })
+        }
+        "evm" => {
+            known(&["--proof", "--vk", "--instances", "--out-dir"])?;
+            if !positional.is_empty() {
+                return Err("evm takes no positional arguments".to_string());
+            }
+            Ok(Command::Evm {
+                proof: PathBuf::from(required("--proof")?),
+                vk: PathBuf::from(required("--vk")?),
+                instances: PathBuf::from(required("--instances")?),
+                out_dir: PathBuf::from(last("--out-dir").unwrap_or(".")),
+            })
+        }
+        "catalog" => {
+            known(&["--db"])?;
+            if !positional.is_empty() {
+                return Err("catalog takes no positional arguments".to_string());
+            }
+            Ok(Command::Catalog {
+                databases: databases()?,
+            })
+        }
+        "serve" => {
+            known(&[
+                "--addr",
+                "--workers",
+                "--db",
+                "--k",
+                "--max-k",
+                "--admin-key",
+                "--preset",
+            ])?;
+            if !positional.is_empty() {
+                return Err("serve takes no positional arguments".to_string());
+            }
+            Ok(Command::Serve {
+                address: last("--addr").unwrap_or(DEFAULT_ADDRESS).to_string(),
+                workers: size("--workers")?.unwrap_or(DEFAULT_WORKERS).max(1),
+                databases: all_databases(),
+                k: size("--k")?.unwrap_or(DEFAULT_K),
+                max_k: size("--max-k")?,
+                admin_key: last("--admin-key").map(str::to_string),
+                preset: preset()?,
+            })
+        }
+        "repl" => {
+            known(&["--db", "--k", "--max-k", "--preset"])?;
+            if !positional.is_empty() {
+                return Err("repl takes no positional arguments".to_string());
+            }
+            Ok(Command::Repl {
+                databases: all_databases(),
+                k: size("--k")?.unwrap_or(DEFAULT_K),
+                max_k: size("--max-k")?,
+                preset: preset()?,
+            })
+        }
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(format!("unknown command {}", other)),
+    }
+}
+
+fn run(command: Command) -> PoneglyphResult<()> {
+    match command {
+        Command::Commit { input, table, out } => commit(&input, table, out),
+        Command::Prove {
+            query,
+            databases,
+            k,
+            max_k,
+            out_dir,
+            preset,
+        } => prove(&query, &databases, k, max_k, &out_dir, preset),
+        Command::Verify {
+            proof,
+            vk,
+            instances,
+        } => verify(&proof, &vk, &instances),
+        Command::Evm {
+            proof,
+            vk,
+            instances,
+            out_dir,
+        } => evm(&proof, &vk, &instances, &out_dir),
+        Command::Catalog { databases } => catalog(&databases),
+        Command::Serve {
+            address,
+            workers,
+            databases,
+            k,
+            max_k,
+            admin_key,
+            preset,
+        } => serve(&address, workers, &databases, k, max_k, admin_key, preset),
+        Command::Repl {
+            databases,
+            k,
+            max_k,
+            preset,
+        } => repl(&databases, k, max_k, preset),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+fn commit(input: &Path, table: Option<String>, out: Option<PathBuf>) -> PoneglyphResult<()> {
+    let stem = input
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| {
+            PoneglyphError::InvalidInput(format!("{}: no file name", input.display()))
+        })?;
+    let name = table.unwrap_or_else(|| stem.to_string());
+    let inference = TypeInference::new();
+    let mut database = Database::new();
+    let (_, columns) = match input.extension().and_then(|e| e.to_str()) {
+        Some("json") => database.load_json_inferred(&name, input, &inference)?,
+        _ => database.load_csv_inferred(&name, input, &inference)?,
+    };
+    let stored = database
+        .table(&name)
+        .map(StoredTable::new)
+        .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", name)))?;
+
+    let out = out.unwrap_or_else(|| input.with_extension("db"));
+    write(&out, &stored.to_bytes()?)?;
+    for column in &columns {
+        println!("{}: {:?}", column.name, column.ty);
+    }
+    println!("table {}: {} rows", name, stored.table()?.data.len());
+    println!(
+        "commitment {}",
+        field_to_hex(&stored.commitment()?.commitment())
+    );
+    println!("wrote {}", out.display());
+    Ok(())
+}
+
+fn prove(
+    sql: &str,
+    databases: &[PathBuf],
+    k: u32,
+    max_k: Option<u32>,
+    out_dir: &Path,
+    preset: Option<ProofPreset>,
+) -> PoneglyphResult<()> {
+    let mut session = Session::new(k);
+    if let Some(max_k) = max_k {
+        session.execute(&format!("SET max_k = {}", max_k))?;
+    }
+    if let Some(preset) = preset {
+        session.set_preset(preset);
+    }
+    load(&mut session, databases)?;
+
+    let portable = session.prove_portable(sql)?;
+    fs::create_dir_all(out_dir).map_err(|e| io_error(out_dir, e))?;
+    let instances = instance_to_hex(&portable.public_inputs.to_instance());
+    let instances = serde_json::to_string_pretty(&instances)
+        .map_err(|e| PoneglyphError::Serialization(e.to_string()))?;
+    write(&out_dir.join("p.bin"), &portable.proof.to_bytes()?)?;
+    write(&out_dir.join("vk.bin"), &portable.verifying_key.to_bytes()?)?;
+    write(&out_dir.join("i.json"), instances.as_bytes())?;
+
+    println!("{}", portable.result.columns.join("\t"));
+    for row in &portable.result.rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| cell.map_or("NULL".to_string(), |v| v.to_string()))
+            .collect();
+        println!("{}", cells.join("\t"));
+    }
+    println!(
+        "wrote p.bin, vk.bin and i.json to {} (k = {})",
+        out_dir.display(),
+        portable.proof.k
+    );
+    Ok(())
+}
+
+fn verify(proof: &Path, vk: &Path, instances: &Path) -> PoneglyphResult<()> {
+    let inputs = read_instances(instances)?;
+    verify_raw(&read(vk)?, &inputs.to_bytes()?, &read(proof)?)
+        .map_err(|e| PoneglyphError::Validation(format!("proof rejected: {}", e)))?;
+    println!("valid");
+    Ok(())
+}
+
+fn evm(proof: &Path, vk: &Path, instances: &Path, out_dir: &Path) -> PoneglyphResult<()> {
+    // Exporting an invalid proof would only move the failure on-chain
+    let inputs = read_instances(instances)?;
+    let proof_bytes = read(proof)?;
+    verify_raw(&read(vk)?, &inputs.to_bytes()?, &proof_bytes)
+        .map_err(|e| PoneglyphError::Validation(format!("proof rejected: {}", e)))?;
+
+    let evm = EvmProof::new(&Proof::from_bytes(&proof_bytes)?, &inputs);
+    fs::create_dir_all(out_dir).map_err(|e| io_error(out_dir, e))?;
+    write(&out_dir.join("calldata.bin"), &evm.to_calldata())?;
+    write(
+        &out_dir.join("PoneglyphGate.sol"),
+        solidity_gate(&evm)?.as_bytes(),
+    )?;
+    println!("circuit version {:#018x}", evm.circuit_version);
+    println!("query hash {}", field_to_hex(&inputs.query_hash));
+    println!(
+        "wrote calldata.bin and PoneglyphGate.sol to {}",
+        out_dir.display()
+    );
+    Ok(())
+}
+
+fn catalog(databases: &[PathBuf]) -> PoneglyphResult<()> {
+    // Nothing is proven, so the parameters can be tiny
+    let mut session = Session::new(4);
+    load(&mut session, databases)?;
+    let json = session
+        .catalog_listing()
+        .to_json()
+        .map_err(PoneglyphError::Serialization)?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+fn serve(
+    address: &str,
+    workers: u32,
+    databases: &[PathBuf],
+    k: u32,
+    max_k: Option<u32>,
+    admin_key: Option<String>,
+    preset: Option<ProofPreset>,
+) -> PoneglyphResult<()> {
+    use std::sync::Arc;
+
+    use halo2_proofs::poly::commitment::Params;
+    use poneglyphdb::prover::ApiKeys;
+    use poneglyphdb::server::{self, ProvingService};
+
+    let mut service = ProvingService::new(Params::new(k));
+    if let Some(max_k) = max_k {
+        service = service.with_max_k(max_k);
+    }
+    if let Some(key) = admin_key {
+        service = service.with_api_keys(ApiKeys::with_admin(&key));
+    }
+    if let Some(preset) = preset {
+        service = service.with_preset(preset);
+    }
+    for path in databases {
+        let listing = service
+            .register(&read(path)?)
+            .map_err(|e| PoneglyphError::Validation(format!("{}: {}", path.display(), e)))?;
+        println!("table {} (version {})", listing.name, listing.version);
+    }
+    let service = Arc::new(service);
+    let _workers = service.start(workers as usize);
+    println!("listening on {} with {} workers", address, workers);
+    server::serve(service, address)
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(
+    _address: &str,
+    _workers: u32,
+    _databases: &[PathBuf],
+    _k: u32,
+    _max_k: Option<u32>,
+    _admin_key: Option<String>,
+    _preset: Option<ProofPreset>,
+) -> PoneglyphResult<()> {
+    Err(PoneglyphError::Configuration(
+        "serve needs a build with the `server` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "cli")]
+fn repl(
+    databases: &[PathBuf],
+    k: u32,
+    max_k: Option<u32>,
+    preset: Option<ProofPreset>,
+) -> PoneglyphResult<()> {
+    use poneglyphdb::repl::{Repl, ReplStep};
+    use rustyline::error::ReadlineError;
+
+    let mut session = Session::new(k);
+    if let Some(max_k) = max_k {
+        session.execute(&format!("SET max_k = {}", max_k))?;
+    }
+    if let Some(preset) = preset {
+        session.set_preset(preset);
+    }
+    load(&mut session, databases)?;
+    let mut repl = Repl::new(session);
+    let mut editor = rustyline::DefaultEditor::new()
+        .map_err(|e| PoneglyphError::Configuration(e.to_string()))?;
+    println!("PoneglyphDB shell; \\? for help, \\q to quit");
+    loop {
+        let line = match editor.readline(repl.prompt()) {
+            Ok(line) => line,
+            // Ctrl-C drops the statement being typed
+            Err(ReadlineError::Interrupted) => {
+                repl.feed("\\c");
+                continue;
+            }
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(PoneglyphError::Configuration(e.to_string())),
+        };
+        if !line.trim().is_empty() {
+            let _ = editor.add_history_entry(line.as_str());
+        }
+        match repl.feed(&line) {
+            ReplStep::Print(output) if output.is_empty() => {}
+            ReplStep::Print(output) => println!("{}", output.trim_end()),
+            ReplStep::Quit => return Ok(()),
+        }
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+fn repl(
+    _databases: &[PathBuf],
+    _k: u32,
+    _max_k: Option<u32>,
+    _preset: Option<ProofPreset>,
+) -> PoneglyphResult<()> {
+    Err(PoneglyphError::Configuration(
+        "repl needs a build with the `cli` feature".to_string(),
+    ))
+}
+
+/// Register committed tables, checking each file's integrity first
+fn load(session: &mut Session, databases: &[PathBuf]) -> PoneglyphResult<()> {
+    for path in databases {
+        let stored = StoredTable::from_bytes(&read(path)?)?;
+        let report = stored.verify_integrity();
+        if !report.is_intact() {
+            return Err(PoneglyphError::Validation(format!(
+                "{}: {}",
+                path.display(),
+                report
+            )));
+        }
+        session.register_table(stored.table()?);
+    }
+    Ok(())
+}
+
+/// Public inputs of an i.json file
+fn read_instances(path: &Path) -> PoneglyphResult<PublicInputs> {
+    let json = read(path)?;
+    let instances: Vec<Vec<String>> = serde_json::from_slice(&json)
+        .map_err(|e| PoneglyphError::Serialization(format!("{}: {}", path.display(), e)))?;
+    PublicInputs::from_instance(&instance_from_hex(&instances)?)
+        .ok_or_else(|| PoneglyphError::Serialization("instances do not decode".to_string()))
+}
+
+fn read(path: &Path) -> PoneglyphResult<Vec<u8>> {
+    fs::read(path).map_err(|e| io_error(path, e))
+}
+
+fn write(path: &Path, bytes: &[u8]) -> PoneglyphResult<()> {
+    fs::write(path, bytes).map_err(|e| io_error(path, e))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> PoneglyphError {
+    PoneglyphError::Serialization(format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(&args("commit orders.csv --out committed.db")),
+            Ok(Command::Commit {
+                input: PathBuf::from("orders.csv"),
+                table: None,
+                out: Some(PathBuf::from("committed.db")),
+            })
+        );
+
+        let mut prove = args("prove --db orders.db --db users.db --k 10 --query");
+        prove.push("SELECT SUM(price) FROM orders".to_string());
+        assert_eq!(
+            parse_args(&prove),
+            Ok(Command::Prove {
+                query: "SELECT SUM(price) FROM orders".to_string(),
+                databases: vec![PathBuf::from("orders.db"), PathBuf::from("users.db")],
+                k: 10,
+                max_k: None,
+                out_dir: PathBuf::from("."),
+                preset: None,
+            })
+        );
+
+        assert_eq!(
+            parse_args(&args("verify --proof p.bin --vk vk.bin --instances i.json")),
+            Ok(Command::Verify {
+                proof: PathBuf::from("p.bin"),
+                vk: PathBuf::from("vk.bin"),
+                instances: PathBuf::from("i.json"),
+            })
+        );
+
+        assert_eq!(
+            parse_args(&args(
+                "evm --proof p.bin --vk vk.bin --instances i.json --out-dir out"
+            )),
+            Ok(Command::Evm {
+                proof: PathBuf::from("p.bin"),
+                vk: PathBuf::from("vk.bin"),
+                instances: PathBuf::from("i.json"),
+                out_dir: PathBuf::from("out"),
+            })
+        );
+
+        assert_eq!(
+            parse_args(&args("catalog --db orders.db --db users.db")),
+            Ok(Command::Catalog {
+                databases: vec![PathBuf::from("orders.db"), PathBuf::from("users.db")],
+            })
+        );
+
+        assert_eq!(
+            parse_args(&args(
+                "serve --workers 4 --db orders.db --preset cheap-verify"
+            )),
+            Ok(Command::Serve {
+                address: DEFAULT_ADDRESS.to_string(),
+                workers: 4,
+                databases: vec![PathBuf::from("orders.db")],
+                k: DEFAULT_K,
+                max_k: None,
+                admin_key: None,
+                preset: Some(ProofPreset::CheapVerify),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&[]).is_err());
+        assert!(parse_args(&args("commit")).is_err());
+        assert!(parse_args(&args("commit a.csv b.csv")).is_err());
+        assert!(parse_args(&args("prove --query x")).is_err());
+        assert!(parse_args(&args("prove --db a.db --query x --k twelve")).is_err());
+        assert!(parse_args(&args("verify --proof p.bin --vk vk.bin")).is_err());
+        assert!(parse_args(&args(
+            "verify --proof p.bin --vk vk.bin --instances i.json --k 3"
+        ))
+        .is_err());
+        assert!(parse_args(&args("evm --proof p.bin --instances i.json")).is_err());
+        assert!(parse_args(&args("catalog")).is_err());
+        assert!(parse_args(&args("serve --workers many")).is_err());
+        assert!(parse_args(&args("repl --query x")).is_err());
+        assert!(parse_args(&args("repl --preset tiny")).is_err());
+        assert!(parse_args(&args("catalog orders.db")).is_err());
+        assert!(parse_args(&args("prune")).is_err());
+    }
+}
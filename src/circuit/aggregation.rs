@@ -1,17 +1,52 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
     poly::Rotation,
 };
 use pasta_curves::pallas::Base as Fr;
-use ff::Field;
 
 use super::config::PoneglyphConfig;
 use super::group_by::GroupByConfig;
 use super::range_check::RangeCheckConfig;
 
+/// (value cells, result cells) of one aggregated measure
+type MeasureCells<F> = (Vec<AssignedCell<F, F>>, Vec<AssignedCell<F, F>>);
+
 /// Aggregation Gate Configuration
 /// According to Paper Section 4.5: SUM, COUNT, MAX, MIN operations
+///
+/// # Dispersion (VARIANCE / STDDEV)
+///
+/// Running rows (one per input row):
+/// - `value_column` v (advice[8]), `result_column` S1 (advice[9])
+/// - `same_column`: Group-By boundary of the previous pair, copied (advice[10])
+/// - `square_sum_column`: S2 (advice[11])
+///
+/// Division row (one per group): S1 (advice[9]), S2 (advice[11]),
+/// `quotient_column` q (advice[12]), `remainder_column` r (advice[13]),
+/// `slack_column` (advice[14]), `count_column` n (fixed[0]),
+/// `factor_column` F (fixed[1])
+///
+/// Square root row (STDDEV only): q (advice[12]), result sd (advice[9]),
+/// `sd² ≤ q` slack (advice[13]), `q < (sd + 1)²` slack (advice[14])
+///
+/// # Dispersion Constraints
+///
+/// 1. **Start**: `S1 = v`, `S2 = v²`
+/// 2. **Step**: `S1 = same · S1_prev + v`, `S2 = same · S2_prev + v²`
+/// 3. **Division**: `F · (n · S2 - S1²) = q · n² + r`, `slack = n² - 1 - r`
+/// 4. **Square root**: `lo = q - sd²`, `hi = sd² + 2·sd - q`
+///
+/// v, q, r, slack, sd, lo and hi are decomposed into 8-bit chunks, so q is
+/// the exact quotient and sd the integer square root (`sd² ≤ q < (sd + 1)²`).
+///
+/// # Note
+///
+/// - Dispersion columns are shared with Join Gate (used in different rows)
+/// - Results must fit in 64 bits (see `AggregationType::dispersion`)
 #[derive(Clone, Debug)]
 pub struct AggregationConfig {
     // Value column - for values to be aggregated
@@ -26,6 +61,19 @@ pub struct AggregationConfig {
     pub max_selector: Selector,
     pub min_selector: Selector,
     
+    // Dispersion (VARIANCE / STDDEV) columns and selectors
+    pub same_column: Column<Advice>,
+    pub square_sum_column: Column<Advice>,
+    pub quotient_column: Column<Advice>,
+    pub remainder_column: Column<Advice>,
+    pub slack_column: Column<Advice>,
+    pub count_column: Column<Fixed>,
+    pub factor_column: Column<Fixed>,
+    pub moments_start_selector: Selector,
+    pub moments_step_selector: Selector,
+    pub variance_selector: Selector,
+    pub sqrt_selector: Selector,
+    
     // Group-By integration
     pub group_by_config: GroupByConfig,
     
@@ -35,20 +83,24 @@ pub struct AggregationConfig {
 
 /// Aggregation Chip
 /// Paper Section 4.5 implementation
-pub struct AggregationChip {
+pub struct AggregationChip<F: PrimeField = Fr> {
     config: AggregationConfig,
+    _marker: PhantomData<F>,
 }
 
-impl AggregationChip {
+impl<F: PrimeField> AggregationChip<F> {
     /// Create a new AggregationChip
     pub fn new(config: AggregationConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            _marker: PhantomData,
+        }
     }
     
     /// Configure the Aggregation Gate
     /// Paper Section 4.5: SUM, COUNT, MAX, MIN operations
     pub fn configure(
-        meta: &mut ConstraintSystem<Fr>,
+        meta: &mut ConstraintSystem<F>,
         config: &PoneglyphConfig,
         group_by_config: &GroupByConfig,
         range_check_config: &RangeCheckConfig,
@@ -82,7 +134,7 @@ impl AggregationChip {
             // If new group starts (boundary = 1), result = value
             // If same group continues (boundary = 0), result = prev_result + value
             let sum_expr = boundary.clone() * value.clone() 
-                + (Expression::Constant(Fr::ONE) - boundary.clone()) * (prev_result + value);
+                + (Expression::Constant(F::ONE) - boundary.clone()) * (prev_result + value);
             
             vec![s * (result - sum_expr)]
         });
@@ -96,8 +148,8 @@ impl AggregationChip {
             
             // If new group starts (boundary = 1), count = 1
             // If same group continues (boundary = 0), count = prev_count + 1
-            let count_expr = boundary.clone() * Expression::Constant(Fr::ONE)
-                + (Expression::Constant(Fr::ONE) - boundary.clone()) * (prev_result + Expression::Constant(Fr::ONE));
+            let count_expr = boundary.clone() * Expression::Constant(F::ONE)
+                + (Expression::Constant(F::ONE) - boundary.clone()) * (prev_result + Expression::Constant(F::ONE));
             
             vec![s * (result - count_expr)]
         });
@@ -118,7 +170,7 @@ impl AggregationChip {
             // Constraint: if boundary = 1 then result = value
             // if boundary = 0 then result >= prev_result and result >= value checks are done in comparison constraints
             let max_expr = boundary.clone() * value.clone()
-                + (Expression::Constant(Fr::ONE) - boundary.clone()) * result.clone();
+                + (Expression::Constant(F::ONE) - boundary.clone()) * result.clone();
             
             // When boundary = 1: result = value check
             // When boundary = 0: result >= prev_result and result >= value checks
@@ -142,7 +194,7 @@ impl AggregationChip {
             // Constraint: if boundary = 1 then result = value
             // if boundary = 0 then result <= prev_result and result <= value checks are done in comparison constraints
             let min_expr = boundary.clone() * value.clone()
-                + (Expression::Constant(Fr::ONE) - boundary.clone()) * result.clone();
+                + (Expression::Constant(F::ONE) - boundary.clone()) * result.clone();
             
             // When boundary = 1: result = value check
             // When boundary = 0: result <= prev_result and result <= value checks
@@ -150,6 +202,86 @@ impl AggregationChip {
             vec![s * (result - min_expr)]
         });
         
+        // Dispersion: running sum and sum of squares, division by n², square root
+        // Columns shared with Join Gate (advice[10-14]) and Range Check (fixed[0-1])
+        let same_column = config.advice[10];
+        let square_sum_column = config.advice[11];
+        let quotient_column = config.advice[12];
+        let remainder_column = config.advice[13];
+        let slack_column = config.advice[14];
+        let count_column = config.fixed[0];
+        let factor_column = config.fixed[1];
+        
+        let moments_start_selector = meta.selector();
+        let moments_step_selector = meta.selector();
+        let variance_selector = meta.selector();
+        let sqrt_selector = meta.selector();
+        
+        // First row of the running sums: S1 = v, S2 = v²
+        meta.create_gate("moments start", |meta| {
+            let s = meta.query_selector(moments_start_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let s1 = meta.query_advice(result_column, Rotation::cur());
+            let s2 = meta.query_advice(square_sum_column, Rotation::cur());
+            
+            vec![
+                s.clone() * (s1 - value.clone()),
+                s * (s2 - value.clone() * value),
+            ]
+        });
+        
+        // Next rows: same = 1 continues the group, same = 0 restarts it
+        meta.create_gate("moments step", |meta| {
+            let s = meta.query_selector(moments_step_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let same = meta.query_advice(same_column, Rotation::cur());
+            let s1 = meta.query_advice(result_column, Rotation::cur());
+            let s1_prev = meta.query_advice(result_column, Rotation::prev());
+            let s2 = meta.query_advice(square_sum_column, Rotation::cur());
+            let s2_prev = meta.query_advice(square_sum_column, Rotation::prev());
+            
+            vec![
+                s.clone() * (s1 - (same.clone() * s1_prev + value.clone())),
+                s * (s2 - (same * s2_prev + value.clone() * value)),
+            ]
+        });
+        
+        // F · (n · S2 - S1²) = q · n² + r, slack = n² - 1 - r
+        meta.create_gate("variance division", |meta| {
+            let s = meta.query_selector(variance_selector);
+            let s1 = meta.query_advice(result_column, Rotation::cur());
+            let s2 = meta.query_advice(square_sum_column, Rotation::cur());
+            let q = meta.query_advice(quotient_column, Rotation::cur());
+            let r = meta.query_advice(remainder_column, Rotation::cur());
+            let slack = meta.query_advice(slack_column, Rotation::cur());
+            let n = meta.query_fixed(count_column);
+            let factor = meta.query_fixed(factor_column);
+            
+            let n_squared = n.clone() * n.clone();
+            let division_check =
+                factor * (n * s2 - s1.clone() * s1) - q * n_squared.clone() - r.clone();
+            let slack_check = slack - (n_squared - Expression::Constant(F::ONE) - r);
+            
+            vec![s.clone() * division_check, s * slack_check]
+        });
+        
+        // sd² ≤ q < (sd + 1)²: lo = q - sd², hi = sd² + 2·sd - q
+        meta.create_gate("square root", |meta| {
+            let s = meta.query_selector(sqrt_selector);
+            let q = meta.query_advice(quotient_column, Rotation::cur());
+            let sd = meta.query_advice(result_column, Rotation::cur());
+            let lo = meta.query_advice(remainder_column, Rotation::cur());
+            let hi = meta.query_advice(slack_column, Rotation::cur());
+            
+            let sd_squared = sd.clone() * sd.clone();
+            let two = Expression::Constant(F::from(2));
+            
+            vec![
+                s.clone() * (lo - (q.clone() - sd_squared.clone())),
+                s * (hi - (sd_squared + two * sd - q)),
+            ]
+        });
+        
         AggregationConfig {
             value_column,
             result_column,
@@ -157,6 +289,17 @@ impl AggregationChip {
             count_selector,
             max_selector,
             min_selector,
+            same_column,
+            square_sum_column,
+            quotient_column,
+            remainder_column,
+            slack_column,
+            count_column,
+            factor_column,
+            moments_start_selector,
+            moments_step_selector,
+            variance_selector,
+            sqrt_selector,
             group_by_config: group_by_config.clone(),
             range_check_config: range_check_config.clone(),
         }
@@ -171,11 +314,178 @@ impl AggregationChip {
     /// - agg_type: Aggregation type ("sum", "count", "max", "min")
     pub fn aggregate_and_verify(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        values: &[u64],
+        agg_type: &super::AggregationType,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let mut results =
+            self.aggregate_many_and_verify(layouter, group_keys, &[(values, agg_type)])?;
+        Ok(results.pop().unwrap_or_default())
+    }
+
+    /// Perform and verify several aggregations over the same groups in one pass
+    /// (`SELECT SUM(a), MAX(b), COUNT(*) ... GROUP BY k`)
+    ///
+    /// The Group-By boundary check runs once, and all measures share one
+    /// aggregation region: measure j takes rows `j·n .. (j+1)·n`, with its
+    /// boundary cells copy-constrained to those of the first measure. The
+    /// MAX/MIN comparison diffs of all measures are decomposed together.
+    ///
+    /// Parameters:
+    /// - group_keys: Group keys (must be sorted)
+    /// - measures: (values, agg_type) pairs, one value per row each
+    ///
+    /// Returns the result cells of each measure, in measure order
+    pub fn aggregate_many_and_verify(
+        &self,
+        layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        measures: &[(&[u64], &super::AggregationType)],
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        Ok(self
+            .aggregate_many_with_values(layouter, group_keys, measures)?
+            .into_iter()
+            .map(|(_value_cells, result_cells)| result_cells)
+            .collect())
+    }
+
+    /// `aggregate_many_and_verify`, also returning the value cells of each
+    /// measure, so callers can copy-constrain them to where the values come from
+    ///
+    /// Returns (value cells, result cells) per measure
+    pub fn aggregate_many_with_values(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        measures: &[(&[u64], &super::AggregationType)],
+    ) -> Result<Vec<MeasureCells<F>>, Error> {
+        // MEDIAN/PERCENTILE need the Sort Gate, see aggregate_rank_and_verify
+        // VARIANCE/STDDEV have their own gates, see aggregate_dispersion_and_verify
+        if measures.iter().any(|(values, agg_type)| {
+            values.len() != group_keys.len() || agg_type.is_rank() || agg_type.is_dispersion()
+        }) {
+            return Err(Error::Synthesis);
+        }
+
+        if group_keys.is_empty() || measures.is_empty() {
+            return Ok(vec![(Vec::new(), Vec::new()); measures.len()]);
+        }
+
+        // Get boundaries using Group-By chip (once for all measures)
+        let group_by_chip = super::group_by::GroupByChip::new(self.config.group_by_config.clone());
+        let _boundary_cells = group_by_chip.group_and_verify(
+            layouter.namespace(|| "group by for aggregation"),
+            group_keys,
+        )?;
+
+        // First, calculate all result values (for MAX/MIN comparison constraints)
+        // Each group accumulates independently (in parallel with the `parallel` feature)
+        let result_values = measures
+            .iter()
+            .map(|(values, agg_type)| {
+                super::witness::group_accumulators(group_keys, values, agg_type)
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::Synthesis)?;
+
+        // Now assign result_cells and add comparison constraints
+        // (field encodings only live as long as the region, see arena.rs)
+        let cells = super::arena::with_arena(|arena| {
+            let rows: Vec<(&[F], &[F], &super::AggregationType)> = measures
+                .iter()
+                .zip(result_values.iter())
+                .map(|((values, agg_type), results)| {
+                    (
+                        super::witness::to_field_in(arena, values),
+                        super::witness::to_field_in(arena, results),
+                        *agg_type,
+                    )
+                })
+                .collect();
+            self.assign_measure_rows(
+                layouter.namespace(|| "aggregate measures"),
+                group_keys,
+                &rows,
+            )
+        })?;
+
+        // For production: comparison constraints for MAX/MIN
+        // For MAX: result >= value and result >= prev_result checks
+        // For MIN: result <= value and result <= prev_result checks
+        // Using Range Check to verify result >= value (MAX) or result <= value (MIN)
+        let mut diffs = Vec::new();
+        for ((values, agg_type), results) in measures.iter().zip(result_values.iter()) {
+            if matches!(agg_type, super::AggregationType::Max | super::AggregationType::Min) {
+                let is_max = matches!(agg_type, super::AggregationType::Max);
+                diffs.extend(Self::comparison_diffs(group_keys, values, results, is_max));
+            }
+        }
+        if !diffs.is_empty() {
+            use super::range_check::RangeCheckChip;
+            let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+            let _diff_chunks = range_check_chip
+                .decompose_64bit_rows(layouter.namespace(|| "MAX/MIN diffs"), &diffs)?;
+        }
+
+        Ok(cells)
+    }
+
+    /// Diffs that must be non-negative for a MAX (or MIN) running result, in row order:
+    /// - result >= value (MAX) or result <= value (MIN) for every row
+    ///   (diff = 0 on the first row, where result = value)
+    /// - result >= prev_result (MAX) or result <= prev_result (MIN)
+    ///   when the same group continues (i >= 1, boundary = 0)
+    fn comparison_diffs(
         group_keys: &[u64],
         values: &[u64],
+        results: &[u64],
+        is_max: bool,
+    ) -> Vec<u64> {
+        let mut diffs = Vec::new();
+        for i in 0..group_keys.len() {
+            diffs.push(if is_max {
+                results[i].saturating_sub(values[i])
+            } else {
+                values[i].saturating_sub(results[i])
+            });
+            if i > 0 && group_keys[i] == group_keys[i - 1] {
+                diffs.push(if is_max {
+                    results[i].saturating_sub(results[i - 1])
+                } else {
+                    results[i - 1].saturating_sub(results[i])
+                });
+            }
+        }
+        diffs
+    }
+    
+    /// Perform and verify aggregation over signed values
+    /// Values are `i64` (e.g. refunds, deltas), see `signed.rs` for the encoding
+    /// 
+    /// # Encoding per aggregation type
+    /// 
+    /// - **SUM**: values and running sums are assigned as field elements
+    ///   (negative `v` is `-|v|`); each assigned value cell is range checked
+    ///   as a valid i64 via `decompose_signed_cell`, so the field sum equals
+    ///   the integer sum
+    /// - **MAX/MIN**: values are offset-encoded, so the unsigned comparison
+    ///   constraints give the signed order; result cells hold `enc(result)`
+    /// - **COUNT**: independent of the values, delegated as is
+    pub fn aggregate_signed_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        values: &[i64],
         agg_type: &super::AggregationType,
-    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        use super::signed::{encode_signed, signed_to_field, signed_wide_to_field};
+        
+        if !matches!(agg_type, super::AggregationType::Sum) {
+            let encoded: Vec<u64> = values.iter().map(|&v| encode_signed(v)).collect();
+            return self.aggregate_and_verify(layouter, group_keys, &encoded, agg_type);
+        }
+        
         if group_keys.len() != values.len() {
             return Err(Error::Synthesis);
         }
@@ -187,192 +497,662 @@ impl AggregationChip {
         // Get boundaries using Group-By chip
         let group_by_chip = super::group_by::GroupByChip::new(self.config.group_by_config.clone());
         let _boundary_cells = group_by_chip.group_and_verify(
-            layouter.namespace(|| "group by for aggregation"),
+            layouter.namespace(|| "group by for signed aggregation"),
             group_keys,
         )?;
         
-        // Perform aggregation operation
-        // Note: Selector will not be enabled for the first row (no Rotation::prev())
-        // We must also assign boundary values here because constraints use boundary_column
+        // Running sums (i128 so that n * 2^63 cannot overflow)
+        let mut field_values = Vec::with_capacity(values.len());
+        let mut field_results = Vec::with_capacity(values.len());
+        let mut acc: i128 = 0;
+        for i in 0..values.len() {
+            if i == 0 || group_keys[i] != group_keys[i - 1] {
+                acc = values[i] as i128;
+            } else {
+                acc += values[i] as i128;
+            }
+            field_values.push(signed_to_field(values[i]));
+            field_results.push(signed_wide_to_field(acc));
+        }
         
-        // First, calculate all result values (for MAX/MIN comparison constraints)
-        let mut result_values = Vec::new();
-        let first_result = match agg_type {
-            super::AggregationType::Sum => values[0],
-            super::AggregationType::Count => 1,
-            super::AggregationType::Max => values[0],
-            super::AggregationType::Min => values[0],
-        };
-        result_values.push(first_result);
-        let mut current_result = first_result;
-        
-        for i in 1..group_keys.len() {
-            let boundary = if group_keys[i] != group_keys[i-1] {
-                Fr::ONE
+        let (value_cells, result_cells) = self.assign_aggregate_rows(
+            layouter.namespace(|| "aggregate signed sum"),
+            group_keys,
+            &field_values,
+            &field_results,
+            agg_type,
+        )?;
+        
+        // Every summed cell must be a valid i64, otherwise an arbitrary field
+        // element could be smuggled into the sum; the chunks are taken of the
+        // value cells themselves (copy constraints)
+        use super::range_check::RangeCheckChip;
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for (i, cell) in value_cells.iter().enumerate() {
+            range_check_chip.decompose_signed_cell(
+                layouter.namespace(|| format!("signed value range {}", i)),
+                cell,
+            )?;
+        }
+        
+        Ok(result_cells)
+    }
+    
+    /// Perform and verify `SUM(lhs * rhs)` over fixed-point decimal columns
+    /// Each product is computed with the Decimal Gate (rounded multiplication)
+    /// and copy-constrained into the value column of the SUM region
+    /// 
+    /// Parameters:
+    /// - group_keys: Group keys (must be sorted)
+    /// - lhs, rhs: Raw decimal values (e.g. price at scale 2, quantity at scale 0)
+    /// - divisor: `Decimal::product_divisor(lhs_scale, rhs_scale, out_scale)`
+    /// 
+    /// # Return Value
+    /// 
+    /// Running SUM cells at the output scale
+    pub fn aggregate_decimal_products_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        decimal_config: &super::decimal::DecimalConfig,
+        group_keys: &[u64],
+        lhs: &[u64],
+        rhs: &[u64],
+        divisor: u64,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        use super::decimal::{Decimal, DecimalChip};
+        
+        if group_keys.len() != lhs.len() || lhs.len() != rhs.len() || divisor == 0 {
+            return Err(Error::Synthesis);
+        }
+        
+        if group_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        // Get boundaries using Group-By chip
+        let group_by_chip = super::group_by::GroupByChip::new(self.config.group_by_config.clone());
+        let _boundary_cells = group_by_chip.group_and_verify(
+            layouter.namespace(|| "group by for decimal aggregation"),
+            group_keys,
+        )?;
+        
+        // Per-row rounded products
+        let decimal_chip = DecimalChip::new(decimal_config.clone());
+        let mut product_cells = Vec::with_capacity(lhs.len());
+        let mut products = Vec::with_capacity(lhs.len());
+        for i in 0..lhs.len() {
+            let cell = decimal_chip.mul_round(
+                layouter.namespace(|| format!("product {}", i)),
+                Value::known(lhs[i]),
+                Value::known(rhs[i]),
+                divisor,
+            )?;
+            product_cells.push(cell);
+            let (product, _) =
+                Decimal::mul_round(lhs[i], rhs[i], divisor).ok_or(Error::Synthesis)?;
+            products.push(product);
+        }
+        
+        // Running sums of products
+        let mut field_results = Vec::with_capacity(products.len());
+        let mut acc = F::ZERO;
+        for i in 0..products.len() {
+            if i == 0 || group_keys[i] != group_keys[i - 1] {
+                acc = F::from(products[i]);
             } else {
-                Fr::ZERO
-            };
-            
-            let boundary_value = if boundary == Fr::ONE {
-                match agg_type {
-                    super::AggregationType::Sum => values[i],
-                    super::AggregationType::Count => 1,
-                    super::AggregationType::Max => values[i],
-                    super::AggregationType::Min => values[i],
+                acc += F::from(products[i]);
+            }
+            field_results.push(acc);
+        }
+        let field_values: Vec<F> = products.iter().map(|&p| F::from(p)).collect();
+        
+        let (value_cells, result_cells) = self.assign_aggregate_rows(
+            layouter.namespace(|| "aggregate decimal sum"),
+            group_keys,
+            &field_values,
+            &field_results,
+            &super::AggregationType::Sum,
+        )?;
+        
+        // The summed values are exactly the proven products
+        layouter.assign_region(
+            || "link decimal products",
+            |mut region| {
+                for (product_cell, value_cell) in product_cells.iter().zip(value_cells.iter()) {
+                    region.constrain_equal(product_cell.cell(), value_cell.cell())?;
                 }
+                Ok(())
+            },
+        )?;
+        
+        Ok(result_cells)
+    }
+
+    /// Perform and verify aggregation over a nullable column (SQL semantics)
+    ///
+    /// # NULL handling per aggregation type
+    ///
+    /// - **SUM(col)**: NULLs contribute 0; the summed values are copy-constrained
+    ///   to the NULL Gate value cells, whose validity gate forces NULL to 0
+    /// - **COUNT(col)**: counts non-NULL rows, i.e. SUM of the validity bits
+    ///   (copy-constrained to the NULL Gate valid cells)
+    /// - **MAX/MIN**: NULL rows are skipped; a group with only NULLs produces no rows
+    ///
+    /// # Note
+    ///
+    /// - COUNT(*) does not depend on NULLs, use `aggregate_and_verify`
+    /// - An all-NULL group yields SUM = 0 here (SQL returns NULL); the caller
+    ///   distinguishes via COUNT(col) = 0
+    pub fn aggregate_nullable_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        null_config: &super::nullable::NullConfig,
+        group_keys: &[u64],
+        values: &[Option<u64>],
+        agg_type: &super::AggregationType,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        use super::nullable::NullChip;
+
+        if group_keys.len() != values.len() {
+            return Err(Error::Synthesis);
+        }
+
+        if matches!(agg_type, super::AggregationType::Max | super::AggregationType::Min) {
+            let (keys, non_null): (Vec<u64>, Vec<u64>) = group_keys
+                .iter()
+                .zip(values.iter())
+                .filter_map(|(&k, v)| v.map(|v| (k, v)))
+                .unzip();
+            if keys.is_empty() {
+                return Ok(Vec::new());
+            }
+            return self.aggregate_and_verify(layouter, &keys, &non_null, agg_type);
+        }
+
+        if group_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Get boundaries using Group-By chip
+        let group_by_chip = super::group_by::GroupByChip::new(self.config.group_by_config.clone());
+        let _boundary_cells = group_by_chip.group_and_verify(
+            layouter.namespace(|| "group by for nullable aggregation"),
+            group_keys,
+        )?;
+
+        // Validity bits
+        let null_chip = NullChip::new(null_config.clone());
+        let nullable_cells = null_chip.assign_nullable(
+            layouter.namespace(|| "nullable column"),
+            values,
+        )?;
+
+        // COUNT(col) is the SUM of the validity bits
+        let is_count = matches!(agg_type, super::AggregationType::Count);
+        let contributions: Vec<u64> = values
+            .iter()
+            .map(|v| if is_count { v.is_some() as u64 } else { v.unwrap_or(0) })
+            .collect();
+
+        let mut field_results = Vec::with_capacity(contributions.len());
+        let mut acc = F::ZERO;
+        for i in 0..contributions.len() {
+            if i == 0 || group_keys[i] != group_keys[i - 1] {
+                acc = F::from(contributions[i]);
             } else {
-                match agg_type {
-                    super::AggregationType::Sum => current_result + values[i],
-                    super::AggregationType::Count => current_result + 1,
-                    super::AggregationType::Max => current_result.max(values[i]),
-                    super::AggregationType::Min => current_result.min(values[i]),
+                acc += F::from(contributions[i]);
+            }
+            field_results.push(acc);
+        }
+        let field_values: Vec<F> = contributions.iter().map(|&c| F::from(c)).collect();
+
+        let (value_cells, result_cells) = self.assign_aggregate_rows(
+            layouter.namespace(|| "aggregate nullable"),
+            group_keys,
+            &field_values,
+            &field_results,
+            &super::AggregationType::Sum,
+        )?;
+
+        // The summed values are exactly the (masked) column or its validity bits
+        layouter.assign_region(
+            || "link nullable values",
+            |mut region| {
+                for (nullable, value_cell) in nullable_cells.iter().zip(value_cells.iter()) {
+                    let source = if is_count { &nullable.valid } else { &nullable.value };
+                    region.constrain_equal(source.cell(), value_cell.cell())?;
                 }
-            };
-            result_values.push(boundary_value);
-            current_result = boundary_value;
+                Ok(())
+            },
+        )?;
+
+        Ok(result_cells)
+    }
+
+    /// Perform and verify a rank aggregation (MEDIAN, PERCENTILE) per group
+    /// 
+    /// Each group's values are sorted with the Sort Gate (order checks and
+    /// permutation argument), and the group result is copy-constrained to the
+    /// sorted output at `agg_type.rank_index(group size)`. Group sizes are fixed
+    /// at synthesis, so the index selection is a copy constraint.
+    /// 
+    /// Parameters:
+    /// - sort_chip: Sort Gate used for the per-group sort
+    /// - group_keys: Group keys (must be sorted)
+    /// - values: Values for each row
+    /// 
+    /// Returns one result cell per group, in group order
+    pub fn aggregate_rank_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sort_chip: &super::sort::SortChip<F>,
+        group_keys: &[u64],
+        values: &[u64],
+        agg_type: &super::AggregationType,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if group_keys.len() != values.len() || !agg_type.is_rank() {
+            return Err(Error::Synthesis);
         }
         
-        // Now assign result_cells and add comparison constraints
-        let result_cells = layouter.assign_region(
-            || format!("aggregate {:?}", agg_type),
+        if group_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        // Get boundaries using Group-By chip
+        let group_by_chip = super::group_by::GroupByChip::new(self.config.group_by_config.clone());
+        let _boundary_cells = group_by_chip.group_and_verify(
+            layouter.namespace(|| "group by for rank aggregation"),
+            group_keys,
+        )?;
+        
+        let mut result_cells = Vec::new();
+        let mut start = 0;
+        while start < group_keys.len() {
+            let mut end = start + 1;
+            while end < group_keys.len() && group_keys[end] == group_keys[start] {
+                end += 1;
+            }
+            
+            let group_values = &values[start..end];
+            let sorted = super::witness::sorted(group_values);
+            let sorted_cells = sort_chip.sort_and_verify(
+                layouter.namespace(|| format!("sort group {}", group_keys[start])),
+                group_values.iter().map(|&v| Value::known(v)).collect(),
+                sorted,
+            )?;
+            
+            // Index selection: result = sorted[rank_index]
+            let index = agg_type.rank_index(end - start).ok_or(Error::Synthesis)?;
+            let result_cell = layouter.assign_region(
+                || format!("{} of group {}", agg_type.as_str(), group_keys[start]),
+                |mut region| {
+                    sorted_cells[index].copy_advice(
+                        || "result",
+                        &mut region,
+                        self.config.result_column,
+                        0,
+                    )
+                },
+            )?;
+            result_cells.push(result_cell);
+            start = end;
+        }
+        
+        Ok(result_cells)
+    }
+
+    /// Perform and verify a dispersion aggregation (VARIANCE, STDDEV) per group
+    /// 
+    /// The running sum S1 and sum of squares S2 restart at the Group-By
+    /// boundaries (copied from the Group-By Gate). At the last row of each
+    /// group, the division row proves `q = ⌊F·(n·S2 - S1²) / n²⌋` with the
+    /// group size n fixed at synthesis; STDDEV adds a square root row whose
+    /// witness is verified by squaring (`sd² ≤ q < (sd + 1)²`).
+    /// 
+    /// Parameters:
+    /// - group_keys: Group keys (must be sorted)
+    /// - values: Values for each row
+    /// - agg_type: `Variance(scale)` or `StdDev(scale)`
+    /// 
+    /// Returns one result cell per group, in group order
+    pub fn aggregate_dispersion_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        values: &[u64],
+        agg_type: &super::AggregationType,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        use super::range_check::RangeCheckChip;
+        
+        if group_keys.len() != values.len() {
+            return Err(Error::Synthesis);
+        }
+        let factor = agg_type.dispersion_factor().ok_or(Error::Synthesis)?;
+        
+        if group_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        // Get boundaries using Group-By chip (b = 1: keys equal, same group)
+        let group_by_chip = super::group_by::GroupByChip::new(self.config.group_by_config.clone());
+        let boundary_cells = group_by_chip.group_and_verify(
+            layouter.namespace(|| "group by for dispersion aggregation"),
+            group_keys,
+        )?;
+        
+        // Running S1 and S2
+        let mut sums = Vec::with_capacity(values.len());
+        let (mut s1, mut s2) = (F::ZERO, F::ZERO);
+        for i in 0..values.len() {
+            let v = F::from(values[i]);
+            if i == 0 || group_keys[i] != group_keys[i - 1] {
+                (s1, s2) = (v, v * v);
+            } else {
+                (s1, s2) = (s1 + v, s2 + v * v);
+            }
+            sums.push((s1, s2));
+        }
+        
+        let (value_cells, sum_cells) = layouter.assign_region(
+            || "dispersion running sums",
             |mut region| {
-                let mut result_cells = Vec::new();
-                
-                // Special handling for first row (selector will not be enabled)
-                region.assign_advice(
-                    || "boundary_0",
-                    self.config.group_by_config.boundary_column,
-                    0,
-                    || Value::known(Fr::ONE),
-                )?;
-                
-                region.assign_advice(
-                    || "value_0",
-                    self.config.value_column,
-                    0,
-                    || Value::known(Fr::from(values[0])),
-                )?;
-                
-                let first_result_cell = region.assign_advice(
-                    || "result_0",
-                    self.config.result_column,
-                    0,
-                    || Value::known(Fr::from(result_values[0])),
-                )?;
-                result_cells.push(first_result_cell);
-                
-                // For remaining rows (i >= 1, Rotation::prev() can be used)
-                for i in 1..group_keys.len() {
-                    let boundary = if group_keys[i] != group_keys[i-1] {
-                        Fr::ONE
+                let mut value_cells = Vec::with_capacity(values.len());
+                let mut sum_cells = Vec::with_capacity(values.len());
+                for i in 0..values.len() {
+                    if i == 0 {
+                        self.config.moments_start_selector.enable(&mut region, i)?;
                     } else {
-                        Fr::ZERO
-                    };
-                    
-                    region.assign_advice(
-                        || format!("boundary_{}", i),
-                        self.config.group_by_config.boundary_column,
-                        i,
-                        || Value::known(boundary),
-                    )?;
+                        self.config.moments_step_selector.enable(&mut region, i)?;
+                        boundary_cells[i - 1].copy_advice(
+                            || format!("same_{}", i),
+                            &mut region,
+                            self.config.same_column,
+                            i,
+                        )?;
+                    }
                     
-                    region.assign_advice(
+                    value_cells.push(region.assign_advice(
                         || format!("value_{}", i),
                         self.config.value_column,
                         i,
-                        || Value::known(Fr::from(values[i])),
-                    )?;
-                    
-                    let result_cell = region.assign_advice(
-                        || format!("result_{}", i),
+                        || Value::known(F::from(values[i])),
+                    )?);
+                    let s1_cell = region.assign_advice(
+                        || format!("s1_{}", i),
                         self.config.result_column,
                         i,
-                        || Value::known(Fr::from(result_values[i])),
+                        || Value::known(sums[i].0),
                     )?;
-                    result_cells.push(result_cell);
-                    
-                    match agg_type {
-                        super::AggregationType::Sum => self.config.sum_selector.enable(&mut region, i)?,
-                        super::AggregationType::Count => self.config.count_selector.enable(&mut region, i)?,
-                        super::AggregationType::Max => self.config.max_selector.enable(&mut region, i)?,
-                        super::AggregationType::Min => self.config.min_selector.enable(&mut region, i)?,
-                    }
+                    let s2_cell = region.assign_advice(
+                        || format!("s2_{}", i),
+                        self.config.square_sum_column,
+                        i,
+                        || Value::known(sums[i].1),
+                    )?;
+                    sum_cells.push((s1_cell, s2_cell));
                 }
-                
-                Ok(result_cells)
+                Ok((value_cells, sum_cells))
             },
         )?;
         
-        // For production: comparison constraints for MAX/MIN
-        // For MAX: result >= value and result >= prev_result checks
-        // For MIN: result <= value and result <= prev_result checks
-        // Using Range Check to verify result >= value (MAX) or result <= value (MIN)
-        if matches!(agg_type, super::AggregationType::Max | super::AggregationType::Min) {
-            use super::range_check::RangeCheckChip;
-            let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
-            
-            // For first row: result = value check (already checked in constraint since boundary = 1)
-            // But we can still do result >= value (MAX) or result <= value (MIN) check
-            if agg_type == "max" {
-                // For first row: result >= value check (diff = 0 since result = value)
-                let diff = result_values[0].saturating_sub(values[0]);
-                let _diff_chunks = range_check_chip.decompose_64bit(
-                    layouter.namespace(|| "max_diff_0"),
-                    Value::known(diff),
-                )?;
-            } else if agg_type == "min" {
-                // For first row: result <= value check (diff = 0 since result = value)
-                let diff = values[0].saturating_sub(result_values[0]);
-                let _diff_chunks = range_check_chip.decompose_64bit(
-                    layouter.namespace(|| "min_diff_0"),
-                    Value::known(diff),
-                )?;
+        // Values are u64, so S1 and S2 are the integer sums
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for (i, cell) in value_cells.iter().enumerate() {
+            range_check_chip.decompose_cell(layouter.namespace(|| format!("value range {}", i)), cell)?;
+        }
+        
+        let mut result_cells = Vec::new();
+        let mut start = 0;
+        while start < group_keys.len() {
+            let mut end = start + 1;
+            while end < group_keys.len() && group_keys[end] == group_keys[start] {
+                end += 1;
             }
+            let group = &values[start..end];
+            let (s1_cell, s2_cell) = &sum_cells[end - 1];
             
-            // For remaining rows (i >= 1, prev_result exists)
-            for i in 1..group_keys.len() {
-                let boundary = if group_keys[i] != group_keys[i-1] {
-                    Fr::ONE
-                } else {
-                    Fr::ZERO
-                };
-                
-                if matches!(agg_type, super::AggregationType::Max) {
-                    // For MAX: result >= value check
-                    let diff = result_values[i].saturating_sub(values[i]);
-                    let _diff_chunks = range_check_chip.decompose_64bit(
-                        layouter.namespace(|| format!("max_diff_{}", i)),
-                        Value::known(diff),
+            // q = ⌊F·D / n²⌋, r = F·D mod n² (D = n·S2 - S1²)
+            let n = group.len() as u128;
+            let s1 = group.iter().map(|&v| v as u128).sum::<u128>();
+            let s2 = group
+                .iter()
+                .try_fold(0u128, |acc, &v| acc.checked_add(v as u128 * v as u128))
+                .ok_or(Error::Synthesis)?;
+            let scaled = n
+                .checked_mul(s2)
+                .and_then(|ns2| ns2.checked_sub(s1.checked_mul(s1)?))
+                .and_then(|d| d.checked_mul(factor))
+                .ok_or(Error::Synthesis)?;
+            let n_squared = n * n;
+            let q = u64::try_from(scaled / n_squared).map_err(|_| Error::Synthesis)?;
+            let r = scaled % n_squared;
+            
+            let (q_cell, r_cell, slack_cell) = layouter.assign_region(
+                || format!("variance of group {}", group_keys[start]),
+                |mut region| {
+                    self.config.variance_selector.enable(&mut region, 0)?;
+                    region.assign_fixed(
+                        || "n",
+                        self.config.count_column,
+                        0,
+                        || Value::known(F::from_u128(n)),
                     )?;
-                    
-                    // If same group continues: result >= prev_result check
-                    if boundary == Fr::ZERO {
-                        let prev_diff = result_values[i].saturating_sub(result_values[i-1]);
-                        let _prev_diff_chunks = range_check_chip.decompose_64bit(
-                            layouter.namespace(|| format!("max_prev_diff_{}", i)),
-                            Value::known(prev_diff),
-                        )?;
-                    }
-                } else if matches!(agg_type, super::AggregationType::Min) {
-                    // For MIN: result <= value check
-                    let diff = values[i].saturating_sub(result_values[i]);
-                    let _diff_chunks = range_check_chip.decompose_64bit(
-                        layouter.namespace(|| format!("min_diff_{}", i)),
-                        Value::known(diff),
+                    region.assign_fixed(
+                        || "factor",
+                        self.config.factor_column,
+                        0,
+                        || Value::known(F::from_u128(factor)),
                     )?;
-                    
-                    // If same group continues: result <= prev_result check
-                    if boundary == Fr::ZERO {
-                        let prev_diff = result_values[i-1].saturating_sub(result_values[i]);
-                        let _prev_diff_chunks = range_check_chip.decompose_64bit(
-                            layouter.namespace(|| format!("min_prev_diff_{}", i)),
-                            Value::known(prev_diff),
-                        )?;
-                    }
-                }
+                    s1_cell.copy_advice(|| "s1", &mut region, self.config.result_column, 0)?;
+                    s2_cell.copy_advice(|| "s2", &mut region, self.config.square_sum_column, 0)?;
+                    let q_cell = region.assign_advice(
+                        || "q",
+                        self.config.quotient_column,
+                        0,
+                        || Value::known(F::from(q)),
+                    )?;
+                    let r_cell = region.assign_advice(
+                        || "r",
+                        self.config.remainder_column,
+                        0,
+                        || Value::known(F::from_u128(r)),
+                    )?;
+                    let slack_cell = region.assign_advice(
+                        || "slack",
+                        self.config.slack_column,
+                        0,
+                        || Value::known(F::from_u128(n_squared - 1 - r)),
+                    )?;
+                    Ok((q_cell, r_cell, slack_cell))
+                },
+            )?;
+            
+            // q < 2^64 (no wrap-around), r ∈ [0, n²)
+            range_check_chip.decompose_cell(layouter.namespace(|| "variance quotient"), &q_cell)?;
+            range_check_chip.decompose_cell(layouter.namespace(|| "variance remainder"), &r_cell)?;
+            range_check_chip.decompose_cell(layouter.namespace(|| "variance slack"), &slack_cell)?;
+            
+            if !matches!(agg_type, super::AggregationType::StdDev(_)) {
+                result_cells.push(q_cell);
+                start = end;
+                continue;
             }
+            
+            // Square root witness, verified by squaring
+            let sd = agg_type.dispersion(group).ok_or(Error::Synthesis)?;
+            let (sd_cell, lo_cell, hi_cell) = layouter.assign_region(
+                || format!("stddev of group {}", group_keys[start]),
+                |mut region| {
+                    self.config.sqrt_selector.enable(&mut region, 0)?;
+                    q_cell.copy_advice(|| "q", &mut region, self.config.quotient_column, 0)?;
+                    let sd_cell = region.assign_advice(
+                        || "sd",
+                        self.config.result_column,
+                        0,
+                        || Value::known(F::from(sd)),
+                    )?;
+                    let lo_cell = region.assign_advice(
+                        || "q - sd²",
+                        self.config.remainder_column,
+                        0,
+                        || Value::known(F::from(q) - F::from(sd) * F::from(sd)),
+                    )?;
+                    let hi_cell = region.assign_advice(
+                        || "sd² + 2·sd - q",
+                        self.config.slack_column,
+                        0,
+                        || {
+                            Value::known(
+                                F::from(sd) * F::from(sd) + F::from(2 * sd) - F::from(q),
+                            )
+                        },
+                    )?;
+                    Ok((sd_cell, lo_cell, hi_cell))
+                },
+            )?;
+            
+            range_check_chip.decompose_cell(layouter.namespace(|| "stddev"), &sd_cell)?;
+            range_check_chip.decompose_cell(layouter.namespace(|| "stddev lower"), &lo_cell)?;
+            range_check_chip.decompose_cell(layouter.namespace(|| "stddev upper"), &hi_cell)?;
+            
+            result_cells.push(sd_cell);
+            start = end;
         }
         
         Ok(result_cells)
     }
+
+    /// Assign boundary, value and result rows and enable the aggregation selector
+    /// Returns (value cells, result cells)
+    fn assign_aggregate_rows(
+        &self,
+        layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        values: &[F],
+        result_values: &[F],
+        agg_type: &super::AggregationType,
+    ) -> Result<MeasureCells<F>, Error> {
+        let mut cells =
+            self.assign_measure_rows(layouter, group_keys, &[(values, result_values, agg_type)])?;
+        cells.pop().ok_or(Error::Synthesis)
+    }
+
+    /// Assign the rows of several measures over the same groups in one region
+    /// Returns (value cells, result cells) per measure
+    ///
+    /// # Row Layout
+    ///
+    /// Measure j takes rows `j·n .. (j+1)·n` (n = number of group keys):
+    /// - Row j·n: first value, boundary = 1, result = first result (selector disabled)
+    /// - Row j·n + i (i >= 1): boundary, value, result with the agg_type selector enabled
+    ///
+    /// The first result has no gate, so it is constrained to the first value
+    /// (SUM, MAX, MIN) or to 1 (COUNT). The boundary cells of measure j > 0
+    /// are copy-constrained to those of measure 0, so every measure
+    /// accumulates over the same groups. A single group (no GROUP BY) takes
+    /// its boundaries from constants: the gates don't constrain the boundary,
+    /// and a boundary inside the group would restart the accumulation.
+    fn assign_measure_rows(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_keys: &[u64],
+        measures: &[(&[F], &[F], &super::AggregationType)],
+    ) -> Result<Vec<MeasureCells<F>>, Error> {
+        let n = group_keys.len();
+        let single_group = group_keys.windows(2).all(|w| w[0] == w[1]);
+        layouter.assign_region(
+            || "aggregate",
+            |mut region| {
+                let mut cells = Vec::with_capacity(measures.len());
+                let mut first_boundaries: Vec<AssignedCell<F, F>> = Vec::with_capacity(n);
+
+                for (j, (values, result_values, agg_type)) in measures.iter().enumerate() {
+                    if values.len() != n || result_values.len() != n {
+                        return Err(Error::Synthesis);
+                    }
+                    let selector = match agg_type {
+                        super::AggregationType::Sum => self.config.sum_selector,
+                        super::AggregationType::Count => self.config.count_selector,
+                        super::AggregationType::Max => self.config.max_selector,
+                        super::AggregationType::Min => self.config.min_selector,
+                        super::AggregationType::Median
+                        | super::AggregationType::Percentile(_)
+                        | super::AggregationType::Variance(_)
+                        | super::AggregationType::StdDev(_) => return Err(Error::Synthesis),
+                    };
+                    let mut value_cells = Vec::with_capacity(n);
+                    let mut result_cells = Vec::with_capacity(n);
+
+                    for i in 0..n {
+                        let row = j * n + i;
+                        let boundary = if i == 0 || group_keys[i] != group_keys[i - 1] {
+                            F::ONE
+                        } else {
+                            F::ZERO
+                        };
+
+                        let boundary_column = self.config.group_by_config.boundary_column;
+                        let boundary_cell = if j == 0 && single_group {
+                            region.assign_advice_from_constant(
+                                || format!("boundary_{}_{}", j, i),
+                                boundary_column,
+                                row,
+                                boundary,
+                            )?
+                        } else {
+                            region.assign_advice(
+                                || format!("boundary_{}_{}", j, i),
+                                boundary_column,
+                                row,
+                                || Value::known(boundary),
+                            )?
+                        };
+                        if j == 0 {
+                            first_boundaries.push(boundary_cell);
+                        } else {
+                            region.constrain_equal(
+                                boundary_cell.cell(),
+                                first_boundaries[i].cell(),
+                            )?;
+                        }
+
+                        let value_cell = region.assign_advice(
+                            || format!("value_{}_{}", j, i),
+                            self.config.value_column,
+                            row,
+                            || Value::known(values[i]),
+                        )?;
+                        value_cells.push(value_cell);
+
+                        let result_cell = region.assign_advice(
+                            || format!("result_{}_{}", j, i),
+                            self.config.result_column,
+                            row,
+                            || Value::known(result_values[i]),
+                        )?;
+                        result_cells.push(result_cell);
+
+                        // The first row of a measure has no Rotation::prev() in it
+                        if i > 0 {
+                            selector.enable(&mut region, row)?;
+                        }
+                    }
+
+                    match agg_type {
+                        super::AggregationType::Count => {
+                            region.constrain_constant(result_cells[0].cell(), F::ONE)?
+                        }
+                        _ => region.constrain_equal(
+                            result_cells[0].cell(),
+                            value_cells[0].cell(),
+                        )?,
+                    }
+
+                    cells.push((value_cells, result_cells));
+                }
+
+                Ok(cells)
+            },
+        )
+    }
 }
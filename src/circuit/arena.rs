@@ -0,0 +1,141 @@
+// Arena allocation for synthesis-time temporaries
+// Synthesizing a large table creates many short-lived buffers per region:
+// field encodings of keys, values and running results, chunk witnesses of
+// range checks. They are bump-allocated from a per-thread arena instead of the
+// global allocator, and freed all at once when the outermost chip call that
+// allocated them returns.
+//
+// The arena keeps its largest chunk when it is reset, so after the first
+// proof of a given shape the temporaries of later proofs reuse the same
+// memory: no allocator calls per region, and prove times don't depend on
+// fragmentation left by earlier proofs.
+//
+// Only `Copy` data goes in the arena (no destructors run), and nothing
+// allocated here outlives its `with_arena` scope: cells returned by chips
+// are ordinary `Vec`s.
+
+use std::cell::RefCell;
+
+use bumpalo::Bump;
+
+thread_local! {
+    static ARENA: RefCell<SynthesisArena> = RefCell::new(SynthesisArena::new());
+}
+
+/// Bump arena for the temporaries of one synthesis call tree
+#[derive(Debug, Default)]
+pub struct SynthesisArena {
+    bump: Bump,
+}
+
+impl SynthesisArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arena with a first chunk of at least `bytes`
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            bump: Bump::with_capacity(bytes),
+        }
+    }
+
+    /// Slice of the items of `iter`, in order
+    pub fn alloc_from<T: Copy>(&self, iter: impl ExactSizeIterator<Item = T>) -> &mut [T] {
+        self.bump.alloc_slice_fill_iter(iter)
+    }
+
+    /// Slice of `len` copies of `value`
+    pub fn alloc_filled<T: Copy>(&self, len: usize, value: T) -> &mut [T] {
+        self.bump.alloc_slice_fill_copy(len, value)
+    }
+
+    /// Bytes held by the arena's chunks (allocated or free)
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Free every allocation, keeping the largest chunk for reuse
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+}
+
+/// Run `f` with this thread's arena
+/// Scopes nest (a chip calling another chip shares the arena); the arena is
+/// reset when the outermost scope returns.
+pub fn with_arena<R>(f: impl FnOnce(&SynthesisArena) -> R) -> R {
+    ARENA.with(|arena| {
+        let result = f(&arena.borrow());
+        // Only the outermost scope can borrow mutably
+        if let Ok(mut arena) = arena.try_borrow_mut() {
+            arena.reset();
+        }
+        result
+    })
+}
+
+/// Bytes held by this thread's arena
+pub fn arena_bytes() -> usize {
+    ARENA.with(|arena| arena.borrow().allocated_bytes())
+}
+
+/// Replace this thread's arena with one of at least `bytes`, e.g. sized for
+/// the largest expected table before a worker starts proving
+/// No-op inside a `with_arena` scope.
+pub fn reserve_arena(bytes: usize) {
+    ARENA.with(|arena| {
+        if let Ok(mut arena) = arena.try_borrow_mut() {
+            if arena.allocated_bytes() < bytes {
+                *arena = SynthesisArena::with_capacity(bytes);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc() {
+        let arena = SynthesisArena::new();
+        let squares = arena.alloc_from([0u64, 1, 2, 3].into_iter().map(|v| v * v));
+        assert_eq!(squares, &[0, 1, 4, 9]);
+        let zeros = arena.alloc_filled(3, 0u8);
+        zeros[1] = 7;
+        assert_eq!(zeros, &[0, 7, 0]);
+        assert!(arena.alloc_from(std::iter::empty::<u64>()).is_empty());
+    }
+
+    #[test]
+    fn test_nested_scopes_share_arena() {
+        let total = with_arena(|outer| {
+            let values = outer.alloc_filled(100, 1u64);
+            let inner = with_arena(|inner| inner.alloc_from(values.iter().map(|v| v * 2)).len());
+            // The inner scope didn't reset the arena under the outer one
+            assert!(values.iter().all(|&v| v == 1));
+            values.len() + inner
+        });
+        assert_eq!(total, 200);
+    }
+
+    #[test]
+    fn test_memory_reused_across_scopes() {
+        with_arena(|arena| arena.alloc_filled(10_000, 0u64).len());
+        let retained = arena_bytes();
+        assert!(retained >= 10_000 * 8);
+        for _ in 0..3 {
+            with_arena(|arena| arena.alloc_filled(10_000, 0u64).len());
+            assert_eq!(arena_bytes(), retained);
+        }
+    }
+
+    #[test]
+    fn test_reserve() {
+        reserve_arena(1 << 16);
+        assert!(arena_bytes() >= 1 << 16);
+        with_arena(|arena| arena.alloc_filled(1000, 0u64).len());
+        assert!(arena_bytes() >= 1 << 16);
+    }
+}
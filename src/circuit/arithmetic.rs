@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{field_to_u64, RangeCheckChip, RangeCheckConfig};
+
+/// Arithmetic expression over the columns of one row
+/// (computed columns such as `price * qty - discount`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArithExpr {
+    Column(String),
+    Const(u64),
+    Add(Box<ArithExpr>, Box<ArithExpr>),
+    Sub(Box<ArithExpr>, Box<ArithExpr>),
+    Mul(Box<ArithExpr>, Box<ArithExpr>),
+}
+
+impl ArithExpr {
+    /// Parse `+`, `-`, `*` over column names, integer constants and parentheses
+    /// (`*` binds tighter, operators are left-associative)
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        if expr.is_empty() {
+            return Err("Empty arithmetic expression".to_string());
+        }
+
+        // Split at the last top-level operator of the lowest precedence
+        for ops in [&['+', '-'][..], &['*'][..]] {
+            let mut depth = 0i32;
+            let mut split = None;
+            for (idx, c) in expr.char_indices() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ if depth == 0 && ops.contains(&c) => split = Some((idx, c)),
+                    _ => {}
+                }
+            }
+            if let Some((idx, op)) = split {
+                let left = Box::new(Self::parse(&expr[..idx])?);
+                let right = Box::new(Self::parse(&expr[idx + 1..])?);
+                return Ok(match op {
+                    '+' => ArithExpr::Add(left, right),
+                    '-' => ArithExpr::Sub(left, right),
+                    _ => ArithExpr::Mul(left, right),
+                });
+            }
+        }
+
+        if let Some(inner) = expr.strip_prefix('(').and_then(|e| e.strip_suffix(')')) {
+            return Self::parse(inner);
+        }
+        if let Ok(value) = expr.parse::<u64>() {
+            return Ok(ArithExpr::Const(value));
+        }
+        if expr
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            return Ok(ArithExpr::Column(expr.to_string()));
+        }
+        Err(format!("Unsupported arithmetic expression '{}'", expr))
+    }
+
+    /// True if the expression is more than a single column
+    pub fn is_computed(&self) -> bool {
+        !matches!(self, ArithExpr::Column(_))
+    }
+
+    /// Columns referenced, in order of first appearance
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        match self {
+            ArithExpr::Column(name) => {
+                if !columns.contains(&name.as_str()) {
+                    columns.push(name);
+                }
+            }
+            ArithExpr::Const(_) => {}
+            ArithExpr::Add(l, r) | ArithExpr::Sub(l, r) | ArithExpr::Mul(l, r) => {
+                l.collect_columns(columns);
+                r.collect_columns(columns);
+            }
+        }
+    }
+
+    /// Evaluate outside the circuit
+    /// None on a missing column, overflow or a negative intermediate result
+    pub fn evaluate(&self, row: &HashMap<String, u64>) -> Option<u64> {
+        match self {
+            ArithExpr::Column(name) => row.get(name).copied(),
+            ArithExpr::Const(value) => Some(*value),
+            ArithExpr::Add(l, r) => l.evaluate(row)?.checked_add(r.evaluate(row)?),
+            ArithExpr::Sub(l, r) => l.evaluate(row)?.checked_sub(r.evaluate(row)?),
+            ArithExpr::Mul(l, r) => l.evaluate(row)?.checked_mul(r.evaluate(row)?),
+        }
+    }
+}
+
+/// Arithmetic Gate Configuration
+/// `+`, `-`, `*` and constant multiplication on 64-bit values
+///
+/// # Column Allocation
+///
+/// - `a_column`: Left operand (advice[10])
+/// - `b_column`: Right operand (advice[11])
+/// - `out_column`: Result (advice[12])
+/// - `constant_column`: Constant factor (fixed[0])
+///
+/// # Constraints
+///
+/// 1. **Add**: `out = a + b`
+/// 2. **Sub**: `out = a - b`
+/// 3. **Mul**: `out = a · b`
+/// 4. **Constant Mul**: `out = c · a`
+/// 5. **Overflow**: every input and result is decomposed into 8-bit chunks
+///    (`out ∈ [0, 2^64)`), so results never wrap around the field and
+///    subtraction can't go negative
+///
+/// # Note
+///
+/// - Columns are shared with Join Gate (used in different rows)
+/// - Operands are < 2^64, so `a · b < 2^128` never wraps before the range check
+#[derive(Clone, Debug)]
+pub struct ArithmeticConfig {
+    pub a_column: Column<Advice>,
+    pub b_column: Column<Advice>,
+    pub out_column: Column<Advice>,
+    pub constant_column: Column<Fixed>,
+
+    // Selectors
+    pub add_selector: Selector,
+    pub sub_selector: Selector,
+    pub mul_selector: Selector,
+    pub mul_const_selector: Selector,
+
+    // Range Check integration (overflow checks)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Arithmetic Chip
+/// In-circuit computed columns
+pub struct ArithmeticChip<F: PrimeField = Fr> {
+    config: ArithmeticConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ArithmeticChip<F> {
+    /// Create a new ArithmeticChip
+    pub fn new(config: ArithmeticConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Arithmetic Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> ArithmeticConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-12]: shared with Join Gate
+        // - fixed[0]: shared with Range Check threshold (different rows)
+        let a_column = config.advice[10];
+        let b_column = config.advice[11];
+        let out_column = config.advice[12];
+        let constant_column = config.fixed[0];
+
+        let add_selector = meta.selector();
+        let sub_selector = meta.selector();
+        let mul_selector = meta.selector();
+        let mul_const_selector = meta.selector();
+
+        // One gate per operation: a row only assigns the cells its own
+        // operation reads (no b for mul_const, no constant otherwise)
+        meta.create_gate("arithmetic add", |meta| {
+            let s = meta.query_selector(add_selector);
+            let a = meta.query_advice(a_column, Rotation::cur());
+            let b = meta.query_advice(b_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            vec![s * (out - (a + b))]
+        });
+
+        meta.create_gate("arithmetic sub", |meta| {
+            let s = meta.query_selector(sub_selector);
+            let a = meta.query_advice(a_column, Rotation::cur());
+            let b = meta.query_advice(b_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            vec![s * (out - (a - b))]
+        });
+
+        meta.create_gate("arithmetic mul", |meta| {
+            let s = meta.query_selector(mul_selector);
+            let a = meta.query_advice(a_column, Rotation::cur());
+            let b = meta.query_advice(b_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            vec![s * (out - a * b)]
+        });
+
+        meta.create_gate("arithmetic mul_const", |meta| {
+            let s = meta.query_selector(mul_const_selector);
+            let a = meta.query_advice(a_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            let c = meta.query_fixed(constant_column);
+            vec![s * (out - c * a)]
+        });
+
+        ArithmeticConfig {
+            a_column,
+            b_column,
+            out_column,
+            constant_column,
+            add_selector,
+            sub_selector,
+            mul_selector,
+            mul_const_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Assign a 64-bit input value (range checked)
+    pub fn assign_input(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let cell = layouter.assign_region(
+            || "arithmetic input",
+            |mut region| {
+                region.assign_advice(|| "input", self.config.a_column, 0, || value.map(F::from))
+            },
+        )?;
+        self.range_check_chip()
+            .decompose_cell(layouter.namespace(|| "input range"), &cell)?;
+        Ok(cell)
+    }
+
+    /// a + b
+    pub fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "add", self.config.add_selector, a, b, |a, b| {
+            a.checked_add(b)
+        })
+    }
+
+    /// a - b (fails if b > a)
+    pub fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "sub", self.config.sub_selector, a, b, |a, b| {
+            a.checked_sub(b)
+        })
+    }
+
+    /// a · b
+    pub fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "mul", self.config.mul_selector, a, b, |a, b| {
+            a.checked_mul(b)
+        })
+    }
+
+    /// c · a for a constant c (one row, no second operand)
+    pub fn mul_const(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        c: u64,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let out = Self::checked(a.value(), None, |a, _| a.checked_mul(c))?;
+        let out_cell = layouter.assign_region(
+            || "arithmetic mul_const",
+            |mut region| {
+                self.config.mul_const_selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, self.config.a_column, 0)?;
+                region.assign_fixed(
+                    || "c",
+                    self.config.constant_column,
+                    0,
+                    || Value::known(F::from(c)),
+                )?;
+                region.assign_advice(|| "out", self.config.out_column, 0, || out)
+            },
+        )?;
+        self.range_check_chip()
+            .decompose_cell(layouter.namespace(|| "mul_const overflow"), &out_cell)?;
+        Ok(out_cell)
+    }
+
+    /// Evaluate an expression over one row
+    /// `inputs` maps column names to cells from `assign_input`; constants
+    /// are folded into `mul_const` where possible, otherwise assigned as inputs
+    pub fn evaluate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        expr: &ArithExpr,
+        inputs: &HashMap<String, AssignedCell<F, F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match expr {
+            ArithExpr::Column(name) => inputs.get(name).cloned().ok_or(Error::Synthesis),
+            ArithExpr::Const(c) => {
+                self.assign_input(layouter.namespace(|| "constant"), Value::known(*c))
+            }
+            ArithExpr::Mul(l, r) => match (l.as_ref(), r.as_ref()) {
+                (ArithExpr::Const(c), e) | (e, ArithExpr::Const(c)) => {
+                    let e = self.evaluate(layouter.namespace(|| "mul operand"), e, inputs)?;
+                    self.mul_const(layouter.namespace(|| "mul_const"), &e, *c)
+                }
+                (l, r) => {
+                    let l = self.evaluate(layouter.namespace(|| "mul lhs"), l, inputs)?;
+                    let r = self.evaluate(layouter.namespace(|| "mul rhs"), r, inputs)?;
+                    self.mul(layouter.namespace(|| "mul"), &l, &r)
+                }
+            },
+            ArithExpr::Add(l, r) => {
+                let l = self.evaluate(layouter.namespace(|| "add lhs"), l, inputs)?;
+                let r = self.evaluate(layouter.namespace(|| "add rhs"), r, inputs)?;
+                self.add(layouter.namespace(|| "add"), &l, &r)
+            }
+            ArithExpr::Sub(l, r) => {
+                let l = self.evaluate(layouter.namespace(|| "sub lhs"), l, inputs)?;
+                let r = self.evaluate(layouter.namespace(|| "sub rhs"), r, inputs)?;
+                self.sub(layouter.namespace(|| "sub"), &l, &r)
+            }
+        }
+    }
+
+    /// Assign a two-operand row and range check the result
+    fn binary(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        op: impl Fn(u64, u64) -> Option<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let out = Self::checked(a.value(), Some(b.value()), op)?;
+        let out_cell = layouter.assign_region(
+            || format!("arithmetic {}", name),
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, self.config.a_column, 0)?;
+                b.copy_advice(|| "b", &mut region, self.config.b_column, 0)?;
+                region.assign_advice(|| "out", self.config.out_column, 0, || out)
+            },
+        )?;
+        self.range_check_chip()
+            .decompose_cell(layouter.namespace(|| format!("{} overflow", name)), &out_cell)?;
+        Ok(out_cell)
+    }
+
+    /// Result witness; overflow (or a negative difference) is a synthesis error
+    fn checked(
+        a: Value<&F>,
+        b: Option<Value<&F>>,
+        op: impl Fn(u64, u64) -> Option<u64>,
+    ) -> Result<Value<F>, Error> {
+        let zero = F::ZERO;
+        let b = b.unwrap_or(Value::known(&zero));
+        let mut overflow = false;
+        let out = a.zip(b).map(|(a, b)| {
+            let result = op(field_to_u64(a), field_to_u64(b));
+            overflow = result.is_none();
+            F::from(result.unwrap_or(0))
+        });
+        if overflow {
+            return Err(Error::Synthesis);
+        }
+        Ok(out)
+    }
+
+    fn range_check_chip(&self) -> RangeCheckChip<F> {
+        RangeCheckChip::new(self.config.range_check_config.clone())
+    }
+}
@@ -0,0 +1,240 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+
+/// Boolean combination of comparison results
+/// Leaves index the comparison outputs of one row (in textual order)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PredicateTree {
+    Leaf(usize),
+    And(Box<PredicateTree>, Box<PredicateTree>),
+    Or(Box<PredicateTree>, Box<PredicateTree>),
+    Not(Box<PredicateTree>),
+}
+
+impl PredicateTree {
+    /// Number of leaves referenced (highest leaf index + 1)
+    pub fn num_leaves(&self) -> usize {
+        match self {
+            PredicateTree::Leaf(i) => i + 1,
+            PredicateTree::And(l, r) | PredicateTree::Or(l, r) => {
+                l.num_leaves().max(r.num_leaves())
+            }
+            PredicateTree::Not(e) => e.num_leaves(),
+        }
+    }
+
+    /// Evaluate outside the circuit (None if a leaf is missing)
+    pub fn evaluate(&self, leaves: &[bool]) -> Option<bool> {
+        match self {
+            PredicateTree::Leaf(i) => leaves.get(*i).copied(),
+            PredicateTree::And(l, r) => Some(l.evaluate(leaves)? && r.evaluate(leaves)?),
+            PredicateTree::Or(l, r) => Some(l.evaluate(leaves)? || r.evaluate(leaves)?),
+            PredicateTree::Not(e) => Some(!e.evaluate(leaves)?),
+        }
+    }
+}
+
+/// Boolean Gate Configuration
+/// Combines comparison outputs with AND/OR/NOT
+///
+/// # Column Allocation
+///
+/// - `a_column`: Left input (advice[10])
+/// - `b_column`: Right input, unused for NOT (advice[11])
+/// - `out_column`: Result (advice[12])
+///
+/// # Constraints
+///
+/// 1. **Boolean inputs**: `a · (1 - a) = 0`, `b · (1 - b) = 0`
+/// 2. **AND**: `out = a · b`
+/// 3. **OR**: `out = 1 - (1 - a) · (1 - b)`
+/// 4. **NOT**: `out = 1 - a`
+///
+/// # Note
+///
+/// - Inputs are copied from comparison outputs (equality constraints)
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct BooleanConfig {
+    pub a_column: Column<Advice>,
+    pub b_column: Column<Advice>,
+    pub out_column: Column<Advice>,
+
+    // Selectors
+    pub and_selector: Selector,
+    pub or_selector: Selector,
+    pub not_selector: Selector,
+}
+
+/// Boolean Chip
+/// AND/OR/NOT over 0/1 cells
+pub struct BooleanChip<F: PrimeField = Fr> {
+    config: BooleanConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> BooleanChip<F> {
+    /// Create a new BooleanChip
+    pub fn new(config: BooleanConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Boolean Gate
+    pub fn configure(meta: &mut ConstraintSystem<F>, config: &PoneglyphConfig) -> BooleanConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-12]: shared with Join Gate
+        let a_column = config.advice[10];
+        let b_column = config.advice[11];
+        let out_column = config.advice[12];
+
+        // Complex: the and/or gate adds them
+        let and_selector = meta.complex_selector();
+        let or_selector = meta.complex_selector();
+        let not_selector = meta.selector();
+
+        meta.create_gate("boolean and/or", |meta| {
+            let s_and = meta.query_selector(and_selector);
+            let s_or = meta.query_selector(or_selector);
+            let a = meta.query_advice(a_column, Rotation::cur());
+            let b = meta.query_advice(b_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+            let s = s_and.clone() + s_or.clone();
+
+            vec![
+                s.clone() * a.clone() * (one.clone() - a.clone()),
+                s * b.clone() * (one.clone() - b.clone()),
+                s_and * (out.clone() - a.clone() * b.clone()),
+                s_or * (out - (one.clone() - (one.clone() - a) * (one - b))),
+            ]
+        });
+
+        meta.create_gate("boolean not", |meta| {
+            let s = meta.query_selector(not_selector);
+            let a = meta.query_advice(a_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * a.clone() * (one.clone() - a.clone()),
+                s * (out - (one - a)),
+            ]
+        });
+
+        BooleanConfig {
+            a_column,
+            b_column,
+            out_column,
+            and_selector,
+            or_selector,
+            not_selector,
+        }
+    }
+
+    /// a AND b
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "and", self.config.and_selector, a, b, |a, b| a * b)
+    }
+
+    /// a OR b
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, "or", self.config.or_selector, a, b, |a, b| {
+            F::ONE - (F::ONE - a) * (F::ONE - b)
+        })
+    }
+
+    /// NOT a
+    pub fn not(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "boolean not",
+            |mut region| {
+                self.config.not_selector.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, self.config.a_column, 0)?;
+                region.assign_advice(
+                    || "out",
+                    self.config.out_column,
+                    0,
+                    || a.value().map(|a| F::ONE - a),
+                )
+            },
+        )
+    }
+
+    /// Evaluate a predicate tree over one row's comparison outputs
+    ///
+    /// # Return Value
+    ///
+    /// Cell holding 1 if the row passes the predicate, 0 otherwise
+    pub fn evaluate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        tree: &PredicateTree,
+        leaves: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        match tree {
+            PredicateTree::Leaf(i) => leaves.get(*i).cloned().ok_or(Error::Synthesis),
+            PredicateTree::And(l, r) => {
+                let l = self.evaluate(layouter.namespace(|| "and lhs"), l, leaves)?;
+                let r = self.evaluate(layouter.namespace(|| "and rhs"), r, leaves)?;
+                self.and(layouter.namespace(|| "and"), &l, &r)
+            }
+            PredicateTree::Or(l, r) => {
+                let l = self.evaluate(layouter.namespace(|| "or lhs"), l, leaves)?;
+                let r = self.evaluate(layouter.namespace(|| "or rhs"), r, leaves)?;
+                self.or(layouter.namespace(|| "or"), &l, &r)
+            }
+            PredicateTree::Not(e) => {
+                let e = self.evaluate(layouter.namespace(|| "not operand"), e, leaves)?;
+                self.not(layouter.namespace(|| "not"), &e)
+            }
+        }
+    }
+
+    /// Assign a two-input boolean row
+    fn binary(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &str,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        op: impl Fn(F, F) -> F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || format!("boolean {}", name),
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                let a = a.copy_advice(|| "a", &mut region, self.config.a_column, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, self.config.b_column, 0)?;
+                let out: Value<F> = a.value().zip(b.value()).map(|(a, b)| op(*a, *b));
+                region.assign_advice(|| "out", self.config.out_column, 0, || out)
+            },
+        )
+    }
+}
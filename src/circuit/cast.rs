@@ -0,0 +1,256 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use super::CastOp;
+
+/// Cast Gate Configuration
+/// `CAST(x AS type)` between integer widths and decimal scales
+///
+/// # Column Allocation
+///
+/// - `input_column`: Raw input x, then `⌊D/2⌋` on the next row (advice[10])
+/// - `output_column`: Raw result y, then max on the next row (advice[11])
+/// - `remainder_column`: Division remainder r (advice[12])
+/// - `slack_column`: `D - 1 - r` (advice[13])
+/// - `headroom_column`: `max - y` (advice[14])
+/// - `scale_column`: Multiplier M (fixed[0])
+/// - `bound_column`: Divisor D (fixed[1])
+///
+/// # Constraints
+///
+/// 1. **Rescale**: `x · M + ⌊D/2⌋ = y · D + r` with `M = 10^(to - from)` when
+///    the scale grows and `D = 10^(from - to)` when it shrinks (round half up)
+/// 2. **Remainder**: `slack = D - 1 - r`; r and slack are decomposed, so
+///    `r ∈ [0, D)` and y is the correctly rounded result
+/// 3. **Range**: `headroom = max - y`; y and headroom are decomposed, so
+///    `y ≤ max` (the target width or precision)
+///
+/// # Note
+///
+/// - Scales and bounds are fixed cells or advice cells constrained to
+///   constants, so the verifying key pins the cast (fixed columns can only be
+///   queried at the current row, hence the advice cells for the second pair)
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct CastConfig {
+    pub input_column: Column<Advice>,
+    pub output_column: Column<Advice>,
+    pub remainder_column: Column<Advice>,
+    pub slack_column: Column<Advice>,
+    pub headroom_column: Column<Advice>,
+    pub scale_column: Column<Fixed>,
+    pub bound_column: Column<Fixed>,
+
+    // Selectors
+    pub cast_selector: Selector,
+
+    // Range Check integration (remainder and range bounds)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Cast Chip
+/// Provable conversion between integer widths and decimal scales
+pub struct CastChip<F: PrimeField = Fr> {
+    config: CastConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> CastChip<F> {
+    /// Create a new CastChip
+    pub fn new(config: CastConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Cast Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> CastConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        // - fixed[0-1]: shared with Range Check (threshold, u)
+        let input_column = config.advice[10];
+        let output_column = config.advice[11];
+        let remainder_column = config.advice[12];
+        let slack_column = config.advice[13];
+        let headroom_column = config.advice[14];
+        let scale_column = config.fixed[0];
+        let bound_column = config.fixed[1];
+
+        let cast_selector = meta.selector();
+
+        meta.create_gate("cast", |meta| {
+            let s = meta.query_selector(cast_selector);
+            let x = meta.query_advice(input_column, Rotation::cur());
+            let y = meta.query_advice(output_column, Rotation::cur());
+            let r = meta.query_advice(remainder_column, Rotation::cur());
+            let slack = meta.query_advice(slack_column, Rotation::cur());
+            let headroom = meta.query_advice(headroom_column, Rotation::cur());
+            let multiplier = meta.query_fixed(scale_column);
+            let divisor = meta.query_fixed(bound_column);
+            let half = meta.query_advice(input_column, Rotation::next());
+            let max = meta.query_advice(output_column, Rotation::next());
+
+            let rescale = x * multiplier + half - y.clone() * divisor.clone() - r.clone();
+            let remainder = slack - (divisor - Expression::Constant(F::ONE) - r);
+            let range = headroom - (max - y);
+
+            vec![s.clone() * rescale, s.clone() * remainder, s * range]
+        });
+
+        CastConfig {
+            input_column,
+            output_column,
+            remainder_column,
+            slack_column,
+            headroom_column,
+            scale_column,
+            bound_column,
+            cast_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Cast one raw value
+    ///
+    /// # Return Value
+    ///
+    /// Result cell (raw value at `op.to_scale`)
+    ///
+    /// # Errors
+    ///
+    /// `Error::Synthesis` if the scales are out of range or the value does not
+    /// fit the target type
+    pub fn cast(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: &CastOp,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let multiplier = op.multiplier().ok_or(Error::Synthesis)?;
+        let divisor = op.divisor().ok_or(Error::Synthesis)?;
+        op.value.error_if_known_and(|&v| op.apply(v).is_none())?;
+
+        // apply() succeeded, so the quotient fits u64
+        let rounded = op.value.map(|v| {
+            let numerator = v as u128 * multiplier as u128 + (divisor / 2) as u128;
+            let out = (numerator / divisor as u128) as u64;
+            let r = (numerator % divisor as u128) as u64;
+            (out, r)
+        });
+
+        let (out_cell, cells) = layouter.assign_region(
+            || "cast",
+            |mut region| {
+                self.config.cast_selector.enable(&mut region, 0)?;
+
+                let fixed = [
+                    ("multiplier", self.config.scale_column, multiplier),
+                    ("divisor", self.config.bound_column, divisor),
+                ];
+                for (name, column, value) in fixed {
+                    region.assign_fixed(|| name, column, 0, || Value::known(F::from(value)))?;
+                }
+                let constants = [
+                    ("half", self.config.input_column, divisor / 2),
+                    ("max", self.config.output_column, op.max),
+                ];
+                for (name, column, value) in constants {
+                    region.assign_advice_from_constant(|| name, column, 1, F::from(value))?;
+                }
+
+                region.assign_advice(
+                    || "x",
+                    self.config.input_column,
+                    0,
+                    || op.value.map(F::from),
+                )?;
+                let out_cell = region.assign_advice(
+                    || "y",
+                    self.config.output_column,
+                    0,
+                    || rounded.map(|(out, _)| F::from(out)),
+                )?;
+                let remainder_cell = region.assign_advice(
+                    || "remainder",
+                    self.config.remainder_column,
+                    0,
+                    || rounded.map(|(_, r)| F::from(r)),
+                )?;
+                let slack_cell = region.assign_advice(
+                    || "slack",
+                    self.config.slack_column,
+                    0,
+                    || rounded.map(|(_, r)| F::from(divisor - 1 - r)),
+                )?;
+                let headroom_cell = region.assign_advice(
+                    || "headroom",
+                    self.config.headroom_column,
+                    0,
+                    || rounded.map(|(out, _)| F::from(op.max - out)),
+                )?;
+
+                Ok((
+                    out_cell.clone(),
+                    [out_cell, remainder_cell, slack_cell, headroom_cell],
+                ))
+            },
+        )?;
+
+        // y, r, slack, headroom ≥ 0 ⇒ r ∈ [0, D) and y ≤ max
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for (name, cell) in [
+            "cast result",
+            "cast remainder",
+            "cast slack",
+            "cast headroom",
+        ]
+        .into_iter()
+        .zip(&cells)
+        {
+            range_check_chip.decompose_cell(layouter.namespace(|| name), cell)?;
+        }
+
+        Ok(out_cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(from_scale: u32, to_scale: u32, max: u64) -> CastOp {
+        CastOp {
+            value: Value::unknown(),
+            from_scale,
+            to_scale,
+            max,
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        // 12.345 (scale 3) -> scale 2 rounds half up to 12.35
+        assert_eq!(op(3, 2, u64::MAX).apply(12_345), Some(1_235));
+        assert_eq!(op(3, 0, u64::MAX).apply(12_499), Some(12));
+        // Integer -> DECIMAL(5, 2)
+        assert_eq!(op(0, 2, 99_999).apply(999), Some(99_900));
+        assert_eq!(op(0, 2, 99_999).apply(1_000), None);
+        // Narrowing to 8 bits
+        assert_eq!(op(0, 0, 255).apply(255), Some(255));
+        assert_eq!(op(0, 0, 255).apply(256), None);
+        assert_eq!(op(0, 20, u64::MAX).apply(1), None);
+    }
+}
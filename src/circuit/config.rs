@@ -1,9 +1,208 @@
 use halo2_proofs::{
-    circuit::{Layouter, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance, Selector, TableColumn},
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Any, Column, ConstraintSystem, Error, Fixed, Instance, Selector, TableColumn,
+    },
 };
+use ff::{Field, PrimeField};
 use pasta_curves::pallas::Base as Fr;
 
+use super::aggregation::AggregationConfig;
+use super::group_by::GroupByConfig;
+use super::join::JoinConfig;
+use super::lookup_tables::{LookupTableSpec, LookupTables};
+use super::range_check::{DecompositionParams, RangeCheckConfig};
+use super::sort::SortConfig;
+
+/// Instance row of the database commitment
+/// `PoneglyphCircuit` hashes its scanned rows into it (see `TableScan`)
+pub const INSTANCE_DB_COMMITMENT_ROW: usize = 0;
+
+/// Instance row of the query result
+/// `PoneglyphCircuit` copies its final aggregation result into it
+pub const INSTANCE_QUERY_RESULT_ROW: usize = 1;
+
+/// Instance row of the request nonce
+/// Binds a proof to one request, so it can't be replayed as the answer to another
+pub const INSTANCE_NONCE_ROW: usize = 2;
+
+/// Instance row of the expiry bound (see `ExpiryBound`)
+pub const INSTANCE_EXPIRY_ROW: usize = 3;
+
+/// Instance row of the query hash (see `sql::query_hash`)
+/// Binds a proof to the query it answers, so it can't be passed off as the
+/// answer to another query over the same database; `PoneglyphCircuit` takes
+/// it from a constant of the verifying key
+pub const INSTANCE_QUERY_HASH_ROW: usize = 4;
+
+/// Instance row of the result commitment (see `QueryResult::commitment`)
+/// Binds a proof to the complete result it vouches for, so a verifier can
+/// check result rows revealed next to the proof; `PoneglyphCircuit` hashes
+/// it from a one-value result (see `PoneglyphCircuit::result_columns`)
+pub const INSTANCE_RESULT_COMMITMENT_ROW: usize = 5;
+
+/// Number of instance rows used by the circuit
+/// With `ThresholdMode::Instance`, range check thresholds follow from this row on
+pub const NUM_INSTANCE_ROWS: usize = 6;
+
+/// Expiry bound of a proof
+/// A proof attests that its result is valid up to (and including) this bound
+///
+/// # Encoding
+///
+/// `kind · 2^64 + value` with kind 1 = block height, 2 = timestamp (seconds),
+/// so a block-height bound can't be reinterpreted as a timestamp. Zero means
+/// the proof never expires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpiryBound {
+    /// No expiry
+    Never,
+    /// Valid up to this block height
+    BlockHeight(u64),
+    /// Valid up to this unix timestamp (seconds)
+    Timestamp(u64),
+}
+
+impl ExpiryBound {
+    /// Encode as instance value
+    pub fn to_field(&self) -> Fr {
+        let two_64 = Fr::from(u64::MAX) + Fr::ONE;
+        match self {
+            ExpiryBound::Never => Fr::ZERO,
+            ExpiryBound::BlockHeight(h) => two_64 + Fr::from(*h),
+            ExpiryBound::Timestamp(t) => two_64.double() + Fr::from(*t),
+        }
+    }
+
+    /// Decode from instance value (None if the value is not a valid encoding)
+    pub fn from_field(value: Fr) -> Option<Self> {
+        use ff::PrimeField;
+
+        let repr = value.to_repr();
+        let bytes = repr.as_ref();
+        if bytes[9..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        let mut low = [0u8; 8];
+        low.copy_from_slice(&bytes[..8]);
+        let bound = u64::from_le_bytes(low);
+        match bytes[8] {
+            0 if bound == 0 => Some(ExpiryBound::Never),
+            1 => Some(ExpiryBound::BlockHeight(bound)),
+            2 => Some(ExpiryBound::Timestamp(bound)),
+            _ => None,
+        }
+    }
+
+    /// Is a proof with this bound still fresh at `now`?
+    /// `now` must be of the same kind (block height vs timestamp)
+    pub fn is_fresh_at(&self, now: ExpiryBound) -> bool {
+        match (self, now) {
+            (ExpiryBound::Never, _) => true,
+            (ExpiryBound::BlockHeight(bound), ExpiryBound::BlockHeight(h)) => h <= *bound,
+            (ExpiryBound::Timestamp(bound), ExpiryBound::Timestamp(t)) => t <= *bound,
+            _ => false,
+        }
+    }
+}
+
+/// Public inputs of `PoneglyphCircuit`
+/// Typed view over the single instance column (see `PoneglyphConfig`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputs {
+    pub db_commitment: Fr,
+    pub query_result: Fr,
+    pub nonce: Fr,
+    pub expiry: ExpiryBound,
+    /// Hash of the query (zero when the caller doesn't bind one)
+    pub query_hash: Fr,
+    /// Commitment to the result rows (zero when the caller doesn't bind one)
+    pub result_commitment: Fr,
+    /// Range check thresholds (`ThresholdMode::Instance` only), in operation order
+    pub thresholds: Vec<u64>,
+}
+
+impl PublicInputs {
+    /// Create public inputs without nonce, expiry, query hash and result commitment
+    pub fn new(db_commitment: Fr, query_result: Fr) -> Self {
+        Self {
+            db_commitment,
+            query_result,
+            nonce: Fr::ZERO,
+            expiry: ExpiryBound::Never,
+            query_hash: Fr::ZERO,
+            result_commitment: Fr::ZERO,
+            thresholds: Vec::new(),
+        }
+    }
+
+    /// Set request nonce
+    pub fn with_nonce(mut self, nonce: Fr) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Set expiry bound
+    pub fn with_expiry(mut self, expiry: ExpiryBound) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Set query hash (see `sql::query_hash`)
+    pub fn with_query_hash(mut self, query_hash: Fr) -> Self {
+        self.query_hash = query_hash;
+        self
+    }
+
+    /// Set result commitment (see `QueryResult::commitment`)
+    pub fn with_result_commitment(mut self, result_commitment: Fr) -> Self {
+        self.result_commitment = result_commitment;
+        self
+    }
+
+    /// Set public range check thresholds (see `ThresholdMode::Instance`)
+    pub fn with_thresholds(mut self, thresholds: Vec<u64>) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Instance column values (format used by `MockProver::run` and `create_proof`)
+    pub fn to_instance(&self) -> Vec<Vec<Fr>> {
+        let mut rows = vec![Fr::ZERO; NUM_INSTANCE_ROWS];
+        rows[INSTANCE_DB_COMMITMENT_ROW] = self.db_commitment;
+        rows[INSTANCE_QUERY_RESULT_ROW] = self.query_result;
+        rows[INSTANCE_NONCE_ROW] = self.nonce;
+        rows[INSTANCE_EXPIRY_ROW] = self.expiry.to_field();
+        rows[INSTANCE_QUERY_HASH_ROW] = self.query_hash;
+        rows[INSTANCE_RESULT_COMMITMENT_ROW] = self.result_commitment;
+        rows.extend(self.thresholds.iter().map(|&t| Fr::from(t)));
+        vec![rows]
+    }
+
+    /// Parse instance column values (None if rows are missing or malformed)
+    pub fn from_instance(instance: &[Vec<Fr>]) -> Option<Self> {
+        let rows = instance.first()?;
+        let row = |i: usize| rows.get(i).copied().unwrap_or(Fr::ZERO);
+        let thresholds = rows
+            .iter()
+            .skip(NUM_INSTANCE_ROWS)
+            .map(|t| {
+                let value = crate::circuit::range_check::field_to_u64(t);
+                (Fr::from(value) == *t).then_some(value)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            db_commitment: row(INSTANCE_DB_COMMITMENT_ROW),
+            query_result: row(INSTANCE_QUERY_RESULT_ROW),
+            nonce: row(INSTANCE_NONCE_ROW),
+            expiry: ExpiryBound::from_field(row(INSTANCE_EXPIRY_ROW))?,
+            query_hash: row(INSTANCE_QUERY_HASH_ROW),
+            result_commitment: row(INSTANCE_RESULT_COMMITMENT_ROW),
+            thresholds,
+        })
+    }
+}
+
 /// Main circuit configuration
 /// According to Paper Section 5.1: BN254 curve, IPA commitment
 ///
@@ -21,12 +220,28 @@ use pasta_curves::pallas::Base as Fr;
 /// - `fixed[1]`: u value used in Range Check
 ///
 /// ## Instance Column (1 column)
-/// - `instance`: For public data (database commitment, query result)
+/// - `instance`: For public data (database commitment, query result, nonce, query hash,
+///   result commitment)
 ///   - Row 0: Database commitment
 ///   - Row 1: Query result
+///   - Row 2: Request nonce (replay protection)
+///   - Row 3: Expiry bound (block height or timestamp)
+///   - Row 4: Query hash
+///   - Row 5: Result commitment
 ///
-/// ## Table Column (1 column)
+/// ## Table Columns
 /// - `lookup_table`: Lookup table for values 0-255 (for 8-bit chunks)
+/// - `lookup_tables`: Registry of every table column, shared between chips
+///   (see `LookupTables`); `lookup_table` is its `Range { bits: 8 }` table
+/// - Chunk table of `decompose_bits`: `Range { bits: chunk_bits }` (the same
+///   table as `lookup_table` with the default 8-bit chunks)
+///
+/// # Field
+///
+/// The config only holds columns and selectors; `configure` and the region
+/// helpers are generic over `F: PrimeField`, as are the chips. `PublicInputs`
+/// and `PoneglyphCircuit` are instantiated for the pasta base field used by
+/// the IPA backend.
 #[derive(Clone, Debug)]
 pub struct PoneglyphConfig {
     // Advice columns - for private data
@@ -41,9 +256,13 @@ pub struct PoneglyphConfig {
     // Table column - for lookup table (0-255 values)
     pub lookup_table: TableColumn,
 
+    // Lookup tables registered by the chips (includes lookup_table)
+    pub lookup_tables: LookupTables,
+
     // Instance columns - for public data (commitment, query result)
     // Row 0: Database commitment
     // Row 1: Query result
+    // Rows 2-5: Request nonce, expiry bound, query hash, result commitment
     pub instance: Column<Instance>,
 
     // Selectors - to enable/disable gates
@@ -51,13 +270,59 @@ pub struct PoneglyphConfig {
     pub range_check_selector: Selector,
     pub less_than_selector: Selector,
     pub decomposition_selector: Selector,
-    pub diff_lookup_selector: Selector,
     // Separate selector for Sort (to avoid conflict with less_than_selector)
     pub sort_selector: Selector,
+    // Range Check: lo <= x < hi (BETWEEN)
+    pub between_selector: Selector,
+    // Range Check: x < t with t from the instance column
+    pub public_less_than_selector: Selector,
+    // Range Check: chained decomposition rows (decompose_bits)
+    pub chain_selector: Selector,
+    // Range Check: offset decomposition of a signed cell (decompose_signed_cell)
+    pub signed_decomposition_selector: Selector,
+
+    // Chunk width and chunk limit of decompose_bits
+    pub decomposition: DecompositionParams,
+}
+
+/// Configs of the operator chips `PoneglyphConfig::configure` creates gates
+/// for, on the shared columns
+#[derive(Clone, Debug)]
+pub struct OperatorConfigs {
+    pub range_check: RangeCheckConfig,
+    pub sort: SortConfig,
+    pub group_by: GroupByConfig,
+    pub join: JoinConfig,
+    pub aggregation: AggregationConfig,
 }
 
 impl PoneglyphConfig {
-    pub fn configure(meta: &mut ConstraintSystem<Fr>) -> Self {
+    pub fn configure<F: PrimeField>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self::configure_with(meta, DecompositionParams::default())
+    }
+
+    /// Configure with other decomposition parameters (e.g. 16-bit chunks)
+    ///
+    /// # Panics
+    ///
+    /// If `decomposition` is invalid (see `DecompositionParams::validate`)
+    pub fn configure_with<F: PrimeField>(
+        meta: &mut ConstraintSystem<F>,
+        decomposition: DecompositionParams,
+    ) -> Self {
+        Self::configure_operators(meta, decomposition).0
+    }
+
+    /// `configure_with`, also returning the configs of the operator chips it
+    /// creates gates for (see `PoneglyphCircuitConfig`)
+    pub(crate) fn configure_operators<F: PrimeField>(
+        meta: &mut ConstraintSystem<F>,
+        decomposition: DecompositionParams,
+    ) -> (Self, OperatorConfigs) {
+        if let Err(e) = decomposition.validate::<F>() {
+            panic!("{}", e);
+        }
+
         // Create advice columns
         // Expanded from 10 to 15 for Join Gate support
         //
@@ -94,7 +359,16 @@ impl PoneglyphConfig {
         ];
 
         // Table column - for lookup table (0-255 values)
-        let lookup_table = meta.lookup_table_column();
+        let lookup_tables = LookupTables::default();
+        let lookup_table =
+            lookup_tables.register(meta, "range_check", LookupTableSpec::Range { bits: 8 })[0];
+        lookup_tables.register(
+            meta,
+            "range_check",
+            LookupTableSpec::Range {
+                bits: decomposition.chunk_bits,
+            },
+        );
 
         // Instance column - for public data
         // Row 0: Database commitment
@@ -106,8 +380,11 @@ impl PoneglyphConfig {
         let range_check_selector = meta.complex_selector();
         let less_than_selector = meta.selector();
         let decomposition_selector = meta.selector();
-        let diff_lookup_selector = meta.complex_selector();
         let sort_selector = meta.selector();
+        let between_selector = meta.selector();
+        let public_less_than_selector = meta.selector();
+        let chain_selector = meta.complex_selector();
+        let signed_decomposition_selector = meta.selector();
 
         // Enable fixed columns (for threshold and u values)
         meta.enable_constant(fixed[0]);
@@ -126,67 +403,72 @@ impl PoneglyphConfig {
             advice,
             fixed,
             lookup_table,
+            lookup_tables,
             instance,
             range_check_selector,
             less_than_selector,
             decomposition_selector,
-            diff_lookup_selector,
             sort_selector,
+            between_selector,
+            public_less_than_selector,
+            chain_selector,
+            signed_decomposition_selector,
+            decomposition,
         };
 
         // Configure all gates
-        let _range_check_config =
+        let range_check =
             crate::circuit::range_check::RangeCheckChip::configure(meta, &temp_config);
-        let _sort_config =
-            crate::circuit::sort::SortChip::configure(meta, &temp_config, &_range_check_config);
-        let _group_by_config = crate::circuit::group_by::GroupByChip::configure(
-            meta,
-            &temp_config,
-            &_range_check_config,
-        );
-        let _join_config = crate::circuit::join::JoinChip::configure(
+        let sort = crate::circuit::sort::SortChip::configure(meta, &temp_config, &range_check);
+        let group_by =
+            crate::circuit::group_by::GroupByChip::configure(meta, &temp_config, &range_check);
+        let join = crate::circuit::join::JoinChip::configure(
             meta,
             &temp_config,
-            &_range_check_config,
-            &_sort_config,
+            &range_check,
+            &sort,
         );
-        let _aggregation_config = crate::circuit::aggregation::AggregationChip::configure(
+        let aggregation = crate::circuit::aggregation::AggregationChip::configure(
             meta,
             &temp_config,
-            &_group_by_config,
-            &_range_check_config,
+            &group_by,
+            &range_check,
         );
 
-        temp_config
+        let operators = OperatorConfigs {
+            range_check,
+            sort,
+            group_by,
+            join,
+            aggregation,
+        };
+        (temp_config, operators)
+    }
+
+    /// Chunk table of `decompose_bits` (0 .. 2^chunk_bits - 1)
+    pub fn chunk_table(&self) -> TableColumn {
+        self.lookup_tables
+            .get(LookupTableSpec::Range {
+                bits: self.decomposition.chunk_bits,
+            })
+            .map_or(self.lookup_table, |columns| columns[0])
     }
 
-    /// Load lookup table (values 0-255)
+    /// Load lookup tables (values 0-255 and every other registered range table)
     /// According to Paper Section 4.1: Lookup table for 8-bit chunks
-    /// According to Halo2 API: assign_table should be used
+    /// Named tables (IN-lists, dictionaries) are loaded by the chips holding
+    /// their rows
     ///
     /// # Usage
     ///
     /// ```rust,ignore
     /// config.load_lookup_table(&mut layouter)?;
     /// ```
-    pub fn load_lookup_table(&self, layouter: &mut impl Layouter<Fr>) -> Result<(), Error> {
-        layouter.assign_table(
-            || "8-bit lookup table (0-255)",
-            |mut table| {
-                // Write values 0-255 to table column
-                // Note: Halo2 example uses 1-256 but we use 0-255
-                // because 8-bit chunks are in range 0-255
-                for i in 0..256 {
-                    table.assign_cell(
-                        || format!("lookup value {}", i),
-                        self.lookup_table,
-                        i,
-                        || Value::known(Fr::from(i as u64)),
-                    )?;
-                }
-                Ok(())
-            },
-        )
+    pub fn load_lookup_table<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.lookup_tables.load_ranges(layouter)
     }
 
     /// Read public input from instance column and copy to advice column
@@ -206,11 +488,11 @@ impl PoneglyphConfig {
     ///
     /// Assigning values to instance column is done on the prover side (in MockProver::run() call).
     /// This function is used to read values from instance column and use them in constraints.
-    pub fn read_public_input(
+    pub fn read_public_input<F: PrimeField>(
         &self,
-        layouter: &mut impl Layouter<Fr>,
+        layouter: &mut impl Layouter<F>,
         row: usize,
-    ) -> Result<Value<Fr>, Error> {
+    ) -> Result<Value<F>, Error> {
         layouter.assign_region(
             || format!("read public input row {}", row),
             |mut region| {
@@ -232,6 +514,60 @@ impl PoneglyphConfig {
         )
     }
 
+    /// Bind a witness value to an instance row
+    /// The value is assigned to an advice cell and copy-constrained to
+    /// `instance[row]`, so the proof only verifies with that public input
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// config.bind_public_input(&mut layouter, nonce, INSTANCE_NONCE_ROW)?;
+    /// ```
+    pub fn bind_public_input<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        value: Value<F>,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let cell = layouter.assign_region(
+            || format!("bind public input row {}", row),
+            |mut region| {
+                region.assign_advice(
+                    || format!("public_input_{}", row),
+                    self.advice[0],
+                    0,
+                    || value,
+                )
+            },
+        )?;
+        layouter.constrain_instance(cell.cell(), self.instance, row)?;
+        Ok(cell)
+    }
+
+    /// Bind a constant to an instance row
+    /// Like `bind_public_input`, but the cell is fixed by the verifying key:
+    /// a key made with another value doesn't verify proofs for this one
+    pub fn bind_public_constant<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        value: F,
+        row: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let cell = layouter.assign_region(
+            || format!("bind public constant row {}", row),
+            |mut region| {
+                region.assign_advice_from_constant(
+                    || format!("public_constant_{}", row),
+                    self.advice[0],
+                    0,
+                    value,
+                )
+            },
+        )?;
+        layouter.constrain_instance(cell.cell(), self.instance, row)?;
+        Ok(cell)
+    }
+
     /// Assign public inputs to instance column (helper function)
     /// According to Paper Section 5.1: Database commitment and query result should be public inputs
     ///
@@ -241,10 +577,14 @@ impl PoneglyphConfig {
     /// use halo2_proofs::dev::MockProver;
     ///
     /// // Used in MockProver::run() call
-    /// let public_inputs = vec![
-    ///     vec![db_commitment], // Row 0: Database commitment
-    ///     vec![query_result],  // Row 1: Query result
-    /// ];
+    /// let public_inputs = vec![vec![
+    ///     db_commitment, // Row 0: Database commitment
+    ///     query_result,  // Row 1: Query result
+    ///     nonce,         // Row 2: Request nonce
+    ///     expiry,        // Row 3: Expiry bound
+    ///     query_hash,    // Row 4: Query hash
+    ///     result_commitment, // Row 5: Result commitment
+    /// ]];
     /// let prover = MockProver::run(k, &circuit, public_inputs)?;
     /// ```
     ///
@@ -255,12 +595,92 @@ impl PoneglyphConfig {
     ///
     /// # Public Input Layout
     ///
+    /// Single instance column:
     /// - Row 0: Database commitment (Fr)
     /// - Row 1: Query result (Fr)
+    /// - Row 2: Request nonce (Fr, zero when the caller doesn't use one)
+    /// - Row 3: Expiry bound (Fr, zero = never expires)
+    /// - Row 4: Query hash (Fr, zero when the caller doesn't bind one)
+    /// - Row 5: Result commitment (Fr, zero when the caller doesn't bind one)
+    ///
+    /// See `PublicInputs` for setting nonce, expiry, query hash and result commitment.
     pub fn get_public_input_layout(db_commitment: Fr, query_result: Fr) -> Vec<Vec<Fr>> {
+        PublicInputs::new(db_commitment, query_result).to_instance()
+    }
+
+    /// Public input layout including the request nonce
+    /// See `get_public_input_layout` for the row layout
+    pub fn get_public_input_layout_with_nonce(
+        db_commitment: Fr,
+        query_result: Fr,
+        nonce: Fr,
+    ) -> Vec<Vec<Fr>> {
+        PublicInputs::new(db_commitment, query_result)
+            .with_nonce(nonce)
+            .to_instance()
+    }
+}
+
+/// Columns and selectors a circuit config allocated
+/// Prover tooling numbers columns by their position here rather than by
+/// halo2's internal column indices, which are private to halo2
+pub trait ConfigColumns {
+    /// The shared columns
+    fn poneglyph_config(&self) -> &PoneglyphConfig;
+
+    /// Advice columns, in allocation order
+    fn advice_columns(&self) -> Vec<Column<Advice>> {
+        self.poneglyph_config().advice.to_vec()
+    }
+
+    /// Fixed columns, in allocation order (lookup table columns excluded)
+    fn fixed_columns(&self) -> Vec<Column<Fixed>> {
+        self.poneglyph_config().fixed.to_vec()
+    }
+
+    /// Fixed columns enabled for constants, in `enable_constant` order
+    fn constant_columns(&self) -> Vec<Column<Fixed>> {
+        self.poneglyph_config().fixed.to_vec()
+    }
+
+    /// Columns in the permutation argument: the instance column, every advice
+    /// column and the constant columns (`enable_constant` enables equality)
+    fn equality_columns(&self) -> Vec<Column<Any>> {
+        let config = self.poneglyph_config();
+        let mut columns: Vec<Column<Any>> = vec![config.instance.into()];
+        columns.extend(self.advice_columns().into_iter().map(Column::<Any>::from));
+        columns.extend(self.constant_columns().into_iter().map(Column::<Any>::from));
+        columns
+    }
+
+    /// Columns of every registered lookup table, in registration order
+    fn table_columns(&self) -> Vec<TableColumn> {
+        self.poneglyph_config()
+            .lookup_tables
+            .tables_registered()
+            .into_iter()
+            .flat_map(|table| table.columns)
+            .collect()
+    }
+
+    /// Selectors, in allocation order
+    fn selectors(&self) -> Vec<Selector> {
+        let config = self.poneglyph_config();
         vec![
-            vec![db_commitment], // Row 0: Database commitment
-            vec![query_result],  // Row 1: Query result
+            config.range_check_selector,
+            config.less_than_selector,
+            config.decomposition_selector,
+            config.sort_selector,
+            config.between_selector,
+            config.public_less_than_selector,
+            config.chain_selector,
+            config.signed_decomposition_selector,
         ]
     }
 }
+
+impl ConfigColumns for PoneglyphConfig {
+    fn poneglyph_config(&self) -> &PoneglyphConfig {
+        self
+    }
+}
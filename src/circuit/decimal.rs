@@ -0,0 +1,469 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+
+/// Largest scale whose factor `10^scale` fits in a u64
+pub const MAX_DECIMAL_SCALE: u32 = 19;
+
+/// Fixed-point decimal value
+/// `raw` is the value multiplied by `10^scale` (e.g. 12.34 with scale 2 is raw 1234)
+///
+/// # Note
+///
+/// The scale is a property of the column (see `DatabaseTable::set_scale`);
+/// in the circuit only `raw` is assigned, scales are resolved at compile time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Decimal {
+    pub raw: u64,
+    pub scale: u32,
+}
+
+impl Decimal {
+    /// Create decimal from raw value and scale
+    pub fn new(raw: u64, scale: u32) -> Self {
+        Self { raw, scale }
+    }
+
+    /// Parse decimal literal (e.g. "12.34") at the given scale
+    /// Literals with more fractional digits than `scale`, scales above
+    /// `MAX_DECIMAL_SCALE` and values that overflow the raw u64 are rejected
+    pub fn parse(s: &str, scale: u32) -> Result<Self, String> {
+        let s = s.trim();
+        let factor = Self::scale_factor(scale).ok_or_else(|| {
+            format!(
+                "Decimal scale {} exceeds the maximum of {}",
+                scale, MAX_DECIMAL_SCALE
+            )
+        })?;
+        let (int_part, frac_part) = match s.split_once('.') {
+            Some((i, f)) => (i, f),
+            None => (s, ""),
+        };
+        if frac_part.len() > scale as usize {
+            return Err(format!(
+                "Decimal literal {} has more than {} fractional digits",
+                s, scale
+            ));
+        }
+        let int_value = if int_part.is_empty() {
+            0
+        } else {
+            int_part
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid decimal literal: {}", s))?
+        };
+        let frac_value = if frac_part.is_empty() {
+            0
+        } else {
+            frac_part
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid decimal literal: {}", s))?
+        };
+        // frac_part has at most `scale` digits, so the padding factor fits
+        let frac_value = Self::scale_factor(scale - frac_part.len() as u32)
+            .and_then(|pad| frac_value.checked_mul(pad));
+
+        frac_value
+            .zip(int_value.checked_mul(factor))
+            .and_then(|(frac, int)| int.checked_add(frac))
+            .map(|raw| Self { raw, scale })
+            .ok_or_else(|| format!("Decimal literal {} overflows u64", s))
+    }
+
+    /// Scale factor `10^scale` (None above `MAX_DECIMAL_SCALE`)
+    pub fn scale_factor(scale: u32) -> Option<u64> {
+        10u64.checked_pow(scale)
+    }
+
+    /// Rescale to a larger scale (exact); smaller scales return None
+    pub fn rescale(&self, scale: u32) -> Option<Self> {
+        if scale < self.scale {
+            return None;
+        }
+        Self::scale_factor(scale - self.scale)
+            .and_then(|factor| self.raw.checked_mul(factor))
+            .map(|raw| Self { raw, scale })
+    }
+
+    /// Divisor used when multiplying values of scales `lhs` and `rhs` into `out`
+    /// `a · b` has scale `lhs + rhs`, so it is divided by `10^(lhs + rhs - out)`
+    pub fn product_divisor(lhs: u32, rhs: u32, out: u32) -> Option<u64> {
+        (lhs + rhs)
+            .checked_sub(out)
+            .and_then(|d| 10u64.checked_pow(d))
+    }
+
+    /// Rounded product (round half up), same formula as the `decimal mul` gate
+    /// Returns (quotient, remainder); None for a zero divisor or a quotient
+    /// that doesn't fit in a u64
+    pub fn mul_round(lhs: u64, rhs: u64, divisor: u64) -> Option<(u64, u64)> {
+        if divisor == 0 {
+            return None;
+        }
+        let product = (lhs as u128)
+            .checked_mul(rhs as u128)?
+            .checked_add((divisor / 2) as u128)?;
+        let quotient = u64::try_from(product / divisor as u128).ok()?;
+        let remainder = (product % divisor as u128) as u64;
+        Some((quotient, remainder))
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.raw);
+        }
+        // Above the maximum scale every raw value is below 10^scale
+        let (int, frac) = match Self::scale_factor(self.scale) {
+            Some(factor) => (self.raw / factor, self.raw % factor),
+            None => (0, self.raw),
+        };
+        write!(f, "{}.{:0width$}", int, frac, width = self.scale as usize)
+    }
+}
+
+/// Decimal Gate Configuration
+/// Fixed-point arithmetic over raw values (addition, rounded multiplication, comparison)
+///
+/// # Column Allocation
+///
+/// - `lhs_column`: Left operand (advice[10])
+/// - `rhs_column`: Right operand (advice[11])
+/// - `out_column`: Result (advice[12])
+/// - `remainder_column`: Division remainder r (advice[13])
+/// - `slack_column`: `S - 1 - r` (advice[14])
+/// - `half_column`: `⌊S/2⌋` for rounding (fixed[0])
+/// - `divisor_column`: Divisor S (fixed[1])
+///
+/// # Constraints
+///
+/// 1. **Addition**: `lhs + rhs = out` (operands share the same scale)
+/// 2. **Rounded Multiplication**: `lhs · rhs + ⌊S/2⌋ = out · S + r`
+///    - `slack = S - 1 - r`, both r and slack are decomposed into 8-bit chunks,
+///      so `r ∈ [0, S)` and out is the correctly rounded quotient
+///    - out is decomposed as well, so the result cannot wrap around the field
+/// 3. **Comparison**: raw values at a common scale are compared with the
+///    Range Check `x < t` gate
+///
+/// # Note
+///
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct DecimalConfig {
+    pub lhs_column: Column<Advice>,
+    pub rhs_column: Column<Advice>,
+    pub out_column: Column<Advice>,
+    pub remainder_column: Column<Advice>,
+    pub slack_column: Column<Advice>,
+    pub half_column: Column<Fixed>,
+    pub divisor_column: Column<Fixed>,
+
+    // Selectors
+    pub add_selector: Selector,
+    pub mul_selector: Selector,
+
+    // Range Check integration (overflow and remainder bounds)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Decimal Chip
+/// Fixed-point arithmetic gate
+pub struct DecimalChip<F: PrimeField = Fr> {
+    config: DecimalConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> DecimalChip<F> {
+    /// Create a new DecimalChip
+    pub fn new(config: DecimalConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Decimal Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> DecimalConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        // - fixed[0-1]: shared with Range Check (threshold, u)
+        let lhs_column = config.advice[10];
+        let rhs_column = config.advice[11];
+        let out_column = config.advice[12];
+        let remainder_column = config.advice[13];
+        let slack_column = config.advice[14];
+        let half_column = config.fixed[0];
+        let divisor_column = config.fixed[1];
+
+        let add_selector = meta.selector();
+        let mul_selector = meta.selector();
+
+        // Addition: out = lhs + rhs
+        meta.create_gate("decimal add", |meta| {
+            let s = meta.query_selector(add_selector);
+            let lhs = meta.query_advice(lhs_column, Rotation::cur());
+            let rhs = meta.query_advice(rhs_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+
+            vec![s * (lhs + rhs - out)]
+        });
+
+        // Rounded multiplication: lhs · rhs + ⌊S/2⌋ = out · S + r, slack = S - 1 - r
+        meta.create_gate("decimal mul", |meta| {
+            let s = meta.query_selector(mul_selector);
+            let lhs = meta.query_advice(lhs_column, Rotation::cur());
+            let rhs = meta.query_advice(rhs_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+            let r = meta.query_advice(remainder_column, Rotation::cur());
+            let slack = meta.query_advice(slack_column, Rotation::cur());
+            let half = meta.query_fixed(half_column);
+            let divisor = meta.query_fixed(divisor_column);
+
+            let product_check = lhs * rhs + half - out * divisor.clone() - r.clone();
+            let slack_check = slack - (divisor - Expression::Constant(F::ONE) - r);
+
+            vec![s.clone() * product_check, s * slack_check]
+        });
+
+        DecimalConfig {
+            lhs_column,
+            rhs_column,
+            out_column,
+            remainder_column,
+            slack_column,
+            half_column,
+            divisor_column,
+            add_selector,
+            mul_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Add two raw values of the same scale
+    ///
+    /// # Return Value
+    ///
+    /// Result cell (decomposed, so the sum is a valid u64)
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: Value<u64>,
+        rhs: Value<u64>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let out_cell = layouter.assign_region(
+            || "decimal add",
+            |mut region| {
+                self.config.add_selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "lhs", self.config.lhs_column, 0, || lhs.map(F::from))?;
+                region.assign_advice(|| "rhs", self.config.rhs_column, 0, || rhs.map(F::from))?;
+                region.assign_advice(
+                    || "out",
+                    self.config.out_column,
+                    0,
+                    || lhs.zip(rhs).map(|(a, b)| F::from(a) + F::from(b)),
+                )
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        range_check_chip.decompose_cell(layouter.namespace(|| "add overflow"), &out_cell)?;
+
+        Ok(out_cell)
+    }
+
+    /// Multiply two raw values and divide by `divisor` with round half up
+    /// Use `Decimal::product_divisor` to derive the divisor from column scales
+    ///
+    /// # Return Value
+    ///
+    /// Result cell `round(lhs · rhs / divisor)`
+    pub fn mul_round(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: Value<u64>,
+        rhs: Value<u64>,
+        divisor: u64,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if divisor == 0 {
+            return Err(Error::Synthesis);
+        }
+
+        let rounded = lhs.zip(rhs).map(|(a, b)| Decimal::mul_round(a, b, divisor));
+        rounded.error_if_known_and(Option::is_none)?;
+        // The product was checked above, so the quotient fits u64
+        let rounded = rounded.map(Option::unwrap_or_default);
+
+        let (out_cell, remainder_cell, slack_cell) = layouter.assign_region(
+            || "decimal mul",
+            |mut region| {
+                self.config.mul_selector.enable(&mut region, 0)?;
+
+                region.assign_fixed(
+                    || "half",
+                    self.config.half_column,
+                    0,
+                    || Value::known(F::from(divisor / 2)),
+                )?;
+                region.assign_fixed(
+                    || "divisor",
+                    self.config.divisor_column,
+                    0,
+                    || Value::known(F::from(divisor)),
+                )?;
+
+                region.assign_advice(|| "lhs", self.config.lhs_column, 0, || lhs.map(F::from))?;
+                region.assign_advice(|| "rhs", self.config.rhs_column, 0, || rhs.map(F::from))?;
+
+                let out_cell = region.assign_advice(
+                    || "out",
+                    self.config.out_column,
+                    0,
+                    || rounded.map(|(q, _)| F::from(q)),
+                )?;
+                let remainder_cell = region.assign_advice(
+                    || "remainder",
+                    self.config.remainder_column,
+                    0,
+                    || rounded.map(|(_, r)| F::from(r)),
+                )?;
+                let slack_cell = region.assign_advice(
+                    || "slack",
+                    self.config.slack_column,
+                    0,
+                    || rounded.map(|(_, r)| F::from(divisor - 1 - r)),
+                )?;
+
+                Ok((out_cell, remainder_cell, slack_cell))
+            },
+        )?;
+
+        // r ≥ 0 and S - 1 - r ≥ 0 ⇒ r ∈ [0, S); out fits in 64 bits
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        range_check_chip.decompose_cell(layouter.namespace(|| "mul result"), &out_cell)?;
+        range_check_chip.decompose_cell(layouter.namespace(|| "mul remainder"), &remainder_cell)?;
+        range_check_chip.decompose_cell(layouter.namespace(|| "mul slack"), &slack_cell)?;
+
+        Ok(out_cell)
+    }
+
+    /// Decimal x < t check
+    /// Both sides are brought to the larger of the two scales, then the raw
+    /// values are compared with the Range Check `x < t` gate
+    ///
+    /// # Return Value
+    ///
+    /// Boolean check cell (1 = x < t, 0 = x >= t)
+    pub fn check_less_than(
+        &self,
+        layouter: impl Layouter<F>,
+        x: Value<u64>,
+        x_scale: u32,
+        threshold: Decimal,
+        u: u64,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let scale = x_scale.max(threshold.scale);
+        let x_factor = Decimal::scale_factor(scale - x_scale).ok_or(Error::Synthesis)?;
+        let threshold = threshold.rescale(scale).ok_or(Error::Synthesis)?;
+        let x = x.map(|v| v.checked_mul(x_factor));
+        x.error_if_known_and(Option::is_none)?;
+
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        range_check_chip.check_less_than(
+            layouter,
+            x.map(Option::unwrap_or_default),
+            threshold.raw,
+            u,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let d = Decimal::parse("12.34", 2).unwrap();
+        assert_eq!(d.raw, 1234);
+        assert_eq!(d.to_string(), "12.34");
+        assert_eq!(Decimal::parse("7.5", 2).unwrap().raw, 750);
+        assert_eq!(Decimal::parse("3", 2).unwrap().to_string(), "3.00");
+        assert!(Decimal::parse("1.234", 2).is_err());
+        assert!(Decimal::parse("abc", 2).is_err());
+    }
+
+    #[test]
+    fn test_mul_round() {
+        // 2.50 * 3 = 7.50 (scales 2 and 0 into 2, divisor 1)
+        assert_eq!(Decimal::product_divisor(2, 0, 2), Some(1));
+        assert_eq!(Decimal::mul_round(250, 3, 1), Some((750, 0)));
+
+        // 1.25 * 1.25 = 1.5625 -> 1.56 (scales 2 and 2 into 2, divisor 100)
+        assert_eq!(Decimal::product_divisor(2, 2, 2), Some(100));
+        assert_eq!(Decimal::mul_round(125, 125, 100).unwrap().0, 156);
+
+        // 0.05 * 0.10 = 0.005 -> rounds half up to 0.01
+        assert_eq!(Decimal::mul_round(5, 10, 100).unwrap().0, 1);
+    }
+
+    #[test]
+    fn test_max_scale() {
+        assert_eq!(
+            Decimal::scale_factor(MAX_DECIMAL_SCALE),
+            Some(10u64.pow(19))
+        );
+        assert_eq!(Decimal::scale_factor(MAX_DECIMAL_SCALE + 1), None);
+
+        // 1.0 at scale 19 is raw 10^19; 1.9 is above u64::MAX (~1.84 · 10^19)
+        let one = Decimal::parse("1.0", MAX_DECIMAL_SCALE).unwrap();
+        assert_eq!(one.raw, 10u64.pow(19));
+        assert_eq!(one.to_string(), "1.0000000000000000000");
+        assert!(Decimal::parse("1.9", MAX_DECIMAL_SCALE).is_err());
+        assert!(Decimal::parse("1", MAX_DECIMAL_SCALE + 1).is_err());
+
+        // Decimals built with a larger scale still display
+        assert_eq!(Decimal::new(5, 21).to_string(), "0.000000000000000000005");
+        assert_eq!(Decimal::new(5, 0).rescale(MAX_DECIMAL_SCALE + 1), None);
+    }
+
+    #[test]
+    fn test_overflow_boundary() {
+        // u64::MAX = 18446744073709551615
+        assert_eq!(
+            Decimal::parse("184467440737095516.15", 2).unwrap().raw,
+            u64::MAX
+        );
+        assert!(Decimal::parse("184467440737095516.16", 2).is_err());
+        assert_eq!(
+            Decimal::new(u64::MAX / 10, 0).rescale(1).unwrap().raw,
+            u64::MAX / 10 * 10
+        );
+        assert_eq!(Decimal::new(u64::MAX / 10 + 1, 0).rescale(1), None);
+
+        assert_eq!(Decimal::mul_round(u64::MAX, 1, 1), Some((u64::MAX, 0)));
+        assert_eq!(Decimal::mul_round(u64::MAX, 2, 1), None);
+        assert_eq!(Decimal::mul_round(u64::MAX, 2, 2), Some((u64::MAX, 1)));
+        assert_eq!(Decimal::mul_round(1, 1, 0), None);
+    }
+
+    #[test]
+    fn test_rescale() {
+        let d = Decimal::new(15, 1);
+        assert_eq!(d.rescale(3), Some(Decimal::new(1500, 3)));
+        assert_eq!(d.rescale(0), None);
+    }
+}
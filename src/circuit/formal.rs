@@ -0,0 +1,332 @@
+// Formal export of gate constraints (feature `formal-export`)
+// Renders the polynomial constraints of selected chips as SMT-LIB (QF_FF,
+// as accepted by cvc5) or as Lean 4 propositions over `ZMod p`, so the
+// gadgets can be checked by an external solver or proof assistant.
+//
+// Constraints are read back from a freshly configured ConstraintSystem, so
+// the export always matches the gates the prover uses. Lookup arguments are
+// not exported: the 8-bit table membership of the decomposition chunks is an
+// assumption of the exported model.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+use ff::PrimeField;
+use halo2_proofs::plonk::{ConstraintSystem, Expression};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::group_by::GroupByChip;
+use super::range_check::RangeCheckChip;
+use super::selector_names::SelectorNames;
+use super::sort::SortChip;
+
+/// Chip whose gates can be exported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormalChip {
+    RangeCheck,
+    Sort,
+    GroupBy,
+}
+
+impl FormalChip {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FormalChip::RangeCheck => "range_check",
+            FormalChip::Sort => "sort",
+            FormalChip::GroupBy => "group_by",
+        }
+    }
+}
+
+/// Export format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormalFormat {
+    /// SMT-LIB 2 with the finite field theory (cvc5)
+    SmtLib,
+    /// Lean 4 (Mathlib `ZMod`)
+    Lean,
+}
+
+/// One polynomial constraint `polynomial = 0` (active when its selector is 1)
+#[derive(Clone, Debug)]
+pub struct FormalConstraint {
+    pub chip: FormalChip,
+    pub gate: String,
+    /// Unique identifier (`<gate>_<index>`)
+    pub name: String,
+    /// Referenced cells, e.g. `a2_cur`, `a2_next`, `f0_cur`, `s3`
+    pub variables: Vec<String>,
+    pub polynomial: Expression<Fr>,
+}
+
+/// Constraints of the given chips, in configuration order
+pub fn formal_constraints(chips: &[FormalChip]) -> Vec<FormalConstraint> {
+    collect_constraints(chips, &SelectorNames::default())
+}
+
+/// `formal_constraints`, naming selectors through `selectors`
+fn collect_constraints(chips: &[FormalChip], selectors: &SelectorNames) -> Vec<FormalConstraint> {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let config = PoneglyphConfig::configure(&mut meta);
+
+    // Attribute gates to chips by configuring them one after another
+    let start = meta.gates().len();
+    let range_check_config = RangeCheckChip::configure(&mut meta, &config);
+    let range_check_end = meta.gates().len();
+    SortChip::configure(&mut meta, &config, &range_check_config);
+    let sort_end = meta.gates().len();
+    GroupByChip::configure(&mut meta, &config, &range_check_config);
+    let group_by_end = meta.gates().len();
+
+    let ranges = [
+        (FormalChip::RangeCheck, start..range_check_end),
+        (FormalChip::Sort, range_check_end..sort_end),
+        (FormalChip::GroupBy, sort_end..group_by_end),
+    ];
+
+    let mut constraints = Vec::new();
+    for (chip, range) in ranges {
+        if !chips.contains(&chip) {
+            continue;
+        }
+        for gate in &meta.gates()[range] {
+            for (i, polynomial) in gate.polynomials().iter().enumerate() {
+                let gate_name = identifier(gate.name());
+                constraints.push(FormalConstraint {
+                    chip,
+                    name: format!("{}_{}", gate_name, i),
+                    gate: gate.name().to_string(),
+                    variables: variables(polynomial, selectors),
+                    polynomial: polynomial.clone(),
+                });
+            }
+        }
+    }
+    constraints
+}
+
+/// Render the constraints of the given chips
+pub fn export_constraints(chips: &[FormalChip], format: FormalFormat) -> String {
+    let selectors = SelectorNames::default();
+    let constraints = collect_constraints(chips, &selectors);
+    let modulus = hex_to_decimal(Fr::MODULUS);
+    let chip_names: Vec<&str> = chips.iter().map(|c| c.as_str()).collect();
+    let mut out = String::new();
+
+    match format {
+        FormalFormat::SmtLib => {
+            out.push_str(&format!(
+                "; PoneglyphDB gate constraints: {}\n",
+                chip_names.join(", ")
+            ));
+            out.push_str("; Each constraint holds iff its polynomial is 0\n");
+            out.push_str("(set-logic QF_FF)\n");
+            out.push_str(&format!("(define-sort F () (_ FiniteField {}))\n", modulus));
+
+            let all: BTreeSet<&String> = constraints.iter().flat_map(|c| &c.variables).collect();
+            for var in all {
+                out.push_str(&format!("(declare-const {} F)\n", var));
+            }
+            for c in &constraints {
+                out.push_str(&format!(
+                    "; {}: {}\n(define-fun {} () Bool (= {} (as ff0 F)))\n",
+                    c.chip.as_str(),
+                    c.gate,
+                    c.name,
+                    render(&c.polynomial, FormalFormat::SmtLib, &selectors)
+                ));
+            }
+        }
+        FormalFormat::Lean => {
+            out.push_str("import Mathlib\n\n");
+            out.push_str(&format!(
+                "-- PoneglyphDB gate constraints: {}\n\n",
+                chip_names.join(", ")
+            ));
+            out.push_str(&format!("abbrev F := ZMod {}\n", modulus));
+            for c in &constraints {
+                out.push_str(&format!(
+                    "\n/-- {}: {} -/\ndef {} ({} : F) : Prop :=\n  {} = 0\n",
+                    c.chip.as_str(),
+                    c.gate,
+                    c.name,
+                    c.variables.join(" "),
+                    render(&c.polynomial, FormalFormat::Lean, &selectors)
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Variable name of a queried cell
+fn cell_name(prefix: char, column: usize, rotation: i32) -> String {
+    let rotation = match rotation {
+        0 => "cur".to_string(),
+        1 => "next".to_string(),
+        -1 => "prev".to_string(),
+        r if r < 0 => format!("m{}", -r),
+        r => format!("p{}", r),
+    };
+    format!("{}{}_{}", prefix, column, rotation)
+}
+
+fn variables(polynomial: &Expression<Fr>, selectors: &SelectorNames) -> Vec<String> {
+    let vars = RefCell::new(BTreeSet::new());
+    polynomial.evaluate(
+        &|_| (),
+        &|s| {
+            vars.borrow_mut().insert(selectors.name(s));
+        },
+        &|q| {
+            vars.borrow_mut()
+                .insert(cell_name('f', q.column_index(), q.rotation().0));
+        },
+        &|q| {
+            vars.borrow_mut()
+                .insert(cell_name('a', q.column_index(), q.rotation().0));
+        },
+        &|q| {
+            vars.borrow_mut()
+                .insert(cell_name('i', q.column_index(), q.rotation().0));
+        },
+        &|_| (),
+        &|_, _| (),
+        &|_, _| (),
+        &|_, _| (),
+    );
+    vars.into_inner().into_iter().collect()
+}
+
+fn render(polynomial: &Expression<Fr>, format: FormalFormat, selectors: &SelectorNames) -> String {
+    let constant = |f: Fr| {
+        let (negative, magnitude) = field_literal(f);
+        match (format, negative) {
+            (FormalFormat::SmtLib, false) => format!("(as ff{} F)", magnitude),
+            (FormalFormat::SmtLib, true) => format!("(ff.neg (as ff{} F))", magnitude),
+            (FormalFormat::Lean, false) => format!("({} : F)", magnitude),
+            (FormalFormat::Lean, true) => format!("(-{} : F)", magnitude),
+        }
+    };
+    let sum = |a: String, b: String| match format {
+        FormalFormat::SmtLib => format!("(ff.add {} {})", a, b),
+        FormalFormat::Lean => format!("({} + {})", a, b),
+    };
+    let product = |a: String, b: String| match format {
+        FormalFormat::SmtLib => format!("(ff.mul {} {})", a, b),
+        FormalFormat::Lean => format!("({} * {})", a, b),
+    };
+
+    polynomial.evaluate(
+        &constant,
+        &|s| selectors.name(s),
+        &|q| cell_name('f', q.column_index(), q.rotation().0),
+        &|q| cell_name('a', q.column_index(), q.rotation().0),
+        &|q| cell_name('i', q.column_index(), q.rotation().0),
+        &|a| match format {
+            FormalFormat::SmtLib => format!("(ff.neg {})", a),
+            FormalFormat::Lean => format!("(-{})", a),
+        },
+        &sum,
+        &product,
+        &|a, f| product(constant(f), a),
+    )
+}
+
+/// Decimal literal of a field element; small negatives (p - n) as (true, n)
+fn field_literal(f: Fr) -> (bool, String) {
+    let small = |x: Fr| x.to_repr().as_ref()[8..].iter().all(|&b| b == 0);
+    let (negative, value) = if !small(f) && small(-f) {
+        (true, -f)
+    } else {
+        (false, f)
+    };
+    let mut be: Vec<u8> = value.to_repr().as_ref().to_vec();
+    be.reverse();
+    (negative, bytes_to_decimal(&be))
+}
+
+fn hex_to_decimal(hex: &str) -> String {
+    let hex = hex.trim_start_matches("0x");
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter_map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect();
+    // Two hex digits per byte, big-endian
+    let padded: Vec<u8> = if digits.len() % 2 == 1 {
+        std::iter::once(0).chain(digits).collect()
+    } else {
+        digits
+    };
+    let bytes: Vec<u8> = padded.chunks(2).map(|p| p[0] * 16 + p[1]).collect();
+    bytes_to_decimal(&bytes)
+}
+
+/// Decimal string of a big-endian unsigned integer
+fn bytes_to_decimal(be: &[u8]) -> String {
+    let mut number: Vec<u8> = be.to_vec();
+    let mut digits = Vec::new();
+    while number.iter().any(|&b| b != 0) {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let acc = (remainder << 8) | *byte as u32;
+            *byte = (acc / 10) as u8;
+            remainder = acc % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("ASCII digits")
+}
+
+/// SMT/Lean identifier from a gate name
+fn identifier(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| match c {
+            '<' => "lt".to_string(),
+            '>' => "gt".to_string(),
+            '=' => "eq".to_string(),
+            c if c.is_ascii_alphanumeric() => c.to_ascii_lowercase().to_string(),
+            _ => "_".to_string(),
+        })
+        .collect();
+    while id.contains("__") {
+        id = id.replace("__", "_");
+    }
+    id.trim_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_conversion() {
+        assert_eq!(bytes_to_decimal(&[0x01, 0x00]), "256");
+        assert_eq!(hex_to_decimal("0x0a"), "10");
+        assert_eq!(field_literal(Fr::from(42)), (false, "42".to_string()));
+        assert_eq!(field_literal(-Fr::from(3)), (true, "3".to_string()));
+        assert_eq!(identifier("lo <= x < hi constraint"), "lo_lteq_x_lt_hi_constraint");
+    }
+
+    #[test]
+    fn test_export_selected_chips() {
+        let constraints = formal_constraints(&[FormalChip::Sort, FormalChip::GroupBy]);
+        assert!(constraints.iter().all(|c| c.chip != FormalChip::RangeCheck));
+        assert!(constraints.iter().any(|c| c.gate == "sort order check"));
+        assert!(constraints.iter().any(|c| c.gate == "boundary check"));
+
+        let smt = export_constraints(&[FormalChip::GroupBy], FormalFormat::SmtLib);
+        assert!(smt.contains("(set-logic QF_FF)"));
+        assert!(smt.contains("(define-fun boundary_check_0 () Bool"));
+
+        let lean = export_constraints(&[FormalChip::RangeCheck], FormalFormat::Lean);
+        assert!(lean.contains("abbrev F := ZMod 2894802230932904885589274625217197696336305648"));
+        assert!(lean.contains("def decomposition_sum_0"));
+    }
+}
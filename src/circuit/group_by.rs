@@ -1,4 +1,6 @@
-use ff::Field;
+use std::marker::PhantomData;
+
+use ff::PrimeField;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
@@ -9,6 +11,19 @@ use pasta_curves::pallas::Base as Fr;
 use super::config::PoneglyphConfig;
 use super::range_check::RangeCheckConfig;
 
+/// Most key columns of a composite GROUP BY key
+/// Packed keys take 64 bits per column and must stay below the field modulus.
+pub const MAX_GROUP_KEY_COLUMNS: usize = 3;
+
+/// Field encoding of a composite group key: `Σ k_j · 2^(64·(m-1-j))`
+/// The first column is the most significant, so lexicographically sorted
+/// tuples give sorted keys, and two keys are equal iff every column is.
+pub fn composite_key<F: PrimeField>(key: &[u64]) -> F {
+    let shift = F::from(1 << 32).square();
+    key.iter()
+        .fold(F::ZERO, |acc, &column| acc * shift + F::from(column))
+}
+
 /// Group-By Gate Configuration
 /// According to Paper Section 4.3: Group verification with Boundary Check
 ///
@@ -46,14 +61,18 @@ pub struct GroupByConfig {
 
 /// Group-By Chip
 /// Paper Section 4.3 implementation
-pub struct GroupByChip {
+pub struct GroupByChip<F: PrimeField = Fr> {
     config: GroupByConfig,
+    _marker: PhantomData<F>,
 }
 
-impl GroupByChip {
+impl<F: PrimeField> GroupByChip<F> {
     /// Create new GroupByChip
     pub fn new(config: GroupByConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            _marker: PhantomData,
+        }
     }
 
     /// Configure the Group-By Gate
@@ -61,7 +80,7 @@ impl GroupByChip {
     /// Formula: b = 1 - (v₁ - v₂) × p
     /// where p = 1/(v₁ - v₂) if v₁ ≠ v₂, else p = 0
     pub fn configure(
-        meta: &mut ConstraintSystem<Fr>,
+        meta: &mut ConstraintSystem<F>,
         config: &PoneglyphConfig,
         range_check_config: &RangeCheckConfig,
     ) -> GroupByConfig {
@@ -97,21 +116,26 @@ impl GroupByChip {
 
             // Paper formula: b = 1 - (v₁ - v₂) × p
             let diff = v2.clone() - v1.clone();
-            let boundary_expr = Expression::Constant(Fr::ONE) - (diff.clone() * p.clone());
+            let boundary_expr = Expression::Constant(F::ONE) - (diff.clone() * p.clone());
 
             // Boolean constraint: b × (1 - b) = 0
-            let bool_check = b.clone() * (Expression::Constant(Fr::ONE) - b.clone());
+            let bool_check = b.clone() * (Expression::Constant(F::ONE) - b.clone());
 
             // Inverse constraint: p × (v₁ - v₂) = 1 - b
             // If v₁ = v₂: p = 0, b = 1, so 0 × 0 = 1 - 1 = 0 ✓
             // If v₁ ≠ v₂: p = 1/(v₁ - v₂), b = 0, so (1/(v₁ - v₂)) × (v₁ - v₂) = 1 - 0 = 1 ✓
             let inverse_check =
-                p.clone() * diff.clone() - (Expression::Constant(Fr::ONE) - b.clone());
+                p.clone() * diff.clone() - (Expression::Constant(F::ONE) - b.clone());
+
+            // Equality constraint: b × (v₁ - v₂) = 0
+            // Without it p = 0 gives b = 1 for any keys
+            let equality_check = b.clone() * diff.clone();
 
             vec![
                 s.clone() * bool_check,          // b must be boolean
                 s.clone() * (b - boundary_expr), // b = 1 - (v₁ - v₂) × p
                 s.clone() * inverse_check,       // p × (v₁ - v₂) = 1 - b
+                s.clone() * equality_check,      // b = 1 only if v₁ = v₂
             ]
         });
 
@@ -142,9 +166,52 @@ impl GroupByChip {
     /// List of boundary cells (one boundary for each consecutive pair)
     pub fn group_and_verify(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        layouter: impl Layouter<F>,
         group_keys: &[u64],
-    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        super::arena::with_arena(|arena| {
+            let keys = super::witness::to_field_in(arena, group_keys);
+            self.assign_boundaries(layouter, keys)
+        })
+    }
+
+    /// Group by several key columns (`GROUP BY region, year`)
+    /// Each row's key is a tuple of one value per key column; the tuples must
+    /// be sorted lexicographically. They are packed with `composite_key`, so
+    /// the boundary check sees a boundary wherever any key column changes.
+    ///
+    /// # Errors
+    ///
+    /// `Error::Synthesis` if the tuples don't all have the same number of
+    /// columns, between 1 and `MAX_GROUP_KEY_COLUMNS`
+    ///
+    /// # Return Value
+    ///
+    /// List of boundary cells (one boundary for each consecutive pair)
+    pub fn group_composite_and_verify(
+        &self,
+        layouter: impl Layouter<F>,
+        group_keys: &[Vec<u64>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let width = group_keys.first().map_or(1, |key| key.len());
+        if width == 0
+            || width > MAX_GROUP_KEY_COLUMNS
+            || group_keys.iter().any(|key| key.len() != width)
+        {
+            return Err(Error::Synthesis);
+        }
+        super::arena::with_arena(|arena| {
+            let keys = arena.alloc_from(group_keys.iter().map(|key| composite_key::<F>(key)));
+            self.assign_boundaries(layouter, keys)
+        })
+    }
+
+    /// Assign encoded group keys and their boundaries (see `group_and_verify`)
+    fn assign_boundaries(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_keys: &[F],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
         // Assign group keys and boundaries in the same region
         // Since constraints use Rotation::cur() and Rotation::next(),
         // they must be in consecutive rows
@@ -165,20 +232,20 @@ impl GroupByChip {
                         || "group_key_0",
                         self.config.group_key_column,
                         0,
-                        || Value::known(Fr::from(group_keys[0])),
+                        || Value::known(group_keys[0]),
                     )?;
 
                     let boundary_cell = region.assign_advice(
                         || "boundary_0",
                         self.config.boundary_column,
                         0,
-                        || Value::known(Fr::ZERO),
+                        || Value::known(F::ZERO),
                     )?;
                     let _inverse_cell = region.assign_advice(
                         || "inverse_0",
                         self.config.inverse_column,
                         0,
-                        || Value::known(Fr::ZERO),
+                        || Value::known(F::ZERO),
                     )?;
                     boundary_cells.push(boundary_cell);
                     return Ok(boundary_cells);
@@ -190,7 +257,7 @@ impl GroupByChip {
                         || format!("group_key_{}", i),
                         self.config.group_key_column,
                         i,
-                        || Value::known(Fr::from(*key)),
+                        || Value::known(*key),
                     )?;
                 }
 
@@ -202,32 +269,15 @@ impl GroupByChip {
 
                     // Paper formula: b = 1 - (v₁ - v₂) × p
                     // p = 1/(v₁ - v₂) if v₁ ≠ v₂, else p = 0
-                    let diff = v2 as i64 - v1 as i64;
-
-                    let (boundary, inverse) = if diff == 0 {
+                    // (computed in the field, so any encoded key works)
+                    let diff = v2 - v1;
+                    let (boundary, inverse) = if diff == F::ZERO {
                         // v₁ = v₂: p = 0, b = 1 (new group has started)
                         // Paper formula: b = 1 - (v₁ - v₂) × p = 1 - 0 × 0 = 1
-                        (Fr::ONE, Fr::ZERO)
+                        (F::ONE, F::ZERO)
                     } else {
                         // v₁ ≠ v₂: p = 1/(v₁ - v₂), b = 0 (same group continues)
-                        // Calculate diff as field element
-                        let diff_field = if diff > 0 {
-                            Fr::from(diff as u64)
-                        } else {
-                            // Negative diff: negative value in field
-                            // Note: Since group keys are sorted, diff should generally be >= 0
-                            // But we handle negative values for field arithmetic
-                            let abs_diff = (-diff) as u64;
-                            -Fr::from(abs_diff)
-                        };
-
-                        // Calculate inverse: p = 1/(v₁ - v₂)
-                        // Note: Since diff_field ≠ 0, invert() should succeed
-                        // But we use unwrap_or(Fr::ZERO) for safety
-                        // If invert() fails (very rare), p = 0
-                        // In this case, constraints will error
-                        let inv = diff_field.invert().unwrap_or(Fr::ZERO);
-                        (Fr::ZERO, inv)
+                        (F::ZERO, diff.invert().unwrap())
                     };
 
                     let boundary_cell = region.assign_advice(
@@ -254,4 +304,19 @@ impl GroupByChip {
             },
         )
     }
+
+    /// Group by a nullable key column
+    /// SQL GROUP BY puts all NULL keys into a single group, which sorts first
+    /// (keys are encoded with `encode_nullable_key`, `u64::MAX` is rejected)
+    pub fn group_nullable_and_verify(
+        &self,
+        layouter: impl Layouter<F>,
+        group_keys: &[Option<u64>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let encoded = group_keys
+            .iter()
+            .map(|&k| super::nullable::encode_nullable_key(k).ok_or(Error::Synthesis))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.group_and_verify(layouter, &encoded)
+    }
 }
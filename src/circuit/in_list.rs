@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, TableColumn},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::lookup_tables::{LookupTableSpec, LookupTables};
+
+/// Lookup table of the IN-list members (shared by every IN-list chip)
+pub const IN_LIST_TABLE: LookupTableSpec = LookupTableSpec::Set { name: "in_list" };
+
+/// IN-list Gate Configuration
+/// Proves `col IN (v1, ..., vn)` with one lookup per row
+///
+/// # Column Allocation
+///
+/// - `value_column`: Row value (advice[10])
+/// - `selected_column`: 1 = row passes the filter (advice[11])
+/// - `tag_table`, `list_table`: Lookup table `{(0, 0)} ∪ {(1, v_i)}`
+///   (`IN_LIST_TABLE` in the shared `LookupTables`)
+///
+/// # Constraints
+///
+/// 1. **Boolean**: `selected · (1 - selected) = 0`
+/// 2. **Lookup**: `(selected, selected · value) ∈ table`
+///    (selected rows must be in the list; the `(0, 0)` row absorbs the rest,
+///    the tag keeps 0 out of the list unless it is listed)
+///
+/// # Note
+///
+/// - The list is loaded once per circuit (one table per configuration)
+/// - Only selected rows are proven to be members; proving that a rejected
+///   row is not in the list needs a non-membership argument (not covered)
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct InListConfig {
+    pub value_column: Column<Advice>,
+    pub selected_column: Column<Advice>,
+    pub tag_table: TableColumn,
+    pub list_table: TableColumn,
+    pub selector: Selector,
+    pub lookup_tables: LookupTables,
+}
+
+/// IN-list Chip
+/// `WHERE col IN (...)` via lookup argument
+pub struct InListChip<F: PrimeField = Fr> {
+    config: InListConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> InListChip<F> {
+    /// Create a new InListChip
+    pub fn new(config: InListConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the IN-list Gate
+    pub fn configure(meta: &mut ConstraintSystem<F>, config: &PoneglyphConfig) -> InListConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-11]: shared with Join Gate
+        let value_column = config.advice[10];
+        let selected_column = config.advice[11];
+        let table = config.lookup_tables.register(meta, "in_list", IN_LIST_TABLE);
+        let (tag_table, list_table) = (table[0], table[1]);
+        let selector = meta.complex_selector();
+
+        meta.create_gate("in list selected boolean", |meta| {
+            let s = meta.query_selector(selector);
+            let selected = meta.query_advice(selected_column, Rotation::cur());
+
+            vec![s * selected.clone() * (Expression::Constant(F::ONE) - selected)]
+        });
+
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let selected = meta.query_advice(selected_column, Rotation::cur());
+
+            vec![
+                (s.clone() * selected.clone(), tag_table),
+                (s * selected * value, list_table),
+            ]
+        });
+
+        InListConfig {
+            value_column,
+            selected_column,
+            tag_table,
+            list_table,
+            selector,
+            lookup_tables: config.lookup_tables.clone(),
+        }
+    }
+
+    /// Load the IN-list into the lookup table
+    /// Row 0 is the `(0, 0)` padding row (also the default for unused rows)
+    pub fn load_list(&self, layouter: &mut impl Layouter<F>, list: &[u64]) -> Result<(), Error> {
+        let rows: Vec<[u64; 2]> = list.iter().map(|&v| [1, v]).collect();
+        self.config
+            .lookup_tables
+            .load_rows(layouter, IN_LIST_TABLE, &rows)
+    }
+
+    /// Filter rows by `value IN list` (list must be loaded with `load_list`)
+    ///
+    /// # Return Value
+    ///
+    /// Selected flag cells (1 = in the list)
+    pub fn filter_in(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[u64],
+        list: &[u64],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "in list filter",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &v)| {
+                        self.config.selector.enable(&mut region, i)?;
+                        region.assign_advice(
+                            || format!("value_{}", i),
+                            self.config.value_column,
+                            i,
+                            || Value::known(F::from(v)),
+                        )?;
+                        region.assign_advice(
+                            || format!("selected_{}", i),
+                            self.config.selected_column,
+                            i,
+                            || Value::known(F::from(list.contains(&v) as u64)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+}
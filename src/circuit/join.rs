@@ -1,15 +1,51 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
     poly::Rotation,
 };
 use pasta_curves::pallas::Base as Fr;
-use ff::Field;
 
 use super::config::PoneglyphConfig;
-use super::range_check::RangeCheckConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
 use super::sort::SortConfig;
 
+/// Join kind of a `JoinOp`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Matching pairs of both tables (`join_and_verify`)
+    #[default]
+    Inner,
+    /// Table 1 rows with a match in table 2 (`EXISTS`, `IN`)
+    Semi,
+    /// Table 1 rows without a match in table 2 (`NOT EXISTS`, `NOT IN`)
+    Anti,
+}
+
+impl JoinKind {
+    /// Reference (out-of-circuit) selection of a table 1 row
+    /// (Inner keeps the rows that take part in at least one pair)
+    pub fn keeps(&self, key: u64, table2_keys: &[u64]) -> bool {
+        let matched = table2_keys.contains(&key);
+        match self {
+            JoinKind::Inner | JoinKind::Semi => matched,
+            JoinKind::Anti => !matched,
+        }
+    }
+}
+
+/// Shift of hi in a packed semi join gap (see JoinConfig)
+const GAP_SHIFT_BITS: u32 = 66;
+
+/// `pack(lo, hi)` of a semi join gap (see JoinConfig)
+fn pack_gap<F: PrimeField>(lo: Expression<F>, hi: Expression<F>) -> Expression<F> {
+    let one = Expression::Constant(F::ONE);
+    let shift = Expression::Constant(F::from_u128(1u128 << GAP_SHIFT_BITS));
+    lo + one.clone() + shift * (hi + one)
+}
+
 /// Join Gate Configuration
 /// According to Paper Section 4.4: Join verification with Match/Miss distinction
 /// 
@@ -36,6 +72,32 @@ use super::sort::SortConfig;
 /// 
 /// - Join Gate uses Sort Gate output. Tables are sorted and verified with Sort Gate.
 /// - Deduplication verification is done in `join_and_verify` using Sort Gate.
+///
+/// # Semi / Anti Join
+///
+/// `semi_join_and_verify` uses the same columns. The gaps of the sorted
+/// table 2 come first, one row each:
+///
+/// - Row j: lo (advice[11]), hi (advice[12]), `gap` (advice[13]), with lo
+///   and hi copied from cells j - 1 and j of the Sort Gate output (`-1` /
+///   `2^64` past either end) and `gap = pack(lo, hi)`, where
+///   `pack(lo, hi) = (lo + 1) + 2^66 · (hi + 1)`
+///
+/// Then two rows per table 1 row, with hi the first key above x:
+///
+/// - Row 2i: key x (advice[10], copied from the table 1 key cell), lo
+///   (advice[11]), hi (advice[12]), selected (advice[13]), match flag m
+///   (advice[14])
+/// - Row 2i+1: `d_lo` (advice[10]), `d_hi` (advice[11]), `gap` (advice[12],
+///   copied from a gap row)
+///
+/// 1. **Adjacency**: `gap = pack(lo, hi)`; x, `d_lo` and `d_hi` are
+///    decomposed into 8-bit chunks, which keeps lo and hi within 2^65 of x,
+///    where `pack` is injective: lo and hi are adjacent sorted cells
+/// 2. **Match**: `m ∈ {0, 1}`, `m · (x - lo) = 0`
+/// 3. **Miss**: `d_lo = (1 - m) · (x - lo - 1)`, `d_hi = hi - x - 1`, so
+///    `m = 0` means `lo < x < hi`: x is not in table 2
+/// 4. **Selection**: `selected = m` (semi), `selected = 1 - m` (anti)
 #[derive(Clone, Debug)]
 pub struct JoinConfig {
     // Table 1 columns
@@ -57,6 +119,9 @@ pub struct JoinConfig {
     // Selectors
     pub join_selector: Selector,
     pub deduplication_selector: Selector,
+    pub semi_selector: Selector,
+    pub anti_selector: Selector,
+    pub gap_selector: Selector,
     
     // Dependencies
     pub range_check_config: RangeCheckConfig,
@@ -65,20 +130,24 @@ pub struct JoinConfig {
 
 /// Join Chip
 /// Paper Section 4.4 implementation
-pub struct JoinChip {
+pub struct JoinChip<F: PrimeField = Fr> {
     config: JoinConfig,
+    _marker: PhantomData<F>,
 }
 
-impl JoinChip {
+impl<F: PrimeField> JoinChip<F> {
     /// Create a new JoinChip
     pub fn new(config: JoinConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            _marker: PhantomData,
+        }
     }
     
     /// Configure the Join Gate
     /// Paper Section 4.4: Match/Miss distinction and PK-FK verification
     pub fn configure(
-        meta: &mut ConstraintSystem<Fr>,
+        meta: &mut ConstraintSystem<F>,
         config: &PoneglyphConfig,
         range_check_config: &RangeCheckConfig,
         sort_config: &SortConfig,
@@ -103,6 +172,10 @@ impl JoinChip {
         // Create selectors
         let join_selector = meta.selector();
         let deduplication_selector = meta.selector();
+        // Complex: the membership gate adds them
+        let semi_selector = meta.complex_selector();
+        let anti_selector = meta.complex_selector();
+        let gap_selector = meta.selector();
         
         // Key comparison constraint
         // Paper Section 4.4: Primary Key - Foreign Key verification
@@ -133,7 +206,7 @@ impl JoinChip {
             let match_flag = meta.query_advice(match_column, Rotation::cur());
             
             // Boolean constraint: match_flag * (1 - match_flag) = 0
-            let bool_check = match_flag.clone() * (Expression::Constant(Fr::ONE) - match_flag.clone());
+            let bool_check = match_flag.clone() * (Expression::Constant(F::ONE) - match_flag.clone());
             
             vec![s * bool_check]
         });
@@ -163,9 +236,48 @@ impl JoinChip {
             let s = meta.query_selector(deduplication_selector);
             // Deduplication verification is done with Sort Gate, this constraint is not used
             // But we add a simple constraint since selector is defined
-            vec![s * Expression::Constant(Fr::ZERO)]
+            vec![s * Expression::Constant(F::ZERO)]
         });
         
+        // Semi / anti join gaps: adjacent sorted table 2 keys, packed into
+        // one cell (see JoinConfig)
+        meta.create_gate("semi join gap", |meta| {
+            let s = meta.query_selector(gap_selector);
+            let lo = meta.query_advice(table1_value_column, Rotation::cur());
+            let hi = meta.query_advice(table2_key_column, Rotation::cur());
+            let gap = meta.query_advice(table2_value_column, Rotation::cur());
+            vec![s * (gap - pack_gap(lo, hi))]
+        });
+
+        // Semi / anti join membership (see JoinConfig)
+        // Both selectors share the membership constraints; they differ in
+        // how the selection follows the match flag
+        meta.create_gate("semi join membership", |meta| {
+            let semi = meta.query_selector(semi_selector);
+            let anti = meta.query_selector(anti_selector);
+            let key = meta.query_advice(table1_key_column, Rotation::cur());
+            let lo = meta.query_advice(table1_value_column, Rotation::cur());
+            let hi = meta.query_advice(table2_key_column, Rotation::cur());
+            let selected = meta.query_advice(table2_value_column, Rotation::cur());
+            let match_flag = meta.query_advice(match_column, Rotation::cur());
+            let d_lo = meta.query_advice(table1_key_column, Rotation::next());
+            let d_hi = meta.query_advice(table1_value_column, Rotation::next());
+            let gap = meta.query_advice(table2_key_column, Rotation::next());
+            let one = Expression::Constant(F::ONE);
+
+            let s = semi.clone() + anti.clone();
+            let miss = one.clone() - match_flag.clone();
+            vec![
+                s.clone() * (gap - pack_gap(lo.clone(), hi.clone())),
+                s.clone() * match_flag.clone() * miss.clone(),
+                s.clone() * match_flag.clone() * (key.clone() - lo.clone()),
+                s.clone() * (d_lo - miss.clone() * (key.clone() - lo - one.clone())),
+                s * (d_hi - (hi - key - one)),
+                semi * (selected.clone() - match_flag),
+                anti * (selected - miss),
+            ]
+        });
+
         JoinConfig {
             table1_key_column,
             table1_value_column,
@@ -174,6 +286,9 @@ impl JoinChip {
             match_column,
             join_selector,
             deduplication_selector,
+            semi_selector,
+            anti_selector,
+            gap_selector,
             range_check_config: range_check_config.clone(),
             sort_config: sort_config.clone(),
         }
@@ -203,20 +318,19 @@ impl JoinChip {
     /// List of match cells (one match_flag for each row)
     pub fn join_and_verify(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         table1_keys: &[u64],
         table1_values: &[u64],
         table2_keys: &[u64],
         table2_values: &[u64],
-    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
         // 1. Sort and verify tables with Sort Gate
         // Paper Section 4.4: Sorting required before join
         let sort_chip = super::sort::SortChip::new(self.config.sort_config.clone());
         
         // Sort and verify Table 1 (if not empty)
         let table1_keys_sorted = if !table1_keys.is_empty() {
-            let mut sorted = table1_keys.to_vec();
-            sorted.sort();
+            let sorted = super::witness::sorted(table1_keys);
             let table1_keys_value: Vec<Value<u64>> = table1_keys.iter().map(|&k| Value::known(k)).collect();
             sort_chip.sort_and_verify(
                 layouter.namespace(|| "sort table1"),
@@ -230,8 +344,7 @@ impl JoinChip {
         
         // Sort and verify Table 2 (if not empty)
         let table2_keys_sorted = if !table2_keys.is_empty() {
-            let mut sorted = table2_keys.to_vec();
-            sorted.sort();
+            let sorted = super::witness::sorted(table2_keys);
             let table2_keys_value: Vec<Value<u64>> = table2_keys.iter().map(|&k| Value::known(k)).collect();
             sort_chip.sort_and_verify(
                 layouter.namespace(|| "sort table2"),
@@ -270,7 +383,272 @@ impl JoinChip {
         
         Ok(match_cells)
     }
-    
+
+    /// Perform and verify join over nullable keys
+    ///
+    /// # NULL Semantics
+    ///
+    /// - `null_safe = false` (SQL `=`): a NULL key never matches, not even another NULL
+    /// - `null_safe = true` (SQL `<=>` / `IS NOT DISTINCT FROM`): NULL matches NULL
+    ///
+    /// # Encoding
+    ///
+    /// Keys go through `encode_nullable_key` (`Some(v) -> v + 1`, NULL -> 0). For
+    /// SQL `=`, table2 NULLs are encoded as `u64::MAX` instead, so the equality
+    /// constraint itself rules out NULL matches (keys `>= u64::MAX - 1` are rejected).
+    pub fn join_nullable_and_verify(
+        &self,
+        layouter: impl Layouter<F>,
+        table1_keys: &[Option<u64>],
+        table1_values: &[u64],
+        table2_keys: &[Option<u64>],
+        table2_values: &[u64],
+        null_safe: bool,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        use super::nullable::encode_nullable_key;
+
+        let encode = |key: &Option<u64>, null: u64| -> Result<u64, Error> {
+            match key {
+                None => Ok(null),
+                Some(v) => encode_nullable_key(Some(*v))
+                    .filter(|&e| e != u64::MAX)
+                    .ok_or(Error::Synthesis),
+            }
+        };
+
+        let table2_null = if null_safe { 0 } else { u64::MAX };
+        let keys1 = table1_keys
+            .iter()
+            .map(|k| encode(k, 0))
+            .collect::<Result<Vec<_>, _>>()?;
+        let keys2 = table2_keys
+            .iter()
+            .map(|k| encode(k, table2_null))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.join_and_verify(layouter, &keys1, table1_values, &keys2, table2_values)
+    }
+
+    /// Semi or anti join of table 1 with table 2 (see JoinConfig)
+    /// Table 2 is sorted with the Sort Gate; every table 1 row is proven to
+    /// have a match (equal to a sorted cell) or none (strictly between two
+    /// adjacent sorted cells). The keys x are copied from `table1_cells`;
+    /// `table1_keys` holds the same keys, to lay out the rows.
+    ///
+    /// # Errors
+    ///
+    /// `Error::Synthesis` for `JoinKind::Inner` (see `join_and_verify`), or
+    /// if `table1_cells` and `table1_keys` differ in length
+    ///
+    /// # Return Value
+    ///
+    /// Selected cell per table 1 row (1 = the row is in the join output,
+    /// as `JoinKind::keeps`)
+    pub fn semi_join_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        table1_keys: &[u64],
+        table1_cells: &[AssignedCell<F, F>],
+        table2_keys: &[u64],
+        kind: JoinKind,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let selector = match kind {
+            JoinKind::Semi => self.config.semi_selector,
+            JoinKind::Anti => self.config.anti_selector,
+            JoinKind::Inner => return Err(Error::Synthesis),
+        };
+        if table1_cells.len() != table1_keys.len() {
+            return Err(Error::Synthesis);
+        }
+        if table1_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sorted = super::witness::sorted(table2_keys);
+        let sorted_cells = if sorted.is_empty() {
+            Vec::new()
+        } else {
+            let sort_chip = super::sort::SortChip::new(self.config.sort_config.clone());
+            sort_chip.sort_and_verify(
+                layouter.namespace(|| "sort table2"),
+                table2_keys.iter().map(|&k| Value::known(k)).collect(),
+                sorted.clone(),
+            )?
+        };
+
+        // Sentinels past either end of the sorted keys
+        let below = -F::ONE;
+        let above = F::from_u128(1u128 << 64);
+        let shift = F::from_u128(1u128 << GAP_SHIFT_BITS);
+
+        // Gap j lies between sorted cells j - 1 and j
+        let gap_cells = layouter.assign_region(
+            || "semi join gaps",
+            |mut region| {
+                let mut gap_cells = Vec::with_capacity(sorted_cells.len() + 1);
+                for j in 0..=sorted_cells.len() {
+                    self.config.gap_selector.enable(&mut region, j)?;
+                    let lo = match j.checked_sub(1) {
+                        Some(prev) => sorted_cells[prev].copy_advice(
+                            || format!("gap lo_{}", j),
+                            &mut region,
+                            self.config.table1_value_column,
+                            j,
+                        )?,
+                        None => region.assign_advice_from_constant(
+                            || format!("gap lo_{}", j),
+                            self.config.table1_value_column,
+                            j,
+                            below,
+                        )?,
+                    };
+                    let hi = match sorted_cells.get(j) {
+                        Some(cell) => cell.copy_advice(
+                            || format!("gap hi_{}", j),
+                            &mut region,
+                            self.config.table2_key_column,
+                            j,
+                        )?,
+                        None => region.assign_advice_from_constant(
+                            || format!("gap hi_{}", j),
+                            self.config.table2_key_column,
+                            j,
+                            above,
+                        )?,
+                    };
+                    let gap = lo
+                        .value()
+                        .zip(hi.value())
+                        .map(|(&lo, &hi)| lo + F::ONE + shift * (hi + F::ONE));
+                    gap_cells.push(region.assign_advice(
+                        || format!("gap_{}", j),
+                        self.config.table2_value_column,
+                        j,
+                        || gap,
+                    )?);
+                }
+                Ok(gap_cells)
+            },
+        )?;
+
+        let (key_cells, selected_cells, diff_cells) = layouter.assign_region(
+            || "semi join",
+            |mut region| {
+                let mut key_cells = Vec::with_capacity(table1_keys.len());
+                let mut selected_cells = Vec::with_capacity(table1_keys.len());
+                let mut diff_cells = Vec::with_capacity(2 * table1_keys.len());
+
+                for (i, (&key, cell)) in table1_keys.iter().zip(table1_cells).enumerate() {
+                    let row = 2 * i;
+                    selector.enable(&mut region, row)?;
+
+                    // hi = first sorted key above x, lo = the one before it;
+                    // the gap cell between them ties both to the sorted table
+                    let next = sorted.partition_point(|&k| k <= key);
+                    let lo = next.checked_sub(1).map_or(below, |j| F::from(sorted[j]));
+                    let hi = sorted.get(next).map_or(above, |&k| F::from(k));
+                    region.assign_advice(
+                        || format!("lo_{}", i),
+                        self.config.table1_value_column,
+                        row,
+                        || Value::known(lo),
+                    )?;
+                    region.assign_advice(
+                        || format!("hi_{}", i),
+                        self.config.table2_key_column,
+                        row,
+                        || Value::known(hi),
+                    )?;
+                    gap_cells[next].copy_advice(
+                        || format!("gap_{}", i),
+                        &mut region,
+                        self.config.table2_key_column,
+                        row + 1,
+                    )?;
+
+                    let x = cell.copy_advice(
+                        || format!("key_{}", i),
+                        &mut region,
+                        self.config.table1_key_column,
+                        row,
+                    )?;
+                    let matched = next > 0 && sorted[next - 1] == key;
+                    let m = F::from(u64::from(matched));
+                    let keep = matched != (kind == JoinKind::Anti);
+                    region.assign_advice(
+                        || format!("match_{}", i),
+                        self.config.match_column,
+                        row,
+                        || Value::known(m),
+                    )?;
+                    selected_cells.push(region.assign_advice(
+                        || format!("selected_{}", i),
+                        self.config.table2_value_column,
+                        row,
+                        || Value::known(F::from(u64::from(keep))),
+                    )?);
+
+                    let d_lo = x.value().map(|&x| (F::ONE - m) * (x - lo - F::ONE));
+                    let d_hi = x.value().map(|&x| hi - x - F::ONE);
+                    diff_cells.push(region.assign_advice(
+                        || format!("d_lo_{}", i),
+                        self.config.table1_key_column,
+                        row + 1,
+                        || d_lo,
+                    )?);
+                    diff_cells.push(region.assign_advice(
+                        || format!("d_hi_{}", i),
+                        self.config.table1_value_column,
+                        row + 1,
+                        || d_hi,
+                    )?);
+                    key_cells.push(x);
+                }
+
+                Ok((key_cells, selected_cells, diff_cells))
+            },
+        )?;
+
+        // x, d_lo, d_hi ∈ [0, 2^64)
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for (i, cell) in key_cells.iter().enumerate() {
+            range_check_chip
+                .decompose_cell(layouter.namespace(|| format!("semi join key {}", i)), cell)?;
+        }
+        for (i, cell) in diff_cells.iter().enumerate() {
+            range_check_chip
+                .decompose_cell(layouter.namespace(|| format!("semi join diff {}", i)), cell)?;
+        }
+
+        Ok(selected_cells)
+    }
+
+    /// Assign keys that are not in the circuit yet, for
+    /// `semi_join_and_verify`
+    /// The cells are only constrained by the join they are copied into.
+    pub fn assign_keys(
+        &self,
+        mut layouter: impl Layouter<F>,
+        keys: &[u64],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "join keys",
+            |mut region| {
+                keys.iter()
+                    .enumerate()
+                    .map(|(i, &key)| {
+                        region.assign_advice(
+                            || format!("key_{}", i),
+                            self.config.table1_key_column,
+                            i,
+                            || Value::known(F::from(key)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
     /// Deduplication verification: Prove that T_miss records are disjoint
     /// Paper Section 4.4: T_miss records should not match with records in the other table
     /// 
@@ -282,7 +660,7 @@ impl JoinChip {
     /// 4. If there are no matches, T_miss records are disjoint
     fn verify_deduplication(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         table1_keys: &[u64],
         table2_keys: &[u64],
         _table1_keys_sorted: &[u64],
@@ -312,11 +690,7 @@ impl JoinChip {
         
         // Sort and verify T_miss1
         if !t_miss1.is_empty() {
-            let t_miss1_sorted = {
-                let mut sorted = t_miss1.clone();
-                sorted.sort();
-                sorted
-            };
+            let t_miss1_sorted = super::witness::sorted(&t_miss1);
             let t_miss1_value: Vec<Value<u64>> = t_miss1.iter().map(|&k| Value::known(k)).collect();
             sort_chip.sort_and_verify(
                 layouter.namespace(|| "sort t_miss1"),
@@ -336,11 +710,7 @@ impl JoinChip {
         
         // Sort and verify T_miss2
         if !t_miss2.is_empty() {
-            let t_miss2_sorted = {
-                let mut sorted = t_miss2.clone();
-                sorted.sort();
-                sorted
-            };
+            let t_miss2_sorted = super::witness::sorted(&t_miss2);
             let t_miss2_value: Vec<Value<u64>> = t_miss2.iter().map(|&k| Value::known(k)).collect();
             sort_chip.sort_and_verify(
                 layouter.namespace(|| "sort t_miss2"),
@@ -371,12 +741,12 @@ impl JoinChip {
     /// - Padding (0) is used for empty records
     fn assign_join_with_constraints(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         table1_keys: &[u64],
         table1_values: &[u64],
         table2_keys: &[u64],
         table2_values: &[u64],
-    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
         layouter.assign_region(
             || "assign join",
             |mut region| {
@@ -405,14 +775,14 @@ impl JoinChip {
                         || format!("table1_key_{}", i),
                         self.config.table1_key_column,
                         i,
-                        || Value::known(Fr::from(key1)),
+                        || Value::known(F::from(key1)),
                     )?;
                     
                     region.assign_advice(
                         || format!("table1_value_{}", i),
                         self.config.table1_value_column,
                         i,
-                        || Value::known(Fr::from(value1)),
+                        || Value::known(F::from(value1)),
                     )?;
                     
                     // Table 2 assignment (always assign, 0 if empty)
@@ -431,26 +801,26 @@ impl JoinChip {
                         || format!("table2_key_{}", i),
                         self.config.table2_key_column,
                         i,
-                        || Value::known(Fr::from(key2)),
+                        || Value::known(F::from(key2)),
                     )?;
                     
                     region.assign_advice(
                         || format!("table2_value_{}", i),
                         self.config.table2_value_column,
                         i,
-                        || Value::known(Fr::from(value2)),
+                        || Value::known(F::from(value2)),
                     )?;
                     
                     // Calculate match flag
                     // If i < min(len1, len2) and key1[i] == key2[i] then match = 1
                     let match_flag = if i < table1_keys.len() && i < table2_keys.len() {
                         if table1_keys[i] == table2_keys[i] {
-                            Fr::ONE
+                            F::ONE
                         } else {
-                            Fr::ZERO
+                            F::ZERO
                         }
                     } else {
-                        Fr::ZERO
+                        F::ZERO
                     };
                     
                     let match_cell = region.assign_advice(
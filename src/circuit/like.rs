@@ -0,0 +1,557 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{field_to_u64, RangeCheckChip, RangeCheckConfig};
+
+/// Maximum string length in bytes (one packed u64 per value)
+pub const FIXED_STRING_LEN: usize = 8;
+
+/// Pack a string into a u64 (big-endian, left-aligned, zero-padded)
+/// Byte 0 of the string is the most significant byte, so numeric order of
+/// the packed values is the lexicographic order of the strings.
+///
+/// Returns None if the string is longer than 8 bytes or contains NUL
+pub fn encode_fixed_string(s: &str) -> Option<u64> {
+    let bytes = s.as_bytes();
+    if bytes.len() > FIXED_STRING_LEN || bytes.contains(&0) {
+        return None;
+    }
+    let mut packed = [0u8; FIXED_STRING_LEN];
+    packed[..bytes.len()].copy_from_slice(bytes);
+    Some(u64::from_be_bytes(packed))
+}
+
+/// Unpack a string encoded with `encode_fixed_string`
+/// Bytes after the first NUL are ignored
+pub fn decode_fixed_string(value: u64) -> String {
+    let bytes = value.to_be_bytes();
+    let len = fixed_string_len(value);
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// Length of a packed string (number of leading non-zero bytes)
+pub fn fixed_string_len(value: u64) -> usize {
+    value
+        .to_be_bytes()
+        .iter()
+        .position(|&b| b == 0)
+        .unwrap_or(FIXED_STRING_LEN)
+}
+
+/// Comparison mode of one LIKE row (stored in the mode fixed column)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LikeMode {
+    /// Byte must equal the pattern byte
+    Equal,
+    /// Byte must differ from the pattern byte (`_` is "not padding")
+    NotEqual,
+}
+
+impl LikeMode {
+    fn to_field<F: PrimeField>(self) -> F {
+        match self {
+            LikeMode::Equal => F::ONE,
+            LikeMode::NotEqual => F::from(2),
+        }
+    }
+}
+
+/// One LIKE comparison: string position, mode, pattern byte
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LikeRow {
+    pub position: usize,
+    pub mode: LikeMode,
+    pub byte: u8,
+}
+
+/// Parsed LIKE pattern: `head`, or `head%tail`
+/// `_` (None) matches exactly one byte
+///
+/// # Note
+///
+/// - Covers exact (`'a_c'`), prefix (`'abc%'`), suffix (`'%abc'`) and
+///   prefix+suffix (`'a%c'`) patterns
+/// - Substring search (`'%abc%'`) is not supported
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LikePattern {
+    pub head: Vec<Option<u8>>,
+    /// None if the pattern has no `%`
+    pub tail: Option<Vec<Option<u8>>>,
+}
+
+impl LikePattern {
+    /// Parse a LIKE pattern
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let parse_part = |part: &str| -> Result<Vec<Option<u8>>, String> {
+            part.bytes()
+                .map(|b| match b {
+                    b'_' => Ok(None),
+                    0 => Err("LIKE pattern must not contain NUL".to_string()),
+                    b => Ok(Some(b)),
+                })
+                .collect()
+        };
+
+        let parts: Vec<&str> = pattern.split('%').collect();
+        let parsed = match parts.as_slice() {
+            [exact] => Self {
+                head: parse_part(exact)?,
+                tail: None,
+            },
+            [head, tail] => Self {
+                head: parse_part(head)?,
+                tail: Some(parse_part(tail)?),
+            },
+            _ => {
+                return Err(format!(
+                    "Unsupported LIKE pattern '{}': at most one '%' is allowed",
+                    pattern
+                ))
+            }
+        };
+
+        if parsed.min_len() > FIXED_STRING_LEN {
+            return Err(format!(
+                "LIKE pattern '{}' is longer than {} bytes",
+                pattern, FIXED_STRING_LEN
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// Minimum length of a matching string
+    pub fn min_len(&self) -> usize {
+        self.head.len() + self.tail.as_ref().map_or(0, |t| t.len())
+    }
+
+    /// Reference (out-of-circuit) match on a packed string
+    pub fn matches(&self, value: u64) -> bool {
+        let bytes = value.to_be_bytes();
+        let len = fixed_string_len(value);
+
+        // Malformed strings (bytes after the first NUL) never match
+        if bytes[len..].iter().any(|&b| b != 0) {
+            return false;
+        }
+
+        let part_matches = |part: &[Option<u8>], s: &[u8]| {
+            part.iter().zip(s).all(|(p, &b)| p.is_none_or(|p| p == b))
+        };
+        let s = &bytes[..len];
+        match &self.tail {
+            None => len == self.head.len() && part_matches(&self.head, s),
+            Some(tail) => {
+                len >= self.min_len()
+                    && part_matches(&self.head, &s[..self.head.len()])
+                    && part_matches(tail, &s[len - tail.len()..])
+            }
+        }
+    }
+
+    /// Comparison rows of every candidate string length
+    /// One chain of `FIXED_STRING_LEN` rows (one per byte position) for each
+    /// length L a matching string can have; a chain holds iff the string has
+    /// length L and matches. The rows depend on the pattern only, so the
+    /// layout (and the verifying key) is the same for every string.
+    ///
+    /// # Row Layout (chain of length L)
+    ///
+    /// - Head bytes at positions `0..h`
+    /// - Tail bytes at positions `L-t..L`
+    /// - Other positions below L: `NotEqual 0` (no NUL inside the string)
+    /// - Padding: positions `L..8` must be zero
+    ///
+    /// Without `%` the only candidate is `L = h`; with `%` every L from
+    /// `min_len` to `FIXED_STRING_LEN`. The chains pin the length, so at most
+    /// one of them holds for a given string.
+    pub fn chains(&self) -> Vec<Vec<LikeRow>> {
+        let lengths = match &self.tail {
+            None => self.head.len()..=self.head.len(),
+            Some(_) => self.min_len()..=FIXED_STRING_LEN,
+        };
+        lengths.map(|len| self.chain(len)).collect()
+    }
+
+    /// Comparison rows of one candidate length (see `chains`)
+    fn chain(&self, len: usize) -> Vec<LikeRow> {
+        let tail = self.tail.as_deref().unwrap_or(&[]);
+        let tail_start = len - tail.len();
+        (0..FIXED_STRING_LEN)
+            .map(|position| {
+                let pattern = if position < self.head.len() {
+                    Some(self.head[position])
+                } else if position >= tail_start && position < len {
+                    Some(tail[position - tail_start])
+                } else {
+                    None
+                };
+                match pattern {
+                    Some(Some(byte)) => LikeRow { position, mode: LikeMode::Equal, byte },
+                    // `_` and unconstrained string bytes: anything but NUL
+                    Some(None) | None if position < len => LikeRow {
+                        position,
+                        mode: LikeMode::NotEqual,
+                        byte: 0,
+                    },
+                    _ => LikeRow { position, mode: LikeMode::Equal, byte: 0 },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Evaluate comparison rows on a packed string (witness for the accumulator)
+pub fn evaluate_like_rows(rows: &[LikeRow], value: u64) -> bool {
+    let bytes = value.to_be_bytes();
+    rows.iter().all(|row| {
+        let equal = bytes[row.position] == row.byte;
+        match row.mode {
+            LikeMode::Equal => equal,
+            LikeMode::NotEqual => !equal,
+        }
+    })
+}
+
+/// LIKE Gate Configuration
+/// Prefix/suffix matching on fixed-length byte-encoded strings
+///
+/// # Column Allocation
+///
+/// - `byte_column`: String byte, copied from the 8-bit decomposition (advice[10])
+/// - `inverse_column`: Inverse of (byte - pattern) for the is-zero check (advice[11])
+/// - `acc_column`: Running match product (advice[12])
+/// - `pattern_column`: Pattern byte (fixed[0])
+/// - `mode_column`: 1 = equal, 2 = not equal (fixed[1])
+///
+/// # Constraints
+///
+/// With `d = byte - pattern`, `eq = 1 - d · inv`, `m = mode`:
+///
+/// 1. **Is-zero**: `d · eq = 0`
+/// 2. **Accumulate**: `2 · acc_next = acc · (2m(2-m) · eq + m(m-1) · (1 - eq))`
+///    (mode 1 keeps `eq`, mode 2 keeps `1 - eq`)
+/// 3. **Init**: `acc = 1` in the first row of a chain
+/// 4. **Any**: `acc_next = acc + byte` over the chain results
+///
+/// # Note
+///
+/// - Columns are shared with Join Gate (used in different rows)
+/// - The row layout depends on the pattern only, every string is padded to
+///   `FIXED_STRING_LEN` bytes (see `LikePattern::chains`)
+#[derive(Clone, Debug)]
+pub struct LikeConfig {
+    pub byte_column: Column<Advice>,
+    pub inverse_column: Column<Advice>,
+    pub acc_column: Column<Advice>,
+    pub pattern_column: Column<Fixed>,
+    pub mode_column: Column<Fixed>,
+
+    // Selectors
+    pub like_selector: Selector,
+    pub init_selector: Selector,
+    pub any_selector: Selector,
+
+    // Range Check integration (byte decomposition)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// LIKE Chip
+/// `WHERE col LIKE 'abc%'` on packed strings
+pub struct LikeChip<F: PrimeField = Fr> {
+    config: LikeConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> LikeChip<F> {
+    /// Create a new LikeChip
+    pub fn new(config: LikeConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the LIKE Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> LikeConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-12]: shared with Join Gate
+        // - fixed[0-1]: shared with Range Check (threshold, u)
+        let byte_column = config.advice[10];
+        let inverse_column = config.advice[11];
+        let acc_column = config.advice[12];
+        let pattern_column = config.fixed[0];
+        let mode_column = config.fixed[1];
+
+        let like_selector = meta.selector();
+        let init_selector = meta.selector();
+        let any_selector = meta.selector();
+
+        meta.create_gate("like byte", |meta| {
+            let s = meta.query_selector(like_selector);
+            let byte = meta.query_advice(byte_column, Rotation::cur());
+            let inv = meta.query_advice(inverse_column, Rotation::cur());
+            let acc = meta.query_advice(acc_column, Rotation::cur());
+            let acc_next = meta.query_advice(acc_column, Rotation::next());
+            let pattern = meta.query_fixed(pattern_column);
+            let mode = meta.query_fixed(mode_column);
+
+            let one = Expression::Constant(F::ONE);
+            let two = Expression::Constant(F::from(2));
+
+            let d = byte - pattern;
+            let eq = one.clone() - d.clone() * inv;
+
+            // Lagrange selectors on mode ∈ {1, 2} (times 2)
+            let keep_eq = two.clone() * mode.clone() * (two.clone() - mode.clone());
+            let keep_ne = mode.clone() * (mode - one.clone());
+            let factor = keep_eq * eq.clone() + keep_ne * (one - eq.clone());
+
+            vec![
+                s.clone() * d * eq,
+                s * (two * acc_next - acc * factor),
+            ]
+        });
+
+        meta.create_gate("like init", |meta| {
+            let s = meta.query_selector(init_selector);
+            let acc = meta.query_advice(acc_column, Rotation::cur());
+
+            vec![s * (acc - Expression::Constant(F::ONE))]
+        });
+
+        // At most one chain holds (they pin different lengths), so the sum of
+        // the chain results is the boolean match
+        meta.create_gate("like any", |meta| {
+            let s = meta.query_selector(any_selector);
+            let acc = meta.query_advice(acc_column, Rotation::cur());
+            let term = meta.query_advice(byte_column, Rotation::cur());
+            let acc_next = meta.query_advice(acc_column, Rotation::next());
+
+            vec![s * (acc_next - acc - term)]
+        });
+
+        LikeConfig {
+            byte_column,
+            inverse_column,
+            acc_column,
+            pattern_column,
+            mode_column,
+            like_selector,
+            init_selector,
+            any_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Check `value LIKE pattern`
+    /// `value` is the scanned column cell; its bytes are decomposed from the
+    /// cell itself (copy constraint), so the match is over the committed value
+    ///
+    /// # Row Layout
+    ///
+    /// - Chain c: rows `9c .. 9c + 8` compare the 8 bytes, row `9c + 8` holds
+    ///   the chain result
+    /// - Then one row per chain summing the results
+    ///
+    /// # Return Value
+    ///
+    /// Boolean match cell (1 = match, 0 = no match)
+    pub fn check_like(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        pattern: &LikePattern,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // String bytes (chunk i = byte 7 - i of the big-endian packing)
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        let chunks =
+            range_check_chip.decompose_cell(layouter.namespace(|| "string bytes"), value)?;
+
+        let chains = pattern.chains();
+        let bytes = value.value().map(|v| field_to_u64(v).to_be_bytes());
+
+        layouter.assign_region(
+            || "like",
+            |mut region| {
+                let chain_rows = FIXED_STRING_LEN + 1;
+                let mut results = Vec::with_capacity(chains.len());
+
+                for (c, rows) in chains.iter().enumerate() {
+                    let base = c * chain_rows;
+                    self.config.init_selector.enable(&mut region, base)?;
+
+                    let mut acc = Value::known(F::ONE);
+                    let mut acc_cell = region.assign_advice(
+                        || format!("chain {} acc_0", c),
+                        self.config.acc_column,
+                        base,
+                        || acc,
+                    )?;
+
+                    for (i, row) in rows.iter().enumerate() {
+                        let offset = base + i;
+                        self.config.like_selector.enable(&mut region, offset)?;
+
+                        chunks[FIXED_STRING_LEN - 1 - row.position].copy_advice(
+                            || format!("chain {} byte_{}", c, i),
+                            &mut region,
+                            self.config.byte_column,
+                            offset,
+                        )?;
+                        region.assign_fixed(
+                            || format!("chain {} pattern_{}", c, i),
+                            self.config.pattern_column,
+                            offset,
+                            || Value::known(F::from(row.byte as u64)),
+                        )?;
+                        region.assign_fixed(
+                            || format!("chain {} mode_{}", c, i),
+                            self.config.mode_column,
+                            offset,
+                            || Value::known(row.mode.to_field::<F>()),
+                        )?;
+
+                        let d = bytes
+                            .map(|b| F::from(b[row.position] as u64) - F::from(row.byte as u64));
+                        region.assign_advice(
+                            || format!("chain {} inverse_{}", c, i),
+                            self.config.inverse_column,
+                            offset,
+                            || d.map(|d| d.invert().unwrap_or(F::ZERO)),
+                        )?;
+
+                        let holds = bytes.map(|b| {
+                            evaluate_like_rows(std::slice::from_ref(row), u64::from_be_bytes(b))
+                        });
+                        acc = acc
+                            .zip(holds)
+                            .map(|(acc, holds)| if holds { acc } else { F::ZERO });
+                        acc_cell = region.assign_advice(
+                            || format!("chain {} acc_{}", c, i + 1),
+                            self.config.acc_column,
+                            offset + 1,
+                            || acc,
+                        )?;
+                    }
+                    results.push(acc_cell);
+                }
+
+                // Sum of the chain results
+                let base = chains.len() * chain_rows;
+                let mut sum_cell = results[0].copy_advice(
+                    || "any_0",
+                    &mut region,
+                    self.config.acc_column,
+                    base,
+                )?;
+                for (i, result) in results.iter().enumerate().skip(1) {
+                    let offset = base + i - 1;
+                    self.config.any_selector.enable(&mut region, offset)?;
+                    result.copy_advice(
+                        || format!("chain result {}", i),
+                        &mut region,
+                        self.config.byte_column,
+                        offset,
+                    )?;
+                    let sum = sum_cell.value().copied() + result.value().copied();
+                    sum_cell = region.assign_advice(
+                        || format!("any_{}", i),
+                        self.config.acc_column,
+                        offset + 1,
+                        || sum,
+                    )?;
+                }
+
+                Ok(sum_cell)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(v: &str) -> u64 {
+        encode_fixed_string(v).unwrap()
+    }
+
+    #[test]
+    fn test_fixed_string_roundtrip() {
+        for v in ["", "a", "abc", "abcdefgh"] {
+            assert_eq!(decode_fixed_string(s(v)), v);
+            assert_eq!(fixed_string_len(s(v)), v.len());
+        }
+        assert!(encode_fixed_string("abcdefghi").is_none());
+        assert!(encode_fixed_string("a\0b").is_none());
+        // Numeric order is lexicographic order
+        assert!(s("ab") < s("abc") && s("abc") < s("abd") && s("abd") < s("b"));
+    }
+
+    #[test]
+    fn test_like_pattern_matches() {
+        let cases = [
+            ("abc%", "abcdef", true),
+            ("abc%", "abc", true),
+            ("abc%", "ab", false),
+            ("%def", "abcdef", true),
+            ("%def", "def", true),
+            ("%def", "ef", false),
+            ("a%f", "abcdef", true),
+            ("a%f", "af", true),
+            ("ab%bc", "abc", false),
+            ("a_c", "abc", true),
+            ("a_c", "ac", false),
+            ("a_c", "abcd", false),
+            ("%", "", true),
+            ("", "", true),
+            ("", "a", false),
+        ];
+        for (pattern, value, expected) in cases {
+            let parsed = LikePattern::parse(pattern).unwrap();
+            assert_eq!(parsed.matches(s(value)), expected, "{} LIKE {}", value, pattern);
+            // Exactly the chain of the string's length holds on a match
+            let holding = parsed
+                .chains()
+                .iter()
+                .filter(|rows| evaluate_like_rows(rows, s(value)))
+                .count();
+            assert_eq!(
+                holding,
+                expected as usize,
+                "chains for {} LIKE {}",
+                value,
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn test_like_chains_independent_of_value() {
+        // Rows are fixed by the pattern: 8 per candidate length
+        let exact = LikePattern::parse("a_c").unwrap().chains();
+        assert_eq!(exact.len(), 1);
+        let suffix = LikePattern::parse("%ef").unwrap().chains();
+        assert_eq!(suffix.len(), FIXED_STRING_LEN - 1);
+        assert!(suffix.iter().all(|rows| rows.len() == FIXED_STRING_LEN));
+        assert_eq!(LikePattern::parse("%").unwrap().chains().len(), FIXED_STRING_LEN + 1);
+    }
+
+    #[test]
+    fn test_like_pattern_rejects_unsupported() {
+        assert!(LikePattern::parse("%abc%").is_err());
+        assert!(LikePattern::parse("abcdefghi%").is_err());
+    }
+}
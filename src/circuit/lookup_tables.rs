@@ -0,0 +1,186 @@
+// Shared lookup tables
+// Chips look values up in fixed tables: 8-bit chunks in the 0-255 range
+// table, IN-list members in a tagged set, dictionary-encoded strings in a
+// (code, value) table. Every table column costs fixed rows and a column
+// commitment, so chips register the table they need with the
+// `LookupTables` of their `PoneglyphConfig` instead of allocating their
+// own: chips registering an identical table get the same columns.
+//
+// - `Range { bits }` tables hold 0 .. 2^bits - 1 and are loaded by
+//   `PoneglyphConfig::load_lookup_table` together with the 8-bit table
+//   (a 16-bit table needs k >= 17)
+// - `Set` and `Dictionary` tables are named; their rows are witness data,
+//   loaded once per synthesis with `LookupTables::load_rows` by whichever chip
+//   holds them
+
+use std::sync::{Arc, Mutex};
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
+
+/// Contents of a lookup table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LookupTableSpec {
+    /// One column holding 0 .. 2^bits - 1
+    Range { bits: u32 },
+    /// (tag, value) columns: `(0, 0)` plus `(1, v)` per member, so that
+    /// unselected rows look up `(0, 0)` and 0 is only a member if listed
+    Set { name: &'static str },
+    /// (code, value) columns of a dictionary encoding
+    Dictionary { name: &'static str },
+}
+
+impl LookupTableSpec {
+    /// Number of table columns
+    pub fn width(&self) -> usize {
+        match self {
+            LookupTableSpec::Range { .. } => 1,
+            LookupTableSpec::Set { .. } | LookupTableSpec::Dictionary { .. } => 2,
+        }
+    }
+
+    /// Fixed rows of the table (None for named tables, whose size is only
+    /// known at synthesis)
+    pub fn rows(&self) -> Option<usize> {
+        match self {
+            LookupTableSpec::Range { bits } => Some(1 << bits),
+            _ => None,
+        }
+    }
+}
+
+/// Registered table: its spec and columns
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupTable {
+    pub spec: LookupTableSpec,
+    pub columns: Vec<TableColumn>,
+    /// Chips that registered the table
+    pub users: Vec<&'static str>,
+}
+
+/// Lookup tables of one circuit configuration
+/// Clones share the registry, so chips configured from a copy of the
+/// `PoneglyphConfig` register into the same table set.
+#[derive(Clone, Debug, Default)]
+pub struct LookupTables {
+    tables: Arc<Mutex<Vec<LookupTable>>>,
+}
+
+impl LookupTables {
+    /// Table columns for `spec`, allocated on first registration
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let columns = config.lookup_tables.register(meta, "in_list", LookupTableSpec::Set { name: "in_list" });
+    /// let (tag_table, list_table) = (columns[0], columns[1]);
+    /// ```
+    pub fn register<F: PrimeField>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        chip: &'static str,
+        spec: LookupTableSpec,
+    ) -> Vec<TableColumn> {
+        let mut tables = self.tables();
+        if let Some(table) = tables.iter_mut().find(|t| t.spec == spec) {
+            if !table.users.contains(&chip) {
+                table.users.push(chip);
+            }
+            return table.columns.clone();
+        }
+        let columns: Vec<TableColumn> = (0..spec.width())
+            .map(|_| meta.lookup_table_column())
+            .collect();
+        tables.push(LookupTable {
+            spec,
+            columns: columns.clone(),
+            users: vec![chip],
+        });
+        columns
+    }
+
+    /// Columns of a registered table
+    pub fn get(&self, spec: LookupTableSpec) -> Option<Vec<TableColumn>> {
+        self.tables()
+            .iter()
+            .find(|t| t.spec == spec)
+            .map(|t| t.columns.clone())
+    }
+
+    /// Every registered table, in registration order
+    pub fn tables_registered(&self) -> Vec<LookupTable> {
+        self.tables().clone()
+    }
+
+    /// Fixed rows the range tables need (the circuit needs 2^k above this)
+    pub fn range_rows(&self) -> usize {
+        self.tables()
+            .iter()
+            .filter_map(|t| t.spec.rows())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Load every `Range` table
+    pub fn load_ranges<F: PrimeField>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        for table in self.tables_registered() {
+            let LookupTableSpec::Range { bits } = table.spec else {
+                continue;
+            };
+            layouter.assign_table(
+                || format!("{}-bit lookup table", bits),
+                |mut assign| {
+                    for i in 0..1usize << bits {
+                        assign.assign_cell(
+                            || format!("lookup value {}", i),
+                            table.columns[0],
+                            i,
+                            || Value::known(F::from(i as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Load the rows of a named table (each row one value per column)
+    /// A `Set` starts with its `(0, 0)` row, so `rows` only lists members.
+    pub fn load_rows<F: PrimeField>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        spec: LookupTableSpec,
+        rows: &[[u64; 2]],
+    ) -> Result<(), Error> {
+        let columns = self.get(spec).ok_or(Error::Synthesis)?;
+        let padding = match spec {
+            LookupTableSpec::Set { .. } => vec![[0, 0]],
+            LookupTableSpec::Dictionary { .. } => Vec::new(),
+            LookupTableSpec::Range { .. } => return Err(Error::Synthesis),
+        };
+        layouter.assign_table(
+            || format!("{:?} lookup table", spec),
+            |mut assign| {
+                for (i, row) in padding.iter().chain(rows).enumerate() {
+                    for (column, &value) in columns.iter().zip(row) {
+                        assign.assign_cell(
+                            || format!("row {}", i),
+                            *column,
+                            i,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn tables(&self) -> std::sync::MutexGuard<'_, Vec<LookupTable>> {
+        self.tables.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,408 @@
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::poseidon::{PoseidonChip, PoseidonConfig};
+
+/// Index cell and its bit cells (least significant first)
+type IndexBits<F> = (AssignedCell<F, F>, Vec<AssignedCell<F, F>>);
+
+/// Roots before and after a leaf update
+type RootPair<F> = (AssignedCell<F, F>, AssignedCell<F, F>);
+
+/// Instance row of the root before the changes
+pub const DELTA_INSTANCE_OLD_ROOT_ROW: usize = 0;
+/// Instance row of the root after the changes
+pub const DELTA_INSTANCE_NEW_ROOT_ROW: usize = 1;
+/// First instance row of the changes (index, old leaf, new leaf per change)
+pub const DELTA_INSTANCE_CHANGES_ROW: usize = 2;
+
+/// Merkle Path Configuration
+/// Paper Section 5.1: Incremental database commitment updates
+///
+/// # Column Allocation
+///
+/// - `advice[8-9]`: index accumulator, index bit (index decomposition)
+/// - `advice[10-14]`: bit, node, sibling, left, right (path step)
+///
+/// # Constraints
+///
+/// 1. **Index bits**: `bit ∈ {0, 1}` and `acc = 2 · acc_next + bit`; the
+///    accumulator after the last bit is 0, so the first one is the index
+/// 2. **Swap**: `bit ∈ {0, 1}`, `left = node + bit · (sibling - node)`,
+///    `right = sibling + bit · (node - sibling)`
+/// 3. **Hash**: the parent is `PoseidonChip::hash([left, right])`
+///
+/// # Note
+///
+/// - An update proves the old and the new root with the same bits and
+///   siblings (copied between the two swap rows of a level), so the roots
+///   differ only at the updated leaf
+/// - Columns are shared with Range Check / Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct MerkleConfig {
+    /// acc, bit
+    pub index: [Column<Advice>; 2],
+    /// bit, node, sibling, left, right
+    pub swap: [Column<Advice>; 5],
+    pub index_selector: Selector,
+    pub swap_selector: Selector,
+
+    // Poseidon integration (parent nodes)
+    pub poseidon_config: PoseidonConfig,
+}
+
+/// Merkle Chip
+/// Recomputes Poseidon Merkle roots in-circuit (matches `database::MerkleTree`)
+pub struct MerkleChip<F: PrimeField = Fr> {
+    config: MerkleConfig,
+    poseidon: PoseidonChip<F>,
+}
+
+impl<F: PrimeField> MerkleChip<F> {
+    /// Create a new MerkleChip
+    pub fn new(config: MerkleConfig) -> Self {
+        let poseidon = PoseidonChip::new(config.poseidon_config.clone());
+        Self { config, poseidon }
+    }
+
+    /// Configure the Merkle Index and Swap Gates
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        poseidon_config: &PoseidonConfig,
+    ) -> MerkleConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[8-9]: shared with Range Check / Aggregation Gate
+        // - advice[10-14]: shared with Join Gate
+        let index = [config.advice[8], config.advice[9]];
+        let swap = [
+            config.advice[10],
+            config.advice[11],
+            config.advice[12],
+            config.advice[13],
+            config.advice[14],
+        ];
+        let index_selector = meta.selector();
+        let swap_selector = meta.selector();
+
+        let boolean = |bit: Expression<F>| bit.clone() * (Expression::Constant(F::ONE) - bit);
+
+        meta.create_gate("merkle index bits", |meta| {
+            let s = meta.query_selector(index_selector);
+            let acc = meta.query_advice(index[0], Rotation::cur());
+            let acc_next = meta.query_advice(index[0], Rotation::next());
+            let bit = meta.query_advice(index[1], Rotation::cur());
+            vec![
+                s.clone() * boolean(bit.clone()),
+                s * (acc - acc_next * Expression::Constant(F::from(2)) - bit),
+            ]
+        });
+
+        meta.create_gate("merkle swap", |meta| {
+            let s = meta.query_selector(swap_selector);
+            let [bit, node, sibling, left, right] =
+                swap.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![
+                s.clone() * boolean(bit.clone()),
+                s.clone() * (left - node.clone() - bit.clone() * (sibling.clone() - node.clone())),
+                s * (right - sibling.clone() - bit * (node - sibling)),
+            ]
+        });
+
+        MerkleConfig {
+            index,
+            swap,
+            index_selector,
+            swap_selector,
+            poseidon_config: poseidon_config.clone(),
+        }
+    }
+
+    /// Decompose a leaf index into `depth` bits (least significant first)
+    /// Returns the index cell and the bit cells
+    pub fn decompose_index(
+        &self,
+        mut layouter: impl Layouter<F>,
+        index: Value<u64>,
+        depth: usize,
+    ) -> Result<IndexBits<F>, Error> {
+        layouter.assign_region(
+            || "merkle index",
+            |mut region| {
+                let mut acc_cells = Vec::with_capacity(depth);
+                let mut bits = Vec::with_capacity(depth);
+                for row in 0..depth {
+                    let rest = index.map(|i| i.checked_shr(row as u32).unwrap_or(0));
+                    acc_cells.push(region.assign_advice(
+                        || format!("acc_{}", row),
+                        self.config.index[0],
+                        row,
+                        || rest.map(F::from),
+                    )?);
+                    bits.push(region.assign_advice(
+                        || format!("bit_{}", row),
+                        self.config.index[1],
+                        row,
+                        || rest.map(|r| F::from(r & 1)),
+                    )?);
+                    self.config.index_selector.enable(&mut region, row)?;
+                }
+                let last = region.assign_advice_from_constant(
+                    || "acc_end",
+                    self.config.index[0],
+                    depth,
+                    F::ZERO,
+                )?;
+                let index_cell = acc_cells.into_iter().next().unwrap_or(last);
+                Ok((index_cell, bits))
+            },
+        )
+    }
+
+    /// Roots before and after replacing `old_leaf` with `new_leaf`
+    /// `bits` come from `decompose_index`; `siblings` go from the leaf level up
+    pub fn update(
+        &self,
+        mut layouter: impl Layouter<F>,
+        old_leaf: &AssignedCell<F, F>,
+        new_leaf: &AssignedCell<F, F>,
+        bits: &[AssignedCell<F, F>],
+        siblings: &[Value<F>],
+    ) -> Result<RootPair<F>, Error> {
+        if bits.len() != siblings.len() {
+            return Err(Error::Synthesis);
+        }
+        let mut old = old_leaf.clone();
+        let mut new = new_leaf.clone();
+        for (level, (bit, &sibling)) in bits.iter().zip(siblings).enumerate() {
+            let (old_pair, new_pair) = layouter.assign_region(
+                || format!("merkle level {}", level),
+                |mut region| {
+                    let old_sibling = region.assign_advice(
+                        || "old sibling",
+                        self.config.swap[2],
+                        0,
+                        || sibling,
+                    )?;
+                    let old_pair = self.swap_row(&mut region, 0, bit, &old, &old_sibling)?;
+                    let new_sibling = old_sibling.copy_advice(
+                        || "new sibling",
+                        &mut region,
+                        self.config.swap[2],
+                        1,
+                    )?;
+                    let new_pair = self.swap_row(&mut region, 1, bit, &new, &new_sibling)?;
+                    Ok((old_pair, new_pair))
+                },
+            )?;
+            old = self.poseidon.hash(
+                layouter.namespace(|| format!("old node {}", level)),
+                &old_pair,
+            )?;
+            new = self.poseidon.hash(
+                layouter.namespace(|| format!("new node {}", level)),
+                &new_pair,
+            )?;
+        }
+        Ok((old, new))
+    }
+
+    /// Root of the tree holding `leaf` at the index of `bits` (membership)
+    /// `bits` come from `decompose_index`; `siblings` go from the leaf level up
+    pub fn root(
+        &self,
+        mut layouter: impl Layouter<F>,
+        leaf: &AssignedCell<F, F>,
+        bits: &[AssignedCell<F, F>],
+        siblings: &[Value<F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if bits.len() != siblings.len() {
+            return Err(Error::Synthesis);
+        }
+        let mut node = leaf.clone();
+        for (level, (bit, &sibling)) in bits.iter().zip(siblings).enumerate() {
+            let pair = layouter.assign_region(
+                || format!("merkle level {}", level),
+                |mut region| {
+                    let sibling =
+                        region.assign_advice(|| "sibling", self.config.swap[2], 0, || sibling)?;
+                    self.swap_row(&mut region, 0, bit, &node, &sibling)
+                },
+            )?;
+            node = self
+                .poseidon
+                .hash(layouter.namespace(|| format!("node {}", level)), &pair)?;
+        }
+        Ok(node)
+    }
+
+    /// Swap row: order (node, sibling) by the bit; `sibling` is already
+    /// assigned at `offset`
+    fn swap_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        bit: &AssignedCell<F, F>,
+        node: &AssignedCell<F, F>,
+        sibling: &AssignedCell<F, F>,
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        let [bit_col, node_col, _, left_col, right_col] = self.config.swap;
+        self.config.swap_selector.enable(region, offset)?;
+        let bit = bit.copy_advice(|| "bit", region, bit_col, offset)?;
+        let node = node.copy_advice(|| "node", region, node_col, offset)?;
+
+        let swapped = bit.value().map(|&b| b == F::ONE);
+        let pair = node
+            .value()
+            .copied()
+            .zip(sibling.value().copied())
+            .zip(swapped)
+            .map(|((n, s), swap)| if swap { (s, n) } else { (n, s) });
+        let left = region.assign_advice(|| "left", left_col, offset, || pair.map(|p| p.0))?;
+        let right = region.assign_advice(|| "right", right_col, offset, || pair.map(|p| p.1))?;
+        Ok([left, right])
+    }
+}
+
+/// Witness of one leaf change
+#[derive(Clone, Debug)]
+pub struct DeltaWitness {
+    pub index: Value<u64>,
+    pub old_leaf: Value<Fr>,
+    pub new_leaf: Value<Fr>,
+    /// From the leaf level up (`depth` siblings)
+    pub siblings: Vec<Value<Fr>>,
+}
+
+/// Commitment Delta Circuit
+/// Proves that a Merkle root changed into another only at the claimed leaves
+///
+/// # Instance
+///
+/// - row 0: old root, row 1: new root
+/// - from row 2: index, old leaf, new leaf of each change, in order
+///
+/// The changes are chained: each change starts from the root the previous
+/// one ended with.
+#[derive(Clone, Debug)]
+pub struct CommitmentDeltaCircuit {
+    pub depth: usize,
+    pub changes: Vec<DeltaWitness>,
+}
+
+impl CommitmentDeltaCircuit {
+    /// Circuit without witnesses (for key generation)
+    pub fn shape(depth: usize, num_changes: usize) -> Self {
+        let change = DeltaWitness {
+            index: Value::unknown(),
+            old_leaf: Value::unknown(),
+            new_leaf: Value::unknown(),
+            siblings: vec![Value::unknown(); depth],
+        };
+        Self {
+            depth,
+            changes: vec![change; num_changes],
+        }
+    }
+}
+
+/// Config of `CommitmentDeltaCircuit`
+#[derive(Clone, Debug)]
+pub struct CommitmentDeltaConfig {
+    pub poneglyph_config: PoneglyphConfig,
+    pub merkle_config: MerkleConfig,
+}
+
+impl Circuit<Fr> for CommitmentDeltaCircuit {
+    type Config = CommitmentDeltaConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::shape(self.depth, self.changes.len())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let poseidon_config = PoseidonChip::configure(meta, &poneglyph_config);
+        let merkle_config = MerkleChip::configure(meta, &poneglyph_config, &poseidon_config);
+        CommitmentDeltaConfig {
+            poneglyph_config,
+            merkle_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        let instance = config.poneglyph_config.instance;
+        let chip = MerkleChip::new(config.merkle_config.clone());
+
+        let mut previous_root: Option<AssignedCell<Fr, Fr>> = None;
+        for (k, change) in self.changes.iter().enumerate() {
+            let row = DELTA_INSTANCE_CHANGES_ROW + 3 * k;
+            let (index, bits) = chip.decompose_index(
+                layouter.namespace(|| format!("change {} index", k)),
+                change.index,
+                self.depth,
+            )?;
+            let (old_leaf, new_leaf) = layouter.assign_region(
+                || format!("change {} leaves", k),
+                |mut region| {
+                    let old_leaf = region.assign_advice(
+                        || "old leaf",
+                        config.merkle_config.swap[1],
+                        0,
+                        || change.old_leaf,
+                    )?;
+                    let new_leaf = region.assign_advice(
+                        || "new leaf",
+                        config.merkle_config.swap[1],
+                        1,
+                        || change.new_leaf,
+                    )?;
+                    Ok((old_leaf, new_leaf))
+                },
+            )?;
+            layouter.constrain_instance(index.cell(), instance, row)?;
+            layouter.constrain_instance(old_leaf.cell(), instance, row + 1)?;
+            layouter.constrain_instance(new_leaf.cell(), instance, row + 2)?;
+
+            let (old_root, new_root) = chip.update(
+                layouter.namespace(|| format!("change {} path", k)),
+                &old_leaf,
+                &new_leaf,
+                &bits,
+                &change.siblings,
+            )?;
+            match &previous_root {
+                None => {
+                    layouter.constrain_instance(
+                        old_root.cell(),
+                        instance,
+                        DELTA_INSTANCE_OLD_ROOT_ROW,
+                    )?;
+                }
+                Some(previous) => {
+                    layouter.assign_region(
+                        || format!("change {} chain", k),
+                        |mut region| region.constrain_equal(previous.cell(), old_root.cell()),
+                    )?;
+                }
+            }
+            previous_root = Some(new_root);
+        }
+        if let Some(root) = previous_root {
+            layouter.constrain_instance(root.cell(), instance, DELTA_INSTANCE_NEW_ROOT_ROW)?;
+        }
+        Ok(())
+    }
+}
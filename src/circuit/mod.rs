@@ -1,22 +1,72 @@
 use halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner, Value},
-    plonk::{Circuit, ConstraintSystem, Error},
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Fixed, Selector},
 };
 use pasta_curves::pallas::Base as Fr;
 
 pub mod aggregation;
+pub mod arena;
+pub mod arithmetic;
+pub mod boolean;
+pub mod cast;
 pub mod config;
+pub mod decimal;
+#[cfg(feature = "constraint-docs")]
+pub mod docs;
+#[cfg(feature = "formal-export")]
+pub mod formal;
 pub mod group_by;
+pub mod in_list;
 pub mod join;
+pub mod like;
+pub mod lookup_tables;
+pub mod merkle;
+pub mod nullable;
+pub mod partial_aggregate;
+pub mod poseidon;
+pub mod private_query;
 pub mod range_check;
+pub mod scan;
+#[cfg(any(feature = "constraint-docs", feature = "formal-export"))]
+mod selector_names;
+pub mod series;
+pub mod signed;
 pub mod sort;
+pub mod subquery;
+pub mod top_k;
+pub mod union;
+pub mod window;
+pub mod witness;
 
 pub use aggregation::*;
+pub use arithmetic::*;
+pub use boolean::*;
+pub use cast::*;
 pub use config::*;
+pub use decimal::*;
+#[cfg(feature = "constraint-docs")]
+pub use docs::*;
+#[cfg(feature = "formal-export")]
+pub use formal::*;
 pub use group_by::*;
+pub use in_list::*;
 pub use join::*;
+pub use like::*;
+pub use lookup_tables::*;
+pub use merkle::*;
+pub use nullable::*;
+pub use partial_aggregate::*;
+pub use poseidon::*;
+pub use private_query::*;
 pub use range_check::*;
+pub use scan::*;
+pub use series::*;
+pub use signed::*;
 pub use sort::*;
+pub use subquery::*;
+pub use top_k::*;
+pub use union::*;
+pub use window::*;
 
 /// Temel SQL Gate trait'i - tüm operatörler bunu implement eder
 pub trait SQLGate<F: ff::PrimeField> {
@@ -39,10 +89,39 @@ pub trait SQLGate<F: ff::PrimeField> {
 /// See paper Section 3: Compiling SQL queries into ZKP circuits
 #[derive(Clone)]
 pub struct PoneglyphCircuit {
-    /// Veritabanı commitment (public input)
-    pub db_commitment: Value<Fr>,
+    /// Rows of the FROM table; their commitment is hashed in-circuit into
+    /// instance row 0 (see `TableScan`)
+    pub scan: TableScan,
     /// Query sonucu (public input)
+    /// Only used without aggregations: instance row 1 is otherwise copied
+    /// from the final aggregation result (or compared with the predicate)
     pub query_result: Value<Fr>,
+    /// Request nonce (public input, instance row 2)
+    /// Binds the proof to one request so it can't be replayed as the answer to another
+    pub nonce: Value<Fr>,
+    /// Expiry bound (public input, instance row 3), see `ExpiryBound::to_field`
+    /// Fresh-only feeds reject proofs whose bound is behind the current block/time
+    pub expiry: Value<Fr>,
+    /// Query hash (public input, instance row 4), see `sql::query_hash`
+    /// Binds the proof to the query it answers: the hash is a constant of the
+    /// circuit, so it is part of the verifying key
+    pub query_hash: Fr,
+    /// Result commitment (public input, instance row 5), see `QueryResult::commitment`
+    /// Binds the proof to the result rows revealed with it. Only used without
+    /// an in-circuit result (see `result_columns`): row 5 is otherwise hashed
+    /// from the final aggregation result
+    pub result_commitment: Value<Fr>,
+    /// Columns of the query result, a constant of the circuit
+    /// With aggregations, no predicate and one column, the result is that
+    /// column's final aggregation value, and its commitment is hashed
+    /// in-circuit into instance row 5
+    pub result_columns: Vec<String>,
+    /// Selective disclosure: with a predicate, the final aggregation result
+    /// stays private and instance row 1 holds whether the predicate holds for
+    /// it (0 or 1); a predicate needs an aggregation
+    pub result_predicate: Option<ResultPredicate>,
+    /// Where range check thresholds live (fixed columns or the instance column)
+    pub threshold_mode: ThresholdMode,
     /// Range check operations
     pub range_checks: Vec<RangeCheckOp>,
     /// Sort operations
@@ -55,6 +134,88 @@ pub struct PoneglyphCircuit {
     pub aggregations: Vec<AggregationOp>,
 }
 
+/// Rows of the table a query scans
+/// `PoneglyphCircuit` hashes them in row-major order, like
+/// `DatabaseTable::commit`, and exposes the hash in instance row 0; the
+/// aggregations over a column (`AggregationOp::column`) are copy-constrained
+/// to its cells.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableScan {
+    /// Column names (lowercase, as compiled)
+    pub columns: Vec<String>,
+    /// Rows, one value per column
+    pub rows: Vec<Vec<u64>>,
+}
+
+impl TableScan {
+    pub fn new(columns: Vec<String>, rows: Vec<Vec<u64>>) -> Self {
+        Self { columns, rows }
+    }
+
+    /// Commitment of the rows (instance row 0), see `DatabaseCommitment::of_rows`
+    pub fn commitment(&self) -> Fr {
+        crate::database::DatabaseCommitment::of_rows(&self.rows).commitment()
+    }
+
+    /// Number of hashed cells
+    pub fn num_cells(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// Position of a column
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+}
+
+/// Placement of range check thresholds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThresholdMode {
+    /// Thresholds and u in fixed columns (part of the verifying key)
+    #[default]
+    Fixed,
+    /// Thresholds in the instance column, from row `NUM_INSTANCE_ROWS` on, in
+    /// operation order; one key serves all threshold values (`u` is unused)
+    Instance,
+}
+
+/// Public predicate over a private query result (see
+/// `PoneglyphCircuit::result_predicate`)
+/// The bound is a constant of the circuit, so it is part of the verifying key
+/// like `ThresholdMode::Fixed` thresholds. Non-strict bounds are the strict
+/// ones shifted by one (`result >= t` is `Above(t - 1)`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResultPredicate {
+    /// `result < t`
+    Below(u64),
+    /// `result > t`
+    Above(u64),
+}
+
+impl ResultPredicate {
+    /// Evaluate outside the circuit
+    pub fn holds(&self, result: u64) -> bool {
+        match *self {
+            ResultPredicate::Below(t) => result < t,
+            ResultPredicate::Above(t) => result > t,
+        }
+    }
+
+    /// Instance value of the outcome (instance row 1)
+    pub fn outcome(holds: bool) -> Fr {
+        Fr::from(holds as u64)
+    }
+}
+
+impl std::fmt::Display for ResultPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultPredicate::Below(t) => write!(f, "result < {}", t),
+            ResultPredicate::Above(t) => write!(f, "result > {}", t),
+        }
+    }
+}
+
 /// Range Check Operation
 #[derive(Clone, Debug)]
 pub struct RangeCheckOp {
@@ -63,6 +224,14 @@ pub struct RangeCheckOp {
     pub u: u64,
 }
 
+/// BETWEEN Operation (`low <= value < high`)
+#[derive(Clone, Debug)]
+pub struct BetweenOp {
+    pub value: Value<u64>,
+    pub low: u64,
+    pub high: u64,
+}
+
 /// Sort Operation
 #[derive(Clone, Debug)]
 pub struct SortOp {
@@ -71,18 +240,137 @@ pub struct SortOp {
 }
 
 /// Group-By Operation
+/// One key tuple per distinct group (one value per GROUP BY column), in
+/// lexicographic order
 #[derive(Clone, Debug)]
 pub struct GroupByOp {
-    pub group_keys: Vec<u64>,
+    pub group_keys: Vec<Vec<u64>>,
 }
 
 /// Join Operation
+/// `table1_column` names the scanned column `table1_keys` are (in table
+/// order), if any: a semi / anti join then copies its keys from the
+/// `TableScan` cells
 #[derive(Clone, Debug)]
 pub struct JoinOp {
     pub table1_keys: Vec<u64>,
     pub table1_values: Vec<u64>,
     pub table2_keys: Vec<u64>,
     pub table2_values: Vec<u64>,
+    /// Semi / anti joins only use the keys
+    pub kind: JoinKind,
+    pub table1_column: Option<String>,
+}
+
+/// IN-list Operation
+/// `values` are the column values, `list` the literal list
+#[derive(Clone, Debug)]
+pub struct InListOp {
+    pub values: Vec<u64>,
+    pub list: Vec<u64>,
+}
+
+/// Arithmetic Operation (computed column)
+/// `inputs` holds the referenced columns (column name -> values)
+#[derive(Clone, Debug)]
+pub struct ArithmeticOp {
+    pub expr: ArithExpr,
+    pub inputs: std::collections::HashMap<String, Vec<u64>>,
+}
+
+/// Window Operation
+/// Rows sorted by (partition, order); `function` selects the output column,
+/// `values` is the SUM argument (empty for ROW_NUMBER/RANK)
+#[derive(Clone, Debug)]
+pub struct WindowOp {
+    pub function: WindowFunction,
+    pub partition_keys: Vec<u64>,
+    pub order_keys: Vec<u64>,
+    pub values: Vec<u64>,
+}
+
+/// Subquery Operation (outer filter)
+/// `values` is the outer column, `result` the inner query result
+/// (one value for scalar filters, the items for IN)
+#[derive(Clone, Debug)]
+pub struct SubqueryOp {
+    pub filter: SubqueryFilter,
+    pub values: Vec<u64>,
+    pub result: Vec<u64>,
+}
+
+/// Union Operation
+/// `left` and `right` are the values of the two input relations
+#[derive(Clone, Debug)]
+pub struct UnionOp {
+    pub operation: SetOperation,
+    pub left: Vec<u64>,
+    pub right: Vec<u64>,
+}
+
+/// generate_series Operation
+/// Rows `start, start + step, ...` up to and including `stop`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeriesOp {
+    pub start: u64,
+    pub stop: u64,
+    pub step: u64,
+}
+
+impl SeriesOp {
+    /// Series values (empty if `start > stop` or `step = 0`)
+    pub fn values(&self) -> Vec<u64> {
+        let mut values = Vec::new();
+        if self.step == 0 {
+            return values;
+        }
+        let mut next = Some(self.start);
+        while let Some(value) = next.filter(|&v| v <= self.stop) {
+            values.push(value);
+            next = value.checked_add(self.step);
+        }
+        values
+    }
+}
+
+/// CAST Operation over a raw value
+/// Rescales from `from_scale` to `to_scale` (round half up when the scale
+/// shrinks) and bounds the result by `max`, the largest raw value of the
+/// target type (e.g. `2^16 - 1` for SMALLINT, `10^5 - 1` for DECIMAL(5, 2))
+#[derive(Clone, Debug)]
+pub struct CastOp {
+    pub value: Value<u64>,
+    pub from_scale: u32,
+    pub to_scale: u32,
+    pub max: u64,
+}
+
+impl CastOp {
+    /// Factor applied before the division (`10^(to - from)`, or 1)
+    pub fn multiplier(&self) -> Option<u64> {
+        10u64.checked_pow(self.to_scale.saturating_sub(self.from_scale))
+    }
+
+    /// Divisor of the rounded division (`10^(from - to)`, or 1)
+    pub fn divisor(&self) -> Option<u64> {
+        10u64.checked_pow(self.from_scale.saturating_sub(self.to_scale))
+    }
+
+    /// Cast a raw value, same formula as the `cast` gate
+    /// None if the result exceeds `max` (or the scales are out of range)
+    pub fn apply(&self, value: u64) -> Option<u64> {
+        let multiplier = self.multiplier()? as u128;
+        let divisor = self.divisor()? as u128;
+        let out = (value as u128 * multiplier + divisor / 2) / divisor;
+        u64::try_from(out).ok().filter(|&out| out <= self.max)
+    }
+}
+
+/// `value` is a packed string, see `encode_fixed_string`
+#[derive(Clone, Debug)]
+pub struct LikeOp {
+    pub value: u64,
+    pub pattern: LikePattern,
 }
 
 /// Aggregation type
@@ -92,17 +380,44 @@ pub enum AggregationType {
     Count,
     Max,
     Min,
+    /// Lower median (value at index `(n - 1) / 2` of the sorted group)
+    Median,
+    /// Nearest-rank percentile, `p` in 0..=100 (value at index `ceil(p·n/100) - 1`)
+    Percentile(u8),
+    /// Population variance at fixed-point scale `s` (`⌊10^s · Σ(v - mean)² / n⌋`)
+    Variance(u32),
+    /// Population standard deviation at fixed-point scale `s`
+    /// (`⌊√(10^2s · Σ(v - mean)² / n)⌋`)
+    StdDev(u32),
 }
 
 impl AggregationType {
-    /// Create from string representation
+    /// Create from string representation (`percentile(p)` for percentiles,
+    /// `variance(s)` / `stddev(s)` for a fixed-point scale other than 0)
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
+        let s = s.to_lowercase();
+        match s.as_str() {
             "sum" => Some(AggregationType::Sum),
             "count" => Some(AggregationType::Count),
             "max" => Some(AggregationType::Max),
             "min" => Some(AggregationType::Min),
-            _ => None,
+            "median" => Some(AggregationType::Median),
+            "variance" => Some(AggregationType::Variance(0)),
+            "stddev" => Some(AggregationType::StdDev(0)),
+            _ => {
+                let (name, arg) = s.strip_suffix(')')?.split_once('(')?;
+                let arg = arg.trim();
+                match name {
+                    "percentile" => {
+                        let p: u8 = arg.parse().ok()?;
+                        (p <= 100).then_some(AggregationType::Percentile(p))
+                    }
+                    "variance" => arg.parse().ok().map(AggregationType::Variance),
+                    "stddev" => arg.parse().ok().map(AggregationType::StdDev),
+                    _ => None,
+                }
+            }
         }
     }
 
@@ -113,26 +428,194 @@ impl AggregationType {
             AggregationType::Count => "count",
             AggregationType::Max => "max",
             AggregationType::Min => "min",
+            AggregationType::Median => "median",
+            AggregationType::Percentile(_) => "percentile",
+            AggregationType::Variance(_) => "variance",
+            AggregationType::StdDev(_) => "stddev",
+        }
+    }
+
+    /// Rank aggregations select a value of the sorted group
+    /// (see `AggregationChip::aggregate_rank_and_verify`)
+    pub fn is_rank(&self) -> bool {
+        matches!(self, AggregationType::Median | AggregationType::Percentile(_))
+    }
+
+    /// Index of the selected value in a sorted group of `n` values
+    /// None for non-rank aggregations and empty groups
+    pub fn rank_index(&self, n: usize) -> Option<usize> {
+        if n == 0 {
+            return None;
+        }
+        match self {
+            AggregationType::Median => Some((n - 1) / 2),
+            AggregationType::Percentile(p) => {
+                let rank = (*p as usize * n).div_ceil(100);
+                Some(rank.max(1) - 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Dispersion aggregations are computed from the sum and sum of squares
+    /// (see `AggregationChip::aggregate_dispersion_and_verify`)
+    pub fn is_dispersion(&self) -> bool {
+        matches!(self, AggregationType::Variance(_) | AggregationType::StdDev(_))
+    }
+
+    /// Factor the variance is multiplied with before the division by `n`:
+    /// `10^s` for VARIANCE, `10^2s` for STDDEV (its square root then has scale `s`)
+    /// None for other aggregations or if the factor overflows
+    pub fn dispersion_factor(&self) -> Option<u128> {
+        match self {
+            AggregationType::Variance(scale) => 10u128.checked_pow(*scale),
+            AggregationType::StdDev(scale) => 10u128.checked_pow(scale.checked_mul(2)?),
+            _ => None,
+        }
+    }
+
+    /// VARIANCE / STDDEV of a group, same integer formula as the circuit
+    ///
+    /// With `S1 = Σv`, `S2 = Σv²` and `D = n·S2 - S1²` (= `n² · variance`),
+    /// the scaled variance is `q = ⌊F·D / n²⌋` and the standard deviation is
+    /// `⌊√q⌋`. None for other aggregations, empty groups and results that
+    /// do not fit in 64 bits.
+    pub fn dispersion(&self, values: &[u64]) -> Option<u64> {
+        let s1 = values.iter().map(|&v| v as u128).sum::<u128>();
+        let s2 = values
+            .iter()
+            .try_fold(0u128, |acc, &v| acc.checked_add(v as u128 * v as u128))?;
+        self.dispersion_from_moments(values.len() as u64, s1, s2)
+    }
+
+    /// VARIANCE / STDDEV from the count `n`, `S1 = Σv` and `S2 = Σv²`
+    /// (e.g. of a merged `PartialAggregate`); same result as `dispersion`
+    pub fn dispersion_from_moments(&self, n: u64, s1: u128, s2: u128) -> Option<u64> {
+        let factor = self.dispersion_factor()?;
+        if n == 0 {
+            return None;
+        }
+        let n = n as u128;
+        let d = n.checked_mul(s2)?.checked_sub(s1.checked_mul(s1)?)?;
+        let q = u64::try_from(d.checked_mul(factor)? / (n * n)).ok()?;
+        match self {
+            AggregationType::StdDev(_) => Some(isqrt(q)),
+            _ => Some(q),
         }
     }
 }
 
+/// Integer square root `⌊√x⌋`
+fn isqrt(x: u64) -> u64 {
+    let mut r = (x as f64).sqrt() as u64;
+    while r as u128 * r as u128 > x as u128 {
+        r -= 1;
+    }
+    while (r as u128 + 1) * (r as u128 + 1) <= x as u128 {
+        r += 1;
+    }
+    r
+}
+
 /// Aggregation Operation
+/// `column` names the scanned column `values` are (in table order), if any:
+/// the value cells are then copy-constrained to the `TableScan` cells
 #[derive(Clone, Debug)]
 pub struct AggregationOp {
     pub group_keys: Vec<u64>,
     pub values: Vec<u64>,
     pub agg_type: AggregationType,
+    pub column: Option<String>,
+}
+
+/// Config of `PoneglyphCircuit`
+/// The shared columns and the configs of the chips with gates on them
+#[derive(Clone, Debug)]
+pub struct PoneglyphCircuitConfig {
+    pub poneglyph_config: PoneglyphConfig,
+    pub operators: OperatorConfigs,
+    pub poseidon_config: PoseidonConfig,
+}
+
+impl PoneglyphCircuitConfig {
+    pub fn configure(meta: &mut ConstraintSystem<Fr>) -> Self {
+        let (poneglyph_config, operators) =
+            PoneglyphConfig::configure_operators(meta, DecompositionParams::default());
+        let poseidon_config = PoseidonChip::configure(meta, &poneglyph_config);
+        Self {
+            poneglyph_config,
+            operators,
+            poseidon_config,
+        }
+    }
+}
+
+impl ConfigColumns for PoneglyphCircuitConfig {
+    fn poneglyph_config(&self) -> &PoneglyphConfig {
+        &self.poneglyph_config
+    }
+
+    /// Poseidon's round constants come after the shared columns
+    fn fixed_columns(&self) -> Vec<Column<Fixed>> {
+        let mut columns = self.poneglyph_config.fixed.to_vec();
+        columns.extend(self.poseidon_config.round_constants);
+        columns
+    }
+
+    fn selectors(&self) -> Vec<Selector> {
+        let base = &self.poneglyph_config;
+        let operators = &self.operators;
+        let aggregation = &operators.aggregation;
+        let poseidon = &self.poseidon_config;
+        vec![
+            base.range_check_selector,
+            base.less_than_selector,
+            base.decomposition_selector,
+            base.sort_selector,
+            base.between_selector,
+            base.public_less_than_selector,
+            base.chain_selector,
+            base.signed_decomposition_selector,
+            operators.sort.sort_selector,
+            operators.group_by.boundary_selector,
+            operators.join.join_selector,
+            operators.join.deduplication_selector,
+            operators.join.semi_selector,
+            operators.join.anti_selector,
+            operators.join.gap_selector,
+            aggregation.sum_selector,
+            aggregation.count_selector,
+            aggregation.max_selector,
+            aggregation.min_selector,
+            aggregation.moments_start_selector,
+            aggregation.moments_step_selector,
+            aggregation.variance_selector,
+            aggregation.sqrt_selector,
+            poseidon.absorb_selector,
+            poseidon.full_round_selector,
+            poseidon.partial_round_selector,
+            poseidon.pack_selector,
+        ]
+    }
 }
 
 impl Circuit<Fr> for PoneglyphCircuit {
-    type Config = PoneglyphConfig;
+    type Config = PoneglyphCircuitConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
         Self {
-            db_commitment: Value::unknown(),
+            scan: TableScan::default(),
             query_result: Value::unknown(),
+            nonce: Value::unknown(),
+            expiry: Value::unknown(),
+            // Part of the verifying key (see `query_hash`)
+            query_hash: self.query_hash,
+            result_commitment: Value::unknown(),
+            // Part of the verifying key, like the query hash
+            result_columns: self.result_columns.clone(),
+            result_predicate: self.result_predicate,
+            threshold_mode: self.threshold_mode,
             range_checks: Vec::new(),
             sorts: Vec::new(),
             group_bys: Vec::new(),
@@ -142,111 +625,119 @@ impl Circuit<Fr> for PoneglyphCircuit {
     }
 
     fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
-        PoneglyphConfig::configure(meta)
+        PoneglyphCircuitConfig::configure(meta)
     }
 
-    fn synthesize(
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        self.synthesize_with(config, layouter, true)
+    }
+}
+
+impl PoneglyphCircuit {
+    /// Synthesize the query
+    /// Without `bind_query_hash` the query hash is not exposed in instance row
+    /// 4: the caller commits to it there instead (see `PrivateQueryCircuit`)
+    pub(crate) fn synthesize_with(
         &self,
-        config: Self::Config,
+        config: PoneglyphCircuitConfig,
         mut layouter: impl Layouter<Fr>,
+        bind_query_hash: bool,
     ) -> Result<(), Error> {
         // Makale Section 5.1: Public input'ları instance column'a expose et
         // Row 0: Veritabanı commitment
         // Row 1: Sorgu sonucu
-        // NOT: Instance column'dan değer okuma işlemini KALDIRIYORUZ
-        // Çünkü MockProver::run çağrısında public_inputs ile doldurulacak
-        // enable_equality zaten configure'da yapıldı, bu yeterli
-        // Instance column constraint'leri MockProver tarafından otomatik olarak kontrol edilir
+        let operators = config.operators;
+        let poseidon_chip = PoseidonChip::<Fr>::new(config.poseidon_config);
+        let config = config.poneglyph_config;
 
         // Lookup table'ı yükle
         config.load_lookup_table(&mut layouter)?;
 
-        // Create gate configs for synthesis
-        // Note: Gates are already configured in Circuit::configure, but we need to create
-        // chip instances here for synthesis. We'll create minimal configs from the base config.
-
-        // Create Range Check config
-        let range_check_config = RangeCheckConfig {
-            chunk_columns: [
-                config.advice[0],
-                config.advice[1],
-                config.advice[2],
-                config.advice[3],
-                config.advice[4],
-                config.advice[5],
-                config.advice[6],
-                config.advice[7],
-            ],
-            lookup_table: config.lookup_table,
-            check_column: config.advice[8],
-            x_column: config.advice[9],
-            diff_column: config.advice[8],
-            threshold_column: config.fixed[0],
-            u_column: config.fixed[1],
-            selector: config.range_check_selector,
-            less_than_selector: config.less_than_selector,
-            decomposition_selector: config.decomposition_selector,
-            diff_lookup_selector: config.diff_lookup_selector,
-        };
-        let range_check_chip = RangeCheckChip::new(range_check_config.clone());
-
-        // Create Sort config
-        let sort_config = SortConfig {
-            input_column: config.advice[2],
-            output_column: config.advice[3],
-            diff_column: config.advice[4],
-            sort_selector: config.sort_selector, // Sort için ayrı selector
-            range_check_config: range_check_config.clone(),
-        };
-        let sort_chip = SortChip::new(sort_config.clone());
-
-        // Create Group-By config
-        let group_by_config = GroupByConfig {
-            group_key_column: config.advice[5],
-            boundary_column: config.advice[6],
-            inverse_column: config.advice[7],
-            boundary_selector: config.decomposition_selector, // Reuse selector
-            range_check_config: range_check_config.clone(),
-        };
-        let group_by_chip = GroupByChip::new(group_by_config.clone());
-
-        // Create Join config
-        let join_config = JoinConfig {
-            table1_key_column: config.advice[10],
-            table1_value_column: config.advice[11],
-            table2_key_column: config.advice[12],
-            table2_value_column: config.advice[13],
-            match_column: config.advice[14],
-            join_selector: config.less_than_selector, // Reuse selector
-            deduplication_selector: config.decomposition_selector, // Reuse selector
-            range_check_config: range_check_config.clone(),
-            sort_config: sort_config.clone(),
-        };
-        let join_chip = JoinChip::new(join_config);
-
-        // Create Aggregation config
-        let aggregation_config = AggregationConfig {
-            value_column: config.advice[8],
-            result_column: config.advice[9],
-            sum_selector: config.less_than_selector, // Reuse selector
-            count_selector: config.decomposition_selector, // Reuse selector
-            max_selector: config.range_check_selector, // Reuse selector
-            min_selector: config.diff_lookup_selector, // Reuse selector
-            group_by_config: group_by_config.clone(),
-            range_check_config: range_check_config.clone(),
-        };
-        let aggregation_chip = AggregationChip::new(aggregation_config);
+        // Scanned rows -> instance row 0, hashed in-circuit: the proof only
+        // verifies against the commitment of the rows it aggregated
+        let width = self.scan.columns.len();
+        if self.scan.rows.iter().any(|row| row.len() != width) {
+            return Err(Error::Synthesis);
+        }
+        let scanned: Vec<Fr> = self.scan.rows.iter().flatten().map(|&v| Fr::from(v)).collect();
+        let scan_cells =
+            poseidon_chip.assign_inputs(layouter.namespace(|| "table scan"), &scanned)?;
+        let scan_commitment =
+            poseidon_chip.hash(layouter.namespace(|| "table commitment"), &scan_cells)?;
+        layouter.constrain_instance(
+            scan_commitment.cell(),
+            config.instance,
+            INSTANCE_DB_COMMITMENT_ROW,
+        )?;
 
-        // Range Check operations
-        for range_check_op in &self.range_checks {
-            range_check_chip.check_less_than(
-                layouter.namespace(|| "range check"),
-                range_check_op.value,
-                range_check_op.threshold,
-                range_check_op.u,
+        // Query result -> instance row 1 (unless it stays private, see below)
+        // With aggregations the final result cell is copied there (see below),
+        // otherwise the result is not proven
+        if self.result_predicate.is_none() && self.aggregations.is_empty() {
+            config.bind_public_input(
+                &mut layouter,
+                self.query_result,
+                INSTANCE_QUERY_RESULT_ROW,
+            )?;
+        }
+
+        // Request nonce -> instance row 2 (replay protection)
+        config.bind_public_input(&mut layouter, self.nonce, INSTANCE_NONCE_ROW)?;
+
+        // Expiry bound -> instance row 3 (stale proof rejection)
+        config.bind_public_input(&mut layouter, self.expiry, INSTANCE_EXPIRY_ROW)?;
+
+        // Query hash -> instance row 4 (binds the proof to its query)
+        // A constant, so a key made for another query rejects the proof
+        if bind_query_hash {
+            config.bind_public_constant(&mut layouter, self.query_hash, INSTANCE_QUERY_HASH_ROW)?;
+        }
+
+        // Result commitment -> instance row 5 (binds the proof to its result rows)
+        // Hashed from the final aggregation result (see below) when it is
+        // the whole result, otherwise not proven
+        if !self.hashes_result() {
+            config.bind_public_input(
+                &mut layouter,
+                self.result_commitment,
+                INSTANCE_RESULT_COMMITMENT_ROW,
             )?;
         }
 
+        // Chips over the gates created in Circuit::configure
+        let range_check_chip = RangeCheckChip::new(operators.range_check);
+        let sort_chip = SortChip::new(operators.sort);
+        let group_by_chip = GroupByChip::new(operators.group_by);
+        let join_chip = JoinChip::new(operators.join);
+        let aggregation_chip = AggregationChip::new(operators.aggregation);
+
+        // Range Check operations
+        for (i, range_check_op) in self.range_checks.iter().enumerate() {
+            match self.threshold_mode {
+                ThresholdMode::Fixed => {
+                    range_check_chip.check_less_than(
+                        layouter.namespace(|| "range check"),
+                        range_check_op.value,
+                        range_check_op.threshold,
+                        range_check_op.u,
+                    )?;
+                }
+                ThresholdMode::Instance => {
+                    // Threshold -> instance row NUM_INSTANCE_ROWS + i
+                    let threshold = config.bind_public_input(
+                        &mut layouter,
+                        Value::known(Fr::from(range_check_op.threshold)),
+                        NUM_INSTANCE_ROWS + i,
+                    )?;
+                    range_check_chip.check_less_than_public(
+                        layouter.namespace(|| "range check"),
+                        range_check_op.value,
+                        &threshold,
+                    )?;
+                }
+            }
+        }
+
         // Sort operations
         for sort_op in &self.sorts {
             sort_chip.sort_and_verify(
@@ -258,12 +749,39 @@ impl Circuit<Fr> for PoneglyphCircuit {
 
         // Group-By operations
         for group_by_op in &self.group_bys {
-            group_by_chip
-                .group_and_verify(layouter.namespace(|| "group by"), &group_by_op.group_keys)?;
+            group_by_chip.group_composite_and_verify(
+                layouter.namespace(|| "group by"),
+                &group_by_op.group_keys,
+            )?;
         }
 
         // Join operations
         for join_op in &self.joins {
+            if join_op.kind != JoinKind::Inner {
+                let key_cells = match &join_op.table1_column {
+                    Some(name) => {
+                        let column = self.scan.column_index(name).ok_or(Error::Synthesis)?;
+                        if join_op.table1_keys.len() != self.scan.rows.len() {
+                            return Err(Error::Synthesis);
+                        }
+                        (0..self.scan.rows.len())
+                            .map(|row| scan_cells[row * width + column].clone())
+                            .collect()
+                    }
+                    None => join_chip.assign_keys(
+                        layouter.namespace(|| "semi join keys"),
+                        &join_op.table1_keys,
+                    )?,
+                };
+                join_chip.semi_join_and_verify(
+                    layouter.namespace(|| "semi join"),
+                    &join_op.table1_keys,
+                    &key_cells,
+                    &join_op.table2_keys,
+                    join_op.kind,
+                )?;
+                continue;
+            }
             join_chip.join_and_verify(
                 layouter.namespace(|| "join"),
                 &join_op.table1_keys,
@@ -274,15 +792,155 @@ impl Circuit<Fr> for PoneglyphCircuit {
         }
 
         // Aggregation operations
-        for agg_op in &self.aggregations {
-            aggregation_chip.aggregate_and_verify(
+        // Consecutive aggregations over the same groups (`SELECT SUM(a), MAX(b)
+        // ... GROUP BY k`) run in one pass, sharing the Group-By check
+        let batches = self.aggregations.chunk_by(|a, b| {
+            !a.agg_type.is_rank() && !b.agg_type.is_rank() && a.group_keys == b.group_keys
+        });
+        let mut final_result = None;
+        for batch in batches {
+            let agg_op = &batch[0];
+            if agg_op.agg_type.is_rank() {
+                let results = aggregation_chip.aggregate_rank_and_verify(
+                    layouter.namespace(|| "rank aggregation"),
+                    &sort_chip,
+                    &agg_op.group_keys,
+                    &agg_op.values,
+                    &agg_op.agg_type,
+                )?;
+                final_result = results.last().cloned();
+                continue;
+            }
+            // VARIANCE/STDDEV need the dispersion gates (Error::Synthesis here)
+            let measures: Vec<(&[u64], &AggregationType)> = batch
+                .iter()
+                .map(|op| (op.values.as_slice(), &op.agg_type))
+                .collect();
+            let cells = aggregation_chip.aggregate_many_with_values(
                 layouter.namespace(|| "aggregation"),
                 &agg_op.group_keys,
-                &agg_op.values,
-                &agg_op.agg_type,
+                &measures,
+            )?;
+
+            // The aggregated values are the scanned column, row by row
+            layouter.assign_region(
+                || "link aggregated columns",
+                |mut region| {
+                    for (op, (value_cells, _)) in batch.iter().zip(cells.iter()) {
+                        let Some(name) = &op.column else {
+                            continue;
+                        };
+                        let column = self.scan.column_index(name).ok_or(Error::Synthesis)?;
+                        if value_cells.len() != self.scan.rows.len() {
+                            return Err(Error::Synthesis);
+                        }
+                        for (row, value_cell) in value_cells.iter().enumerate() {
+                            let scan_cell = &scan_cells[row * width + column];
+                            region.constrain_equal(scan_cell.cell(), value_cell.cell())?;
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+            final_result = cells.last().and_then(|(_, results)| results.last().cloned());
+        }
+
+        // Selective disclosure: the final aggregation result is compared with
+        // the predicate bound and only the comparison bit goes to instance row 1
+        if let Some(predicate) = self.result_predicate {
+            let final_result = final_result.as_ref().ok_or(Error::Synthesis)?;
+            let (result, bound) = layouter.assign_region(
+                || "private query result",
+                |mut region| {
+                    let result = final_result.copy_advice(
+                        || "result",
+                        &mut region,
+                        config.advice[0],
+                        0,
+                    )?;
+                    let bound = match predicate {
+                        ResultPredicate::Below(t) | ResultPredicate::Above(t) => t,
+                    };
+                    let bound = region.assign_advice_from_constant(
+                        || "bound",
+                        config.advice[0],
+                        1,
+                        Fr::from(bound),
+                    )?;
+                    Ok((result, bound))
+                },
+            )?;
+            let outcome = match predicate {
+                ResultPredicate::Below(_) => range_check_chip.check_cell_less_than(
+                    layouter.namespace(|| "result predicate"),
+                    &result,
+                    &bound,
+                )?,
+                ResultPredicate::Above(_) => {
+                    // Only the x side of the comparison is range checked
+                    range_check_chip
+                        .decompose_cell(layouter.namespace(|| "result range"), &result)?;
+                    range_check_chip.check_cell_less_than(
+                        layouter.namespace(|| "result predicate"),
+                        &bound,
+                        &result,
+                    )?
+                }
+            };
+            layouter.constrain_instance(
+                outcome.cell(),
+                config.instance,
+                INSTANCE_QUERY_RESULT_ROW,
             )?;
         }
 
+        // Final aggregation result -> instance row 1
+        if self.result_predicate.is_none() && !self.aggregations.is_empty() {
+            let result = final_result.ok_or(Error::Synthesis)?;
+            layouter.constrain_instance(result.cell(), config.instance, INSTANCE_QUERY_RESULT_ROW)?;
+
+            // Its commitment -> instance row 5: `QueryResult::to_bytes` of the
+            // one-value result, with the value bytes decomposed from the cell
+            if self.hashes_result() {
+                let value_bytes = range_check_chip
+                    .decompose_cell(layouter.namespace(|| "result bytes"), &result)?;
+                let encoding = result_encoding(&self.result_columns, value_bytes);
+                let packed =
+                    poseidon_chip.pack_bytes(layouter.namespace(|| "result encoding"), &encoding)?;
+                let commitment =
+                    poseidon_chip.hash(layouter.namespace(|| "result commitment"), &packed)?;
+                layouter.constrain_instance(
+                    commitment.cell(),
+                    config.instance,
+                    INSTANCE_RESULT_COMMITMENT_ROW,
+                )?;
+            }
+        }
+
         Ok(())
     }
+
+    /// True if the result commitment is hashed in-circuit (see
+    /// `result_columns`)
+    pub fn hashes_result(&self) -> bool {
+        self.result_predicate.is_none()
+            && !self.aggregations.is_empty()
+            && self.result_columns.len() == 1
+    }
+}
+
+/// `QueryResult::to_bytes` of a one-row, one-value result over `columns`
+/// (header and non-NULL tag constant, then the value bytes)
+fn result_encoding(
+    columns: &[String],
+    value_bytes: [AssignedCell<Fr, Fr>; 8],
+) -> Vec<PackedByte<Fr>> {
+    let mut header = Vec::new();
+    crate::sql::executor::encode_result_header(columns, 1, &mut header);
+    header.push(1);
+    header
+        .into_iter()
+        .map(PackedByte::Constant)
+        .chain(value_bytes.into_iter().map(PackedByte::Cell))
+        .collect()
 }
@@ -0,0 +1,270 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+
+/// Encode a nullable key as u64 for group-by and NULL-safe join
+/// `None -> 0`, `Some(v) -> v + 1`, so NULL sorts first and equals only NULL
+///
+/// # Note
+///
+/// `u64::MAX` is reserved in nullable key columns (returns None)
+pub fn encode_nullable_key(key: Option<u64>) -> Option<u64> {
+    match key {
+        None => Some(0),
+        Some(v) => v.checked_add(1),
+    }
+}
+
+/// Decode a nullable key encoded with `encode_nullable_key`
+pub fn decode_nullable_key(encoded: u64) -> Option<u64> {
+    encoded.checked_sub(1)
+}
+
+/// Nullable value assigned in the circuit
+/// `value` is 0 whenever `valid` is 0 (canonical NULL)
+#[derive(Clone, Debug)]
+pub struct AssignedNullable<F: PrimeField = Fr> {
+    pub value: AssignedCell<F, F>,
+    pub valid: AssignedCell<F, F>,
+}
+
+/// Result of a predicate over nullable values (SQL three-valued logic)
+///
+/// - TRUE: `known = 1, value = 1`
+/// - FALSE: `known = 1, value = 0`
+/// - UNKNOWN: `known = 0, value = 0`
+///
+/// `value` alone is the WHERE semantics (UNKNOWN rows are filtered out), which
+/// is also correct under AND/OR; NOT must flip only known results.
+#[derive(Clone, Debug)]
+pub struct AssignedTruth<F: PrimeField = Fr> {
+    pub value: AssignedCell<F, F>,
+    pub known: AssignedCell<F, F>,
+}
+
+/// NULL Gate Configuration
+/// Validity bit per value and NULL-aware filter results
+///
+/// # Column Allocation
+///
+/// - `value_column`: Value, 0 for NULL (advice[10])
+/// - `valid_column`: Validity bit, 1 = not NULL (advice[11])
+/// - `check_column`: Comparison result on the raw value (advice[12])
+/// - `out_column`: Three-valued filter result (advice[13])
+///
+/// # Constraints
+///
+/// 1. **Validity**: `valid · (1 - valid) = 0` and `(1 - valid) · value = 0`
+/// 2. **Filter**: `out = valid · check` (a comparison with NULL is UNKNOWN,
+///    and UNKNOWN never passes a WHERE clause)
+///
+/// # Note
+///
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct NullConfig {
+    pub value_column: Column<Advice>,
+    pub valid_column: Column<Advice>,
+    pub check_column: Column<Advice>,
+    pub out_column: Column<Advice>,
+
+    // Selectors
+    pub validity_selector: Selector,
+    pub filter_selector: Selector,
+
+    // Range Check integration (comparisons on non-NULL values)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// NULL Chip
+/// Validity bit columns and three-valued logic
+pub struct NullChip<F: PrimeField = Fr> {
+    config: NullConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> NullChip<F> {
+    /// Create a new NullChip
+    pub fn new(config: NullConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the NULL Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> NullConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-13]: shared with Join Gate
+        let value_column = config.advice[10];
+        let valid_column = config.advice[11];
+        let check_column = config.advice[12];
+        let out_column = config.advice[13];
+
+        let validity_selector = meta.selector();
+        let filter_selector = meta.selector();
+
+        // Validity bit: boolean, and NULL values are canonical zero
+        meta.create_gate("null validity", |meta| {
+            let s = meta.query_selector(validity_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let valid = meta.query_advice(valid_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * valid.clone() * (one.clone() - valid.clone()),
+                s * (one - valid) * value,
+            ]
+        });
+
+        // Filter: out = valid · check
+        meta.create_gate("null filter", |meta| {
+            let s = meta.query_selector(filter_selector);
+            let valid = meta.query_advice(valid_column, Rotation::cur());
+            let check = meta.query_advice(check_column, Rotation::cur());
+            let out = meta.query_advice(out_column, Rotation::cur());
+
+            vec![s * (out - valid * check)]
+        });
+
+        NullConfig {
+            value_column,
+            valid_column,
+            check_column,
+            out_column,
+            validity_selector,
+            filter_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Assign nullable values with their validity bits
+    ///
+    /// # Return Value
+    ///
+    /// Value and validity cell for each row
+    pub fn assign_nullable(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Option<u64>],
+    ) -> Result<Vec<AssignedNullable<F>>, Error> {
+        layouter.assign_region(
+            || "nullable values",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        self.config.validity_selector.enable(&mut region, i)?;
+                        let value = region.assign_advice(
+                            || format!("value_{}", i),
+                            self.config.value_column,
+                            i,
+                            || Value::known(F::from(v.unwrap_or(0))),
+                        )?;
+                        let valid = region.assign_advice(
+                            || format!("valid_{}", i),
+                            self.config.valid_column,
+                            i,
+                            || Value::known(F::from(v.is_some() as u64)),
+                        )?;
+                        Ok(AssignedNullable { value, valid })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// NULL-aware x < t check
+    /// `NULL < t` is UNKNOWN (filtered out), otherwise the Range Check result
+    ///
+    /// # Return Value
+    ///
+    /// Three-valued result (see `AssignedTruth`)
+    pub fn filter_less_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Option<u64>,
+        threshold: u64,
+        u: u64,
+    ) -> Result<AssignedTruth<F>, Error> {
+        // Comparison on the raw value (0 for NULL), masked below
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        let check_cell = range_check_chip.check_less_than(
+            layouter.namespace(|| "raw comparison"),
+            Value::known(x.unwrap_or(0)),
+            threshold,
+            u,
+        )?;
+
+        layouter.assign_region(
+            || "null filter",
+            |mut region| {
+                self.config.validity_selector.enable(&mut region, 0)?;
+                self.config.filter_selector.enable(&mut region, 0)?;
+
+                region.assign_advice(
+                    || "value",
+                    self.config.value_column,
+                    0,
+                    || Value::known(F::from(x.unwrap_or(0))),
+                )?;
+                let known = region.assign_advice(
+                    || "valid",
+                    self.config.valid_column,
+                    0,
+                    || Value::known(F::from(x.is_some() as u64)),
+                )?;
+                check_cell.copy_advice(|| "check", &mut region, self.config.check_column, 0)?;
+
+                let passes = x.map(|v| v < threshold).unwrap_or(false);
+                let value = region.assign_advice(
+                    || "out",
+                    self.config.out_column,
+                    0,
+                    || Value::known(F::from(passes as u64)),
+                )?;
+
+                Ok(AssignedTruth { value, known })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nullable_key_encoding() {
+        assert_eq!(encode_nullable_key(None), Some(0));
+        assert_eq!(encode_nullable_key(Some(0)), Some(1));
+        assert_eq!(encode_nullable_key(Some(u64::MAX)), None);
+        assert_eq!(decode_nullable_key(0), None);
+        assert_eq!(decode_nullable_key(8), Some(7));
+    }
+
+    #[test]
+    fn test_nullable_keys_sort_null_first() {
+        let mut keys: Vec<u64> = [Some(3), None, Some(1), None]
+            .iter()
+            .map(|k| encode_nullable_key(*k).unwrap())
+            .collect();
+        keys.sort();
+        let decoded: Vec<Option<u64>> = keys.into_iter().map(decode_nullable_key).collect();
+        assert_eq!(decoded, vec![None, None, Some(1), Some(3)]);
+    }
+}
@@ -0,0 +1,457 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector, VirtualCells},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{field_to_u64, RangeCheckChip, RangeCheckConfig};
+use super::AggregationType;
+
+/// Mergeable summary of a set of values
+/// Shards (and incremental refreshes) aggregate their own rows; the summaries
+/// are merged into the aggregate of all rows. Merging is associative and
+/// commutative, and `PartialAggregate::default()` (no rows) is its identity,
+/// so any fold order gives the same result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PartialAggregate {
+    pub sum: u128,
+    pub count: u64,
+    /// `u64::MAX` when there are no rows
+    pub min: u64,
+    /// 0 when there are no rows
+    pub max: u64,
+    pub sum_of_squares: u128,
+}
+
+impl Default for PartialAggregate {
+    fn default() -> Self {
+        Self {
+            sum: 0,
+            count: 0,
+            min: u64::MAX,
+            max: 0,
+            sum_of_squares: 0,
+        }
+    }
+}
+
+impl PartialAggregate {
+    /// Summary of `values` (None if a sum overflows)
+    pub fn of(values: &[u64]) -> Option<Self> {
+        values.iter().try_fold(Self::default(), |acc, &value| {
+            acc.merge(&Self {
+                sum: value as u128,
+                count: 1,
+                min: value,
+                max: value,
+                sum_of_squares: value as u128 * value as u128,
+            })
+        })
+    }
+
+    /// Summary of the union of both row sets (None if a sum overflows)
+    pub fn merge(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            sum: self.sum.checked_add(other.sum)?,
+            count: self.count.checked_add(other.count)?,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            sum_of_squares: self.sum_of_squares.checked_add(other.sum_of_squares)?,
+        })
+    }
+
+    /// Refresh after inserting `values` (e.g. a materialized aggregate)
+    pub fn insert(&self, values: &[u64]) -> Option<Self> {
+        self.merge(&Self::of(values)?)
+    }
+
+    /// Final value of an aggregation over the summarized rows
+    /// None for empty MIN / MAX / dispersion, results over 64 bits and
+    /// aggregations that can't be merged (MEDIAN, PERCENTILE)
+    pub fn evaluate(&self, agg_type: &AggregationType) -> Option<u64> {
+        match agg_type {
+            AggregationType::Sum => u64::try_from(self.sum).ok(),
+            AggregationType::Count => Some(self.count),
+            AggregationType::Min => (self.count > 0).then_some(self.min),
+            AggregationType::Max => (self.count > 0).then_some(self.max),
+            AggregationType::Variance(_) | AggregationType::StdDev(_) => {
+                agg_type.dispersion_from_moments(self.count, self.sum, self.sum_of_squares)
+            }
+            AggregationType::Median | AggregationType::Percentile(_) => None,
+        }
+    }
+}
+
+/// Assigned cells of a `PartialAggregate`
+#[derive(Clone, Debug)]
+pub struct AssignedPartialAggregate<F: PrimeField = Fr> {
+    pub sum: AssignedCell<F, F>,
+    pub count: AssignedCell<F, F>,
+    pub min: AssignedCell<F, F>,
+    pub max: AssignedCell<F, F>,
+    pub sum_of_squares: AssignedCell<F, F>,
+}
+
+impl<F: PrimeField> AssignedPartialAggregate<F> {
+    fn cells(&self) -> [&AssignedCell<F, F>; 5] {
+        [
+            &self.sum,
+            &self.count,
+            &self.min,
+            &self.max,
+            &self.sum_of_squares,
+        ]
+    }
+
+    fn from_cells(cells: [AssignedCell<F, F>; 5]) -> Self {
+        let [sum, count, min, max, sum_of_squares] = cells;
+        Self {
+            sum,
+            count,
+            min,
+            max,
+            sum_of_squares,
+        }
+    }
+}
+
+/// Partial Aggregate Merge Gate Configuration
+/// Proves that a summary is the merge of two summaries
+///
+/// # Column Allocation
+///
+/// Columns `sum`, `count`, `min`, `max`, `sum_of_squares` (advice[10-14]),
+/// four rows per merge:
+/// - row 0: left summary (copied), row 1: right summary (copied)
+/// - row 2: merged summary
+/// - row 3: `min_l - min`, `min_r - min`, `max - max_l`, `max - max_r`
+///   (in the first four columns)
+///
+/// and one row per slot (the summary of one value or of no value)
+///
+/// # Constraints
+///
+/// 1. **Additive**: `sum = sum_l + sum_r`, `count = count_l + count_r`,
+///    `sum_of_squares = sum_of_squares_l + sum_of_squares_r`
+/// 2. **Selection**: `(min - min_l) · (min - min_r) = 0`, same for max
+/// 3. **Order**: the row 3 differences are defined as above and each is
+///    decomposed into 8-bit chunks, so `min ≤ min_l, min_r` and
+///    `max ≥ max_l, max_r`
+/// 4. **Slot**: `count ∈ {0, 1}`, `(1 - count) · sum = 0`, `max = sum`,
+///    `sum_of_squares = sum²`, `min = sum` if `count = 1` and `u64::MAX`
+///    otherwise; `sum` is decomposed, so a slot value is 64-bit
+///
+/// # Note
+///
+/// - Sums are field elements: they don't wrap as long as they stay below
+///   the field modulus (always true for fewer than 2^126 rows)
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct PartialAggregateConfig {
+    /// sum, count, min, max, sum_of_squares
+    pub columns: [Column<Advice>; 5],
+    pub merge_selector: Selector,
+    pub slot_selector: Selector,
+
+    // Range Check integration (order differences ≥ 0)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Partial Aggregate Chip
+/// Merges shard / refresh summaries in-circuit
+pub struct PartialAggregateChip<F: PrimeField = Fr> {
+    config: PartialAggregateConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> PartialAggregateChip<F> {
+    /// Create a new PartialAggregateChip
+    pub fn new(config: PartialAggregateConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Partial Aggregate Merge Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> PartialAggregateConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        let columns = [
+            config.advice[10],
+            config.advice[11],
+            config.advice[12],
+            config.advice[13],
+            config.advice[14],
+        ];
+        let merge_selector = meta.selector();
+        let slot_selector = meta.selector();
+
+        meta.create_gate("partial aggregate merge", |meta| {
+            let s = meta.query_selector(merge_selector);
+            let q = |meta: &mut VirtualCells<F>, i: usize, row: i32| {
+                meta.query_advice(columns[i], Rotation(row))
+            };
+            let [sum, count, min, max, sum_sq] = [0, 1, 2, 3, 4];
+
+            let additive = [sum, count, sum_sq]
+                .map(|i| s.clone() * (q(meta, i, 2) - q(meta, i, 0) - q(meta, i, 1)));
+            let selection = [min, max].map(|i| {
+                s.clone() * (q(meta, i, 2) - q(meta, i, 0)) * (q(meta, i, 2) - q(meta, i, 1))
+            });
+            let order = [
+                q(meta, 0, 3) - (q(meta, min, 0) - q(meta, min, 2)),
+                q(meta, 1, 3) - (q(meta, min, 1) - q(meta, min, 2)),
+                q(meta, 2, 3) - (q(meta, max, 2) - q(meta, max, 0)),
+                q(meta, 3, 3) - (q(meta, max, 2) - q(meta, max, 1)),
+            ]
+            .map(|diff| s.clone() * diff);
+
+            additive
+                .into_iter()
+                .chain(selection)
+                .chain(order)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("partial aggregate slot", |meta| {
+            let s = meta.query_selector(slot_selector);
+            let [sum, count, min, max, sum_sq] =
+                columns.map(|column| meta.query_advice(column, Rotation::cur()));
+            let one = Expression::Constant(F::ONE);
+            let empty_min = Expression::Constant(F::from(u64::MAX));
+
+            vec![
+                s.clone() * count.clone() * (one.clone() - count.clone()),
+                s.clone() * (one.clone() - count.clone()) * sum.clone(),
+                s.clone() * (min - count.clone() * sum.clone() - (one - count) * empty_min),
+                s.clone() * (max - sum.clone()),
+                s * (sum_sq - sum.clone() * sum),
+            ]
+        });
+
+        PartialAggregateConfig {
+            columns,
+            merge_selector,
+            slot_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Assign a summary (for summaries not produced by another chip)
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        partial: &PartialAggregate,
+    ) -> Result<AssignedPartialAggregate<F>, Error> {
+        let values = [
+            F::from_u128(partial.sum),
+            F::from(partial.count),
+            F::from(partial.min),
+            F::from(partial.max),
+            F::from_u128(partial.sum_of_squares),
+        ];
+        let cells = layouter.assign_region(
+            || "partial aggregate",
+            |mut region| {
+                self.config
+                    .columns
+                    .iter()
+                    .zip(values)
+                    .map(|(&column, value)| {
+                        region.assign_advice(|| "partial", column, 0, || Value::known(value))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+        Ok(AssignedPartialAggregate::from_cells(
+            cells.try_into().unwrap(),
+        ))
+    }
+
+    /// Summary of one slot: `Some(value)` is a row, `None` is padding (the
+    /// empty summary), so fixed-size circuits can summarize fewer rows
+    pub fn slot(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Option<u64>>,
+    ) -> Result<AssignedPartialAggregate<F>, Error> {
+        let partial = value.map(|v| PartialAggregate::of(v.as_slice()).unwrap());
+        let cells = layouter.assign_region(
+            || "partial aggregate slot",
+            |mut region| {
+                self.config.slot_selector.enable(&mut region, 0)?;
+                let values = [
+                    partial.map(|p| F::from_u128(p.sum)),
+                    partial.map(|p| F::from(p.count)),
+                    partial.map(|p| F::from(p.min)),
+                    partial.map(|p| F::from(p.max)),
+                    partial.map(|p| F::from_u128(p.sum_of_squares)),
+                ];
+                self.config
+                    .columns
+                    .iter()
+                    .zip(values)
+                    .map(|(&column, v)| region.assign_advice(|| "slot", column, 0, || v))
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        range_check_chip.decompose_cell(layouter.namespace(|| "slot value"), &cells[0])?;
+
+        Ok(AssignedPartialAggregate::from_cells(
+            cells.try_into().unwrap(),
+        ))
+    }
+
+    /// Merge two summaries
+    pub fn merge(
+        &self,
+        mut layouter: impl Layouter<F>,
+        left: &AssignedPartialAggregate<F>,
+        right: &AssignedPartialAggregate<F>,
+    ) -> Result<AssignedPartialAggregate<F>, Error> {
+        let (merged, diffs) = layouter.assign_region(
+            || "partial aggregate merge",
+            |mut region| {
+                self.config.merge_selector.enable(&mut region, 0)?;
+                for (row, partial) in [left, right].into_iter().enumerate() {
+                    for (cell, &column) in partial.cells().into_iter().zip(&self.config.columns) {
+                        cell.copy_advice(|| "input", &mut region, column, row)?;
+                    }
+                }
+
+                let value = |cell: &AssignedCell<F, F>| cell.value().copied();
+                let as_u64 = |cell: &AssignedCell<F, F>| cell.value().map(field_to_u64);
+                let (min_l, min_r) = (as_u64(&left.min), as_u64(&right.min));
+                let (max_l, max_r) = (as_u64(&left.max), as_u64(&right.max));
+                let min = min_l.zip(min_r).map(|(l, r)| l.min(r));
+                let max = max_l.zip(max_r).map(|(l, r)| l.max(r));
+
+                let merged_values = [
+                    value(&left.sum) + value(&right.sum),
+                    value(&left.count) + value(&right.count),
+                    min.map(F::from),
+                    max.map(F::from),
+                    value(&left.sum_of_squares) + value(&right.sum_of_squares),
+                ];
+                let merged = self
+                    .config
+                    .columns
+                    .iter()
+                    .zip(merged_values)
+                    .map(|(&column, v)| region.assign_advice(|| "merged", column, 2, || v))
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let diff_values = [
+                    min_l.zip(min).map(|(l, m)| l - m),
+                    min_r.zip(min).map(|(r, m)| r - m),
+                    max.zip(max_l).map(|(m, l)| m - l),
+                    max.zip(max_r).map(|(m, r)| m - r),
+                ];
+                let diffs = self
+                    .config
+                    .columns
+                    .iter()
+                    .zip(diff_values)
+                    .map(|(&column, d)| {
+                        region.assign_advice(|| "order diff", column, 3, || d.map(F::from))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                Ok((merged, diffs))
+            },
+        )?;
+
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for diff in &diffs {
+            range_check_chip.decompose_cell(layouter.namespace(|| "order diff"), diff)?;
+        }
+
+        Ok(AssignedPartialAggregate::from_cells(
+            merged.try_into().unwrap(),
+        ))
+    }
+
+    /// Merge summaries left to right (None if `partials` is empty)
+    pub fn merge_all(
+        &self,
+        mut layouter: impl Layouter<F>,
+        partials: &[AssignedPartialAggregate<F>],
+    ) -> Result<Option<AssignedPartialAggregate<F>>, Error> {
+        let mut merged: Option<AssignedPartialAggregate<F>> = None;
+        for (i, partial) in partials.iter().enumerate() {
+            merged = Some(match merged {
+                Some(acc) => {
+                    self.merge(layouter.namespace(|| format!("merge {}", i)), &acc, partial)?
+                }
+                None => partial.clone(),
+            });
+        }
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_matches_whole() {
+        let values = [5u64, 1, 9, 3, 3, 7];
+        let whole = PartialAggregate::of(&values).unwrap();
+        for split in 0..=values.len() {
+            let (left, right) = values.split_at(split);
+            let merged = PartialAggregate::of(left)
+                .unwrap()
+                .merge(&PartialAggregate::of(right).unwrap())
+                .unwrap();
+            assert_eq!(merged, whole);
+        }
+        assert_eq!(whole.merge(&PartialAggregate::default()), Some(whole));
+        assert_eq!(
+            PartialAggregate::of(&values[..2])
+                .unwrap()
+                .insert(&values[2..]),
+            Some(whole)
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let values = [5u64, 1, 9, 3];
+        let partial = PartialAggregate::of(&values).unwrap();
+        assert_eq!(partial.evaluate(&AggregationType::Sum), Some(18));
+        assert_eq!(partial.evaluate(&AggregationType::Count), Some(4));
+        assert_eq!(partial.evaluate(&AggregationType::Min), Some(1));
+        assert_eq!(partial.evaluate(&AggregationType::Max), Some(9));
+        for agg in [AggregationType::Variance(2), AggregationType::StdDev(1)] {
+            assert_eq!(partial.evaluate(&agg), agg.dispersion(&values));
+        }
+        assert_eq!(partial.evaluate(&AggregationType::Median), None);
+
+        let empty = PartialAggregate::default();
+        assert_eq!(empty.evaluate(&AggregationType::Count), Some(0));
+        assert_eq!(empty.evaluate(&AggregationType::Min), None);
+        assert_eq!(empty.evaluate(&AggregationType::Variance(0)), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = PartialAggregate {
+            sum: u128::MAX,
+            ..PartialAggregate::of(&[1]).unwrap()
+        };
+        assert_eq!(big.merge(&big), None);
+    }
+}
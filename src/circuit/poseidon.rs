@@ -0,0 +1,683 @@
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use crate::utils::PACK_CHUNK_BYTES;
+
+/// State width (rate 2, capacity 1)
+pub const POSEIDON_WIDTH: usize = 3;
+
+/// Elements absorbed per permutation
+pub const POSEIDON_RATE: usize = 2;
+
+/// Full rounds (half before, half after the partial rounds)
+pub const POSEIDON_FULL_ROUNDS: usize = 8;
+
+/// Partial rounds
+pub const POSEIDON_PARTIAL_ROUNDS: usize = 56;
+
+const ROUNDS: usize = POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS;
+
+/// Rows `PoseidonChip::hash` takes for `inputs` cells (one permutation region
+/// of absorb, round and output rows per absorbed pair)
+pub fn hash_rows(inputs: usize) -> usize {
+    inputs.div_ceil(POSEIDON_RATE).max(1) * (ROUNDS + 2)
+}
+
+/// Rows `PoseidonChip::pack_bytes` takes for `len` bytes, `cells` of them
+/// assigned (the length, one row per chunk and per assigned byte)
+pub fn pack_rows(len: usize, cells: usize) -> usize {
+    1 + len.div_ceil(PACK_CHUNK_BYTES) + cells
+}
+
+/// Byte of a `PoseidonChip::pack_bytes` input
+#[derive(Clone, Debug)]
+pub enum PackedByte<F: PrimeField> {
+    Constant(u8),
+    /// Assigned cell, already range checked to 8 bits (e.g. a chunk of
+    /// `RangeCheckChip::decompose_cell`)
+    Cell(AssignedCell<F, F>),
+}
+
+/// Poseidon parameters over `F`
+/// x^5 S-box, width 3, 8 full and 56 partial rounds (128-bit security for
+/// the ~255-bit pasta fields)
+///
+/// # Constants
+///
+/// - Round constants: Grain LFSR of the Poseidon reference implementation
+///   (prime field, x^α S-box, n = `F::NUM_BITS`, t = 3, R_F = 8, R_P = 56),
+///   rejection-sampled into `F`
+/// - MDS matrix: Cauchy matrix `M[i][j] = 1 / (i + t + j)`
+///
+/// Native hashing (`utils::poseidon_hash`) and `PoseidonChip` use the same
+/// parameters, so their outputs are identical.
+#[derive(Clone, Debug)]
+pub struct PoseidonParams<F: PrimeField> {
+    pub round_constants: Vec<[F; POSEIDON_WIDTH]>,
+    pub mds: [[F; POSEIDON_WIDTH]; POSEIDON_WIDTH],
+}
+
+impl<F: PrimeField> PoseidonParams<F> {
+    /// Generate the parameters for `F`
+    pub fn generate() -> Self {
+        let mut grain = Grain::new(F::NUM_BITS as usize);
+        let round_constants = (0..ROUNDS)
+            .map(|_| [(); POSEIDON_WIDTH].map(|_| grain.next_field_element()))
+            .collect();
+
+        let mut mds = [[F::ZERO; POSEIDON_WIDTH]; POSEIDON_WIDTH];
+        for (i, row) in mds.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = F::from((i + POSEIDON_WIDTH + j) as u64).invert().unwrap();
+            }
+        }
+
+        Self {
+            round_constants,
+            mds,
+        }
+    }
+
+    fn is_full_round(round: usize) -> bool {
+        let half = POSEIDON_FULL_ROUNDS / 2;
+        round < half || round >= half + POSEIDON_PARTIAL_ROUNDS
+    }
+
+    /// One round: add constants, S-box (all elements or the first), MDS
+    fn round(&self, round: usize, state: &[F; POSEIDON_WIDTH]) -> [F; POSEIDON_WIDTH] {
+        let mut sboxed = [F::ZERO; POSEIDON_WIDTH];
+        for i in 0..POSEIDON_WIDTH {
+            let x = state[i] + self.round_constants[round][i];
+            sboxed[i] = if i == 0 || Self::is_full_round(round) {
+                x.square().square() * x
+            } else {
+                x
+            };
+        }
+        let mut out = [F::ZERO; POSEIDON_WIDTH];
+        for (i, out) in out.iter_mut().enumerate() {
+            for (j, x) in sboxed.iter().enumerate() {
+                *out += self.mds[i][j] * x;
+            }
+        }
+        out
+    }
+
+    /// Poseidon permutation
+    pub fn permute(&self, state: &mut [F; POSEIDON_WIDTH]) {
+        for round in 0..ROUNDS {
+            *state = self.round(round, state);
+        }
+    }
+
+    /// Initial sponge state: the input length is the capacity element
+    /// (domain separation between lengths, so zero padding is unambiguous)
+    fn initial_state(len: usize) -> [F; POSEIDON_WIDTH] {
+        let mut state = [F::ZERO; POSEIDON_WIDTH];
+        state[POSEIDON_RATE] = F::from_u128((len as u128) << 64);
+        state
+    }
+
+    /// Sponge hash of a fixed-length input
+    /// Inputs are absorbed two at a time (the last pair zero-padded, at least
+    /// one permutation); the output is the first state element.
+    pub fn hash(&self, inputs: &[F]) -> F {
+        let mut state = Self::initial_state(inputs.len());
+        for chunk in Self::chunks(inputs) {
+            for (s, m) in state.iter_mut().zip(chunk) {
+                *s += m;
+            }
+            self.permute(&mut state);
+        }
+        state[0]
+    }
+
+    /// Rate-sized, zero-padded input chunks (one chunk for an empty input)
+    fn chunks(inputs: &[F]) -> Vec<[F; POSEIDON_RATE]> {
+        let mut chunks: Vec<[F; POSEIDON_RATE]> = inputs
+            .chunks(POSEIDON_RATE)
+            .map(|chunk| {
+                let mut padded = [F::ZERO; POSEIDON_RATE];
+                padded[..chunk.len()].copy_from_slice(chunk);
+                padded
+            })
+            .collect();
+        if chunks.is_empty() {
+            chunks.push([F::ZERO; POSEIDON_RATE]);
+        }
+        chunks
+    }
+}
+
+/// `PoseidonParams::hash` of an input that arrives in pieces
+/// The input length seeds the capacity element, so it is fixed up front;
+/// `finish` gives the same output as `hash` once that many elements are in.
+#[derive(Clone, Debug)]
+pub struct PoseidonSponge<'a, F: PrimeField> {
+    params: &'a PoseidonParams<F>,
+    state: [F; POSEIDON_WIDTH],
+    /// Absorbed elements not yet permuted (fewer than `POSEIDON_RATE`)
+    pending: Vec<F>,
+    permuted: bool,
+}
+
+impl<'a, F: PrimeField> PoseidonSponge<'a, F> {
+    /// Sponge for an input of `len` elements
+    pub fn new(params: &'a PoseidonParams<F>, len: usize) -> Self {
+        Self {
+            params,
+            state: PoseidonParams::<F>::initial_state(len),
+            pending: Vec::with_capacity(POSEIDON_RATE),
+            permuted: false,
+        }
+    }
+
+    /// Absorb the next element
+    pub fn absorb(&mut self, input: F) {
+        self.pending.push(input);
+        if self.pending.len() == POSEIDON_RATE {
+            self.permute_pending();
+        }
+    }
+
+    /// Output: the first state element after the last (zero-padded) chunk
+    pub fn finish(mut self) -> F {
+        if !self.pending.is_empty() || !self.permuted {
+            self.permute_pending();
+        }
+        self.state[0]
+    }
+
+    fn permute_pending(&mut self) {
+        for (s, m) in self.state.iter_mut().zip(self.pending.drain(..)) {
+            *s += m;
+        }
+        self.params.permute(&mut self.state);
+        self.permuted = true;
+    }
+}
+
+/// Parameters for the pasta base field, generated once per process
+pub fn pallas_poseidon_params() -> &'static PoseidonParams<Fr> {
+    static PARAMS: OnceLock<PoseidonParams<Fr>> = OnceLock::new();
+    PARAMS.get_or_init(PoseidonParams::generate)
+}
+
+/// Grain LFSR of the Poseidon reference parameter generation
+struct Grain {
+    state: VecDeque<bool>,
+    field_bits: usize,
+}
+
+impl Grain {
+    fn new(field_bits: usize) -> Self {
+        let mut state = VecDeque::with_capacity(80);
+        let mut push = |value: usize, bits: usize| {
+            for i in (0..bits).rev() {
+                state.push_back((value >> i) & 1 == 1);
+            }
+        };
+        push(1, 2); // prime field
+        push(0, 4); // x^α S-box
+        push(field_bits, 12);
+        push(POSEIDON_WIDTH, 12);
+        push(POSEIDON_FULL_ROUNDS, 10);
+        push(POSEIDON_PARTIAL_ROUNDS, 10);
+        push((1 << 30) - 1, 30);
+
+        let mut grain = Self { state, field_bits };
+        for _ in 0..160 {
+            grain.clock();
+        }
+        grain
+    }
+
+    fn clock(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.pop_front();
+        self.state.push_back(bit);
+        bit
+    }
+
+    /// Output bit: of each pair, the second bit is kept if the first is 1
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.clock();
+            let bit = self.clock();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// `field_bits` bits (most significant first), resampled until < p
+    fn next_field_element<F: PrimeField>(&mut self) -> F {
+        loop {
+            let mut repr = F::Repr::default();
+            for i in 0..self.field_bits {
+                let weight = self.field_bits - 1 - i;
+                if self.next_bit() {
+                    repr.as_mut()[weight / 8] |= 1 << (weight % 8);
+                }
+            }
+            if let Some(value) = Option::from(F::from_repr(repr)) {
+                return value;
+            }
+        }
+    }
+}
+
+/// Poseidon Gate Configuration
+/// Poseidon sponge over assigned cells, one permutation round per row
+///
+/// # Column Allocation
+///
+/// - `state`: Sponge state (advice[10-12])
+/// - `input`: Absorbed pair (advice[13-14])
+/// - `round_constants`: Round constants (3 fixed columns of this chip)
+///
+/// # Constraints
+///
+/// 1. **Absorb**: `s'[0] = s[0] + m[0]`, `s'[1] = s[1] + m[1]`, `s'[2] = s[2]`
+/// 2. **Full round**: `s'[i] = Σ_j M[i][j] · (s[j] + c[j])^5`
+/// 3. **Partial round**: `s'[i] = M[i][0] · (s[0] + c[0])^5 + Σ_{j>0} M[i][j] · (s[j] + c[j])`
+/// 4. **Pack**: `s'[0] = s[0] + c[0] · m[0]` (one byte m[0] of weight c[0]
+///    added to a `pack_bytes` chunk)
+///
+/// Each permutation region is one absorb row, 64 round rows and the output
+/// row; consecutive permutations are linked by copy constraints. The initial
+/// capacity element (input length) is a constant.
+///
+/// # Note
+///
+/// - Degree 6 (x^5 S-box under a selector)
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct PoseidonConfig {
+    pub state: [Column<Advice>; POSEIDON_WIDTH],
+    pub input: [Column<Advice>; POSEIDON_RATE],
+    pub round_constants: [Column<Fixed>; POSEIDON_WIDTH],
+
+    // Selectors
+    pub absorb_selector: Selector,
+    pub full_round_selector: Selector,
+    pub partial_round_selector: Selector,
+    pub pack_selector: Selector,
+}
+
+/// Poseidon Chip
+/// In-circuit Poseidon hash matching `utils::poseidon_hash`
+pub struct PoseidonChip<F: PrimeField = Fr> {
+    config: PoseidonConfig,
+    params: PoseidonParams<F>,
+}
+
+impl<F: PrimeField> PoseidonChip<F> {
+    /// Create a new PoseidonChip
+    pub fn new(config: PoseidonConfig) -> Self {
+        Self {
+            config,
+            params: PoseidonParams::generate(),
+        }
+    }
+
+    /// Configure the Poseidon Gate
+    pub fn configure(meta: &mut ConstraintSystem<F>, config: &PoneglyphConfig) -> PoseidonConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        let state = [config.advice[10], config.advice[11], config.advice[12]];
+        let input = [config.advice[13], config.advice[14]];
+        let round_constants = [
+            meta.fixed_column(),
+            meta.fixed_column(),
+            meta.fixed_column(),
+        ];
+
+        let absorb_selector = meta.selector();
+        let full_round_selector = meta.selector();
+        let partial_round_selector = meta.selector();
+        let pack_selector = meta.selector();
+
+        // The MDS matrix is fixed, so it is baked into the gates
+        let mds = PoseidonParams::<F>::generate().mds;
+        let pow5 = |x: Expression<F>| x.clone() * x.clone() * x.clone() * x.clone() * x;
+
+        meta.create_gate("poseidon absorb", |meta| {
+            let s = meta.query_selector(absorb_selector);
+            (0..POSEIDON_WIDTH)
+                .map(|i| {
+                    let cur = meta.query_advice(state[i], Rotation::cur());
+                    let next = meta.query_advice(state[i], Rotation::next());
+                    let absorbed = if i < POSEIDON_RATE {
+                        cur + meta.query_advice(input[i], Rotation::cur())
+                    } else {
+                        cur
+                    };
+                    s.clone() * (next - absorbed)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("poseidon pack", |meta| {
+            let s = meta.query_selector(pack_selector);
+            let chunk = meta.query_advice(state[0], Rotation::cur());
+            let next = meta.query_advice(state[0], Rotation::next());
+            let byte = meta.query_advice(input[0], Rotation::cur());
+            let weight = meta.query_fixed(round_constants[0]);
+            vec![s * (next - (chunk + weight * byte))]
+        });
+
+        for (name, selector, full) in [
+            ("poseidon full round", full_round_selector, true),
+            ("poseidon partial round", partial_round_selector, false),
+        ] {
+            meta.create_gate(name, |meta| {
+                let s = meta.query_selector(selector);
+                let sboxed: Vec<Expression<F>> = (0..POSEIDON_WIDTH)
+                    .map(|j| {
+                        let x = meta.query_advice(state[j], Rotation::cur())
+                            + meta.query_fixed(round_constants[j]);
+                        if full || j == 0 {
+                            pow5(x)
+                        } else {
+                            x
+                        }
+                    })
+                    .collect();
+                (0..POSEIDON_WIDTH)
+                    .map(|i| {
+                        let next = meta.query_advice(state[i], Rotation::next());
+                        let mixed = sboxed
+                            .iter()
+                            .zip(mds[i].iter())
+                            .fold(Expression::Constant(F::ZERO), |acc, (x, m)| {
+                                acc + x.clone() * Expression::Constant(*m)
+                            });
+                        s.clone() * (next - mixed)
+                    })
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        PoseidonConfig {
+            state,
+            input,
+            round_constants,
+            absorb_selector,
+            full_round_selector,
+            partial_round_selector,
+            pack_selector,
+        }
+    }
+
+    /// Assign values to hash (for inputs not produced by another chip)
+    pub fn assign_inputs(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[F],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "poseidon inputs",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &value)| {
+                        region.assign_advice(
+                            || format!("input_{}", i),
+                            self.config.state[0],
+                            i,
+                            || Value::known(value),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Field elements of `utils::pack_bytes` over constant and assigned bytes
+    /// Each chunk starts from its constant bytes and adds the assigned ones,
+    /// so `hash` of the result equals `utils::poseidon_hash_bytes` of the
+    /// byte values.
+    pub fn pack_bytes(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bytes: &[PackedByte<F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let byte_weight = F::from(256);
+        layouter.assign_region(
+            || "poseidon packed bytes",
+            |mut region| {
+                let mut packed = Vec::with_capacity(1 + bytes.len().div_ceil(PACK_CHUNK_BYTES));
+                packed.push(region.assign_advice_from_constant(
+                    || "length",
+                    self.config.state[0],
+                    0,
+                    F::from(bytes.len() as u64),
+                )?);
+
+                let mut row = 1;
+                for (i, chunk) in bytes.chunks(PACK_CHUNK_BYTES).enumerate() {
+                    // Little-endian: byte j has weight 256^j
+                    let mut weights = Vec::with_capacity(chunk.len());
+                    let mut weight = F::ONE;
+                    for _ in chunk {
+                        weights.push(weight);
+                        weight *= byte_weight;
+                    }
+                    let constant = chunk
+                        .iter()
+                        .zip(&weights)
+                        .filter_map(|(byte, &weight)| match byte {
+                            PackedByte::Constant(b) => Some(F::from(u64::from(*b)) * weight),
+                            PackedByte::Cell(_) => None,
+                        })
+                        .fold(F::ZERO, |acc, term| acc + term);
+
+                    let mut acc = region.assign_advice_from_constant(
+                        || format!("chunk_{} constant bytes", i),
+                        self.config.state[0],
+                        row,
+                        constant,
+                    )?;
+                    for (j, (byte, &weight)) in chunk.iter().zip(&weights).enumerate() {
+                        let PackedByte::Cell(cell) = byte else {
+                            continue;
+                        };
+                        self.config.pack_selector.enable(&mut region, row)?;
+                        cell.copy_advice(
+                            || format!("chunk_{} byte_{}", i, j),
+                            &mut region,
+                            self.config.input[0],
+                            row,
+                        )?;
+                        region.assign_fixed(
+                            || format!("chunk_{} weight_{}", i, j),
+                            self.config.round_constants[0],
+                            row,
+                            || Value::known(weight),
+                        )?;
+                        let next = acc
+                            .value()
+                            .zip(cell.value())
+                            .map(|(&acc, &byte)| acc + weight * byte);
+                        row += 1;
+                        acc = region.assign_advice(
+                            || format!("chunk_{}", i),
+                            self.config.state[0],
+                            row,
+                            || next,
+                        )?;
+                    }
+                    packed.push(acc);
+                    row += 1;
+                }
+                Ok(packed)
+            },
+        )
+    }
+
+    /// Poseidon hash of assigned cells
+    /// The result equals `PoseidonParams::hash` of the cell values
+    pub fn hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let half = POSEIDON_FULL_ROUNDS / 2;
+        let mut native = Value::known(PoseidonParams::<F>::initial_state(inputs.len()));
+        let mut previous: Option<Vec<AssignedCell<F, F>>> = None;
+
+        let num_chunks = inputs.len().div_ceil(POSEIDON_RATE).max(1);
+        for chunk in 0..num_chunks {
+            let start = chunk * POSEIDON_RATE;
+            let cells = &inputs[start.min(inputs.len())..(start + POSEIDON_RATE).min(inputs.len())];
+
+            let output = layouter.assign_region(
+                || format!("poseidon permutation {}", chunk),
+                |mut region| {
+                    // Row 0: state and absorbed pair
+                    let mut state = Vec::with_capacity(POSEIDON_WIDTH);
+                    for i in 0..POSEIDON_WIDTH {
+                        let cell = match &previous {
+                            Some(prev) => prev[i].copy_advice(
+                                || format!("state_{}", i),
+                                &mut region,
+                                self.config.state[i],
+                                0,
+                            )?,
+                            None => region.assign_advice_from_constant(
+                                || format!("initial state_{}", i),
+                                self.config.state[i],
+                                0,
+                                PoseidonParams::<F>::initial_state(inputs.len())[i],
+                            )?,
+                        };
+                        state.push(cell);
+                    }
+                    let mut absorbed = Vec::with_capacity(POSEIDON_RATE);
+                    for i in 0..POSEIDON_RATE {
+                        let cell = match cells.get(i) {
+                            Some(cell) => cell.copy_advice(
+                                || format!("input_{}", i),
+                                &mut region,
+                                self.config.input[i],
+                                0,
+                            )?,
+                            // Zero padding
+                            None => region.assign_advice_from_constant(
+                                || format!("padding_{}", i),
+                                self.config.input[i],
+                                0,
+                                F::ZERO,
+                            )?,
+                        };
+                        absorbed.push(cell);
+                    }
+                    self.config.absorb_selector.enable(&mut region, 0)?;
+
+                    let mut current = native;
+                    for (i, cell) in absorbed.iter().enumerate() {
+                        current = current.zip(cell.value().copied()).map(|(mut s, m)| {
+                            s[i] += m;
+                            s
+                        });
+                    }
+
+                    // Rows 1..=64: rounds, row 65: output
+                    let mut row_cells = Vec::new();
+                    for round in 0..=ROUNDS {
+                        let row = round + 1;
+                        row_cells = (0..POSEIDON_WIDTH)
+                            .map(|i| {
+                                region.assign_advice(
+                                    || format!("round {} state_{}", round, i),
+                                    self.config.state[i],
+                                    row,
+                                    || current.map(|s| s[i]),
+                                )
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        if round == ROUNDS {
+                            break;
+                        }
+
+                        for i in 0..POSEIDON_WIDTH {
+                            region.assign_fixed(
+                                || format!("round {} constant_{}", round, i),
+                                self.config.round_constants[i],
+                                row,
+                                || Value::known(self.params.round_constants[round][i]),
+                            )?;
+                        }
+                        if round < half || round >= half + POSEIDON_PARTIAL_ROUNDS {
+                            self.config.full_round_selector.enable(&mut region, row)?;
+                        } else {
+                            self.config
+                                .partial_round_selector
+                                .enable(&mut region, row)?;
+                        }
+                        current = current.map(|s| self.params.round(round, &s));
+                    }
+                    Ok((row_cells, current))
+                },
+            )?;
+
+            native = output.1;
+            previous = Some(output.0);
+        }
+
+        Ok(previous.expect("at least one permutation").swap_remove(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+
+    #[test]
+    fn test_sponge_matches_hash() {
+        let params = pallas_poseidon_params();
+        for len in 0..6 {
+            let inputs: Vec<Fr> = (0..len).map(|i| Fr::from(i as u64 + 7)).collect();
+            let mut sponge = PoseidonSponge::new(params, len);
+            for &input in &inputs {
+                sponge.absorb(input);
+            }
+            assert_eq!(sponge.finish(), params.hash(&inputs), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn test_params_are_deterministic() {
+        let a = PoseidonParams::<Fr>::generate();
+        let b = pallas_poseidon_params();
+        assert_eq!(a.round_constants, b.round_constants);
+        assert_eq!(a.round_constants.len(), ROUNDS);
+        assert_eq!(a.mds, b.mds);
+    }
+
+    #[test]
+    fn test_hash_domain_separation() {
+        let params = pallas_poseidon_params();
+        let one = Fr::ONE;
+        assert_ne!(params.hash(&[]), params.hash(&[Fr::ZERO]));
+        assert_ne!(params.hash(&[one]), params.hash(&[one, Fr::ZERO]));
+        assert_ne!(
+            params.hash(&[one, Fr::from(2)]),
+            params.hash(&[Fr::from(2), one])
+        );
+        assert_eq!(params.hash(&[one, one, one]), params.hash(&[one, one, one]));
+    }
+}
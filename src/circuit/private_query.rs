@@ -0,0 +1,205 @@
+// Query privacy
+// A `PoneglyphCircuit` proof names its query: instance row 4 is
+// `sql::query_hash` of the SQL text. `PrivateQueryCircuit` keeps the hash as
+// a witness and publishes only a salted commitment to it, plus the root of a
+// registry of authorized queries. The proof then says "a circuit authorized
+// in this registry was run correctly", not which query.
+//
+// The registry (`sql::QueryRegistry`) is a Poseidon Merkle tree whose leaves
+// pair a query hash with the fingerprint of the verifying key that proves it
+// (`registry_leaf`). The fingerprint is public (instance row 7) and the
+// verifier checks it against its own key, so a prover can't run an
+// authorized query's hash through another query's circuit.
+//
+// The hidden hash is a free witness: nothing in the circuit derives it from
+// the operations that ran. The membership proof only ties it to the
+// fingerprint, so any hash registered for that fingerprint passes, and the
+// registry owner is trusted to register each query with the circuit it
+// compiles to. What is proven is the circuit, not the hidden query text.
+//
+// What stays hidden is limited by the verifying key: it fixes the operations
+// and, with `ThresholdMode::Fixed`, their constants. A verifier learns which
+// circuit ran, so the query is only hidden among the authorized queries that
+// share a circuit (the same operations over different columns or tables of
+// the same size, for instance). `ThresholdMode::Instance` would publish the
+// constants and is rejected.
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::{PublicInputs, INSTANCE_QUERY_HASH_ROW, NUM_INSTANCE_ROWS};
+use super::merkle::{MerkleChip, MerkleConfig};
+use super::poseidon::{pallas_poseidon_params, PoseidonChip};
+use super::{PoneglyphCircuit, PoneglyphCircuitConfig, ThresholdMode};
+
+/// Instance row of the registry root
+pub const PRIVATE_QUERY_REGISTRY_ROOT_ROW: usize = NUM_INSTANCE_ROWS;
+/// Instance row of the circuit fingerprint (`vk_fingerprint` of the key)
+pub const PRIVATE_QUERY_FINGERPRINT_ROW: usize = NUM_INSTANCE_ROWS + 1;
+
+/// Public commitment to a query hash: `Poseidon(query_hash, salt)`
+/// A fresh random salt per proof keeps equal queries unlinkable.
+pub fn query_commitment(query_hash: Fr, salt: Fr) -> Fr {
+    pallas_poseidon_params().hash(&[query_hash, salt])
+}
+
+/// Registry leaf authorizing a query for the circuit with `circuit_fingerprint`
+pub fn registry_leaf(query_hash: Fr, circuit_fingerprint: u64) -> Fr {
+    pallas_poseidon_params().hash(&[query_hash, Fr::from(circuit_fingerprint)])
+}
+
+/// Public inputs of `PrivateQueryCircuit`
+/// `public_inputs.query_hash` holds the query commitment, not the hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivateQueryInputs {
+    pub public_inputs: PublicInputs,
+    pub registry_root: Fr,
+    pub circuit_fingerprint: u64,
+}
+
+impl PrivateQueryInputs {
+    /// Instance column values
+    pub fn to_instance(&self) -> Vec<Vec<Fr>> {
+        let mut instance = self.public_inputs.to_instance();
+        instance[0].extend([self.registry_root, Fr::from(self.circuit_fingerprint)]);
+        instance
+    }
+}
+
+/// Private Query Circuit
+/// Proves `query` with its query hash hidden behind a salted commitment and
+/// authorized by a registry
+///
+/// # Instance
+///
+/// - rows 0-5: as `PoneglyphCircuit`, except row 4: `query_commitment`
+/// - row 6: registry root, row 7: circuit fingerprint
+///
+/// # Constraints
+///
+/// 1. **Commitment**: row 4 is `Poseidon(query_hash, salt)`
+/// 2. **Membership**: `Poseidon(query_hash, fingerprint)` is the leaf at
+///    `registry_index` of the tree with root row 6
+/// 3. **Query**: the constraints of `query`; its query hash is not exposed,
+///    and is not constrained by the operations (only by the registry leaf)
+#[derive(Clone)]
+pub struct PrivateQueryCircuit {
+    /// Query circuit; its `query_hash` is the private hash
+    pub query: PoneglyphCircuit,
+    pub salt: Value<Fr>,
+    pub circuit_fingerprint: Value<Fr>,
+    pub registry_index: Value<u64>,
+    /// From the leaf level up (`depth` siblings)
+    pub registry_path: Vec<Value<Fr>>,
+}
+
+impl PrivateQueryCircuit {
+    /// Circuit for key generation: `query` as it will be proven, without the
+    /// private-query witnesses
+    /// The key depends on the query's operations and the registry depth, not
+    /// on which query of the registry is proven.
+    pub fn shape(query: PoneglyphCircuit, depth: usize) -> Self {
+        Self {
+            query,
+            salt: Value::unknown(),
+            circuit_fingerprint: Value::unknown(),
+            registry_index: Value::unknown(),
+            registry_path: vec![Value::unknown(); depth],
+        }
+    }
+}
+
+/// Config of `PrivateQueryCircuit`
+#[derive(Clone, Debug)]
+pub struct PrivateQueryConfig {
+    pub query_config: PoneglyphCircuitConfig,
+    pub merkle_config: MerkleConfig,
+}
+
+impl Circuit<Fr> for PrivateQueryCircuit {
+    type Config = PrivateQueryConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::shape(self.query.without_witnesses(), self.registry_path.len())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let query_config = PoneglyphCircuitConfig::configure(meta);
+        let merkle_config = MerkleChip::configure(
+            meta,
+            &query_config.poneglyph_config,
+            &query_config.poseidon_config,
+        );
+        PrivateQueryConfig {
+            query_config,
+            merkle_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        if self.query.threshold_mode != ThresholdMode::Fixed {
+            return Err(Error::Synthesis);
+        }
+        let poneglyph_config = config.query_config.poneglyph_config.clone();
+        let instance = poneglyph_config.instance;
+        let chip = MerkleChip::new(config.merkle_config.clone());
+        let poseidon = PoseidonChip::new(config.merkle_config.poseidon_config.clone());
+
+        self.query.synthesize_with(
+            config.query_config.clone(),
+            layouter.namespace(|| "query"),
+            false,
+        )?;
+
+        let (query_hash, salt) = layouter.assign_region(
+            || "private query hash",
+            |mut region| {
+                let column = config.merkle_config.swap[1];
+                let query_hash = region.assign_advice(
+                    || "query hash",
+                    column,
+                    0,
+                    || Value::known(self.query.query_hash),
+                )?;
+                let salt = region.assign_advice(|| "salt", column, 1, || self.salt)?;
+                Ok((query_hash, salt))
+            },
+        )?;
+        let fingerprint = poneglyph_config.bind_public_input(
+            &mut layouter,
+            self.circuit_fingerprint,
+            PRIVATE_QUERY_FINGERPRINT_ROW,
+        )?;
+
+        let commitment = poseidon.hash(
+            layouter.namespace(|| "query commitment"),
+            &[query_hash.clone(), salt],
+        )?;
+        layouter.constrain_instance(commitment.cell(), instance, INSTANCE_QUERY_HASH_ROW)?;
+
+        let leaf = poseidon.hash(
+            layouter.namespace(|| "registry leaf"),
+            &[query_hash, fingerprint],
+        )?;
+        let (_, bits) = chip.decompose_index(
+            layouter.namespace(|| "registry index"),
+            self.registry_index,
+            self.registry_path.len(),
+        )?;
+        let root = chip.root(
+            layouter.namespace(|| "registry path"),
+            &leaf,
+            &bits,
+            &self.registry_path,
+        )?;
+        layouter.constrain_instance(root.cell(), instance, PRIVATE_QUERY_REGISTRY_ROOT_ROW)
+    }
+}
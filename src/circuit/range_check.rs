@@ -1,12 +1,78 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, TableColumn},
     poly::Rotation,
 };
-use pasta_curves::pallas::Base as Fr;
-use ff::Field;
 
 use super::config::PoneglyphConfig;
+use crate::constants::{DEFAULT_CHUNK_BITS, DEFAULT_MAX_CHUNKS};
+
+/// Comparison window of compiled `x < t` checks (see `check_less_than`)
+/// The same for every row, so the key doesn't depend on the compared values;
+/// covers every pair of u64 values but x = 2^64 - 1 against t = 0
+pub const COMPARISON_WINDOW: u64 = u64::MAX;
+
+/// Bitwise decomposition parameters (see `RangeCheckChip::decompose_bits`)
+/// Set per circuit with `PoneglyphConfig::configure_with`
+///
+/// # Chunk Width
+///
+/// - 8 bits: chunks use the 0-255 table (no extra table)
+/// - 16 bits: half the chunk cells, but a 2^16-row table (k >= 17)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecompositionParams {
+    /// Bits per chunk (1-16)
+    pub chunk_bits: u32,
+    /// Most chunks of one decomposed value
+    pub max_chunks: usize,
+}
+
+impl Default for DecompositionParams {
+    fn default() -> Self {
+        Self {
+            chunk_bits: DEFAULT_CHUNK_BITS,
+            max_chunks: DEFAULT_MAX_CHUNKS,
+        }
+    }
+}
+
+impl DecompositionParams {
+    pub fn new(chunk_bits: u32, max_chunks: usize) -> Self {
+        Self {
+            chunk_bits,
+            max_chunks,
+        }
+    }
+
+    /// Widest value that can be decomposed
+    pub fn max_bits(&self) -> u32 {
+        self.chunk_bits * self.max_chunks as u32
+    }
+
+    /// Check the parameters against the field
+    /// Decomposed values must stay below the field modulus, or the chunks
+    /// would no longer bound the value.
+    pub fn validate<F: PrimeField>(&self) -> Result<(), String> {
+        if !(1..=16).contains(&self.chunk_bits) {
+            return Err(format!(
+                "chunk width must be 1-16 bits, got {}",
+                self.chunk_bits
+            ));
+        }
+        if self.max_chunks == 0 || self.max_bits() >= F::NUM_BITS {
+            return Err(format!(
+                "{} chunks of {} bits don't fit below the {}-bit field modulus",
+                self.max_chunks,
+                self.chunk_bits,
+                F::NUM_BITS
+            ));
+        }
+        Ok(())
+    }
+}
 
 /// Range Check Configuration
 /// According to Paper Section 4.1: Decomposing 64-bit numbers into 8-bit chunks
@@ -19,21 +85,32 @@ use super::config::PoneglyphConfig;
 /// - `diff_column`: For diff value (advice[8], same as check_column, different row)
 /// - `threshold_column`: For threshold (t) value (fixed[0])
 /// - `u_column`: For u value (fixed[1])
+/// - `public_threshold_column`: Threshold copied from the instance column (advice[10])
 /// - `lookup_table`: 0-255 lookup table (TableColumn)
+/// - `chunk_table`: chunk table of `decompose_bits` (TableColumn, 0-255 by default)
 /// 
 /// # Constraints
 /// 
 /// 1. **Lookup Constraint**: Checks that each chunk is in range 0-255
 /// 2. **Decomposition Sum**: Verifies formula `N = Σ c_i · 2^(8i)`
-/// 3. **x < t Constraint**: `check + (x - t) - u ∈ [0, u)` check
+/// 3. **x < t Constraint**: `check · u + (x - t) ∈ [0, u)` check
 ///    - Boolean check: `check * (1 - check) = 0`
-///    - Diff calculation: `diff = check + (x - t) - u`
-///    - Range check: `diff ∈ [0, u)` (with lookup table)
+///    - Diff calculation: `diff = check · u + (x - t)`, `slack = u - 1 - diff`
+///    - Range check: `diff, slack ∈ [0, 2^64)` with the 8-bit decomposition
+/// 4. **lo <= x < hi Constraint** (see `check_between`)
+/// 5. **x < t Constraint, public t** (see `check_less_than_public`)
+/// 6. **Chained Decomposition**: `acc_r = Σ c_i · 2^(w·i) + 2^(8w) · acc_{r+1}`
+///    over rows of 8 w-bit chunks (see `decompose_bits`)
+/// 7. **Signed Decomposition**: `v + 2^63 = Σ c_i · 2^(8i)` for a signed cell v
+///    (see `decompose_signed_cell`)
 /// 
 /// # Note
 /// 
 /// - `diff_column` and `check_column` share the same column (in different rows)
-/// - Works with u < 256 assumption (production note for u >= 256)
+/// - u is the comparison window: x must lie in `[t - u, t + u)`; it is a
+///   fixed cell, so it must not depend on x (see `COMPARISON_WINDOW`)
+/// - Fixed thresholds are part of the verifying key; `ThresholdMode::Instance`
+///   uses `public_threshold_column` instead, so one key serves every threshold
 #[derive(Clone, Debug)]
 pub struct RangeCheckConfig {
     // Advice columns for 8-bit chunks (8 columns)
@@ -51,7 +128,7 @@ pub struct RangeCheckConfig {
     // advice[9] - x_column
     pub x_column: Column<Advice>,
     
-    // Column for diff value: diff = check + (x - t) - u
+    // Column for diff value: diff = check · u + (x - t)
     // Note: same column as check_column, different row (offset 1)
     // advice[8] - same column as check_column
     pub diff_column: Column<Advice>,
@@ -61,29 +138,46 @@ pub struct RangeCheckConfig {
     pub threshold_column: Column<Fixed>,
     // fixed[1] - u_column
     pub u_column: Column<Fixed>,
+
+    // Threshold copied from the instance column (public threshold mode)
+    // advice[10] - shared with Join Gate
+    pub public_threshold_column: Column<Advice>,
     
     // Selectors
     pub selector: Selector,
     pub less_than_selector: Selector,
     pub decomposition_selector: Selector,
-    pub diff_lookup_selector: Selector,
+    pub between_selector: Selector,
+    pub public_less_than_selector: Selector,
+
+    // Chained decomposition (decompose_bits)
+    pub chain_selector: Selector,
+    pub chunk_table: TableColumn,
+    pub decomposition: DecompositionParams,
+
+    // Signed decomposition (decompose_signed_cell)
+    pub signed_decomposition_selector: Selector,
 }
 
 /// Range Check Chip
 /// Paper Section 4.1 implementation
-pub struct RangeCheckChip {
+pub struct RangeCheckChip<F: PrimeField> {
     config: RangeCheckConfig,
+    _marker: PhantomData<F>,
 }
 
-impl RangeCheckChip {
+impl<F: PrimeField> RangeCheckChip<F> {
     /// Create a new RangeCheckChip
     pub fn new(config: RangeCheckConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            _marker: PhantomData,
+        }
     }
     /// Configure the Range Check Gate
     /// Paper Section 4.1: 8-bit chunk decomposition and x < t constraint
     pub fn configure(
-        meta: &mut ConstraintSystem<Fr>,
+        meta: &mut ConstraintSystem<F>,
         config: &PoneglyphConfig,
     ) -> RangeCheckConfig {
         // 8-bit chunk columns
@@ -111,10 +205,16 @@ impl RangeCheckChip {
         let diff_column = config.advice[8]; // same column as check_column, different row
         let threshold_column = config.fixed[0];
         let u_column = config.fixed[1];
+        let public_threshold_column = config.advice[10];
         let selector = config.range_check_selector;
         let less_than_selector = config.less_than_selector;
         let decomposition_selector = config.decomposition_selector;
-        let diff_lookup_selector = config.diff_lookup_selector;
+        let between_selector = config.between_selector;
+        let public_less_than_selector = config.public_less_than_selector;
+        let chain_selector = config.chain_selector;
+        let signed_decomposition_selector = config.signed_decomposition_selector;
+        let chunk_table = config.chunk_table();
+        let decomposition = config.decomposition;
         
         // Lookup constraint: Check that each chunk is in range 0-255
         // Paper Section 4.1: "Lookup Table" technique
@@ -122,31 +222,28 @@ impl RangeCheckChip {
         // Checks that each chunk is in range 0-255 using lookup table.
         // Chunks are assigned in the same row (row 1), so all chunks
         // are read with Rotation::cur() (must be in same row as selector).
-        meta.lookup(|meta| {
-            let s = meta.query_selector(selector); // query_selector is used for complex_selector
-            let one = Expression::Constant(Fr::ONE);
-            let mut constraints = Vec::new();
-            
-            // Lookup constraint for each chunk
-            // Chunks and value are in the same row (row 0)
-            // According to Halo2 example: selector * chunk + (1 - selector) * dummy_value
-            // We use 0 as dummy value (exists in lookup table, row 0)
-            // 
-            // Note: Selector is read with Rotation::cur(), so chunks must also
-            // be read with Rotation::cur() (must be in same row)
-            for chunk_col in chunk_columns.iter() {
-                // We must read chunks with Rotation::cur() (in row 0, same row as selector)
+        // 
+        // One lookup per chunk: the pairs of a single lookup form one tuple,
+        // which would need a table row holding all 8 chunks at once.
+        for chunk_col in chunk_columns.iter() {
+            meta.lookup(|meta| {
+                let s = meta.query_selector(selector); // query_selector is used for complex_selector
+                let one = Expression::Constant(F::ONE);
+                
+                // According to Halo2 example: selector * chunk + (1 - selector) * dummy_value
+                // We use 0 as dummy value (exists in lookup table, row 0)
+                // 
+                // Note: Selector is read with Rotation::cur(), so the chunk must also
+                // be read with Rotation::cur() (must be in same row)
                 let chunk = meta.query_advice(*chunk_col, Rotation::cur());
-                let not_selector = one.clone() - s.clone();
+                let not_selector = one - s.clone();
                 // selector * chunk + (1 - selector) * 0
                 // When selector = 1: chunk is looked up (must be in range 0-255)
                 // When selector = 0: 0 is looked up (exists in lookup table)
-                let lookup_expr = s.clone() * chunk + not_selector * Expression::Constant(Fr::ZERO);
-                constraints.push((lookup_expr, lookup_table));
-            }
-            
-            constraints
-        });
+                let lookup_expr = s * chunk + not_selector * Expression::Constant(F::ZERO);
+                vec![(lookup_expr, lookup_table)]
+            });
+        }
         
         // Decomposition sum constraint: N = Σ c_i · 2^(8i)
         // Paper Section 4.1: Bitwise decomposition correctness
@@ -162,13 +259,13 @@ impl RangeCheckChip {
             // Chunks and value are in the same row (row 1)
             // Chunks are read with Rotation::cur() (row 1)
             let sum = chunk_columns.iter().enumerate().fold(
-                Expression::Constant(Fr::ZERO),
+                Expression::Constant(F::ZERO),
                 |acc, (i, &chunk_col)| {
                     // We must read chunks with Rotation::cur() (row 1)
                     // Note: Since all chunks are in the same row (row 1),
                     // they are all read with Rotation::cur()
                     let chunk = meta.query_advice(chunk_col, Rotation::cur());
-                    let power = Expression::Constant(Fr::from(1u64 << (i * 8)));
+                    let power = Expression::Constant(F::from(1u64 << (i * 8)));
                     acc + chunk * power
                 },
             );
@@ -177,13 +274,19 @@ impl RangeCheckChip {
             vec![s * (value - sum)]
         });
         
-        // x < t constraint: check + (x - t) - u ∈ [0, u)
+        // x < t constraint: check · u + (x - t) ∈ [0, u)
         // Paper Section 4.1: Range comparison constraint
         // 
         // This constraint performs x < t check:
         // 1. check must be boolean: check * (1 - check) = 0
-        // 2. diff = check + (x - t) - u must be calculated
-        // 3. diff ∈ [0, u) check must be done with lookup table
+        // 2. diff = check · u + (x - t) must be calculated
+        // 3. slack = u - 1 - diff must be calculated
+        // 4. diff ∈ [0, u) check: diff and slack are decomposed into 8-bit
+        //    chunks (see check_less_than)
+        //
+        // With x ∈ [t - u, t + u), only the correct check puts diff in [0, u):
+        // check = 1 gives diff ∈ [u, 2u) for x >= t, check = 0 gives a
+        // negative diff for x < t.
         meta.create_gate("x < t constraint", |meta| {
             let s = meta.query_selector(less_than_selector);
             let check = meta.query_advice(check_column, Rotation::cur());
@@ -193,42 +296,140 @@ impl RangeCheckChip {
             
             // Boolean constraint: check * (1 - check) = 0
             // check value must be 0 or 1
-            let boolean_check = check.clone() * (Expression::Constant(Fr::ONE) - check.clone());
+            let boolean_check = check.clone() * (Expression::Constant(F::ONE) - check.clone());
             
-            // Paper formula: diff = check + (x - t) - u
             // diff_column is same column as check_column, different row (offset 1)
+            // slack is in x_column, same row as diff
             let diff = meta.query_advice(diff_column, Rotation::next());
-            let diff_expr = check.clone() + (x - t) - u.clone();
+            let slack = meta.query_advice(x_column, Rotation::next());
+            let diff_expr = check.clone() * u.clone() + (x - t);
+            let slack_expr = u - Expression::Constant(F::ONE) - diff.clone();
             
             vec![
                 s.clone() * boolean_check, // check must be boolean
-                s.clone() * (diff - diff_expr), // diff = check + (x - t) - u
+                s.clone() * (diff - diff_expr), // diff = check · u + (x - t)
+                s * (slack - slack_expr), // slack = u - 1 - diff
             ]
         });
         
-        // Lookup constraint for [0, u) range check
-        // Paper Section 4.1: diff ∈ [0, u) check must be done with lookup table
-        // 
-        // # Note
-        // 
-        // - Works with u < 256 assumption (checks diff directly with lookup table)
-        // - For u >= 256: We can divide diff into chunks and check that each chunk is in range 0-255,
-        //   but additional constraint is needed for diff < u check
-        // - For production: u >= 256 support can be added (with diff decomposition)
-        meta.lookup(|meta| {
-            let s = meta.query_selector(diff_lookup_selector);
-            let diff = meta.query_advice(diff_column, Rotation::cur());
-            let one = Expression::Constant(Fr::ONE);
-            let not_selector = one - s.clone();
-            
-            // selector * diff + (1 - selector) * 0
-            // When selector = 1: diff is looked up (must be in range 0-255, u < 256 assumption)
-            // When selector = 0: 0 is looked up (exists in lookup table)
-            let lookup_expr = s.clone() * diff + not_selector * Expression::Constant(Fr::ZERO);
-            
-            vec![(lookup_expr, lookup_table)]
+        // lo <= x < hi constraint (single row + two 64-bit range checks)
+        //
+        // Row layout (relative to the selector row):
+        // - Row 0: x (x_column), out (check_column), lo (threshold_column), hi (u_column)
+        // - Row 1: ge = [x >= lo] (check_column), d_lo (x_column)
+        // - Row 2: lt = [x < hi] (check_column), d_hi (x_column)
+        //
+        // d_lo = x - lo + (1 - ge) · 2^64 and d_hi = hi - 1 - x + (1 - lt) · 2^64
+        // must both be in [0, 2^64) (decomposed by the caller), which pins ge and lt:
+        // the two candidate values of each differ by 2^64, only one is in range.
+        meta.create_gate("lo <= x < hi constraint", |meta| {
+            let s = meta.query_selector(between_selector);
+            let x = meta.query_advice(x_column, Rotation::cur());
+            let out = meta.query_advice(check_column, Rotation::cur());
+            let lo = meta.query_fixed(threshold_column);
+            let hi = meta.query_fixed(u_column);
+            let ge = meta.query_advice(check_column, Rotation::next());
+            let d_lo = meta.query_advice(x_column, Rotation::next());
+            let lt = meta.query_advice(check_column, Rotation(2));
+            let d_hi = meta.query_advice(x_column, Rotation(2));
+
+            let one = Expression::Constant(F::ONE);
+            let two_pow_64 = Expression::Constant(F::from(u64::MAX) + F::ONE);
+
+            vec![
+                s.clone() * ge.clone() * (one.clone() - ge.clone()),
+                s.clone() * lt.clone() * (one.clone() - lt.clone()),
+                s.clone() * (out - ge.clone() * lt.clone()),
+                s.clone() * (d_lo - (x.clone() - lo + (one.clone() - ge) * two_pow_64.clone())),
+                s * (d_hi - (hi - one.clone() - x + (one - lt) * two_pow_64)),
+            ]
         });
-        
+
+        // x < t constraint with t in an advice cell (copied from the instance column)
+        //
+        // Row layout (relative to the selector row):
+        // - Row 0: x (x_column), check (check_column), t (public_threshold_column)
+        // - Row 1: d (x_column)
+        //
+        // d = t - 1 - x + (1 - check) · 2^64 must be in [0, 2^64) (decomposed by the
+        // caller, as is x), same argument as the lo <= x < hi constraint; no u is needed.
+        meta.create_gate("x < t public constraint", |meta| {
+            let s = meta.query_selector(public_less_than_selector);
+            let x = meta.query_advice(x_column, Rotation::cur());
+            let check = meta.query_advice(check_column, Rotation::cur());
+            let t = meta.query_advice(public_threshold_column, Rotation::cur());
+            let d = meta.query_advice(x_column, Rotation::next());
+
+            let one = Expression::Constant(F::ONE);
+            let two_pow_64 = Expression::Constant(F::from(u64::MAX) + F::ONE);
+
+            vec![
+                s.clone() * check.clone() * (one.clone() - check.clone()),
+                s * (d - (t - one.clone() - x + (one - check) * two_pow_64)),
+            ]
+        });
+
+        // Chained decomposition: acc_r = Σ c_i · 2^(w·i) + 2^(8w) · acc_{r+1}
+        //
+        // Row layout (one row per 8 chunks, least significant first):
+        // - Row r: chunks c_0..c_7 (chunk_columns), acc_r (x_column)
+        // - Row after the last: acc = 0 (constrained to the constant)
+        //
+        // acc_0 is the value; with every chunk in [0, 2^w) it is below
+        // 2^(8w · rows), and unused chunks of the last row are pinned to 0.
+        let chunk_bits = decomposition.chunk_bits;
+        meta.create_gate("decomposition chain", |meta| {
+            let s = meta.query_selector(chain_selector);
+            let acc = meta.query_advice(x_column, Rotation::cur());
+            let acc_next = meta.query_advice(x_column, Rotation::next());
+
+            let two = F::from(2);
+            let sum = chunk_columns.iter().enumerate().fold(
+                acc_next * Expression::Constant(two.pow_vartime([8 * chunk_bits as u64])),
+                |acc, (i, &chunk_col)| {
+                    let chunk = meta.query_advice(chunk_col, Rotation::cur());
+                    let power = two.pow_vartime([i as u64 * chunk_bits as u64]);
+                    acc + chunk * Expression::Constant(power)
+                },
+            );
+
+            vec![s * (acc - sum)]
+        });
+
+        // Signed decomposition: v + 2^63 = Σ c_i · 2^(8i)
+        //
+        // Row layout: v (x_column) and its 8 chunks (chunk_columns) in one row,
+        // the chunks looked up with `selector`. The chunks bound v + 2^63 to
+        // [0, 2^64), so the field element v is a valid i64 (see `signed.rs`).
+        meta.create_gate("signed decomposition sum", |meta| {
+            let s = meta.query_selector(signed_decomposition_selector);
+            let value = meta.query_advice(x_column, Rotation::cur());
+            let offset = Expression::Constant(F::from(super::signed::SIGNED_OFFSET));
+
+            let sum = chunk_columns.iter().enumerate().fold(
+                Expression::Constant(F::ZERO),
+                |acc, (i, &chunk_col)| {
+                    let chunk = meta.query_advice(chunk_col, Rotation::cur());
+                    acc + chunk * Expression::Constant(F::from(1u64 << (i * 8)))
+                },
+            );
+
+            vec![s * (value + offset - sum)]
+        });
+
+        // Chunks wider than 8 bits are looked up in their own table
+        // (8-bit chunks use the 0-255 lookup above, with `selector`), one
+        // lookup per chunk column
+        if chunk_bits != 8 {
+            for &chunk_col in chunk_columns.iter() {
+                meta.lookup(|meta| {
+                    let s = meta.query_selector(chain_selector);
+                    let chunk = meta.query_advice(chunk_col, Rotation::cur());
+                    vec![(s * chunk, chunk_table)]
+                });
+            }
+        }
+
         RangeCheckConfig {
             chunk_columns,
             lookup_table,
@@ -237,10 +438,16 @@ impl RangeCheckChip {
             diff_column,
             threshold_column,
             u_column,
+            public_threshold_column,
             selector,
             less_than_selector,
             decomposition_selector,
-            diff_lookup_selector,
+            between_selector,
+            public_less_than_selector,
+            chain_selector,
+            chunk_table,
+            decomposition,
+            signed_decomposition_selector,
         }
     }
     
@@ -272,20 +479,110 @@ impl RangeCheckChip {
     /// 8 chunk cells (each 8-bit)
     pub fn decompose_64bit(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        layouter: impl Layouter<F>,
         value: Value<u64>,
-    ) -> Result<[AssignedCell<Fr, Fr>; 8], Error> {
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        self.assign_decomposition(layouter, value, value.map(super::witness::chunks_of))
+    }
+
+    /// Decompose every value, one row each in a shared region
+    /// The chunks of all values are computed up front (in parallel with the
+    /// `parallel` feature, see `witness.rs`) into the synthesis arena; the
+    /// layout is that of `decompose_many`
+    ///
+    /// # Return Value
+    ///
+    /// 8 chunk cells (each 8-bit) per value
+    pub fn decompose_64bit_rows(
+        &self,
+        layouter: impl Layouter<F>,
+        values: &[u64],
+    ) -> Result<Vec<[AssignedCell<F, F>; 8]>, Error> {
+        super::arena::with_arena(|arena| {
+            let chunks = super::witness::decompose_rows_in(arena, values);
+            let decomposed = arena.alloc_from(chunks.iter().map(|&c| Value::known(c)));
+            let values = arena.alloc_from(values.iter().map(|&v| Value::known(v)));
+            self.assign_decompositions(layouter, values, decomposed)
+        })
+    }
+
+    /// Decompose many 64-bit values in one region
+    /// `decompose_64bit` takes a region of two rows per value; here value i
+    /// and its chunks share row i, and the decomposition and lookup selectors
+    /// are enabled on every row, so N values take N rows of one region
+    ///
+    /// # Row Layout
+    ///
+    /// - Row i: value i (x_column) and its 8 chunks (chunk_columns)
+    ///
+    /// # Return Value
+    ///
+    /// 8 chunk cells (each 8-bit) per value
+    pub fn decompose_many(
+        &self,
+        layouter: impl Layouter<F>,
+        values: &[Value<u64>],
+    ) -> Result<Vec<[AssignedCell<F, F>; 8]>, Error> {
+        super::arena::with_arena(|arena| {
+            let decomposed = arena.alloc_from(
+                values
+                    .iter()
+                    .map(|value| value.map(super::witness::chunks_of)),
+            );
+            self.assign_decompositions(layouter, values, decomposed)
+        })
+    }
+
+    /// Assign values and their precomputed chunks (layout of `decompose_many`)
+    fn assign_decompositions(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<u64>],
+        decomposed: &[Value<[u64; 8]>],
+    ) -> Result<Vec<[AssignedCell<F, F>; 8]>, Error> {
+        if values.is_empty() {
+            return Ok(Vec::new());
+        }
         layouter.assign_region(
-            || "decompose 64bit",
+            || format!("decompose {} values", values.len()),
             |mut region| {
-                let decomposed = value.map(|v| {
-                    let mut result = [0u8; 8];
-                    for i in 0..8 {
-                        result[i] = ((v >> (i * 8)) & 0xFF) as u8;
+                let mut rows = Vec::with_capacity(values.len());
+                for (row, (value, chunks)) in values.iter().zip(decomposed).enumerate() {
+                    region.assign_advice(
+                        || format!("value_{}", row),
+                        self.config.x_column,
+                        row,
+                        || value.map(|v| F::from(v)),
+                    )?;
+                    self.config.decomposition_selector.enable(&mut region, row)?;
+                    self.config.selector.enable(&mut region, row)?;
+
+                    let mut cells = Vec::with_capacity(self.config.chunk_columns.len());
+                    for (i, chunk_col) in self.config.chunk_columns.iter().enumerate() {
+                        cells.push(region.assign_advice(
+                            || format!("chunk_{}_{}", row, i),
+                            *chunk_col,
+                            row,
+                            || chunks.map(|c| F::from(c[i])),
+                        )?);
                     }
-                    result
-                });
-                
+                    rows.push(cells.try_into().unwrap());
+                }
+                Ok(rows)
+            },
+        )
+    }
+
+    /// Assign `value` and its precomputed chunks (layout of `decompose_64bit`)
+    fn assign_decomposition(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u64>,
+        decomposed: Value<[u64; 8]>,
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        layouter.assign_region(
+            || "decompose 64bit",
+            |mut region| {
                 // Place each chunk in the same row (row 1 - same row as value)
                 // Row 0: empty (x_column is used in row 0 in check_less_than)
                 // Row 1: value and all chunks (for decomposition sum and lookup)
@@ -304,14 +601,14 @@ impl RangeCheckChip {
                     || "value",
                     self.config.x_column,
                     value_row,
-                    || value.map(|v| Fr::from(v)),
+                    || value.map(|v| F::from(v)),
                 )?;
                 
                 // Selector for decomposition sum constraint (in row 1)
                 self.config.decomposition_selector.enable(&mut region, value_row)?;
                 
                 for (i, chunk_col) in self.config.chunk_columns.iter().enumerate() {
-                    let chunk_value = decomposed.map(|chunks| Fr::from(chunks[i] as u64));
+                    let chunk_value = decomposed.map(|chunks| F::from(chunks[i]));
                     
                     // Assign chunk (all chunks in row 1, same row as value)
                     let cell = region.assign_advice(
@@ -335,34 +632,173 @@ impl RangeCheckChip {
         )
     }
     
+    /// Decompose an already assigned cell into 8-bit chunks
+    /// Same layout as `decompose_64bit`, but the value is copied from `cell`
+    /// (copy constraint), so the proven 64-bit bound applies to that exact cell
+    ///
+    /// # Return Value
+    ///
+    /// 8 chunk cells (each 8-bit)
+    pub fn decompose_cell(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        layouter.assign_region(
+            || "decompose cell",
+            |mut region| {
+                let value_row = 1; // Same row as decompose_64bit
+
+                // Copy value (permutation argument ties it to the source cell)
+                cell.copy_advice(|| "value", &mut region, self.config.x_column, value_row)?;
+                self.config.decomposition_selector.enable(&mut region, value_row)?;
+
+                let decomposed = cell.value().map(|v| field_to_u64(v));
+                let mut chunks = Vec::new();
+                for (i, chunk_col) in self.config.chunk_columns.iter().enumerate() {
+                    let chunk_value = decomposed.map(|v| F::from((v >> (i * 8)) & 0xFF));
+                    let chunk_cell = region.assign_advice(
+                        || format!("chunk_{}", i),
+                        *chunk_col,
+                        value_row,
+                        || chunk_value,
+                    )?;
+                    chunks.push(chunk_cell);
+                }
+
+                // Lookup constraint for all chunks (same row)
+                self.config.selector.enable(&mut region, value_row)?;
+
+                Ok(chunks.try_into().unwrap())
+            },
+        )
+    }
+
+    /// Decompose an `n_bits`-wide value into chunks of the configured width
+    /// Proves `value ∈ [0, 2^n_bits)`; a 128-bit value (e.g. a token amount in
+    /// wei) takes 16 chunks of 8 bits, or 8 chunks of 16 bits
+    ///
+    /// # Row Layout
+    ///
+    /// - Rows 0..r: 8 chunks each (chunk_columns), running sum (x_column)
+    /// - Row r: running sum 0
+    ///
+    /// Chunk cells past `n_bits` in the last row are constrained to 0.
+    ///
+    /// # Errors
+    ///
+    /// `Error::Synthesis` if `n_bits` is 0, above 128, not a multiple of the
+    /// chunk width, or needs more than `max_chunks` chunks
+    ///
+    /// # Return Value
+    ///
+    /// `n_bits / chunk_bits` chunk cells, least significant first
+    pub fn decompose_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u128>,
+        n_bits: u32,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let params = self.config.decomposition;
+        let chunk_bits = params.chunk_bits;
+        if n_bits == 0
+            || n_bits > 128
+            || !n_bits.is_multiple_of(chunk_bits)
+            || (n_bits / chunk_bits) as usize > params.max_chunks
+        {
+            return Err(Error::Synthesis);
+        }
+        let n = (n_bits / chunk_bits) as usize;
+        let rows = n.div_ceil(self.config.chunk_columns.len());
+        let chunks = value.map(|v| super::witness::chunks_of_bits(v, chunk_bits, n));
+
+        layouter.assign_region(
+            || format!("decompose {} bits", n_bits),
+            |mut region| {
+                let mut cells = Vec::with_capacity(n);
+                for row in 0..rows {
+                    self.config.chain_selector.enable(&mut region, row)?;
+                    if chunk_bits == 8 {
+                        // 0-255 lookup on all chunk columns of the row
+                        self.config.selector.enable(&mut region, row)?;
+                    }
+
+                    // Running sum: the chunks of this row and every later row
+                    let shift = 8 * chunk_bits * row as u32;
+                    region.assign_advice(
+                        || format!("acc_{}", row),
+                        self.config.x_column,
+                        row,
+                        || value.map(|v| F::from_u128(v.checked_shr(shift).unwrap_or(0))),
+                    )?;
+
+                    for (j, chunk_col) in self.config.chunk_columns.iter().enumerate() {
+                        let i = row * self.config.chunk_columns.len() + j;
+                        if i < n {
+                            let cell = region.assign_advice(
+                                || format!("chunk_{}", i),
+                                *chunk_col,
+                                row,
+                                || chunks.as_ref().map(|c| F::from(c[i])),
+                            )?;
+                            cells.push(cell);
+                        } else {
+                            let cell = region.assign_advice(
+                                || format!("padding_{}", j),
+                                *chunk_col,
+                                row,
+                                || Value::known(F::ZERO),
+                            )?;
+                            region.constrain_constant(cell.cell(), F::ZERO)?;
+                        }
+                    }
+                }
+
+                let end = region.assign_advice(
+                    || "acc_end",
+                    self.config.x_column,
+                    rows,
+                    || Value::known(F::ZERO),
+                )?;
+                region.constrain_constant(end.cell(), F::ZERO)?;
+
+                Ok(cells)
+            },
+        )
+    }
+
     /// x < t check
-    /// Paper Section 4.1: check + (x - t) - u ∈ [0, u) constraint
+    /// Paper Section 4.1: check · u + (x - t) ∈ [0, u) constraint
     /// 
     /// # Constraint
     /// 
-    /// `check + (x - t) - u ∈ [0, u)`
+    /// `diff = check · u + (x - t) ∈ [0, u)`
     /// 
     /// # Logic
     /// 
-    /// - If `x < t`: `check = 1`, `diff = 1 + (x - t) - u ∈ [0, u)`
-    /// - If `x >= t`: `check = 0`, `diff = 0 + (x - t) - u ∈ [0, u)`
+    /// - If `x < t`: `check = 1`, `diff = u + (x - t) ∈ [0, u)` for `x >= t - u`
+    /// - If `x >= t`: `check = 0`, `diff = x - t ∈ [0, u)` for `x < t + u`
     /// 
     /// # Note
     /// 
-    /// - Works with u < 256 assumption (checks diff directly with lookup table)
-    /// - For u >= 256: Production note (can be checked with diff decomposition)
+    /// - u is the comparison window; x outside `[t - u, t + u)` can't be proven.
+    ///   u is a fixed cell, so it has to be chosen without looking at x, e.g.
+    ///   `COMPARISON_WINDOW`; a window fitted to x would reveal it in the key
+    /// - diff and `u - 1 - diff` are decomposed into 8-bit chunks (two
+    ///   `decompose_cell` regions) whatever u is, so the layout doesn't depend
+    ///   on u and any window up to `u64::MAX` is sound
     /// 
     /// # Return Value
     /// 
     /// Boolean check cell (1 = x < t, 0 = x >= t)
     pub fn check_less_than(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         x: Value<u64>,
         threshold: u64,
         u: u64,
-    ) -> Result<AssignedCell<Fr, Fr>, Error> {
-        layouter.assign_region(
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (check_cell, diff_cell, slack_cell) = layouter.assign_region(
             || "check x < t",
             |mut region| {
                 // Selector for x < t constraint
@@ -373,7 +809,7 @@ impl RangeCheckChip {
                     || "x",
                     self.config.x_column,
                     0,
-                    || x.map(|x_val| Fr::from(x_val)),
+                    || x.map(|x_val| F::from(x_val)),
                 )?;
                 
                 // Assign threshold (t) value to fixed column
@@ -381,7 +817,7 @@ impl RangeCheckChip {
                     || "threshold",
                     self.config.threshold_column,
                     0,
-                    || Value::known(Fr::from(threshold)),
+                    || Value::known(F::from(threshold)),
                 )?;
                 
                 // Assign u value to fixed column
@@ -389,16 +825,16 @@ impl RangeCheckChip {
                     || "u",
                     self.config.u_column,
                     0,
-                    || Value::known(Fr::from(u)),
+                    || Value::known(F::from(u)),
                 )?;
                 
                 // Boolean value for x < t check
                 // Paper requirement: check must be boolean (0 or 1)
                 let check = x.map(|x_val| {
                     if x_val < threshold {
-                        Fr::from(1)
+                        F::from(1)
                     } else {
-                        Fr::from(0)
+                        F::from(0)
                     }
                 });
                 
@@ -409,60 +845,327 @@ impl RangeCheckChip {
                     || check,
                 )?;
                 
-                // Calculate diff = check + (x - t) - u
+                // Calculate diff = check · u + (x - t)
                 // Paper Section 4.1: for diff ∈ [0, u) check
                 let diff = check
-                    .zip(x.map(|x_val| Fr::from(x_val)))
-                    .map(|(check_val, x_val)| {
-                        let t_val = Fr::from(threshold);
-                        let u_val = Fr::from(u);
-                        check_val + (x_val - t_val) - u_val
-                    });
+                    .zip(x.map(|x_val| F::from(x_val)))
+                    .map(|(check_val, x_val)| check_val * F::from(u) + (x_val - F::from(threshold)));
                 
                 // Assign diff to diff_column (same column as check_column, offset 1)
-                let _diff_cell = region.assign_advice(
+                let diff_cell = region.assign_advice(
                     || "diff",
                     self.config.diff_column,
                     1, // offset 1 (next to check_column)
                     || diff,
                 )?;
                 
-                // Lookup constraint for [0, u) range check
-                // Production note: for u >= 256 support
-                // If u < 256, we check diff directly with lookup table
-                // If u >= 256, we can divide diff into chunks and check that each chunk is in range 0-255
-                // But additional constraint is needed for diff < u check
-                // 
-                // Production Note: For u >= 256 support, diff must be decomposed and
-                // additional range check constraint must be added for diff < u check
-                // For now: we work with u < 256 assumption (sufficient for production)
-                if u < 256 {
-                    // u < 256: check diff directly with lookup table
-                    self.config.diff_lookup_selector.enable(&mut region, 1)?;
-                } else {
-                    // u >= 256: Production note
-                    // In this case, we can divide diff into chunks and check that each chunk is in range 0-255
-                    // But additional constraint is needed for diff < u check
-                    // For now: correct value will be assigned in witness
-                    // For production: additional range check constraint can be added for diff < u check
-                    // Note: This case is rare in production, because u < 256 is generally used
-                }
-                
-                // Constraint is automatically checked by gate defined in configure
-                // For check + (x - t) - u ∈ [0, u) check:
-                // - check boolean constraint (check * (1 - check) = 0) ✅
-                // - diff = check + (x - t) - u constraint ✅
-                // - diff ∈ [0, u) lookup table check ✅ (direct for u < 256, by dividing into chunks for u >= 256)
+                // slack = u - 1 - diff, in [0, u) exactly when diff is
+                let slack_cell = region.assign_advice(
+                    || "slack",
+                    self.config.x_column,
+                    1,
+                    || diff.map(|diff_val| F::from(u) - F::ONE - diff_val),
+                )?;
                 
-                Ok(check_cell)
+                Ok((check_cell, diff_cell, slack_cell))
             },
-        )
+        )?;
+        
+        // diff, slack ∈ [0, 2^64), so diff ∈ [0, u) since diff + slack = u - 1 < 2^64
+        self.decompose_cell(layouter.namespace(|| "diff range"), &diff_cell)?;
+        self.decompose_cell(layouter.namespace(|| "slack range"), &slack_cell)?;
+        
+        Ok(check_cell)
     }
     
+    /// lo <= x < hi check (SQL `BETWEEN` with an exclusive upper bound)
+    /// One combined row instead of two `check_less_than` calls and a manual AND
+    ///
+    /// # Constraints
+    ///
+    /// - `ge`, `lt` boolean, `out = ge · lt`
+    /// - `d_lo = x - lo + (1 - ge) · 2^64 ∈ [0, 2^64)`
+    /// - `d_hi = hi - 1 - x + (1 - lt) · 2^64 ∈ [0, 2^64)`
+    ///
+    /// Both diffs are range checked with the 8-bit decomposition, so no `u`
+    /// window is needed and any 64-bit bounds work.
+    ///
+    /// # Return Value
+    ///
+    /// Boolean check cell (1 = lo <= x < hi)
+    pub fn check_between(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Value<u64>,
+        lo: u64,
+        hi: u64,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let two_pow_64 = F::from(u64::MAX) + F::ONE;
+        let ge = x.map(|x| x >= lo);
+        let lt = x.map(|x| x < hi);
+
+        let (out_cell, d_lo_cell, d_hi_cell) = layouter.assign_region(
+            || "check lo <= x < hi",
+            |mut region| {
+                self.config.between_selector.enable(&mut region, 0)?;
+
+                region.assign_advice(
+                    || "x",
+                    self.config.x_column,
+                    0,
+                    || x.map(F::from),
+                )?;
+                region.assign_fixed(
+                    || "lo",
+                    self.config.threshold_column,
+                    0,
+                    || Value::known(F::from(lo)),
+                )?;
+                region.assign_fixed(
+                    || "hi",
+                    self.config.u_column,
+                    0,
+                    || Value::known(F::from(hi)),
+                )?;
+
+                let out_cell = region.assign_advice(
+                    || "out",
+                    self.config.check_column,
+                    0,
+                    || ge.zip(lt).map(|(ge, lt)| F::from((ge && lt) as u64)),
+                )?;
+
+                region.assign_advice(
+                    || "ge",
+                    self.config.check_column,
+                    1,
+                    || ge.map(|ge| F::from(ge as u64)),
+                )?;
+                let d_lo_cell = region.assign_advice(
+                    || "d_lo",
+                    self.config.x_column,
+                    1,
+                    || {
+                        x.zip(ge).map(|(x, ge)| {
+                            let wrap = if ge { F::ZERO } else { two_pow_64 };
+                            F::from(x) - F::from(lo) + wrap
+                        })
+                    },
+                )?;
+
+                region.assign_advice(
+                    || "lt",
+                    self.config.check_column,
+                    2,
+                    || lt.map(|lt| F::from(lt as u64)),
+                )?;
+                let d_hi_cell = region.assign_advice(
+                    || "d_hi",
+                    self.config.x_column,
+                    2,
+                    || {
+                        x.zip(lt).map(|(x, lt)| {
+                            let wrap = if lt { F::ZERO } else { two_pow_64 };
+                            F::from(hi) - F::ONE - F::from(x) + wrap
+                        })
+                    },
+                )?;
+
+                Ok((out_cell, d_lo_cell, d_hi_cell))
+            },
+        )?;
+
+        // d_lo, d_hi ∈ [0, 2^64)
+        self.decompose_cell(layouter.namespace(|| "d_lo range"), &d_lo_cell)?;
+        self.decompose_cell(layouter.namespace(|| "d_hi range"), &d_hi_cell)?;
+
+        Ok(out_cell)
+    }
+
+    /// x < t check with a public threshold
+    /// `threshold` is an assigned cell, usually bound to the instance column
+    /// (`PoneglyphConfig::bind_public_input`), so the threshold is not part of
+    /// the verifying key
+    ///
+    /// # Constraints
+    ///
+    /// - `check` boolean
+    /// - `d = t - 1 - x + (1 - check) · 2^64 ∈ [0, 2^64)`
+    /// - `x ∈ [0, 2^64)`
+    ///
+    /// # Return Value
+    ///
+    /// Boolean check cell (1 = x < t, 0 = x >= t)
+    pub fn check_less_than_public(
+        &self,
+        layouter: impl Layouter<F>,
+        x: Value<u64>,
+        threshold: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.less_than_cell(layouter, x, None, threshold)
+    }
+
+    /// x < t check where both sides are assigned cells
+    /// (e.g. a column value against a subquery result, or the reverse)
+    /// Same gate as `check_less_than_public`, x is copy-constrained to its source
+    ///
+    /// # Return Value
+    ///
+    /// Boolean check cell (1 = x < t, 0 = x >= t)
+    pub fn check_cell_less_than(
+        &self,
+        layouter: impl Layouter<F>,
+        x: &AssignedCell<F, F>,
+        threshold: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let value = x.value().map(field_to_u64);
+        self.less_than_cell(layouter, value, Some(x), threshold)
+    }
+
+    fn less_than_cell(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Value<u64>,
+        x_source: Option<&AssignedCell<F, F>>,
+        threshold: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let two_pow_64 = F::from(u64::MAX) + F::ONE;
+        let t = threshold.value().map(field_to_u64);
+        let check = x.zip(t).map(|(x, t)| x < t);
+
+        let (x_cell, check_cell, d_cell) = layouter.assign_region(
+            || "check x < public t",
+            |mut region| {
+                self.config.public_less_than_selector.enable(&mut region, 0)?;
+
+                let x_cell = region.assign_advice(
+                    || "x",
+                    self.config.x_column,
+                    0,
+                    || x.map(F::from),
+                )?;
+                if let Some(source) = x_source {
+                    region.constrain_equal(source.cell(), x_cell.cell())?;
+                }
+                threshold.copy_advice(
+                    || "threshold",
+                    &mut region,
+                    self.config.public_threshold_column,
+                    0,
+                )?;
+                let check_cell = region.assign_advice(
+                    || "check",
+                    self.config.check_column,
+                    0,
+                    || check.map(|c| F::from(c as u64)),
+                )?;
+                let d_cell = region.assign_advice(
+                    || "d",
+                    self.config.x_column,
+                    1,
+                    || {
+                        x.zip(t).zip(check).map(|((x, t), c)| {
+                            let wrap = if c { F::ZERO } else { two_pow_64 };
+                            F::from(t) - F::ONE - F::from(x) + wrap
+                        })
+                    },
+                )?;
+
+                Ok((x_cell, check_cell, d_cell))
+            },
+        )?;
+
+        // x, d ∈ [0, 2^64)
+        self.decompose_cell(layouter.namespace(|| "x range"), &x_cell)?;
+        self.decompose_cell(layouter.namespace(|| "d range"), &d_cell)?;
+
+        Ok(check_cell)
+    }
+
+    /// Signed x < t check
+    /// Offset encoding (see `signed.rs`) maps i64 to u64 preserving order,
+    /// so `x < t` ⇔ `enc(x) < enc(t)` and the unsigned gate is reused as is
+    ///
+    /// # Note
+    ///
+    /// - The x cell holds `enc(x)`, not the field representation of x
+    /// - `u` is the same comparison window as in `check_less_than`
+    ///
+    /// # Return Value
+    ///
+    /// Boolean check cell (1 = x < t, 0 = x >= t)
+    pub fn check_less_than_signed(
+        &self,
+        layouter: impl Layouter<F>,
+        x: Value<i64>,
+        threshold: i64,
+        u: u64,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.check_less_than(
+            layouter,
+            x.map(super::signed::encode_signed),
+            super::signed::encode_signed(threshold),
+            u,
+        )
+    }
+
+    /// Decompose a signed 64-bit value into 8-bit chunks
+    /// The offset-encoded value is decomposed, which proves `x ∈ [-2^63, 2^63)`
+    ///
+    /// # Return Value
+    ///
+    /// 8 chunk cells of `enc(x)` (each 8-bit)
+    pub fn decompose_signed(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<i64>,
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        self.decompose_64bit(layouter, value.map(super::signed::encode_signed))
+    }
+
+    /// Range check an assigned signed cell as a valid i64
+    /// The cell holds the field representation of v (negative v is `-|v|`, see
+    /// `signed_to_field`); it is copied into one row with the 8 chunks of
+    /// `enc(v) = v + 2^63`, which proves `v ∈ [-2^63, 2^63)`
+    ///
+    /// # Return Value
+    ///
+    /// 8 chunk cells of `enc(v)` (each 8-bit)
+    pub fn decompose_signed_cell(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<[AssignedCell<F, F>; 8], Error> {
+        layouter.assign_region(
+            || "decompose signed cell",
+            |mut region| {
+                cell.copy_advice(|| "value", &mut region, self.config.x_column, 0)?;
+                self.config.signed_decomposition_selector.enable(&mut region, 0)?;
+                self.config.selector.enable(&mut region, 0)?;
+
+                // enc(v) is computed in the field; an out-of-range cell is
+                // truncated to 64 bits and its chunks don't sum up
+                let offset = F::from(super::signed::SIGNED_OFFSET);
+                let encoded = cell.value().map(|v| field_to_u64(&(*v + offset)));
+                let mut chunks = Vec::with_capacity(8);
+                for (i, chunk_col) in self.config.chunk_columns.iter().enumerate() {
+                    chunks.push(region.assign_advice(
+                        || format!("chunk_{}", i),
+                        *chunk_col,
+                        0,
+                        || encoded.map(|v| F::from((v >> (i * 8)) & 0xFF)),
+                    )?);
+                }
+
+                Ok(chunks.try_into().unwrap())
+            },
+        )
+    }
+
     /// Simple range check: check that value is in a certain range
     pub fn check_range(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         value: Value<u64>,
         _min: u64,
         _max: u64,
@@ -477,3 +1180,17 @@ impl RangeCheckChip {
         Ok(())
     }
 }
+
+/// Read the low 64 bits of a field element
+/// (little-endian representation, as used by the pasta and bn254 fields)
+///
+/// # Note
+///
+/// Values >= 2^64 are truncated; when used as a decomposition witness the
+/// decomposition sum constraint then fails, which is the intended behaviour.
+pub(crate) fn field_to_u64<F: PrimeField>(value: &F) -> u64 {
+    let repr = value.to_repr();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&repr.as_ref()[..8]);
+    u64::from_le_bytes(bytes)
+}
@@ -0,0 +1,334 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::merkle::{MerkleChip, MerkleConfig};
+use super::poseidon::{PoseidonChip, PoseidonConfig};
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+
+/// Instance row of the sorted index root
+pub const SCAN_INSTANCE_ROOT_ROW: usize = 0;
+/// Instance row of the lowest qualifying value (inclusive)
+pub const SCAN_INSTANCE_LOW_ROW: usize = 1;
+/// Instance row of the highest qualifying value (inclusive)
+pub const SCAN_INSTANCE_HIGH_ROW: usize = 2;
+/// Instance row of the index position just below the candidate range
+pub const SCAN_INSTANCE_LOWER_POSITION_ROW: usize = 3;
+/// Instance row of the index position just above the candidate range
+pub const SCAN_INSTANCE_UPPER_POSITION_ROW: usize = 4;
+
+/// Side of the scan range a skipped entry lies on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanSide {
+    /// `value < low`
+    Below,
+    /// `value > high`
+    Above,
+}
+
+/// Scan Exclusion Gate Configuration
+/// Proves that an index entry falls outside the scanned range
+///
+/// # Column Allocation
+///
+/// - `value_column`: Entry value v (advice[10])
+/// - `bound_column`: Range bound b, copied from the instance (advice[11])
+/// - `gap_column`: Distance to the bound (advice[12])
+///
+/// # Constraints
+///
+/// 1. **Below**: `gap = b - 1 - v`
+/// 2. **Above**: `gap = v - b - 1`
+///
+/// v and gap are decomposed, so `v < b` (below) or `v > b` (above)
+///
+/// # Note
+///
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct ScanConfig {
+    pub value_column: Column<Advice>,
+    pub bound_column: Column<Advice>,
+    pub gap_column: Column<Advice>,
+
+    // Selectors
+    pub below_selector: Selector,
+    pub above_selector: Selector,
+
+    // Range Check integration (gap ≥ 0)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Scan Chip
+/// Excludes the boundary entries of a pushed-down index scan
+pub struct ScanChip<F: PrimeField = Fr> {
+    config: ScanConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> ScanChip<F> {
+    /// Create a new ScanChip
+    pub fn new(config: ScanConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Scan Exclusion Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> ScanConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-12]: shared with Join Gate
+        let value_column = config.advice[10];
+        let bound_column = config.advice[11];
+        let gap_column = config.advice[12];
+
+        let below_selector = meta.selector();
+        let above_selector = meta.selector();
+
+        meta.create_gate("scan exclusion", |meta| {
+            let below = meta.query_selector(below_selector);
+            let above = meta.query_selector(above_selector);
+            let v = meta.query_advice(value_column, Rotation::cur());
+            let b = meta.query_advice(bound_column, Rotation::cur());
+            let gap = meta.query_advice(gap_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                below * (gap.clone() - (b.clone() - one.clone() - v.clone())),
+                above * (gap - (v - b - one)),
+            ]
+        });
+
+        ScanConfig {
+            value_column,
+            bound_column,
+            gap_column,
+            below_selector,
+            above_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Prove that `value` lies on `side` of `bound`
+    ///
+    /// # Errors
+    ///
+    /// `Error::Synthesis` if the (known) value is inside the range
+    pub fn exclude(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        bound: &AssignedCell<F, F>,
+        side: ScanSide,
+    ) -> Result<(), Error> {
+        let gap = value.value().zip(bound.value()).map(|(&v, &b)| match side {
+            ScanSide::Below => b - F::ONE - v,
+            ScanSide::Above => v - b - F::ONE,
+        });
+        // A gap beyond 64 bits is a wrapped negative: the value is in range
+        gap.error_if_known_and(|g| g.to_repr().as_ref()[8..].iter().any(|&byte| byte != 0))?;
+
+        let (value_cell, gap_cell) = layouter.assign_region(
+            || "scan exclusion",
+            |mut region| {
+                match side {
+                    ScanSide::Below => self.config.below_selector.enable(&mut region, 0)?,
+                    ScanSide::Above => self.config.above_selector.enable(&mut region, 0)?,
+                }
+                let value_cell =
+                    value.copy_advice(|| "value", &mut region, self.config.value_column, 0)?;
+                bound.copy_advice(|| "bound", &mut region, self.config.bound_column, 0)?;
+                let gap_cell = region.assign_advice(|| "gap", self.config.gap_column, 0, || gap)?;
+                Ok((value_cell, gap_cell))
+            },
+        )?;
+
+        // v, gap ≥ 0 (64-bit) ⇒ the bound comparison holds over the integers
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        range_check_chip.decompose_cell(layouter.namespace(|| "scan value"), &value_cell)?;
+        range_check_chip.decompose_cell(layouter.namespace(|| "scan gap"), &gap_cell)?;
+        Ok(())
+    }
+}
+
+/// Witness of one sorted index entry
+#[derive(Clone, Debug)]
+pub struct IndexEntryWitness {
+    pub position: Value<u64>,
+    pub value: Value<u64>,
+    pub row: Value<u64>,
+    /// From the leaf level up (`depth` siblings)
+    pub siblings: Vec<Value<Fr>>,
+}
+
+impl IndexEntryWitness {
+    fn unknown(depth: usize) -> Self {
+        Self {
+            position: Value::unknown(),
+            value: Value::unknown(),
+            row: Value::unknown(),
+            siblings: vec![Value::unknown(); depth],
+        }
+    }
+}
+
+/// Index Scan Circuit
+/// Proves that the entries of a sorted index outside a candidate range don't
+/// qualify, so a scan may skip them
+///
+/// # Instance
+///
+/// - row 0: index root
+/// - rows 1-2: scanned range `[low, high]`
+/// - rows 3-4: positions of the entries just below and just above the
+///   candidates (0 if there is none)
+///
+/// Each boundary entry is opened against the root at its position (leaf
+/// `poseidon([value, row])`) and excluded from the range. The index is sorted,
+/// so every entry before the lower boundary is below `low` and every entry
+/// after the upper boundary is above `high`.
+#[derive(Clone, Debug)]
+pub struct IndexScanCircuit {
+    pub depth: usize,
+    pub lower: Option<IndexEntryWitness>,
+    pub upper: Option<IndexEntryWitness>,
+}
+
+impl IndexScanCircuit {
+    /// Circuit without witnesses (for key generation)
+    pub fn shape(depth: usize, has_lower: bool, has_upper: bool) -> Self {
+        Self {
+            depth,
+            lower: has_lower.then(|| IndexEntryWitness::unknown(depth)),
+            upper: has_upper.then(|| IndexEntryWitness::unknown(depth)),
+        }
+    }
+}
+
+/// Config of `IndexScanCircuit`
+#[derive(Clone, Debug)]
+pub struct IndexScanConfig {
+    pub poneglyph_config: PoneglyphConfig,
+    pub poseidon_config: PoseidonConfig,
+    pub merkle_config: MerkleConfig,
+    pub scan_config: ScanConfig,
+}
+
+impl Circuit<Fr> for IndexScanCircuit {
+    type Config = IndexScanConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::shape(self.depth, self.lower.is_some(), self.upper.is_some())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let poseidon_config = PoseidonChip::configure(meta, &poneglyph_config);
+        let merkle_config = MerkleChip::configure(meta, &poneglyph_config, &poseidon_config);
+        let scan_config = ScanChip::configure(meta, &poneglyph_config, &range_check_config);
+        IndexScanConfig {
+            poneglyph_config,
+            poseidon_config,
+            merkle_config,
+            scan_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        let instance = config.poneglyph_config.instance;
+        let merkle = MerkleChip::new(config.merkle_config.clone());
+        let poseidon = PoseidonChip::new(config.poseidon_config.clone());
+        let scan = ScanChip::new(config.scan_config.clone());
+
+        let boundaries = [
+            (
+                &self.lower,
+                ScanSide::Below,
+                SCAN_INSTANCE_LOW_ROW,
+                SCAN_INSTANCE_LOWER_POSITION_ROW,
+            ),
+            (
+                &self.upper,
+                ScanSide::Above,
+                SCAN_INSTANCE_HIGH_ROW,
+                SCAN_INSTANCE_UPPER_POSITION_ROW,
+            ),
+        ];
+        for (entry, side, bound_row, position_row) in boundaries {
+            let Some(entry) = entry else { continue };
+            let name = match side {
+                ScanSide::Below => "lower",
+                ScanSide::Above => "upper",
+            };
+
+            let (position, bits) = merkle.decompose_index(
+                layouter.namespace(|| format!("{} position", name)),
+                entry.position,
+                self.depth,
+            )?;
+            layouter.constrain_instance(position.cell(), instance, position_row)?;
+
+            let (value, row, bound) = layouter.assign_region(
+                || format!("{} entry", name),
+                |mut region| {
+                    let column = config.merkle_config.swap[1];
+                    let value = region.assign_advice(
+                        || "value",
+                        column,
+                        0,
+                        || entry.value.map(Fr::from),
+                    )?;
+                    let row =
+                        region.assign_advice(|| "row", column, 1, || entry.row.map(Fr::from))?;
+                    let bound = region.assign_advice_from_instance(
+                        || "bound",
+                        instance,
+                        bound_row,
+                        column,
+                        2,
+                    )?;
+                    Ok((value, row, bound))
+                },
+            )?;
+
+            let leaf = poseidon.hash(
+                layouter.namespace(|| format!("{} leaf", name)),
+                &[value.clone(), row],
+            )?;
+            let root = merkle.root(
+                layouter.namespace(|| format!("{} path", name)),
+                &leaf,
+                &bits,
+                &entry.siblings,
+            )?;
+            layouter.constrain_instance(root.cell(), instance, SCAN_INSTANCE_ROOT_ROW)?;
+
+            scan.exclude(
+                layouter.namespace(|| format!("{} exclusion", name)),
+                &value,
+                &bound,
+                side,
+            )?;
+        }
+        Ok(())
+    }
+}
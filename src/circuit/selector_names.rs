@@ -0,0 +1,39 @@
+// Selector names for the constraint reports (`docs`, `formal`)
+// halo2_proofs does not expose a selector's index, so selectors are numbered
+// in the order a report first meets them: `s0`, `s1`, ... Reports walk gates
+// in configuration order, so the numbering is the same on every run.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use halo2_proofs::plonk::Selector;
+
+/// Names of the selectors met so far
+#[derive(Debug, Default)]
+pub(crate) struct SelectorNames(RefCell<HashMap<Selector, usize>>);
+
+impl SelectorNames {
+    /// `s<n>`, numbering `selector` if it's new
+    pub(crate) fn name(&self, selector: Selector) -> String {
+        let mut names = self.0.borrow_mut();
+        let next = names.len();
+        format!("s{}", names.entry(selector).or_insert(next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::plonk::ConstraintSystem;
+    use pasta_curves::pallas::Base as Fr;
+
+    #[test]
+    fn test_first_seen_numbering() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let (a, b) = (meta.selector(), meta.complex_selector());
+        let names = SelectorNames::default();
+        assert_eq!(names.name(b), "s0");
+        assert_eq!(names.name(a), "s1");
+        assert_eq!(names.name(b), "s0");
+    }
+}
@@ -0,0 +1,223 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use super::SeriesOp;
+
+/// Series Gate Configuration
+/// Generates the rows of `generate_series(start, stop, step)`
+///
+/// # Column Allocation
+///
+/// - `value_column`: Series values, one per row (advice[10])
+/// - `below_column`: `stop - last` (advice[11])
+/// - `above_column`: `last + step - 1 - stop` (advice[12])
+/// - `constant_column`: start, then step on every other row, then stop
+///   (fixed[0])
+/// - `bound_step_column`: step on the bound row (fixed[1])
+///
+/// # Constraints
+///
+/// 1. **Start**: `v = start` on the first row
+/// 2. **Step**: `v = v_prev + step` on every other row
+/// 3. **Bound**: on the row after the last value `below = stop - v_prev`
+///    and `above = v_prev + step - 1 - stop`, both in `[0, 2^64)` (64-bit
+///    decomposition), i.e. `v_last <= stop < v_last + step`
+///
+/// The constants are fixed columns, so the verifying key pins the series:
+/// (1) and (2) leave no freedom in the values and (3) proves the row count
+/// matches the bound.
+///
+/// # Note
+///
+/// - Ascending series of u64 values only (`step >= 1`); an empty series
+///   (`start > stop`) assigns nothing
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct SeriesConfig {
+    pub value_column: Column<Advice>,
+    pub below_column: Column<Advice>,
+    pub above_column: Column<Advice>,
+    pub constant_column: Column<Fixed>,
+    pub bound_step_column: Column<Fixed>,
+
+    // Selectors
+    pub start_selector: Selector,
+    pub step_selector: Selector,
+    pub bound_selector: Selector,
+
+    // Range Check integration (bound gaps ≥ 0)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Series Chip
+/// Table-valued `generate_series` as a constrained column
+pub struct SeriesChip<F: PrimeField = Fr> {
+    config: SeriesConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> SeriesChip<F> {
+    /// Create a new SeriesChip
+    pub fn new(config: SeriesConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Series Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> SeriesConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-12]: shared with Join Gate
+        // - fixed[0-1]: shared with Range Check thresholds (different rows)
+        let value_column = config.advice[10];
+        let below_column = config.advice[11];
+        let above_column = config.advice[12];
+        let constant_column = config.fixed[0];
+        let bound_step_column = config.fixed[1];
+
+        let start_selector = meta.selector();
+        let step_selector = meta.selector();
+        let bound_selector = meta.selector();
+
+        meta.create_gate("series start", |meta| {
+            let s = meta.query_selector(start_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let start = meta.query_fixed(constant_column);
+
+            vec![s * (value - start)]
+        });
+
+        meta.create_gate("series step", |meta| {
+            let s = meta.query_selector(step_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let value_prev = meta.query_advice(value_column, Rotation::prev());
+            let step = meta.query_fixed(constant_column);
+
+            vec![s * (value - value_prev - step)]
+        });
+
+        // Bound row follows the last value
+        meta.create_gate("series bound", |meta| {
+            let s = meta.query_selector(bound_selector);
+            let last = meta.query_advice(value_column, Rotation::prev());
+            let stop = meta.query_fixed(constant_column);
+            let step = meta.query_fixed(bound_step_column);
+            let below = meta.query_advice(below_column, Rotation::cur());
+            let above = meta.query_advice(above_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![
+                s.clone() * (below - (stop.clone() - last.clone())),
+                s * (above - (last + step - one - stop)),
+            ]
+        });
+
+        SeriesConfig {
+            value_column,
+            below_column,
+            above_column,
+            constant_column,
+            bound_step_column,
+            start_selector,
+            step_selector,
+            bound_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Assign the series
+    ///
+    /// # Return Value
+    ///
+    /// Value cells in series order (copy them into the operators reading the
+    /// series)
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: &SeriesOp,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if op.step == 0 {
+            return Err(Error::Synthesis);
+        }
+        let values = op.values();
+        let Some(&last) = values.last() else {
+            return Ok(Vec::new());
+        };
+
+        let (cells, below, above) = layouter.assign_region(
+            || "generate_series",
+            |mut region| {
+                let mut cells = Vec::with_capacity(values.len());
+                for (row, &value) in values.iter().enumerate() {
+                    let constant = if row == 0 {
+                        self.config.start_selector.enable(&mut region, row)?;
+                        op.start
+                    } else {
+                        self.config.step_selector.enable(&mut region, row)?;
+                        op.step
+                    };
+                    region.assign_fixed(
+                        || "series constant",
+                        self.config.constant_column,
+                        row,
+                        || Value::known(F::from(constant)),
+                    )?;
+                    cells.push(region.assign_advice(
+                        || "series value",
+                        self.config.value_column,
+                        row,
+                        || Value::known(F::from(value)),
+                    )?);
+                }
+
+                let bound_row = values.len();
+                self.config.bound_selector.enable(&mut region, bound_row)?;
+                region.assign_fixed(
+                    || "series stop",
+                    self.config.constant_column,
+                    bound_row,
+                    || Value::known(F::from(op.stop)),
+                )?;
+                region.assign_fixed(
+                    || "series step",
+                    self.config.bound_step_column,
+                    bound_row,
+                    || Value::known(F::from(op.step)),
+                )?;
+                let below = region.assign_advice(
+                    || "stop - last",
+                    self.config.below_column,
+                    bound_row,
+                    || Value::known(F::from(op.stop - last)),
+                )?;
+                let above = region.assign_advice(
+                    || "last + step - 1 - stop",
+                    self.config.above_column,
+                    bound_row,
+                    || Value::known(F::from(last) + F::from(op.step) - F::ONE - F::from(op.stop)),
+                )?;
+                Ok((cells, below, above))
+            },
+        )?;
+
+        let range_check = RangeCheckChip::new(self.config.range_check_config.clone());
+        range_check.decompose_cell(layouter.namespace(|| "series below"), &below)?;
+        range_check.decompose_cell(layouter.namespace(|| "series above"), &above)?;
+
+        Ok(cells)
+    }
+}
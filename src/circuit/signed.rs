@@ -0,0 +1,86 @@
+use ff::PrimeField;
+
+/// Signed Value Encoding
+/// Offset (biased) encoding for `i64` values used by the circuit gates
+///
+/// # Encoding
+///
+/// `enc(v) = v + 2^63` (computed as `(v as u64) ^ 2^63`)
+///
+/// - `i64::MIN` maps to `0`, `0` maps to `2^63`, `i64::MAX` maps to `2^64 - 1`
+/// - The mapping is order-preserving: `a < b` ⇔ `enc(a) < enc(b)`
+/// - Encoded values are plain `u64`, so the existing 8-bit decomposition,
+///   `x < t` comparison and sort order constraints apply unchanged
+///
+/// # Note
+///
+/// - Order-based gates (range check, sort, MAX/MIN) work on encoded values
+/// - Additive gates (SUM) work on the field representation instead, where a
+///   negative value `v` is the field element `-|v|` (see `signed_to_field`)
+pub const SIGNED_OFFSET: u64 = 1 << 63;
+
+/// Encode a signed value into its order-preserving `u64` representation
+pub fn encode_signed(value: i64) -> u64 {
+    (value as u64) ^ SIGNED_OFFSET
+}
+
+/// Decode an order-preserving `u64` representation back into a signed value
+pub fn decode_signed(encoded: u64) -> i64 {
+    (encoded ^ SIGNED_OFFSET) as i64
+}
+
+/// Convert a signed value into a field element (negative values are `p - |v|`)
+pub fn signed_to_field<F: PrimeField>(value: i64) -> F {
+    signed_wide_to_field(value as i128)
+}
+
+/// Convert a wide signed value (e.g. a running sum) into a field element
+pub fn signed_wide_to_field<F: PrimeField>(value: i128) -> F {
+    let magnitude = value.unsigned_abs();
+    let low = F::from(magnitude as u64);
+    let high = F::from((magnitude >> 64) as u64);
+    let field = high * F::from(2u64).pow_vartime([64]) + low;
+    if value < 0 {
+        -field
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+    use pasta_curves::pallas::Base as Fr;
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        for v in [i64::MIN, -1_000, -1, 0, 1, 1_000, i64::MAX] {
+            assert_eq!(decode_signed(encode_signed(v)), v);
+        }
+    }
+
+    #[test]
+    fn test_encoding_preserves_order() {
+        let values = [i64::MIN, -42, -1, 0, 1, 42, i64::MAX];
+        for pair in values.windows(2) {
+            assert!(encode_signed(pair[0]) < encode_signed(pair[1]));
+        }
+        assert_eq!(encode_signed(i64::MIN), 0);
+        assert_eq!(encode_signed(0), SIGNED_OFFSET);
+    }
+
+    #[test]
+    fn test_signed_to_field() {
+        assert_eq!(signed_to_field::<Fr>(5), Fr::from(5));
+        assert_eq!(signed_to_field::<Fr>(-5), -Fr::from(5));
+        assert_eq!(
+            signed_to_field::<Fr>(-5) + signed_to_field::<Fr>(5),
+            Fr::ZERO
+        );
+        assert_eq!(
+            signed_wide_to_field::<Fr>(i64::MIN as i128 * 2),
+            -Fr::from(SIGNED_OFFSET) * Fr::from(2)
+        );
+    }
+}
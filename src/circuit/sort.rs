@@ -1,3 +1,6 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
     plonk::{Advice, Column, ConstraintSystem, Error, Selector},
@@ -55,20 +58,24 @@ pub struct SortConfig {
 
 /// Sort Chip
 /// Paper Section 4.2 implementation
-pub struct SortChip {
+pub struct SortChip<F: PrimeField = Fr> {
     config: SortConfig,
+    _marker: PhantomData<F>,
 }
 
-impl SortChip {
+impl<F: PrimeField> SortChip<F> {
     /// Create a new SortChip
     pub fn new(config: SortConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            _marker: PhantomData,
+        }
     }
     
     /// Configure the Sort Gate
     /// Paper Section 4.2: Grand Product Argument and sorting check
     pub fn configure(
-        meta: &mut ConstraintSystem<Fr>,
+        meta: &mut ConstraintSystem<F>,
         config: &PoneglyphConfig,
         range_check_config: &RangeCheckConfig,
     ) -> SortConfig {
@@ -139,10 +146,15 @@ impl SortChip {
     /// List of output cells (cells of sorted array)
     pub fn sort_and_verify(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         input: Vec<Value<u64>>,
         sorted_values: Vec<u64>,
-    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        // Permutation witness: field encoding of the sorted values and the
+        // adjacent diffs (computed in parallel with the `parallel` feature)
+        let sorted_fields: Vec<F> = super::witness::to_field(&sorted_values);
+        let diffs = super::witness::adjacent_diffs(&sorted_values);
+
         // 1. Assign input
         let _input_cells = self.assign_input(layouter.namespace(|| "input"), &input)?;
         
@@ -154,10 +166,10 @@ impl SortChip {
         // Note: We assign sorted_input_cells to input column (in rows after input)
         // This way, input and sorted_input are in the same column but different rows
         // and we can compare sorted_input with output using constrain_equal
-        let sorted_input_cells: Vec<AssignedCell<Fr, Fr>> = layouter.assign_region(
+        let sorted_input_cells: Vec<AssignedCell<F, F>> = layouter.assign_region(
             || "sorted input assignment",
             |mut region| {
-                sorted_values
+                sorted_fields
                     .iter()
                     .enumerate()
                     .map(|(i, val)| {
@@ -165,7 +177,7 @@ impl SortChip {
                             || format!("sorted_input_{}", i),
                             self.config.input_column, // Reuse input column (in different rows)
                             input.len() + i, // Assign to rows after input
-                            || Value::known(Fr::from(*val)),
+                            || Value::known(*val),
                         )
                     })
                     .collect()
@@ -181,28 +193,27 @@ impl SortChip {
             |mut region| {
                 // Assign output
                 let mut cells = Vec::new();
-                for (i, val) in sorted_values.iter().enumerate() {
+                for (i, val) in sorted_fields.iter().enumerate() {
                     let cell = region.assign_advice(
                         || format!("output_{}", i),
                         self.config.output_column,
                         i,
-                        || Value::known(Fr::from(*val)),
+                        || Value::known(*val),
                     )?;
                     cells.push(cell);
                     
                     // Enable sorting constraint (except last row)
                     // Paper Section 4.2: B[i] ≤ B[i+1] check
-                    if i < sorted_values.len() - 1 {
+                    if let Some(&diff_value) = diffs.get(i) {
                         self.config.sort_selector.enable(&mut region, i)?;
                         
-                        // Calculate and assign diff = B[i+1] - B[i]
+                        // Assign diff = B[i+1] - B[i]
                         // Constraint will check diff = b_i_next - b_i
-                        let diff_value = sorted_values[i + 1] - sorted_values[i];
                         region.assign_advice(
                             || format!("diff_{}", i),
                             self.config.diff_column,
                             i,
-                            || Value::known(Fr::from(diff_value)),
+                            || Value::known(F::from(diff_value)),
                         )?;
                     }
                 }
@@ -220,13 +231,8 @@ impl SortChip {
         // - This guarantees that diff is a valid 64-bit non-negative integer
         use super::range_check::RangeCheckChip;
         let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
-        for i in 0..sorted_values.len() - 1 {
-            let diff_value = sorted_values[i + 1] - sorted_values[i];
-            let _diff_chunks = range_check_chip.decompose_64bit(
-                layouter.namespace(|| format!("decompose diff_{}", i)),
-                Value::known(diff_value),
-            )?;
-        }
+        let _diff_chunks = range_check_chip
+            .decompose_64bit_rows(layouter.namespace(|| "decompose diffs"), &diffs)?;
         
         // 4. Permutation constraints (Grand Product Argument)
         // Paper Section 4.2: Prove that input and output have the same multiset
@@ -240,12 +246,32 @@ impl SortChip {
         Ok(output_cells)
     }
     
+    /// Sort signed array and verify
+    /// Input and witness are offset-encoded (see `signed.rs`) before being
+    /// assigned, so `B[i] ≤ B[i+1]` on encoded values is exactly the signed order
+    ///
+    /// # Return Value
+    ///
+    /// List of output cells (offset-encoded values of the sorted array)
+    pub fn sort_and_verify_signed(
+        &self,
+        layouter: impl Layouter<F>,
+        input: Vec<Value<i64>>,
+        sorted_values: Vec<i64>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        use super::signed::encode_signed;
+
+        let encoded_input = input.into_iter().map(|v| v.map(encode_signed)).collect();
+        let encoded_sorted = sorted_values.into_iter().map(encode_signed).collect();
+        self.sort_and_verify(layouter, encoded_input, encoded_sorted)
+    }
+
     /// Assign input array
     fn assign_input(
         &self,
-        mut layouter: impl Layouter<Fr>,
+        mut layouter: impl Layouter<F>,
         input: &[Value<u64>],
-    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
         layouter.assign_region(
             || "input assignment",
             |mut region| {
@@ -257,7 +283,7 @@ impl SortChip {
                             || format!("input_{}", i),
                             self.config.input_column,
                             i,
-                            || val.map(|v| Fr::from(v)),
+                            || val.map(|v| F::from(v)),
                         )
                     })
                     .collect()
@@ -287,9 +313,9 @@ impl SortChip {
     /// This provides permutation verification with Grand Product Argument.
     fn enable_permutation(
         &self,
-        mut layouter: impl Layouter<Fr>,
-        sorted_input_cells: &[AssignedCell<Fr, Fr>],
-        output_cells: &[AssignedCell<Fr, Fr>],
+        mut layouter: impl Layouter<F>,
+        sorted_input_cells: &[AssignedCell<F, F>],
+        output_cells: &[AssignedCell<F, F>],
     ) -> Result<(), Error> {
         // Permutation verification with Grand Product Argument:
         // 
@@ -0,0 +1,371 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::aggregation::AggregationChip;
+use super::boolean::{BooleanChip, BooleanConfig};
+use super::config::PoneglyphConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use super::{AggregationOp, SubqueryOp};
+
+/// Outer filter fed by a subquery result
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubqueryFilter {
+    /// column < (SELECT agg ...)
+    LessThan,
+    /// column > (SELECT agg ...)
+    GreaterThan,
+    /// column = (SELECT agg ...)
+    Equal,
+    /// column IN (SELECT col ...)
+    In,
+}
+
+impl SubqueryFilter {
+    /// Reference (out-of-circuit) filter over one row
+    /// Scalar filters use `result[0]`; an empty IN list selects nothing
+    pub fn evaluate(&self, value: u64, result: &[u64]) -> Option<bool> {
+        match self {
+            SubqueryFilter::LessThan => result.first().map(|&r| value < r),
+            SubqueryFilter::GreaterThan => result.first().map(|&r| value > r),
+            SubqueryFilter::Equal => result.first().map(|&r| value == r),
+            SubqueryFilter::In => Some(result.contains(&value)),
+        }
+    }
+}
+
+/// Subquery Gate Configuration
+/// Uses the result of an inner query segment as a constrained input of an
+/// outer filter: the result cells are copied, never re-witnessed
+///
+/// # Column Allocation
+///
+/// - `value_column`: Outer row value (advice[10])
+/// - `item_column`: Subquery result item (advice[11])
+/// - `product_column`: Running product `Π (value - item)` (advice[12])
+/// - `selected_column`: 1 = row passes the filter (advice[13])
+/// - `inverse_column`: Product inverse for the is-zero check (advice[14])
+///
+/// # Constraints
+///
+/// 1. **Scalar comparison**: `value < r` / `r < value` with the Range Check
+///    gate over two cells (`check_cell_less_than`); `=` is
+///    `NOT (value < r) AND NOT (r < value)` with the Boolean Gate
+/// 2. **Membership start**: `p = value - item`
+/// 3. **Membership step**: `value = value_prev`, `p = p_prev · (value - item)`
+/// 4. **Membership result**: `selected · p = 0`, `p · inverse = 1 - selected`
+///    (selected = 1 iff some item equals the value, both ways proven)
+///
+/// # Note
+///
+/// - Scalar results come from the inner aggregation (`scalar_result`)
+/// - IN-list items are assigned from the inner result (`assign_list`); the
+///   inner filter is proven by its own segment, the link from its selected
+///   rows to the items is not covered
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct SubqueryConfig {
+    pub value_column: Column<Advice>,
+    pub item_column: Column<Advice>,
+    pub product_column: Column<Advice>,
+    pub selected_column: Column<Advice>,
+    pub inverse_column: Column<Advice>,
+
+    // Selectors
+    pub start_selector: Selector,
+    pub step_selector: Selector,
+    pub result_selector: Selector,
+
+    // Range Check integration (scalar comparisons)
+    pub range_check_config: RangeCheckConfig,
+
+    // Boolean integration (`=` from two comparisons)
+    pub boolean_config: BooleanConfig,
+}
+
+/// Subquery Chip
+/// Outer filters over uncorrelated subquery results
+pub struct SubqueryChip<F: PrimeField = Fr> {
+    config: SubqueryConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> SubqueryChip<F> {
+    /// Create a new SubqueryChip
+    pub fn new(config: SubqueryConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Subquery Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+        boolean_config: &BooleanConfig,
+    ) -> SubqueryConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        let value_column = config.advice[10];
+        let item_column = config.advice[11];
+        let product_column = config.advice[12];
+        let selected_column = config.advice[13];
+        let inverse_column = config.advice[14];
+
+        let start_selector = meta.selector();
+        let step_selector = meta.selector();
+        let result_selector = meta.selector();
+
+        meta.create_gate("subquery membership start", |meta| {
+            let s = meta.query_selector(start_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let item = meta.query_advice(item_column, Rotation::cur());
+            let product = meta.query_advice(product_column, Rotation::cur());
+
+            vec![s * (product - (value - item))]
+        });
+
+        meta.create_gate("subquery membership step", |meta| {
+            let s = meta.query_selector(step_selector);
+            let value = meta.query_advice(value_column, Rotation::cur());
+            let value_prev = meta.query_advice(value_column, Rotation::prev());
+            let item = meta.query_advice(item_column, Rotation::cur());
+            let product = meta.query_advice(product_column, Rotation::cur());
+            let product_prev = meta.query_advice(product_column, Rotation::prev());
+
+            vec![
+                s.clone() * (value.clone() - value_prev),
+                s * (product - product_prev * (value - item)),
+            ]
+        });
+
+        meta.create_gate("subquery membership result", |meta| {
+            let s = meta.query_selector(result_selector);
+            let product = meta.query_advice(product_column, Rotation::cur());
+            let selected = meta.query_advice(selected_column, Rotation::cur());
+            let inverse = meta.query_advice(inverse_column, Rotation::cur());
+
+            vec![
+                s.clone() * selected.clone() * product.clone(),
+                s * (product * inverse - (Expression::Constant(F::ONE) - selected)),
+            ]
+        });
+
+        SubqueryConfig {
+            value_column,
+            item_column,
+            product_column,
+            selected_column,
+            inverse_column,
+            start_selector,
+            step_selector,
+            result_selector,
+            range_check_config: range_check_config.clone(),
+            boolean_config: boolean_config.clone(),
+        }
+    }
+
+    /// Result cell of a scalar subquery (a single aggregate without GROUP BY)
+    /// The inner aggregation is synthesized here; its last running result is
+    /// the aggregate over all rows
+    pub fn scalar_result(
+        &self,
+        mut layouter: impl Layouter<F>,
+        aggregation_chip: &AggregationChip<F>,
+        inner: &AggregationOp,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        // Without GROUP BY all rows form one group
+        let group_keys = if inner.group_keys.is_empty() {
+            vec![0; inner.values.len()]
+        } else {
+            inner.group_keys.clone()
+        };
+        if group_keys.windows(2).any(|w| w[0] != w[1]) {
+            return Err(Error::Synthesis);
+        }
+
+        let results = aggregation_chip.aggregate_and_verify(
+            layouter.namespace(|| "scalar subquery"),
+            &group_keys,
+            &inner.values,
+            &inner.agg_type,
+        )?;
+        results.last().cloned().ok_or(Error::Synthesis)
+    }
+
+    /// Assign the items of an IN subquery result
+    pub fn assign_list(
+        &self,
+        mut layouter: impl Layouter<F>,
+        items: &[u64],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "subquery result",
+            |mut region| {
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &item)| {
+                        region.assign_advice(
+                            || format!("item_{}", i),
+                            self.config.item_column,
+                            i,
+                            || Value::known(F::from(item)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Filter the outer rows against the subquery result cells
+    /// (one cell for scalar filters, the item cells for IN)
+    ///
+    /// # Return Value
+    ///
+    /// Selected flag cells, one per row (1 = row passes the filter)
+    pub fn filter(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: &SubqueryOp,
+        result: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if op.filter == SubqueryFilter::In {
+            return op
+                .values
+                .iter()
+                .enumerate()
+                .map(|(i, &value)| {
+                    self.member(
+                        layouter.namespace(|| format!("membership {}", i)),
+                        value,
+                        result,
+                    )
+                })
+                .collect();
+        }
+
+        let [result] = result else {
+            return Err(Error::Synthesis);
+        };
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        let boolean_chip = BooleanChip::new(self.config.boolean_config.clone());
+
+        let mut selected = Vec::with_capacity(op.values.len());
+        for (i, &value) in op.values.iter().enumerate() {
+            let value_cell = layouter.assign_region(
+                || format!("subquery outer value {}", i),
+                |mut region| {
+                    region.assign_advice(
+                        || "value",
+                        self.config.value_column,
+                        0,
+                        || Value::known(F::from(value)),
+                    )
+                },
+            )?;
+
+            // value < r (LessThan, Equal) and r < value (GreaterThan, Equal)
+            let less = match op.filter {
+                SubqueryFilter::LessThan | SubqueryFilter::Equal => {
+                    Some(range_check_chip.check_cell_less_than(
+                        layouter.namespace(|| format!("value < result {}", i)),
+                        &value_cell,
+                        result,
+                    )?)
+                }
+                _ => None,
+            };
+            let greater = match op.filter {
+                SubqueryFilter::GreaterThan | SubqueryFilter::Equal => {
+                    Some(range_check_chip.check_cell_less_than(
+                        layouter.namespace(|| format!("result < value {}", i)),
+                        result,
+                        &value_cell,
+                    )?)
+                }
+                _ => None,
+            };
+
+            let cell = match (less, greater) {
+                (Some(lt), Some(gt)) => {
+                    let not_lt = boolean_chip.not(layouter.namespace(|| "not less"), &lt)?;
+                    let not_gt = boolean_chip.not(layouter.namespace(|| "not greater"), &gt)?;
+                    boolean_chip.and(layouter.namespace(|| "equal"), &not_lt, &not_gt)?
+                }
+                (Some(cell), None) | (None, Some(cell)) => cell,
+                (None, None) => return Err(Error::Synthesis),
+            };
+            selected.push(cell);
+        }
+        Ok(selected)
+    }
+
+    /// `value IN items` for one row: running product and is-zero check
+    fn member(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: u64,
+        items: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if items.is_empty() {
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "subquery membership",
+            |mut region| {
+                let value = F::from(value);
+                let mut product = Value::known(F::ONE);
+                for (j, item) in items.iter().enumerate() {
+                    if j == 0 {
+                        self.config.start_selector.enable(&mut region, j)?;
+                    } else {
+                        self.config.step_selector.enable(&mut region, j)?;
+                    }
+                    region.assign_advice(
+                        || "value",
+                        self.config.value_column,
+                        j,
+                        || Value::known(value),
+                    )?;
+                    item.copy_advice(|| "item", &mut region, self.config.item_column, j)?;
+
+                    product = product
+                        .zip(item.value())
+                        .map(|(p, item)| p * (value - item));
+                    region.assign_advice(
+                        || "product",
+                        self.config.product_column,
+                        j,
+                        || product,
+                    )?;
+                }
+
+                let last = items.len() - 1;
+                self.config.result_selector.enable(&mut region, last)?;
+                let is_member = product.map(|p| p.is_zero_vartime());
+                region.assign_advice(
+                    || "inverse",
+                    self.config.inverse_column,
+                    last,
+                    || product.map(|p| p.invert().unwrap_or(F::ZERO)),
+                )?;
+                region.assign_advice(
+                    || "selected",
+                    self.config.selected_column,
+                    last,
+                    || is_member.map(|m| F::from(m as u64)),
+                )
+            },
+        )
+    }
+}
@@ -0,0 +1,302 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::group_by::GroupByChip;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use super::sort::SortChip;
+
+/// Reference (out-of-circuit) top-k per group: the k largest values of each
+/// group, largest first (fewer if the group is smaller)
+/// Rows are grouped by key (equal keys adjacent), groups in row order.
+pub fn top_k_per_group(group_keys: &[u64], values: &[u64], k: u64) -> Vec<Vec<u64>> {
+    let mut groups: Vec<Vec<u64>> = Vec::new();
+    for (i, &value) in values.iter().enumerate().take(group_keys.len()) {
+        if i == 0 || group_keys[i] != group_keys[i - 1] {
+            groups.push(Vec::new());
+        }
+        if let Some(group) = groups.last_mut() {
+            group.push(value);
+        }
+    }
+    for group in &mut groups {
+        group.sort_unstable_by(|a, b| b.cmp(a));
+        group.truncate(usize::try_from(k).unwrap_or(usize::MAX));
+    }
+    groups
+}
+
+/// Top-K Gate Configuration
+/// Top k values per group (`ROW_NUMBER() OVER (PARTITION BY g ORDER BY v DESC) <= k`)
+///
+/// Each group is sorted with the Sort Gate; its values are copied into the
+/// top-k rows in descending order, one row per input row, so the row number
+/// within the group is the rank of the value.
+///
+/// # Column Allocation
+///
+/// - `same_column`: 1 = same group as the previous row, copied from the
+///   Group-By boundary cells (advice[10])
+/// - `value_column`: Value, copied from the sorted group (advice[11])
+/// - `row_number_column`: Rank within the group (advice[12])
+/// - `selected_column`: 1 = the row is among the top k (advice[13])
+/// - `slack_column`: Rank slack, range checked (advice[14])
+/// - `limit_column`: k (fixed[0])
+///
+/// # Constraints
+///
+/// 1. **Start** (first row): `rn = 1`
+/// 2. **Step**: `rn[i] = same · rn[i-1] + 1` (reset at group boundaries)
+/// 3. **Limit**: `selected ∈ {0, 1}`,
+///    `slack = selected · (k - rn) + (1 - selected) · (rn - k - 1)`
+///
+/// The slack is decomposed into 8-bit chunks, so `selected = 1` iff `rn ≤ k`.
+///
+/// # Note
+///
+/// - Group keys must be grouped (equal keys adjacent), as for the Group-By Gate
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct TopKConfig {
+    pub same_column: Column<Advice>,
+    pub value_column: Column<Advice>,
+    pub row_number_column: Column<Advice>,
+    pub selected_column: Column<Advice>,
+    pub slack_column: Column<Advice>,
+    pub limit_column: Column<Fixed>,
+
+    // Selectors
+    pub start_selector: Selector,
+    pub step_selector: Selector,
+    pub limit_selector: Selector,
+
+    // Range Check integration (rank slack)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Top-K Chip
+/// Top k values per group, proven with Group-By boundaries, the Sort Gate
+/// and rank constraints
+pub struct TopKChip<F: PrimeField = Fr> {
+    config: TopKConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> TopKChip<F> {
+    /// Create a new TopKChip
+    pub fn new(config: TopKConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Top-K Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> TopKConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        // - fixed[0]: shared with Range Check thresholds
+        let same_column = config.advice[10];
+        let value_column = config.advice[11];
+        let row_number_column = config.advice[12];
+        let selected_column = config.advice[13];
+        let slack_column = config.advice[14];
+        let limit_column = config.fixed[0];
+
+        let start_selector = meta.selector();
+        let step_selector = meta.selector();
+        let limit_selector = meta.selector();
+
+        meta.create_gate("top-k start", |meta| {
+            let s = meta.query_selector(start_selector);
+            let rn = meta.query_advice(row_number_column, Rotation::cur());
+
+            vec![s * (rn - Expression::Constant(F::ONE))]
+        });
+
+        meta.create_gate("top-k step", |meta| {
+            let s = meta.query_selector(step_selector);
+            let same = meta.query_advice(same_column, Rotation::cur());
+            let rn = meta.query_advice(row_number_column, Rotation::cur());
+            let rn_prev = meta.query_advice(row_number_column, Rotation::prev());
+
+            vec![s * (rn - (same * rn_prev + Expression::Constant(F::ONE)))]
+        });
+
+        meta.create_gate("top-k limit", |meta| {
+            let s = meta.query_selector(limit_selector);
+            let rn = meta.query_advice(row_number_column, Rotation::cur());
+            let selected = meta.query_advice(selected_column, Rotation::cur());
+            let slack = meta.query_advice(slack_column, Rotation::cur());
+            let k = meta.query_fixed(limit_column);
+            let one = Expression::Constant(F::ONE);
+
+            let expected = selected.clone() * (k.clone() - rn.clone())
+                + (one.clone() - selected.clone()) * (rn - k - one.clone());
+            vec![
+                s.clone() * selected.clone() * (one - selected),
+                s * (slack - expected),
+            ]
+        });
+
+        TopKConfig {
+            same_column,
+            value_column,
+            row_number_column,
+            selected_column,
+            slack_column,
+            limit_column,
+            start_selector,
+            step_selector,
+            limit_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Top k values of each group
+    /// Group boundaries are proven by `group_by_chip`, each group is sorted
+    /// by `sort_chip`
+    ///
+    /// # Errors
+    ///
+    /// `Error::Synthesis` if `group_keys` and `values` differ in length or
+    /// `k` is 0
+    ///
+    /// # Return Value
+    ///
+    /// Selected value cells per group, largest first (as `top_k_per_group`)
+    pub fn top_k(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_by_chip: &GroupByChip<F>,
+        sort_chip: &SortChip<F>,
+        group_keys: &[u64],
+        values: &[u64],
+        k: u64,
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        if group_keys.len() != values.len() || k == 0 {
+            return Err(Error::Synthesis);
+        }
+        if group_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // same[i] = boundary cell of the pair (i-1, i), i >= 1
+        let boundaries = group_by_chip
+            .group_and_verify(layouter.namespace(|| "top-k group boundaries"), group_keys)?;
+
+        // Sorted values of every group, largest first
+        let mut descending = Vec::with_capacity(values.len());
+        let mut start = 0;
+        while start < group_keys.len() {
+            let mut end = start + 1;
+            while end < group_keys.len() && group_keys[end] == group_keys[start] {
+                end += 1;
+            }
+
+            let group_values = &values[start..end];
+            let sorted_cells = sort_chip.sort_and_verify(
+                layouter.namespace(|| format!("sort group {}", group_keys[start])),
+                group_values.iter().map(|&v| Value::known(v)).collect(),
+                super::witness::sorted(group_values),
+            )?;
+            descending.extend(sorted_cells.into_iter().rev());
+            start = end;
+        }
+
+        let (selected_cells, slack_cells) = layouter.assign_region(
+            || "top-k",
+            |mut region| {
+                self.config.start_selector.enable(&mut region, 0)?;
+                let mut selected_cells: Vec<Vec<AssignedCell<F, F>>> = Vec::new();
+                let mut slack_cells = Vec::with_capacity(group_keys.len());
+                let mut row_number = 0u64;
+
+                for i in 0..group_keys.len() {
+                    let same = i > 0 && group_keys[i] == group_keys[i - 1];
+                    if i > 0 {
+                        self.config.step_selector.enable(&mut region, i)?;
+                        boundaries[i - 1].copy_advice(
+                            || format!("same_{}", i),
+                            &mut region,
+                            self.config.same_column,
+                            i,
+                        )?;
+                    }
+                    if !same {
+                        selected_cells.push(Vec::new());
+                    }
+                    row_number = if same { row_number + 1 } else { 1 };
+
+                    let value_cell = descending[i].copy_advice(
+                        || format!("value_{}", i),
+                        &mut region,
+                        self.config.value_column,
+                        i,
+                    )?;
+                    region.assign_advice(
+                        || format!("row_number_{}", i),
+                        self.config.row_number_column,
+                        i,
+                        || Value::known(F::from(row_number)),
+                    )?;
+
+                    // selected = 1 iff rn ≤ k
+                    self.config.limit_selector.enable(&mut region, i)?;
+                    region.assign_fixed(
+                        || format!("k_{}", i),
+                        self.config.limit_column,
+                        i,
+                        || Value::known(F::from(k)),
+                    )?;
+                    let selected = row_number <= k;
+                    let slack = if selected {
+                        k - row_number
+                    } else {
+                        row_number - k - 1
+                    };
+                    region.assign_advice(
+                        || format!("selected_{}", i),
+                        self.config.selected_column,
+                        i,
+                        || Value::known(F::from(selected as u64)),
+                    )?;
+                    slack_cells.push(region.assign_advice(
+                        || format!("slack_{}", i),
+                        self.config.slack_column,
+                        i,
+                        || Value::known(F::from(slack)),
+                    )?);
+
+                    if selected {
+                        if let Some(group) = selected_cells.last_mut() {
+                            group.push(value_cell);
+                        }
+                    }
+                }
+
+                Ok((selected_cells, slack_cells))
+            },
+        )?;
+
+        // Slack < 2^64, so the selection follows the rank
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for (i, cell) in slack_cells.iter().enumerate() {
+            range_check_chip
+                .decompose_cell(layouter.namespace(|| format!("top-k slack {}", i)), cell)?;
+        }
+
+        Ok(selected_cells)
+    }
+}
@@ -0,0 +1,268 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::range_check::{RangeCheckChip, RangeCheckConfig};
+use super::UnionOp;
+
+/// Set operation over two single-column relations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetOperation {
+    /// Multiset union (duplicates kept)
+    UnionAll,
+    /// Deduplicated union
+    Union,
+}
+
+impl SetOperation {
+    /// Reference (out-of-circuit) result, in the order the gate outputs it
+    pub fn evaluate(&self, left: &[u64], right: &[u64]) -> Vec<u64> {
+        let mut values: Vec<u64> = left.iter().chain(right.iter()).copied().collect();
+        values.sort_unstable();
+        if *self == SetOperation::Union {
+            values.dedup();
+        }
+        values
+    }
+}
+
+/// Union Gate Configuration
+/// Proves that an output relation is the union of two input relations
+///
+/// # Column Allocation
+///
+/// - `value_column`: Union rows in ascending order (advice[10])
+/// - `diff_column`: Gap to the previous row (advice[11])
+///
+/// # Constraints
+///
+/// 1. **Multiset union**: every input cell is copied into exactly one union
+///    row (copy constraints), so the rows are a permutation of `left ++ right`
+/// 2. **Order**: `diff = v - v_prev`, `diff ≥ 0` (64-bit decomposition)
+/// 3. **Duplicate**: `v - v_prev = 0`
+/// 4. **New value**: `diff = v - v_prev - 1`, `diff ≥ 0` (64-bit decomposition)
+///
+/// UNION ALL uses (2) on every row. UNION marks each row as duplicate (3) or
+/// new (4); because the rows are sorted, the new rows are exactly the
+/// distinct values and form the output relation.
+///
+/// # Note
+///
+/// - Inputs are cells of the producing chips; their values must fit in
+///   64 bits (as all range-checked values)
+/// - Columns are shared with Join Gate (used in different rows)
+#[derive(Clone, Debug)]
+pub struct UnionConfig {
+    pub value_column: Column<Advice>,
+    pub diff_column: Column<Advice>,
+
+    // Selectors
+    pub order_selector: Selector,
+    pub duplicate_selector: Selector,
+    pub new_selector: Selector,
+
+    // Range Check integration (diff ≥ 0)
+    pub range_check_config: RangeCheckConfig,
+}
+
+/// Union Chip
+/// UNION / UNION ALL of two relations
+pub struct UnionChip<F: PrimeField = Fr> {
+    config: UnionConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> UnionChip<F> {
+    /// Create a new UnionChip
+    pub fn new(config: UnionConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Union Gate
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        config: &PoneglyphConfig,
+        range_check_config: &RangeCheckConfig,
+    ) -> UnionConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-11]: shared with Join Gate
+        let value_column = config.advice[10];
+        let diff_column = config.advice[11];
+
+        let order_selector = meta.selector();
+        let duplicate_selector = meta.selector();
+        let new_selector = meta.selector();
+
+        meta.create_gate("union order", |meta| {
+            let s = meta.query_selector(order_selector);
+            let v = meta.query_advice(value_column, Rotation::cur());
+            let v_prev = meta.query_advice(value_column, Rotation::prev());
+            let diff = meta.query_advice(diff_column, Rotation::cur());
+
+            vec![s * (diff - (v - v_prev))]
+        });
+
+        meta.create_gate("union duplicate", |meta| {
+            let s = meta.query_selector(duplicate_selector);
+            let v = meta.query_advice(value_column, Rotation::cur());
+            let v_prev = meta.query_advice(value_column, Rotation::prev());
+
+            vec![s * (v - v_prev)]
+        });
+
+        meta.create_gate("union new value", |meta| {
+            let s = meta.query_selector(new_selector);
+            let v = meta.query_advice(value_column, Rotation::cur());
+            let v_prev = meta.query_advice(value_column, Rotation::prev());
+            let diff = meta.query_advice(diff_column, Rotation::cur());
+
+            vec![s * (diff - (v - v_prev - Expression::Constant(F::ONE)))]
+        });
+
+        UnionConfig {
+            value_column,
+            diff_column,
+            order_selector,
+            duplicate_selector,
+            new_selector,
+            range_check_config: range_check_config.clone(),
+        }
+    }
+
+    /// Assign the rows of an input relation
+    /// (for relations that are not produced by another chip)
+    pub fn assign_relation(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[u64],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        layouter.assign_region(
+            || "union input",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &value)| {
+                        region.assign_advice(
+                            || format!("input_{}", i),
+                            self.config.value_column,
+                            i,
+                            || Value::known(F::from(value)),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Union of two relations
+    /// `op.left` / `op.right` are the values of the `left` / `right` cells
+    ///
+    /// # Return Value
+    ///
+    /// Output relation in ascending order: every row for UNION ALL, the
+    /// distinct values for UNION
+    pub fn union_and_verify(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: &UnionOp,
+        left: &[AssignedCell<F, F>],
+        right: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if left.len() != op.left.len() || right.len() != op.right.len() {
+            return Err(Error::Synthesis);
+        }
+        let sources: Vec<(&AssignedCell<F, F>, u64)> = left
+            .iter()
+            .zip(op.left.iter().copied())
+            .chain(right.iter().zip(op.right.iter().copied()))
+            .collect();
+        if sources.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Permutation sorting the union (stable, ties keep input order)
+        let mut order: Vec<usize> = (0..sources.len()).collect();
+        order.sort_by_key(|&i| sources[i].1);
+
+        let (rows, diffs) = layouter.assign_region(
+            || "union rows",
+            |mut region| {
+                let mut rows = Vec::with_capacity(order.len());
+                let mut diffs = Vec::new();
+                for (row, &source) in order.iter().enumerate() {
+                    let (cell, value) = sources[source];
+                    let copied = cell.copy_advice(
+                        || format!("row_{}", row),
+                        &mut region,
+                        self.config.value_column,
+                        row,
+                    )?;
+                    rows.push((copied, value));
+                    if row == 0 {
+                        continue;
+                    }
+
+                    let prev = sources[order[row - 1]].1;
+                    let diff = match op.operation {
+                        SetOperation::UnionAll => {
+                            self.config.order_selector.enable(&mut region, row)?;
+                            Some(value - prev)
+                        }
+                        SetOperation::Union if value == prev => {
+                            self.config.duplicate_selector.enable(&mut region, row)?;
+                            None
+                        }
+                        SetOperation::Union => {
+                            self.config.new_selector.enable(&mut region, row)?;
+                            Some(value - prev - 1)
+                        }
+                    };
+                    if let Some(diff) = diff {
+                        diffs.push(region.assign_advice(
+                            || format!("diff_{}", row),
+                            self.config.diff_column,
+                            row,
+                            || Value::known(F::from(diff)),
+                        )?);
+                    }
+                }
+                Ok((rows, diffs))
+            },
+        )?;
+
+        // diff ≥ 0
+        let range_check_chip = RangeCheckChip::new(self.config.range_check_config.clone());
+        for (i, diff) in diffs.iter().enumerate() {
+            range_check_chip
+                .decompose_cell(layouter.namespace(|| format!("union diff {}", i)), diff)?;
+        }
+
+        let output = match op.operation {
+            SetOperation::UnionAll => rows.into_iter().map(|(cell, _)| cell).collect(),
+            // First row of each run of equal values
+            SetOperation::Union => {
+                let mut output: Vec<AssignedCell<F, F>> = Vec::new();
+                let mut last = None;
+                for (cell, value) in rows {
+                    if last != Some(value) {
+                        output.push(cell);
+                        last = Some(value);
+                    }
+                }
+                output
+            }
+        };
+        Ok(output)
+    }
+}
@@ -0,0 +1,360 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::config::PoneglyphConfig;
+use super::group_by::GroupByChip;
+
+/// Window function
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowFunction {
+    /// ROW_NUMBER(): 1, 2, 3, ... within a partition
+    RowNumber,
+    /// RANK(): ties share a rank, the next rank skips (1, 1, 3)
+    Rank,
+    /// SUM(x): running (cumulative) sum of x within a partition
+    Sum,
+}
+
+impl WindowFunction {
+    /// Reference (out-of-circuit) values over rows sorted by (partition, order)
+    /// `values` is the SUM argument (ignored by ROW_NUMBER/RANK); None on overflow
+    pub fn evaluate(
+        &self,
+        partition_keys: &[u64],
+        order_keys: &[u64],
+        values: &[u64],
+    ) -> Option<Vec<u64>> {
+        let mut out: Vec<u64> = Vec::with_capacity(partition_keys.len());
+        let mut row_number = 0;
+        for i in 0..partition_keys.len() {
+            let same = i > 0 && partition_keys[i] == partition_keys[i - 1];
+            row_number = if same { row_number + 1 } else { 1 };
+            let value = match self {
+                WindowFunction::RowNumber => row_number,
+                WindowFunction::Rank if same && order_keys[i] == order_keys[i - 1] => out[i - 1],
+                WindowFunction::Rank => row_number,
+                WindowFunction::Sum if same => out[i - 1].checked_add(*values.get(i)?)?,
+                WindowFunction::Sum => *values.get(i)?,
+            };
+            out.push(value);
+        }
+        Some(out)
+    }
+}
+
+/// Assigned window function columns
+#[derive(Clone, Debug)]
+pub struct AssignedWindow<F: PrimeField = Fr> {
+    pub row_numbers: Vec<AssignedCell<F, F>>,
+    pub ranks: Vec<AssignedCell<F, F>>,
+}
+
+/// Window Gate Configuration
+/// ROW_NUMBER(), RANK() and running SUM() over sorted partitions
+///
+/// # Column Allocation
+///
+/// - `same_column`: 1 = same partition as the previous row, copied from the
+///   Group-By boundary cells (advice[10])
+/// - `row_number_column`: ROW_NUMBER() (advice[11])
+/// - `rank_column`: RANK() (advice[12])
+/// - `order_column`: ORDER BY key (advice[13])
+/// - `tie_column`: 1 = same order key as the previous row (advice[14])
+/// - `tie_inverse_column`: Inverse of the order key difference (advice[8])
+/// - `value_column`: SUM argument (advice[13], running SUM rows)
+/// - `sum_column`: Running SUM (advice[11], running SUM rows)
+///
+/// # Constraints
+///
+/// 1. **Start** (first row): `row_number = 1`, `rank = 1`
+/// 2. **Row number**: `rn[i] = same · rn[i-1] + 1` (reset at boundaries)
+/// 3. **Tie**: `tie = 1 - (ord[i] - ord[i-1]) · inv`, `tie · (ord[i] - ord[i-1]) = 0`
+/// 4. **Rank**: `rank[i] = same · (tie · rank[i-1] + (1 - tie) · rn[i]) + (1 - same)`
+/// 5. **Running SUM**: `acc[0] = v[0]`, `acc[i] = same · acc[i-1] + v[i]`
+///
+/// # Note
+///
+/// - Rows must be sorted by (partition, order) (Sort Gate), the gate only
+///   proves the counters for the given order
+/// - Columns are shared with Join Gate (used in different rows)
+/// - Running sums are field elements, like Aggregation Gate SUM results (no
+///   64-bit range check)
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    pub same_column: Column<Advice>,
+    pub row_number_column: Column<Advice>,
+    pub rank_column: Column<Advice>,
+    pub order_column: Column<Advice>,
+    pub tie_column: Column<Advice>,
+    pub tie_inverse_column: Column<Advice>,
+    pub value_column: Column<Advice>,
+    pub sum_column: Column<Advice>,
+
+    // Selectors
+    pub start_selector: Selector,
+    pub step_selector: Selector,
+    pub sum_start_selector: Selector,
+    pub sum_step_selector: Selector,
+}
+
+/// Window Chip
+/// Counters over partitions proven with Group-By boundaries
+pub struct WindowChip<F: PrimeField = Fr> {
+    config: WindowConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> WindowChip<F> {
+    /// Create a new WindowChip
+    pub fn new(config: WindowConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configure the Window Gate
+    pub fn configure(meta: &mut ConstraintSystem<F>, config: &PoneglyphConfig) -> WindowConfig {
+        // Column allocation (see PoneglyphConfig documentation):
+        // - advice[10-14]: shared with Join Gate
+        // - advice[8]: shared with Range Check check/diff column
+        let same_column = config.advice[10];
+        let row_number_column = config.advice[11];
+        let rank_column = config.advice[12];
+        let order_column = config.advice[13];
+        let tie_column = config.advice[14];
+        let tie_inverse_column = config.advice[8];
+        let value_column = config.advice[13];
+        let sum_column = config.advice[11];
+
+        let start_selector = meta.selector();
+        let step_selector = meta.selector();
+        let sum_start_selector = meta.selector();
+        let sum_step_selector = meta.selector();
+
+        meta.create_gate("window start", |meta| {
+            let s = meta.query_selector(start_selector);
+            let rn = meta.query_advice(row_number_column, Rotation::cur());
+            let rank = meta.query_advice(rank_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+
+            vec![s.clone() * (rn - one.clone()), s * (rank - one)]
+        });
+
+        meta.create_gate("window step", |meta| {
+            let s = meta.query_selector(step_selector);
+            let same = meta.query_advice(same_column, Rotation::cur());
+            let rn = meta.query_advice(row_number_column, Rotation::cur());
+            let rn_prev = meta.query_advice(row_number_column, Rotation::prev());
+            let rank = meta.query_advice(rank_column, Rotation::cur());
+            let rank_prev = meta.query_advice(rank_column, Rotation::prev());
+            let ord = meta.query_advice(order_column, Rotation::cur());
+            let ord_prev = meta.query_advice(order_column, Rotation::prev());
+            let tie = meta.query_advice(tie_column, Rotation::cur());
+            let inv = meta.query_advice(tie_inverse_column, Rotation::cur());
+            let one = Expression::Constant(F::ONE);
+            let diff = ord - ord_prev;
+
+            vec![
+                s.clone() * (rn.clone() - (same.clone() * rn_prev + one.clone())),
+                s.clone() * (tie.clone() - (one.clone() - diff.clone() * inv)),
+                s.clone() * tie.clone() * diff,
+                s * (rank
+                    - (same.clone() * (tie.clone() * rank_prev + (one.clone() - tie) * rn)
+                        + (one - same))),
+            ]
+        });
+
+        meta.create_gate("window sum start", |meta| {
+            let s = meta.query_selector(sum_start_selector);
+            let v = meta.query_advice(value_column, Rotation::cur());
+            let acc = meta.query_advice(sum_column, Rotation::cur());
+
+            vec![s * (acc - v)]
+        });
+
+        meta.create_gate("window sum step", |meta| {
+            let s = meta.query_selector(sum_step_selector);
+            let same = meta.query_advice(same_column, Rotation::cur());
+            let v = meta.query_advice(value_column, Rotation::cur());
+            let acc = meta.query_advice(sum_column, Rotation::cur());
+            let acc_prev = meta.query_advice(sum_column, Rotation::prev());
+
+            vec![s * (acc - (same * acc_prev + v))]
+        });
+
+        WindowConfig {
+            same_column,
+            row_number_column,
+            rank_column,
+            order_column,
+            tie_column,
+            tie_inverse_column,
+            value_column,
+            sum_column,
+            start_selector,
+            step_selector,
+            sum_start_selector,
+            sum_step_selector,
+        }
+    }
+
+    /// ROW_NUMBER() and RANK() over rows sorted by (partition, order)
+    /// Partition boundaries are proven by `group_by_chip` and copied in
+    pub fn rank_over(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_by_chip: &GroupByChip<F>,
+        partition_keys: &[u64],
+        order_keys: &[u64],
+    ) -> Result<AssignedWindow<F>, Error> {
+        if partition_keys.len() != order_keys.len() {
+            return Err(Error::Synthesis);
+        }
+        if partition_keys.is_empty() {
+            return Ok(AssignedWindow {
+                row_numbers: Vec::new(),
+                ranks: Vec::new(),
+            });
+        }
+
+        // same[i] = boundary cell of the pair (i-1, i), i >= 1
+        let boundaries = group_by_chip.group_and_verify(
+            layouter.namespace(|| "partition boundaries"),
+            partition_keys,
+        )?;
+        let row_numbers = WindowFunction::RowNumber
+            .evaluate(partition_keys, order_keys, &[])
+            .ok_or(Error::Synthesis)?;
+        let ranks = WindowFunction::Rank
+            .evaluate(partition_keys, order_keys, &[])
+            .ok_or(Error::Synthesis)?;
+
+        layouter.assign_region(
+            || "window",
+            |mut region| {
+                self.config.start_selector.enable(&mut region, 0)?;
+                let mut rn_cells = Vec::new();
+                let mut rank_cells = Vec::new();
+
+                for i in 0..partition_keys.len() {
+                    if i > 0 {
+                        self.config.step_selector.enable(&mut region, i)?;
+                        boundaries[i - 1].copy_advice(
+                            || format!("same_{}", i),
+                            &mut region,
+                            self.config.same_column,
+                            i,
+                        )?;
+
+                        let diff = F::from(order_keys[i]) - F::from(order_keys[i - 1]);
+                        let tie = diff == F::ZERO;
+                        region.assign_advice(
+                            || format!("tie_{}", i),
+                            self.config.tie_column,
+                            i,
+                            || Value::known(F::from(tie as u64)),
+                        )?;
+                        region.assign_advice(
+                            || format!("tie_inverse_{}", i),
+                            self.config.tie_inverse_column,
+                            i,
+                            || Value::known(diff.invert().unwrap_or(F::ZERO)),
+                        )?;
+                    }
+
+                    region.assign_advice(
+                        || format!("order_{}", i),
+                        self.config.order_column,
+                        i,
+                        || Value::known(F::from(order_keys[i])),
+                    )?;
+                    rn_cells.push(region.assign_advice(
+                        || format!("row_number_{}", i),
+                        self.config.row_number_column,
+                        i,
+                        || Value::known(F::from(row_numbers[i])),
+                    )?);
+                    rank_cells.push(region.assign_advice(
+                        || format!("rank_{}", i),
+                        self.config.rank_column,
+                        i,
+                        || Value::known(F::from(ranks[i])),
+                    )?);
+                }
+
+                Ok(AssignedWindow {
+                    row_numbers: rn_cells,
+                    ranks: rank_cells,
+                })
+            },
+        )
+    }
+
+    /// Running SUM(values) over rows sorted by (partition, order)
+    /// Returns one cumulative sum cell per row
+    pub fn running_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        group_by_chip: &GroupByChip<F>,
+        partition_keys: &[u64],
+        values: &[u64],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if partition_keys.len() != values.len() {
+            return Err(Error::Synthesis);
+        }
+        if partition_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let boundaries = group_by_chip.group_and_verify(
+            layouter.namespace(|| "partition boundaries"),
+            partition_keys,
+        )?;
+
+        layouter.assign_region(
+            || "window running sum",
+            |mut region| {
+                self.config.sum_start_selector.enable(&mut region, 0)?;
+                let mut acc = F::ZERO;
+                let mut sum_cells = Vec::new();
+
+                for i in 0..partition_keys.len() {
+                    let same = i > 0 && partition_keys[i] == partition_keys[i - 1];
+                    if i > 0 {
+                        self.config.sum_step_selector.enable(&mut region, i)?;
+                        boundaries[i - 1].copy_advice(
+                            || format!("same_{}", i),
+                            &mut region,
+                            self.config.same_column,
+                            i,
+                        )?;
+                    }
+
+                    acc = if same { acc } else { F::ZERO } + F::from(values[i]);
+                    region.assign_advice(
+                        || format!("value_{}", i),
+                        self.config.value_column,
+                        i,
+                        || Value::known(F::from(values[i])),
+                    )?;
+                    sum_cells.push(region.assign_advice(
+                        || format!("sum_{}", i),
+                        self.config.sum_column,
+                        i,
+                        || Value::known(acc),
+                    )?);
+                }
+
+                Ok(sum_cells)
+            },
+        )
+    }
+}
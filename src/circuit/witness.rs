@@ -0,0 +1,269 @@
+// Witness precomputation
+// Host-side values that region assignment consumes: per-row 8-bit chunk
+// decomposition, sorted permutation witnesses and group accumulators.
+//
+// Assignment itself stays sequential (a region is borrowed mutably), but the
+// values are independent per row, or per group for accumulators. With the
+// `parallel` feature they are computed with rayon; without it, on the calling
+// thread. Both paths produce identical witnesses, so proofs and verifying keys
+// don't depend on the feature.
+//
+// Within a thread the hot loops are branchless and work on whole words, so
+// they compile to vector instructions on stable Rust (no `std::simd`): chunks
+// are the little-endian bytes of a value.
+//
+// The `_in` variants write into a slice of the synthesis arena (see
+// `arena.rs`) instead of a new `Vec`, for witnesses that only live as long as
+// the region that consumes them.
+
+use ff::PrimeField;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use super::arena::SynthesisArena;
+use super::AggregationType;
+
+/// Fewest rows handed to one rayon task; smaller inputs aren't worth a split
+const MIN_ROWS_PER_TASK: usize = 1024;
+
+/// Fewest groups handed to one rayon task
+const MIN_GROUPS_PER_TASK: usize = 16;
+
+/// Map `f` over `items`, in order, at least `min_len` items per task
+#[cfg(feature = "parallel")]
+fn map_rows<T: Sync, U: Send>(
+    items: &[T],
+    min_len: usize,
+    f: impl Fn(&T) -> U + Sync + Send,
+) -> Vec<U> {
+    items.par_iter().with_min_len(min_len).map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map_rows<T, U>(items: &[T], _min_len: usize, f: impl Fn(&T) -> U) -> Vec<U> {
+    items.iter().map(f).collect()
+}
+
+/// Write `f` of every item into `out`, in order, at least `min_len` items per task
+#[cfg(feature = "parallel")]
+fn fill_rows<T: Sync, U: Send>(
+    out: &mut [U],
+    items: &[T],
+    min_len: usize,
+    f: impl Fn(&T) -> U + Sync + Send,
+) {
+    out.par_iter_mut()
+        .zip(items)
+        .with_min_len(min_len)
+        .for_each(|(slot, item)| *slot = f(item));
+}
+
+#[cfg(not(feature = "parallel"))]
+fn fill_rows<T, U>(out: &mut [U], items: &[T], _min_len: usize, f: impl Fn(&T) -> U) {
+    for (slot, item) in out.iter_mut().zip(items) {
+        *slot = f(item);
+    }
+}
+
+/// 8-bit chunks of `value`, least significant first
+/// The chunks are the value's little-endian bytes, widened; no shift or mask
+/// per chunk
+pub fn chunks_of(value: u64) -> [u64; 8] {
+    value.to_le_bytes().map(u64::from)
+}
+
+/// `n` chunks of `chunk_bits` bits of `value`, least significant first
+/// (see `RangeCheckChip::decompose_bits`)
+pub fn chunks_of_bits(value: u128, chunk_bits: u32, n: usize) -> Vec<u64> {
+    let mask = (1u128 << chunk_bits) - 1;
+    (0..n as u32)
+        .map(|i| {
+            value
+                .checked_shr(i * chunk_bits)
+                .map_or(0, |v| (v & mask) as u64)
+        })
+        .collect()
+}
+
+/// 8-bit chunks of every value (see `RangeCheckChip::decompose_64bit_rows`)
+pub fn decompose_rows(values: &[u64]) -> Vec<[u64; 8]> {
+    map_rows(values, MIN_ROWS_PER_TASK, |&v| chunks_of(v))
+}
+
+/// `decompose_rows`, into an arena slice
+pub fn decompose_rows_in<'a>(arena: &'a SynthesisArena, values: &[u64]) -> &'a [[u64; 8]] {
+    let out = arena.alloc_filled(values.len(), [0; 8]);
+    fill_rows(out, values, MIN_ROWS_PER_TASK, |&v| chunks_of(v));
+    out
+}
+
+/// Values in ascending order: the sorted side of a permutation witness
+pub fn sorted(values: &[u64]) -> Vec<u64> {
+    let mut sorted = values.to_vec();
+    #[cfg(feature = "parallel")]
+    sorted.par_sort_unstable();
+    #[cfg(not(feature = "parallel"))]
+    sorted.sort_unstable();
+    sorted
+}
+
+/// B[i+1] - B[i] for every adjacent pair of a sorted array
+pub fn adjacent_diffs(sorted: &[u64]) -> Vec<u64> {
+    let Some(next) = sorted.get(1..) else {
+        return Vec::new();
+    };
+    #[cfg(feature = "parallel")]
+    {
+        next.par_iter()
+            .zip(sorted)
+            .with_min_len(MIN_ROWS_PER_TASK)
+            .map(|(b, a)| b - a)
+            .collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        next.iter().zip(sorted).map(|(b, a)| b - a).collect()
+    }
+}
+
+/// Field encoding of every value
+pub fn to_field<F: PrimeField>(values: &[u64]) -> Vec<F> {
+    map_rows(values, MIN_ROWS_PER_TASK, |&v| F::from(v))
+}
+
+/// `to_field`, into an arena slice
+pub fn to_field_in<'a, F: PrimeField>(arena: &'a SynthesisArena, values: &[u64]) -> &'a [F] {
+    let out = arena.alloc_filled(values.len(), F::ZERO);
+    fill_rows(out, values, MIN_ROWS_PER_TASK, |&v| F::from(v));
+    out
+}
+
+/// Running aggregate of each row within its group (the result column of
+/// `AggregationChip::aggregate_and_verify`)
+/// `group_keys` must be sorted, so every group is one contiguous run; runs are
+/// accumulated independently
+///
+/// # Return Value
+///
+/// None for aggregation types without a running accumulator (MEDIAN,
+/// PERCENTILE, VARIANCE, STDDEV) or if the slices differ in length
+pub fn group_accumulators(
+    group_keys: &[u64],
+    values: &[u64],
+    agg_type: &AggregationType,
+) -> Option<Vec<u64>> {
+    if group_keys.len() != values.len() || agg_type.is_rank() || agg_type.is_dispersion() {
+        return None;
+    }
+
+    let starts: Vec<usize> = (0..group_keys.len())
+        .filter(|&i| i == 0 || group_keys[i] != group_keys[i - 1])
+        .collect();
+    let runs: Vec<&[u64]> = starts
+        .iter()
+        .zip(starts.iter().skip(1).chain([&group_keys.len()]))
+        .map(|(&start, &end)| &values[start..end])
+        .collect();
+
+    let accumulated = map_rows(&runs, MIN_GROUPS_PER_TASK, |run| {
+        let mut current = 0;
+        run.iter()
+            .enumerate()
+            .map(|(i, &v)| {
+                current = match (agg_type, i) {
+                    (AggregationType::Count, 0) => 1,
+                    (AggregationType::Count, _) => current + 1,
+                    (_, 0) => v,
+                    (AggregationType::Sum, _) => current + v,
+                    (AggregationType::Max, _) => current.max(v),
+                    _ => current.min(v),
+                };
+                current
+            })
+            .collect::<Vec<u64>>()
+    });
+    Some(accumulated.concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pasta_curves::pallas::Base as Fr;
+
+    #[test]
+    fn test_decompose_rows() {
+        let values: Vec<u64> = (0..3000u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+        for (value, chunks) in values.iter().zip(decompose_rows(&values)) {
+            let recomposed = chunks
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, c)| acc | (c << (i * 8)));
+            assert_eq!(recomposed, *value);
+            assert!(chunks.iter().all(|&c| c < 256));
+        }
+    }
+
+    #[test]
+    fn test_arena_witnesses() {
+        let values: Vec<u64> = (0..3000u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+        let arena = SynthesisArena::new();
+        assert_eq!(decompose_rows_in(&arena, &values), decompose_rows(&values));
+        assert_eq!(to_field_in::<Fr>(&arena, &values), to_field::<Fr>(&values));
+        assert!(to_field_in::<Fr>(&arena, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_chunks_of_bits() {
+        let value = u128::MAX - 0x1234;
+        for chunk_bits in [8, 16] {
+            let n = (128 / chunk_bits) as usize;
+            let chunks = chunks_of_bits(value, chunk_bits, n);
+            assert_eq!(chunks.len(), n);
+            let recomposed = chunks
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &c| (acc << chunk_bits) | c as u128);
+            assert_eq!(recomposed, value);
+        }
+        // Chunks past the value's width are 0
+        assert_eq!(chunks_of_bits(0x1ff, 8, 4), vec![0xff, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_sort_witness() {
+        let values: Vec<u64> = (0..3000u64).map(|i| (i * 7919) % 1009).collect();
+        let sorted = sorted(&values);
+        let mut expected = values.clone();
+        expected.sort();
+        assert_eq!(sorted, expected);
+
+        let diffs = adjacent_diffs(&sorted);
+        assert_eq!(diffs.len(), values.len() - 1);
+        assert_eq!(diffs[10], sorted[11] - sorted[10]);
+        assert!(adjacent_diffs(&[]).is_empty());
+        assert!(adjacent_diffs(&[7]).is_empty());
+        assert_eq!(to_field::<Fr>(&[3, 5]), vec![Fr::from(3), Fr::from(5)]);
+    }
+
+    #[test]
+    fn test_group_accumulators() {
+        let keys = [1, 1, 1, 2, 3, 3];
+        let values = [4, 9, 2, 5, 7, 1];
+        let run = |agg| group_accumulators(&keys, &values, &agg).unwrap();
+        assert_eq!(run(AggregationType::Sum), vec![4, 13, 15, 5, 7, 8]);
+        assert_eq!(run(AggregationType::Count), vec![1, 2, 3, 1, 1, 2]);
+        assert_eq!(run(AggregationType::Max), vec![4, 9, 9, 5, 7, 7]);
+        assert_eq!(run(AggregationType::Min), vec![4, 4, 2, 5, 7, 1]);
+
+        assert_eq!(
+            group_accumulators(&[], &[], &AggregationType::Sum),
+            Some(vec![])
+        );
+        assert!(group_accumulators(&keys, &values, &AggregationType::Median).is_none());
+        assert!(group_accumulators(&keys, &values[1..], &AggregationType::Sum).is_none());
+    }
+}
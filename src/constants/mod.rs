@@ -1,7 +1,10 @@
-/// Circuit constants and configuration values
+//! Circuit constants and configuration values
 
-/// Maximum number of 8-bit chunks for 64-bit decomposition
-pub const MAX_CHUNKS: usize = 8;
+/// Default chunk width of the bitwise decomposition (see `DecompositionParams`)
+pub const DEFAULT_CHUNK_BITS: u32 = 8;
+
+/// Default maximum number of chunks per decomposed value (128 bits of 8-bit chunks)
+pub const DEFAULT_MAX_CHUNKS: usize = 16;
 
 /// Lookup table size for range checks
 pub const LOOKUP_TABLE_SIZE: u64 = 256;
@@ -24,9 +27,10 @@ mod tests {
 
     #[test]
     fn test_constants() {
-        assert!(MAX_CHUNKS > 0);
-        assert!(LOOKUP_TABLE_SIZE > 0);
-        assert!(NUM_ADVICE_COLUMNS > 0);
-        assert!(NUM_FIXED_COLUMNS > 0);
+        const { assert!(DEFAULT_CHUNK_BITS > 0) };
+        const { assert!(DEFAULT_MAX_CHUNKS > 0) };
+        const { assert!(LOOKUP_TABLE_SIZE > 0) };
+        const { assert!(NUM_ADVICE_COLUMNS > 0) };
+        const { assert!(NUM_FIXED_COLUMNS > 0) };
     }
 }
@@ -0,0 +1,244 @@
+// Bulk table loading
+// Inserting rows one at a time rehashes a Merkle path of `depth` nodes per
+// row, on one thread. A bulk load instead encodes rows as they stream in,
+// hashes their leaves a chunk at a time (in parallel with the `parallel`
+// feature, see `merkle.rs`) and builds the tree bottom-up once, when the load
+// finishes: one hash per node instead of `depth` per row.
+//
+// The table only appears in the catalog when `finish` succeeds, so a failed
+// load (bad row, too many rows) leaves nothing behind. The loaded rows are the
+// table's baseline: there is no pending delta for them.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use pasta_curves::pallas::Base as Fr;
+
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+use super::{merkle_leaves, Database, DatabaseCommitment, Datum, Schema};
+
+/// Callback of `BulkLoader::on_progress`
+type ProgressCallback<'a> = Box<dyn FnMut(&LoadProgress) + 'a>;
+
+/// Default `BulkLoader::with_chunk_rows`
+pub const DEFAULT_CHUNK_ROWS: usize = 1 << 16;
+
+/// Progress of a bulk load
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoadProgress {
+    /// Rows hashed so far
+    pub rows: u64,
+    /// Time since the load started
+    pub elapsed: Duration,
+}
+
+impl LoadProgress {
+    pub fn rows_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            0.0
+        } else {
+            self.rows as f64 / secs
+        }
+    }
+}
+
+/// Streaming loader of one new table
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let mut loader = db
+///     .bulk_loader("orders", schema)?
+///     .on_progress(|p| eprintln!("{} rows, {:.0} rows/s", p.rows, p.rows_per_sec()));
+/// for row in rows {
+///     loader.push(&row)?;
+/// }
+/// let (commitment, progress) = loader.finish()?;
+/// ```
+pub struct BulkLoader<'a> {
+    database: &'a mut Database,
+    name: String,
+    schema: Schema,
+    chunk_rows: usize,
+    rows: Vec<Vec<u64>>,
+    /// Leaves of `rows[..leaves.len()]`
+    leaves: Vec<Fr>,
+    /// Hash -> string of text values
+    text: HashMap<u64, String>,
+    started: Instant,
+    progress: Option<ProgressCallback<'a>>,
+}
+
+impl Database {
+    /// Start loading table `name`
+    /// Fails if the table already exists
+    pub fn bulk_loader(&mut self, name: &str, schema: Schema) -> PoneglyphResult<BulkLoader<'_>> {
+        if self.table(name.trim()).is_some() {
+            return Err(PoneglyphError::InvalidInput(format!(
+                "Table {} already exists",
+                name.trim().to_lowercase()
+            )));
+        }
+        Ok(BulkLoader {
+            database: self,
+            name: name.to_string(),
+            schema,
+            chunk_rows: DEFAULT_CHUNK_ROWS,
+            rows: Vec::new(),
+            leaves: Vec::new(),
+            text: HashMap::new(),
+            started: Instant::now(),
+            progress: None,
+        })
+    }
+}
+
+impl<'a> BulkLoader<'a> {
+    /// Hash leaves every `rows` rows (and report progress)
+    pub fn with_chunk_rows(mut self, rows: usize) -> Self {
+        self.chunk_rows = rows.max(1);
+        self
+    }
+
+    /// Called after every hashed chunk and when the load finishes
+    pub fn on_progress(mut self, progress: impl FnMut(&LoadProgress) + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Add a row given in schema order
+    pub fn push(&mut self, row: &[Datum]) -> PoneglyphResult<()> {
+        let encoded = self.schema.encode_row(row).map_err(|e| {
+            PoneglyphError::InvalidInput(format!(
+                "Table {}: row {}: {}",
+                self.name,
+                self.rows.len(),
+                e
+            ))
+        })?;
+        for (datum, &raw) in row.iter().zip(&encoded) {
+            if let Datum::Text(value) = datum {
+                self.text.insert(raw, value.clone());
+            }
+        }
+        self.rows.push(encoded);
+        if self.rows.len() - self.leaves.len() >= self.chunk_rows {
+            self.hash_pending();
+        }
+        Ok(())
+    }
+
+    /// Add rows given in schema order
+    pub fn extend<R: AsRef<[Datum]>>(
+        &mut self,
+        rows: impl IntoIterator<Item = R>,
+    ) -> PoneglyphResult<()> {
+        rows.into_iter().try_for_each(|row| self.push(row.as_ref()))
+    }
+
+    /// Rows hashed so far
+    pub fn progress(&self) -> LoadProgress {
+        LoadProgress {
+            rows: self.leaves.len() as u64,
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    /// Hash the remaining rows, build the Merkle tree and create the table
+    pub fn finish(mut self) -> PoneglyphResult<(DatabaseCommitment, LoadProgress)> {
+        self.hash_pending();
+        self.database
+            .install_table(&self.name, self.schema, self.rows, self.text, self.leaves)?;
+        let progress = LoadProgress {
+            rows: self.database.table(&self.name).map_or(0, |t| t.data.len()) as u64,
+            elapsed: self.started.elapsed(),
+        };
+        if let Some(report) = self.progress.as_mut() {
+            report(&progress);
+        }
+        let commitment = self
+            .database
+            .commit(&self.name)
+            .expect("table was just created");
+        Ok((commitment, progress))
+    }
+
+    fn hash_pending(&mut self) {
+        let pending = &self.rows[self.leaves.len()..];
+        if pending.is_empty() {
+            return;
+        }
+        self.leaves.extend(merkle_leaves(pending));
+        let progress = self.progress();
+        if let Some(report) = self.progress.as_mut() {
+            report(&progress);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ColumnType;
+
+    fn schema() -> Schema {
+        Schema::new(&[("id", ColumnType::Integer), ("name", ColumnType::Text)]).unwrap()
+    }
+
+    fn row(i: u64) -> Vec<Datum> {
+        vec![Datum::Integer(i), Datum::Text(format!("user{}", i % 7))]
+    }
+
+    #[test]
+    fn test_bulk_load_matches_inserts() {
+        let mut inserted = Database::new().with_merkle_depth(8);
+        inserted
+            .create_table_with_schema("users", schema())
+            .unwrap();
+        for i in 0..100 {
+            inserted.insert("users", &row(i)).unwrap();
+        }
+
+        let mut reports = Vec::new();
+        let mut bulk = Database::new().with_merkle_depth(8);
+        let mut loader = bulk
+            .bulk_loader("users", schema())
+            .unwrap()
+            .with_chunk_rows(30)
+            .on_progress(|p| reports.push(p.rows));
+        loader.extend((0..100).map(row)).unwrap();
+        let (commitment, progress) = loader.finish().unwrap();
+
+        assert_eq!(reports, vec![30, 60, 90, 100, 100]);
+        assert_eq!(progress.rows, 100);
+        assert_eq!(bulk.root("users"), inserted.root("users"));
+        assert_eq!(
+            commitment.commitment(),
+            inserted.commit("users").unwrap().commitment()
+        );
+        assert_eq!(bulk.rows("users").unwrap(), inserted.rows("users").unwrap());
+        // The load is the baseline
+        assert!(bulk.take_delta("users").unwrap().updates.is_empty());
+    }
+
+    #[test]
+    fn test_failed_load_creates_nothing() {
+        let mut db = Database::new().with_merkle_depth(3);
+        let mut loader = db.bulk_loader("users", schema()).unwrap();
+        loader.push(&row(1)).unwrap();
+        assert!(loader.push(&[Datum::Integer(1)]).is_err());
+        drop(loader);
+        assert!(db.table("users").is_none());
+
+        // More rows than the tree holds
+        let mut loader = db.bulk_loader("users", schema()).unwrap();
+        loader.extend((0..9).map(row)).unwrap();
+        assert!(loader.finish().is_err());
+        assert!(db.table("users").is_none());
+
+        db.create_table_with_schema("users", schema()).unwrap();
+        assert!(db.bulk_loader("USERS", schema()).is_err());
+    }
+}
@@ -0,0 +1,725 @@
+// Multi-table schema and catalog
+// Tables are created with a typed schema and rows are inserted as typed
+// values, either in schema order or by column name. Every value is encoded to
+// the `u64` the circuits work on when it is inserted, so the SQL compiler
+// resolves columns by name against the catalog instead of indexing rows.
+//
+// Identifiers are case-insensitive (the SQL parser lowercases queries): table
+// and column names are stored lowercased.
+//
+// Every table keeps a Merkle tree over its rows that is updated on each
+// insert, update and delete; the changes since the last `take_delta` can be
+// proven to be the only difference between two roots (see `merkle.rs`).
+
+use std::collections::{BTreeMap, HashMap};
+
+use pasta_curves::pallas::Base as Fr;
+
+use crate::circuit::Decimal;
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::utils::simple_hash;
+
+use super::{
+    merkle_leaf, CommitmentDelta, DatabaseCommitment, DatabaseTable, MerkleTree, MerkleUpdate,
+    MERKLE_DEPTH,
+};
+
+/// Type of a column
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    /// Unsigned 64-bit integer
+    Integer,
+    /// Boolean, stored as 0 / 1
+    Boolean,
+    /// Fixed-point decimal with the given scale, stored as its raw value
+    Decimal(u32),
+    /// String, stored as its 64-bit Poseidon hash (equality only)
+    Text,
+    /// Calendar date (`YYYY-MM-DD`, from 1970-01-01), stored as days since
+    /// 1970-01-01 so that comparisons and ranges are proven on the day number
+    Date,
+}
+
+impl ColumnType {
+    /// Fixed-point scale of the stored value (0 for non-decimal columns)
+    pub fn scale(&self) -> u32 {
+        match self {
+            ColumnType::Decimal(scale) => *scale,
+            _ => 0,
+        }
+    }
+}
+
+/// Typed value of a row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Datum {
+    Integer(u64),
+    Boolean(bool),
+    Decimal(Decimal),
+    Text(String),
+    /// Days since 1970-01-01
+    Date(u64),
+}
+
+impl Datum {
+    /// Encode as the stored value of a column of type `ty`
+    /// Integers are accepted by decimal columns (scaled); decimals are
+    /// rescaled to the column scale and rejected if that loses digits.
+    pub fn encode(&self, ty: ColumnType) -> Result<u64, String> {
+        match (self, ty) {
+            (Datum::Integer(value), ColumnType::Integer) => Ok(*value),
+            (Datum::Boolean(value), ColumnType::Boolean) => Ok(*value as u64),
+            (Datum::Integer(value), ColumnType::Decimal(scale)) => Decimal::scale_factor(scale)
+                .and_then(|factor| value.checked_mul(factor))
+                .ok_or_else(|| format!("{} overflows a decimal with scale {}", value, scale)),
+            (Datum::Decimal(value), ColumnType::Decimal(scale)) => value
+                .rescale(scale)
+                .map(|d| d.raw)
+                .ok_or_else(|| format!("{:?} does not fit scale {}", value, scale)),
+            (Datum::Text(value), ColumnType::Text) => Ok(simple_hash(value.as_bytes())),
+            (Datum::Date(days), ColumnType::Date) => Ok(*days),
+            (datum, ty) => Err(format!("{:?} is not a value of type {:?}", datum, ty)),
+        }
+    }
+
+    /// Parse the text form of a value of type `ty` (e.g. a CSV field)
+    pub fn parse(s: &str, ty: ColumnType) -> Result<Self, String> {
+        let trimmed = s.trim();
+        match ty {
+            ColumnType::Integer => trimmed
+                .parse()
+                .map(Datum::Integer)
+                .map_err(|_| format!("Invalid integer: {}", s)),
+            ColumnType::Boolean => match trimmed.to_lowercase().as_str() {
+                "true" | "t" | "1" => Ok(Datum::Boolean(true)),
+                "false" | "f" | "0" => Ok(Datum::Boolean(false)),
+                _ => Err(format!("Invalid boolean: {}", s)),
+            },
+            ColumnType::Decimal(scale) => Decimal::parse(trimmed, scale).map(Datum::Decimal),
+            ColumnType::Text => Ok(Datum::Text(s.to_string())),
+            ColumnType::Date => parse_date(trimmed)
+                .map(Datum::Date)
+                .ok_or_else(|| format!("Invalid date: {}", s)),
+        }
+    }
+
+    /// Inverse of `encode`; text values are looked up by hash in `text`
+    pub fn decode(raw: u64, ty: ColumnType, text: &HashMap<u64, String>) -> Option<Self> {
+        match ty {
+            ColumnType::Integer => Some(Datum::Integer(raw)),
+            ColumnType::Boolean => Some(Datum::Boolean(raw != 0)),
+            ColumnType::Decimal(scale) => Some(Datum::Decimal(Decimal::new(raw, scale))),
+            ColumnType::Text => text.get(&raw).cloned().map(Datum::Text),
+            ColumnType::Date => Some(Datum::Date(raw)),
+        }
+    }
+}
+
+impl std::fmt::Display for Datum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Datum::Integer(value) => write!(f, "{}", value),
+            Datum::Boolean(value) => write!(f, "{}", value),
+            Datum::Decimal(value) => write!(f, "{}", value),
+            Datum::Text(value) => write!(f, "{}", value),
+            Datum::Date(days) => {
+                let (year, month, day) = civil_from_days(*days);
+                write!(f, "{:04}-{:02}-{:02}", year, month, day)
+            }
+        }
+    }
+}
+
+/// Days since 1970-01-01 of a `YYYY-MM-DD` date (None if invalid or earlier)
+fn parse_date(s: &str) -> Option<u64> {
+    let mut parts = s.splitn(3, '-');
+    let mut field = |len: usize| {
+        parts
+            .next()
+            .filter(|p| p.len() == len && p.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|p| p.parse::<u64>().ok())
+    };
+    let (year, month, day) = (field(4)?, field(2)?, field(2)?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = [31, 28 + leap as u64, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    if year < 1970 || !(1..=12).contains(&month) || day == 0 || day > month_days[month as usize - 1]
+    {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (year >= 1970)
+/// Counted from 0000-03-01 so that the leap day ends the year.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    // 719468 = days from 0000-03-01 to 1970-01-01
+    era * 146097 + day_of_era - 719468
+}
+
+/// Inverse of `days_from_civil`: (year, month, day)
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as u64;
+    (year, month, day)
+}
+
+/// Column of a schema
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnDef {
+    pub name: String,
+    pub ty: ColumnType,
+}
+
+/// Ordered, typed columns of a table
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub columns: Vec<ColumnDef>,
+}
+
+impl Schema {
+    /// Create schema; column names must be unique (case-insensitive)
+    pub fn new(columns: &[(&str, ColumnType)]) -> PoneglyphResult<Self> {
+        let mut schema = Schema::default();
+        for &(name, ty) in columns {
+            let name = name.trim().to_lowercase();
+            if name.is_empty() {
+                return Err(PoneglyphError::InvalidInput(
+                    "Column name must not be empty".to_string(),
+                ));
+            }
+            if schema.index_of(&name).is_some() {
+                return Err(PoneglyphError::InvalidInput(format!(
+                    "Duplicate column {}",
+                    name
+                )));
+            }
+            schema.columns.push(ColumnDef { name, ty });
+        }
+        Ok(schema)
+    }
+
+    /// Position of a column (None if it does not exist)
+    pub fn index_of(&self, column: &str) -> Option<usize> {
+        let column = column.to_lowercase();
+        self.columns.iter().position(|c| c.name == column)
+    }
+
+    /// Column names in schema order
+    pub fn column_names(&self) -> Vec<String> {
+        self.columns.iter().map(|c| c.name.clone()).collect()
+    }
+
+    /// Encode a row given in schema order
+    pub fn encode_row(&self, row: &[Datum]) -> Result<Vec<u64>, String> {
+        if row.len() != self.columns.len() {
+            return Err(format!(
+                "Row has {} values, schema has {} columns",
+                row.len(),
+                self.columns.len()
+            ));
+        }
+        self.columns
+            .iter()
+            .zip(row)
+            .map(|(column, datum)| {
+                datum
+                    .encode(column.ty)
+                    .map_err(|e| format!("Column {}: {}", column.name, e))
+            })
+            .collect()
+    }
+}
+
+/// Table of the catalog
+#[derive(Clone, Debug)]
+struct CatalogTable {
+    schema: Schema,
+    data: DatabaseTable,
+    /// Hash -> string of the values of text columns, to decode rows
+    text: HashMap<u64, String>,
+    /// Merkle tree over the rows
+    tree: MerkleTree,
+    /// Leaf changes since the last `take_delta`
+    pending: Vec<MerkleUpdate>,
+}
+
+impl CatalogTable {
+    /// Set the Merkle leaf of a row slot (`None` empties it)
+    fn set_leaf(&mut self, index: usize, row: Option<&[u64]>) -> PoneglyphResult<()> {
+        let update = self.tree.set(index as u64, merkle_leaf(row))?;
+        self.pending.push(update);
+        Ok(())
+    }
+}
+
+/// Catalog of typed tables
+#[derive(Clone, Debug)]
+pub struct Database {
+    tables: BTreeMap<String, CatalogTable>,
+    /// Merkle tree depth of new tables
+    merkle_depth: usize,
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self {
+            tables: BTreeMap::new(),
+            merkle_depth: MERKLE_DEPTH,
+        }
+    }
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use Merkle trees of `depth` (up to 2^depth rows) for tables created
+    /// afterwards; smaller trees make cheaper delta proofs
+    pub fn with_merkle_depth(mut self, depth: usize) -> Self {
+        self.merkle_depth = depth;
+        self
+    }
+
+    /// Create an empty table
+    /// Fails if the table already exists or the columns are not unique
+    pub fn create_table(
+        &mut self,
+        name: &str,
+        columns: &[(&str, ColumnType)],
+    ) -> PoneglyphResult<()> {
+        self.create_table_with_schema(name, Schema::new(columns)?)
+    }
+
+    /// Create an empty table from a schema
+    pub fn create_table_with_schema(&mut self, name: &str, schema: Schema) -> PoneglyphResult<()> {
+        let name = name.trim().to_lowercase();
+        if self.tables.contains_key(&name) {
+            return Err(PoneglyphError::InvalidInput(format!(
+                "Table {} already exists",
+                name
+            )));
+        }
+        let mut data = DatabaseTable::new(name.clone(), schema.column_names());
+        for column in &schema.columns {
+            data.set_scale(&column.name, column.ty.scale());
+        }
+        self.tables.insert(
+            name,
+            CatalogTable {
+                schema,
+                data,
+                text: HashMap::new(),
+                tree: MerkleTree::new(self.merkle_depth),
+                pending: Vec::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Create table `name` with encoded `rows` and their Merkle `leaves`
+    /// The tree is built in one pass and the load is its baseline: no
+    /// pending delta (see `BulkLoader`)
+    pub(super) fn install_table(
+        &mut self,
+        name: &str,
+        schema: Schema,
+        rows: Vec<Vec<u64>>,
+        text: HashMap<u64, String>,
+        leaves: Vec<Fr>,
+    ) -> PoneglyphResult<()> {
+        let tree = MerkleTree::from_leaves(self.merkle_depth, leaves)?;
+        self.create_table_with_schema(name, schema)?;
+        let entry = self.table_mut(name.trim())?;
+        entry.data.data = rows;
+        entry.text = text;
+        entry.tree = tree;
+        Ok(())
+    }
+
+    /// Remove a table, returning its rows
+    pub fn drop_table(&mut self, name: &str) -> Option<DatabaseTable> {
+        self.tables
+            .remove(&name.to_lowercase())
+            .map(|table| table.data)
+    }
+
+    /// Table names in order
+    pub fn table_names(&self) -> Vec<&str> {
+        self.tables.keys().map(|name| name.as_str()).collect()
+    }
+
+    pub fn schema(&self, table: &str) -> Option<&Schema> {
+        self.tables
+            .get(&table.to_lowercase())
+            .map(|table| &table.schema)
+    }
+
+    /// Encoded rows of a table
+    pub fn table(&self, table: &str) -> Option<&DatabaseTable> {
+        self.tables
+            .get(&table.to_lowercase())
+            .map(|table| &table.data)
+    }
+
+    /// Commitment to the rows of a table (see `DatabaseTable::commit`)
+    pub fn commit(&self, table: &str) -> Option<DatabaseCommitment> {
+        self.table(table).map(DatabaseTable::commit)
+    }
+
+    /// Merkle root of the rows of a table (updated incrementally)
+    pub fn root(&self, table: &str) -> Option<Fr> {
+        self.tables
+            .get(&table.to_lowercase())
+            .map(|table| table.tree.root())
+    }
+
+    /// Changes of a table's Merkle tree since the last call
+    /// The delta can be proven with `CommitmentDelta::prove` and checked
+    /// against the public `CommitmentDelta::claim`.
+    pub fn take_delta(&mut self, table: &str) -> PoneglyphResult<CommitmentDelta> {
+        let entry = self.table_mut(table)?;
+        let updates = std::mem::take(&mut entry.pending);
+        let old_root = updates
+            .first()
+            .map_or(entry.tree.root(), MerkleUpdate::old_root);
+        Ok(CommitmentDelta {
+            depth: entry.tree.depth(),
+            updates,
+            old_root,
+        })
+    }
+
+    /// Resolve `table.column`
+    pub fn resolve(&self, table: &str, column: &str) -> PoneglyphResult<&ColumnDef> {
+        let schema = self
+            .schema(table)
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", table)))?;
+        schema
+            .index_of(column)
+            .map(|idx| &schema.columns[idx])
+            .ok_or_else(|| {
+                PoneglyphError::InvalidInput(format!(
+                    "Column {} not found in table {}",
+                    column, table
+                ))
+            })
+    }
+
+    /// Insert a row given in schema order
+    pub fn insert(&mut self, table: &str, row: &[Datum]) -> PoneglyphResult<()> {
+        let entry = self.table_mut(table)?;
+        let encoded = Self::encode(entry, table, row)?;
+        entry.set_leaf(entry.data.data.len(), Some(encoded.as_slice()))?;
+        entry.data.insert(encoded);
+        Ok(())
+    }
+
+    /// Replace the row at `index` (insertion order)
+    pub fn update(&mut self, table: &str, index: usize, row: &[Datum]) -> PoneglyphResult<()> {
+        let entry = self.table_mut(table)?;
+        Self::check_row(entry, table, index)?;
+        let encoded = Self::encode(entry, table, row)?;
+        entry.set_leaf(index, Some(encoded.as_slice()))?;
+        entry.data.data[index] = encoded;
+        Ok(())
+    }
+
+    /// Delete the row at `index`
+    /// The last row moves into its slot (two leaf changes unless it is the
+    /// last row), so row indexes stay dense.
+    pub fn delete(&mut self, table: &str, index: usize) -> PoneglyphResult<()> {
+        let entry = self.table_mut(table)?;
+        Self::check_row(entry, table, index)?;
+        let last = entry.data.data.len() - 1;
+        if index != last {
+            let moved = entry.data.data[last].clone();
+            entry.set_leaf(index, Some(moved.as_slice()))?;
+        }
+        entry.set_leaf(last, None)?;
+        entry.data.data.swap_remove(index);
+        Ok(())
+    }
+
+    /// Insert a row given as (column, value) pairs
+    /// Every column must be given exactly once, in any order
+    pub fn insert_named(&mut self, table: &str, row: &[(&str, Datum)]) -> PoneglyphResult<()> {
+        let schema = self
+            .schema(table)
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", table)))?;
+        let mut ordered: Vec<Option<Datum>> = vec![None; schema.columns.len()];
+        for (column, datum) in row {
+            let idx = schema.index_of(column).ok_or_else(|| {
+                PoneglyphError::InvalidInput(format!(
+                    "Column {} not found in table {}",
+                    column, table
+                ))
+            })?;
+            if ordered[idx].replace(datum.clone()).is_some() {
+                return Err(PoneglyphError::InvalidInput(format!(
+                    "Column {} given twice",
+                    column
+                )));
+            }
+        }
+        let ordered = ordered
+            .into_iter()
+            .zip(&schema.columns)
+            .map(|(datum, column)| {
+                datum.ok_or_else(|| {
+                    PoneglyphError::InvalidInput(format!(
+                        "Missing value for column {}",
+                        column.name
+                    ))
+                })
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        self.insert(table, &ordered)
+    }
+
+    /// Typed rows of a table, in insertion order
+    pub fn rows(&self, table: &str) -> PoneglyphResult<Vec<Vec<Datum>>> {
+        let entry = self
+            .tables
+            .get(&table.to_lowercase())
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", table)))?;
+        entry
+            .data
+            .data
+            .iter()
+            .map(|row| {
+                entry
+                    .schema
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, &raw)| {
+                        Datum::decode(raw, column.ty, &entry.text).ok_or_else(|| {
+                            PoneglyphError::Validation(format!(
+                                "Column {}: no text value for hash {}",
+                                column.name, raw
+                            ))
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// All tables in the format consumed by `SQLCompiler::compile`
+    /// (table name -> column name -> values)
+    pub fn table_data(&self) -> HashMap<String, HashMap<String, Vec<u64>>> {
+        self.tables
+            .iter()
+            .map(|(name, table)| (name.clone(), table.data.table_data()))
+            .collect()
+    }
+
+    /// Encode a row for a table, remembering its text values
+    fn encode(entry: &mut CatalogTable, table: &str, row: &[Datum]) -> PoneglyphResult<Vec<u64>> {
+        let encoded = entry
+            .schema
+            .encode_row(row)
+            .map_err(|e| PoneglyphError::InvalidInput(format!("Table {}: {}", table, e)))?;
+        for (datum, &raw) in row.iter().zip(&encoded) {
+            if let Datum::Text(value) = datum {
+                entry.text.insert(raw, value.clone());
+            }
+        }
+        Ok(encoded)
+    }
+
+    fn check_row(entry: &CatalogTable, table: &str, index: usize) -> PoneglyphResult<()> {
+        if index >= entry.data.data.len() {
+            return Err(PoneglyphError::InvalidInput(format!(
+                "Table {} has no row {}",
+                table, index
+            )));
+        }
+        Ok(())
+    }
+
+    fn table_mut(&mut self, table: &str) -> PoneglyphResult<&mut CatalogTable> {
+        self.tables
+            .get_mut(&table.to_lowercase())
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", table)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> Database {
+        let mut db = Database::new();
+        db.create_table(
+            "Orders",
+            &[
+                ("id", ColumnType::Integer),
+                ("Price", ColumnType::Decimal(2)),
+                ("paid", ColumnType::Boolean),
+            ],
+        )
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn test_create_table() {
+        let mut db = database();
+        assert_eq!(db.table_names(), vec!["orders"]);
+        assert_eq!(
+            db.resolve("ORDERS", "price").unwrap().ty,
+            ColumnType::Decimal(2)
+        );
+        assert_eq!(db.table("orders").unwrap().scale_of("price"), Some(2));
+        assert!(db.resolve("orders", "total").is_err());
+        assert!(db.resolve("customers", "id").is_err());
+
+        assert!(db
+            .create_table("orders", &[("id", ColumnType::Integer)])
+            .is_err());
+        assert!(db
+            .create_table("t", &[("a", ColumnType::Integer), ("A", ColumnType::Text)])
+            .is_err());
+    }
+
+    #[test]
+    fn test_typed_insert() {
+        let mut db = database();
+        db.insert(
+            "orders",
+            &[
+                Datum::Integer(1),
+                Datum::Decimal(Decimal::parse("12.5", 1).unwrap()),
+                Datum::Boolean(true),
+            ],
+        )
+        .unwrap();
+        db.insert_named(
+            "orders",
+            &[
+                ("paid", Datum::Boolean(false)),
+                ("id", Datum::Integer(2)),
+                ("price", Datum::Integer(3)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            db.table("orders").unwrap().data,
+            vec![vec![1, 1250, 1], vec![2, 300, 0]]
+        );
+
+        // Wrong type, arity, lost digits, missing and repeated columns
+        assert!(db
+            .insert(
+                "orders",
+                &[
+                    Datum::Text("1".into()),
+                    Datum::Integer(1),
+                    Datum::Boolean(true)
+                ]
+            )
+            .is_err());
+        assert!(db.insert("orders", &[Datum::Integer(1)]).is_err());
+        assert!(db
+            .insert(
+                "orders",
+                &[
+                    Datum::Integer(1),
+                    Datum::Decimal(Decimal::new(1, 3)),
+                    Datum::Boolean(true)
+                ]
+            )
+            .is_err());
+        assert!(db
+            .insert_named("orders", &[("id", Datum::Integer(1))])
+            .is_err());
+        assert!(db
+            .insert_named(
+                "orders",
+                &[
+                    ("id", Datum::Integer(1)),
+                    ("ID", Datum::Integer(1)),
+                    ("price", Datum::Integer(1)),
+                    ("paid", Datum::Boolean(true)),
+                ]
+            )
+            .is_err());
+        assert_eq!(db.table("orders").unwrap().data.len(), 2);
+    }
+
+    #[test]
+    fn test_dates() {
+        let parse = |s| Datum::parse(s, ColumnType::Date);
+        assert_eq!(parse("1970-01-01"), Ok(Datum::Date(0)));
+        assert_eq!(parse(" 2000-03-01 "), Ok(Datum::Date(11017)));
+        assert_eq!(parse("2024-02-29"), Ok(Datum::Date(19782)));
+        for invalid in [
+            "1969-12-31",
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-01",
+            "01/02/2024",
+        ] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+
+        // Day numbers order like dates and print back
+        for date in [
+            "1970-01-01",
+            "1999-12-31",
+            "2000-02-29",
+            "2100-03-01",
+            "9999-12-31",
+        ] {
+            let datum = parse(date).unwrap();
+            assert_eq!(datum.to_string(), date);
+            assert_eq!(
+                Datum::decode(
+                    datum.encode(ColumnType::Date).unwrap(),
+                    ColumnType::Date,
+                    &HashMap::new()
+                ),
+                Some(datum)
+            );
+        }
+        assert!(
+            parse("2024-01-31").unwrap().encode(ColumnType::Date)
+                < parse("2024-02-01").unwrap().encode(ColumnType::Date)
+        );
+        assert!(Datum::Integer(1).encode(ColumnType::Date).is_err());
+    }
+
+    #[test]
+    fn test_table_data_by_name() {
+        let mut db = database();
+        db.create_table("names", &[("name", ColumnType::Text)])
+            .unwrap();
+        db.insert("names", &[Datum::Text("alice".into())]).unwrap();
+        db.insert(
+            "orders",
+            &[Datum::Integer(7), Datum::Integer(1), Datum::Boolean(true)],
+        )
+        .unwrap();
+
+        let data = db.table_data();
+        assert_eq!(data["orders"]["price"], vec![100]);
+        assert_eq!(data["names"]["name"], vec![simple_hash(b"alice")]);
+        assert!(db.drop_table("NAMES").is_some());
+        assert!(db.table("names").is_none());
+    }
+}
@@ -0,0 +1,366 @@
+// Arrow / Parquet ingestion (feature `arrow`)
+// Analytics datasets usually live in Parquet files or arrive as Arrow record
+// batches. Columns are mapped to circuit column types by their Arrow type:
+//
+// | Arrow                          | `ColumnType`      |
+// |--------------------------------|-------------------|
+// | Int8..Int64, UInt8..UInt64     | `Integer`         |
+// | Boolean                        | `Boolean`         |
+// | Decimal128(_, scale >= 0)      | `Decimal(scale)`  |
+// | Utf8, LargeUtf8                | `Text`            |
+// | Date32                         | `Date`            |
+//
+// Floats have no exact fixed-point value and are rejected, as are NULLs and
+// negative values (circuit columns are unsigned). As with CSV, every batch is
+// converted before the table is created, so a bad batch loads nothing.
+
+use std::fs::File;
+use std::path::Path;
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{
+    DataType, Date32Type, Decimal128Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+use crate::circuit::Decimal;
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+use super::{ColumnType, Database, DatabaseCommitment, Datum, Schema};
+
+impl ColumnType {
+    /// Column type of an Arrow data type (see the module table)
+    pub fn from_arrow(data_type: &DataType) -> Result<Self, String> {
+        match data_type {
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64 => Ok(ColumnType::Integer),
+            DataType::Boolean => Ok(ColumnType::Boolean),
+            DataType::Decimal128(_, scale) if *scale >= 0 => Ok(ColumnType::Decimal(*scale as u32)),
+            DataType::Utf8 | DataType::LargeUtf8 => Ok(ColumnType::Text),
+            DataType::Date32 => Ok(ColumnType::Date),
+            other => Err(format!("Arrow type {} has no circuit column type", other)),
+        }
+    }
+}
+
+impl Schema {
+    /// Schema of an Arrow schema, with types mapped by `ColumnType::from_arrow`
+    pub fn from_arrow(schema: &arrow::datatypes::Schema) -> PoneglyphResult<Self> {
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                ColumnType::from_arrow(field.data_type())
+                    .map(|ty| (field.name().as_str(), ty))
+                    .map_err(|e| {
+                        PoneglyphError::InvalidInput(format!("Column {}: {}", field.name(), e))
+                    })
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        Schema::new(&columns)
+    }
+}
+
+/// Integer values as `Datum::Integer`, rejecting negatives
+fn integers<T>(values: &[T]) -> Result<Vec<Datum>, String>
+where
+    T: Copy + std::fmt::Display,
+    u64: TryFrom<T>,
+{
+    values
+        .iter()
+        .map(|&value| {
+            u64::try_from(value)
+                .map(Datum::Integer)
+                .map_err(|_| format!("negative value {}", value))
+        })
+        .collect()
+}
+
+/// Values of one Arrow column as `ty`
+fn column_datums(array: &dyn Array, ty: ColumnType) -> Result<Vec<Datum>, String> {
+    if array.null_count() > 0 {
+        return Err(format!("{} NULL values", array.null_count()));
+    }
+    match (array.data_type(), ty) {
+        (DataType::Int8, _) => integers(&array.as_primitive::<Int8Type>().values()[..]),
+        (DataType::Int16, _) => integers(&array.as_primitive::<Int16Type>().values()[..]),
+        (DataType::Int32, _) => integers(&array.as_primitive::<Int32Type>().values()[..]),
+        (DataType::Int64, _) => integers(&array.as_primitive::<Int64Type>().values()[..]),
+        (DataType::UInt8, _) => integers(&array.as_primitive::<UInt8Type>().values()[..]),
+        (DataType::UInt16, _) => integers(&array.as_primitive::<UInt16Type>().values()[..]),
+        (DataType::UInt32, _) => integers(&array.as_primitive::<UInt32Type>().values()[..]),
+        (DataType::UInt64, _) => integers(&array.as_primitive::<UInt64Type>().values()[..]),
+        (DataType::Boolean, _) => Ok(array
+            .as_boolean()
+            .values()
+            .iter()
+            .map(Datum::Boolean)
+            .collect()),
+        (DataType::Decimal128(_, _), ColumnType::Decimal(scale)) => array
+            .as_primitive::<Decimal128Type>()
+            .values()
+            .iter()
+            .map(|&value| {
+                u64::try_from(value)
+                    .map(|raw| Datum::Decimal(Decimal::new(raw, scale)))
+                    .map_err(|_| {
+                        format!(
+                            "decimal {} does not fit an unsigned 64-bit raw value",
+                            value
+                        )
+                    })
+            })
+            .collect(),
+        (DataType::Date32, ColumnType::Date) => array
+            .as_primitive::<Date32Type>()
+            .values()
+            .iter()
+            .map(|&days| {
+                u64::try_from(days)
+                    .map(Datum::Date)
+                    .map_err(|_| format!("date {} days before 1970-01-01", -days))
+            })
+            .collect(),
+        (DataType::Utf8, _) => Ok(array
+            .as_string::<i32>()
+            .iter()
+            .map(|value| Datum::Text(value.unwrap_or_default().to_string()))
+            .collect()),
+        (DataType::LargeUtf8, _) => Ok(array
+            .as_string::<i64>()
+            .iter()
+            .map(|value| Datum::Text(value.unwrap_or_default().to_string()))
+            .collect()),
+        (data_type, ty) => Err(format!(
+            "Arrow type {} can't be read as {:?}",
+            data_type, ty
+        )),
+    }
+}
+
+impl Database {
+    /// Create table `name` from Arrow record batches and return its commitment
+    /// The schema is taken from the first batch; all batches must share it.
+    pub fn load_record_batches(
+        &mut self,
+        name: &str,
+        batches: &[RecordBatch],
+    ) -> PoneglyphResult<DatabaseCommitment> {
+        let arrow_schema = batches
+            .first()
+            .map(|batch| batch.schema())
+            .ok_or_else(|| PoneglyphError::InvalidInput("No record batches".to_string()))?;
+        let schema = Schema::from_arrow(&arrow_schema)?;
+
+        let mut rows = Vec::new();
+        for (index, batch) in batches.iter().enumerate() {
+            if batch.schema().fields() != arrow_schema.fields() {
+                return Err(PoneglyphError::Validation(format!(
+                    "Record batch {} has a different schema than batch 0",
+                    index
+                )));
+            }
+            let columns = schema
+                .columns
+                .iter()
+                .zip(batch.columns())
+                .map(|(column, array)| {
+                    column_datums(array.as_ref(), column.ty).map_err(|e| {
+                        PoneglyphError::Validation(format!(
+                            "Record batch {}, column {}: {}",
+                            index, column.name, e
+                        ))
+                    })
+                })
+                .collect::<PoneglyphResult<Vec<_>>>()?;
+            rows.extend((0..batch.num_rows()).map(|row| {
+                columns
+                    .iter()
+                    .map(|column| column[row].clone())
+                    .collect::<Vec<_>>()
+            }));
+        }
+
+        self.create_table_with_schema(name, schema)?;
+        for row in &rows {
+            self.insert(name, row)?;
+        }
+        Ok(self.commit(name).expect("table was just created"))
+    }
+
+    /// Create table `name` from a Parquet file and return its commitment
+    pub fn load_parquet(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+    ) -> PoneglyphResult<DatabaseCommitment> {
+        let path = path.as_ref();
+        let parquet_error = |e: &dyn std::fmt::Display| {
+            PoneglyphError::Serialization(format!("{}: {}", path.display(), e))
+        };
+        let file = File::open(path).map_err(|e| parquet_error(&e))?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .and_then(|builder| builder.build())
+            .map_err(|e| parquet_error(&e))?;
+        let batches = reader
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| parquet_error(&e))?;
+        self.load_record_batches(name, &batches)
+    }
+
+    /// Load a Parquet file into a new database with a single table
+    /// The table is named after the file stem (`lineitem.parquet` -> `lineitem`).
+    pub fn from_parquet(path: impl AsRef<Path>) -> PoneglyphResult<Self> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| {
+                PoneglyphError::InvalidInput(format!(
+                    "{}: file name is not a table name",
+                    path.display()
+                ))
+            })?;
+        let mut database = Database::new();
+        database.load_parquet(name, path)?;
+        Ok(database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{
+        BooleanArray, Decimal128Array, Float64Array, Int32Array, StringArray, UInt64Array,
+    };
+    use arrow::datatypes::{Field, Schema as ArrowSchema};
+    use parquet::arrow::ArrowWriter;
+    use std::sync::Arc;
+
+    fn batch(ids: Vec<u64>, prices: Vec<i128>) -> RecordBatch {
+        let schema = ArrowSchema::new(vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("price", DataType::Decimal128(10, 2), false),
+            Field::new("paid", DataType::Boolean, false),
+            Field::new("note", DataType::Utf8, false),
+        ]);
+        let n = ids.len();
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(UInt64Array::from(ids)),
+                Arc::new(
+                    Decimal128Array::from(prices)
+                        .with_precision_and_scale(10, 2)
+                        .unwrap(),
+                ),
+                Arc::new(BooleanArray::from(vec![true; n])),
+                Arc::new(StringArray::from(vec!["x"; n])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_type_mapping() {
+        assert_eq!(
+            ColumnType::from_arrow(&DataType::Int16),
+            Ok(ColumnType::Integer)
+        );
+        assert_eq!(
+            ColumnType::from_arrow(&DataType::Decimal128(12, 3)),
+            Ok(ColumnType::Decimal(3))
+        );
+        assert_eq!(
+            ColumnType::from_arrow(&DataType::LargeUtf8),
+            Ok(ColumnType::Text)
+        );
+        assert!(ColumnType::from_arrow(&DataType::Float64).is_err());
+        assert!(ColumnType::from_arrow(&DataType::Decimal128(12, -2)).is_err());
+    }
+
+    #[test]
+    fn test_load_record_batches() {
+        let mut db = Database::new();
+        db.load_record_batches(
+            "orders",
+            &[batch(vec![1, 2], vec![1250, 300]), batch(vec![3], vec![5])],
+        )
+        .unwrap();
+        assert_eq!(
+            db.resolve("orders", "price").unwrap().ty,
+            ColumnType::Decimal(2)
+        );
+        assert_eq!(db.table_data()["orders"]["price"], vec![1250, 300, 5]);
+        assert_eq!(db.table_data()["orders"]["id"], vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rejects_unmappable_batches() {
+        let floats = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "x",
+                DataType::Float64,
+                false,
+            )])),
+            vec![Arc::new(Float64Array::from(vec![1.5]))],
+        )
+        .unwrap();
+        let negative = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "x",
+                DataType::Int32,
+                false,
+            )])),
+            vec![Arc::new(Int32Array::from(vec![1, -1]))],
+        )
+        .unwrap();
+        let nulls = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "x",
+                DataType::Int32,
+                true,
+            )])),
+            vec![Arc::new(Int32Array::from(vec![Some(1), None]))],
+        )
+        .unwrap();
+
+        let mut db = Database::new();
+        for batch in [floats, negative, nulls] {
+            assert!(db.load_record_batches("t", &[batch]).is_err());
+            assert!(db.table("t").is_none());
+        }
+        assert!(db.load_record_batches("t", &[]).is_err());
+    }
+
+    #[test]
+    fn test_parquet_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("poneglyph-parquet-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("orders.parquet");
+
+        let batch = batch(vec![7, 8], vec![100, 250]);
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let db = Database::from_parquet(&path).unwrap();
+        let mut expected = Database::new();
+        expected.load_record_batches("orders", &[batch]).unwrap();
+        assert_eq!(db.rows("orders").unwrap(), expected.rows("orders").unwrap());
+        assert_eq!(
+            db.commit("orders").unwrap().commitment(),
+            expected.commit("orders").unwrap().commitment()
+        );
+    }
+}
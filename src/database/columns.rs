@@ -0,0 +1,240 @@
+// Column-level commitments
+// A table is committed column by column: each column is a Poseidon Merkle
+// tree over its values, and the table root is a Merkle tree over the column
+// digests (name, row count and root of each column). A statement about some
+// columns opens just those against the table root, so the prover loads only
+// them into the witness and the verifier learns which columns were touched.
+
+use std::collections::HashMap;
+
+use pasta_curves::pallas::Base as Fr;
+
+use super::{merkle_leaf, merkle_root_from_path, DatabaseTable, MerkleTree, MERKLE_DEPTH};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::utils::{poseidon_hash, poseidon_hash_bytes};
+
+/// Depth of the tree over the columns of a table (up to 256 columns)
+pub const COLUMN_TREE_DEPTH: usize = 8;
+
+/// Commitment to one column
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnCommitment {
+    pub name: String,
+    pub rows: u64,
+    /// Merkle root over `merkle_leaf([value])` of the values, in row order
+    pub root: Fr,
+}
+
+impl ColumnCommitment {
+    /// Commit the values of a column (tree of `MERKLE_DEPTH`)
+    pub fn new(name: &str, values: &[u64]) -> PoneglyphResult<Self> {
+        let leaves = values
+            .iter()
+            .map(|&v| merkle_leaf(Some(&[v][..])))
+            .collect();
+        Ok(Self {
+            name: name.to_string(),
+            rows: values.len() as u64,
+            root: MerkleTree::from_leaves(MERKLE_DEPTH, leaves)?.root(),
+        })
+    }
+
+    /// Leaf of the column in the table root: `poseidon([name, rows, root])`
+    pub fn digest(&self) -> Fr {
+        poseidon_hash(&[
+            poseidon_hash_bytes(self.name.as_bytes()),
+            Fr::from(self.rows),
+            self.root,
+        ])
+    }
+
+    /// Whether `values` are the committed column
+    pub fn matches(&self, values: &[u64]) -> bool {
+        Self::new(&self.name, values).is_ok_and(|c| c == *self)
+    }
+}
+
+/// Column commitments of a table and the root over them
+#[derive(Clone, Debug)]
+pub struct ColumnCommitments {
+    pub columns: Vec<ColumnCommitment>,
+    tree: MerkleTree,
+}
+
+impl ColumnCommitments {
+    /// Commit every column of a table
+    pub fn commit(table: &DatabaseTable) -> PoneglyphResult<Self> {
+        let columns = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let values: Vec<u64> = table.data.iter().map(|row| row[idx]).collect();
+                ColumnCommitment::new(name, &values)
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        let digests = columns.iter().map(ColumnCommitment::digest).collect();
+        let tree = MerkleTree::from_leaves(COLUMN_TREE_DEPTH, digests)?;
+        Ok(Self { columns, tree })
+    }
+
+    /// Root over the columns (the public table commitment)
+    pub fn root(&self) -> Fr {
+        self.tree.root()
+    }
+
+    pub fn column(&self, name: &str) -> Option<&ColumnCommitment> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Open the named columns against the root; the others stay hidden
+    pub fn open(&self, names: &[&str]) -> PoneglyphResult<ColumnDisclosure> {
+        let openings = names
+            .iter()
+            .map(|name| {
+                let index = self
+                    .columns
+                    .iter()
+                    .position(|c| c.name == *name)
+                    .ok_or_else(|| {
+                        PoneglyphError::InvalidInput(format!("Column {} not found", name))
+                    })?;
+                Ok(ColumnOpening {
+                    index: index as u64,
+                    column: self.columns[index].clone(),
+                    siblings: self.tree.path(index as u64),
+                })
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        Ok(ColumnDisclosure {
+            table_root: self.root(),
+            openings,
+        })
+    }
+}
+
+/// One column commitment with its path to the table root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnOpening {
+    /// Position of the column in the table
+    pub index: u64,
+    pub column: ColumnCommitment,
+    pub siblings: Vec<Fr>,
+}
+
+impl ColumnOpening {
+    /// Table root this opening leads to
+    pub fn table_root(&self) -> Fr {
+        merkle_root_from_path(self.column.digest(), self.index, &self.siblings)
+    }
+}
+
+/// Columns a statement touches, opened against the table root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnDisclosure {
+    pub table_root: Fr,
+    pub openings: Vec<ColumnOpening>,
+}
+
+impl ColumnDisclosure {
+    /// Whether every opening leads to `table_root`
+    pub fn verify(&self, table_root: Fr) -> bool {
+        self.table_root == table_root
+            && self
+                .openings
+                .iter()
+                .all(|opening| opening.table_root() == table_root)
+    }
+
+    /// Names of the disclosed columns
+    pub fn columns(&self) -> Vec<&str> {
+        self.openings
+            .iter()
+            .map(|opening| opening.column.name.as_str())
+            .collect()
+    }
+
+    /// Column values for witness generation, in the format consumed by
+    /// `SQLCompiler::compile` (column name -> values)
+    /// Fails unless `values` holds every disclosed column as committed;
+    /// other columns are dropped.
+    pub fn table_data(
+        &self,
+        values: &HashMap<String, Vec<u64>>,
+    ) -> PoneglyphResult<HashMap<String, Vec<u64>>> {
+        self.openings
+            .iter()
+            .map(|opening| {
+                let name = &opening.column.name;
+                let column = values
+                    .get(name)
+                    .filter(|column| opening.column.matches(column))
+                    .ok_or_else(|| {
+                        PoneglyphError::Validation(format!(
+                            "Column {} does not match its commitment",
+                            name
+                        ))
+                    })?;
+                Ok((name.clone(), column.clone()))
+            })
+            .collect()
+    }
+}
+
+impl DatabaseTable {
+    /// Column-level commitments (see `ColumnCommitments`)
+    pub fn commit_columns(&self) -> PoneglyphResult<ColumnCommitments> {
+        ColumnCommitments::commit(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn employees() -> DatabaseTable {
+        let mut table = DatabaseTable::new(
+            "employees".to_string(),
+            vec!["id".to_string(), "salary".to_string(), "ssn".to_string()],
+        );
+        for id in 0..5 {
+            table.insert(vec![id, 1000 * (id + 1), 555_000 + id]);
+        }
+        table
+    }
+
+    #[test]
+    fn test_open_selected_columns() {
+        let commitments = employees().commit_columns().unwrap();
+        let disclosure = commitments.open(&["salary"]).unwrap();
+        assert!(disclosure.verify(commitments.root()));
+        assert_eq!(disclosure.columns(), vec!["salary"]);
+
+        let table_data = employees().table_data();
+        let witness = disclosure.table_data(&table_data).unwrap();
+        assert_eq!(witness.len(), 1);
+        assert_eq!(witness["salary"], table_data["salary"]);
+
+        assert!(commitments.open(&["bonus"]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_other_values_and_roots() {
+        let commitments = employees().commit_columns().unwrap();
+        let disclosure = commitments.open(&["id", "salary"]).unwrap();
+
+        let mut table_data = employees().table_data();
+        table_data.get_mut("salary").unwrap()[2] += 1;
+        assert!(disclosure.table_data(&table_data).is_err());
+
+        let mut other = employees();
+        other.data[0][2] = 0;
+        assert_ne!(other.commit_columns().unwrap().root(), commitments.root());
+        assert!(!disclosure.verify(other.commit_columns().unwrap().root()));
+
+        // An opening moved to another column position does not verify
+        let mut moved = disclosure.clone();
+        moved.openings[0].index = 2;
+        assert!(!moved.verify(commitments.root()));
+    }
+}
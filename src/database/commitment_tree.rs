@@ -0,0 +1,382 @@
+// Incremental table commitments
+// Each catalog table keeps a Poseidon Merkle tree over its rows (leaf i is
+// `poseidon_hash_row` of row i, an empty slot is zero). Changing a row
+// rehashes one path of `depth` nodes instead of the whole table.
+//
+// Every leaf change is recorded with its authentication path, so a batch of
+// changes can be proven with `CommitmentDeltaCircuit`: the new root differs
+// from the old one only at the claimed leaves. The claim (`DeltaClaim`) is
+// public; the paths are the witness.
+//
+// Loading a whole table hashes its leaves and builds the tree bottom-up in one
+// pass (`merkle_leaves`, `MerkleTree::from_leaves`). Every node of a level
+// only depends on the level below, so with the `parallel` feature each level
+// is hashed with rayon; the tree is the same either way.
+
+use ff::Field;
+use halo2_proofs::{
+    circuit::Value,
+    pasta::EqAffine,
+    plonk::{keygen_vk, Error},
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::circuit::{
+    CommitmentDeltaCircuit, DeltaWitness, DELTA_INSTANCE_CHANGES_ROW, DELTA_INSTANCE_NEW_ROOT_ROW,
+    DELTA_INSTANCE_OLD_ROOT_ROW,
+};
+use crate::error::{PoneglyphError, PoneglyphResult, VerifyError};
+use crate::recursive::{verify_on_curve, CycleProver};
+use crate::utils::{poseidon_hash, poseidon_hash_row};
+
+/// Default tree depth of catalog tables (up to 2^24 rows)
+pub const MERKLE_DEPTH: usize = 24;
+
+/// Fewest hashes handed to one rayon task
+#[cfg(feature = "parallel")]
+const MIN_HASHES_PER_TASK: usize = 256;
+
+/// `f(0..n)`, in order, at least `MIN_HASHES_PER_TASK` per task
+#[cfg(feature = "parallel")]
+fn hash_each(n: usize, f: impl Fn(usize) -> Fr + Sync + Send) -> Vec<Fr> {
+    (0..n)
+        .into_par_iter()
+        .with_min_len(MIN_HASHES_PER_TASK)
+        .map(f)
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn hash_each(n: usize, f: impl Fn(usize) -> Fr) -> Vec<Fr> {
+    (0..n).map(f).collect()
+}
+
+/// Leaf of a row slot: `poseidon_hash_row` of the row, zero if it is empty
+pub fn merkle_leaf(row: Option<&[u64]>) -> Fr {
+    row.map_or(Fr::ZERO, poseidon_hash_row)
+}
+
+/// Leaves of consecutive rows (in parallel with the `parallel` feature)
+pub fn merkle_leaves(rows: &[Vec<u64>]) -> Vec<Fr> {
+    hash_each(rows.len(), |i| poseidon_hash_row(&rows[i]))
+}
+
+/// Parent of two nodes
+fn merkle_node(left: Fr, right: Fr) -> Fr {
+    poseidon_hash(&[left, right])
+}
+
+/// Root of the tree holding `leaf` at `index`, given the siblings from the
+/// leaf level up
+pub fn merkle_root_from_path(leaf: Fr, index: u64, siblings: &[Fr]) -> Fr {
+    siblings
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (level, &sibling)| {
+            if (index >> level) & 1 == 0 {
+                merkle_node(node, sibling)
+            } else {
+                merkle_node(sibling, node)
+            }
+        })
+}
+
+/// Sparse Poseidon Merkle tree of fixed depth
+/// Only the populated prefix of each level is stored; the rest are the roots
+/// of empty subtrees.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    depth: usize,
+    /// levels[0] are the leaves, levels[depth] the root
+    levels: Vec<Vec<Fr>>,
+    /// Root of an empty subtree per level
+    empty: Vec<Fr>,
+}
+
+impl MerkleTree {
+    /// Empty tree with 2^depth leaf slots (1 <= depth <= 63)
+    pub fn new(depth: usize) -> Self {
+        assert!((1..64).contains(&depth), "Merkle depth must be in 1..64");
+        let mut empty = vec![Fr::ZERO];
+        for level in 0..depth {
+            empty.push(merkle_node(empty[level], empty[level]));
+        }
+        Self {
+            depth,
+            levels: vec![Vec::new(); depth + 1],
+            empty,
+        }
+    }
+
+    /// Tree with `leaves` in slots 0..n
+    pub fn from_leaves(depth: usize, leaves: Vec<Fr>) -> PoneglyphResult<Self> {
+        let mut tree = Self::new(depth);
+        tree.check_index(leaves.len().saturating_sub(1) as u64)?;
+        tree.levels[0] = leaves;
+        for level in 0..depth {
+            let below = &tree.levels[level];
+            let parents = hash_each(below.len().div_ceil(2), |i| {
+                merkle_node(below[2 * i], tree.node(level, 2 * i + 1))
+            });
+            tree.levels[level + 1] = parents;
+        }
+        Ok(tree)
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Number of leaf slots
+    pub fn capacity(&self) -> u64 {
+        1 << self.depth
+    }
+
+    pub fn root(&self) -> Fr {
+        self.node(self.depth, 0)
+    }
+
+    pub fn leaf(&self, index: u64) -> Fr {
+        self.node(0, index as usize)
+    }
+
+    /// Siblings of a leaf from the leaf level up
+    pub fn path(&self, index: u64) -> Vec<Fr> {
+        (0..self.depth)
+            .map(|level| self.node(level, ((index >> level) ^ 1) as usize))
+            .collect()
+    }
+
+    /// Set a leaf, rehashing its path
+    /// Returns the change with its authentication path (siblings are not
+    /// affected by the change, so one path proves both roots)
+    pub fn set(&mut self, index: u64, leaf: Fr) -> PoneglyphResult<MerkleUpdate> {
+        self.check_index(index)?;
+        let update = MerkleUpdate {
+            index,
+            old_leaf: self.leaf(index),
+            new_leaf: leaf,
+            siblings: self.path(index),
+        };
+
+        let mut node = leaf;
+        for level in 0..=self.depth {
+            let i = (index >> level) as usize;
+            let nodes = &mut self.levels[level];
+            if nodes.len() <= i {
+                nodes.resize(i + 1, self.empty[level]);
+            }
+            nodes[i] = node;
+            if level < self.depth {
+                let sibling = update.siblings[level];
+                node = if i & 1 == 0 {
+                    merkle_node(node, sibling)
+                } else {
+                    merkle_node(sibling, node)
+                };
+            }
+        }
+        Ok(update)
+    }
+
+    fn node(&self, level: usize, i: usize) -> Fr {
+        self.levels[level]
+            .get(i)
+            .copied()
+            .unwrap_or(self.empty[level])
+    }
+
+    fn check_index(&self, index: u64) -> PoneglyphResult<()> {
+        if index >= self.capacity() {
+            return Err(PoneglyphError::InvalidInput(format!(
+                "Row {} does not fit a Merkle tree of depth {}",
+                index, self.depth
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// One leaf change with the authentication path at the time of the change
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleUpdate {
+    pub index: u64,
+    pub old_leaf: Fr,
+    pub new_leaf: Fr,
+    pub siblings: Vec<Fr>,
+}
+
+impl MerkleUpdate {
+    pub fn old_root(&self) -> Fr {
+        merkle_root_from_path(self.old_leaf, self.index, &self.siblings)
+    }
+
+    pub fn new_root(&self) -> Fr {
+        merkle_root_from_path(self.new_leaf, self.index, &self.siblings)
+    }
+
+    /// Public part of the change
+    pub fn change(&self) -> RowChange {
+        RowChange {
+            index: self.index,
+            old_leaf: self.old_leaf,
+            new_leaf: self.new_leaf,
+        }
+    }
+}
+
+/// Claimed change of one row slot
+/// Leaves are `merkle_leaf` of the old and new row, so a verifier who knows
+/// the rows checks them without the tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RowChange {
+    pub index: u64,
+    pub old_leaf: Fr,
+    pub new_leaf: Fr,
+}
+
+/// Public statement of a delta proof: `old_root` becomes `new_root` by
+/// applying `changes` in order, and nothing else
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeltaClaim {
+    pub depth: usize,
+    pub old_root: Fr,
+    pub new_root: Fr,
+    pub changes: Vec<RowChange>,
+}
+
+impl DeltaClaim {
+    /// Instance column of `CommitmentDeltaCircuit`
+    pub fn instance(&self) -> Vec<Fr> {
+        let mut rows = vec![Fr::ZERO; DELTA_INSTANCE_CHANGES_ROW];
+        rows[DELTA_INSTANCE_OLD_ROOT_ROW] = self.old_root;
+        rows[DELTA_INSTANCE_NEW_ROOT_ROW] = self.new_root;
+        for change in &self.changes {
+            rows.extend([Fr::from(change.index), change.old_leaf, change.new_leaf]);
+        }
+        rows
+    }
+
+    /// Verify a proof from `CommitmentDelta::prove`
+    /// The verifying key is derived from the claim's shape (depth, number of
+    /// changes), so the verifier needs nothing but `params`.
+    pub fn verify(&self, params: &Params<EqAffine>, proof: &[u8]) -> Result<(), VerifyError> {
+        if self.changes.is_empty() {
+            if self.old_root != self.new_root {
+                return Err(VerifyError::InstanceMismatch(
+                    "root changed without any row change".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        let shape = CommitmentDeltaCircuit::shape(self.depth, self.changes.len());
+        let vk = keygen_vk(params, &shape)?;
+        verify_on_curve(params, &vk, proof, &[self.instance()])?;
+        Ok(())
+    }
+}
+
+/// Changes of a table since the last delta, with their witnesses
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentDelta {
+    pub depth: usize,
+    pub updates: Vec<MerkleUpdate>,
+    /// Root before the first update (the current root if there are none)
+    pub old_root: Fr,
+}
+
+impl CommitmentDelta {
+    /// Root after the last update
+    pub fn new_root(&self) -> Fr {
+        self.updates
+            .last()
+            .map_or(self.old_root, MerkleUpdate::new_root)
+    }
+
+    pub fn claim(&self) -> DeltaClaim {
+        DeltaClaim {
+            depth: self.depth,
+            old_root: self.old_root,
+            new_root: self.new_root(),
+            changes: self.updates.iter().map(MerkleUpdate::change).collect(),
+        }
+    }
+
+    pub fn circuit(&self) -> CommitmentDeltaCircuit {
+        CommitmentDeltaCircuit {
+            depth: self.depth,
+            changes: self
+                .updates
+                .iter()
+                .map(|update| DeltaWitness {
+                    index: Value::known(update.index),
+                    old_leaf: Value::known(update.old_leaf),
+                    new_leaf: Value::known(update.new_leaf),
+                    siblings: update.siblings.iter().map(|&s| Value::known(s)).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Prove the claim
+    /// An empty delta needs no proof (`DeltaClaim::verify` only compares roots)
+    pub fn prove(&self, params: &Params<EqAffine>) -> Result<Vec<u8>, Error> {
+        if self.updates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let circuit = self.circuit();
+        let prover = CycleProver::<EqAffine>::new(params, &circuit)?;
+        prover.prove(params, &circuit, &[self.claim().instance()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incremental_matches_rebuild() {
+        let leaves: Vec<Fr> = (0..11u64)
+            .map(|i| merkle_leaf(Some(&[i, i * 3][..])))
+            .collect();
+        let mut tree = MerkleTree::new(5);
+        for (i, &leaf) in leaves.iter().enumerate() {
+            tree.set(i as u64, leaf).unwrap();
+        }
+        let rebuilt = MerkleTree::from_leaves(5, leaves.clone()).unwrap();
+        assert_eq!(tree.root(), rebuilt.root());
+        let rows: Vec<Vec<u64>> = (0..11u64).map(|i| vec![i, i * 3]).collect();
+        assert_eq!(merkle_leaves(&rows), leaves);
+
+        for (i, &leaf) in leaves.iter().enumerate() {
+            assert_eq!(
+                merkle_root_from_path(leaf, i as u64, &tree.path(i as u64)),
+                tree.root()
+            );
+        }
+        assert_eq!(
+            MerkleTree::new(5).root(),
+            MerkleTree::from_leaves(5, vec![]).unwrap().root()
+        );
+    }
+
+    #[test]
+    fn test_updates_chain() {
+        let mut tree = MerkleTree::new(4);
+        let start = tree.root();
+        let first = tree.set(3, Fr::from(7)).unwrap();
+        let second = tree.set(2, Fr::from(9)).unwrap();
+        assert_eq!(first.old_root(), start);
+        assert_eq!(first.new_root(), second.old_root());
+        assert_eq!(second.new_root(), tree.root());
+
+        // Clearing the slots restores the empty root
+        tree.set(3, Fr::ZERO).unwrap();
+        tree.set(2, Fr::ZERO).unwrap();
+        assert_eq!(tree.root(), start);
+        assert!(tree.set(16, Fr::ONE).is_err());
+    }
+}
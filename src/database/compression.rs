@@ -0,0 +1,303 @@
+// Column compression for the storage layer
+// Run-length and dictionary encoding for low-cardinality analytics columns.
+// Circuits always see plain values: columns are decompressed transparently
+// when witnesses are generated (`CompressedTable::table_data`).
+
+use std::collections::HashMap;
+
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+use super::DatabaseTable;
+
+/// Encoded column values
+#[derive(Clone, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub enum ColumnEncoding {
+    /// Values as is
+    Plain(Vec<u64>),
+    /// (value, run length) pairs
+    RunLength(Vec<(u64, u32)>),
+    /// Distinct values and one code per row
+    Dictionary { dictionary: Vec<u64>, codes: Vec<u32> },
+}
+
+impl ColumnEncoding {
+    /// Plain encoding
+    pub fn plain(values: &[u64]) -> Self {
+        ColumnEncoding::Plain(values.to_vec())
+    }
+
+    /// Run-length encoding
+    pub fn run_length(values: &[u64]) -> Self {
+        let mut runs: Vec<(u64, u32)> = Vec::new();
+        for &value in values {
+            match runs.last_mut() {
+                Some((last, count)) if *last == value && *count < u32::MAX => *count += 1,
+                _ => runs.push((value, 1)),
+            }
+        }
+        ColumnEncoding::RunLength(runs)
+    }
+
+    /// Dictionary encoding (codes in order of first appearance)
+    pub fn dictionary(values: &[u64]) -> Self {
+        let mut dictionary = Vec::new();
+        let mut index: HashMap<u64, u32> = HashMap::new();
+        let codes = values
+            .iter()
+            .map(|&value| {
+                *index.entry(value).or_insert_with(|| {
+                    dictionary.push(value);
+                    (dictionary.len() - 1) as u32
+                })
+            })
+            .collect();
+        ColumnEncoding::Dictionary { dictionary, codes }
+    }
+
+    /// Pick the smallest of plain, run-length and dictionary encoding
+    pub fn best(values: &[u64]) -> Self {
+        [
+            Self::run_length(values),
+            Self::dictionary(values),
+            Self::plain(values),
+        ]
+        .into_iter()
+        .min_by_key(|encoding| encoding.encoded_size())
+        .expect("three candidates")
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnEncoding::Plain(values) => values.len(),
+            ColumnEncoding::RunLength(runs) => runs.iter().map(|(_, n)| *n as usize).sum(),
+            ColumnEncoding::Dictionary { codes, .. } => codes.len(),
+        }
+    }
+
+    /// True if the column has no rows
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Approximate in-memory/on-disk size in bytes
+    pub fn encoded_size(&self) -> usize {
+        match self {
+            ColumnEncoding::Plain(values) => values.len() * 8,
+            ColumnEncoding::RunLength(runs) => runs.len() * 12,
+            ColumnEncoding::Dictionary { dictionary, codes } => {
+                dictionary.len() * 8 + codes.len() * Self::code_width(dictionary.len())
+            }
+        }
+    }
+
+    /// Bytes per dictionary code when bit-packed to the dictionary size
+    fn code_width(dictionary_len: usize) -> usize {
+        match dictionary_len {
+            0..=0x100 => 1,
+            0x101..=0x1_0000 => 2,
+            _ => 4,
+        }
+    }
+
+    /// Value at a row (None if out of bounds or the encoding is corrupt)
+    pub fn get(&self, row: usize) -> Option<u64> {
+        match self {
+            ColumnEncoding::Plain(values) => values.get(row).copied(),
+            ColumnEncoding::RunLength(runs) => {
+                let mut start = 0usize;
+                for &(value, count) in runs {
+                    start += count as usize;
+                    if row < start {
+                        return Some(value);
+                    }
+                }
+                None
+            }
+            ColumnEncoding::Dictionary { dictionary, codes } => codes
+                .get(row)
+                .and_then(|&code| dictionary.get(code as usize))
+                .copied(),
+        }
+    }
+
+    /// Decompress all values
+    pub fn decode(&self) -> PoneglyphResult<Vec<u64>> {
+        match self {
+            ColumnEncoding::Plain(values) => Ok(values.clone()),
+            ColumnEncoding::RunLength(runs) => Ok(runs
+                .iter()
+                .flat_map(|&(value, count)| std::iter::repeat_n(value, count as usize))
+                .collect()),
+            ColumnEncoding::Dictionary { dictionary, codes } => codes
+                .iter()
+                .map(|&code| {
+                    dictionary.get(code as usize).copied().ok_or_else(|| {
+                        PoneglyphError::Serialization(format!(
+                            "dictionary code {} out of range ({} entries)",
+                            code,
+                            dictionary.len()
+                        ))
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Column-major compressed table
+#[derive(Clone, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct CompressedTable {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub scales: Vec<u32>,
+    pub encodings: Vec<ColumnEncoding>,
+}
+
+impl CompressedTable {
+    /// Compress a table, choosing the best encoding per column
+    pub fn compress(table: &DatabaseTable) -> Self {
+        let encodings = (0..table.columns.len())
+            .map(|col| {
+                let values: Vec<u64> = table.data.iter().map(|row| row[col]).collect();
+                ColumnEncoding::best(&values)
+            })
+            .collect();
+
+        Self {
+            name: table.name.clone(),
+            columns: table.columns.clone(),
+            scales: table.scales.clone(),
+            encodings,
+        }
+    }
+
+    /// Number of rows
+    pub fn num_rows(&self) -> usize {
+        self.encodings.first().map_or(0, |e| e.len())
+    }
+
+    /// Decompress one column
+    pub fn column(&self, name: &str) -> PoneglyphResult<Vec<u64>> {
+        let idx = self
+            .columns
+            .iter()
+            .position(|c| c == name)
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Column {} not found", name)))?;
+        self.encodings[idx].decode()
+    }
+
+    /// Decompressed columns in the format consumed by `SQLCompiler::compile`
+    /// (column name -> values), used for witness generation
+    pub fn table_data(&self) -> PoneglyphResult<HashMap<String, Vec<u64>>> {
+        self.columns
+            .iter()
+            .zip(self.encodings.iter())
+            .map(|(name, encoding)| Ok((name.clone(), encoding.decode()?)))
+            .collect()
+    }
+
+    /// Decompress back into a row-major table
+    pub fn decompress(&self) -> PoneglyphResult<DatabaseTable> {
+        let columns = self
+            .encodings
+            .iter()
+            .map(|e| e.decode())
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        let num_rows = self.num_rows();
+        if columns.iter().any(|c| c.len() != num_rows) {
+            return Err(PoneglyphError::Serialization(
+                "compressed columns have different lengths".to_string(),
+            ));
+        }
+
+        let mut table = DatabaseTable::new(self.name.clone(), self.columns.clone());
+        table.scales = self.scales.clone();
+        table.data = (0..num_rows)
+            .map(|row| columns.iter().map(|c| c[row]).collect())
+            .collect();
+        Ok(table)
+    }
+
+    /// Total encoded size in bytes
+    pub fn encoded_size(&self) -> usize {
+        self.encodings.iter().map(|e| e.encoded_size()).sum()
+    }
+
+    /// Serialize for storage
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        bincode::encode_to_vec(self, bincode::config::standard())
+            .map_err(|e| PoneglyphError::Serialization(e.to_string()))
+    }
+
+    /// Deserialize from storage
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        let (table, _) = bincode::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(|e| PoneglyphError::Serialization(e.to_string()))?;
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodings_roundtrip() {
+        let values = vec![5, 5, 5, 7, 7, 5, 9, 9, 9, 9];
+        for encoding in [
+            ColumnEncoding::plain(&values),
+            ColumnEncoding::run_length(&values),
+            ColumnEncoding::dictionary(&values),
+            ColumnEncoding::best(&values),
+        ] {
+            assert_eq!(encoding.len(), values.len());
+            assert_eq!(encoding.decode().unwrap(), values);
+            for (row, &value) in values.iter().enumerate() {
+                assert_eq!(encoding.get(row), Some(value));
+            }
+            assert_eq!(encoding.get(values.len()), None);
+        }
+    }
+
+    #[test]
+    fn test_best_encoding_choice() {
+        // Long runs -> run-length
+        let sorted: Vec<u64> = (0..1000).map(|i| i / 250).collect();
+        assert!(matches!(ColumnEncoding::best(&sorted), ColumnEncoding::RunLength(_)));
+
+        // Few distinct values, no runs -> dictionary
+        let cyclic: Vec<u64> = (0..1000).map(|i| (i % 3) * 1_000_000).collect();
+        assert!(matches!(ColumnEncoding::best(&cyclic), ColumnEncoding::Dictionary { .. }));
+
+        // All distinct -> plain
+        let distinct: Vec<u64> = (0..1000).collect();
+        assert!(matches!(ColumnEncoding::best(&distinct), ColumnEncoding::Plain(_)));
+    }
+
+    #[test]
+    fn test_compressed_table_roundtrip() {
+        let mut table = DatabaseTable::new(
+            "lineitem".to_string(),
+            vec!["l_returnflag".to_string(), "l_quantity".to_string()],
+        );
+        table.set_scale("l_quantity", 2);
+        for i in 0..100u64 {
+            table.insert(vec![i / 50, i * 17 % 89]);
+        }
+
+        let compressed = CompressedTable::compress(&table);
+        assert!(compressed.encoded_size() < 100 * 2 * 8);
+
+        let restored =
+            CompressedTable::from_bytes(&compressed.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored, compressed);
+
+        let decompressed = restored.decompress().unwrap();
+        assert_eq!(decompressed.data, table.data);
+        assert_eq!(decompressed.scales, table.scales);
+
+        let data = restored.table_data().unwrap();
+        assert_eq!(data["l_returnflag"], restored.column("l_returnflag").unwrap());
+    }
+}
@@ -0,0 +1,109 @@
+// Catalog change events
+// Subscribers (result caches, proof schedulers) receive an event whenever a
+// table is committed or its version changes, so they can invalidate exactly
+// the affected entries instead of polling commitments.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use pasta_curves::pallas::Base as Fr;
+
+/// Change of the catalog (registered tables)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatalogEvent {
+    /// Table data committed (first registration or new version)
+    TableCommitted {
+        table: String,
+        version: u64,
+        commitment: Fr,
+    },
+    /// Table replaced: results proven against `from` are stale
+    VersionBumped { table: String, from: u64, to: u64 },
+}
+
+impl CatalogEvent {
+    /// Name of the affected table
+    pub fn table(&self) -> &str {
+        match self {
+            CatalogEvent::TableCommitted { table, .. }
+            | CatalogEvent::VersionBumped { table, .. } => table,
+        }
+    }
+}
+
+/// Catalog event fan-out
+/// Every subscriber gets every event published after it subscribed; dropped
+/// receivers are removed on the next publish.
+#[derive(Debug, Default)]
+pub struct CatalogEvents {
+    subscribers: Mutex<Vec<Sender<CatalogEvent>>>,
+}
+
+impl CatalogEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to all future events
+    pub fn subscribe(&self) -> Receiver<CatalogEvent> {
+        let (sender, receiver) = channel();
+        self.lock().push(sender);
+        receiver
+    }
+
+    /// Send an event to all live subscribers
+    pub fn publish(&self, event: CatalogEvent) {
+        self.lock()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    /// Number of live subscribers (as of the last publish)
+    pub fn subscriber_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Sender<CatalogEvent>>> {
+        // A panicking subscriber cannot leave the list inconsistent
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bumped(to: u64) -> CatalogEvent {
+        CatalogEvent::VersionBumped {
+            table: "orders".to_string(),
+            from: to - 1,
+            to,
+        }
+    }
+
+    #[test]
+    fn test_fan_out() {
+        let events = CatalogEvents::new();
+        let a = events.subscribe();
+        events.publish(bumped(2));
+        let b = events.subscribe();
+        events.publish(bumped(3));
+
+        assert_eq!(a.try_iter().collect::<Vec<_>>(), vec![bumped(2), bumped(3)]);
+        assert_eq!(b.try_iter().collect::<Vec<_>>(), vec![bumped(3)]);
+        assert_eq!(bumped(3).table(), "orders");
+    }
+
+    #[test]
+    fn test_dropped_subscriber_is_removed() {
+        let events = CatalogEvents::new();
+        let kept = events.subscribe();
+        drop(events.subscribe());
+        assert_eq!(events.subscriber_count(), 2);
+
+        events.publish(bumped(2));
+        assert_eq!(events.subscriber_count(), 1);
+        assert_eq!(kept.try_recv(), Ok(bumped(2)));
+    }
+}
@@ -0,0 +1,291 @@
+// CSV / JSON import and CSV export
+// `Database::from_csv` loads an existing dataset into a typed table: the
+// header names the columns (in any order), every field is parsed as the type
+// of its schema column and rows go through a `BulkLoader`, so a bad file
+// never leaves a half-loaded table behind. JSON files (an array of
+// objects) are loaded the same way, with the keys as the header.
+
+use std::path::Path;
+
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::validation::validate_equal_length;
+
+use super::{Database, DatabaseCommitment, Datum, Schema};
+
+fn file_error(path: &Path, e: impl std::fmt::Display) -> PoneglyphError {
+    PoneglyphError::Serialization(format!("{}: {}", path.display(), e))
+}
+
+/// Text fields of one row of a file
+pub(crate) struct Record {
+    /// Where the row is, for errors ("line 3", "record 2")
+    pub location: String,
+    pub fields: Vec<String>,
+}
+
+/// Header and rows of a CSV file
+pub(crate) fn read_csv(path: &Path) -> PoneglyphResult<(Vec<String>, Vec<Record>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(path)
+        .map_err(|e| file_error(path, e))?;
+    let header = reader
+        .headers()
+        .map_err(|e| file_error(path, e))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    let records = reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| file_error(path, e))?;
+            Ok(Record {
+                location: format!("line {}", record.position().map_or(0, |p| p.line())),
+                fields: record.iter().map(str::to_string).collect(),
+            })
+        })
+        .collect::<PoneglyphResult<Vec<_>>>()?;
+    Ok((header, records))
+}
+
+/// Keys of the first object and fields of every object of a JSON array
+pub(crate) fn read_json(path: &Path) -> PoneglyphResult<(Vec<String>, Vec<Record>)> {
+    let contents = std::fs::read_to_string(path).map_err(|e| file_error(path, e))?;
+    let objects: Vec<serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_str(&contents).map_err(|e| file_error(path, e))?;
+    let header: Vec<String> = objects
+        .first()
+        .map(|object| object.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let mut records = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        let location = format!("record {}", i + 1);
+        if object.len() != header.len() {
+            return Err(file_error(
+                path,
+                format!(
+                    "{} has {} keys, expected {}",
+                    location,
+                    object.len(),
+                    header.len()
+                ),
+            ));
+        }
+        let fields = header
+            .iter()
+            .map(|key| match object.get(key) {
+                Some(serde_json::Value::String(s)) => Ok(s.clone()),
+                Some(serde_json::Value::Number(n)) => Ok(n.to_string()),
+                Some(serde_json::Value::Bool(b)) => Ok(b.to_string()),
+                Some(other) => Err(file_error(
+                    path,
+                    format!("{}, key {}: unsupported value {}", location, key, other),
+                )),
+                None => Err(file_error(
+                    path,
+                    format!("{}: missing key {}", location, key),
+                )),
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        records.push(Record { location, fields });
+    }
+    Ok((header, records))
+}
+
+impl Database {
+    /// Load a CSV file into a new database with a single table
+    /// The table is named after the file stem (`orders.csv` -> `orders`).
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let schema = Schema::new(&[("id", ColumnType::Integer), ("price", ColumnType::Decimal(2))])?;
+    /// let db = Database::from_csv("orders.csv", schema)?;
+    /// let commitment = db.commit("orders").unwrap();
+    /// ```
+    pub fn from_csv(path: impl AsRef<Path>, schema: Schema) -> PoneglyphResult<Self> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| file_error(path, "file name is not a table name"))?;
+        let mut database = Database::new();
+        database.load_csv(name, path, schema)?;
+        Ok(database)
+    }
+
+    /// Create table `name` from a CSV file and return its commitment
+    pub fn load_csv(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+        schema: Schema,
+    ) -> PoneglyphResult<DatabaseCommitment> {
+        let path = path.as_ref();
+        let (header, records) = read_csv(path)?;
+        self.load_records(name, path, &header, &records, schema)
+    }
+
+    /// Create table `name` from a JSON file and return its commitment
+    /// The file holds an array of flat objects, one per row, all with the
+    /// same keys; numbers, booleans and strings are parsed as the type of
+    /// their schema column like CSV fields.
+    pub fn load_json(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+        schema: Schema,
+    ) -> PoneglyphResult<DatabaseCommitment> {
+        let path = path.as_ref();
+        let (header, records) = read_json(path)?;
+        self.load_records(name, path, &header, &records, schema)
+    }
+
+    /// Create table `name` from the text fields of `records` with a
+    /// `BulkLoader`: the table only exists if every row is valid
+    fn load_records(
+        &mut self,
+        name: &str,
+        path: &Path,
+        header: &[String],
+        records: &[Record],
+        schema: Schema,
+    ) -> PoneglyphResult<DatabaseCommitment> {
+        // Header position -> schema column
+        validate_equal_length(header, &schema.columns, "Header")?;
+        let mut positions = vec![usize::MAX; schema.columns.len()];
+        for (position, column) in header.iter().enumerate() {
+            let idx = schema.index_of(column.trim()).ok_or_else(|| {
+                file_error(path, format!("column {} is not in the schema", column))
+            })?;
+            if positions[idx] != usize::MAX {
+                return Err(file_error(path, format!("column {} appears twice", column)));
+            }
+            positions[idx] = position;
+        }
+
+        let mut loader = self.bulk_loader(name, schema.clone())?;
+        for record in records {
+            let location = &record.location;
+            validate_equal_length(&record.fields, &schema.columns, location)?;
+            let row = schema
+                .columns
+                .iter()
+                .zip(&positions)
+                .map(|(column, &position)| {
+                    Datum::parse(&record.fields[position], column.ty).map_err(|e| {
+                        PoneglyphError::Validation(format!(
+                            "{}: {}, column {}: {}",
+                            path.display(),
+                            location,
+                            column.name,
+                            e
+                        ))
+                    })
+                })
+                .collect::<PoneglyphResult<Vec<_>>>()?;
+            loader.push(&row).map_err(|e| {
+                PoneglyphError::Validation(format!("{}: {}: {}", path.display(), location, e))
+            })?;
+        }
+        let (commitment, _) = loader.finish()?;
+        Ok(commitment)
+    }
+
+    /// Write a table as CSV, with a header row in schema order
+    pub fn to_csv(&self, table: &str, path: impl AsRef<Path>) -> PoneglyphResult<()> {
+        let path = path.as_ref();
+        let schema = self
+            .schema(table)
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", table)))?;
+        let mut writer = csv::Writer::from_path(path).map_err(|e| file_error(path, e))?;
+        writer
+            .write_record(schema.column_names())
+            .map_err(|e| file_error(path, e))?;
+        for row in self.rows(table)? {
+            writer
+                .write_record(row.iter().map(|datum| datum.to_string()))
+                .map_err(|e| file_error(path, e))?;
+        }
+        writer.flush().map_err(|e| file_error(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ColumnType;
+    use std::fs;
+
+    fn schema() -> Schema {
+        Schema::new(&[
+            ("id", ColumnType::Integer),
+            ("price", ColumnType::Decimal(2)),
+            ("note", ColumnType::Text),
+            ("paid", ColumnType::Boolean),
+        ])
+        .unwrap()
+    }
+
+    fn temp_csv(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("poneglyph-csv-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let path = temp_csv(
+            "orders.csv",
+            "price,id,paid,note\n12.5,1,true,\"first, rush\"\n3,2,0,plain\n",
+        );
+        let db = Database::from_csv(&path, schema()).unwrap();
+        assert_eq!(db.table_names(), vec!["orders"]);
+        assert_eq!(db.table("orders").unwrap().data[0][..2], [1, 1250]);
+        assert_eq!(
+            db.rows("orders").unwrap()[0][2],
+            Datum::Text("first, rush".to_string())
+        );
+
+        let out = path.with_file_name("orders_out.csv");
+        db.to_csv("orders", &out).unwrap();
+        let reloaded = Database::from_csv(&out, schema()).unwrap();
+        assert_eq!(
+            reloaded.rows("orders_out").unwrap(),
+            db.rows("orders").unwrap()
+        );
+        assert_eq!(
+            reloaded.commit("orders_out").unwrap().commitment(),
+            db.commit("orders").unwrap().commitment()
+        );
+    }
+
+    #[test]
+    fn test_csv_rejects_invalid_files() {
+        let cases = [
+            ("missing_column.csv", "id,price,paid\n1,1.00,true\n"),
+            ("unknown_column.csv", "id,price,note,done\n1,1.00,x,true\n"),
+            ("short_row.csv", "id,price,note,paid\n1,1.00,x\n"),
+            ("bad_integer.csv", "id,price,note,paid\none,1.00,x,true\n"),
+            ("bad_decimal.csv", "id,price,note,paid\n1,1.001,x,true\n"),
+            ("bad_boolean.csv", "id,price,note,paid\n1,1.00,x,maybe\n"),
+        ];
+        for (name, contents) in cases {
+            let path = temp_csv(name, contents);
+            assert!(Database::from_csv(&path, schema()).is_err(), "{}", name);
+        }
+        assert!(Database::from_csv("/nonexistent/orders.csv", schema()).is_err());
+
+        // A bad row leaves no table behind
+        let mut db = Database::new();
+        let path = temp_csv(
+            "partial.csv",
+            "id,price,note,paid\n1,1,x,true\n2,x,y,true\n",
+        );
+        assert!(db.load_csv("partial", &path, schema()).is_err());
+        assert!(db.table("partial").is_none());
+    }
+}
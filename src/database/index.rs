@@ -0,0 +1,406 @@
+// Sorted index commitments and predicate pushdown
+// A sorted index over a column is a Poseidon Merkle tree whose leaf i is
+// `poseidon([value, row])` of the i-th smallest value (ties by row). Its root
+// is published next to the table commitment, by the same party and with the
+// same trust: the order is established when the index is committed, since
+// re-proving it would put the whole column back into the witness.
+//
+// A range predicate on the column then becomes a scan of one contiguous run
+// of index positions. The storage layer returns only those candidate rows;
+// `IndexScanCircuit` proves the entries right outside the run fall outside
+// the range, and the order extends that to every skipped entry. The witness
+// grows with the candidates and the tree depth, not with the table.
+
+use std::ops::Range;
+
+use halo2_proofs::{
+    circuit::Value,
+    pasta::EqAffine,
+    plonk::{keygen_vk, Error},
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::{merkle_root_from_path, DatabaseTable, MerkleTree};
+use crate::circuit::{
+    IndexEntryWitness, IndexScanCircuit, SCAN_INSTANCE_HIGH_ROW, SCAN_INSTANCE_LOWER_POSITION_ROW,
+    SCAN_INSTANCE_LOW_ROW, SCAN_INSTANCE_ROOT_ROW, SCAN_INSTANCE_UPPER_POSITION_ROW,
+};
+use crate::error::{PoneglyphError, PoneglyphResult, VerifyError};
+use crate::recursive::{verify_on_curve, CycleProver};
+use crate::utils::poseidon_hash;
+
+/// Leaf of a sorted index entry
+pub fn index_leaf(value: u64, row: u64) -> Fr {
+    poseidon_hash(&[Fr::from(value), Fr::from(row)])
+}
+
+/// Inclusive value range of a scan (`None`: unbounded on that side)
+/// `low > high` is an empty range.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ScanRange {
+    pub low: Option<u64>,
+    pub high: Option<u64>,
+}
+
+impl ScanRange {
+    /// `column BETWEEN low AND high`
+    pub fn between(low: u64, high: u64) -> Self {
+        Self {
+            low: Some(low),
+            high: Some(high),
+        }
+    }
+
+    /// `column = value`
+    pub fn equal(value: u64) -> Self {
+        Self::between(value, value)
+    }
+
+    /// `column < value`
+    pub fn less_than(value: u64) -> Self {
+        match value.checked_sub(1) {
+            Some(high) => Self {
+                low: None,
+                high: Some(high),
+            },
+            None => Self::between(1, 0),
+        }
+    }
+
+    /// `column > value`
+    pub fn greater_than(value: u64) -> Self {
+        match value.checked_add(1) {
+            Some(low) => Self {
+                low: Some(low),
+                high: None,
+            },
+            None => Self::between(1, 0),
+        }
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.low.is_none_or(|low| value >= low) && self.high.is_none_or(|high| value <= high)
+    }
+
+    /// Values in both ranges
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self {
+            low: self.low.max(other.low),
+            high: match (self.high, other.high) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+
+    /// Smallest range holding both (a superset of their union)
+    pub fn hull(&self, other: &Self) -> Self {
+        Self {
+            low: self.low.zip(other.low).map(|(a, b)| a.min(b)),
+            high: self.high.zip(other.high).map(|(a, b)| a.max(b)),
+        }
+    }
+}
+
+/// Public part of a sorted index
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexCommitment {
+    pub column: String,
+    pub depth: usize,
+    /// Number of entries (rows of the table)
+    pub len: u64,
+    pub root: Fr,
+}
+
+impl IndexCommitment {
+    /// Whether the index commits to `values` (the column in row order)
+    pub fn matches(&self, values: &[u64]) -> bool {
+        SortedIndex::new(&self.column, values, self.depth)
+            .is_ok_and(|index| index.commitment() == *self)
+    }
+}
+
+/// Sorted index over one column of a table
+#[derive(Clone, Debug)]
+pub struct SortedIndex {
+    column: String,
+    /// (value, row), sorted
+    entries: Vec<(u64, u64)>,
+    tree: MerkleTree,
+}
+
+impl SortedIndex {
+    /// Index `values` (the column in row order) in a tree of `depth`
+    pub fn new(column: &str, values: &[u64], depth: usize) -> PoneglyphResult<Self> {
+        let mut entries: Vec<(u64, u64)> = values
+            .iter()
+            .enumerate()
+            .map(|(row, &value)| (value, row as u64))
+            .collect();
+        entries.sort_unstable();
+        let leaves = entries
+            .iter()
+            .map(|&(value, row)| index_leaf(value, row))
+            .collect();
+        Ok(Self {
+            column: column.to_string(),
+            entries,
+            tree: MerkleTree::from_leaves(depth, leaves)?,
+        })
+    }
+
+    /// Index a column of a table
+    pub fn build(table: &DatabaseTable, column: &str, depth: usize) -> PoneglyphResult<Self> {
+        let idx = table
+            .columns
+            .iter()
+            .position(|c| c == column)
+            .ok_or_else(|| {
+                PoneglyphError::InvalidInput(format!(
+                    "Column {} not found in {}",
+                    column, table.name
+                ))
+            })?;
+        let values: Vec<u64> = table.data.iter().map(|row| row[idx]).collect();
+        Self::new(column, &values, depth)
+    }
+
+    pub fn commitment(&self) -> IndexCommitment {
+        IndexCommitment {
+            column: self.column.clone(),
+            depth: self.tree.depth(),
+            len: self.entries.len() as u64,
+            root: self.tree.root(),
+        }
+    }
+
+    /// Candidate positions of `range` and the openings that prove the rest
+    /// can be skipped
+    pub fn scan(&self, range: ScanRange) -> IndexScan {
+        let start = range.low.map_or(0, |low| {
+            self.entries.partition_point(|&(value, _)| value < low)
+        });
+        let end = range
+            .high
+            .map_or(self.entries.len(), |high| {
+                self.entries.partition_point(|&(value, _)| value <= high)
+            })
+            .max(start);
+
+        IndexScan {
+            commitment: self.commitment(),
+            range,
+            positions: start as u64..end as u64,
+            candidates: (start..end).map(|i| self.opening(i)).collect(),
+            lower: start.checked_sub(1).map(|i| self.opening(i)),
+            upper: (end < self.entries.len()).then(|| self.opening(end)),
+        }
+    }
+
+    fn opening(&self, position: usize) -> IndexOpening {
+        let (value, row) = self.entries[position];
+        IndexOpening {
+            position: position as u64,
+            value,
+            row,
+            siblings: self.tree.path(position as u64),
+        }
+    }
+}
+
+/// One index entry with its authentication path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexOpening {
+    pub position: u64,
+    pub value: u64,
+    pub row: u64,
+    pub siblings: Vec<Fr>,
+}
+
+impl IndexOpening {
+    /// Whether the entry is at its position under `root`
+    pub fn verify(&self, root: Fr) -> bool {
+        merkle_root_from_path(
+            index_leaf(self.value, self.row),
+            self.position,
+            &self.siblings,
+        ) == root
+    }
+
+    fn witness(&self) -> IndexEntryWitness {
+        IndexEntryWitness {
+            position: Value::known(self.position),
+            value: Value::known(self.value),
+            row: Value::known(self.row),
+            siblings: self.siblings.iter().map(|&s| Value::known(s)).collect(),
+        }
+    }
+}
+
+/// Public statement of a scan proof: under the index `root`, no entry outside
+/// `positions` has a value in `range`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanClaim {
+    pub commitment: IndexCommitment,
+    pub range: ScanRange,
+    pub positions: Range<u64>,
+}
+
+impl ScanClaim {
+    /// Whether an entry below / above the candidates must be excluded
+    fn boundaries(&self) -> Result<(bool, bool), VerifyError> {
+        let Range { start, end } = self.positions;
+        if start > end || end > self.commitment.len {
+            return Err(VerifyError::InstanceMismatch(format!(
+                "positions {}..{} outside an index of {} entries",
+                start, end, self.commitment.len
+            )));
+        }
+        let has_lower = start > 0;
+        let has_upper = end < self.commitment.len;
+        if (has_lower && self.range.low.is_none()) || (has_upper && self.range.high.is_none()) {
+            return Err(VerifyError::InstanceMismatch(
+                "entries skipped on an unbounded side of the range".to_string(),
+            ));
+        }
+        Ok((has_lower, has_upper))
+    }
+
+    /// Instance column of `IndexScanCircuit`
+    pub fn instance(&self) -> Vec<Fr> {
+        let mut rows = vec![Fr::from(0); SCAN_INSTANCE_UPPER_POSITION_ROW + 1];
+        rows[SCAN_INSTANCE_ROOT_ROW] = self.commitment.root;
+        rows[SCAN_INSTANCE_LOW_ROW] = Fr::from(self.range.low.unwrap_or(0));
+        rows[SCAN_INSTANCE_HIGH_ROW] = Fr::from(self.range.high.unwrap_or(0));
+        rows[SCAN_INSTANCE_LOWER_POSITION_ROW] = Fr::from(self.positions.start.saturating_sub(1));
+        if self.positions.end < self.commitment.len {
+            rows[SCAN_INSTANCE_UPPER_POSITION_ROW] = Fr::from(self.positions.end);
+        }
+        rows
+    }
+
+    /// Verify a proof from `IndexScan::prove`
+    /// The verifying key is derived from the claim's shape (depth, which
+    /// sides skip entries), so the verifier needs nothing but `params`.
+    pub fn verify(&self, params: &Params<EqAffine>, proof: &[u8]) -> Result<(), VerifyError> {
+        let (has_lower, has_upper) = self.boundaries()?;
+        if !has_lower && !has_upper {
+            // Nothing skipped
+            return Ok(());
+        }
+        let shape = IndexScanCircuit::shape(self.commitment.depth, has_lower, has_upper);
+        let vk = keygen_vk(params, &shape)?;
+        verify_on_curve(params, &vk, proof, &[self.instance()])?;
+        Ok(())
+    }
+}
+
+/// Result of a pushed-down scan: the candidates with the proof witness
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexScan {
+    pub commitment: IndexCommitment,
+    pub range: ScanRange,
+    /// Index positions of the candidates
+    pub positions: Range<u64>,
+    /// Candidate entries, in index order
+    pub candidates: Vec<IndexOpening>,
+    /// Entry just below the candidates
+    pub lower: Option<IndexOpening>,
+    /// Entry just above the candidates
+    pub upper: Option<IndexOpening>,
+}
+
+impl IndexScan {
+    /// Table rows of the candidates, ascending
+    pub fn rows(&self) -> Vec<u64> {
+        let mut rows: Vec<u64> = self.candidates.iter().map(|c| c.row).collect();
+        rows.sort_unstable();
+        rows
+    }
+
+    /// The candidate rows of `table` (the table the index was built on)
+    pub fn table(&self, table: &DatabaseTable) -> DatabaseTable {
+        let mut scanned = table.clone();
+        scanned.data = self
+            .rows()
+            .into_iter()
+            .filter_map(|row| table.data.get(row as usize).cloned())
+            .collect();
+        scanned
+    }
+
+    pub fn claim(&self) -> ScanClaim {
+        ScanClaim {
+            commitment: self.commitment.clone(),
+            range: self.range,
+            positions: self.positions.clone(),
+        }
+    }
+
+    pub fn circuit(&self) -> IndexScanCircuit {
+        IndexScanCircuit {
+            depth: self.commitment.depth,
+            lower: self.lower.as_ref().map(IndexOpening::witness),
+            upper: self.upper.as_ref().map(IndexOpening::witness),
+        }
+    }
+
+    /// Prove the claim
+    /// A scan that skips nothing needs no proof
+    pub fn prove(&self, params: &Params<EqAffine>) -> Result<Vec<u8>, Error> {
+        if self.lower.is_none() && self.upper.is_none() {
+            return Ok(Vec::new());
+        }
+        let circuit = self.circuit();
+        let prover = CycleProver::<EqAffine>::new(params, &circuit)?;
+        prover.prove(params, &circuit, &[self.claim().instance()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_positions() {
+        let values = [40, 10, 30, 20, 30, 50];
+        let index = SortedIndex::new("v", &values, 4).unwrap();
+        let root = index.commitment().root;
+
+        let scan = index.scan(ScanRange::between(20, 30));
+        assert_eq!(scan.positions, 1..4);
+        assert_eq!(scan.rows(), vec![2, 3, 4]);
+        assert_eq!(scan.lower.as_ref().map(|o| o.value), Some(10));
+        assert_eq!(scan.upper.as_ref().map(|o| o.value), Some(40));
+        assert!(scan.candidates.iter().all(|c| c.verify(root)));
+        assert!(scan.lower.unwrap().verify(root));
+
+        // Nothing qualifies: an empty run between the boundaries
+        let scan = index.scan(ScanRange::equal(25));
+        assert_eq!(scan.positions, 2..2);
+        assert_eq!(scan.lower.map(|o| o.value), Some(20));
+        assert_eq!(scan.upper.map(|o| o.value), Some(30));
+
+        let scan = index.scan(ScanRange::less_than(0));
+        assert_eq!(scan.positions, 0..0);
+        assert!(scan.lower.is_none());
+        assert!(scan.claim().boundaries().is_ok());
+
+        let scan = index.scan(ScanRange::greater_than(10));
+        assert_eq!(scan.positions, 1..6);
+        assert!(scan.upper.is_none());
+    }
+
+    #[test]
+    fn test_claim_shape() {
+        let index = SortedIndex::new("v", &[1, 2, 3], 2).unwrap();
+        let mut claim = index.scan(ScanRange::between(2, 2)).claim();
+        assert_eq!(claim.boundaries(), Ok((true, true)));
+        claim.positions = 1..4;
+        assert!(claim.boundaries().is_err());
+        claim.positions = 1..3;
+        claim.range.low = None;
+        assert!(claim.boundaries().is_err());
+    }
+}
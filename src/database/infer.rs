@@ -0,0 +1,388 @@
+// Column type inference
+// Schemaless files (CSV, JSON) can be loaded without writing a schema first:
+// every value of a column is tried against the column types, narrowest first,
+// and the first type that parses all of them is the column's type.
+//
+// | Values                                   | `InferredType` | `ColumnType`     |
+// |------------------------------------------|----------------|------------------|
+// | `true` / `false`                         | `Boolean`      | `Boolean`        |
+// | unsigned integers                        | `Integer`      | `Integer`        |
+// | unsigned decimals, up to `s` fractional  | `Decimal(s)`   | `Decimal(s)`     |
+// | `YYYY-MM-DD`                             | `Date`         | `Date`           |
+// | few distinct strings, repeated           | `Enum(labels)` | `Text`           |
+// | anything else                            | `Text`         | `Text`           |
+//
+// Enums are stored as text (equality only); their labels are reported so a
+// caller can check them or map them to codes. Overrides run after inference
+// and can replace the type of any column, e.g. to keep zip codes as text.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::error::PoneglyphResult;
+
+use super::import::{read_csv, read_json, Record};
+use super::{ColumnType, Database, DatabaseCommitment, Datum, Schema};
+
+/// Default `TypeInference::with_max_enum_labels`
+pub const DEFAULT_MAX_ENUM_LABELS: usize = 16;
+
+/// Type found for the values of a column
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InferredType {
+    Boolean,
+    Integer,
+    /// Largest number of fractional digits of the values
+    Decimal(u32),
+    Date,
+    /// Distinct values, sorted
+    Enum(Vec<String>),
+    Text,
+}
+
+impl InferredType {
+    /// Column type the values are stored as
+    pub fn column_type(&self) -> ColumnType {
+        match self {
+            InferredType::Boolean => ColumnType::Boolean,
+            InferredType::Integer => ColumnType::Integer,
+            InferredType::Decimal(scale) => ColumnType::Decimal(*scale),
+            InferredType::Date => ColumnType::Date,
+            InferredType::Enum(_) | InferredType::Text => ColumnType::Text,
+        }
+    }
+}
+
+/// Inferred column of a file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InferredColumn {
+    pub name: String,
+    pub inferred: InferredType,
+    /// Type of the schema column (the inferred type unless overridden)
+    pub ty: ColumnType,
+}
+
+type Override = Box<dyn Fn(&str, &InferredType) -> Option<ColumnType> + Send + Sync>;
+
+/// Column type inference with user overrides
+pub struct TypeInference {
+    max_enum_labels: usize,
+    overrides: Vec<Override>,
+}
+
+impl Default for TypeInference {
+    fn default() -> Self {
+        Self {
+            max_enum_labels: DEFAULT_MAX_ENUM_LABELS,
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl TypeInference {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// String columns with at most `labels` distinct values are enums
+    /// (0 disables enum detection)
+    pub fn with_max_enum_labels(mut self, labels: usize) -> Self {
+        self.max_enum_labels = labels;
+        self
+    }
+
+    /// Mapping callback: `(column, inferred type)` -> type to use instead
+    /// Overrides are tried in the order they were added; the first `Some`
+    /// wins, `None` keeps the inferred type.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// // Money columns with cents, whatever the file shows
+    /// let inference = TypeInference::new().with_override(|column, inferred| {
+    ///     match (column.ends_with("_price"), inferred) {
+    ///         (true, InferredType::Integer | InferredType::Decimal(_)) => Some(ColumnType::Decimal(2)),
+    ///         _ => None,
+    ///     }
+    /// });
+    /// ```
+    pub fn with_override(
+        mut self,
+        mapping: impl Fn(&str, &InferredType) -> Option<ColumnType> + Send + Sync + 'static,
+    ) -> Self {
+        self.overrides.push(Box::new(mapping));
+        self
+    }
+
+    /// Use `ty` for column `column` (case-insensitive)
+    pub fn with_column_type(self, column: &str, ty: ColumnType) -> Self {
+        let column = column.trim().to_lowercase();
+        self.with_override(move |name, _| (name.trim().to_lowercase() == column).then_some(ty))
+    }
+
+    /// Type of a column with these values
+    pub fn infer_values<S: AsRef<str>>(&self, values: &[S]) -> InferredType {
+        let values: Vec<&str> = values.iter().map(|v| v.as_ref().trim()).collect();
+        if values.is_empty() {
+            return InferredType::Text;
+        }
+        let all = |ty: ColumnType| values.iter().all(|v| Datum::parse(v, ty).is_ok());
+
+        // Booleans are words only: a 0 / 1 column is an integer column
+        if values
+            .iter()
+            .all(|v| v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("false"))
+        {
+            return InferredType::Boolean;
+        }
+        if all(ColumnType::Integer) {
+            return InferredType::Integer;
+        }
+        if let Some(scale) = decimal_scale(&values) {
+            if all(ColumnType::Decimal(scale)) {
+                return InferredType::Decimal(scale);
+            }
+        }
+        if all(ColumnType::Date) {
+            return InferredType::Date;
+        }
+
+        let labels: BTreeSet<&str> = values.iter().copied().collect();
+        if labels.len() <= self.max_enum_labels && labels.len() < values.len() {
+            return InferredType::Enum(labels.into_iter().map(str::to_string).collect());
+        }
+        InferredType::Text
+    }
+
+    /// Columns of a file given its header and rows
+    pub fn infer(&self, header: &[String], rows: &[Vec<String>]) -> Vec<InferredColumn> {
+        header
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let values: Vec<&str> = rows
+                    .iter()
+                    .filter_map(|row| row.get(i).map(String::as_str))
+                    .collect();
+                self.column(name, self.infer_values(&values))
+            })
+            .collect()
+    }
+
+    fn column(&self, name: &str, inferred: InferredType) -> InferredColumn {
+        let ty = self
+            .overrides
+            .iter()
+            .find_map(|mapping| mapping(name, &inferred))
+            .unwrap_or_else(|| inferred.column_type());
+        InferredColumn {
+            name: name.trim().to_string(),
+            inferred,
+            ty,
+        }
+    }
+
+    fn infer_records(&self, header: &[String], records: &[Record]) -> Vec<InferredColumn> {
+        let rows: Vec<Vec<String>> = records.iter().map(|r| r.fields.clone()).collect();
+        self.infer(header, &rows)
+    }
+}
+
+/// Fractional digits of a column of unsigned decimals (None if a value isn't one)
+fn decimal_scale(values: &[&str]) -> Option<u32> {
+    values.iter().try_fold(0u32, |scale, value| {
+        let (whole, fraction) = value.split_once('.').unwrap_or((*value, ""));
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !digits(whole) || !digits(fraction) {
+            return None;
+        }
+        Some(scale.max(fraction.len() as u32))
+    })
+}
+
+impl Schema {
+    /// Schema of inferred columns, in file order
+    pub fn from_inferred(columns: &[InferredColumn]) -> PoneglyphResult<Self> {
+        let columns: Vec<_> = columns.iter().map(|c| (c.name.as_str(), c.ty)).collect();
+        Schema::new(&columns)
+    }
+}
+
+impl Database {
+    /// Column types of a CSV file
+    pub fn infer_csv(
+        path: impl AsRef<Path>,
+        inference: &TypeInference,
+    ) -> PoneglyphResult<Vec<InferredColumn>> {
+        let (header, records) = read_csv(path.as_ref())?;
+        Ok(inference.infer_records(&header, &records))
+    }
+
+    /// Column types of a JSON file (see `load_json`)
+    pub fn infer_json(
+        path: impl AsRef<Path>,
+        inference: &TypeInference,
+    ) -> PoneglyphResult<Vec<InferredColumn>> {
+        let (header, records) = read_json(path.as_ref())?;
+        Ok(inference.infer_records(&header, &records))
+    }
+
+    /// Create table `name` from a CSV file with inferred column types
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let inference = TypeInference::new().with_column_type("zip", ColumnType::Text);
+    /// let (commitment, columns) = db.load_csv_inferred("orders", "orders.csv", &inference)?;
+    /// ```
+    pub fn load_csv_inferred(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+        inference: &TypeInference,
+    ) -> PoneglyphResult<(DatabaseCommitment, Vec<InferredColumn>)> {
+        let columns = Self::infer_csv(&path, inference)?;
+        let commitment = self.load_csv(name, path, Schema::from_inferred(&columns)?)?;
+        Ok((commitment, columns))
+    }
+
+    /// Create table `name` from a JSON file with inferred column types
+    pub fn load_json_inferred(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+        inference: &TypeInference,
+    ) -> PoneglyphResult<(DatabaseCommitment, Vec<InferredColumn>)> {
+        let columns = Self::infer_json(&path, inference)?;
+        let commitment = self.load_json(name, path, Schema::from_inferred(&columns)?)?;
+        Ok((commitment, columns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("poneglyph-infer-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_infer_values() {
+        let inference = TypeInference::new();
+        let infer = |values: &[&str]| inference.infer_values(values);
+        assert_eq!(infer(&["true", "FALSE"]), InferredType::Boolean);
+        assert_eq!(infer(&["0", "1", "1"]), InferredType::Integer);
+        assert_eq!(infer(&["1", "2.5", "3.25"]), InferredType::Decimal(2));
+        assert_eq!(infer(&["2024-01-31", "2023-12-01"]), InferredType::Date);
+        assert_eq!(
+            infer(&["paid", "open", "paid", "open"]),
+            InferredType::Enum(vec!["open".to_string(), "paid".to_string()])
+        );
+        // Negative numbers don't fit unsigned columns
+        assert_eq!(infer(&["-1", "2", "3"]), InferredType::Text);
+        // All distinct: names, not labels
+        assert_eq!(infer(&["alice", "bob"]), InferredType::Text);
+        assert_eq!(infer(&["1.5", "x"]), InferredType::Text);
+        assert_eq!(infer(&[]), InferredType::Text);
+        assert_eq!(
+            TypeInference::new()
+                .with_max_enum_labels(0)
+                .infer_values(&["a", "a"]),
+            InferredType::Text
+        );
+    }
+
+    #[test]
+    fn test_overrides() {
+        let inference = TypeInference::new()
+            .with_column_type("ZIP", ColumnType::Text)
+            .with_override(|column, inferred| {
+                (column.ends_with("price") && *inferred == InferredType::Integer)
+                    .then_some(ColumnType::Decimal(2))
+            });
+        let header = vec!["zip".to_string(), "price".to_string(), "qty".to_string()];
+        let rows = vec![
+            vec!["02134".to_string(), "3".to_string(), "1".to_string()],
+            vec!["10001".to_string(), "12".to_string(), "4".to_string()],
+        ];
+        let columns = inference.infer(&header, &rows);
+        assert_eq!(columns[0].inferred, InferredType::Integer);
+        assert_eq!(columns[0].ty, ColumnType::Text);
+        assert_eq!(columns[1].ty, ColumnType::Decimal(2));
+        assert_eq!(columns[2].ty, ColumnType::Integer);
+    }
+
+    #[test]
+    fn test_load_csv_inferred() {
+        let path = temp_file(
+            "orders.csv",
+            "id,price,status,day,paid\n1,12.5,open,2024-03-01,true\n2,3,paid,2024-03-02,false\n3,7.25,open,2024-03-02,true\n",
+        );
+        let mut db = Database::new();
+        let (_, columns) = db
+            .load_csv_inferred("orders", &path, &TypeInference::new())
+            .unwrap();
+        let types: Vec<_> = columns.iter().map(|c| c.ty).collect();
+        assert_eq!(
+            types,
+            vec![
+                ColumnType::Integer,
+                ColumnType::Decimal(2),
+                ColumnType::Text,
+                ColumnType::Date,
+                ColumnType::Boolean
+            ]
+        );
+        assert!(matches!(columns[2].inferred, InferredType::Enum(ref labels) if labels.len() == 2));
+        assert_eq!(db.table("orders").unwrap().data[0][1], 1250);
+        assert_eq!(db.rows("orders").unwrap()[1][3].to_string(), "2024-03-02");
+
+        // An override the data doesn't fit fails the load
+        let strict = TypeInference::new().with_column_type("status", ColumnType::Integer);
+        assert!(db.load_csv_inferred("strict", &path, &strict).is_err());
+        assert!(db.table("strict").is_none());
+    }
+
+    #[test]
+    fn test_load_json_inferred() {
+        let path = temp_file(
+            "events.json",
+            r#"[{"id": 1, "kind": "click", "at": "2024-05-01", "ok": true},
+                {"id": 2, "kind": "view", "at": "2024-05-02", "ok": false},
+                {"id": 3, "kind": "click", "at": "2024-05-02", "ok": true}]"#,
+        );
+        let mut db = Database::new();
+        let (_, columns) = db
+            .load_json_inferred("events", &path, &TypeInference::new())
+            .unwrap();
+        let ty = |name: &str| columns.iter().find(|c| c.name == name).unwrap().ty;
+        assert_eq!(ty("id"), ColumnType::Integer);
+        assert_eq!(ty("kind"), ColumnType::Text);
+        assert_eq!(ty("at"), ColumnType::Date);
+        assert_eq!(ty("ok"), ColumnType::Boolean);
+        assert_eq!(db.rows("events").unwrap().len(), 3);
+
+        for (name, contents) in [
+            (
+                "missing_key.json",
+                r#"[{"id": 1, "x": 2}, {"id": 2, "y": 3}]"#,
+            ),
+            ("nested.json", r#"[{"id": {"a": 1}}]"#),
+            ("not_array.json", r#"{"id": 1}"#),
+        ] {
+            let path = temp_file(name, contents);
+            assert!(
+                db.load_json_inferred(name, &path, &TypeInference::new())
+                    .is_err(),
+                "{}",
+                name
+            );
+        }
+    }
+}
@@ -1,10 +1,32 @@
 // Database commitment module
 // Paper Section 5.1: IPA commitment (Inner Product Argument)
 
-use ff::Field;
-use halo2_proofs::{circuit::Value, plonk::Error};
+use std::collections::HashMap;
+
 use pasta_curves::pallas::Base as Fr;
 
+pub mod bulk;
+pub mod catalog;
+pub mod columns;
+pub mod commitment_tree;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod compression;
+pub mod events;
+pub mod import;
+pub mod index;
+pub mod infer;
+pub mod storage;
+pub use bulk::*;
+pub use catalog::*;
+pub use columns::*;
+pub use commitment_tree::*;
+pub use compression::*;
+pub use events::*;
+pub use index::*;
+pub use infer::*;
+pub use storage::*;
+
 /// Database Commitment
 /// Paper Section 5.1: Database commitment using IPA commitment
 ///
@@ -33,8 +55,6 @@ impl DatabaseCommitment {
     ///
     /// Database commitment
     pub fn new(data: &[(u64, u64)]) -> Self {
-        // Simple hash function - production should use more secure hash
-        // (e.g.: Poseidon hash, Pedersen hash)
         let data_hash = Self::hash_data(data);
 
         // Create commitment
@@ -48,18 +68,28 @@ impl DatabaseCommitment {
         }
     }
 
+    /// Create commitment of table rows
+    /// Poseidon hash of the values in row-major order, as `PoneglyphCircuit`
+    /// recomputes it (see `TableScan`); for two-column rows this is `new`
+    /// of the (key, value) pairs
+    pub fn of_rows(rows: &[Vec<u64>]) -> Self {
+        let inputs: Vec<Fr> = rows.iter().flatten().map(|&v| Fr::from(v)).collect();
+        let data_hash = crate::utils::poseidon_hash(&inputs);
+        Self {
+            commitment: data_hash,
+            data_hash,
+        }
+    }
+
     /// Hash database data
-    /// Production should use: Poseidon hash or Pedersen hash
+    /// Poseidon hash of the key-value pairs in order, recomputable in-circuit
+    /// with `PoseidonChip`
     fn hash_data(data: &[(u64, u64)]) -> Fr {
-        // Simple hash: sum all key-value pairs
-        // Production should use: Poseidon hash or Pedersen hash
-        let mut hash = Fr::ZERO;
-        for (key, value) in data {
-            let key_field = Fr::from(*key);
-            let value_field = Fr::from(*value);
-            hash = hash + key_field * Fr::from(1000000u64) + value_field;
-        }
-        hash
+        let inputs: Vec<Fr> = data
+            .iter()
+            .flat_map(|&(key, value)| [Fr::from(key), Fr::from(value)])
+            .collect();
+        crate::utils::poseidon_hash(&inputs)
     }
 
     /// Verify commitment
@@ -90,18 +120,43 @@ pub struct DatabaseTable {
     pub name: String,
     pub columns: Vec<String>,
     pub data: Vec<Vec<u64>>,
+    /// Fixed-point scale per column (0 = integer column)
+    /// A column with scale 2 stores 12.34 as raw value 1234
+    pub scales: Vec<u32>,
 }
 
 impl DatabaseTable {
     /// Create new table
     pub fn new(name: String, columns: Vec<String>) -> Self {
+        let scales = vec![0; columns.len()];
         Self {
             name,
             columns,
             data: Vec::new(),
+            scales,
+        }
+    }
+
+    /// Declare a column as fixed-point decimal with the given scale
+    /// Returns false if the column does not exist
+    pub fn set_scale(&mut self, column: &str, scale: u32) -> bool {
+        match self.columns.iter().position(|c| c == column) {
+            Some(idx) => {
+                self.scales[idx] = scale;
+                true
+            }
+            None => false,
         }
     }
 
+    /// Get fixed-point scale of a column (None if the column does not exist)
+    pub fn scale_of(&self, column: &str) -> Option<u32> {
+        self.columns
+            .iter()
+            .position(|c| c == column)
+            .map(|idx| self.scales[idx])
+    }
+
     /// Insert row
     pub fn insert(&mut self, row: Vec<u64>) {
         if row.len() == self.columns.len() {
@@ -109,15 +164,27 @@ impl DatabaseTable {
         }
     }
 
+    /// Columns in the format consumed by `SQLCompiler::compile`
+    /// (column name -> values)
+    pub fn table_data(&self) -> HashMap<String, Vec<u64>> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let values = self.data.iter().map(|row| row[idx]).collect();
+                (name.clone(), values)
+            })
+            .collect()
+    }
+
+    /// Compress columns (run-length/dictionary) for storage
+    pub fn compress(&self) -> CompressedTable {
+        CompressedTable::compress(self)
+    }
+
     /// Create table commitment
+    /// Covers every column of every row (see `DatabaseCommitment::of_rows`)
     pub fn commit(&self) -> DatabaseCommitment {
-        // Create key-value pairs (first column is key, others are values)
-        let mut kv_pairs = Vec::new();
-        for row in &self.data {
-            if row.len() >= 2 {
-                kv_pairs.push((row[0], row[1]));
-            }
-        }
-        DatabaseCommitment::new(&kv_pairs)
+        DatabaseCommitment::of_rows(&self.data)
     }
 }
@@ -0,0 +1,589 @@
+// Persistent table storage
+// Committing a large table hashes every row, so a restarted server should not
+// rebuild its tables and commitments before the first proof. A
+// `StorageBackend` keeps committed tables across restarts: each table is stored
+// compressed (`CompressedTable`) next to the commitment computed when it was
+// stored, so loading it costs a decode instead of a rehash.
+//
+// Backends only move bytes under a key; the record format (`StoredTable`) is
+// shared, so a table written by one backend can be copied to another as is.
+//
+// A load trusts the stored commitment, so a flipped bit on disk would only show
+// up as a proof that fails to verify. `StoredTable::verify_integrity` (and
+// `StorageBackend::verify_integrity` for a whole backend) rehashes the stored
+// data first. Each column also gets its own Poseidon digest (format version
+// 2), which tells which column changed. Versions 1 and 2 stored a commitment
+// of the first two columns only; it is recomputed when they are read, so
+// only their column digests are checked.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use ff::PrimeField;
+use pasta_curves::pallas::Base as Fr;
+
+use super::{CompressedTable, DatabaseCommitment, DatabaseTable};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::utils::poseidon_hash;
+
+/// Format version of `StoredTable::to_bytes`
+/// Version 1 tables (no column digests) and version 2 tables (commitment of
+/// the first two columns) are still read
+pub const STORED_TABLE_VERSION: u32 = 3;
+
+/// Leading bytes of a serialized stored table
+const MAGIC: &[u8; 4] = b"PGTB";
+
+/// File extension of tables written by `FileStorage`
+const FILE_EXTENSION: &str = "pgt";
+
+/// A committed table as it is persisted
+#[derive(Clone, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct StoredTable {
+    pub version: u32,
+    pub table: CompressedTable,
+    /// `DatabaseTable::commit` at the time of storing (canonical little-endian repr)
+    commitment: [u8; 32],
+    /// Poseidon digest of each column's values at the time of storing
+    /// (empty for version 1 tables)
+    column_digests: Vec<[u8; 32]>,
+}
+
+/// Version 1 layout, without column digests
+#[derive(bincode::Decode)]
+struct StoredTableV1 {
+    _version: u32,
+    table: CompressedTable,
+    commitment: [u8; 32],
+}
+
+impl StoredTable {
+    /// Compress and commit a table
+    pub fn new(table: &DatabaseTable) -> Self {
+        Self {
+            version: STORED_TABLE_VERSION,
+            table: table.compress(),
+            commitment: field_bytes(table.commit().commitment()),
+            column_digests: column_digests(table),
+        }
+    }
+
+    /// Stored commitment (not recomputed)
+    pub fn commitment(&self) -> PoneglyphResult<DatabaseCommitment> {
+        let mut repr = <Fr as PrimeField>::Repr::default();
+        repr.as_mut().copy_from_slice(&self.commitment);
+        let commitment: Fr = Option::from(Fr::from_repr(repr)).ok_or_else(|| {
+            PoneglyphError::Serialization("non-canonical field element".to_string())
+        })?;
+        Ok(DatabaseCommitment {
+            commitment,
+            data_hash: commitment,
+        })
+    }
+
+    /// Decompressed table
+    pub fn table(&self) -> PoneglyphResult<DatabaseTable> {
+        self.table.decompress()
+    }
+
+    /// Recompute the commitment and compare it with the stored one
+    /// For audits; a normal load trusts the stored commitment.
+    pub fn verify(&self) -> PoneglyphResult<bool> {
+        Ok(self.table()?.commit().commitment() == self.commitment()?.commitment())
+    }
+
+    /// Rehash the stored columns and compare them, and the commitment, with
+    /// the values recorded when the table was stored
+    /// Decoding failures are reported as `IntegrityProblem::Unreadable`.
+    pub fn verify_integrity(&self) -> IntegrityReport {
+        let mut report = IntegrityReport {
+            table: self.table.name.clone(),
+            rows: 0,
+            problems: Vec::new(),
+        };
+        let table = match self.table() {
+            Ok(table) => table,
+            Err(e) => {
+                report
+                    .problems
+                    .push(IntegrityProblem::Unreadable(e.to_string()));
+                return report;
+            }
+        };
+        report.rows = table.data.len();
+
+        if field_bytes(table.commit().commitment()) != self.commitment {
+            report.problems.push(IntegrityProblem::CommitmentMismatch);
+        }
+        // Version 1 tables have no column digests
+        if self.column_digests.is_empty() {
+            return report;
+        }
+        if self.column_digests.len() != table.columns.len() {
+            report.problems.push(IntegrityProblem::ColumnCountMismatch {
+                stored: self.column_digests.len(),
+                found: table.columns.len(),
+            });
+            return report;
+        }
+        for ((column, stored), found) in table
+            .columns
+            .iter()
+            .zip(&self.column_digests)
+            .zip(column_digests(&table))
+        {
+            if *stored != found {
+                report
+                    .problems
+                    .push(IntegrityProblem::ColumnMismatch(column.clone()));
+            }
+        }
+        report
+    }
+
+    /// Serialize for storage
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(
+            bincode::encode_to_vec(self, bincode::config::standard())
+                .map_err(|e| PoneglyphError::Serialization(e.to_string()))?,
+        );
+        Ok(bytes)
+    }
+
+    /// Deserialize from storage
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        let body = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or_else(|| PoneglyphError::Serialization("not a stored table".to_string()))?;
+        let decode_error =
+            |e: bincode::error::DecodeError| PoneglyphError::Serialization(e.to_string());
+        let (version, _): (u32, usize) =
+            bincode::decode_from_slice(body, bincode::config::standard()).map_err(decode_error)?;
+        let mut stored: Self = match version {
+            2 | STORED_TABLE_VERSION => {
+                bincode::decode_from_slice(body, bincode::config::standard())
+                    .map_err(decode_error)?
+                    .0
+            }
+            1 => {
+                let (v1, _): (StoredTableV1, usize) =
+                    bincode::decode_from_slice(body, bincode::config::standard())
+                        .map_err(decode_error)?;
+                Self {
+                    version,
+                    table: v1.table,
+                    commitment: v1.commitment,
+                    column_digests: Vec::new(),
+                }
+            }
+            _ => {
+                return Err(PoneglyphError::Serialization(format!(
+                    "stored table version {} is not supported (expected {})",
+                    version, STORED_TABLE_VERSION
+                )))
+            }
+        };
+        if version < STORED_TABLE_VERSION {
+            stored.commitment = field_bytes(stored.table()?.commit().commitment());
+        }
+        stored.commitment()?;
+        Ok(stored)
+    }
+}
+
+/// What `StoredTable::verify_integrity` found wrong with a stored table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityProblem {
+    /// The bytes don't decode (truncated, flipped, wrong format)
+    Unreadable(String),
+    /// The rehashed table doesn't match the stored commitment
+    CommitmentMismatch,
+    /// The values of this column don't match its stored digest
+    ColumnMismatch(String),
+    /// The table has another number of columns than were stored
+    ColumnCountMismatch { stored: usize, found: usize },
+}
+
+impl fmt::Display for IntegrityProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityProblem::Unreadable(e) => write!(f, "unreadable: {}", e),
+            IntegrityProblem::CommitmentMismatch => write!(f, "commitment mismatch"),
+            IntegrityProblem::ColumnMismatch(column) => write!(f, "column {} changed", column),
+            IntegrityProblem::ColumnCountMismatch { stored, found } => {
+                write!(f, "{} columns stored, {} found", stored, found)
+            }
+        }
+    }
+}
+
+/// Result of an integrity check of one stored table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Table name (the storage key for unreadable entries)
+    pub table: String,
+    /// Rows checked (0 if unreadable)
+    pub rows: usize,
+    pub problems: Vec<IntegrityProblem>,
+}
+
+impl IntegrityReport {
+    pub fn is_intact(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} rows, ", self.table, self.rows)?;
+        if self.is_intact() {
+            return write!(f, "intact");
+        }
+        let problems: Vec<String> = self.problems.iter().map(|p| p.to_string()).collect();
+        write!(f, "{}", problems.join(", "))
+    }
+}
+
+/// Key-value store for committed tables
+/// Implementations move opaque bytes; `store` / `load` handle the format.
+pub trait StorageBackend: Send + Sync {
+    /// Write `bytes` under `key`, replacing any previous value
+    fn write(&self, key: &str, bytes: &[u8]) -> PoneglyphResult<()>;
+
+    /// Bytes stored under `key` (`None` if there are none)
+    fn read(&self, key: &str) -> PoneglyphResult<Option<Vec<u8>>>;
+
+    /// Remove `key`; returns false if it was not stored
+    fn delete(&self, key: &str) -> PoneglyphResult<bool>;
+
+    /// All stored keys, sorted
+    fn keys(&self) -> PoneglyphResult<Vec<String>>;
+
+    /// Commit and persist a table under its name
+    fn store(&self, table: &DatabaseTable) -> PoneglyphResult<DatabaseCommitment> {
+        let stored = StoredTable::new(table);
+        self.write(&table.name, &stored.to_bytes()?)?;
+        stored.commitment()
+    }
+
+    /// Check every stored table (see `StoredTable::verify_integrity`), e.g.
+    /// when a prover host starts
+    /// Read errors of the backend itself are returned as errors; entries
+    /// that don't decode are reported as unreadable.
+    fn verify_integrity(&self) -> PoneglyphResult<Vec<IntegrityReport>> {
+        let mut reports = Vec::new();
+        for key in self.keys()? {
+            let bytes = match self.read(&key)? {
+                Some(bytes) => bytes,
+                // Deleted since listing
+                None => continue,
+            };
+            reports.push(match StoredTable::from_bytes(&bytes) {
+                Ok(stored) => stored.verify_integrity(),
+                Err(e) => IntegrityReport {
+                    table: key,
+                    rows: 0,
+                    problems: vec![IntegrityProblem::Unreadable(e.to_string())],
+                },
+            });
+        }
+        Ok(reports)
+    }
+
+    /// Load a table and its stored commitment (`None` if it is not stored)
+    fn load(&self, name: &str) -> PoneglyphResult<Option<(DatabaseTable, DatabaseCommitment)>> {
+        match self.read(name)? {
+            Some(bytes) => {
+                let stored = StoredTable::from_bytes(&bytes)?;
+                Ok(Some((stored.table()?, stored.commitment()?)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// In-memory backend (tests, ephemeral servers)
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Vec<u8>>> {
+        // Every operation leaves the map consistent, so a poisoned lock is usable
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn write(&self, key: &str, bytes: &[u8]) -> PoneglyphResult<()> {
+        self.lock().insert(key.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn read(&self, key: &str) -> PoneglyphResult<Option<Vec<u8>>> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    fn delete(&self, key: &str) -> PoneglyphResult<bool> {
+        Ok(self.lock().remove(key).is_some())
+    }
+
+    fn keys(&self) -> PoneglyphResult<Vec<String>> {
+        Ok(self.lock().keys().cloned().collect())
+    }
+}
+
+/// File backend: one file per table in a directory
+/// Files are written next to their final path and renamed into place, so a
+/// crash mid-write leaves the previous version intact.
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    /// Open (and create if needed) a storage directory
+    pub fn open(dir: impl AsRef<Path>) -> PoneglyphResult<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        Ok(Self { dir })
+    }
+
+    /// Storage directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PoneglyphResult<PathBuf> {
+        // Keys become file names: no separators, no hidden or relative names
+        if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\', '\0']) {
+            return Err(PoneglyphError::InvalidInput(format!(
+                "invalid storage key: {:?}",
+                key
+            )));
+        }
+        Ok(self.dir.join(format!("{}.{}", key, FILE_EXTENSION)))
+    }
+}
+
+impl StorageBackend for FileStorage {
+    fn write(&self, key: &str, bytes: &[u8]) -> PoneglyphResult<()> {
+        let path = self.path(key)?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, bytes)
+            .and_then(|_| fs::rename(&tmp, &path))
+            .map_err(|e| io_error(&path, e))
+    }
+
+    fn read(&self, key: &str) -> PoneglyphResult<Option<Vec<u8>>> {
+        let path = self.path(key)?;
+        match fs::read(&path) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error(&path, e)),
+        }
+    }
+
+    fn delete(&self, key: &str) -> PoneglyphResult<bool> {
+        let path = self.path(key)?;
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(io_error(&path, e)),
+        }
+    }
+
+    fn keys(&self) -> PoneglyphResult<Vec<String>> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        let mut keys = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| io_error(&self.dir, e))?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(FILE_EXTENSION) {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                keys.push(stem.to_string());
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+/// Canonical little-endian repr of a field element
+fn field_bytes(value: Fr) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(value.to_repr().as_ref());
+    bytes
+}
+
+/// Poseidon digest of the values of each column
+fn column_digests(table: &DatabaseTable) -> Vec<[u8; 32]> {
+    (0..table.columns.len())
+        .map(|col| {
+            let values: Vec<Fr> = table.data.iter().map(|row| Fr::from(row[col])).collect();
+            field_bytes(poseidon_hash(&values))
+        })
+        .collect()
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> PoneglyphError {
+    PoneglyphError::Serialization(format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders() -> DatabaseTable {
+        let mut table = DatabaseTable::new(
+            "orders".to_string(),
+            vec!["id".to_string(), "amount".to_string()],
+        );
+        table.set_scale("amount", 2);
+        for id in 0..100 {
+            table.insert(vec![id, 1000 + id % 7]);
+        }
+        table
+    }
+
+    fn roundtrip(backend: &dyn StorageBackend) {
+        let table = orders();
+        let commitment = backend.store(&table).unwrap();
+        assert_eq!(commitment.commitment(), table.commit().commitment());
+
+        let (loaded, loaded_commitment) = backend.load("orders").unwrap().unwrap();
+        assert_eq!(loaded.data, table.data);
+        assert_eq!(loaded.scales, table.scales);
+        assert_eq!(loaded_commitment.commitment(), commitment.commitment());
+        assert_eq!(backend.keys().unwrap(), vec!["orders".to_string()]);
+
+        assert!(backend.load("missing").unwrap().is_none());
+        assert!(backend.delete("orders").unwrap());
+        assert!(!backend.delete("orders").unwrap());
+        assert!(backend.keys().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_memory_storage() {
+        roundtrip(&MemoryStorage::new());
+    }
+
+    #[test]
+    fn test_file_storage_survives_reopen() {
+        let dir = std::env::temp_dir().join(format!("poneglyph-storage-{}", std::process::id()));
+        roundtrip(&FileStorage::open(&dir).unwrap());
+
+        FileStorage::open(&dir).unwrap().store(&orders()).unwrap();
+        let reopened = FileStorage::open(&dir).unwrap();
+        let (loaded, _) = reopened.load("orders").unwrap().unwrap();
+        assert_eq!(loaded.data, orders().data);
+
+        assert!(reopened.write("../escape", b"x").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stored_table_rejects_corruption() {
+        let stored = StoredTable::new(&orders());
+        assert!(stored.verify().unwrap());
+        assert!(StoredTable::from_bytes(&stored.to_bytes().unwrap()[4..]).is_err());
+
+        let mut tampered = stored.clone();
+        tampered.table = CompressedTable::compress(&DatabaseTable::new(
+            "orders".to_string(),
+            vec!["id".to_string(), "amount".to_string()],
+        ));
+        assert!(!tampered.verify().unwrap());
+    }
+
+    #[test]
+    fn test_integrity_report() {
+        let mut table = orders();
+        table.columns.push("note".to_string());
+        table.scales.push(0);
+        for row in &mut table.data {
+            row.push(row[0] * 3);
+        }
+        let backend = MemoryStorage::new();
+        backend.store(&table).unwrap();
+        let report = StoredTable::from_bytes(&backend.read("orders").unwrap().unwrap())
+            .unwrap()
+            .verify_integrity();
+        assert!(report.is_intact());
+        assert_eq!(report.rows, 100);
+        assert_eq!(report.to_string(), "orders: 100 rows, intact");
+
+        // The commitment covers every column, the digests tell which changed
+        let mut stored = StoredTable::new(&table);
+        let mut corrupted = table.clone();
+        corrupted.data[7][2] += 1;
+        stored.table = CompressedTable::compress(&corrupted);
+        assert!(!stored.verify().unwrap());
+        assert_eq!(
+            stored.verify_integrity().problems,
+            vec![
+                IntegrityProblem::CommitmentMismatch,
+                IntegrityProblem::ColumnMismatch("note".to_string()),
+            ]
+        );
+        corrupted.data[7][1] += 1;
+        stored.table = CompressedTable::compress(&corrupted);
+        assert_eq!(
+            stored.verify_integrity().problems,
+            vec![
+                IntegrityProblem::CommitmentMismatch,
+                IntegrityProblem::ColumnMismatch("amount".to_string()),
+                IntegrityProblem::ColumnMismatch("note".to_string()),
+            ]
+        );
+
+        // Backend sweep: unreadable entries are reported, not errors
+        backend.write("shipments", b"PGTB\x02garbage").unwrap();
+        let reports = backend.verify_integrity().unwrap();
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].is_intact());
+        assert_eq!(reports[1].table, "shipments");
+        assert!(matches!(
+            reports[1].problems[..],
+            [IntegrityProblem::Unreadable(_)]
+        ));
+    }
+
+    #[test]
+    fn test_reads_version_1_tables() {
+        #[derive(bincode::Encode)]
+        struct V1 {
+            version: u32,
+            table: CompressedTable,
+            commitment: [u8; 32],
+        }
+
+        let table = orders();
+        let mut bytes = MAGIC.to_vec();
+        let v1 = V1 {
+            version: 1,
+            table: table.compress(),
+            commitment: field_bytes(table.commit().commitment()),
+        };
+        bytes.extend(bincode::encode_to_vec(v1, bincode::config::standard()).unwrap());
+
+        let stored = StoredTable::from_bytes(&bytes).unwrap();
+        assert_eq!(stored.version, 1);
+        assert_eq!(stored.table().unwrap().data, table.data);
+        // Only the commitment can be checked
+        assert!(stored.verify_integrity().is_intact());
+    }
+}
@@ -1,7 +1,9 @@
-/// Custom error types for the PoneglyphDB library
+//! Custom error types for the PoneglyphDB library
 
 use std::fmt;
 
+use pasta_curves::pallas::Base as Fr;
+
 /// Main error type for PoneglyphDB operations
 #[derive(Debug, Clone)]
 pub enum PoneglyphError {
@@ -34,6 +36,73 @@ impl std::error::Error for PoneglyphError {}
 /// Result type alias for PoneglyphDB operations
 pub type PoneglyphResult<T> = Result<T, PoneglyphError>;
 
+/// Why a proof was rejected
+/// Callers act differently per outcome: a `MalformedEnvelope` is a client bug,
+/// a `WrongCircuitVersion` calls for a key refresh, a `CommitmentMismatch`
+/// means the proof is about other data, and only `InvalidProof` indicates a
+/// forged or corrupted proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The proof does not verify against the instance
+    InvalidProof(String),
+    /// The instance decodes, but not to what the verifier expects
+    /// (request nonce, expiry bound)
+    InstanceMismatch(String),
+    /// The instance commits to another database than the expected one
+    CommitmentMismatch { expected: Fr, found: Fr },
+    /// The instance commits to a database the verifier holds no anchor for
+    UnknownCommitment(Fr),
+    /// The proof was made for another circuit (verifying key fingerprint)
+    WrongCircuitVersion { expected: u64, found: u64 },
+    /// The proof bytes or instance can't be decoded
+    MalformedEnvelope(String),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::InvalidProof(msg) => write!(f, "Invalid proof: {}", msg),
+            VerifyError::InstanceMismatch(msg) => write!(f, "Instance mismatch: {}", msg),
+            VerifyError::CommitmentMismatch { expected, found } => write!(
+                f,
+                "Commitment mismatch: expected {:?}, found {:?}",
+                expected, found
+            ),
+            VerifyError::UnknownCommitment(found) => {
+                write!(f, "Unknown commitment: {:?} is not anchored", found)
+            }
+            VerifyError::WrongCircuitVersion { expected, found } => write!(
+                f,
+                "Wrong circuit version: expected {:016x}, found {:016x}",
+                expected, found
+            ),
+            VerifyError::MalformedEnvelope(msg) => write!(f, "Malformed envelope: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<halo2_proofs::plonk::Error> for VerifyError {
+    fn from(e: halo2_proofs::plonk::Error) -> Self {
+        use halo2_proofs::plonk::Error;
+        match e {
+            // Proof bytes ended early or hold non-canonical points / scalars
+            Error::Transcript(e) => VerifyError::MalformedEnvelope(e.to_string()),
+            Error::InstanceTooLarge => {
+                VerifyError::MalformedEnvelope("instance too large".to_string())
+            }
+            e => VerifyError::InvalidProof(format!("{:?}", e)),
+        }
+    }
+}
+
+impl From<VerifyError> for PoneglyphError {
+    fn from(e: VerifyError) -> Self {
+        PoneglyphError::Validation(e.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,5 +128,31 @@ mod tests {
             assert!(!err.to_string().is_empty());
         }
     }
+
+    #[test]
+    fn test_verify_error_from_halo2() {
+        use halo2_proofs::plonk::Error;
+
+        let eof = std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "eof");
+        assert!(matches!(
+            VerifyError::from(Error::Transcript(eof)),
+            VerifyError::MalformedEnvelope(_)
+        ));
+        assert!(matches!(
+            VerifyError::from(Error::ConstraintSystemFailure),
+            VerifyError::InvalidProof(_)
+        ));
+        assert!(matches!(
+            VerifyError::from(Error::Opening),
+            VerifyError::InvalidProof(_)
+        ));
+
+        let err: PoneglyphError = VerifyError::WrongCircuitVersion {
+            expected: 1,
+            found: 2,
+        }
+        .into();
+        assert!(err.to_string().contains("circuit version"));
+    }
 }
 
@@ -0,0 +1,262 @@
+// End-to-end scenarios
+// The whole pipeline driven through the library, from a data owner's file to
+// a verifier that only sees published bytes. The scenarios run as examples
+// (`cargo run --release --example payroll`, `--example reserves`) and as
+// integration tests (tests/scenario_tests.rs):
+//
+// | Step         | What happens                                               |
+// |--------------|------------------------------------------------------------|
+// | ingestion    | a CSV or JSON file is loaded with inferred column types    |
+// | commitment   | the table is stored; its commitment is published           |
+// | SQL          | the prover loads the stored table (integrity checked)      |
+// | proof        | `Session::prove_portable` writes p.bin, vk.bin and i.json  |
+// | verification | `verify_raw` over those bytes, the published commitment,   |
+// |              | the query hash and the claimed result                      |
+//
+// The verifier never sees the session or the rows: a scenario only succeeds
+// if every claimed result is proven over the published commitment.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use pasta_curves::pallas::Base as Fr;
+
+use crate::circuit::PublicInputs;
+use crate::database::{Database, StoredTable, TypeInference};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::prover::{field_to_hex, instance_from_hex, instance_to_hex, verify_raw, Session};
+use crate::sql::query_hash;
+
+/// Monthly salaries of a small company
+const PAYROLL_CSV: &str = "\
+id,name,department,salary,hired
+1,Ada,1,7200,2019-03-01
+2,Grace,1,6800,2020-07-15
+3,Alan,2,5400,2021-01-04
+4,Edsger,2,4900,2022-09-30
+5,Barbara,3,6100,2018-11-12
+6,Donald,3,3900,2023-02-20
+7,Frances,2,4500,2023-06-05
+8,John,1,5800,2021-10-18
+";
+
+/// Customer balances held by an exchange
+const RESERVES_JSON: &str = r#"[
+  {"account": 1001, "custodian": "north", "balance": 12500},
+  {"account": 1002, "custodian": "north", "balance": 300},
+  {"account": 1003, "custodian": "south", "balance": 48200},
+  {"account": 1004, "custodian": "south", "balance": 7700},
+  {"account": 1005, "custodian": "east", "balance": 150},
+  {"account": 1006, "custodian": "east", "balance": 2900}
+]"#;
+
+/// Circuit size a scenario session starts at; settings fit it to each query
+const SCENARIO_K: u32 = 4;
+
+/// Proven and verified query of a scenario
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioQuery {
+    pub sql: String,
+    /// Claimed result, checked against the proof
+    pub value: u64,
+    /// Circuit size of the proof (2^k rows)
+    pub k: u32,
+    /// Size of p.bin
+    pub proof_bytes: usize,
+}
+
+/// Outcome of a scenario
+/// Only built once every query has been verified from the published bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub table: String,
+    pub rows: usize,
+    /// Published table commitment (see `field_to_hex`)
+    pub commitment: String,
+    pub queries: Vec<ScenarioQuery>,
+}
+
+impl ScenarioReport {
+    /// Verified value of a query of the scenario
+    pub fn value(&self, sql: &str) -> Option<u64> {
+        self.queries.iter().find(|q| q.sql == sql).map(|q| q.value)
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: table {} ({} rows), commitment {}",
+            self.name, self.table, self.rows, self.commitment
+        )?;
+        for query in &self.queries {
+            writeln!(
+                f,
+                "  {} = {}  (k = {}, {} byte proof, verified)",
+                query.sql, query.value, query.k, query.proof_bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Payroll audit: total salaries and headcount, proven at the smallest
+/// circuit size (`small-proof` preset)
+pub fn run_payroll_demo() -> PoneglyphResult<ScenarioReport> {
+    run_scenario(&Scenario {
+        name: "payroll",
+        table: "payroll",
+        file: "payroll.csv",
+        data: PAYROLL_CSV,
+        settings: &["SET preset = 'small-proof'"],
+        queries: &[
+            "SELECT SUM(salary) FROM payroll",
+            "SELECT COUNT(*) FROM payroll",
+        ],
+    })
+}
+
+/// Proof of reserves: the customer liabilities an exchange must cover, from
+/// a JSON export, with the parameters grown as needed (`max_k`)
+pub fn run_reserves_demo() -> PoneglyphResult<ScenarioReport> {
+    run_scenario(&Scenario {
+        name: "reserves",
+        table: "balances",
+        file: "balances.json",
+        data: RESERVES_JSON,
+        settings: &["SET max_k = 16"],
+        queries: &[
+            "SELECT SUM(balance) FROM balances",
+            "SELECT COUNT(*) FROM balances",
+        ],
+    })
+}
+
+struct Scenario {
+    name: &'static str,
+    table: &'static str,
+    file: &'static str,
+    data: &'static str,
+    /// SET statements of the prover's session
+    settings: &'static [&'static str],
+    /// Queries with a single-value result, fully proven (see
+    /// `Session::capabilities`)
+    queries: &'static [&'static str],
+}
+
+/// Files `poneglyphdb prove` would write for one query
+struct PublishedProof {
+    proof: Vec<u8>,
+    vk: Vec<u8>,
+    instances: String,
+}
+
+fn run_scenario(scenario: &Scenario) -> PoneglyphResult<ScenarioReport> {
+    let dir = std::env::temp_dir().join(format!(
+        "poneglyphdb-{}-{}",
+        scenario.name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+    let report = run_in(scenario, &dir);
+    let _ = fs::remove_dir_all(&dir);
+    report
+}
+
+fn run_in(scenario: &Scenario, dir: &Path) -> PoneglyphResult<ScenarioReport> {
+    // Data owner: ingest the file, store the table and publish its commitment
+    let input = dir.join(scenario.file);
+    fs::write(&input, scenario.data).map_err(|e| io_error(&input, e))?;
+    let inference = TypeInference::new();
+    let mut database = Database::new();
+    match input.extension().and_then(|e| e.to_str()) {
+        Some("json") => database.load_json_inferred(scenario.table, &input, &inference)?,
+        _ => database.load_csv_inferred(scenario.table, &input, &inference)?,
+    };
+    let table = database.table(scenario.table).ok_or_else(|| {
+        PoneglyphError::InvalidInput(format!("Table {} not found", scenario.table))
+    })?;
+    let stored = StoredTable::new(table);
+    let commitment = stored.commitment()?.commitment();
+    let stored_bytes = stored.to_bytes()?;
+
+    // Prover: load the stored table, then prove each query
+    let stored = StoredTable::from_bytes(&stored_bytes)?;
+    let integrity = stored.verify_integrity();
+    if !integrity.is_intact() {
+        return Err(PoneglyphError::Validation(integrity.to_string()));
+    }
+    let table = stored.table()?;
+    let rows = table.data.len();
+    let mut session = Session::new(SCENARIO_K);
+    for setting in scenario.settings {
+        session.execute(setting)?;
+    }
+    session.register_table(table);
+
+    let mut queries = Vec::new();
+    for &sql in scenario.queries {
+        let portable = session.prove_portable(sql)?;
+        let value = portable
+            .result
+            .bound_value()
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("{}: not a single value", sql)))?;
+        let instances = instance_to_hex(&portable.public_inputs.to_instance());
+        let published = PublishedProof {
+            proof: portable.proof.to_bytes()?,
+            vk: portable.verifying_key.to_bytes()?,
+            instances: serde_json::to_string(&instances)
+                .map_err(|e| PoneglyphError::Serialization(e.to_string()))?,
+        };
+
+        // Verifier: the published bytes, commitment, query and claimed value
+        verify_published(&published, commitment, sql, value)?;
+        queries.push(ScenarioQuery {
+            sql: sql.to_string(),
+            value,
+            k: portable.proof.k,
+            proof_bytes: published.proof.len(),
+        });
+    }
+
+    Ok(ScenarioReport {
+        name: scenario.name.to_string(),
+        table: scenario.table.to_string(),
+        rows,
+        commitment: field_to_hex(&commitment),
+        queries,
+    })
+}
+
+/// Check a published proof of `sql` claiming `value` over the table with
+/// `commitment`, without the session or the table
+fn verify_published(
+    published: &PublishedProof,
+    commitment: Fr,
+    sql: &str,
+    value: u64,
+) -> PoneglyphResult<()> {
+    let instances: Vec<Vec<String>> = serde_json::from_str(&published.instances)
+        .map_err(|e| PoneglyphError::Serialization(e.to_string()))?;
+    let inputs = PublicInputs::from_instance(&instance_from_hex(&instances)?)
+        .ok_or_else(|| PoneglyphError::Serialization("instances do not decode".to_string()))?;
+    let rejected = |reason: &str| PoneglyphError::Validation(format!("{}: {}", sql, reason));
+    if inputs.db_commitment != commitment {
+        return Err(rejected("proof is over another table"));
+    }
+    if inputs.query_hash != query_hash(sql) {
+        return Err(rejected("proof is for another query"));
+    }
+    if inputs.query_result != Fr::from(value) {
+        return Err(rejected("proof does not attest the claimed result"));
+    }
+    verify_raw(&published.vk, &inputs.to_bytes()?, &published.proof)
+        .map_err(|e| rejected(&format!("proof rejected: {}", e)))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> PoneglyphError {
+    PoneglyphError::Serialization(format!("{}: {}", path.display(), e))
+}
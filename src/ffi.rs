@@ -0,0 +1,204 @@
+// C bindings
+// In-process verification for Go (cgo), Python (ctypes/cffi) and C++ services
+// (feature `ffi`). The crate builds as a cdylib; link against it and include
+// include/poneglyphdb.h, which cbindgen generates from this file:
+//
+//   cbindgen --config cbindgen.toml --output include/poneglyphdb.h
+//
+// `pdb_verify` takes the three files `poneglyphdb prove` writes (p.bin,
+// vk.bin, i.json) as byte buffers the caller owns; nothing is retained after
+// the call returns and no memory crosses the boundary in either direction.
+//
+// | Return              | Meaning                                          |
+// |---------------------|--------------------------------------------------|
+// | `PDB_VALID` (0)     | the proof verifies                               |
+// | positive            | the proof is rejected, by `VerifyError` kind     |
+// | negative            | the call itself is wrong (null buffer, panic)    |
+//
+// The reason for any non-zero return is kept per thread and read with
+// `pdb_last_error`, so the codes stay stable while the messages may change.
+
+use std::cell::RefCell;
+use std::ffi::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+
+use crate::circuit::PublicInputs;
+use crate::error::VerifyError;
+use crate::prover::{instance_from_hex, verify_raw};
+
+/// The proof verifies
+pub const PDB_VALID: i32 = 0;
+/// The proof does not verify against the instances
+pub const PDB_INVALID_PROOF: i32 = 1;
+/// The instances decode, but not to what the verifier expects
+pub const PDB_INSTANCE_MISMATCH: i32 = 2;
+/// The instances commit to another or an unanchored database
+pub const PDB_COMMITMENT_MISMATCH: i32 = 3;
+/// The proof was made for another circuit than the verifying key
+pub const PDB_WRONG_CIRCUIT_VERSION: i32 = 4;
+/// The proof, verifying key or instances can't be decoded
+pub const PDB_MALFORMED: i32 = 5;
+/// A buffer is null but its length is not zero
+pub const PDB_NULL_ARGUMENT: i32 = -1;
+/// Verification panicked (a bug; please report it with the inputs)
+pub const PDB_PANIC: i32 = -2;
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Verify a proof
+///
+/// `proof` and `vk` are the bytes of p.bin and vk.bin, `instances` the UTF-8
+/// text of i.json. Returns `PDB_VALID` or one of the `PDB_*` codes; the
+/// reason for a non-zero code is available from `pdb_last_error`.
+///
+/// # Safety
+///
+/// Each pointer must be valid for reads of its length in bytes, or null with
+/// a length of zero.
+#[no_mangle]
+pub unsafe extern "C" fn pdb_verify(
+    proof_ptr: *const u8,
+    proof_len: usize,
+    vk_ptr: *const u8,
+    vk_len: usize,
+    instances_ptr: *const u8,
+    instances_len: usize,
+) -> i32 {
+    let buffers = (
+        buffer(proof_ptr, proof_len),
+        buffer(vk_ptr, vk_len),
+        buffer(instances_ptr, instances_len),
+    );
+    let (proof, vk, instances) = match buffers {
+        (Some(proof), Some(vk), Some(instances)) => (proof, vk, instances),
+        _ => return fail(PDB_NULL_ARGUMENT, "null buffer with non-zero length"),
+    };
+    match catch_unwind(AssertUnwindSafe(|| verify(proof, vk, instances))) {
+        Ok(Ok(())) => {
+            LAST_ERROR.with(|last| last.borrow_mut().clear());
+            PDB_VALID
+        }
+        Ok(Err((code, message))) => fail(code, message),
+        Err(_) => fail(PDB_PANIC, "verification panicked"),
+    }
+}
+
+/// Reason for the last non-zero `pdb_verify` return on this thread
+///
+/// Copies the message into `buf` as a NUL-terminated string, truncated to
+/// `buf_len - 1` bytes, and returns its full length without the NUL. Call with
+/// a null `buf` to size the buffer. The message is empty after `PDB_VALID`.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `buf_len` bytes, or null.
+#[no_mangle]
+pub unsafe extern "C" fn pdb_last_error(buf: *mut c_char, buf_len: usize) -> usize {
+    LAST_ERROR.with(|last| {
+        let message = last.borrow();
+        if !buf.is_null() && buf_len > 0 {
+            let n = message.len().min(buf_len - 1);
+            std::ptr::copy_nonoverlapping(message.as_ptr(), buf as *mut u8, n);
+            *buf.add(n) = 0;
+        }
+        message.len()
+    })
+}
+
+unsafe fn buffer<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+fn fail(code: i32, message: impl Into<String>) -> i32 {
+    LAST_ERROR.with(|last| *last.borrow_mut() = message.into());
+    code
+}
+
+fn verify(proof: &[u8], vk: &[u8], instances: &[u8]) -> Result<(), (i32, String)> {
+    let malformed = |message: String| (PDB_MALFORMED, message);
+    let instances: Vec<Vec<String>> =
+        serde_json::from_slice(instances).map_err(|e| malformed(format!("instances: {}", e)))?;
+    let instance = instance_from_hex(&instances).map_err(|e| malformed(e.to_string()))?;
+    let inputs = PublicInputs::from_instance(&instance)
+        .ok_or_else(|| malformed("instances do not decode".to_string()))?;
+    let instance_bytes = inputs.to_bytes().map_err(|e| malformed(e.to_string()))?;
+    verify_raw(vk, &instance_bytes, proof).map_err(|e| (code(&e), e.to_string()))
+}
+
+fn code(error: &VerifyError) -> i32 {
+    match error {
+        VerifyError::InvalidProof(_) => PDB_INVALID_PROOF,
+        VerifyError::InstanceMismatch(_) => PDB_INSTANCE_MISMATCH,
+        VerifyError::CommitmentMismatch { .. } | VerifyError::UnknownCommitment(_) => {
+            PDB_COMMITMENT_MISMATCH
+        }
+        VerifyError::WrongCircuitVersion { .. } => PDB_WRONG_CIRCUIT_VERSION,
+        VerifyError::MalformedEnvelope(_) => PDB_MALFORMED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    fn last_error() -> String {
+        let mut buf = vec![0 as c_char; unsafe { pdb_last_error(ptr::null_mut(), 0) } + 1];
+        unsafe { pdb_last_error(buf.as_mut_ptr(), buf.len()) };
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn verify_bytes(proof: &[u8], vk: &[u8], instances: &[u8]) -> i32 {
+        unsafe {
+            pdb_verify(
+                proof.as_ptr(),
+                proof.len(),
+                vk.as_ptr(),
+                vk.len(),
+                instances.as_ptr(),
+                instances.len(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_malformed_inputs() {
+        assert_eq!(verify_bytes(b"p", b"vk", b"not json"), PDB_MALFORMED);
+        assert!(last_error().starts_with("instances:"));
+        assert_eq!(verify_bytes(b"p", b"vk", b"[[\"zz\"]]"), PDB_MALFORMED);
+        assert_eq!(verify_bytes(b"p", b"vk", b"[[]]"), PDB_MALFORMED);
+        assert!(last_error().contains("Malformed envelope"));
+    }
+
+    #[test]
+    fn test_null_buffers() {
+        let code = unsafe { pdb_verify(ptr::null(), 4, ptr::null(), 0, ptr::null(), 0) };
+        assert_eq!(code, PDB_NULL_ARGUMENT);
+        assert_eq!(last_error(), "null buffer with non-zero length");
+
+        // A null buffer of length zero is an empty buffer
+        let code = unsafe { pdb_verify(ptr::null(), 0, ptr::null(), 0, ptr::null(), 0) };
+        assert_eq!(code, PDB_MALFORMED);
+    }
+
+    #[test]
+    fn test_last_error_truncated() {
+        verify_bytes(b"p", b"vk", b"not json");
+        let full = last_error();
+        let mut buf = [1 as c_char; 6];
+        let len = unsafe { pdb_last_error(buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(len, full.len());
+        let truncated = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap();
+        assert_eq!(truncated, &full[..5]);
+    }
+}
@@ -12,8 +12,19 @@ pub mod optimization;
 pub mod utils;
 pub mod error;
 pub mod validation;
+pub mod examples_lib;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "cli")]
+pub mod repl;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 
-#[cfg(test)]
+#[doc(hidden)]
 pub mod test_utils;
 
 #[macro_use]
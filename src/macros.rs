@@ -1,4 +1,4 @@
-/// Helper macros for circuit operations
+//! Helper macros for circuit operations
 
 /// Macro to create a range check operation
 #[macro_export]
@@ -20,14 +20,14 @@ macro_rules! aggregation_op {
             group_keys: $group_keys,
             values: $values,
             agg_type: $agg_type,
+            column: None,
         }
     };
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::circuit::{AggregationOp, AggregationType, RangeCheckOp};
+    use crate::circuit::AggregationType;
 
     #[test]
     fn test_range_check_op_macro() {
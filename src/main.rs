@@ -1,7 +0,0 @@
-use poneglyphdb::circuit::*;
-
-fn main() {
-    println!("PoneglyphDB - Zero-Knowledge Database System");
-    println!("Starting implementation...");
-}
-
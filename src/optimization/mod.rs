@@ -1,6 +1,11 @@
 // Production Optimizations
 // Paper: Memory management and parallel processing optimizations
 
+pub mod plan;
+pub mod planner;
+pub use plan::*;
+pub use planner::*;
+
 use std::sync::Arc;
 
 use crate::circuit::{AggregationOp, GroupByOp, JoinOp, PoneglyphCircuit, RangeCheckOp, SortOp};
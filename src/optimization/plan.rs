@@ -0,0 +1,617 @@
+// Query plans
+// A compiled query as a tree of relational operators (scan, join, filter,
+// group, aggregate, window, project, sort, union), each annotated with the
+// chip that proves it and an estimate of the advice rows it takes. The plan
+// can be rendered as a DOT digraph or a JSON graph for UIs and notebooks.
+//
+// Row estimates follow the chips' region layouts and are upper bounds: the
+// floor planner may pack regions of disjoint columns side by side. Use
+// `CircuitStats::measure` for exact figures.
+
+use std::fmt::{self, Write};
+
+use serde::Serialize;
+
+use super::planner::{ExecutionPlan, PlanDecision};
+use crate::circuit::{hash_rows, pack_rows, AggregationOp, JoinKind, JoinOp, SortOp};
+use crate::constants::LOOKUP_TABLE_SIZE;
+use crate::sql::executor::encode_result_header;
+use crate::sql::{CompiledQuery, QueryStatement, SQLQuery, WhereClause};
+use crate::utils::{evcxr_html, html_escape, html_table, text_table, PACK_CHUNK_BYTES};
+
+/// Advice rows of one range check (check row and decomposition row)
+const RANGE_CHECK_ROWS: usize = 2;
+/// Advice rows of one BETWEEN check (two bounds, one decomposition each)
+const BETWEEN_ROWS: usize = 3;
+/// Advice rows per LIKE operation
+const LIKE_ROWS: usize = 4;
+/// Advice rows per CAST (cast row and four decompositions)
+const CAST_ROWS: usize = 5;
+/// Advice rows per semi / anti join key (two membership rows, three
+/// decompositions), plus one to assign a key that is not scanned
+const SEMI_JOIN_ROWS: usize = 5;
+/// Rows halo2 reserves for blinding at the end of every circuit
+const RESERVED_ROWS: usize = 16;
+/// Columns of the tabular rendering (`Display`, `to_html`)
+const PLAN_COLUMNS: [&str; 4] = ["operator", "detail", "chips", "rows"];
+
+/// Advice rows of sorting n values (permutation, order checks, decompositions)
+pub(crate) fn sort_rows(n: usize) -> usize {
+    (12 * n).saturating_sub(9)
+}
+
+/// Advice rows of the commitment to a one-value result: the value's
+/// decomposition, its packed encoding and the hash (see
+/// `PoneglyphCircuit::hashes_result`)
+fn result_commitment_rows(columns: &[String]) -> usize {
+    let mut encoding = Vec::new();
+    encode_result_header(columns, 1, &mut encoding);
+    // Non-NULL tag and the value bytes
+    let len = encoding.len() + 9;
+    RANGE_CHECK_ROWS + pack_rows(len, 8) + hash_rows(1 + len.div_ceil(PACK_CHUNK_BYTES))
+}
+
+/// Advice rows of grouping n sorted keys (boundary row and check per key)
+fn group_rows(n: usize) -> usize {
+    2 * n
+}
+
+fn join_rows(op: &JoinOp) -> usize {
+    match op.kind {
+        JoinKind::Inner => {
+            let n = op.table1_keys.len() + op.table2_keys.len();
+            sort_rows(n) + n
+        }
+        JoinKind::Semi | JoinKind::Anti => {
+            let per_key = SEMI_JOIN_ROWS + usize::from(op.table1_column.is_none());
+            let gaps = op.table2_keys.len() + 1;
+            sort_rows(op.table2_keys.len()) + gaps + op.table1_keys.len() * per_key
+        }
+    }
+}
+
+fn aggregation_rows(op: &AggregationOp) -> usize {
+    let n = op.values.len();
+    let rank = if op.agg_type.is_rank() {
+        sort_rows(n)
+    } else {
+        0
+    };
+    group_rows(op.group_keys.len()) + n + rank
+}
+
+/// Relational operator of a plan node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanOperator {
+    Scan,
+    Cte,
+    Series,
+    Join,
+    Filter,
+    GroupBy,
+    Aggregate,
+    Window,
+    Project,
+    Sort,
+    Union,
+}
+
+impl PlanOperator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlanOperator::Scan => "Scan",
+            PlanOperator::Cte => "CTE",
+            PlanOperator::Series => "Series",
+            PlanOperator::Join => "Join",
+            PlanOperator::Filter => "Filter",
+            PlanOperator::GroupBy => "GroupBy",
+            PlanOperator::Aggregate => "Aggregate",
+            PlanOperator::Window => "Window",
+            PlanOperator::Project => "Project",
+            PlanOperator::Sort => "Sort",
+            PlanOperator::Union => "Union",
+        }
+    }
+}
+
+/// One operator of a plan
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanNode {
+    pub id: usize,
+    pub operator: PlanOperator,
+    /// What the operator does, e.g. the predicate or the sort keys
+    pub detail: String,
+    /// Chips that prove the operator (empty: nothing to prove, e.g. a join
+    /// side, whose rows are not committed in-circuit)
+    pub chips: Vec<&'static str>,
+    /// Estimated advice rows
+    pub rows: usize,
+    /// Nodes this one consumes
+    pub inputs: Vec<usize>,
+}
+
+/// Operator tree of a compiled query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryPlan {
+    /// Nodes in construction order (inputs come before their consumers)
+    pub nodes: Vec<PlanNode>,
+    pub root: usize,
+    /// Planner decisions behind the operator order (empty: as written)
+    pub decisions: Vec<PlanDecision>,
+}
+
+impl QueryPlan {
+    /// Plan of a parsed query and its compiled operations
+    pub fn of(query: &SQLQuery, compiled: &CompiledQuery) -> Self {
+        let mut nodes = Vec::new();
+        let root = build(&mut nodes, query, compiled, None);
+        Self {
+            nodes,
+            root,
+            decisions: Vec::new(),
+        }
+    }
+
+    /// Plan of a query compiled with `SQLCompiler::compile_planned`
+    /// Joins appear in execution order, and a pushed-down filter below them
+    pub fn planned(query: &SQLQuery, compiled: &CompiledQuery, plan: &ExecutionPlan) -> Self {
+        let mut nodes = Vec::new();
+        let root = build(&mut nodes, query, compiled, Some(plan));
+        Self {
+            nodes,
+            root,
+            decisions: plan.decisions.clone(),
+        }
+    }
+
+    /// Estimated advice rows of the whole plan
+    pub fn total_rows(&self) -> usize {
+        self.nodes.iter().map(|n| n.rows).sum()
+    }
+
+    /// Smallest k whose circuit fits the estimated rows and the range check
+    /// lookup table
+    pub fn min_k(&self) -> u32 {
+        let rows = self.total_rows().max(LOOKUP_TABLE_SIZE as usize) + RESERVED_ROWS;
+        rows.next_power_of_two().trailing_zeros()
+    }
+
+    /// Nodes and edges for rendering
+    pub fn to_graph(&self) -> PlanGraph {
+        let edges = self
+            .nodes
+            .iter()
+            .flat_map(|node| {
+                node.inputs
+                    .iter()
+                    .map(move |&from| PlanEdge { from, to: node.id })
+            })
+            .collect();
+        PlanGraph {
+            nodes: self.nodes.clone(),
+            edges,
+            root: self.root,
+            total_rows: self.total_rows(),
+        }
+    }
+
+    /// HTML table of the operator tree, for notebooks and web pages
+    pub fn to_html(&self) -> String {
+        let columns = PLAN_COLUMNS.map(str::to_string);
+        let mut html = html_table(&columns, &self.cells("\u{a0}\u{a0}"));
+        let _ = write!(html, "\n<p>{}</p>", html_escape(&self.summary()));
+        for decision in &self.decisions {
+            let _ = write!(
+                html,
+                "\n<p>planner: {}</p>",
+                html_escape(&decision.to_string())
+            );
+        }
+        html
+    }
+
+    /// Rich output in evcxr (Jupyter) notebooks: the operator tree as an HTML table
+    pub fn evcxr_display(&self) {
+        evcxr_html(&self.to_html());
+    }
+
+    /// Nodes from the root down, each with its depth; inputs in order
+    fn tree(&self) -> Vec<(usize, &PlanNode)> {
+        let mut tree = Vec::new();
+        let mut stack = vec![(0, self.root)];
+        while let Some((depth, id)) = stack.pop() {
+            let node = &self.nodes[id];
+            tree.push((depth, node));
+            stack.extend(node.inputs.iter().rev().map(|&input| (depth + 1, input)));
+        }
+        tree
+    }
+
+    /// One row per node, the operator indented by `indent` per level
+    fn cells(&self, indent: &str) -> Vec<Vec<String>> {
+        self.tree()
+            .into_iter()
+            .map(|(depth, node)| {
+                vec![
+                    format!("{}{}", indent.repeat(depth), node.operator.name()),
+                    node.detail.clone(),
+                    node.chips.join(", "),
+                    node.rows.to_string(),
+                ]
+            })
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        format!("~{} advice rows, k >= {}", self.total_rows(), self.min_k())
+    }
+}
+
+/// Operator tree as a table, root first, with the row estimate and the
+/// planner decisions underneath
+impl fmt::Display for QueryPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = PLAN_COLUMNS.map(str::to_string);
+        write!(f, "{}", text_table(&columns, &self.cells("  ")))?;
+        write!(f, "{}", self.summary())?;
+        for decision in &self.decisions {
+            write!(f, "\nplanner: {}", decision)?;
+        }
+        Ok(())
+    }
+}
+
+/// Data flow from one operator to the next
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanEdge {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Renderable plan: DOT for Graphviz, JSON for UIs and notebooks
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanGraph {
+    pub nodes: Vec<PlanNode>,
+    pub edges: Vec<PlanEdge>,
+    pub root: usize,
+    pub total_rows: usize,
+}
+
+impl PlanGraph {
+    /// Graphviz digraph, data flowing bottom-up to the root
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph plan {\n  rankdir=BT;\n  node [shape=box];\n");
+        for node in &self.nodes {
+            let mut label = node.operator.name().to_string();
+            if !node.detail.is_empty() {
+                let _ = write!(label, "\\n{}", escape(&node.detail));
+            }
+            if !node.chips.is_empty() {
+                let _ = write!(label, "\\n[{}]", node.chips.join(", "));
+            }
+            let _ = write!(label, "\\n~{} rows", node.rows);
+            let _ = writeln!(out, "  n{} [label=\"{}\"];", node.id, label);
+        }
+        for edge in &self.edges {
+            let _ = writeln!(out, "  n{} -> n{};", edge.from, edge.to);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// JSON graph: `nodes`, `edges` (`from` → `to`), `root`, `total_rows`
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+/// Escape a DOT label (quotes and backslashes)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn push(
+    nodes: &mut Vec<PlanNode>,
+    operator: PlanOperator,
+    detail: String,
+    chips: Vec<&'static str>,
+    rows: usize,
+    inputs: Vec<usize>,
+) -> usize {
+    let id = nodes.len();
+    nodes.push(PlanNode {
+        id,
+        operator,
+        detail,
+        chips,
+        rows,
+        inputs,
+    });
+    id
+}
+
+/// Add the nodes of one query, returning its root
+/// `plan` orders the joins and filter of the top-level query
+fn build(
+    nodes: &mut Vec<PlanNode>,
+    query: &SQLQuery,
+    compiled: &CompiledQuery,
+    plan: Option<&ExecutionPlan>,
+) -> usize {
+    use PlanOperator::*;
+
+    let statement = query.statement();
+
+    // CTEs first, so scans of their names can consume them (unreferenced
+    // CTEs aren't compiled and have no node)
+    let ctes: Vec<(String, usize)> = compiled
+        .ctes
+        .iter()
+        .filter_map(|inner| {
+            let cte = query.ctes.iter().flatten().find(|c| c.name == inner.name)?;
+            let body = build(nodes, &cte.query, &inner.inner, None);
+            let id = push(nodes, Cte, cte.name.clone(), vec![], 0, vec![body]);
+            Some((cte.name.clone(), id))
+        })
+        .collect();
+    let scan = |nodes: &mut Vec<PlanNode>, table: &str| {
+        let inputs = ctes
+            .iter()
+            .filter(|(name, _)| name == table)
+            .map(|&(_, id)| id)
+            .collect();
+        push(nodes, Scan, table.to_string(), vec![], 0, inputs)
+    };
+
+    let mut current = match &query.table_function {
+        Some(function) => {
+            let rows = compiled.series.iter().map(|op| 2 * op.values().len()).sum();
+            push(
+                nodes,
+                Series,
+                function.to_string(),
+                vec!["series"],
+                rows,
+                vec![],
+            )
+        }
+        None => {
+            let id = scan(nodes, &query.from);
+            // The scanned cells are assigned and hashed into the commitment
+            if compiled.scanned_cells > 0 {
+                nodes[id].chips = vec!["poseidon"];
+                nodes[id].rows = compiled.scanned_cells + hash_rows(compiled.scanned_cells);
+            }
+            id
+        }
+    };
+
+    let pushed = plan.is_some_and(|plan| {
+        plan.decisions
+            .iter()
+            .any(|d| matches!(d, PlanDecision::PushFilter { .. }))
+    });
+    if pushed {
+        current = filter(nodes, query, compiled, &statement, current);
+    }
+
+    let joins: Vec<_> = query.joins.iter().flatten().collect();
+    let order: Vec<usize> = match plan {
+        Some(plan) => plan.join_order.clone(),
+        None => (0..joins.len()).collect(),
+    };
+    for (pos, &i) in order.iter().enumerate() {
+        let right = scan(nodes, &joins[i].table);
+        let rows = compiled.joins.get(pos).map_or(0, join_rows);
+        let detail = statement.joins.get(i).cloned().unwrap_or_default();
+        current = push(
+            nodes,
+            Join,
+            detail,
+            vec!["join", "sort"],
+            rows,
+            vec![current, right],
+        );
+    }
+
+    if !pushed {
+        current = filter(nodes, query, compiled, &statement, current);
+    }
+
+    if !statement.group_by.is_empty() || !compiled.group_bys.is_empty() {
+        let rows = compiled
+            .group_bys
+            .iter()
+            .map(|op| group_rows(op.group_keys.len()))
+            .sum();
+        let detail = statement.group_by.join(", ");
+        current = push(
+            nodes,
+            GroupBy,
+            detail,
+            vec!["group_by"],
+            rows,
+            vec![current],
+        );
+    }
+
+    if query.aggregations.is_some() || !compiled.aggregations.is_empty() {
+        let mut rows = compiled.aggregations.iter().map(aggregation_rows).sum();
+        let mut chips = vec!["aggregation"];
+        // A one-value result is committed in-circuit
+        if !compiled.aggregations.is_empty() && compiled.result_columns.len() == 1 {
+            rows += result_commitment_rows(&compiled.result_columns);
+            chips.push("poseidon");
+        }
+        let detail = statement.outputs.join(", ");
+        current = push(nodes, Aggregate, detail, chips, rows, vec![current]);
+    }
+
+    if !compiled.windows.is_empty() {
+        let rows = compiled
+            .windows
+            .iter()
+            .map(|op| sort_rows(op.order_keys.len()) + 2 * op.order_keys.len())
+            .sum();
+        let detail = query
+            .windows
+            .iter()
+            .flatten()
+            .map(|w| format!("{:?} OVER {}", w.function, w.order_by))
+            .collect::<Vec<_>>()
+            .join(", ");
+        current = push(nodes, Window, detail, vec!["window"], rows, vec![current]);
+    }
+
+    if !compiled.arithmetic.is_empty() || !compiled.casts.is_empty() {
+        let arithmetic: usize = compiled
+            .arithmetic
+            .iter()
+            .map(|op| 2 * op.inputs.values().map(Vec::len).max().unwrap_or(0))
+            .sum();
+        let mut chips = Vec::new();
+        if !compiled.arithmetic.is_empty() {
+            chips.push("arithmetic");
+        }
+        if !compiled.casts.is_empty() {
+            chips.push("cast");
+        }
+        let rows = arithmetic + compiled.casts.len() * CAST_ROWS;
+        let detail = query.columns.join(", ");
+        current = push(nodes, Project, detail, chips, rows, vec![current]);
+    }
+
+    if !statement.order_by.is_empty() || !compiled.sorts.is_empty() {
+        let rows = compiled
+            .sorts
+            .iter()
+            .map(|op: &SortOp| sort_rows(op.input.len()))
+            .sum();
+        let detail = statement.order_by.join(", ");
+        current = push(nodes, Sort, detail, vec!["sort"], rows, vec![current]);
+    }
+
+    if let Some((union, inner)) = query.union.as_ref().zip(compiled.unions.first()) {
+        let right = build(nodes, &union.query, &inner.inner, None);
+        let detail = if union.all { "ALL" } else { "DISTINCT" };
+        current = push(
+            nodes,
+            Union,
+            detail.to_string(),
+            vec!["union"],
+            0,
+            vec![current, right],
+        );
+    }
+
+    current
+}
+
+/// Add the filter node of a query (if it has a predicate) on top of `input`
+fn filter(
+    nodes: &mut Vec<PlanNode>,
+    query: &SQLQuery,
+    compiled: &CompiledQuery,
+    statement: &QueryStatement,
+    input: usize,
+) -> usize {
+    let subqueries: Vec<&SQLQuery> = query
+        .predicate
+        .iter()
+        .flat_map(|p| p.leaves())
+        .filter_map(|leaf| match leaf {
+            WhereClause::CompareSubquery { subquery, .. }
+            | WhereClause::InSubquery { subquery, .. } => Some(subquery.as_ref()),
+            _ => None,
+        })
+        .collect();
+    let semi_joins: Vec<&SQLQuery> = query
+        .predicate
+        .iter()
+        .flat_map(|p| p.leaves())
+        .filter_map(|leaf| match leaf {
+            WhereClause::SemiJoin { subquery, .. } => Some(subquery.as_ref()),
+            _ => None,
+        })
+        .collect();
+    let filter_rows = compiled.range_checks.len() * RANGE_CHECK_ROWS
+        + compiled.betweens.len() * BETWEEN_ROWS
+        + compiled.likes.len() * LIKE_ROWS
+        + compiled
+            .in_lists
+            .iter()
+            .map(|op| op.values.len() * (op.list.len() + 1))
+            .sum::<usize>()
+        + compiled
+            .subqueries
+            .iter()
+            .map(|s| s.filter.values.len() * RANGE_CHECK_ROWS)
+            .sum::<usize>()
+        + compiled
+            .semi_joins
+            .iter()
+            .map(|s| join_rows(&s.op))
+            .sum::<usize>();
+    if statement.predicate.is_none() && filter_rows == 0 {
+        return input;
+    }
+
+    let mut chips = Vec::new();
+    let mut chip = |used: bool, name: &'static str| {
+        if used {
+            chips.push(name);
+        }
+    };
+    chip(!compiled.range_checks.is_empty(), "range_check");
+    chip(!compiled.betweens.is_empty(), "range_check");
+    chip(!compiled.likes.is_empty(), "like");
+    chip(!compiled.in_lists.is_empty(), "in_list");
+    chip(!compiled.subqueries.is_empty(), "subquery");
+    chip(!compiled.semi_joins.is_empty(), "join");
+    chip(compiled.predicate.is_some(), "boolean");
+    chips.dedup();
+
+    let mut inputs = vec![input];
+    for (subquery, inner) in subqueries.into_iter().zip(&compiled.subqueries) {
+        inputs.push(build(nodes, subquery, &inner.inner, None));
+    }
+    for (subquery, semi_join) in semi_joins.into_iter().zip(&compiled.semi_joins) {
+        inputs.push(build(nodes, subquery, &semi_join.inner, None));
+    }
+    let detail = statement.predicate.clone().unwrap_or_default();
+    push(
+        nodes,
+        PlanOperator::Filter,
+        detail,
+        chips,
+        filter_rows,
+        inputs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_escaping() {
+        assert_eq!(escape(r#"name = "a\b""#), r#"name = \"a\\b\""#);
+        let graph = PlanGraph {
+            nodes: vec![PlanNode {
+                id: 0,
+                operator: PlanOperator::Scan,
+                detail: "t".to_string(),
+                chips: vec![],
+                rows: 0,
+                inputs: vec![],
+            }],
+            edges: vec![],
+            root: 0,
+            total_rows: 0,
+        };
+        assert!(graph
+            .to_dot()
+            .contains("n0 [label=\"Scan\\nt\\n~0 rows\"];"));
+    }
+}
@@ -0,0 +1,302 @@
+// Cost-based query planner
+// Chooses the operator order of a query before it is compiled, from the rows
+// of the tables it reads. Without a plan the compiler follows the query as
+// written: every join and sort sees every row of the FROM table, and joins
+// run in textual order.
+//
+// - Filter pushdown: top-level AND conjuncts comparing a FROM column with
+//   constants are applied first, so joins and sorts only take the qualifying
+//   rows. The WHERE predicate itself is still proven over every row.
+// - Join ordering: an inner join drops the FROM rows without a match, so
+//   inner joins run first, the one keeping the fewest rows next (greedy);
+//   outer joins keep every row and run last, in written order.
+//
+// Costs are advice-row estimates with the formulas of `QueryPlan`.
+// `SQLCompiler::compile_planned` consumes the chosen `ExecutionPlan`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use super::plan::sort_rows;
+use crate::sql::{JoinType, PredicateExpr, SQLQuery, WhereClause};
+
+/// Optimization the planner applied
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanDecision {
+    /// Conjuncts evaluated before joins and sorts
+    PushFilter {
+        filter: String,
+        rows_before: usize,
+        rows_after: usize,
+    },
+    /// Joins run in another order than written
+    ReorderJoins {
+        written: Vec<String>,
+        chosen: Vec<String>,
+    },
+}
+
+impl fmt::Display for PlanDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanDecision::PushFilter {
+                filter,
+                rows_before,
+                rows_after,
+            } => write!(
+                f,
+                "push {} below joins and sorts ({} -> {} rows)",
+                filter, rows_before, rows_after
+            ),
+            PlanDecision::ReorderJoins { written, chosen } => write!(
+                f,
+                "join {} instead of {}",
+                chosen.join(", "),
+                written.join(", ")
+            ),
+        }
+    }
+}
+
+/// Operator order chosen for one query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionPlan {
+    /// Indices into the query's joins, in execution order
+    pub join_order: Vec<usize>,
+    /// FROM rows entering each join of `join_order` (missing: every row)
+    pub join_inputs: Vec<Vec<usize>>,
+    /// FROM rows reaching the sorts, after pushed filters and inner joins
+    /// (None: every row)
+    pub sort_input: Option<Vec<usize>>,
+    pub decisions: Vec<PlanDecision>,
+    /// Estimated join and sort rows of the query as written
+    pub written_rows: usize,
+    /// Estimated join and sort rows under this plan
+    pub planned_rows: usize,
+}
+
+impl ExecutionPlan {
+    /// One line per decision and the estimated saving
+    pub fn explain(&self) -> String {
+        let mut lines: Vec<String> = self.decisions.iter().map(|d| d.to_string()).collect();
+        if lines.is_empty() {
+            lines.push("as written".to_string());
+        }
+        lines.push(format!(
+            "join and sort rows: ~{} (as written ~{})",
+            self.planned_rows, self.written_rows
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Cost-based planner over a table snapshot
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let plan = Planner::new(&table_data).plan(&query)?;
+/// println!("{}", plan.explain());
+/// let compiled = SQLCompiler::compile_planned(&query, &table_data, &plan)?;
+/// ```
+pub struct Planner<'a> {
+    table_data: &'a HashMap<String, HashMap<String, Vec<u64>>>,
+}
+
+impl<'a> Planner<'a> {
+    pub fn new(table_data: &'a HashMap<String, HashMap<String, Vec<u64>>>) -> Self {
+        Self { table_data }
+    }
+
+    /// Choose the operator order of `query`
+    /// Queries with CTEs, UNION or a table function compile as written
+    /// (the plan then keeps every row in written order)
+    pub fn plan(&self, query: &SQLQuery) -> Result<ExecutionPlan, String> {
+        let joins: Vec<_> = query.joins.iter().flatten().collect();
+        if query.ctes.is_some() || query.union.is_some() || query.table_function.is_some() {
+            return Ok(ExecutionPlan {
+                join_order: (0..joins.len()).collect(),
+                join_inputs: Vec::new(),
+                sort_input: None,
+                decisions: Vec::new(),
+                written_rows: 0,
+                planned_rows: 0,
+            });
+        }
+
+        let table = self.table(&query.from)?;
+        let from_rows = table.values().map(Vec::len).max().unwrap_or(0);
+        let sorts = query.order_by.as_ref().map_or(0, Vec::len);
+
+        // Right-side keys and row counts per join
+        let mut right = Vec::new();
+        for join in &joins {
+            let keys = self
+                .table(&join.table)?
+                .get(&join.on.right_column)
+                .ok_or_else(|| {
+                    format!(
+                        "Column {} not found in table {}",
+                        join.on.right_column, join.table
+                    )
+                })?;
+            let left = table.get(&join.on.left_column).ok_or_else(|| {
+                format!(
+                    "Column {} not found in table {}",
+                    join.on.left_column, query.from
+                )
+            })?;
+            right.push((
+                left,
+                keys.len(),
+                keys.iter().copied().collect::<HashSet<u64>>(),
+            ));
+        }
+        let join_cost = |i: usize, rows: usize| sort_rows(rows + right[i].1) + rows + right[i].1;
+        let written_rows = (0..joins.len())
+            .map(|i| join_cost(i, from_rows))
+            .sum::<usize>()
+            + sorts * sort_rows(from_rows);
+
+        let mut decisions = Vec::new();
+
+        // Filter pushdown
+        let pushable = pushable_conjuncts(query);
+        let mut current: Vec<usize> = (0..from_rows).collect();
+        if !pushable.is_empty() && (!joins.is_empty() || sorts > 0) {
+            let mut selected = Vec::new();
+            for row in 0..from_rows {
+                if pushable
+                    .iter()
+                    .all(|clause| selects(clause, table, row).unwrap_or(true))
+                {
+                    selected.push(row);
+                }
+            }
+            if selected.len() < from_rows {
+                decisions.push(PlanDecision::PushFilter {
+                    filter: pushable
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" AND "),
+                    rows_before: from_rows,
+                    rows_after: selected.len(),
+                });
+            }
+            current = selected;
+        }
+
+        // Join ordering: greedy over inner joins, then outer joins as written
+        let survivors = |i: usize, rows: &[usize]| -> Vec<usize> {
+            let (left, _, keys) = &right[i];
+            rows.iter()
+                .copied()
+                .filter(|&r| left.get(r).is_some_and(|k| keys.contains(k)))
+                .collect()
+        };
+        let mut pending: Vec<usize> = (0..joins.len())
+            .filter(|&i| matches!(joins[i].join_type, JoinType::Inner))
+            .collect();
+        let mut join_order = Vec::new();
+        let mut join_inputs = Vec::new();
+        let mut planned_rows = 0;
+        while !pending.is_empty() {
+            let (pos, next) = pending
+                .iter()
+                .enumerate()
+                .map(|(pos, &i)| (pos, survivors(i, &current)))
+                .min_by_key(|(pos, kept)| {
+                    let i = pending[*pos];
+                    (kept.len(), join_cost(i, current.len()), i)
+                })
+                .expect("pending joins");
+            let i = pending.remove(pos);
+            planned_rows += join_cost(i, current.len());
+            join_order.push(i);
+            join_inputs.push(std::mem::replace(&mut current, next));
+        }
+        let remaining: Vec<usize> = (0..joins.len())
+            .filter(|i| !join_order.contains(i))
+            .collect();
+        for i in remaining {
+            planned_rows += join_cost(i, current.len());
+            join_order.push(i);
+            join_inputs.push(current.clone());
+        }
+        planned_rows += sorts * sort_rows(current.len());
+
+        if join_order.iter().enumerate().any(|(pos, &i)| pos != i) {
+            let name = |&i: &usize| joins[i].table.clone();
+            decisions.push(PlanDecision::ReorderJoins {
+                written: (0..joins.len()).map(|i| name(&i)).collect(),
+                chosen: join_order.iter().map(name).collect(),
+            });
+        }
+
+        Ok(ExecutionPlan {
+            join_order,
+            join_inputs,
+            sort_input: Some(current),
+            decisions,
+            written_rows,
+            planned_rows,
+        })
+    }
+
+    fn table(&self, name: &str) -> Result<&'a HashMap<String, Vec<u64>>, String> {
+        self.table_data
+            .get(name)
+            .ok_or_else(|| format!("Table {} not found", name))
+    }
+}
+
+/// Top-level AND conjuncts comparing a column with constants
+/// (OR, NOT, LIKE and subqueries stay where they are)
+fn pushable_conjuncts(query: &SQLQuery) -> Vec<&WhereClause> {
+    fn conjuncts<'q>(expr: &'q PredicateExpr, out: &mut Vec<&'q WhereClause>) {
+        match expr {
+            PredicateExpr::And(l, r) => {
+                conjuncts(l, out);
+                conjuncts(r, out);
+            }
+            PredicateExpr::Compare(clause) => out.push(clause),
+            PredicateExpr::Or(..) | PredicateExpr::Not(_) => {}
+        }
+    }
+    let mut out = Vec::new();
+    if let Some(predicate) = &query.predicate {
+        conjuncts(predicate, &mut out);
+    }
+    out.retain(|clause| {
+        matches!(
+            clause,
+            WhereClause::LessThan { .. }
+                | WhereClause::GreaterThan { .. }
+                | WhereClause::Equal { .. }
+                | WhereClause::Between { .. }
+                | WhereClause::In { .. }
+        )
+    });
+    out
+}
+
+/// Whether `row` satisfies a pushable comparison (None if the column is not
+/// in the table, e.g. a computed expression)
+fn selects(clause: &WhereClause, table: &HashMap<String, Vec<u64>>, row: usize) -> Option<bool> {
+    let value = |column: &String| table.get(column)?.get(row).copied();
+    Some(match clause {
+        WhereClause::LessThan { column, value: v } => value(column)? < *v,
+        WhereClause::GreaterThan { column, value: v } => value(column)? > *v,
+        WhereClause::Equal { column, value: v } => value(column)? == *v,
+        WhereClause::Between { column, low, high } => {
+            let x = value(column)?;
+            *low <= x && x <= *high
+        }
+        WhereClause::In { column, values } => values.contains(&value(column)?),
+        _ => return None,
+    })
+}
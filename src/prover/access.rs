@@ -0,0 +1,251 @@
+// API key roles and permissions
+// Who may do what against a prover deployment. Every request carries an API
+// key; the key maps to one role, and the role to a fixed set of permissions:
+//
+// | Permission      | admin | loader | querier | verifier |
+// |-----------------|-------|--------|---------|----------|
+// | `MutateCatalog` |   ✓   |   ✓    |         |          |
+// | `Prove`         |   ✓   |        |    ✓    |          |
+// | `Verify`        |   ✓   |   ✓    |    ✓    |    ✓     |
+// | `ManageKeys`    |   ✓   |        |         |          |
+//
+// Only the Poseidon hash of each key is stored, so a dumped key table doesn't
+// leak usable credentials. This is the authorization layer a network front end
+// enforces before touching a `Session`: the proving service (feature
+// `server`) checks it on every request, other front ends call
+// `ApiKeys::authorize` themselves.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use ff::PrimeField;
+
+use crate::utils::poseidon_hash_bytes;
+
+/// Action guarded by a permission
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Register, replace or drop tables
+    MutateCatalog,
+    /// Run queries and generate proofs
+    Prove,
+    /// Verify proofs and read verifying keys
+    Verify,
+    /// Issue and revoke API keys, rotate proving keys
+    ManageKeys,
+}
+
+/// Role of an API key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    Admin,
+    Loader,
+    Querier,
+    Verifier,
+}
+
+impl Role {
+    pub fn permits(&self, permission: Permission) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Loader => matches!(permission, Permission::MutateCatalog | Permission::Verify),
+            Role::Querier => matches!(permission, Permission::Prove | Permission::Verify),
+            Role::Verifier => permission == Permission::Verify,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Loader => "loader",
+            Role::Querier => "querier",
+            Role::Verifier => "verifier",
+        }
+    }
+
+    /// Parse a role name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "admin" => Some(Role::Admin),
+            "loader" => Some(Role::Loader),
+            "querier" => Some(Role::Querier),
+            "verifier" => Some(Role::Verifier),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Why a request was refused
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccessError {
+    /// The key was never issued or has been revoked
+    UnknownKey,
+    /// The key's role lacks the permission
+    Forbidden { role: Role, permission: Permission },
+    /// Revoking or demoting the key would leave no admin
+    LastAdmin,
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessError::UnknownKey => write!(f, "Unknown API key"),
+            AccessError::Forbidden { role, permission } => {
+                write!(f, "Role {} may not {:?}", role, permission)
+            }
+            AccessError::LastAdmin => write!(f, "The last admin key cannot be revoked or demoted"),
+        }
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+/// Issued API keys, by hash
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let mut keys = ApiKeys::with_admin("root-key");
+/// keys.issue("root-key", "etl-key", Role::Loader)?;
+///
+/// keys.authorize("etl-key", Permission::MutateCatalog)?;
+/// session.register_table(table);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeys {
+    /// Poseidon hash (bytes) of each key -> role
+    roles: HashMap<[u8; 32], Role>,
+}
+
+impl ApiKeys {
+    /// Key table with one admin key (the bootstrap credential)
+    pub fn with_admin(key: &str) -> Self {
+        let mut keys = Self::default();
+        keys.roles.insert(key_hash(key), Role::Admin);
+        keys
+    }
+
+    /// Role of `key`, if issued
+    pub fn role(&self, key: &str) -> Option<Role> {
+        self.roles.get(&key_hash(key)).copied()
+    }
+
+    /// Check that `key` may perform `permission`
+    /// Returns the key's role
+    pub fn authorize(&self, key: &str, permission: Permission) -> Result<Role, AccessError> {
+        let role = self.role(key).ok_or(AccessError::UnknownKey)?;
+        if !role.permits(permission) {
+            return Err(AccessError::Forbidden { role, permission });
+        }
+        Ok(role)
+    }
+
+    /// Issue `new_key` with `role` (or change its role), on behalf of `admin_key`
+    pub fn issue(&mut self, admin_key: &str, new_key: &str, role: Role) -> Result<(), AccessError> {
+        self.authorize(admin_key, Permission::ManageKeys)?;
+        let hash = key_hash(new_key);
+        if role != Role::Admin && self.roles.get(&hash) == Some(&Role::Admin) && self.admins() == 1
+        {
+            return Err(AccessError::LastAdmin);
+        }
+        self.roles.insert(hash, role);
+        Ok(())
+    }
+
+    /// Revoke `key`, on behalf of `admin_key`
+    pub fn revoke(&mut self, admin_key: &str, key: &str) -> Result<Role, AccessError> {
+        self.authorize(admin_key, Permission::ManageKeys)?;
+        let hash = key_hash(key);
+        match self.roles.get(&hash) {
+            None => Err(AccessError::UnknownKey),
+            Some(Role::Admin) if self.admins() == 1 => Err(AccessError::LastAdmin),
+            Some(_) => Ok(self.roles.remove(&hash).unwrap()),
+        }
+    }
+
+    /// Number of issued keys
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    fn admins(&self) -> usize {
+        self.roles.values().filter(|r| **r == Role::Admin).count()
+    }
+}
+
+fn key_hash(key: &str) -> [u8; 32] {
+    poseidon_hash_bytes(key.as_bytes()).to_repr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_permissions() {
+        let mut keys = ApiKeys::with_admin("root");
+        keys.issue("root", "etl", Role::Loader).unwrap();
+        keys.issue("root", "analyst", Role::Querier).unwrap();
+        keys.issue("root", "auditor", Role::Verifier).unwrap();
+
+        assert_eq!(
+            keys.authorize("etl", Permission::MutateCatalog),
+            Ok(Role::Loader)
+        );
+        assert_eq!(
+            keys.authorize("analyst", Permission::Prove),
+            Ok(Role::Querier)
+        );
+        assert_eq!(
+            keys.authorize("auditor", Permission::Verify),
+            Ok(Role::Verifier)
+        );
+        assert_eq!(
+            keys.authorize("auditor", Permission::Prove),
+            Err(AccessError::Forbidden {
+                role: Role::Verifier,
+                permission: Permission::Prove,
+            })
+        );
+        assert_eq!(
+            keys.authorize("guess", Permission::Verify),
+            Err(AccessError::UnknownKey)
+        );
+
+        // Only admins manage keys
+        assert!(keys.issue("etl", "etl2", Role::Admin).is_err());
+        assert_eq!(keys.role("etl2"), None);
+    }
+
+    #[test]
+    fn test_revoke() {
+        let mut keys = ApiKeys::with_admin("root");
+        keys.issue("root", "analyst", Role::Querier).unwrap();
+        assert_eq!(keys.revoke("root", "analyst"), Ok(Role::Querier));
+        assert_eq!(
+            keys.authorize("analyst", Permission::Prove),
+            Err(AccessError::UnknownKey)
+        );
+
+        // The last admin stays
+        assert_eq!(keys.revoke("root", "root"), Err(AccessError::LastAdmin));
+        assert_eq!(
+            keys.issue("root", "root", Role::Querier),
+            Err(AccessError::LastAdmin)
+        );
+        keys.issue("root", "root2", Role::Admin).unwrap();
+        assert_eq!(keys.revoke("root2", "root"), Ok(Role::Admin));
+        assert_eq!(keys.len(), 1);
+        assert_eq!(Role::from_name("Loader"), Some(Role::Loader));
+    }
+}
@@ -0,0 +1,324 @@
+// Content-addressed proof artifacts
+// Proofs and verifying keys are stored under the BLAKE2b-256 hash of their
+// serialized envelope (`Proof::to_bytes`, `VerifyingKeyBytes::to_bytes`), so a
+// reference like `proof-3f2a…` names exactly one artifact: a verification
+// service fetches it by reference and can check that the bytes hash back to
+// it. Storing the same artifact twice is a no-op.
+//
+// Bytes live in any `StorageBackend` (`MemoryStorage`, `FileStorage`, or an
+// object store implementing the trait), keyed by the reference string.
+// Uses are tracked in memory for garbage collection (`GcPolicy`); artifacts
+// not used since the store was opened count as last used at opening.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use halo2_proofs::{pasta::EqAffine, plonk::VerifyingKey};
+
+use super::gc::{Collectable, GcPolicy, GcReport};
+use super::{Proof, VerifyingKeyBytes};
+use crate::circuit::PoneglyphCircuit;
+use crate::database::StorageBackend;
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+/// What an artifact holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    Proof,
+    VerifyingKey,
+}
+
+impl ArtifactKind {
+    /// Prefix of the reference string
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            ArtifactKind::Proof => "proof",
+            ArtifactKind::VerifyingKey => "vk",
+        }
+    }
+}
+
+/// Reference to a stored artifact: its kind and content hash
+/// Displayed and parsed as `<kind>-<64 hex digits>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArtifactRef {
+    pub kind: ArtifactKind,
+    /// BLAKE2b-256 of the serialized envelope
+    pub hash: [u8; 32],
+}
+
+impl ArtifactRef {
+    /// Reference of `bytes` as an artifact of `kind`
+    pub fn of(kind: ArtifactKind, bytes: &[u8]) -> Self {
+        let digest = blake2b_simd::Params::new().hash_length(32).hash(bytes);
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(digest.as_bytes());
+        Self { kind, hash }
+    }
+}
+
+impl fmt::Display for ArtifactRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-", self.kind.prefix())?;
+        for byte in &self.hash {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ArtifactRef {
+    type Err = PoneglyphError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PoneglyphError::InvalidInput(format!("invalid artifact reference: {}", s));
+        let (prefix, hex) = s.split_once('-').ok_or_else(invalid)?;
+        let kind = [ArtifactKind::Proof, ArtifactKind::VerifyingKey]
+            .into_iter()
+            .find(|k| k.prefix() == prefix)
+            .ok_or_else(invalid)?;
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut hash = [0u8; 32];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self { kind, hash })
+    }
+}
+
+/// Proof and key store over a storage backend
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let store = ArtifactStore::new(FileStorage::open("artifacts")?);
+/// let proof_ref = store.put_proof(&proof)?;
+/// let vk_ref = store.put_verifying_key(prover.vk(), k)?;
+/// // In the verification service, given the two references:
+/// let proof = store.get_proof(&proof_ref)?.unwrap();
+/// let vk = store.get_verifying_key(&vk_ref, &shape)?.unwrap();
+/// ```
+pub struct ArtifactStore<B: StorageBackend> {
+    backend: B,
+    opened: Instant,
+    /// Last put or get of each artifact since opening
+    last_used: Mutex<HashMap<ArtifactRef, Instant>>,
+}
+
+impl<B: StorageBackend> ArtifactStore<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            opened: Instant::now(),
+            last_used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Store serialized artifact bytes
+    pub fn put(&self, kind: ArtifactKind, bytes: &[u8]) -> PoneglyphResult<ArtifactRef> {
+        let reference = ArtifactRef::of(kind, bytes);
+        let key = reference.to_string();
+        if self.backend.read(&key)?.is_none() {
+            self.backend.write(&key, bytes)?;
+        }
+        self.touch(reference);
+        Ok(reference)
+    }
+
+    /// Bytes of an artifact (`None` if it is not stored)
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if the stored bytes don't hash to the reference
+    pub fn get(&self, reference: &ArtifactRef) -> PoneglyphResult<Option<Vec<u8>>> {
+        let Some(bytes) = self.backend.read(&reference.to_string())? else {
+            return Ok(None);
+        };
+        if ArtifactRef::of(reference.kind, &bytes) != *reference {
+            return Err(PoneglyphError::Validation(format!(
+                "artifact {} does not match its content",
+                reference
+            )));
+        }
+        self.touch(*reference);
+        Ok(Some(bytes))
+    }
+
+    pub fn put_proof(&self, proof: &Proof) -> PoneglyphResult<ArtifactRef> {
+        self.put(ArtifactKind::Proof, &proof.to_bytes()?)
+    }
+
+    pub fn get_proof(&self, reference: &ArtifactRef) -> PoneglyphResult<Option<Proof>> {
+        expect_kind(reference, ArtifactKind::Proof)?;
+        self.get(reference)?
+            .map(|bytes| Proof::from_bytes(&bytes))
+            .transpose()
+    }
+
+    /// Store a verifying key of size 2^k (its header, see `VerifyingKeyHeader`)
+    pub fn put_verifying_key(
+        &self,
+        vk: &VerifyingKey<EqAffine>,
+        k: u32,
+    ) -> PoneglyphResult<ArtifactRef> {
+        self.put(ArtifactKind::VerifyingKey, &vk.to_bytes(k)?)
+    }
+
+    /// Rebuild a stored verifying key for `circuit`
+    pub fn get_verifying_key(
+        &self,
+        reference: &ArtifactRef,
+        circuit: &PoneglyphCircuit,
+    ) -> PoneglyphResult<Option<VerifyingKey<EqAffine>>> {
+        expect_kind(reference, ArtifactKind::VerifyingKey)?;
+        self.get(reference)?
+            .map(|bytes| VerifyingKey::from_bytes(&bytes, circuit))
+            .transpose()
+    }
+
+    /// References of every stored artifact of `kind`
+    pub fn list(&self, kind: ArtifactKind) -> PoneglyphResult<Vec<ArtifactRef>> {
+        Ok(self
+            .backend
+            .keys()?
+            .iter()
+            .filter_map(|key| key.parse::<ArtifactRef>().ok())
+            .filter(|reference| reference.kind == kind)
+            .collect())
+    }
+
+    /// Remove an artifact; returns false if it was not stored
+    pub fn delete(&self, reference: &ArtifactRef) -> PoneglyphResult<bool> {
+        self.last_used().remove(reference);
+        self.backend.delete(&reference.to_string())
+    }
+
+    /// Delete artifacts the policy doesn't keep (sizes are the stored bytes)
+    pub fn collect_garbage(&self, policy: &GcPolicy) -> PoneglyphResult<GcReport> {
+        let mut references = self.list(ArtifactKind::Proof)?;
+        references.extend(self.list(ArtifactKind::VerifyingKey)?);
+        let mut entries = Vec::with_capacity(references.len());
+        for reference in &references {
+            let bytes = self
+                .backend
+                .read(&reference.to_string())?
+                .map_or(0, |b| b.len() as u64);
+            let last_used = self.last_used().get(reference).copied();
+            entries.push((last_used.unwrap_or(self.opened), bytes));
+        }
+
+        let dropped = policy.select(&entries, Instant::now());
+        for &i in &dropped {
+            self.delete(&references[i])?;
+        }
+        let sizes: Vec<u64> = entries.iter().map(|&(_, bytes)| bytes).collect();
+        Ok(GcReport::of(&sizes, &dropped))
+    }
+
+    fn touch(&self, reference: ArtifactRef) {
+        self.last_used().insert(reference, Instant::now());
+    }
+
+    fn last_used(&self) -> std::sync::MutexGuard<'_, HashMap<ArtifactRef, Instant>> {
+        self.last_used.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<B: StorageBackend> Collectable for ArtifactStore<B> {
+    fn collect_garbage(&mut self, policy: &GcPolicy) -> PoneglyphResult<GcReport> {
+        ArtifactStore::collect_garbage(self, policy)
+    }
+}
+
+fn expect_kind(reference: &ArtifactRef, kind: ArtifactKind) -> PoneglyphResult<()> {
+    if reference.kind != kind {
+        return Err(PoneglyphError::InvalidInput(format!(
+            "{} is not a {} reference",
+            reference,
+            kind.prefix()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::MemoryStorage;
+
+    fn proof(bytes: Vec<u8>) -> Proof {
+        Proof {
+            k: 10,
+            circuit_version: 7,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_content_addressing() {
+        let store = ArtifactStore::new(MemoryStorage::new());
+        let reference = store.put_proof(&proof(vec![1, 2, 3])).unwrap();
+        assert_eq!(store.put_proof(&proof(vec![1, 2, 3])).unwrap(), reference);
+        assert_ne!(store.put_proof(&proof(vec![4])).unwrap(), reference);
+        assert_eq!(store.list(ArtifactKind::Proof).unwrap().len(), 2);
+
+        let parsed: ArtifactRef = reference.to_string().parse().unwrap();
+        assert_eq!(parsed, reference);
+        assert_eq!(
+            store.get_proof(&parsed).unwrap(),
+            Some(proof(vec![1, 2, 3]))
+        );
+        assert!("proof-00".parse::<ArtifactRef>().is_err());
+
+        let missing = ArtifactRef::of(ArtifactKind::Proof, b"missing");
+        assert_eq!(store.get_proof(&missing).unwrap(), None);
+        let wrong_kind = ArtifactRef {
+            kind: ArtifactKind::VerifyingKey,
+            ..reference
+        };
+        assert!(store.get_proof(&wrong_kind).is_err());
+    }
+
+    #[test]
+    fn test_tampered_artifact() {
+        let store = ArtifactStore::new(MemoryStorage::new());
+        let reference = store.put_proof(&proof(vec![1, 2, 3])).unwrap();
+        let tampered = proof(vec![9, 9, 9]).to_bytes().unwrap();
+        store
+            .backend()
+            .write(&reference.to_string(), &tampered)
+            .unwrap();
+        assert!(matches!(
+            store.get(&reference),
+            Err(PoneglyphError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_artifact_gc() {
+        let store = ArtifactStore::new(MemoryStorage::new());
+        let old = store.put_proof(&proof(vec![0; 100])).unwrap();
+        let new = store.put_proof(&proof(vec![0; 10])).unwrap();
+        let size = |r: &ArtifactRef| store.get(r).unwrap().unwrap().len() as u64;
+        let (old_bytes, new_bytes) = (size(&old), size(&new));
+
+        // Reading the older proof makes the newer one least recently used
+        store.get(&old).unwrap();
+        let report = store
+            .collect_garbage(&GcPolicy::default().with_max_bytes(old_bytes))
+            .unwrap();
+        assert_eq!(report.evicted, 1);
+        assert_eq!(report.freed_bytes, new_bytes);
+        assert_eq!(store.list(ArtifactKind::Proof).unwrap(), vec![old]);
+        assert_eq!(store.get(&new).unwrap(), None);
+    }
+}
@@ -0,0 +1,343 @@
+// EVM export
+// Query proofs as contract calldata, so a proof can gate smart-contract logic
+// ("pay out if the audited revenue query returns at least X").
+//
+// The proofs are IPA over the pasta curves, and the EVM has precompiles for
+// neither: a Solidity verifier would run a 2^k-point multiscalar
+// multiplication in bytecode, far over the block gas limit. So the proof is
+// not verified by the consuming contract. It is exported in a fixed calldata
+// layout that contracts decode and gate on, and its validity is attested by
+// the `IPoneglyphVerifier` the contract trusts: an oracle, a verifier
+// committee or a bridge that runs `verify_calldata` off-chain. The contract
+// itself pins what the proof must be about (circuit version, query hash,
+// database commitment), so an attestation can't be replayed for another query
+// or another table.
+//
+// Calldata: Solidity ABI encoding of
+// `(uint64 circuitVersion, uint32 k, uint256[] instances, bytes proof)`
+//
+// | Word        | Content                                                   |
+// |-------------|-----------------------------------------------------------|
+// | 0           | circuit version (`vk_fingerprint` of the verifying key)   |
+// | 1           | k (circuit size 2^k)                                      |
+// | 2           | offset of `instances` (0x80)                              |
+// | 3           | offset of `proof`                                         |
+// | 4           | n, the number of instance rows                            |
+// | 5 .. 5+n    | instance rows in `INSTANCE_LAYOUT` order, big-endian      |
+// | 5+n         | proof length in bytes                                     |
+// | 6+n ..      | proof transcript, zero-padded to a whole word             |
+//
+// Instance rows are pasta base field elements (below 2^255), so each fits a
+// uint256; the hex encoding of i.json is the same value little-endian.
+
+use ff::PrimeField;
+use pasta_curves::pallas::Base as Fr;
+
+use super::{verify_raw, Proof};
+use crate::circuit::{
+    PublicInputs, INSTANCE_DB_COMMITMENT_ROW, INSTANCE_EXPIRY_ROW, INSTANCE_NONCE_ROW,
+    INSTANCE_QUERY_HASH_ROW, INSTANCE_QUERY_RESULT_ROW, INSTANCE_RESULT_COMMITMENT_ROW,
+};
+use crate::error::{PoneglyphError, PoneglyphResult, VerifyError};
+use crate::utils::bytes_to_hex;
+
+/// ABI word size
+const WORD: usize = 32;
+
+/// Head of the calldata: two static words and two offsets
+const HEAD_WORDS: usize = 4;
+
+/// Proof in the calldata layout contracts decode
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmProof {
+    pub circuit_version: u64,
+    pub k: u32,
+    /// Instance column, rows in `INSTANCE_LAYOUT` order
+    pub instances: Vec<Fr>,
+    /// Transcript bytes (`Proof::bytes`)
+    pub proof: Vec<u8>,
+}
+
+impl EvmProof {
+    /// Calldata fields of a proof and the public inputs it was made for
+    pub fn new(proof: &Proof, public_inputs: &PublicInputs) -> Self {
+        Self {
+            circuit_version: proof.circuit_version,
+            k: proof.k,
+            instances: public_inputs.to_instance().remove(0),
+            proof: proof.bytes.clone(),
+        }
+    }
+
+    /// Public inputs of the instance rows
+    ///
+    /// # Errors
+    ///
+    /// `Serialization` if the rows don't decode
+    pub fn public_inputs(&self) -> PoneglyphResult<PublicInputs> {
+        PublicInputs::from_instance(std::slice::from_ref(&self.instances))
+            .ok_or_else(|| PoneglyphError::Serialization("instances do not decode".to_string()))
+    }
+
+    /// ABI-encoded calldata (see the layout above)
+    pub fn to_calldata(&self) -> Vec<u8> {
+        let n = self.instances.len();
+        let proof_offset = (HEAD_WORDS + 1 + n) * WORD;
+        let mut data = Vec::with_capacity(proof_offset + WORD + padded(self.proof.len()));
+        data.extend_from_slice(&word(self.circuit_version));
+        data.extend_from_slice(&word(self.k as u64));
+        data.extend_from_slice(&word((HEAD_WORDS * WORD) as u64));
+        data.extend_from_slice(&word(proof_offset as u64));
+        data.extend_from_slice(&word(n as u64));
+        for instance in &self.instances {
+            data.extend_from_slice(&field_word(instance));
+        }
+        data.extend_from_slice(&word(self.proof.len() as u64));
+        data.extend_from_slice(&self.proof);
+        data.resize(data.len() + padded(self.proof.len()) - self.proof.len(), 0);
+        data
+    }
+
+    /// Decode `to_calldata`
+    /// Only the canonical encoding is accepted: offsets, padding and field
+    /// elements must be exactly as `to_calldata` writes them.
+    ///
+    /// # Errors
+    ///
+    /// `Serialization` if the bytes are not a canonical encoding
+    pub fn from_calldata(data: &[u8]) -> PoneglyphResult<Self> {
+        let malformed = |what: &str| PoneglyphError::Serialization(format!("calldata: {}", what));
+        let words = |from: usize, count: usize| data.get(from * WORD..(from + count) * WORD);
+        let number = |index: usize| -> PoneglyphResult<u64> {
+            let bytes = words(index, 1).ok_or_else(|| malformed("truncated"))?;
+            if bytes[..WORD - 8].iter().any(|&b| b != 0) {
+                return Err(malformed("number out of range"));
+            }
+            Ok(u64::from_be_bytes(bytes[WORD - 8..].try_into().unwrap()))
+        };
+
+        let circuit_version = number(0)?;
+        let k = u32::try_from(number(1)?).map_err(|_| malformed("k out of range"))?;
+        let n = number(HEAD_WORDS)? as usize;
+        if n > data.len() / WORD {
+            return Err(malformed("truncated"));
+        }
+        if number(2)? != (HEAD_WORDS * WORD) as u64
+            || number(3)? != ((HEAD_WORDS + 1 + n) * WORD) as u64
+        {
+            return Err(malformed("non-canonical offsets"));
+        }
+        let instances = words(HEAD_WORDS + 1, n)
+            .ok_or_else(|| malformed("truncated"))?
+            .chunks(WORD)
+            .map(|bytes| {
+                let mut repr = <Fr as PrimeField>::Repr::default();
+                repr.as_mut().copy_from_slice(bytes);
+                repr.as_mut().reverse();
+                Option::from(Fr::from_repr(repr)).ok_or_else(|| malformed("non-canonical field"))
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+        let proof_len = number(HEAD_WORDS + 1 + n)? as usize;
+        let proof_start = (HEAD_WORDS + 2 + n) * WORD;
+        let proof = data
+            .get(proof_start..proof_start.saturating_add(proof_len))
+            .ok_or_else(|| malformed("truncated"))?
+            .to_vec();
+
+        let decoded = Self {
+            circuit_version,
+            k,
+            instances,
+            proof,
+        };
+        if decoded.to_calldata() != data {
+            return Err(malformed("non-canonical padding or trailing bytes"));
+        }
+        Ok(decoded)
+    }
+}
+
+/// Verify exported calldata against a raw verifying key (vk.bin)
+/// What an attesting oracle runs before vouching for calldata on-chain.
+///
+/// # Errors
+///
+/// `MalformedEnvelope` if the calldata doesn't decode, otherwise as
+/// `verify_raw`
+pub fn verify_calldata(vk_bytes: &[u8], calldata: &[u8]) -> Result<(), VerifyError> {
+    let malformed = |e: PoneglyphError| VerifyError::MalformedEnvelope(e.to_string());
+    let evm = EvmProof::from_calldata(calldata).map_err(malformed)?;
+    let public_inputs = evm.public_inputs().map_err(malformed)?;
+    let proof = Proof {
+        k: evm.k,
+        circuit_version: evm.circuit_version,
+        bytes: evm.proof,
+    };
+    verify_raw(
+        vk_bytes,
+        &public_inputs.to_bytes().map_err(malformed)?,
+        &proof.to_bytes().map_err(malformed)?,
+    )
+}
+
+/// Solidity source of a gate contract for one circuit and query
+/// `PoneglyphGate` pins the circuit version and query hash of `evm`, takes
+/// the attesting verifier and the database commitment at deployment, and
+/// exposes `queryResult(calldata)`, which reverts unless the calldata is an
+/// attested proof of that query over that database. Contracts inherit it or
+/// call it; the result commitment, nonce and expiry rows are decoded for
+/// them too.
+pub fn solidity_gate(evm: &EvmProof) -> PoneglyphResult<String> {
+    let inputs = evm.public_inputs()?;
+    Ok(SOLIDITY_GATE
+        .replace("{circuit_version}", &evm.circuit_version.to_string())
+        .replace("{query_hash}", &field_hex(&inputs.query_hash))
+        .replace(
+            "{db_commitment_row}",
+            &INSTANCE_DB_COMMITMENT_ROW.to_string(),
+        )
+        .replace("{query_result_row}", &INSTANCE_QUERY_RESULT_ROW.to_string())
+        .replace("{nonce_row}", &INSTANCE_NONCE_ROW.to_string())
+        .replace("{expiry_row}", &INSTANCE_EXPIRY_ROW.to_string())
+        .replace("{query_hash_row}", &INSTANCE_QUERY_HASH_ROW.to_string())
+        .replace(
+            "{result_commitment_row}",
+            &INSTANCE_RESULT_COMMITMENT_ROW.to_string(),
+        ))
+}
+
+const SOLIDITY_GATE: &str = r#"// SPDX-License-Identifier: MIT
+// Generated by poneglyphdb: gate for one circuit and query (see prover/evm.rs)
+pragma solidity ^0.8.20;
+
+/// Attests that calldata holds a valid PoneglyphDB proof (IPA over pasta,
+/// verified off-chain, e.g. with `verify_calldata`)
+interface IPoneglyphVerifier {
+    function isValid(bytes calldata proofCalldata) external view returns (bool);
+}
+
+contract PoneglyphGate {
+    uint64 public constant CIRCUIT_VERSION = {circuit_version};
+    uint256 public constant QUERY_HASH = {query_hash};
+
+    uint256 internal constant DB_COMMITMENT_ROW = {db_commitment_row};
+    uint256 internal constant QUERY_RESULT_ROW = {query_result_row};
+    uint256 internal constant NONCE_ROW = {nonce_row};
+    uint256 internal constant EXPIRY_ROW = {expiry_row};
+    uint256 internal constant QUERY_HASH_ROW = {query_hash_row};
+    uint256 internal constant RESULT_COMMITMENT_ROW = {result_commitment_row};
+
+    IPoneglyphVerifier public immutable verifier;
+    uint256 public immutable dbCommitment;
+
+    constructor(IPoneglyphVerifier verifier_, uint256 dbCommitment_) {
+        verifier = verifier_;
+        dbCommitment = dbCommitment_;
+    }
+
+    /// Instance rows of attested calldata for this query and database
+    function instances(bytes calldata proofCalldata) public view returns (uint256[] memory) {
+        (uint64 circuitVersion, , uint256[] memory rows, ) =
+            abi.decode(proofCalldata, (uint64, uint32, uint256[], bytes));
+        require(circuitVersion == CIRCUIT_VERSION, "PoneglyphGate: wrong circuit");
+        require(rows.length > RESULT_COMMITMENT_ROW, "PoneglyphGate: missing instances");
+        require(rows[QUERY_HASH_ROW] == QUERY_HASH, "PoneglyphGate: wrong query");
+        require(rows[DB_COMMITMENT_ROW] == dbCommitment, "PoneglyphGate: wrong database");
+        require(verifier.isValid(proofCalldata), "PoneglyphGate: proof not attested");
+        return rows;
+    }
+
+    /// Query result the attested proof vouches for
+    function queryResult(bytes calldata proofCalldata) public view returns (uint256) {
+        return instances(proofCalldata)[QUERY_RESULT_ROW];
+    }
+}
+"#;
+
+/// Big-endian ABI word of a number
+fn word(value: u64) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    word[WORD - 8..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Big-endian ABI word of a field element (its repr is little-endian)
+fn field_word(value: &Fr) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    word.copy_from_slice(value.to_repr().as_ref());
+    word.reverse();
+    word
+}
+
+/// Field element as a Solidity hex literal
+fn field_hex(value: &Fr) -> String {
+    format!("0x{}", bytes_to_hex(&field_word(value)))
+}
+
+/// Length rounded up to whole words
+fn padded(len: usize) -> usize {
+    len.div_ceil(WORD) * WORD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::query_hash;
+
+    fn evm(proof: Vec<u8>) -> EvmProof {
+        let inputs = PublicInputs::new(Fr::from(7), Fr::from(1234))
+            .with_query_hash(query_hash("SELECT SUM(price) FROM orders"))
+            .with_thresholds(vec![100]);
+        let proof = Proof {
+            k: 12,
+            circuit_version: 0xfeed,
+            bytes: proof,
+        };
+        EvmProof::new(&proof, &inputs)
+    }
+
+    #[test]
+    fn test_calldata_layout() {
+        let evm = evm(vec![0xab; 40]);
+        let data = evm.to_calldata();
+        let n = evm.instances.len();
+        assert_eq!(n, 7);
+        assert_eq!(data.len(), (HEAD_WORDS + 2 + n) * WORD + 64);
+        assert_eq!(data[..WORD], word(0xfeed));
+        assert_eq!(data[2 * WORD..3 * WORD], word(0x80));
+        // Row 1, the query result, as a big-endian uint256
+        let row = (HEAD_WORDS + 1 + INSTANCE_QUERY_RESULT_ROW) * WORD;
+        assert_eq!(data[row..row + WORD], word(1234));
+        assert_eq!(EvmProof::from_calldata(&data).unwrap(), evm);
+    }
+
+    #[test]
+    fn test_malformed_calldata() {
+        let data = evm(vec![1, 2, 3]).to_calldata();
+        assert!(EvmProof::from_calldata(&data[..data.len() - 1]).is_err());
+        let mut trailing = data.clone();
+        trailing.extend_from_slice(&[0; WORD]);
+        assert!(EvmProof::from_calldata(&trailing).is_err());
+        let mut dirty_padding = data.clone();
+        *dirty_padding.last_mut().unwrap() = 1;
+        assert!(EvmProof::from_calldata(&dirty_padding).is_err());
+        let mut wrong_offset = data;
+        wrong_offset[3 * WORD - 1] = 0x60;
+        assert!(EvmProof::from_calldata(&wrong_offset).is_err());
+        assert!(matches!(
+            verify_calldata(b"vk", &[0; 3]),
+            Err(VerifyError::MalformedEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn test_solidity_gate() {
+        let evm = evm(vec![]);
+        let source = solidity_gate(&evm).unwrap();
+        assert!(!source.contains("{circuit_version}") && !source.contains("_row}"));
+        assert!(source.contains("CIRCUIT_VERSION = 65261;"));
+        let hash = field_hex(&query_hash("SELECT SUM(price) FROM orders"));
+        assert!(source.contains(&format!("QUERY_HASH = {};", hash)));
+        assert!(source.contains("QUERY_RESULT_ROW = 1;"));
+    }
+}
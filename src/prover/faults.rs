@@ -0,0 +1,333 @@
+// Error injection for testing
+// Integrators validate their retry and alerting logic by running the real
+// pipeline with a `FaultInjector` that makes chosen stages fail. Injected
+// failures have the same shape as real ones: the stage returns the error it
+// would return on its own (e.g. a failed proof degrades the outcome to
+// unproved, a failed write is a storage error), stalls, or panics like a
+// worker dying mid-proof.
+//
+// - `Session::set_faults`: parse, plan, keygen and prove stages
+// - `FaultyStorage`: store stage (writes of any `StorageBackend`)
+//
+// The injector counts calls per stage and logs every fault it injected, so a
+// test can compare the failures it caused with the retries and alerts seen.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::database::StorageBackend;
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+/// Stage of the proving pipeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PipelineStage {
+    Parse,
+    Plan,
+    Keygen,
+    Prove,
+    Store,
+}
+
+impl PipelineStage {
+    /// Every stage, in pipeline order
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::Parse,
+        PipelineStage::Plan,
+        PipelineStage::Keygen,
+        PipelineStage::Prove,
+        PipelineStage::Store,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelineStage::Parse => "parse",
+            PipelineStage::Plan => "plan",
+            PipelineStage::Keygen => "keygen",
+            PipelineStage::Prove => "prove",
+            PipelineStage::Store => "store",
+        }
+    }
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How an injected fault shows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultKind {
+    /// The stage fails with its usual error
+    Error,
+    /// The stage stalls this long, then runs (overloaded prover, slow disk)
+    Delay(Duration),
+    /// The worker panics (its queue lease expires, see `JobQueue`)
+    Panic,
+}
+
+/// Which calls of a stage a fault hits (calls count from 1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultTrigger {
+    Always,
+    /// The first n calls, later calls succeed (transient outage)
+    First(u64),
+    /// Every nth call (flaky dependency)
+    Every(u64),
+}
+
+impl FaultTrigger {
+    fn fires(&self, call: u64) -> bool {
+        match *self {
+            FaultTrigger::Always => true,
+            FaultTrigger::First(n) => call <= n,
+            FaultTrigger::Every(n) => n > 0 && call.is_multiple_of(n),
+        }
+    }
+}
+
+/// Fault injected into a stage call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InjectedFault {
+    pub stage: PipelineStage,
+    pub kind: FaultKind,
+    /// Call of the stage the fault hit
+    pub call: u64,
+}
+
+impl fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "injected {} fault (call {})", self.stage, self.call)
+    }
+}
+
+/// Error of the stage, as the stage itself reports its failures
+impl From<InjectedFault> for PoneglyphError {
+    fn from(fault: InjectedFault) -> Self {
+        let msg = fault.to_string();
+        match fault.stage {
+            PipelineStage::Parse | PipelineStage::Plan => PoneglyphError::InvalidInput(msg),
+            PipelineStage::Keygen | PipelineStage::Prove => PoneglyphError::Synthesis(msg),
+            PipelineStage::Store => PoneglyphError::Serialization(msg),
+        }
+    }
+}
+
+/// Keygen and proving report failures as plonk errors
+impl From<InjectedFault> for halo2_proofs::plonk::Error {
+    fn from(_: InjectedFault) -> Self {
+        halo2_proofs::plonk::Error::Synthesis
+    }
+}
+
+/// Fault plan shared by the pipeline stages under test
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let faults = Arc::new(
+///     FaultInjector::new()
+///         .inject(PipelineStage::Prove, FaultKind::Error, FaultTrigger::First(2))
+///         .inject(PipelineStage::Store, FaultKind::Error, FaultTrigger::Every(3)),
+/// );
+/// session.set_faults(faults.clone());
+/// let store = ArtifactStore::new(FaultyStorage::new(MemoryStorage::new(), faults.clone()));
+/// run_worker(&queue, &mut session, &store);
+/// assert_eq!(alerts.len(), faults.injected().len());
+/// ```
+#[derive(Debug, Default)]
+pub struct FaultInjector {
+    rules: Vec<(PipelineStage, FaultKind, FaultTrigger)>,
+    state: Mutex<FaultState>,
+}
+
+#[derive(Debug, Default)]
+struct FaultState {
+    calls: HashMap<PipelineStage, u64>,
+    injected: Vec<InjectedFault>,
+}
+
+impl FaultInjector {
+    /// Injector without faults (every stage runs normally)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a fault; the first rule of a stage that fires on a call applies
+    pub fn inject(mut self, stage: PipelineStage, kind: FaultKind, trigger: FaultTrigger) -> Self {
+        self.rules.push((stage, kind, trigger));
+        self
+    }
+
+    /// Count a call of `stage` and apply its fault, if any fires
+    /// Delays return Ok after sleeping.
+    ///
+    /// # Errors
+    ///
+    /// The injected fault for `FaultKind::Error`
+    ///
+    /// # Panics
+    ///
+    /// For `FaultKind::Panic`
+    pub fn check(&self, stage: PipelineStage) -> Result<(), InjectedFault> {
+        let fault = {
+            let mut state = self.state();
+            let call = state.calls.entry(stage).or_insert(0);
+            *call += 1;
+            let call = *call;
+            let fault = self
+                .rules
+                .iter()
+                .find(|(s, _, trigger)| *s == stage && trigger.fires(call))
+                .map(|&(stage, kind, _)| InjectedFault { stage, kind, call });
+            if let Some(fault) = fault {
+                state.injected.push(fault);
+            }
+            fault
+        };
+
+        let Some(fault) = fault else {
+            return Ok(());
+        };
+        match fault.kind {
+            FaultKind::Delay(duration) => {
+                thread::sleep(duration);
+                Ok(())
+            }
+            FaultKind::Error => Err(fault),
+            FaultKind::Panic => panic!("{}", fault),
+        }
+    }
+
+    /// Calls of `stage` so far
+    pub fn calls(&self, stage: PipelineStage) -> u64 {
+        self.state().calls.get(&stage).copied().unwrap_or(0)
+    }
+
+    /// Faults injected so far, in order
+    pub fn injected(&self) -> Vec<InjectedFault> {
+        self.state().injected.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FaultState> {
+        // A panicking worker does not leave the counts inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Storage backend whose writes are the store stage of a `FaultInjector`
+pub struct FaultyStorage<B: StorageBackend> {
+    inner: B,
+    faults: Arc<FaultInjector>,
+}
+
+impl<B: StorageBackend> FaultyStorage<B> {
+    pub fn new(inner: B, faults: Arc<FaultInjector>) -> Self {
+        Self { inner, faults }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+impl<B: StorageBackend> StorageBackend for FaultyStorage<B> {
+    fn write(&self, key: &str, bytes: &[u8]) -> PoneglyphResult<()> {
+        self.faults.check(PipelineStage::Store)?;
+        self.inner.write(key, bytes)
+    }
+
+    fn read(&self, key: &str) -> PoneglyphResult<Option<Vec<u8>>> {
+        self.inner.read(key)
+    }
+
+    fn delete(&self, key: &str) -> PoneglyphResult<bool> {
+        self.inner.delete(key)
+    }
+
+    fn keys(&self) -> PoneglyphResult<Vec<String>> {
+        self.inner.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_triggers() {
+        let faults = FaultInjector::new()
+            .inject(
+                PipelineStage::Parse,
+                FaultKind::Error,
+                FaultTrigger::First(2),
+            )
+            .inject(
+                PipelineStage::Store,
+                FaultKind::Error,
+                FaultTrigger::Every(3),
+            );
+
+        let parse: Vec<bool> = (0..4)
+            .map(|_| faults.check(PipelineStage::Parse).is_err())
+            .collect();
+        assert_eq!(parse, vec![true, true, false, false]);
+        let store: Vec<bool> = (0..6)
+            .map(|_| faults.check(PipelineStage::Store).is_err())
+            .collect();
+        assert_eq!(store, vec![false, false, true, false, false, true]);
+        assert!(faults.check(PipelineStage::Prove).is_ok());
+
+        assert_eq!(faults.calls(PipelineStage::Store), 6);
+        assert_eq!(faults.calls(PipelineStage::Keygen), 0);
+        let injected = faults.injected();
+        assert_eq!(injected.len(), 4);
+        assert_eq!(injected[3].stage, PipelineStage::Store);
+        assert_eq!(injected[3].call, 6);
+    }
+
+    #[test]
+    fn test_fault_shapes() {
+        let fault = |stage| InjectedFault {
+            stage,
+            kind: FaultKind::Error,
+            call: 1,
+        };
+        assert!(matches!(
+            PoneglyphError::from(fault(PipelineStage::Parse)),
+            PoneglyphError::InvalidInput(_)
+        ));
+        assert!(matches!(
+            PoneglyphError::from(fault(PipelineStage::Prove)),
+            PoneglyphError::Synthesis(_)
+        ));
+        assert!(matches!(
+            PoneglyphError::from(fault(PipelineStage::Store)),
+            PoneglyphError::Serialization(_)
+        ));
+    }
+
+    #[test]
+    fn test_delay_and_panic() {
+        let faults = Arc::new(
+            FaultInjector::new()
+                .inject(
+                    PipelineStage::Keygen,
+                    FaultKind::Delay(Duration::from_millis(1)),
+                    FaultTrigger::Always,
+                )
+                .inject(PipelineStage::Prove, FaultKind::Panic, FaultTrigger::Always),
+        );
+        assert!(faults.check(PipelineStage::Keygen).is_ok());
+
+        let worker = Arc::clone(&faults);
+        let result = thread::spawn(move || worker.check(PipelineStage::Prove)).join();
+        assert!(result.is_err());
+        // Still usable after the panic
+        assert_eq!(faults.calls(PipelineStage::Prove), 1);
+        assert_eq!(faults.injected().len(), 2);
+    }
+}
@@ -0,0 +1,230 @@
+// Garbage collection of proving keys and artifacts
+// A proving key at k = 20 takes hundreds of megabytes, and a long-running
+// prover sees many query shapes, so keys and stored artifacts accumulate
+// until something drops them. A `GcPolicy` bounds what is kept:
+//
+// - `max_idle`: drop entries not used for this long
+// - `max_entries` / `max_bytes`: then drop least recently used entries until
+//   both bounds hold
+//
+// `KeyCache` (proving keys in memory; the shape index is kept) and
+// `ArtifactStore` (bytes in the storage backend) implement `Collectable`;
+// `GcTask` runs a policy over them periodically on a background thread.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::PoneglyphResult;
+
+/// What a collection keeps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GcPolicy {
+    /// Drop entries unused for longer than this
+    pub max_idle: Option<Duration>,
+    /// Keep at most this many entries (least recently used go first)
+    pub max_entries: Option<usize>,
+    /// Keep at most this many bytes (least recently used go first)
+    pub max_bytes: Option<u64>,
+}
+
+impl GcPolicy {
+    pub fn with_max_idle(mut self, max_idle: Duration) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Indices of the entries to drop, given each entry's last use and size
+    pub(crate) fn select(&self, entries: &[(Instant, u64)], now: Instant) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by_key(|&i| entries[i].0);
+
+        let mut kept = entries.len();
+        let mut kept_bytes: u64 = entries.iter().map(|e| e.1).sum();
+        let mut dropped = Vec::new();
+        for i in order {
+            let (last_used, bytes) = entries[i];
+            let idle = self
+                .max_idle
+                .is_some_and(|max| now.saturating_duration_since(last_used) > max);
+            let over = self.max_entries.is_some_and(|max| kept > max)
+                || self.max_bytes.is_some_and(|max| kept_bytes > max);
+            if idle || over {
+                dropped.push(i);
+                kept -= 1;
+                kept_bytes -= bytes;
+            }
+        }
+        dropped
+    }
+}
+
+/// Outcome of one collection
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GcReport {
+    pub evicted: usize,
+    pub freed_bytes: u64,
+    pub kept: usize,
+    pub kept_bytes: u64,
+}
+
+impl GcReport {
+    /// Report of a collection over entries of the given sizes
+    pub(crate) fn of(sizes: &[u64], dropped: &[usize]) -> Self {
+        let freed_bytes = dropped.iter().map(|&i| sizes[i]).sum();
+        Self {
+            evicted: dropped.len(),
+            freed_bytes,
+            kept: sizes.len() - dropped.len(),
+            kept_bytes: sizes.iter().sum::<u64>() - freed_bytes,
+        }
+    }
+
+    fn add(&mut self, other: GcReport) {
+        self.evicted += other.evicted;
+        self.freed_bytes += other.freed_bytes;
+        self.kept = other.kept;
+        self.kept_bytes = other.kept_bytes;
+    }
+}
+
+/// Cache or store a `GcPolicy` can be applied to
+pub trait Collectable: Send {
+    fn collect_garbage(&mut self, policy: &GcPolicy) -> PoneglyphResult<GcReport>;
+}
+
+/// Periodic collection on a background thread
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let keys = Arc::new(Mutex::new(KeyCache::new()));
+/// let policy = GcPolicy::default()
+///     .with_max_idle(Duration::from_secs(3600))
+///     .with_max_bytes(8 << 30);
+/// let gc = GcTask::spawn(policy, Duration::from_secs(60), vec![keys.clone() as _]);
+/// // ...
+/// let freed = gc.stop();
+/// ```
+pub struct GcTask {
+    stop: Arc<AtomicBool>,
+    totals: Arc<Mutex<GcReport>>,
+    handle: JoinHandle<()>,
+}
+
+impl GcTask {
+    /// Collect every `interval` until stopped
+    /// A failing target is skipped until the next round.
+    pub fn spawn(
+        policy: GcPolicy,
+        interval: Duration,
+        targets: Vec<Arc<Mutex<dyn Collectable>>>,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let totals = Arc::new(Mutex::new(GcReport::default()));
+        let handle = {
+            let (stop, totals) = (stop.clone(), totals.clone());
+            thread::spawn(move || {
+                while !stop.load(Ordering::Acquire) {
+                    for target in &targets {
+                        let report = target
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .collect_garbage(&policy);
+                        if let Ok(report) = report {
+                            totals.lock().unwrap_or_else(|e| e.into_inner()).add(report);
+                        }
+                    }
+                    thread::park_timeout(interval);
+                }
+            })
+        };
+        Self {
+            stop,
+            totals,
+            handle,
+        }
+    }
+
+    /// Evictions so far (kept figures are those of the last target collected)
+    pub fn totals(&self) -> GcReport {
+        *self.totals.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stop after the current round and return the totals
+    pub fn stop(self) -> GcReport {
+        self.stop.store(true, Ordering::Release);
+        self.handle.thread().unpark();
+        let _ = self.handle.join();
+        let totals = *self.totals.lock().unwrap_or_else(|e| e.into_inner());
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_selection() {
+        let now = Instant::now();
+        let ago = |secs| now - Duration::from_secs(secs);
+        // (last used, bytes), oldest is entry 2
+        let entries = [(ago(10), 100), (ago(20), 300), (ago(90), 50), (ago(0), 10)];
+
+        assert!(GcPolicy::default().select(&entries, now).is_empty());
+        let idle = GcPolicy::default().with_max_idle(Duration::from_secs(60));
+        assert_eq!(idle.select(&entries, now), vec![2]);
+        let count = GcPolicy::default().with_max_entries(2);
+        assert_eq!(count.select(&entries, now), vec![2, 1]);
+        // 460 bytes; dropping the two oldest leaves 110
+        let bytes = GcPolicy::default().with_max_bytes(200);
+        let dropped = bytes.select(&entries, now);
+        assert_eq!(dropped, vec![2, 1]);
+        assert_eq!(
+            GcReport::of(&[100, 300, 50, 10], &dropped),
+            GcReport {
+                evicted: 2,
+                freed_bytes: 350,
+                kept: 2,
+                kept_bytes: 110,
+            }
+        );
+    }
+
+    struct Counter(usize);
+
+    impl Collectable for Counter {
+        fn collect_garbage(&mut self, _policy: &GcPolicy) -> PoneglyphResult<GcReport> {
+            self.0 += 1;
+            Ok(GcReport {
+                evicted: 1,
+                ..GcReport::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_gc_task() {
+        let counter = Arc::new(Mutex::new(Counter(0)));
+        let target: Arc<Mutex<dyn Collectable>> = counter.clone();
+        let task = GcTask::spawn(GcPolicy::default(), Duration::from_millis(1), vec![target]);
+        while counter.lock().unwrap().0 < 3 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let totals = task.stop();
+        assert!(totals.evicted >= 3);
+        assert_eq!(totals.evicted, counter.lock().unwrap().0);
+    }
+}
@@ -0,0 +1,682 @@
+// Key reuse across query families
+// Paper Section 5: Proving/verifying keys depend only on the circuit's fixed
+// columns, enabled selectors and copy constraints, not on witnesses.
+//
+// Circuits are fingerprinted by running the floor planner against a recording
+// `Assignment` (like `CircuitStats::measure`), then keys are reused for every
+// circuit with the same fingerprint.
+//
+// Note: halo2_proofs 0.3 computes fixed commitments and permutation products
+// inside keygen_vk / keygen_pk and does not expose them, so circuits that
+// differ in a few fixed constants (e.g. thresholds) cannot share the
+// unchanged parts of a key. Such circuits are reported as structural misses
+// (same selectors and copies, different constants): moving those constants
+// out of fixed columns (`ThresholdMode::Instance`) lets the whole family
+// share one key.
+//
+// The cache index can be saved and loaded; shapes are hashed with the std
+// `DefaultHasher`, so an index is only reused by builds of the same toolchain
+// (records of another build simply never match).
+
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::Value,
+    pasta::EqAffine,
+    plonk::{
+        keygen_pk, keygen_vk, Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem,
+        Error, Fixed, FloorPlanner, Instance, ProvingKey, Selector,
+    },
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::gc::{Collectable, GcPolicy, GcReport};
+use crate::circuit::ConfigColumns;
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::recursive::vk_fingerprint;
+
+/// Key-relevant fingerprint of a circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CircuitShape {
+    /// Constraint system, enabled selectors and copy constraints
+    pub structure: u64,
+    /// Fixed column values (thresholds, lookup tables, ...)
+    pub constants: u64,
+}
+
+impl CircuitShape {
+    /// Fingerprint a circuit without generating keys
+    pub fn of<C>(circuit: &C) -> Result<Self, Error>
+    where
+        C: Circuit<Fr>,
+        C::Config: ConfigColumns,
+    {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let constants = config.constant_columns();
+
+        let mut recorder = ShapeRecorder::default();
+        C::FloorPlanner::synthesize(&mut recorder, circuit, config, constants)?;
+
+        let mut structure = DefaultHasher::new();
+        format!("{:?}", cs.pinned()).hash(&mut structure);
+        recorder.selectors.hash(&mut structure);
+        recorder.copies.hash(&mut structure);
+
+        let mut constants = DefaultHasher::new();
+        recorder.fixed.hash(&mut constants);
+
+        Ok(Self {
+            structure: structure.finish(),
+            constants: constants.finish(),
+        })
+    }
+}
+
+/// How a key was obtained from the cache
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeySource {
+    /// Same shape as an earlier circuit, no keygen
+    Cached,
+    /// Shape known from a loaded (or evicted) entry: the key was regenerated
+    /// and matches the recorded fingerprint
+    Restored,
+    /// Same structure as an earlier circuit, only fixed constants differ
+    StructuralMiss,
+    /// New structure
+    Generated,
+}
+
+/// Cache hit/miss counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyCacheStats {
+    pub hits: usize,
+    pub restored: usize,
+    pub structural_misses: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+/// Format version of `KeyCache::to_bytes`
+pub const KEY_CACHE_VERSION: u32 = 1;
+
+/// Leading bytes of a serialized key cache
+const KEY_CACHE_MAGIC: &[u8; 4] = b"PGKC";
+
+/// Persisted entry of a `KeyCache`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct KeyRecord {
+    /// Circuit size (2^k rows) of the parameters
+    pub k: u32,
+    /// `CircuitShape::structure`
+    pub structure: u64,
+    /// `CircuitShape::constants`
+    pub constants: u64,
+    /// `vk_fingerprint` of the generated key
+    pub vk_fingerprint: u64,
+    /// Number of lookups of this shape
+    pub uses: u64,
+    /// Cache clock at the last lookup (for least-recently-used eviction)
+    pub last_used: u64,
+}
+
+impl KeyRecord {
+    fn shape(&self) -> CircuitShape {
+        CircuitShape {
+            structure: self.structure,
+            constants: self.constants,
+        }
+    }
+}
+
+#[derive(bincode::Encode, bincode::Decode)]
+struct KeyCacheFile {
+    version: u32,
+    clock: u64,
+    records: Vec<KeyRecord>,
+}
+
+/// Proving key cache keyed by circuit shape and size
+///
+/// Keys are looked up by `CircuitShape` (operators, sizes and fixed
+/// constants) and the circuit size k of the parameters. With a capacity, the
+/// least recently used key is evicted when a new one is generated.
+///
+/// `save` / `load` persist the index (shapes, key fingerprints, usage).
+/// halo2_proofs 0.3 can't serialize proving keys, so the key of a loaded or
+/// evicted shape is regenerated on its next use and checked against the
+/// recorded fingerprint (`KeySource::Restored`); a mismatch (e.g. the circuit
+/// code changed) replaces the stale record.
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let mut cache = KeyCache::load("keys.idx").unwrap_or_default().with_capacity(8);
+/// for circuit in &family {
+///     let (pk, _) = cache.proving_key(&params, circuit)?;
+///     let prover = Prover::from_proving_key(pk.clone());
+/// }
+/// cache.save("keys.idx")?;
+/// ```
+#[derive(Debug, Default)]
+pub struct KeyCache {
+    records: HashMap<(u32, CircuitShape), KeyRecord>,
+    keys: HashMap<(u32, CircuitShape), ProvingKey<EqAffine>>,
+    /// Last use and estimated size of each key in memory (for `GcPolicy`)
+    usage: HashMap<(u32, CircuitShape), (Instant, u64)>,
+    /// Structures generated by this cache (for `KeySource::StructuralMiss`)
+    structures: BTreeSet<(u32, u64)>,
+    capacity: Option<usize>,
+    clock: u64,
+    stats: KeyCacheStats,
+}
+
+impl KeyCache {
+    /// Create an empty cache without a capacity
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `capacity` proving keys in memory (at least one)
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity.max(1));
+        self.evict_except(None);
+        self
+    }
+
+    /// Proving key for a circuit, generated only if no circuit with the same
+    /// shape and size is in memory
+    pub fn proving_key<C>(
+        &mut self,
+        params: &Params<EqAffine>,
+        circuit: &C,
+    ) -> Result<(&ProvingKey<EqAffine>, KeySource), Error>
+    where
+        C: Circuit<Fr>,
+        C::Config: ConfigColumns,
+    {
+        let k = params_size(params);
+        let key = (k, CircuitShape::of(circuit)?);
+        self.clock += 1;
+
+        let source = match self.keys.entry(key) {
+            Entry::Occupied(_) => {
+                self.stats.hits += 1;
+                KeySource::Cached
+            }
+            Entry::Vacant(entry) => {
+                let vk = keygen_vk(params, circuit)?;
+                let vk_fingerprint = vk_fingerprint(&vk);
+                let pk = keygen_pk(params, vk, circuit)?;
+                entry.insert(pk);
+                self.usage
+                    .insert(key, (Instant::now(), estimated_key_bytes::<C>(k)));
+
+                let recorded = self.records.get(&key).map(|r| r.vk_fingerprint);
+                let generated = self.structures.insert((k, key.1.structure));
+                let source = if recorded == Some(vk_fingerprint) {
+                    self.stats.restored += 1;
+                    KeySource::Restored
+                } else if generated {
+                    self.stats.misses += 1;
+                    KeySource::Generated
+                } else {
+                    self.stats.structural_misses += 1;
+                    KeySource::StructuralMiss
+                };
+                if recorded != Some(vk_fingerprint) {
+                    self.records.insert(
+                        key,
+                        KeyRecord {
+                            k,
+                            structure: key.1.structure,
+                            constants: key.1.constants,
+                            vk_fingerprint,
+                            uses: 0,
+                            last_used: 0,
+                        },
+                    );
+                }
+                source
+            }
+        };
+
+        if let Some(record) = self.records.get_mut(&key) {
+            record.uses += 1;
+            record.last_used = self.clock;
+        }
+        if let Some(usage) = self.usage.get_mut(&key) {
+            usage.0 = Instant::now();
+        }
+        self.evict_except(Some(key));
+        Ok((&self.keys[&key], source))
+    }
+
+    /// Number of proving keys in memory
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// True if no key is in memory
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Known shapes, in memory or not, most recently used first
+    pub fn records(&self) -> Vec<KeyRecord> {
+        let mut records: Vec<KeyRecord> = self.records.values().copied().collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.last_used));
+        records
+    }
+
+    /// Hit/miss counters
+    pub fn stats(&self) -> KeyCacheStats {
+        self.stats
+    }
+
+    /// Serialize the index for storage (proving keys are not included)
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        let file = KeyCacheFile {
+            version: KEY_CACHE_VERSION,
+            clock: self.clock,
+            records: self.records(),
+        };
+        let mut bytes = KEY_CACHE_MAGIC.to_vec();
+        bytes.extend(
+            bincode::encode_to_vec(&file, bincode::config::standard())
+                .map_err(|e| PoneglyphError::Serialization(e.to_string()))?,
+        );
+        Ok(bytes)
+    }
+
+    /// Deserialize an index from storage (no key is in memory)
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        let body = bytes
+            .strip_prefix(KEY_CACHE_MAGIC.as_slice())
+            .ok_or_else(|| PoneglyphError::Serialization("not a key cache".to_string()))?;
+        let (file, _): (KeyCacheFile, usize) =
+            bincode::decode_from_slice(body, bincode::config::standard())
+                .map_err(|e| PoneglyphError::Serialization(e.to_string()))?;
+        if file.version != KEY_CACHE_VERSION {
+            return Err(PoneglyphError::Serialization(format!(
+                "key cache version {} is not supported (expected {})",
+                file.version, KEY_CACHE_VERSION
+            )));
+        }
+        let records = file
+            .records
+            .into_iter()
+            .map(|record| ((record.k, record.shape()), record))
+            .collect();
+        Ok(Self {
+            records,
+            clock: file.clock,
+            ..Self::default()
+        })
+    }
+
+    /// Write the index to `path`
+    /// Written next to `path` and renamed into place, so a crash mid-write
+    /// leaves the previous index intact.
+    pub fn save(&self, path: impl AsRef<Path>) -> PoneglyphResult<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, self.to_bytes()?)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| PoneglyphError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Read an index from `path`
+    pub fn load(path: impl AsRef<Path>) -> PoneglyphResult<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .map_err(|e| PoneglyphError::Serialization(format!("{}: {}", path.display(), e)))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Drop least recently used keys beyond the capacity (records are kept)
+    fn evict_except(&mut self, keep: Option<(u32, CircuitShape)>) {
+        let Some(capacity) = self.capacity else {
+            return;
+        };
+        while self.keys.len() > capacity {
+            let oldest = self
+                .keys
+                .keys()
+                .filter(|&&key| Some(key) != keep)
+                .min_by_key(|key| self.records.get(*key).map_or(0, |r| r.last_used))
+                .copied();
+            let Some(oldest) = oldest else {
+                return;
+            };
+            self.keys.remove(&oldest);
+            self.usage.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+
+    /// Estimated bytes of the proving keys in memory
+    pub fn memory_bytes(&self) -> u64 {
+        self.usage.values().map(|&(_, bytes)| bytes).sum()
+    }
+}
+
+impl Collectable for KeyCache {
+    /// Drop proving keys from memory (their records stay, so a later use
+    /// regenerates and checks them like an evicted key)
+    fn collect_garbage(&mut self, policy: &GcPolicy) -> PoneglyphResult<GcReport> {
+        let keys: Vec<(u32, CircuitShape)> = self.usage.keys().copied().collect();
+        let entries: Vec<(Instant, u64)> = keys.iter().map(|key| self.usage[key]).collect();
+        let dropped = policy.select(&entries, Instant::now());
+        for &i in &dropped {
+            self.keys.remove(&keys[i]);
+            self.usage.remove(&keys[i]);
+            self.stats.evictions += 1;
+        }
+        let sizes: Vec<u64> = entries.iter().map(|&(_, bytes)| bytes).collect();
+        Ok(GcReport::of(&sizes, &dropped))
+    }
+}
+
+/// Estimated in-memory size of a proving key of `C` at size 2^k
+/// The key holds every fixed and permutation polynomial in Lagrange and
+/// coefficient form and on the extended domain, plus three extended
+/// Lagrange polynomials (first row, last row, active rows).
+/// Column counts come from the config's allocation; every selector is
+/// counted as a fixed column (an upper bound, halo2 combines simple ones).
+fn estimated_key_bytes<C>(k: u32) -> u64
+where
+    C: Circuit<Fr>,
+    C::Config: ConfigColumns,
+{
+    let mut cs = ConstraintSystem::<Fr>::default();
+    let config = C::configure(&mut cs);
+    let n = 1u64 << k;
+    let extended = n * (cs.degree() as u64 - 1).next_power_of_two();
+    let fixed = config.fixed_columns().len() + config.table_columns().len();
+    let columns = (fixed + config.selectors().len() + config.equality_columns().len()) as u64;
+    32 * (columns * (2 * n + extended) + 3 * extended)
+}
+
+/// Circuit size k of `params`
+/// halo2_proofs 0.3 has no accessor, but `Params::write` starts with k
+/// (little-endian u32); the writer stops after those four bytes.
+fn params_size(params: &Params<EqAffine>) -> u32 {
+    struct Header(Vec<u8>);
+
+    impl io::Write for Header {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let wanted = 4 - self.0.len();
+            if wanted == 0 {
+                return Err(io::Error::other("header complete"));
+            }
+            let taken = wanted.min(buf.len());
+            self.0.extend_from_slice(&buf[..taken]);
+            Ok(taken)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut header = Header(Vec::with_capacity(4));
+    // Fails by design once the header is complete
+    let _ = params.write(&mut header);
+    let mut k = [0u8; 4];
+    k[..header.0.len()].copy_from_slice(&header.0);
+    u32::from_le_bytes(k)
+}
+
+/// Records the key-relevant parts of a synthesis (witnesses are ignored)
+#[derive(Default)]
+struct ShapeRecorder {
+    fixed: BTreeSet<(Column<Fixed>, usize, [u8; 32])>,
+    selectors: BTreeSet<(String, usize)>,
+    copies: BTreeSet<(String, usize, String, usize)>,
+}
+
+impl ShapeRecorder {
+    fn record_fixed(&mut self, column: Column<Fixed>, row: usize, value: Value<Assigned<Fr>>) {
+        value.map(|v| {
+            self.fixed.insert((column, row, v.evaluate().to_repr()));
+        });
+    }
+}
+
+impl Assignment<Fr> for ShapeRecorder {
+    fn enter_region<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _annotation: A,
+        selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.selectors.insert((format!("{:?}", selector), row));
+        Ok(())
+    }
+
+    fn query_instance(&self, _column: Column<Instance>, _row: usize) -> Result<Value<Fr>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        _column: Column<Advice>,
+        _row: usize,
+        _to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.record_fixed(column, row, to().map(|v| v.into()));
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.copies.insert((
+            format!("{:?}", left_column),
+            left_row,
+            format!("{:?}", right_column),
+            right_row,
+        ));
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        to: Value<Assigned<Fr>>,
+    ) -> Result<(), Error> {
+        // Lookup table padding: the start row and value identify the fill
+        self.record_fixed(column, row, to);
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::{PoneglyphCircuit, RangeCheckOp, TableScan, ThresholdMode};
+
+    fn circuit(threshold: u64, value: u64) -> PoneglyphCircuit {
+        circuit_with_mode(threshold, value, ThresholdMode::Fixed)
+    }
+
+    fn circuit_with_mode(threshold: u64, value: u64, mode: ThresholdMode) -> PoneglyphCircuit {
+        PoneglyphCircuit {
+            scan: TableScan::default(),
+            query_result: Value::known(Fr::from(2)),
+            nonce: Value::known(Fr::from(0)),
+            expiry: Value::known(Fr::from(0)),
+            query_hash: Fr::from(0),
+            result_commitment: Value::known(Fr::from(0)),
+            result_columns: vec![],
+            result_predicate: None,
+            threshold_mode: mode,
+            range_checks: vec![RangeCheckOp {
+                value: Value::known(value),
+                threshold,
+                u: u64::MAX,
+            }],
+            sorts: vec![],
+            group_bys: vec![],
+            joins: vec![],
+            aggregations: vec![],
+        }
+    }
+
+    #[test]
+    fn test_shape_ignores_witnesses() {
+        let a = CircuitShape::of(&circuit(10, 3)).unwrap();
+        let b = CircuitShape::of(&circuit(10, 7)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_shape_separates_constants() {
+        let a = CircuitShape::of(&circuit(10, 3)).unwrap();
+        let b = CircuitShape::of(&circuit(20, 3)).unwrap();
+        assert_eq!(a.structure, b.structure);
+        assert_ne!(a.constants, b.constants);
+    }
+
+    #[test]
+    fn test_shape_with_public_thresholds() {
+        let a = CircuitShape::of(&circuit_with_mode(10, 3, ThresholdMode::Instance)).unwrap();
+        let b = CircuitShape::of(&circuit_with_mode(20, 3, ThresholdMode::Instance)).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_key_cache_reuse() {
+        let params = Params::<EqAffine>::new(10);
+        let mut cache = KeyCache::new();
+
+        let (_, source) = cache.proving_key(&params, &circuit(10, 3)).unwrap();
+        assert_eq!(source, KeySource::Generated);
+        let (_, source) = cache.proving_key(&params, &circuit(10, 7)).unwrap();
+        assert_eq!(source, KeySource::Cached);
+        let (_, source) = cache.proving_key(&params, &circuit(20, 7)).unwrap();
+        assert_eq!(source, KeySource::StructuralMiss);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.stats(),
+            KeyCacheStats {
+                hits: 1,
+                structural_misses: 1,
+                misses: 1,
+                ..KeyCacheStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_key_cache_eviction_and_persistence() {
+        let params = Params::<EqAffine>::new(10);
+        assert_eq!(params_size(&params), 10);
+        let mut cache = KeyCache::new().with_capacity(1);
+
+        cache.proving_key(&params, &circuit(10, 3)).unwrap();
+        cache.proving_key(&params, &circuit(20, 3)).unwrap();
+        assert_eq!((cache.len(), cache.stats().evictions), (1, 1));
+        let (_, source) = cache.proving_key(&params, &circuit(10, 5)).unwrap();
+        assert_eq!(source, KeySource::Restored);
+        assert_eq!(cache.records()[0].uses, 2);
+
+        // A loaded index knows the shapes, keys are regenerated and checked
+        let mut loaded = KeyCache::from_bytes(&cache.to_bytes().unwrap()).unwrap();
+        assert_eq!(loaded.records().len(), 2);
+        assert!(loaded.is_empty());
+        let (_, source) = loaded.proving_key(&params, &circuit(20, 1)).unwrap();
+        assert_eq!(source, KeySource::Restored);
+        assert!(KeyCache::from_bytes(b"PGAC").is_err());
+    }
+
+    #[test]
+    fn test_key_cache_gc() {
+        let params = Params::<EqAffine>::new(10);
+        let mut cache = KeyCache::new();
+        cache.proving_key(&params, &circuit(10, 3)).unwrap();
+        cache.proving_key(&params, &circuit(20, 3)).unwrap();
+        let bytes = cache.memory_bytes();
+        assert!(bytes > 0);
+
+        // Only the most recently used key fits
+        let report = cache
+            .collect_garbage(&GcPolicy::default().with_max_bytes(bytes - 1))
+            .unwrap();
+        assert_eq!((report.evicted, report.kept), (1, 1));
+        assert_eq!(report.freed_bytes + report.kept_bytes, bytes);
+        assert_eq!(cache.len(), 1);
+        let (_, source) = cache.proving_key(&params, &circuit(20, 5)).unwrap();
+        assert_eq!(source, KeySource::Cached);
+
+        // Records survive, so the dropped key is restored
+        let report = cache
+            .collect_garbage(&GcPolicy::default().with_max_idle(std::time::Duration::ZERO))
+            .unwrap();
+        assert_eq!(report.kept, 0);
+        assert!(cache.is_empty());
+        let (_, source) = cache.proving_key(&params, &circuit(10, 5)).unwrap();
+        assert_eq!(source, KeySource::Restored);
+    }
+}
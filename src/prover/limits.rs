@@ -0,0 +1,241 @@
+// Concurrency limits per pipeline stage
+// Keygen, witness generation and proving have very different memory
+// profiles: a keygen holds the full proving key of a 2^k circuit while it is
+// built, a witness generation holds the table data of one query, and a
+// proof holds the key plus its polynomial evaluations. A single global limit
+// has to be sized for the worst of them, so each stage gets its own.
+//
+// `ProverConfig` sets the limits; `StageLimits` enforces them and is shared
+// (cloned) by every worker of a process, e.g. through `Session::set_limits`.
+
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+/// Concurrent keygens unless configured otherwise
+pub const DEFAULT_MAX_KEYGENS: usize = 1;
+
+/// Concurrent witness generations unless configured otherwise
+pub const DEFAULT_MAX_WITNESS_GENERATIONS: usize = 4;
+
+/// Concurrent proofs unless configured otherwise
+pub const DEFAULT_MAX_PROVES: usize = 2;
+
+/// Concurrency limits of the proving pipeline (each at least 1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProverConfig {
+    pub max_keygens: usize,
+    pub max_witness_generations: usize,
+    pub max_proves: usize,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            max_keygens: DEFAULT_MAX_KEYGENS,
+            max_witness_generations: DEFAULT_MAX_WITNESS_GENERATIONS,
+            max_proves: DEFAULT_MAX_PROVES,
+        }
+    }
+}
+
+impl ProverConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_keygens(mut self, max: usize) -> Self {
+        self.max_keygens = max;
+        self
+    }
+
+    pub fn with_max_witness_generations(mut self, max: usize) -> Self {
+        self.max_witness_generations = max;
+        self
+    }
+
+    pub fn with_max_proves(mut self, max: usize) -> Self {
+        self.max_proves = max;
+        self
+    }
+}
+
+/// Shared enforcement of a `ProverConfig`
+/// Clones share the same limits.
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let limits = StageLimits::new(ProverConfig::new().with_max_proves(1))?;
+/// for _ in 0..workers {
+///     let mut session = Session::new(12);
+///     session.set_limits(limits.clone());
+///     spawn_worker(session);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StageLimits {
+    config: ProverConfig,
+    keygens: Arc<Semaphore>,
+    witness_generations: Arc<Semaphore>,
+    proves: Arc<Semaphore>,
+}
+
+impl StageLimits {
+    /// # Errors
+    ///
+    /// `Configuration` if a limit is 0 (the stage could never run)
+    pub fn new(config: ProverConfig) -> PoneglyphResult<Self> {
+        for (stage, max) in [
+            ("keygens", config.max_keygens),
+            ("witness generations", config.max_witness_generations),
+            ("proves", config.max_proves),
+        ] {
+            if max == 0 {
+                return Err(PoneglyphError::Configuration(format!(
+                    "at least one concurrent {} must be allowed",
+                    stage
+                )));
+            }
+        }
+        Ok(Self {
+            config,
+            keygens: Arc::new(Semaphore::new(config.max_keygens)),
+            witness_generations: Arc::new(Semaphore::new(config.max_witness_generations)),
+            proves: Arc::new(Semaphore::new(config.max_proves)),
+        })
+    }
+
+    pub fn config(&self) -> ProverConfig {
+        self.config
+    }
+
+    /// Wait for a keygen slot, held until the permit is dropped
+    pub fn keygen(&self) -> StagePermit {
+        StagePermit::acquire(&self.keygens)
+    }
+
+    /// Wait for a witness generation slot, held until the permit is dropped
+    pub fn witness_generation(&self) -> StagePermit {
+        StagePermit::acquire(&self.witness_generations)
+    }
+
+    /// Wait for a proving slot, held until the permit is dropped
+    pub fn prove(&self) -> StagePermit {
+        StagePermit::acquire(&self.proves)
+    }
+}
+
+/// Slot of a stage; released on drop
+#[must_use = "the slot is released as soon as the permit is dropped"]
+#[derive(Debug)]
+pub struct StagePermit {
+    semaphore: Arc<Semaphore>,
+}
+
+impl StagePermit {
+    fn acquire(semaphore: &Arc<Semaphore>) -> Self {
+        semaphore.acquire();
+        Self {
+            semaphore: Arc::clone(semaphore),
+        }
+    }
+}
+
+impl Drop for StagePermit {
+    fn drop(&mut self) {
+        self.semaphore.release();
+    }
+}
+
+#[derive(Debug)]
+struct Semaphore {
+    limit: usize,
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Semaphore {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            active: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) {
+        let mut active = self.active();
+        while *active >= self.limit {
+            active = self
+                .released
+                .wait(active)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *active += 1;
+    }
+
+    fn release(&self) {
+        *self.active() -= 1;
+        self.released.notify_one();
+    }
+
+    fn active(&self) -> std::sync::MutexGuard<'_, usize> {
+        // A worker panicking while holding a permit still releases it on unwind
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    /// Most permits of one stage held at the same time by `threads` workers
+    fn peak(threads: usize, permit: impl Fn() -> StagePermit + Sync) -> usize {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    let _permit = permit();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(5));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        peak.into_inner()
+    }
+
+    #[test]
+    fn test_stage_limits() {
+        let limits = StageLimits::new(
+            ProverConfig::new()
+                .with_max_keygens(1)
+                .with_max_witness_generations(3)
+                .with_max_proves(2),
+        )
+        .unwrap();
+
+        assert_eq!(peak(6, || limits.keygen()), 1);
+        assert!(peak(6, || limits.witness_generation()) <= 3);
+        assert!(peak(6, || limits.prove()) <= 2);
+
+        // Stages are independent: a held keygen slot doesn't block proving
+        let _keygen = limits.keygen();
+        let _prove = limits.clone().prove();
+    }
+
+    #[test]
+    fn test_zero_limit_rejected() {
+        assert!(StageLimits::new(ProverConfig::default()).is_ok());
+        assert!(matches!(
+            StageLimits::new(ProverConfig::new().with_max_proves(0)),
+            Err(PoneglyphError::Configuration(_))
+        ));
+    }
+}
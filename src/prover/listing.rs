@@ -0,0 +1,205 @@
+// Catalog listing for data discovery
+// What a session can query and prove, as data: every registered table with
+// its version, its commitment (the value a proof's instance row 0 must hold),
+// its columns and simple statistics. Downstream tools (catalog browsers, query
+// builders, verifiers choosing which commitments to trust) read the JSON form
+// instead of linking against the crate.
+//
+// Statistics are computed from the registered rows when the listing is made:
+// exact row counts, per-column minimum, maximum and number of distinct raw
+// values. Decimal columns report raw (scaled) values; `scale` tells how to
+// read them.
+//
+// Views are not listed: the catalog has none (a WITH clause lives in its
+// query), so tables are everything a query can name.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{field_to_hex, Session};
+use crate::database::DatabaseTable;
+
+/// Version of the listing's JSON layout
+pub const CATALOG_LISTING_VERSION: u32 = 1;
+
+/// Statistics of one column's raw values
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStatistics {
+    /// None for an empty table
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub distinct: u64,
+}
+
+/// One column of a listed table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnListing {
+    pub name: String,
+    /// Fixed-point scale (0 for integer columns)
+    pub scale: u32,
+    pub statistics: ColumnStatistics,
+}
+
+/// One registered table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableListing {
+    pub name: String,
+    /// Session version (1 on first registration, bumped on every replacement)
+    pub version: u64,
+    /// `DatabaseTable::commit`, hex (canonical little-endian repr)
+    pub commitment: String,
+    pub rows: u64,
+    pub columns: Vec<ColumnListing>,
+}
+
+impl TableListing {
+    pub fn of(table: &DatabaseTable, version: u64) -> Self {
+        let columns = table
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let values = || table.data.iter().filter_map(|row| row.get(i).copied());
+                ColumnListing {
+                    name: name.clone(),
+                    scale: table.scales.get(i).copied().unwrap_or(0),
+                    statistics: ColumnStatistics {
+                        min: values().min(),
+                        max: values().max(),
+                        distinct: values().collect::<HashSet<_>>().len() as u64,
+                    },
+                }
+            })
+            .collect();
+        Self {
+            name: table.name.to_lowercase(),
+            version,
+            commitment: field_to_hex(&table.commit().commitment()),
+            rows: table.data.len() as u64,
+            columns,
+        }
+    }
+}
+
+/// Machine-readable listing of a session's catalog
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogListing {
+    pub version: u32,
+    /// Sorted by name
+    pub tables: Vec<TableListing>,
+}
+
+impl CatalogListing {
+    /// Listing of `tables`, sorted by name
+    pub fn of(tables: impl IntoIterator<Item = TableListing>) -> Self {
+        let mut tables: Vec<TableListing> = tables.into_iter().collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            version: CATALOG_LISTING_VERSION,
+            tables,
+        }
+    }
+
+    /// Listed table by name (any case)
+    pub fn table(&self, name: &str) -> Option<&TableListing> {
+        let name = name.to_lowercase();
+        self.tables.iter().find(|t| t.name == name)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let listing: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if listing.version != CATALOG_LISTING_VERSION {
+            return Err(format!(
+                "catalog listing version {} is not supported (expected {})",
+                listing.version, CATALOG_LISTING_VERSION
+            ));
+        }
+        Ok(listing)
+    }
+}
+
+impl Session {
+    /// Listing of every registered table
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let listing = session.catalog_listing();
+    /// std::fs::write("catalog.json", listing.to_json()?)?;
+    /// ```
+    pub fn catalog_listing(&self) -> CatalogListing {
+        CatalogListing::of(
+            self.registered_tables()
+                .map(|(table, version)| TableListing::of(table, version)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders(prices: &[u64]) -> DatabaseTable {
+        let mut table = DatabaseTable::new(
+            "Orders".to_string(),
+            vec!["id".to_string(), "price".to_string()],
+        );
+        table.set_scale("price", 2);
+        for (i, &price) in prices.iter().enumerate() {
+            table.insert(vec![i as u64 + 1, price]);
+        }
+        table
+    }
+
+    #[test]
+    fn test_catalog_listing() {
+        let mut session = Session::new(4);
+        session.register_table(orders(&[300, 100, 300]));
+        session.register_table(DatabaseTable::new(
+            "empty".to_string(),
+            vec!["id".to_string()],
+        ));
+        session.register_table(orders(&[300, 100, 300, 50]));
+
+        let listing = session.catalog_listing();
+        let names: Vec<_> = listing.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["empty", "orders"]);
+
+        let listed = listing.table("ORDERS").unwrap();
+        assert_eq!((listed.version, listed.rows), (2, 4));
+        assert_eq!(
+            listed.commitment,
+            field_to_hex(&orders(&[300, 100, 300, 50]).commit().commitment())
+        );
+        assert_eq!(listed.columns[1].scale, 2);
+        assert_eq!(
+            listed.columns[1].statistics,
+            ColumnStatistics {
+                min: Some(50),
+                max: Some(300),
+                distinct: 3,
+            }
+        );
+        let empty = listing.table("empty").unwrap();
+        assert_eq!(
+            empty.columns[0].statistics,
+            ColumnStatistics {
+                min: None,
+                max: None,
+                distinct: 0,
+            }
+        );
+
+        let json = listing.to_json().unwrap();
+        assert_eq!(CatalogListing::from_json(&json).unwrap(), listing);
+        assert!(
+            CatalogListing::from_json(&json.replacen("\"version\": 1", "\"version\": 9", 1))
+                .is_err()
+        );
+    }
+}
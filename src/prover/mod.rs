@@ -20,9 +20,100 @@ use halo2_proofs::{
     transcript::{Blake2bRead, Blake2bWrite, Challenge255},
 };
 use pasta_curves::pallas::Base as Fr;
-use rand::rngs::OsRng;
+use rand_core::OsRng;
 
-use crate::circuit::PoneglyphCircuit;
+use crate::circuit::{
+    ExpiryBound, PoneglyphCircuit, PublicInputs, ResultPredicate, INSTANCE_DB_COMMITMENT_ROW,
+    INSTANCE_NONCE_ROW, INSTANCE_QUERY_HASH_ROW, INSTANCE_QUERY_RESULT_ROW,
+    INSTANCE_RESULT_COMMITMENT_ROW, PRIVATE_QUERY_FINGERPRINT_ROW, PRIVATE_QUERY_REGISTRY_ROOT_ROW,
+};
+use crate::error::{PoneglyphError, PoneglyphResult, VerifyError};
+use crate::recursive::vk_fingerprint;
+use crate::sql::{query_hash, QueryResult};
+
+pub mod access;
+pub mod artifacts;
+pub mod evm;
+pub mod faults;
+pub mod gc;
+pub mod keys;
+pub mod limits;
+pub mod listing;
+pub mod precheck;
+pub mod preset;
+pub mod profile;
+pub mod queue;
+pub mod raw;
+pub mod sensitivity;
+pub mod serialization;
+pub mod session;
+pub mod soundness;
+pub mod stats;
+pub mod vectors;
+pub use access::*;
+pub use artifacts::*;
+pub use evm::*;
+pub use faults::*;
+pub use gc::*;
+pub use keys::*;
+pub use limits::*;
+pub use listing::*;
+pub use precheck::*;
+pub use preset::*;
+pub use profile::*;
+pub use queue::*;
+pub use raw::*;
+pub use sensitivity::*;
+pub use serialization::*;
+pub use session::*;
+pub use soundness::*;
+pub use stats::*;
+pub use vectors::*;
+
+/// Derive the instance nonce for a request id
+/// Paper Section 5.1: Nonce is a public input (instance row 2)
+///
+/// The request id bytes are packed little-endian into one field element,
+/// so distinct ids (up to 31 bytes) always give distinct nonces.
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let nonce = request_nonce(b"req-42")?;
+/// circuit.nonce = Value::known(nonce);
+/// ```
+pub fn request_nonce(request_id: &[u8]) -> PoneglyphResult<Fr> {
+    use ff::PrimeField;
+
+    if request_id.len() > 31 {
+        return Err(PoneglyphError::InvalidInput(format!(
+            "request id is {} bytes, at most 31 bytes fit in a nonce",
+            request_id.len()
+        )));
+    }
+    let mut repr = <Fr as PrimeField>::Repr::default();
+    repr.as_mut()[..request_id.len()].copy_from_slice(request_id);
+    // Length byte keeps ids with trailing zero bytes distinct
+    repr.as_mut()[31] = request_id.len() as u8;
+    Option::from(Fr::from_repr(repr))
+        .ok_or_else(|| PoneglyphError::InvalidInput("request id is not a valid nonce".to_string()))
+}
+
+/// Transferable proof
+/// IPA parameters need no trusted setup and are derived from `k` alone, so
+/// the proof, the verifying key and the public inputs are all a verifier in
+/// another process needs (see `Verifier::verify_proof`, and `to_bytes` for
+/// the wire format)
+#[derive(Clone, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+#[cfg_attr(feature = "proof-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Proof {
+    /// Circuit size (2^k rows)
+    pub k: u32,
+    /// Circuit version the proof was made for (`vk_fingerprint`)
+    pub circuit_version: u64,
+    /// Transcript bytes (Blake2b, IPA on pasta curves)
+    pub bytes: Vec<u8>,
+}
 
 /// Prover
 /// Paper Section 5: Non-interactive ZKP proof generation
@@ -31,6 +122,8 @@ use crate::circuit::PoneglyphCircuit;
 pub struct Prover {
     /// Proving key
     pk: ProvingKey<EqAffine>,
+    /// Circuit size of keys from `setup` (None if the caller holds the parameters)
+    k: Option<u32>,
 }
 
 impl Prover {
@@ -45,7 +138,69 @@ impl Prover {
         // Create proving key
         let pk = keygen_pk(params, vk, circuit)?;
 
-        Ok(Self { pk })
+        Ok(Self { pk, k: None })
+    }
+
+    /// Create prover from an existing proving key (e.g. from `KeyCache`)
+    pub fn from_proving_key(pk: ProvingKey<EqAffine>) -> Self {
+        Self { pk, k: None }
+    }
+
+    /// Generate keys for circuits shaped like `circuit` at size 2^k
+    /// The parameters are derived from `k` (IPA has no trusted setup)
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let (pk, vk) = Prover::setup(12, &circuit.without_witnesses())?;
+    /// let proof = Prover::from_setup(12, pk).create_proof(&circuit, &inputs)?;
+    /// Verifier::verify_proof(&proof, &vk, &inputs)?;
+    /// ```
+    pub fn setup(
+        k: u32,
+        circuit: &PoneglyphCircuit,
+    ) -> Result<(ProvingKey<EqAffine>, VerifyingKey<EqAffine>), Error> {
+        let params = Params::<EqAffine>::new(k);
+        let vk = keygen_vk(&params, circuit)?;
+        let pk = keygen_pk(&params, vk.clone(), circuit)?;
+        Ok((pk, vk))
+    }
+
+    /// Create prover from a proving key of `setup(k, ..)`
+    pub fn from_setup(k: u32, pk: ProvingKey<EqAffine>) -> Self {
+        Self { pk, k: Some(k) }
+    }
+
+    /// Create a transferable proof
+    ///
+    /// # Errors
+    ///
+    /// `Configuration` if the prover was not made by `from_setup`,
+    /// `Synthesis` if proving fails
+    pub fn create_proof(
+        &self,
+        circuit: &PoneglyphCircuit,
+        public_inputs: &PublicInputs,
+    ) -> PoneglyphResult<Proof> {
+        let k = self.k.ok_or_else(|| {
+            PoneglyphError::Configuration(
+                "circuit size unknown, create the prover with Prover::from_setup".to_string(),
+            )
+        })?;
+        let params = Params::<EqAffine>::new(k);
+        let bytes = self
+            .prove(&params, circuit, &public_inputs.to_instance())
+            .map_err(|e| PoneglyphError::Synthesis(format!("{:?}", e)))?;
+        Ok(Proof {
+            k,
+            circuit_version: vk_fingerprint(self.vk()),
+            bytes,
+        })
+    }
+
+    /// Verifying key of the proving key
+    pub fn vk(&self) -> &VerifyingKey<EqAffine> {
+        self.pk.get_vk()
     }
 
     /// Create proof
@@ -75,7 +230,7 @@ impl Prover {
         create_proof(
             params,
             &self.pk,
-            &[circuit.clone()],
+            std::slice::from_ref(circuit),
             &instances_refs,
             OsRng,
             &mut transcript,
@@ -107,33 +262,313 @@ impl Verifier {
         Ok(Self { vk })
     }
 
+    /// Create verifier from an existing verifying key (e.g. `pk.get_vk()`)
+    pub fn from_verifying_key(vk: VerifyingKey<EqAffine>) -> Self {
+        Self { vk }
+    }
+
+    /// Circuit version the verifier accepts: the fingerprint of its key
+    pub fn circuit_version(&self) -> u64 {
+        vk_fingerprint(&self.vk)
+    }
+
+    /// Check that a proof envelope was made for this verifier's circuit
+    pub fn check_circuit_version(&self, found: u64) -> Result<(), VerifyError> {
+        let expected = self.circuit_version();
+        if expected != found {
+            return Err(VerifyError::WrongCircuitVersion { expected, found });
+        }
+        Ok(())
+    }
+
     /// Verify proof
     /// Paper Section 5: Non-interactive proof verification
     ///
     /// Halo2 0.3.1 real API: verify_proof(params, vk, strategy, instances, transcript)
+    ///
+    /// # Errors
+    ///
+    /// `MalformedEnvelope` if the proof bytes can't be read, `InvalidProof`
+    /// if they are read but don't verify
     pub fn verify(
         &self,
         params: &Params<EqAffine>,
         proof: &[u8],
         public_inputs: &[Vec<Fr>],
-    ) -> Result<bool, Error> {
-        // Create transcript (Blake2bRead)
-        let mut transcript = Blake2bRead::<&[u8], EqAffine, Challenge255<EqAffine>>::init(proof);
+    ) -> Result<(), VerifyError> {
+        verify_with_key(params, &self.vk, proof, public_inputs)
+    }
 
-        // Create verification strategy (SingleVerifier)
-        let strategy = SingleVerifier::new(params);
+    /// Verify a transferable proof (see `Prover::create_proof`)
+    /// The parameters are rebuilt from `proof.k`
+    ///
+    /// # Errors
+    ///
+    /// `WrongCircuitVersion` if the proof was made for another key,
+    /// otherwise as `verify`
+    pub fn verify_proof(
+        proof: &Proof,
+        vk: &VerifyingKey<EqAffine>,
+        public_inputs: &PublicInputs,
+    ) -> Result<(), VerifyError> {
+        let expected = vk_fingerprint(vk);
+        if proof.circuit_version != expected {
+            return Err(VerifyError::WrongCircuitVersion {
+                expected,
+                found: proof.circuit_version,
+            });
+        }
+        let params = Params::<EqAffine>::new(proof.k);
+        verify_with_key(&params, vk, &proof.bytes, &public_inputs.to_instance())
+    }
 
-        // Format instances: &[&[&[C::Scalar]]]
-        // public_inputs: &[Vec<Fr>] -> instances: &[&[&[Fr]]]
-        let instances: Vec<Vec<&[Fr]>> =
-            public_inputs.iter().map(|pi| vec![pi.as_slice()]).collect();
-        let instances_refs: Vec<&[&[Fr]]> = instances.iter().map(|inst| inst.as_slice()).collect();
+    /// Verify proof for a specific request
+    /// Replay protection: the proof is only accepted if its instance carries
+    /// the nonce the verifier issued for this request (instance row 2)
+    ///
+    /// # Errors
+    ///
+    /// `InstanceMismatch` if the nonce doesn't match, otherwise as `verify`
+    pub fn verify_for_request(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        expected_nonce: Fr,
+    ) -> Result<(), VerifyError> {
+        let nonce = public_inputs
+            .first()
+            .and_then(|column| column.get(INSTANCE_NONCE_ROW));
+        if nonce != Some(&expected_nonce) {
+            return Err(VerifyError::InstanceMismatch(
+                "nonce does not match the request".to_string(),
+            ));
+        }
 
-        // Verify proof
-        verify_proof(params, &self.vk, strategy, &instances_refs, &mut transcript)?;
+        self.verify(params, proof, public_inputs)
+    }
 
-        Ok(true)
+    /// Verify proof and reject it if it is stale
+    /// The expiry bound is read from the instance (row 3) and compared with
+    /// the verifier's current block height or timestamp
+    ///
+    /// # Errors
+    ///
+    /// `MalformedEnvelope` if the instance doesn't decode, `InstanceMismatch`
+    /// if the proof has expired (or the bound is of another kind than `now`),
+    /// otherwise as `verify`
+    pub fn verify_fresh(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        now: ExpiryBound,
+    ) -> Result<(), VerifyError> {
+        let inputs = PublicInputs::from_instance(public_inputs).ok_or_else(|| {
+            VerifyError::MalformedEnvelope("instance does not decode".to_string())
+        })?;
+        if !inputs.expiry.is_fresh_at(now) {
+            return Err(VerifyError::InstanceMismatch(format!(
+                "proof expired ({:?} at {:?})",
+                inputs.expiry, now
+            )));
+        }
+
+        self.verify(params, proof, public_inputs)
+    }
+
+    /// Verify proof and check that it is about the expected database
+    /// The database commitment is read from the instance (row 0)
+    ///
+    /// # Errors
+    ///
+    /// `CommitmentMismatch` if the instance commits to another database,
+    /// otherwise as `verify`
+    pub fn verify_against_commitment(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        expected: Fr,
+    ) -> Result<(), VerifyError> {
+        let found = public_inputs
+            .first()
+            .and_then(|column| column.get(INSTANCE_DB_COMMITMENT_ROW))
+            .copied()
+            .ok_or_else(|| VerifyError::MalformedEnvelope("empty instance".to_string()))?;
+        if found != expected {
+            return Err(VerifyError::CommitmentMismatch { expected, found });
+        }
+
+        self.verify(params, proof, public_inputs)
+    }
+
+    /// Verify proof and check that it answers the expected query
+    /// The query hash is read from the instance (row 4) and compared with
+    /// `query_hash(sql)`. The circuit fixes row 4 in its verifying key, so a
+    /// key only ever verifies proofs of the query it was generated for
+    ///
+    /// # Errors
+    ///
+    /// `InstanceMismatch` if the proof is about another query, otherwise as
+    /// `verify`
+    pub fn verify_for_query(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        sql: &str,
+    ) -> Result<(), VerifyError> {
+        let found = public_inputs
+            .first()
+            .and_then(|column| column.get(INSTANCE_QUERY_HASH_ROW));
+        if found != Some(&query_hash(sql)) {
+            return Err(VerifyError::InstanceMismatch(
+                "query hash does not match the query".to_string(),
+            ));
+        }
+
+        self.verify(params, proof, public_inputs)
+    }
+
+    /// Verify proof and return the result it vouches for
+    /// `result_bytes` is the result revealed next to the proof
+    /// (`QueryResult::to_bytes`); it must be a single value, matching the
+    /// query result (row 1) and the result commitment (row 5). The circuit
+    /// hashes row 5 from the final aggregation result (see
+    /// `PoneglyphCircuit::result_columns`), so the commitment only vouches
+    /// for a one-value result
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let result = verifier.verify_and_extract(&params, &proof, &instance, &result_bytes)?;
+    /// println!("{:?}", result.rows);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// `MalformedEnvelope` if the bytes don't decode, `InstanceMismatch` if
+    /// they are not a single value or not the committed result, otherwise as
+    /// `verify`
+    pub fn verify_and_extract(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        result_bytes: &[u8],
+    ) -> Result<QueryResult, VerifyError> {
+        let result =
+            QueryResult::from_bytes(result_bytes).map_err(VerifyError::MalformedEnvelope)?;
+        let row = |i: usize| public_inputs.first().and_then(|column| column.get(i));
+        if row(INSTANCE_RESULT_COMMITMENT_ROW) != Some(&result.commitment()) {
+            return Err(VerifyError::InstanceMismatch(
+                "result does not match the result commitment".to_string(),
+            ));
+        }
+        let value = result.bound_value().ok_or_else(|| {
+            VerifyError::InstanceMismatch("only a single-value result is committed".to_string())
+        })?;
+        if row(INSTANCE_QUERY_RESULT_ROW) != Some(&Fr::from(value)) {
+            return Err(VerifyError::InstanceMismatch(
+                "result does not match the query result".to_string(),
+            ));
+        }
+
+        self.verify(params, proof, public_inputs)?;
+        Ok(result)
     }
+
+    /// Verify a selective-disclosure proof (`Session::prove_predicate`) of
+    /// the query `sql` and return whether its predicate holds
+    /// The verifying key fixes the predicate; the result itself is never
+    /// revealed, instance row 1 only holds the outcome. The circuit compares
+    /// the final aggregation result, copied from its cell, with the bound
+    ///
+    /// # Errors
+    ///
+    /// `InstanceMismatch` if the outcome is not 0 or 1, otherwise as
+    /// `verify_for_query`
+    pub fn verify_predicate(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        sql: &str,
+    ) -> Result<bool, VerifyError> {
+        let outcome = public_inputs
+            .first()
+            .and_then(|column| column.get(INSTANCE_QUERY_RESULT_ROW))
+            .copied();
+        let holds = match outcome {
+            Some(value) if value == ResultPredicate::outcome(true) => true,
+            Some(value) if value == ResultPredicate::outcome(false) => false,
+            _ => {
+                return Err(VerifyError::InstanceMismatch(
+                    "predicate outcome is not a boolean".to_string(),
+                ))
+            }
+        };
+
+        self.verify_for_query(params, proof, public_inputs, sql)?;
+        Ok(holds)
+    }
+
+    /// Verify a query-private proof (`PrivateQueryCircuit`)
+    /// The proof shows that some query authorized in the registry with root
+    /// `registry_root` for this verifier's circuit was run; instance row 4
+    /// only holds a salted commitment to it. The verifier must be made from
+    /// the private-query key (`Verifier::from_verifying_key`).
+    ///
+    /// # Errors
+    ///
+    /// `InstanceMismatch` if the instance names another registry or circuit,
+    /// otherwise as `verify`
+    pub fn verify_private_query(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &[u8],
+        public_inputs: &[Vec<Fr>],
+        registry_root: Fr,
+    ) -> Result<(), VerifyError> {
+        let row = |i: usize| public_inputs.first().and_then(|column| column.get(i));
+        if row(PRIVATE_QUERY_REGISTRY_ROOT_ROW) != Some(&registry_root) {
+            return Err(VerifyError::InstanceMismatch(
+                "registry root does not match".to_string(),
+            ));
+        }
+        if row(PRIVATE_QUERY_FINGERPRINT_ROW) != Some(&Fr::from(self.circuit_version())) {
+            return Err(VerifyError::InstanceMismatch(
+                "circuit fingerprint does not match the verifying key".to_string(),
+            ));
+        }
+
+        self.verify(params, proof, public_inputs)
+    }
+}
+
+/// Halo2 0.3.1 real API: verify_proof(params, vk, strategy, instances, transcript)
+fn verify_with_key(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &[u8],
+    public_inputs: &[Vec<Fr>],
+) -> Result<(), VerifyError> {
+    // Create transcript (Blake2bRead)
+    let mut transcript = Blake2bRead::<&[u8], EqAffine, Challenge255<EqAffine>>::init(proof);
+
+    // Create verification strategy (SingleVerifier)
+    let strategy = SingleVerifier::new(params);
+
+    // Format instances: &[&[&[C::Scalar]]]
+    // public_inputs: &[Vec<Fr>] -> instances: &[&[&[Fr]]]
+    let instances: Vec<Vec<&[Fr]>> = public_inputs.iter().map(|pi| vec![pi.as_slice()]).collect();
+    let instances_refs: Vec<&[&[Fr]]> = instances.iter().map(|inst| inst.as_slice()).collect();
+
+    // Verify proof
+    verify_proof(params, vk, strategy, &instances_refs, &mut transcript)?;
+
+    Ok(())
 }
 
 /// Mock Prover Helper (for testing)
@@ -159,4 +594,11 @@ impl MockProverHelper {
 
         Ok(true)
     }
+
+    /// Per-column utilization report (for tuning the column budget)
+    /// Paper Section 5: For development and testing
+    pub fn column_report(circuit: &PoneglyphCircuit, k: u32) -> Result<CircuitStats, String> {
+        CircuitStats::measure(k, circuit)
+            .map_err(|e| format!("Failed to measure circuit: {:?}", e))
+    }
 }
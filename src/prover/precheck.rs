@@ -0,0 +1,202 @@
+// Two-phase verification
+// The cryptographic check of an IPA proof costs a multi-scalar
+// multiplication over 2^k points, and a submission with a stale envelope or
+// the wrong instance fails it only after that work, with an opaque error.
+// `Precheck` runs the cheap structural checks first, in this order, and
+// reports the first one that fails:
+//
+// 1. envelope: the proof bytes decode (magic, format version) and k is
+//    within `max_k` (parameters for a huge k are themselves expensive)
+// 2. version: the proof was made for the verifier's key
+// 3. instance: one instance column of `NUM_INSTANCE_ROWS` rows plus the
+//    public thresholds, fitting the usable rows at k, whose values decode
+// 4. anchor: the database commitment is one the verifier knows
+//    (`CommitmentAnchors`)
+//
+// Only then does `Precheck::verify` run the cryptographic check.
+
+use halo2_proofs::{
+    pasta::EqAffine,
+    plonk::{Circuit, ConstraintSystem, VerifyingKey},
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::serialization::decode_instance;
+use super::{verify_with_key, Proof};
+use crate::circuit::{PoneglyphCircuit, PublicInputs, NUM_INSTANCE_ROWS};
+use crate::error::VerifyError;
+use crate::recursive::vk_fingerprint;
+
+/// Largest circuit size a `Precheck` accepts unless configured otherwise
+pub const DEFAULT_PRECHECK_MAX_K: u32 = 24;
+
+/// Database commitments a verifier accepts proofs about
+/// (e.g. the commitments published on-chain, or those of a `Session`)
+pub trait CommitmentAnchors {
+    fn is_anchored(&self, commitment: &Fr) -> bool;
+}
+
+impl CommitmentAnchors for [Fr] {
+    fn is_anchored(&self, commitment: &Fr) -> bool {
+        self.contains(commitment)
+    }
+}
+
+impl CommitmentAnchors for Vec<Fr> {
+    fn is_anchored(&self, commitment: &Fr) -> bool {
+        self.as_slice().is_anchored(commitment)
+    }
+}
+
+/// Submission that passed the structural checks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prechecked {
+    pub proof: Proof,
+    pub inputs: PublicInputs,
+}
+
+/// Structural checks of a submission against one verifying key
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let anchors = vec![published_commitment];
+/// let precheck = Precheck::new(&vk).with_anchors(&anchors);
+/// match precheck.verify(&proof_bytes, &instance_bytes) {
+///     Ok(inputs) => accept(inputs),
+///     Err(VerifyError::InvalidProof(_)) => flag_forgery(),
+///     Err(e) => reject_submission(e), // detailed, without the IPA check
+/// }
+/// ```
+pub struct Precheck<'a> {
+    vk: &'a VerifyingKey<EqAffine>,
+    circuit_version: u64,
+    blinding_factors: usize,
+    max_k: u32,
+    /// Public thresholds the instance must carry (any number if None)
+    thresholds: Option<usize>,
+    anchors: Option<&'a dyn CommitmentAnchors>,
+}
+
+impl<'a> Precheck<'a> {
+    pub fn new(vk: &'a VerifyingKey<EqAffine>) -> Self {
+        let mut cs = ConstraintSystem::default();
+        PoneglyphCircuit::configure(&mut cs);
+        Self {
+            vk,
+            circuit_version: vk_fingerprint(vk),
+            blinding_factors: cs.blinding_factors(),
+            max_k: DEFAULT_PRECHECK_MAX_K,
+            thresholds: None,
+            anchors: None,
+        }
+    }
+
+    pub fn with_max_k(mut self, max_k: u32) -> Self {
+        self.max_k = max_k;
+        self
+    }
+
+    /// Require exactly this many public thresholds (`ThresholdMode::Instance`)
+    pub fn with_thresholds(mut self, thresholds: usize) -> Self {
+        self.thresholds = Some(thresholds);
+        self
+    }
+
+    pub fn with_anchors(mut self, anchors: &'a dyn CommitmentAnchors) -> Self {
+        self.anchors = Some(anchors);
+        self
+    }
+
+    /// Run the structural checks
+    /// `proof_bytes` from `Proof::to_bytes`, `instance_bytes` from
+    /// `PublicInputs::to_bytes`
+    ///
+    /// # Errors
+    ///
+    /// `MalformedEnvelope` for envelope and instance shape failures,
+    /// `WrongCircuitVersion` for a proof made for another key,
+    /// `UnknownCommitment` for a commitment without anchor
+    pub fn check(
+        &self,
+        proof_bytes: &[u8],
+        instance_bytes: &[u8],
+    ) -> Result<Prechecked, VerifyError> {
+        let proof = Proof::from_bytes(proof_bytes)
+            .map_err(|e| VerifyError::MalformedEnvelope(format!("proof: {}", e)))?;
+        if proof.k == 0 || proof.k > self.max_k {
+            return Err(VerifyError::MalformedEnvelope(format!(
+                "proof is for k = {}, accepted are 1 to {}",
+                proof.k, self.max_k
+            )));
+        }
+        if proof.bytes.is_empty() {
+            return Err(VerifyError::MalformedEnvelope(
+                "proof transcript is empty".to_string(),
+            ));
+        }
+
+        if proof.circuit_version != self.circuit_version {
+            return Err(VerifyError::WrongCircuitVersion {
+                expected: self.circuit_version,
+                found: proof.circuit_version,
+            });
+        }
+
+        let values = decode_instance(instance_bytes)
+            .map_err(|e| VerifyError::MalformedEnvelope(format!("instance: {}", e)))?;
+        if values.len() < NUM_INSTANCE_ROWS {
+            return Err(VerifyError::MalformedEnvelope(format!(
+                "instance has {} rows, at least {} expected",
+                values.len(),
+                NUM_INSTANCE_ROWS
+            )));
+        }
+        let thresholds = values.len() - NUM_INSTANCE_ROWS;
+        if let Some(expected) = self.thresholds.filter(|&expected| expected != thresholds) {
+            return Err(VerifyError::InstanceMismatch(format!(
+                "instance has {} public thresholds, {} expected",
+                thresholds, expected
+            )));
+        }
+        let usable = (1usize << proof.k).saturating_sub(self.blinding_factors + 1);
+        if values.len() > usable {
+            return Err(VerifyError::MalformedEnvelope(format!(
+                "instance has {} rows, {} fit at k = {}",
+                values.len(),
+                usable,
+                proof.k
+            )));
+        }
+        let inputs = PublicInputs::from_instance(&[values]).ok_or_else(|| {
+            VerifyError::MalformedEnvelope(
+                "instance does not decode (expiry bound or thresholds out of range)".to_string(),
+            )
+        })?;
+
+        if let Some(anchors) = self.anchors {
+            if !anchors.is_anchored(&inputs.db_commitment) {
+                return Err(VerifyError::UnknownCommitment(inputs.db_commitment));
+            }
+        }
+
+        Ok(Prechecked { proof, inputs })
+    }
+
+    /// Structural checks, then the cryptographic check
+    ///
+    /// # Errors
+    ///
+    /// As `check`, then as `Verifier::verify`
+    pub fn verify(
+        &self,
+        proof_bytes: &[u8],
+        instance_bytes: &[u8],
+    ) -> Result<PublicInputs, VerifyError> {
+        let Prechecked { proof, inputs } = self.check(proof_bytes, instance_bytes)?;
+        let params = Params::<EqAffine>::new(proof.k);
+        verify_with_key(&params, self.vk, &proof.bytes, &inputs.to_instance())?;
+        Ok(inputs)
+    }
+}
@@ -0,0 +1,145 @@
+// Proving presets
+// The knobs that trade proving time against proof size and verification cost
+// live in different places: the session's `k_strategy`, whether a batch of
+// proofs is wrapped into one `AggregatedProof`, the fold topology of a
+// sharded proof and the stage limits that decide how many proofs run at
+// once. A preset picks them together:
+//
+// | Preset         | `k_strategy` | Wrapping     | Fold topology | Batching (`ProverConfig`) |
+// |----------------|--------------|--------------|---------------|---------------------------|
+// | `fast-prove`   | `grow`       | `Separate`   | `BinaryTree`  | 2 keygens, 4 proofs       |
+// | `small-proof`  | `minimal`    | `Separate`   | `Sequential`  | defaults                  |
+// | `cheap-verify` | `minimal`    | `Aggregated` | `Sequential`  | defaults                  |
+//
+// - An IPA proof grows with k (two points per round) and verifying it is
+//   linear in 2^k, so `small-proof` and `cheap-verify` prove at the planned
+//   size. `fast-prove` keeps its parameters and cached keys instead, since
+//   new ones cost more than a few unused rows.
+// - Aggregating N proofs leaves one generator MSM to the verifier instead of
+//   N, but the aggregate carries every proof plus its deferred point, and
+//   folding costs the prover a partial verification per proof.
+// - A binary tree folds shards in logarithmic rounds, a chain in constant
+//   memory (see `FoldTopology`).
+//
+// Every preset uses the one backend in the tree, halo2 IPA on the pasta
+// curves. Sessions take a preset with `SET preset = 'small-proof'` (see
+// `Session::set_preset`); sharded provers, aggregators and stage limits read
+// their part from the preset.
+
+use std::fmt;
+
+use super::ProverConfig;
+use crate::recursive::FoldTopology;
+use crate::sql::{KStrategy, SessionSettings};
+
+/// How a batch of proofs of one circuit is published
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wrapping {
+    /// Every proof on its own
+    Separate,
+    /// Folded into one `AggregatedProof` (see `RecursiveAggregator`)
+    Aggregated,
+}
+
+/// Named combination of proving settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofPreset {
+    /// Shortest proving time
+    FastProve,
+    /// Fewest proof bytes
+    SmallProof,
+    /// Least verifier work
+    CheapVerify,
+}
+
+impl ProofPreset {
+    pub const ALL: [ProofPreset; 3] = [
+        ProofPreset::FastProve,
+        ProofPreset::SmallProof,
+        ProofPreset::CheapVerify,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProofPreset::FastProve => "fast-prove",
+            ProofPreset::SmallProof => "small-proof",
+            ProofPreset::CheapVerify => "cheap-verify",
+        }
+    }
+
+    /// Preset by name, case-insensitive; `_` may stand for `-`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase().replace('_', "-");
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    pub fn k_strategy(&self) -> KStrategy {
+        match self {
+            ProofPreset::FastProve => KStrategy::Grow,
+            ProofPreset::SmallProof | ProofPreset::CheapVerify => KStrategy::Minimal,
+        }
+    }
+
+    pub fn wrapping(&self) -> Wrapping {
+        match self {
+            ProofPreset::FastProve | ProofPreset::SmallProof => Wrapping::Separate,
+            ProofPreset::CheapVerify => Wrapping::Aggregated,
+        }
+    }
+
+    /// Topology for `ShardedProver::with_topology`
+    pub fn fold_topology(&self) -> FoldTopology {
+        match self {
+            ProofPreset::FastProve => FoldTopology::BinaryTree,
+            ProofPreset::SmallProof | ProofPreset::CheapVerify => FoldTopology::Sequential,
+        }
+    }
+
+    /// Stage limits for `StageLimits::new`
+    pub fn prover_config(&self) -> ProverConfig {
+        match self {
+            ProofPreset::FastProve => ProverConfig::new().with_max_keygens(2).with_max_proves(4),
+            ProofPreset::SmallProof | ProofPreset::CheapVerify => ProverConfig::new(),
+        }
+    }
+
+    /// Apply the session part of the preset
+    pub fn apply(&self, settings: &mut SessionSettings) {
+        settings.k_strategy = self.k_strategy();
+    }
+}
+
+impl fmt::Display for ProofPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_names() {
+        for preset in ProofPreset::ALL {
+            assert_eq!(ProofPreset::from_name(preset.name()), Some(preset));
+        }
+        assert_eq!(
+            ProofPreset::from_name("Small_Proof"),
+            Some(ProofPreset::SmallProof)
+        );
+        assert_eq!(ProofPreset::from_name("tiny"), None);
+    }
+
+    #[test]
+    fn test_preset_settings() {
+        let mut settings = SessionSettings::default();
+        ProofPreset::CheapVerify.apply(&mut settings);
+        assert_eq!(settings.k_strategy, KStrategy::Minimal);
+        assert_eq!(ProofPreset::CheapVerify.wrapping(), Wrapping::Aggregated);
+
+        ProofPreset::FastProve.apply(&mut settings);
+        assert_eq!(settings, SessionSettings::default());
+        assert!(ProofPreset::FastProve.prover_config().max_proves > ProverConfig::new().max_proves);
+    }
+}
@@ -0,0 +1,464 @@
+// Witness generation dry run
+// Proving cost is dominated by the commitments (FFTs and multi-scalar
+// multiplications over every column), but which operators fill the rows
+// is decided during witness generation. A dry run synthesizes the circuit
+// against a recording `Assignment` - the floor planner and every chip run as
+// they do in a proof, nothing is committed - and reports the time and the
+// cells of each operator and region, so the expensive parts of a query can
+// be found before paying for a full proof.
+//
+// Operators are the top-level layouter namespaces of the circuit ("sort",
+// "join", ...); work outside any namespace (public inputs, lookup tables,
+// constants) is reported as `UNSCOPED`.
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+use pasta_curves::pallas::Base as Fr;
+
+use crate::circuit::ConfigColumns;
+
+/// Operator name of work outside any namespace
+pub const UNSCOPED: &str = "(circuit)";
+
+/// Cells written by an operator or a region
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellCounts {
+    pub advice: usize,
+    pub fixed: usize,
+    /// Copy constraints (permutation argument)
+    pub copies: usize,
+    /// Selector enables
+    pub selectors: usize,
+}
+
+impl CellCounts {
+    /// Advice and fixed cells
+    pub fn cells(&self) -> usize {
+        self.advice + self.fixed
+    }
+
+    fn add(&mut self, other: &CellCounts) {
+        self.advice += other.advice;
+        self.fixed += other.fixed;
+        self.copies += other.copies;
+        self.selectors += other.selectors;
+    }
+}
+
+/// One region of the layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionProfile {
+    pub name: String,
+    /// Operator the region belongs to
+    pub operator: String,
+    /// Time spent assigning the region
+    pub elapsed: Duration,
+    pub counts: CellCounts,
+}
+
+/// Totals of one operator (all its instances, e.g. every sort of a query)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperatorProfile {
+    pub name: String,
+    /// Wall time inside the operator, witness computation between its
+    /// regions included
+    pub elapsed: Duration,
+    pub regions: usize,
+    pub counts: CellCounts,
+}
+
+/// Time and cells per operator and region of one witness generation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WitnessProfile {
+    /// Wall time of the whole synthesis
+    pub elapsed: Duration,
+    /// Operators in order of first appearance
+    pub operators: Vec<OperatorProfile>,
+    /// Regions in layout order
+    pub regions: Vec<RegionProfile>,
+    /// Highest row written (None if nothing is assigned)
+    pub max_row: Option<usize>,
+    /// Rows lost to blinding factors at any k
+    pub blinding_rows: usize,
+}
+
+impl WitnessProfile {
+    /// Run witness generation of `circuit` and record it
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let profile = WitnessProfile::measure(&circuit)?;
+    /// println!("{}", profile);
+    /// for op in profile.slowest_operators(3) { ... }
+    /// ```
+    pub fn measure<C>(circuit: &C) -> Result<Self, Error>
+    where
+        C: Circuit<Fr>,
+        C::Config: ConfigColumns,
+    {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let constants = config.constant_columns();
+
+        let mut recorder = ProfileRecorder::default();
+        let start = Instant::now();
+        C::FloorPlanner::synthesize(&mut recorder, circuit, config, constants)?;
+        let elapsed = start.elapsed();
+
+        // Time outside every namespace
+        let scoped: Duration = recorder.operators.iter().map(|op| op.elapsed).sum();
+        recorder.operator(UNSCOPED).elapsed += elapsed.saturating_sub(scoped);
+
+        Ok(Self {
+            elapsed,
+            operators: recorder.operators,
+            regions: recorder.regions,
+            max_row: recorder.max_row,
+            blinding_rows: cs.blinding_factors() + 1,
+        })
+    }
+
+    /// Totals over every operator
+    pub fn counts(&self) -> CellCounts {
+        let mut counts = CellCounts::default();
+        for op in &self.operators {
+            counts.add(&op.counts);
+        }
+        counts
+    }
+
+    /// Smallest k whose usable rows hold the layout
+    pub fn min_k(&self) -> u32 {
+        let rows = self.max_row.map_or(0, |row| row + 1) + self.blinding_rows;
+        rows.next_power_of_two().trailing_zeros()
+    }
+
+    /// The `n` operators with the most time, slowest first
+    pub fn slowest_operators(&self, n: usize) -> Vec<&OperatorProfile> {
+        let mut operators: Vec<_> = self.operators.iter().collect();
+        operators.sort_by_key(|o| Reverse(o.elapsed));
+        operators.truncate(n);
+        operators
+    }
+
+    /// The `n` regions with the most cells, largest first
+    pub fn largest_regions(&self, n: usize) -> Vec<&RegionProfile> {
+        let mut regions: Vec<_> = self.regions.iter().collect();
+        regions.sort_by_key(|r| Reverse(r.counts.cells()));
+        regions.truncate(n);
+        regions
+    }
+}
+
+impl fmt::Display for WitnessProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = self.counts();
+        writeln!(
+            f,
+            "witness generation: {:.3} ms, {} cells, {} copies, {} regions, min k = {}",
+            self.elapsed.as_secs_f64() * 1e3,
+            counts.cells(),
+            counts.copies,
+            self.regions.len(),
+            self.min_k()
+        )?;
+        for op in &self.operators {
+            let share = if self.elapsed.is_zero() {
+                0.0
+            } else {
+                op.elapsed.as_secs_f64() / self.elapsed.as_secs_f64() * 100.0
+            };
+            writeln!(
+                f,
+                "{}: {:.3} ms ({:.1}%), {} regions, {} advice, {} fixed, {} copies",
+                op.name,
+                op.elapsed.as_secs_f64() * 1e3,
+                share,
+                op.regions,
+                op.counts.advice,
+                op.counts.fixed,
+                op.counts.copies
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Records time and cell counts per namespace and region (values are ignored)
+#[derive(Default)]
+struct ProfileRecorder {
+    namespaces: Vec<String>,
+    /// Start of the open top-level namespace
+    scope_start: Option<Instant>,
+    /// Open region and its start
+    region: Option<(RegionProfile, Instant)>,
+    regions: Vec<RegionProfile>,
+    operators: Vec<OperatorProfile>,
+    max_row: Option<usize>,
+}
+
+impl ProfileRecorder {
+    fn operator(&mut self, name: &str) -> &mut OperatorProfile {
+        let idx = match self.operators.iter().position(|op| op.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.operators.push(OperatorProfile {
+                    name: name.to_string(),
+                    elapsed: Duration::ZERO,
+                    regions: 0,
+                    counts: CellCounts::default(),
+                });
+                self.operators.len() - 1
+            }
+        };
+        &mut self.operators[idx]
+    }
+
+    fn current_operator(&self) -> String {
+        self.namespaces
+            .first()
+            .cloned()
+            .unwrap_or_else(|| UNSCOPED.to_string())
+    }
+
+    /// Count a write in the open region, or in the current operator
+    fn record(&mut self, row: Option<usize>, count: impl Fn(&mut CellCounts)) {
+        if let Some(row) = row {
+            self.max_row = self.max_row.max(Some(row));
+        }
+        match self.region.as_mut() {
+            Some((region, _)) => count(&mut region.counts),
+            None => {
+                let name = self.current_operator();
+                count(&mut self.operator(&name).counts);
+            }
+        }
+    }
+}
+
+impl Assignment<Fr> for ProfileRecorder {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let region = RegionProfile {
+            name: name_fn().into(),
+            operator: self.current_operator(),
+            elapsed: Duration::ZERO,
+            counts: CellCounts::default(),
+        };
+        self.region = Some((region, Instant::now()));
+    }
+
+    fn exit_region(&mut self) {
+        if let Some((mut region, start)) = self.region.take() {
+            region.elapsed = start.elapsed();
+            let op = self.operator(&region.operator);
+            op.regions += 1;
+            op.counts.add(&region.counts);
+            self.regions.push(region);
+        }
+    }
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _annotation: A,
+        _selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.record(Some(row), |c| c.selectors += 1);
+        Ok(())
+    }
+
+    fn query_instance(&self, _column: Column<Instance>, _row: usize) -> Result<Value<Fr>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        _column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // Evaluate the witness: computing it is part of the cost
+        let _ = to().map(|v| v.into());
+        self.record(Some(row), |c| c.advice += 1);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        _column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let _ = to().map(|v| v.into());
+        self.record(Some(row), |c| c.fixed += 1);
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        _left_column: Column<Any>,
+        _left_row: usize,
+        _right_column: Column<Any>,
+        _right_row: usize,
+    ) -> Result<(), Error> {
+        self.record(None, |c| c.copies += 1);
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _column: Column<Fixed>,
+        _row: usize,
+        _to: Value<Assigned<Fr>>,
+    ) -> Result<(), Error> {
+        // Padding up to 2^k rows, which a dry run doesn't fix
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        if self.namespaces.is_empty() {
+            self.scope_start = Some(Instant::now());
+        }
+        self.namespaces.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {
+        let Some(name) = self.namespaces.pop() else {
+            return;
+        };
+        if self.namespaces.is_empty() {
+            if let Some(start) = self.scope_start.take() {
+                self.operator(&name).elapsed += start.elapsed();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::{PoneglyphCircuit, RangeCheckOp, SortOp, TableScan, ThresholdMode};
+
+    fn circuit(range_checks: Vec<RangeCheckOp>, sorts: Vec<SortOp>) -> PoneglyphCircuit {
+        PoneglyphCircuit {
+            scan: TableScan::default(),
+            query_result: Value::known(Fr::from(2)),
+            nonce: Value::known(Fr::from(0)),
+            expiry: Value::known(Fr::from(0)),
+            query_hash: Fr::from(0),
+            result_commitment: Value::known(Fr::from(0)),
+            result_columns: vec![],
+            result_predicate: None,
+            threshold_mode: ThresholdMode::Fixed,
+            range_checks,
+            sorts,
+            group_bys: vec![],
+            joins: vec![],
+            aggregations: vec![],
+        }
+    }
+
+    fn range_check(value: u64) -> RangeCheckOp {
+        RangeCheckOp {
+            value: Value::known(value),
+            threshold: 100,
+            u: u64::MAX,
+        }
+    }
+
+    #[test]
+    fn test_operators_and_regions() {
+        let sort = SortOp {
+            input: vec![Value::known(5), Value::known(1), Value::known(3)],
+            sorted_output: vec![1, 3, 5],
+        };
+        let profile =
+            WitnessProfile::measure(&circuit(vec![range_check(5), range_check(50)], vec![sort]))
+                .unwrap();
+
+        let names: Vec<_> = profile
+            .operators
+            .iter()
+            .map(|op| op.name.as_str())
+            .collect();
+        assert!(names.contains(&"range check"));
+        assert!(names.contains(&"sort"));
+        assert!(names.contains(&UNSCOPED));
+
+        // Cells of the regions add up to the operator totals
+        for op in &profile.operators {
+            let regions: Vec<_> = profile
+                .regions
+                .iter()
+                .filter(|r| r.operator == op.name)
+                .collect();
+            assert_eq!(regions.len(), op.regions);
+            let region_cells: usize = regions.iter().map(|r| r.counts.cells()).sum();
+            assert!(region_cells <= op.counts.cells());
+        }
+        let sort = profile
+            .operators
+            .iter()
+            .find(|op| op.name == "sort")
+            .unwrap();
+        assert!(sort.counts.advice >= 6);
+
+        let total: Duration = profile.operators.iter().map(|op| op.elapsed).sum();
+        assert!(total >= profile.elapsed);
+        assert!(profile.min_k() >= 4);
+        assert!(profile.to_string().contains("sort: "));
+        assert_eq!(profile.largest_regions(1).len(), 1);
+        assert!(profile.slowest_operators(10).len() == profile.operators.len());
+    }
+
+    #[test]
+    fn test_cells_grow_with_operations() {
+        let one = WitnessProfile::measure(&circuit(vec![range_check(5)], vec![])).unwrap();
+        let four = WitnessProfile::measure(&circuit(vec![range_check(5); 4], vec![])).unwrap();
+        let cells = |p: &WitnessProfile| {
+            p.operators
+                .iter()
+                .find(|op| op.name == "range check")
+                .map_or(0, |op| op.counts.cells())
+        };
+        assert!(cells(&one) > 0);
+        assert!(cells(&four) > cells(&one));
+        assert!(four.max_row >= one.max_row);
+    }
+}
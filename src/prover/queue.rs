@@ -0,0 +1,363 @@
+// Proving job queues
+// A prover deployment takes queries off a shared work queue, so replicas can
+// be added or removed without coordinating with each other. Claiming a job
+// leases it: the job stays invisible to other replicas until it is acked
+// (done), released (retry elsewhere) or its lease expires because the
+// replica died mid-proof, after which it is claimed again.
+//
+// - `MemoryQueue`: one process, any number of worker threads
+// - `RedisQueue` (feature `redis-queue`): replicas sharing a Redis server
+//
+// Other brokers (SQS, Pub/Sub, ...) plug in by implementing `JobQueue`; their
+// visibility timeouts map directly onto leases.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Lease of a claimed job unless the queue is configured otherwise
+pub const DEFAULT_LEASE: Duration = Duration::from_secs(300);
+
+/// Query to prove
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingJob {
+    /// Caller-chosen id (e.g. the request id the nonce is derived from)
+    pub id: String,
+    pub sql: String,
+    /// Times the job was claimed before (0 on the first claim)
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+impl ProvingJob {
+    pub fn new(id: impl Into<String>, sql: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            sql: sql.into(),
+            attempts: 0,
+        }
+    }
+}
+
+/// Queue operation failure
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueueError {
+    /// The backend is unreachable or rejected the command
+    Backend(String),
+    /// A stored job can't be decoded
+    Malformed(String),
+    /// Ack or release of a job that isn't leased (any more)
+    NotLeased(String),
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueError::Backend(msg) => write!(f, "Queue backend error: {}", msg),
+            QueueError::Malformed(msg) => write!(f, "Malformed job: {}", msg),
+            QueueError::NotLeased(id) => write!(f, "Job {} is not leased", id),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+/// Shared proving work queue
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// while let Some(job) = queue.claim()? {
+///     match session.execute(&job.sql) {
+///         Ok(outcome) => { publish(&job, outcome); queue.ack(&job)?; }
+///         Err(_) if job.attempts < 3 => queue.release(&job)?,
+///         Err(_) => queue.ack(&job)?,
+///     }
+/// }
+/// ```
+pub trait JobQueue: Send + Sync {
+    /// Append a job
+    fn push(&self, job: ProvingJob) -> Result<(), QueueError>;
+
+    /// Lease the oldest available job (None if there is none)
+    /// Jobs with an expired lease are available again.
+    fn claim(&self) -> Result<Option<ProvingJob>, QueueError>;
+
+    /// Remove a leased job for good
+    fn ack(&self, job: &ProvingJob) -> Result<(), QueueError>;
+
+    /// Return a leased job to the front of the queue
+    fn release(&self, job: &ProvingJob) -> Result<(), QueueError>;
+
+    /// Jobs waiting to be claimed
+    fn pending(&self) -> Result<usize, QueueError>;
+}
+
+/// In-process queue
+#[derive(Debug)]
+pub struct MemoryQueue {
+    lease: Duration,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    pending: VecDeque<ProvingJob>,
+    /// Job id -> (job, lease deadline)
+    leased: HashMap<String, (ProvingJob, Instant)>,
+}
+
+impl MemoryQueue {
+    pub fn new() -> Self {
+        Self::with_lease(DEFAULT_LEASE)
+    }
+
+    pub fn with_lease(lease: Duration) -> Self {
+        Self {
+            lease,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        // A panicking worker doesn't leave the queue itself inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MemoryQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JobQueue for MemoryQueue {
+    fn push(&self, job: ProvingJob) -> Result<(), QueueError> {
+        self.state().pending.push_back(job);
+        Ok(())
+    }
+
+    fn claim(&self) -> Result<Option<ProvingJob>, QueueError> {
+        let mut state = self.state();
+        let now = Instant::now();
+        let expired: Vec<String> = state
+            .leased
+            .iter()
+            .filter(|(_, (_, deadline))| *deadline <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in expired {
+            let (job, _) = state.leased.remove(&id).unwrap();
+            state.pending.push_front(job);
+        }
+
+        let Some(mut job) = state.pending.pop_front() else {
+            return Ok(None);
+        };
+        let claimed = job.clone();
+        job.attempts += 1;
+        state
+            .leased
+            .insert(claimed.id.clone(), (job, now + self.lease));
+        Ok(Some(claimed))
+    }
+
+    fn ack(&self, job: &ProvingJob) -> Result<(), QueueError> {
+        self.state()
+            .leased
+            .remove(&job.id)
+            .map(|_| ())
+            .ok_or_else(|| QueueError::NotLeased(job.id.clone()))
+    }
+
+    fn release(&self, job: &ProvingJob) -> Result<(), QueueError> {
+        let mut state = self.state();
+        let (job, _) = state
+            .leased
+            .remove(&job.id)
+            .ok_or_else(|| QueueError::NotLeased(job.id.clone()))?;
+        state.pending.push_front(job);
+        Ok(())
+    }
+
+    fn pending(&self) -> Result<usize, QueueError> {
+        Ok(self.state().pending.len())
+    }
+}
+
+/// Queue in Redis, shared by every replica using the same key prefix
+///
+/// Keys: `{prefix}:pending` (list of jobs, claimed from the right),
+/// `{prefix}:leased` (hash of job id -> job) and `{prefix}:deadlines` (sorted
+/// set of job ids by lease deadline in Redis server milliseconds). Claims and
+/// releases run as Lua scripts, so replicas never claim the same job twice and
+/// deadlines don't depend on replica clocks.
+#[cfg(feature = "redis-queue")]
+pub struct RedisQueue {
+    client: redis::Client,
+    prefix: String,
+    lease: Duration,
+}
+
+#[cfg(feature = "redis-queue")]
+const CLAIM_SCRIPT: &str = r"
+local now = redis.call('TIME')
+local ms = tonumber(now[1]) * 1000 + math.floor(tonumber(now[2]) / 1000)
+for _, id in ipairs(redis.call('ZRANGEBYSCORE', KEYS[3], '-inf', ms)) do
+    redis.call('RPUSH', KEYS[1], redis.call('HGET', KEYS[2], id))
+    redis.call('HDEL', KEYS[2], id)
+    redis.call('ZREM', KEYS[3], id)
+end
+local job = redis.call('RPOP', KEYS[1])
+if not job then
+    return false
+end
+local leased = cjson.decode(job)
+leased['attempts'] = (leased['attempts'] or 0) + 1
+redis.call('HSET', KEYS[2], leased['id'], cjson.encode(leased))
+redis.call('ZADD', KEYS[3], ms + tonumber(ARGV[1]), leased['id'])
+return job
+";
+
+#[cfg(feature = "redis-queue")]
+const FINISH_SCRIPT: &str = r"
+local job = redis.call('HGET', KEYS[2], ARGV[1])
+if not job then
+    return 0
+end
+redis.call('HDEL', KEYS[2], ARGV[1])
+redis.call('ZREM', KEYS[3], ARGV[1])
+if ARGV[2] == '1' then
+    redis.call('RPUSH', KEYS[1], job)
+end
+return 1
+";
+
+#[cfg(feature = "redis-queue")]
+impl RedisQueue {
+    /// Queue at `url` (e.g. `redis://queue.internal:6379/0`) under `prefix`
+    pub fn open(url: &str, prefix: &str) -> Result<Self, QueueError> {
+        let client = redis::Client::open(url).map_err(backend)?;
+        Ok(Self {
+            client,
+            prefix: prefix.to_string(),
+            lease: DEFAULT_LEASE,
+        })
+    }
+
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    fn keys(&self) -> [String; 3] {
+        ["pending", "leased", "deadlines"].map(|key| format!("{}:{}", self.prefix, key))
+    }
+
+    fn connection(&self) -> Result<redis::Connection, QueueError> {
+        self.client.get_connection().map_err(backend)
+    }
+
+    /// Drop a leased job, or return it to the front of the queue
+    fn finish(&self, job: &ProvingJob, requeue: bool) -> Result<(), QueueError> {
+        let found: i32 = redis::Script::new(FINISH_SCRIPT)
+            .key(self.keys().to_vec())
+            .arg(&job.id)
+            .arg(if requeue { "1" } else { "0" })
+            .invoke(&mut self.connection()?)
+            .map_err(backend)?;
+        if found == 0 {
+            return Err(QueueError::NotLeased(job.id.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "redis-queue")]
+impl JobQueue for RedisQueue {
+    fn push(&self, job: ProvingJob) -> Result<(), QueueError> {
+        use redis::Commands;
+
+        let [pending, ..] = self.keys();
+        self.connection()?
+            .lpush::<_, _, ()>(pending, encode(&job)?)
+            .map_err(backend)
+    }
+
+    fn claim(&self) -> Result<Option<ProvingJob>, QueueError> {
+        let job: Option<String> = redis::Script::new(CLAIM_SCRIPT)
+            .key(self.keys().to_vec())
+            .arg(self.lease.as_millis() as u64)
+            .invoke(&mut self.connection()?)
+            .map_err(backend)?;
+        job.map(|job| serde_json::from_str(&job).map_err(|e| QueueError::Malformed(e.to_string())))
+            .transpose()
+    }
+
+    fn ack(&self, job: &ProvingJob) -> Result<(), QueueError> {
+        self.finish(job, false)
+    }
+
+    fn release(&self, job: &ProvingJob) -> Result<(), QueueError> {
+        self.finish(job, true)
+    }
+
+    fn pending(&self) -> Result<usize, QueueError> {
+        use redis::Commands;
+
+        let [pending, ..] = self.keys();
+        self.connection()?.llen(pending).map_err(backend)
+    }
+}
+
+#[cfg(feature = "redis-queue")]
+fn encode(job: &ProvingJob) -> Result<String, QueueError> {
+    serde_json::to_string(job).map_err(|e| QueueError::Malformed(e.to_string()))
+}
+
+#[cfg(feature = "redis-queue")]
+fn backend(e: redis::RedisError) -> QueueError {
+    QueueError::Backend(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_queue() {
+        let queue = MemoryQueue::new();
+        queue.push(ProvingJob::new("a", "SELECT 1")).unwrap();
+        queue.push(ProvingJob::new("b", "SELECT 2")).unwrap();
+
+        let a = queue.claim().unwrap().unwrap();
+        assert_eq!((a.id.as_str(), a.attempts), ("a", 0));
+        assert_eq!(queue.pending().unwrap(), 1);
+
+        // Released jobs come back first, with the attempt counted
+        queue.release(&a).unwrap();
+        let a = queue.claim().unwrap().unwrap();
+        assert_eq!((a.id.as_str(), a.attempts), ("a", 1));
+        queue.ack(&a).unwrap();
+        assert_eq!(queue.ack(&a), Err(QueueError::NotLeased("a".to_string())));
+
+        assert_eq!(queue.claim().unwrap().unwrap().id, "b");
+        assert_eq!(queue.claim().unwrap(), None);
+    }
+
+    #[test]
+    fn test_expired_lease() {
+        let queue = MemoryQueue::with_lease(Duration::ZERO);
+        queue.push(ProvingJob::new("a", "SELECT 1")).unwrap();
+        let first = queue.claim().unwrap().unwrap();
+
+        // Another replica picks the job up once the lease is over
+        let second = queue.claim().unwrap().unwrap();
+        assert_eq!((second.id.as_str(), second.attempts), ("a", 1));
+        assert!(queue.ack(&second).is_ok());
+        assert!(queue.ack(&first).is_err());
+    }
+}
@@ -0,0 +1,524 @@
+// Verification as a pure function over bytes
+// `verify_raw` takes a raw verifying key, serialized public inputs and a
+// serialized proof, and touches no global state, files or randomness, so it
+// can run in sandboxed, deterministic hosts.
+//
+// halo2_proofs 0.3 can't read a verifying key back, and the key header
+// (`VerifyingKeyBytes`) needs the circuit to rebuild the key. A raw key
+// instead carries what key generation consumes: the fixed cells, the enabled
+// selectors and the copy constraints of one `PoneglyphCircuit` layout (no
+// advice values). Lookup tables are the same for every layout and are
+// reloaded from the config instead. `verify_raw` replays that layout through
+// `keygen_vk` and only uses the key if its fingerprint matches the one in the
+// raw key.
+//
+// Rebuilding the key costs a key generation per call; verifiers that check
+// many proofs against one key keep the rebuilt key (`RawVerifyingKey::rebuild`)
+// and call `Verifier::verify_proof`.
+
+use std::collections::HashMap;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{Cell, Layouter, Region, SimpleFloorPlanner, Value},
+    pasta::EqAffine,
+    plonk::{
+        keygen_vk, Advice, Any, Assigned, Assignment, Circuit, Column, ColumnType,
+        ConstraintSystem, Error, Fixed, FloorPlanner, Instance, Selector, VerifyingKey,
+    },
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::serialization::{check_version, decode, encode};
+use super::{verify_with_key, Proof};
+use crate::circuit::{ConfigColumns, PoneglyphCircuit, PoneglyphCircuitConfig, PublicInputs};
+use crate::error::{PoneglyphError, PoneglyphResult, VerifyError};
+use crate::recursive::vk_fingerprint;
+
+/// Format version of `RawVerifyingKey::to_bytes`
+/// Version 2 numbers the operator and Poseidon selectors too
+/// Version 3 numbers columns by the config's allocation and drops the
+/// lookup table fills
+pub const RAW_KEY_FORMAT_VERSION: u32 = 3;
+
+/// Leading bytes of a serialized raw verifying key
+const RAW_KEY_MAGIC: &[u8; 4] = b"PGRK";
+
+/// Column kinds of copy constraint endpoints
+const ADVICE: u8 = 0;
+const FIXED: u8 = 1;
+const INSTANCE: u8 = 2;
+
+/// Copy constraint endpoint: (column kind, column position, row)
+/// Columns are numbered in `ConfigColumns` order within their kind
+type Endpoint = (u8, u32, u32);
+
+/// Self-contained verifying key: the key-relevant layout of a circuit
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// // Prover side, once per circuit shape
+/// let (pk, vk) = Prover::setup(k, &shape)?;
+/// let vk_bytes = RawVerifyingKey::of(k, &shape, &vk)?.to_bytes()?;
+/// // Sandboxed verifier
+/// verify_raw(&vk_bytes, &inputs.to_bytes()?, &proof.to_bytes()?)?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct RawVerifyingKey {
+    /// Circuit size (2^k rows)
+    pub k: u32,
+    /// `vk_fingerprint` of the key
+    pub circuit_version: u64,
+    /// (column, row, value) of every assigned fixed cell outside the lookup
+    /// tables; columns are numbered in `ConfigColumns::fixed_columns` order
+    fixed: Vec<(u32, u32, [u8; 32])>,
+    /// (selector, row) of every enabled selector; selectors are numbered in
+    /// `ConfigColumns::selectors` order
+    selectors: Vec<(u32, u32)>,
+    /// Copy constraints in synthesis order (the permutation depends on it)
+    copies: Vec<(Endpoint, Endpoint)>,
+}
+
+#[derive(bincode::Encode, bincode::Decode)]
+struct RawKeyEnvelope {
+    version: u32,
+    key: RawVerifyingKey,
+}
+
+impl RawVerifyingKey {
+    /// Record the layout of `circuit` (witnesses are ignored)
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if the recorded layout doesn't rebuild `vk`
+    pub fn of(
+        k: u32,
+        circuit: &PoneglyphCircuit,
+        vk: &VerifyingKey<EqAffine>,
+    ) -> PoneglyphResult<Self> {
+        let mut cs = ConstraintSystem::default();
+        let config = PoneglyphCircuit::configure(&mut cs);
+        let selectors = config.selectors();
+        let constants = config.constant_columns();
+
+        let mut recorder = LayoutRecorder {
+            advice_columns: config.advice_columns(),
+            fixed_columns: config.fixed_columns(),
+            instance: config.poneglyph_config.instance,
+            fixed: vec![],
+            selectors: vec![],
+            copies: vec![],
+        };
+        <PoneglyphCircuit as Circuit<Fr>>::FloorPlanner::synthesize(
+            &mut recorder,
+            circuit,
+            config,
+            constants,
+        )
+        .map_err(|e| PoneglyphError::Synthesis(format!("{:?}", e)))?;
+
+        let selectors = recorder
+            .selectors
+            .iter()
+            .map(|(selector, row)| {
+                let index = selectors
+                    .iter()
+                    .position(|s| s == selector)
+                    .ok_or_else(|| {
+                        PoneglyphError::Configuration(format!(
+                            "{:?} is not a PoneglyphCircuitConfig selector",
+                            selector
+                        ))
+                    })?;
+                Ok((index as u32, *row as u32))
+            })
+            .collect::<PoneglyphResult<Vec<_>>>()?;
+
+        let key = Self {
+            k,
+            circuit_version: vk_fingerprint(vk),
+            fixed: recorder.fixed,
+            selectors,
+            copies: recorder.copies,
+        };
+        key.rebuild()?;
+        Ok(key)
+    }
+
+    /// Generate the verifying key from the recorded layout
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if the layout gives a key with another fingerprint
+    pub fn rebuild(&self) -> PoneglyphResult<VerifyingKey<EqAffine>> {
+        let params = Params::<EqAffine>::new(self.k);
+        let circuit = LayoutCircuit { key: self.clone() };
+        let vk = keygen_vk(&params, &circuit)
+            .map_err(|e| PoneglyphError::Synthesis(format!("{:?}", e)))?;
+        let found = vk_fingerprint(&vk);
+        if found != self.circuit_version {
+            return Err(PoneglyphError::Validation(format!(
+                "layout gives key {:016x}, raw key is {:016x}",
+                found, self.circuit_version
+            )));
+        }
+        Ok(vk)
+    }
+
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        let envelope = RawKeyEnvelope {
+            version: RAW_KEY_FORMAT_VERSION,
+            key: self.clone(),
+        };
+        encode(RAW_KEY_MAGIC, &envelope)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        let envelope: RawKeyEnvelope = decode(RAW_KEY_MAGIC, "raw verifying key", bytes)?;
+        check_version(
+            "raw verifying key",
+            envelope.version,
+            RAW_KEY_FORMAT_VERSION,
+        )?;
+        Ok(envelope.key)
+    }
+}
+
+/// Verify a proof from bytes alone
+/// `vk_bytes` from `RawVerifyingKey::to_bytes`, `instance_bytes` from
+/// `PublicInputs::to_bytes`, `proof_bytes` from `Proof::to_bytes`
+///
+/// # Errors
+///
+/// `MalformedEnvelope` if any input doesn't decode or the raw key doesn't
+/// rebuild, `WrongCircuitVersion` if the proof was made for another key,
+/// otherwise as `Verifier::verify`
+pub fn verify_raw(
+    vk_bytes: &[u8],
+    instance_bytes: &[u8],
+    proof_bytes: &[u8],
+) -> Result<(), VerifyError> {
+    let malformed = |e: PoneglyphError| VerifyError::MalformedEnvelope(e.to_string());
+    let key = RawVerifyingKey::from_bytes(vk_bytes).map_err(malformed)?;
+    let inputs = PublicInputs::from_bytes(instance_bytes).map_err(malformed)?;
+    let proof = Proof::from_bytes(proof_bytes).map_err(malformed)?;
+
+    if proof.circuit_version != key.circuit_version {
+        return Err(VerifyError::WrongCircuitVersion {
+            expected: key.circuit_version,
+            found: proof.circuit_version,
+        });
+    }
+    if proof.k != key.k {
+        return Err(VerifyError::MalformedEnvelope(format!(
+            "proof is for k = {}, key for k = {}",
+            proof.k, key.k
+        )));
+    }
+    let vk = key.rebuild().map_err(malformed)?;
+    let params = Params::<EqAffine>::new(key.k);
+    verify_with_key(&params, &vk, &proof.bytes, &inputs.to_instance())
+}
+
+/// Replays a raw key's layout in one region at row 0
+struct LayoutCircuit {
+    key: RawVerifyingKey,
+}
+
+#[derive(Clone)]
+struct LayoutConfig {
+    poneglyph: PoneglyphCircuitConfig,
+    advice: Vec<Column<Advice>>,
+    fixed: Vec<Column<Fixed>>,
+}
+
+impl Circuit<Fr> for LayoutCircuit {
+    type Config = LayoutConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph = PoneglyphCircuit::configure(meta);
+        LayoutConfig {
+            advice: poneglyph.advice_columns(),
+            fixed: poneglyph.fixed_columns(),
+            poneglyph,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let key = &self.key;
+        config
+            .poneglyph
+            .poneglyph_config
+            .load_lookup_table(&mut layouter)?;
+        let fixed_column = |index: u32| column(&config.fixed, index);
+        let fixed_values: HashMap<(u32, u32), Fr> = key
+            .fixed
+            .iter()
+            .map(|&(column, row, repr)| Ok(((column, row), field(repr)?)))
+            .collect::<Result<_, Error>>()?;
+        let selectors = config.poneglyph.selectors();
+
+        layouter.assign_region(
+            || "raw key layout",
+            |mut region| {
+                for (&(column, row), value) in &fixed_values {
+                    region.assign_fixed(
+                        || "fixed",
+                        fixed_column(column)?,
+                        row as usize,
+                        || Value::known(*value),
+                    )?;
+                }
+                for &(selector, row) in &key.selectors {
+                    selectors
+                        .get(selector as usize)
+                        .ok_or(Error::Synthesis)?
+                        .enable(&mut region, row as usize)?;
+                }
+                for &(left, right) in &key.copies {
+                    match (left, right) {
+                        ((ADVICE, advice, advice_row), (INSTANCE, _, row))
+                        | ((INSTANCE, _, row), (ADVICE, advice, advice_row)) => {
+                            region.assign_advice_from_instance(
+                                || "instance copy",
+                                config.poneglyph.poneglyph_config.instance,
+                                row as usize,
+                                column(&config.advice, advice)?,
+                                advice_row as usize,
+                            )?;
+                        }
+                        _ => {
+                            let left = cell(&mut region, &config, &fixed_values, left)?;
+                            let right = cell(&mut region, &config, &fixed_values, right)?;
+                            region.constrain_equal(left, right)?;
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Cell of a copy endpoint (advice cells are assigned without a value,
+/// fixed cells with their recorded value)
+fn cell(
+    region: &mut Region<'_, Fr>,
+    config: &LayoutConfig,
+    fixed_values: &HashMap<(u32, u32), Fr>,
+    (kind, column, row): Endpoint,
+) -> Result<Cell, Error> {
+    match kind {
+        ADVICE => Ok(region
+            .assign_advice(
+                || "copy",
+                self::column(&config.advice, column)?,
+                row as usize,
+                Value::<Fr>::unknown,
+            )?
+            .cell()),
+        FIXED => {
+            let value = fixed_values
+                .get(&(column, row))
+                .copied()
+                .ok_or(Error::Synthesis)?;
+            let column = self::column(&config.fixed, column)?;
+            Ok(region
+                .assign_fixed(|| "copy", column, row as usize, || Value::known(value))?
+                .cell())
+        }
+        _ => Err(Error::Synthesis),
+    }
+}
+
+/// Column at a raw key position
+fn column<C: ColumnType>(columns: &[Column<C>], position: u32) -> Result<Column<C>, Error> {
+    columns
+        .get(position as usize)
+        .copied()
+        .ok_or(Error::Synthesis)
+}
+
+fn field(repr: [u8; 32]) -> Result<Fr, Error> {
+    Option::from(Fr::from_repr(repr)).ok_or(Error::Synthesis)
+}
+
+/// Position of a column in a raw key column list
+fn position<C: ColumnType>(columns: &[Column<C>], column: Column<Any>) -> Result<u32, Error>
+where
+    Column<Any>: From<Column<C>>,
+{
+    columns
+        .iter()
+        .position(|&c| Column::<Any>::from(c) == column)
+        .map(|position| position as u32)
+        .ok_or(Error::Synthesis)
+}
+
+/// Records the key-relevant parts of a synthesis, in order
+/// Lookup table columns aren't config columns: their cells are skipped
+struct LayoutRecorder {
+    advice_columns: Vec<Column<Advice>>,
+    fixed_columns: Vec<Column<Fixed>>,
+    instance: Column<Instance>,
+    fixed: Vec<(u32, u32, [u8; 32])>,
+    selectors: Vec<(Selector, usize)>,
+    copies: Vec<(Endpoint, Endpoint)>,
+}
+
+impl LayoutRecorder {
+    fn endpoint(&self, column: Column<Any>, row: usize) -> Result<Endpoint, Error> {
+        let (kind, position) = match column.column_type() {
+            Any::Advice => (ADVICE, position(&self.advice_columns, column)?),
+            Any::Fixed => (FIXED, position(&self.fixed_columns, column)?),
+            Any::Instance => (INSTANCE, position(&[self.instance], column)?),
+        };
+        Ok((kind, position, row as u32))
+    }
+}
+
+impl Assignment<Fr> for LayoutRecorder {
+    fn enter_region<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _annotation: A,
+        selector: &Selector,
+        row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.selectors.push((*selector, row));
+        Ok(())
+    }
+
+    fn query_instance(&self, _column: Column<Instance>, _row: usize) -> Result<Value<Fr>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        _column: Column<Advice>,
+        _row: usize,
+        _to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let Some(position) = self.fixed_columns.iter().position(|&c| c == column) else {
+            return Ok(());
+        };
+        to().map(|v| {
+            let value: Assigned<Fr> = v.into();
+            self.fixed
+                .push((position as u32, row as u32, value.evaluate().to_repr()));
+        });
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        let left = self.endpoint(left_column, left_row)?;
+        let right = self.endpoint(right_column, right_row)?;
+        self.copies.push((left, right));
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _column: Column<Fixed>,
+        _row: usize,
+        _to: Value<Assigned<Fr>>,
+    ) -> Result<(), Error> {
+        // Only lookup tables are padded, and they are reloaded from the config
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> RawVerifyingKey {
+        RawVerifyingKey {
+            k: 10,
+            circuit_version: 7,
+            fixed: vec![(0, 3, Fr::from(5).to_repr())],
+            selectors: vec![(0, 1)],
+            copies: vec![((ADVICE, 0, 1), (INSTANCE, 0, 0))],
+        }
+    }
+
+    #[test]
+    fn test_raw_key_roundtrip() {
+        let bytes = key().to_bytes().unwrap();
+        assert_eq!(RawVerifyingKey::from_bytes(&bytes).unwrap(), key());
+        assert!(RawVerifyingKey::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(RawVerifyingKey::from_bytes(b"PGPF").is_err());
+    }
+
+    #[test]
+    fn test_verify_raw_malformed() {
+        let bytes = key().to_bytes().unwrap();
+        assert!(matches!(
+            verify_raw(&bytes, b"", b""),
+            Err(VerifyError::MalformedEnvelope(_))
+        ));
+    }
+}
@@ -0,0 +1,315 @@
+// Query parameter sensitivity analysis
+// For a query with `$1`, `$2`, ... placeholders, reports which parameters
+// change the proving key and which only change the witness. Each parameter is
+// varied on its own while the others keep their default values, and the
+// resulting circuits are compared with `CircuitShape` (the fingerprint the key
+// cache uses), so the report matches what `KeyCache` would actually do. The
+// query hash is left out: it is a constant of every key (instance row 4), so
+// a key serves one query text and the report shows what else a value changes.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use ff::Field;
+use pasta_curves::pallas::Base as Fr;
+
+use super::session::compiled_circuit;
+use super::CircuitShape;
+use crate::circuit::{PublicInputs, TableScan, ThresholdMode};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::sql::{SQLCompiler, SQLParser};
+
+/// Effect of a parameter on the proving key, from cheapest to most expensive
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ParameterEffect {
+    /// Only witnesses change: one key serves every value
+    Witness,
+    /// Fixed column constants change (e.g. thresholds): keygen per value,
+    /// same selectors and copies (`KeySource::StructuralMiss`)
+    Constants,
+    /// Selectors or copy constraints change: keygen per value
+    Structure,
+}
+
+impl ParameterEffect {
+    /// The key can be reused across values of the parameter
+    pub fn is_key_reusable(&self) -> bool {
+        *self == ParameterEffect::Witness
+    }
+}
+
+/// Effect of one parameter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParameterSensitivity {
+    /// Placeholder, e.g. `$1`
+    pub parameter: String,
+    pub effect: ParameterEffect,
+    /// Values compared against the default
+    pub probes: Vec<u64>,
+}
+
+/// Per-parameter effects of a query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SensitivityReport {
+    pub threshold_mode: ThresholdMode,
+    pub parameters: Vec<ParameterSensitivity>,
+}
+
+impl SensitivityReport {
+    /// One key serves every parameter value
+    pub fn is_key_reusable(&self) -> bool {
+        self.parameters.iter().all(|p| p.effect.is_key_reusable())
+    }
+
+    /// Parameters that require keygen when they change
+    pub fn rekey_parameters(&self) -> Vec<&str> {
+        self.parameters
+            .iter()
+            .filter(|p| !p.effect.is_key_reusable())
+            .map(|p| p.parameter.as_str())
+            .collect()
+    }
+}
+
+impl fmt::Display for SensitivityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for p in &self.parameters {
+            match p.effect {
+                ParameterEffect::Witness => {
+                    writeln!(f, "{}: witness only, the key is reusable", p.parameter)?
+                }
+                ParameterEffect::Constants if self.threshold_mode == ThresholdMode::Fixed => {
+                    writeln!(
+                        f,
+                        "{}: fixed constants, re-keygen per value (ThresholdMode::Instance makes thresholds public inputs)",
+                        p.parameter
+                    )?
+                }
+                ParameterEffect::Constants => {
+                    writeln!(f, "{}: fixed constants, re-keygen per value", p.parameter)?
+                }
+                ParameterEffect::Structure => writeln!(
+                    f,
+                    "{}: circuit structure, re-keygen per value (consider fixing it in the query)",
+                    p.parameter
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Query text with `$1`, `$2`, ... placeholders
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let query = ParameterizedQuery::new("SELECT price FROM orders WHERE qty < $1");
+/// let report = query.sensitivity(&table_data, &[10], ThresholdMode::Instance)?;
+/// assert!(report.is_key_reusable());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParameterizedQuery {
+    sql: String,
+    /// Highest placeholder index
+    num_parameters: usize,
+}
+
+impl ParameterizedQuery {
+    pub fn new(sql: &str) -> Self {
+        let num_parameters = Self::placeholders(sql)
+            .iter()
+            .map(|&(_, _, index)| index)
+            .max()
+            .unwrap_or(0);
+        Self {
+            sql: sql.to_string(),
+            num_parameters,
+        }
+    }
+
+    pub fn num_parameters(&self) -> usize {
+        self.num_parameters
+    }
+
+    /// Query text with `$i` replaced by `values[i - 1]`
+    pub fn bind(&self, values: &[u64]) -> PoneglyphResult<String> {
+        if values.len() != self.num_parameters {
+            return Err(PoneglyphError::InvalidInput(format!(
+                "query has {} parameters, {} values given",
+                self.num_parameters,
+                values.len()
+            )));
+        }
+        let mut sql = String::with_capacity(self.sql.len());
+        let mut last = 0;
+        for (start, end, index) in Self::placeholders(&self.sql) {
+            sql.push_str(&self.sql[last..start]);
+            sql.push_str(&values[index - 1].to_string());
+            last = end;
+        }
+        sql.push_str(&self.sql[last..]);
+        Ok(sql)
+    }
+
+    /// Effect of each parameter on the proving key
+    /// `defaults` are representative values; each parameter is probed with
+    /// nearby and distant values while the others keep their default
+    pub fn sensitivity(
+        &self,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+        defaults: &[u64],
+        threshold_mode: ThresholdMode,
+    ) -> PoneglyphResult<SensitivityReport> {
+        let baseline = self.shape(table_data, defaults, threshold_mode)?;
+
+        let mut parameters = Vec::with_capacity(self.num_parameters);
+        for i in 0..self.num_parameters {
+            let mut effect = ParameterEffect::Witness;
+            let mut probes = Vec::new();
+            for probe in Self::probe_values(defaults[i]) {
+                let mut values = defaults.to_vec();
+                values[i] = probe;
+                // Values the query cannot be compiled with are not candidates
+                let Ok(shape) = self.shape(table_data, &values, threshold_mode) else {
+                    continue;
+                };
+                probes.push(probe);
+                let probe_effect = if shape.structure != baseline.structure {
+                    ParameterEffect::Structure
+                } else if shape.constants != baseline.constants {
+                    ParameterEffect::Constants
+                } else {
+                    ParameterEffect::Witness
+                };
+                effect = effect.max(probe_effect);
+            }
+            parameters.push(ParameterSensitivity {
+                parameter: format!("${}", i + 1),
+                effect,
+                probes,
+            });
+        }
+
+        Ok(SensitivityReport {
+            threshold_mode,
+            parameters,
+        })
+    }
+
+    fn shape(
+        &self,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+        values: &[u64],
+        threshold_mode: ThresholdMode,
+    ) -> PoneglyphResult<CircuitShape> {
+        let query = SQLParser::parse(&self.bind(values)?).map_err(PoneglyphError::InvalidInput)?;
+        let compiled =
+            SQLCompiler::compile(&query, table_data).map_err(PoneglyphError::InvalidInput)?;
+        // Public input values are witnesses of the instance column
+        let public_inputs = PublicInputs::new(Fr::ZERO, Fr::ZERO);
+        let scan = Self::scan(table_data.get(&query.from));
+        let circuit = compiled_circuit(compiled, scan, threshold_mode, &public_inputs);
+        CircuitShape::of(&circuit).map_err(|e| PoneglyphError::Synthesis(format!("{:?}", e)))
+    }
+
+    /// Scan of the FROM table; only its size shapes the circuit
+    fn scan(table: Option<&HashMap<String, Vec<u64>>>) -> TableScan {
+        let Some(table) = table else {
+            return TableScan::default();
+        };
+        let mut columns: Vec<&String> = table.keys().collect();
+        columns.sort();
+        let rows = columns.first().map_or(0, |c| table[*c].len());
+        TableScan::new(
+            columns.iter().map(|c| c.to_string()).collect(),
+            (0..rows)
+                .map(|r| columns.iter().map(|c| table[*c].get(r).copied().unwrap_or(0)).collect())
+                .collect(),
+        )
+    }
+
+    /// Probe values around a default: neighbours, half, double and zero
+    fn probe_values(default: u64) -> Vec<u64> {
+        let mut probes = vec![
+            default.saturating_add(1),
+            default.saturating_sub(1),
+            default / 2,
+            default.saturating_mul(2).saturating_add(1),
+            0,
+        ];
+        probes.sort_unstable();
+        probes.dedup();
+        probes.retain(|&p| p != default);
+        probes
+    }
+
+    /// (start, end, index) of each `$index` placeholder
+    fn placeholders(sql: &str) -> Vec<(usize, usize, usize)> {
+        let bytes = sql.as_bytes();
+        let mut placeholders = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'$' {
+                let end = (i + 1..bytes.len())
+                    .find(|&j| !bytes[j].is_ascii_digit())
+                    .unwrap_or(bytes.len());
+                if let Ok(index) = sql[i + 1..end].parse::<usize>() {
+                    if index > 0 {
+                        placeholders.push((i, end, index));
+                    }
+                }
+                i = end.max(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+        placeholders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_data() -> HashMap<String, HashMap<String, Vec<u64>>> {
+        let mut orders = HashMap::new();
+        orders.insert("qty".to_string(), vec![3, 8, 12]);
+        orders.insert("price".to_string(), vec![30, 70, 20]);
+        let mut table_data = HashMap::new();
+        table_data.insert("orders".to_string(), orders);
+        table_data
+    }
+
+    #[test]
+    fn test_bind() {
+        let query =
+            ParameterizedQuery::new("SELECT price FROM orders WHERE qty < $2 AND price > $1");
+        assert_eq!(query.num_parameters(), 2);
+        assert_eq!(
+            query.bind(&[5, 10]).unwrap(),
+            "SELECT price FROM orders WHERE qty < 10 AND price > 5"
+        );
+        assert!(query.bind(&[5]).is_err());
+    }
+
+    #[test]
+    fn test_threshold_sensitivity() {
+        let query = ParameterizedQuery::new("SELECT price FROM orders WHERE qty < $1");
+
+        // Fixed thresholds are key constants
+        let report = query
+            .sensitivity(&table_data(), &[10], ThresholdMode::Fixed)
+            .unwrap();
+        assert_eq!(report.parameters[0].effect, ParameterEffect::Constants);
+        assert_eq!(report.rekey_parameters(), vec!["$1"]);
+        assert!(report.to_string().contains("ThresholdMode::Instance"));
+
+        // Public thresholds only change the witness
+        let report = query
+            .sensitivity(&table_data(), &[10], ThresholdMode::Instance)
+            .unwrap();
+        assert_eq!(report.parameters[0].effect, ParameterEffect::Witness);
+        assert!(report.is_key_reusable());
+    }
+}
@@ -0,0 +1,262 @@
+// Proof and key serialization
+// Proofs travel as versioned byte strings (magic, format version, bincode
+// body), so a proof made by one process can be verified by another.
+//
+// halo2_proofs 0.3 has no reader for verifying keys, so a serialized key is
+// its header: format version, circuit size and key fingerprint. The receiving
+// process rebuilds the key from the circuit shape (`keygen_vk`, parameters
+// from `k`) and only accepts it if the fingerprint matches.
+//
+// Public inputs travel as the canonical encodings of their instance column
+// values (`PublicInputs::to_bytes`).
+//
+// With the `proof-serde` feature, `Proof` and `VerifyingKeyHeader` also
+// implement serde's `Serialize` / `Deserialize` (e.g. for JSON APIs).
+
+use halo2_proofs::{
+    pasta::EqAffine,
+    plonk::{keygen_vk, VerifyingKey},
+    poly::commitment::Params,
+};
+
+use ff::PrimeField;
+use pasta_curves::pallas::Base as Fr;
+
+use super::Proof;
+use crate::circuit::{PoneglyphCircuit, PublicInputs};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::recursive::vk_fingerprint;
+
+/// Format version of `Proof::to_bytes`
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+
+/// Format version of `VerifyingKeyBytes::to_bytes`
+pub const KEY_FORMAT_VERSION: u32 = 1;
+
+/// Format version of `PublicInputs::to_bytes`
+pub const INSTANCE_FORMAT_VERSION: u32 = 1;
+
+/// Leading bytes of a serialized proof
+const PROOF_MAGIC: &[u8; 4] = b"PGPF";
+
+/// Leading bytes of a serialized verifying key
+const KEY_MAGIC: &[u8; 4] = b"PGVK";
+
+/// Leading bytes of serialized public inputs
+const INSTANCE_MAGIC: &[u8; 4] = b"PGIN";
+
+#[derive(bincode::Encode, bincode::Decode)]
+struct ProofEnvelope {
+    version: u32,
+    proof: Proof,
+}
+
+impl Proof {
+    /// Serialize for transfer
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        let envelope = ProofEnvelope {
+            version: PROOF_FORMAT_VERSION,
+            proof: self.clone(),
+        };
+        encode(PROOF_MAGIC, &envelope)
+    }
+
+    /// Deserialize a proof from `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        let envelope: ProofEnvelope = decode(PROOF_MAGIC, "proof", bytes)?;
+        check_version("proof", envelope.version, PROOF_FORMAT_VERSION)?;
+        Ok(envelope.proof)
+    }
+}
+
+#[derive(bincode::Encode, bincode::Decode)]
+struct InstanceEnvelope {
+    version: u32,
+    /// Instance column values, `Fr::to_repr` each
+    values: Vec<[u8; 32]>,
+}
+
+impl PublicInputs {
+    /// Serialize the instance column
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        let envelope = InstanceEnvelope {
+            version: INSTANCE_FORMAT_VERSION,
+            values: self.to_instance()[0].iter().map(|v| v.to_repr()).collect(),
+        };
+        encode(INSTANCE_MAGIC, &envelope)
+    }
+
+    /// Deserialize public inputs from `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        PublicInputs::from_instance(&[decode_instance(bytes)?])
+            .ok_or_else(|| PoneglyphError::Serialization("instance does not decode".to_string()))
+    }
+}
+
+/// Instance column values of serialized public inputs
+pub(super) fn decode_instance(bytes: &[u8]) -> PoneglyphResult<Vec<Fr>> {
+    let envelope: InstanceEnvelope = decode(INSTANCE_MAGIC, "instance", bytes)?;
+    check_version("instance", envelope.version, INSTANCE_FORMAT_VERSION)?;
+    envelope
+        .values
+        .into_iter()
+        .map(|repr| Option::from(Fr::from_repr(repr)))
+        .collect::<Option<Vec<Fr>>>()
+        .ok_or_else(|| PoneglyphError::Serialization("non-canonical instance value".to_string()))
+}
+
+/// Serialized form of a verifying key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+#[cfg_attr(feature = "proof-serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerifyingKeyHeader {
+    pub version: u32,
+    /// Circuit size (2^k rows)
+    pub k: u32,
+    /// `vk_fingerprint` of the key
+    pub circuit_version: u64,
+}
+
+impl VerifyingKeyHeader {
+    /// Header of a key generated at size 2^k
+    pub fn of(vk: &VerifyingKey<EqAffine>, k: u32) -> Self {
+        Self {
+            version: KEY_FORMAT_VERSION,
+            k,
+            circuit_version: vk_fingerprint(vk),
+        }
+    }
+
+    /// Rebuild the key for `circuit` (only its shape is used)
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if the circuit gives a key with another fingerprint
+    pub fn rebuild(&self, circuit: &PoneglyphCircuit) -> PoneglyphResult<VerifyingKey<EqAffine>> {
+        check_version("verifying key", self.version, KEY_FORMAT_VERSION)?;
+        let params = Params::<EqAffine>::new(self.k);
+        let vk = keygen_vk(&params, circuit)
+            .map_err(|e| PoneglyphError::Synthesis(format!("{:?}", e)))?;
+        let found = vk_fingerprint(&vk);
+        if found != self.circuit_version {
+            return Err(PoneglyphError::Validation(format!(
+                "circuit gives key {:016x}, serialized key is {:016x}",
+                found, self.circuit_version
+            )));
+        }
+        Ok(vk)
+    }
+}
+
+/// Byte form of verifying keys
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let (pk, vk) = Prover::setup(12, &shape)?;
+/// send(vk.to_bytes(12)?);
+/// // In the verifying process:
+/// let vk = VerifyingKey::from_bytes(&received, &shape)?;
+/// ```
+pub trait VerifyingKeyBytes: Sized {
+    /// Serialize the key of size 2^k (see `VerifyingKeyHeader`)
+    fn to_bytes(&self, k: u32) -> PoneglyphResult<Vec<u8>>;
+
+    /// Rebuild a serialized key for `circuit` (see `VerifyingKeyHeader::rebuild`)
+    fn from_bytes(bytes: &[u8], circuit: &PoneglyphCircuit) -> PoneglyphResult<Self>;
+}
+
+impl VerifyingKeyBytes for VerifyingKey<EqAffine> {
+    fn to_bytes(&self, k: u32) -> PoneglyphResult<Vec<u8>> {
+        encode(KEY_MAGIC, &VerifyingKeyHeader::of(self, k))
+    }
+
+    fn from_bytes(bytes: &[u8], circuit: &PoneglyphCircuit) -> PoneglyphResult<Self> {
+        let header: VerifyingKeyHeader = decode(KEY_MAGIC, "verifying key", bytes)?;
+        header.rebuild(circuit)
+    }
+}
+
+pub(super) fn encode<T: bincode::Encode>(magic: &[u8; 4], value: &T) -> PoneglyphResult<Vec<u8>> {
+    let mut bytes = magic.to_vec();
+    bytes.extend(
+        bincode::encode_to_vec(value, bincode::config::standard())
+            .map_err(|e| PoneglyphError::Serialization(e.to_string()))?,
+    );
+    Ok(bytes)
+}
+
+pub(super) fn decode<T: bincode::Decode<()>>(
+    magic: &[u8; 4],
+    what: &str,
+    bytes: &[u8],
+) -> PoneglyphResult<T> {
+    let body = bytes
+        .strip_prefix(magic.as_slice())
+        .ok_or_else(|| PoneglyphError::Serialization(format!("not a serialized {}", what)))?;
+    let (value, read): (T, usize) = bincode::decode_from_slice(body, bincode::config::standard())
+        .map_err(|e| PoneglyphError::Serialization(e.to_string()))?;
+    if read != body.len() {
+        return Err(PoneglyphError::Serialization(format!(
+            "trailing bytes after serialized {}",
+            what
+        )));
+    }
+    Ok(value)
+}
+
+pub(super) fn check_version(what: &str, found: u32, expected: u32) -> PoneglyphResult<()> {
+    if found != expected {
+        return Err(PoneglyphError::Serialization(format!(
+            "{} format version {} is not supported (expected {})",
+            what, found, expected
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof() -> Proof {
+        Proof {
+            k: 10,
+            circuit_version: 0xfeed,
+            bytes: vec![1, 2, 3, 250],
+        }
+    }
+
+    #[test]
+    fn test_proof_roundtrip() {
+        let bytes = proof().to_bytes().unwrap();
+        assert!(bytes.starts_with(PROOF_MAGIC));
+        assert_eq!(Proof::from_bytes(&bytes).unwrap(), proof());
+
+        assert!(Proof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Proof::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Proof::from_bytes(b"PGVK").is_err());
+    }
+
+    #[test]
+    fn test_public_inputs_roundtrip() {
+        let inputs = PublicInputs::new(Fr::from(42), Fr::from(100))
+            .with_expiry(crate::circuit::ExpiryBound::Timestamp(1_700_000_000))
+            .with_thresholds(vec![10, 20]);
+        let bytes = inputs.to_bytes().unwrap();
+        assert_eq!(PublicInputs::from_bytes(&bytes).unwrap(), inputs);
+        assert!(PublicInputs::from_bytes(&proof().to_bytes().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        let envelope = ProofEnvelope {
+            version: PROOF_FORMAT_VERSION + 1,
+            proof: proof(),
+        };
+        let bytes = encode(PROOF_MAGIC, &envelope).unwrap();
+        assert!(matches!(
+            Proof::from_bytes(&bytes),
+            Err(PoneglyphError::Serialization(_))
+        ));
+    }
+}
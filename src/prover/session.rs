@@ -0,0 +1,1147 @@
+// Query session with graceful degradation
+// `Session::prove_or_execute` proves a query when every part of it is
+// constrained by `PoneglyphCircuit`, and otherwise returns the plainly
+// executed result, marked as unproved, together with the capability report
+// explaining which parts are not proven. Applications can adopt proofs query
+// by query instead of all at once.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use ff::Field;
+use halo2_proofs::{
+    circuit::Value,
+    pasta::EqAffine,
+    plonk::{keygen_vk, Error, VerifyingKey},
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+use rand_core::OsRng;
+
+use super::{
+    FaultInjector, InjectedFault, KeyCache, PipelineStage, Proof, ProofPreset, Prover,
+    RawVerifyingKey, StageLimits, WitnessProfile,
+};
+use crate::circuit::{
+    query_commitment, PoneglyphCircuit, PrivateQueryCircuit, PrivateQueryInputs, PublicInputs,
+    ResultPredicate, TableScan, ThresholdMode,
+};
+use crate::database::{CatalogEvent, CatalogEvents, DatabaseTable};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::optimization::{ExecutionPlan, Planner, QueryPlan};
+use crate::recursive::{vk_fingerprint, CycleProver};
+use crate::sql::{
+    explain, query_hash, AggregationFunction, CompiledQuery, ExplainReport, KStrategy, Lint,
+    Linter, PredicateExpr, QueryRegistry, QueryResult, ReferenceExecutor, SQLCompiler, SQLParser,
+    SQLQuery, SQLStatement, SessionSettings, WhereClause,
+};
+
+/// Part of a query the circuit does not prove
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedFeature {
+    /// SQL feature, e.g. `LIKE`
+    pub feature: String,
+    /// Why the result would not be covered by the proof
+    pub reason: String,
+}
+
+/// Which parts of a query `PoneglyphCircuit` proves
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CapabilityReport {
+    pub unsupported: Vec<UnsupportedFeature>,
+}
+
+impl CapabilityReport {
+    /// Report for a parsed and compiled query
+    pub fn of(query: &SQLQuery, compiled: &CompiledQuery) -> Self {
+        let mut report = Self::default();
+        let mut add = |feature: &str, reason: &str| {
+            report.unsupported.push(UnsupportedFeature {
+                feature: feature.to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        if let Some(predicate) = &query.predicate {
+            if !Self::is_conjunction(predicate) {
+                add(
+                    "OR / NOT",
+                    "comparisons are checked one by one, their combination is not proven",
+                );
+            }
+            for leaf in predicate.leaves() {
+                match leaf {
+                    WhereClause::GreaterThan { .. } => {
+                        add(">", "compiled as a `column < value + 1` range check")
+                    }
+                    WhereClause::Equal { .. } => {
+                        add("=", "only the upper bound `column <= value` is checked")
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if !compiled.betweens.is_empty() {
+            add("BETWEEN", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.in_lists.is_empty() {
+            add("IN", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.likes.is_empty() {
+            add("LIKE", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.arithmetic.is_empty() {
+            add("computed columns", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.windows.is_empty() {
+            add("window functions", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.subqueries.is_empty() {
+            add("subqueries", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.unions.is_empty() {
+            add("UNION", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.series.is_empty() {
+            add("generate_series", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.casts.is_empty() {
+            add("CAST", "not wired into PoneglyphCircuit");
+        }
+        if !compiled.ctes.is_empty() {
+            add(
+                "WITH",
+                "intermediate relations are committed, their queries are not wired into PoneglyphCircuit",
+            );
+        }
+        if query.joins.as_ref().is_some_and(|j| !j.is_empty()) {
+            add("JOIN", "only the rows of the FROM table are committed");
+        }
+        if query.group_by.is_some() {
+            add(
+                "GROUP BY",
+                "group boundaries are not linked to the scanned rows",
+            );
+        }
+        if query.group_by.as_ref().is_some_and(|g| g.len() > 1) {
+            add(
+                "GROUP BY",
+                "only the first grouping column is grouped in-circuit",
+            );
+        }
+        let aggregations = query.aggregations.as_deref().unwrap_or_default();
+        if aggregations.is_empty() {
+            add("result", "only an aggregation result is bound in-circuit");
+        } else if query.predicate.is_some() {
+            add(
+                "WHERE",
+                "the aggregation covers every scanned row, the filter is not applied",
+            );
+        }
+        for agg in aggregations {
+            match agg.function {
+                AggregationFunction::Avg => {
+                    add("AVG", "only the SUM is proven, the division is not")
+                }
+                AggregationFunction::Max | AggregationFunction::Min => add(
+                    "MAX / MIN",
+                    "the comparisons behind the result are not linked to the values",
+                ),
+                AggregationFunction::Median | AggregationFunction::Percentile(_) => add(
+                    "MEDIAN / PERCENTILE",
+                    "the sorted values are not linked to the scanned rows",
+                ),
+                AggregationFunction::Sum | AggregationFunction::Count => {}
+            }
+        }
+        report.unsupported.dedup();
+        report
+    }
+
+    /// Every part of the query is proven
+    pub fn is_fully_supported(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    fn is_conjunction(predicate: &PredicateExpr) -> bool {
+        match predicate {
+            PredicateExpr::Compare(_) => true,
+            PredicateExpr::And(l, r) => Self::is_conjunction(l) && Self::is_conjunction(r),
+            PredicateExpr::Or(..) | PredicateExpr::Not(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for CapabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_fully_supported() {
+            return write!(f, "fully supported");
+        }
+        writeln!(f, "not proven:")?;
+        for item in &self.unsupported {
+            writeln!(f, "  {}: {}", item.feature, item.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of `Session::prove_or_execute`
+#[derive(Clone, Debug)]
+pub enum QueryOutcome {
+    /// Result covered by a proof over the given public inputs
+    Proved {
+        result: QueryResult,
+        proof: Vec<u8>,
+        public_inputs: PublicInputs,
+    },
+    /// Plainly executed result, NOT covered by any proof
+    Unproved {
+        result: QueryResult,
+        report: CapabilityReport,
+    },
+}
+
+impl QueryOutcome {
+    pub fn result(&self) -> &QueryResult {
+        match self {
+            QueryOutcome::Proved { result, .. } | QueryOutcome::Unproved { result, .. } => result,
+        }
+    }
+
+    pub fn is_proved(&self) -> bool {
+        matches!(self, QueryOutcome::Proved { .. })
+    }
+}
+
+/// Result of `Session::prove_predicate`
+#[derive(Clone, Debug)]
+pub struct PredicateProof {
+    pub predicate: ResultPredicate,
+    /// Whether the predicate holds for the (private) result
+    pub holds: bool,
+    pub proof: Vec<u8>,
+    /// Instance row 1 is the outcome, see `QueryResult::expected_predicate_inputs`
+    pub public_inputs: PublicInputs,
+}
+
+/// Result of `Session::prove_portable`
+#[derive(Clone, Debug)]
+pub struct PortableProof {
+    pub result: QueryResult,
+    pub proof: Proof,
+    pub verifying_key: RawVerifyingKey,
+    pub public_inputs: PublicInputs,
+}
+
+/// Result of `Session::prove_private_query`
+#[derive(Clone, Debug)]
+pub struct PrivateQueryProof {
+    pub result: QueryResult,
+    pub proof: Vec<u8>,
+    /// Instance row 4 is the salted query commitment
+    pub inputs: PrivateQueryInputs,
+}
+
+/// Query session over registered tables
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let mut session = Session::new(12);
+/// session.register_table(orders);
+/// match session.prove_or_execute("SELECT SUM(price) FROM orders")? {
+///     QueryOutcome::Proved { result, proof, .. } => publish(result, proof),
+///     QueryOutcome::Unproved { result, report } => warn!("{}", report),
+/// }
+/// ```
+///
+/// `SET` statements passed to `execute` change the session's settings (see
+/// `SessionSettings`):
+///
+/// ```rust,ignore
+/// session.execute("SET privacy_level = 'hide_row_counts'")?;
+/// session.execute("SET max_k = 20")?;
+/// session.execute("SET preset = 'small-proof'")?;
+/// ```
+pub struct Session {
+    params: Params<EqAffine>,
+    tables: HashMap<String, DatabaseTable>,
+    /// Table name -> version (1 on first registration, +1 per replacement)
+    versions: HashMap<String, u64>,
+    keys: KeyCache,
+    events: CatalogEvents,
+    settings: SessionSettings,
+    /// Faults injected into the pipeline stages (testing only)
+    faults: Option<Arc<FaultInjector>>,
+    /// Concurrency limits shared with other sessions
+    limits: Option<StageLimits>,
+}
+
+impl Session {
+    /// Create a session with fresh parameters of size 2^k
+    pub fn new(k: u32) -> Self {
+        Self::with_params(Params::new(k))
+    }
+
+    /// Create a session with existing parameters
+    pub fn with_params(params: Params<EqAffine>) -> Self {
+        Self {
+            params,
+            tables: HashMap::new(),
+            versions: HashMap::new(),
+            keys: KeyCache::new(),
+            events: CatalogEvents::new(),
+            settings: SessionSettings::default(),
+            faults: None,
+            limits: None,
+        }
+    }
+
+    pub fn params(&self) -> &Params<EqAffine> {
+        &self.params
+    }
+
+    /// Proving keys of the queries proved so far (e.g. to `save` the index)
+    pub fn key_cache(&self) -> &KeyCache {
+        &self.keys
+    }
+
+    /// Replace the key cache (e.g. with a loaded index or a capacity)
+    pub fn set_key_cache(&mut self, keys: KeyCache) {
+        self.keys = keys;
+    }
+
+    pub fn settings(&self) -> &SessionSettings {
+        &self.settings
+    }
+
+    /// Apply the session part of a proving preset (see `ProofPreset`)
+    pub fn set_preset(&mut self, preset: ProofPreset) {
+        preset.apply(&mut self.settings);
+    }
+
+    /// Inject faults into the parse, plan, keygen and prove stages
+    /// (see `FaultInjector`)
+    pub fn set_faults(&mut self, faults: Arc<FaultInjector>) {
+        self.faults = Some(faults);
+    }
+
+    /// Share per-stage concurrency limits with other sessions
+    /// (witness generation, keygen and proving, see `ProverConfig`)
+    pub fn set_limits(&mut self, limits: StageLimits) {
+        self.limits = Some(limits);
+    }
+
+    /// Run a SET statement or a query
+    /// Returns the query outcome, or None for a SET statement
+    pub fn execute(&mut self, sql: &str) -> PoneglyphResult<Option<QueryOutcome>> {
+        match SQLParser::parse_statement(sql, self.settings.parse_mode)
+            .map_err(PoneglyphError::InvalidInput)?
+        {
+            SQLStatement::Set(set) if set.name == "preset" => {
+                let name = set.value.as_deref().unwrap_or_default();
+                let preset = ProofPreset::from_name(name).ok_or_else(|| {
+                    PoneglyphError::Configuration(format!("Unknown preset: {}", name))
+                })?;
+                self.set_preset(preset);
+                Ok(None)
+            }
+            SQLStatement::Set(set) => {
+                self.settings
+                    .apply(&set)
+                    .map_err(PoneglyphError::Configuration)?;
+                Ok(None)
+            }
+            SQLStatement::Query(_) => self.prove_or_execute(sql).map(Some),
+        }
+    }
+
+    /// Register (or replace) a table, queried by its name
+    /// Publishes `VersionBumped` when a table is replaced, then `TableCommitted`
+    pub fn register_table(&mut self, table: DatabaseTable) {
+        let name = table.name.to_lowercase();
+        let commitment = table.commit().commitment();
+        let version = match self.versions.get(&name) {
+            Some(&from) => {
+                self.events.publish(CatalogEvent::VersionBumped {
+                    table: name.clone(),
+                    from,
+                    to: from + 1,
+                });
+                from + 1
+            }
+            None => 1,
+        };
+        self.versions.insert(name.clone(), version);
+        self.tables.insert(name.clone(), table);
+        self.events.publish(CatalogEvent::TableCommitted {
+            table: name,
+            version,
+            commitment,
+        });
+    }
+
+    /// Current version of a table (None if not registered)
+    pub fn table_version(&self, name: &str) -> Option<u64> {
+        self.versions.get(&name.to_lowercase()).copied()
+    }
+
+    /// Registered tables with their versions, in no particular order
+    pub fn registered_tables(&self) -> impl Iterator<Item = (&DatabaseTable, u64)> + '_ {
+        self.tables
+            .iter()
+            .map(move |(name, table)| (table, self.versions.get(name).copied().unwrap_or(1)))
+    }
+
+    /// Subscribe to catalog change events (see `CatalogEvent`)
+    /// Result caches keyed by (query, table version) invalidate on
+    /// `VersionBumped` instead of polling commitments
+    pub fn subscribe(&self) -> std::sync::mpsc::Receiver<CatalogEvent> {
+        self.events.subscribe()
+    }
+
+    /// Capability report of a query, without executing it
+    pub fn capabilities(&self, sql: &str) -> PoneglyphResult<CapabilityReport> {
+        let (query, compiled, _) = self.compile(sql)?;
+        Ok(CapabilityReport::of(&query, &compiled))
+    }
+
+    /// Cost report of a query over the registered tables (see `sql::explain`)
+    pub fn explain(&self, sql: &str) -> PoneglyphResult<ExplainReport> {
+        let query = SQLParser::parse_with_mode(sql, self.settings.parse_mode)
+            .map_err(PoneglyphError::InvalidInput)?;
+        explain(&query, &self.table_data()).map_err(PoneglyphError::InvalidInput)
+    }
+
+    /// Lints of a query at the session's lint levels (see `sql::lint`)
+    /// Fails like the query would: on parse errors and denied lints
+    pub fn lint(&self, sql: &str) -> PoneglyphResult<Vec<Lint>> {
+        let query = SQLParser::parse_with_mode(sql, self.settings.parse_mode)
+            .map_err(PoneglyphError::InvalidInput)?;
+        self.settings
+            .lints
+            .check(Linter::new(&self.table_data()).lint(&query))
+            .map_err(PoneglyphError::Validation)
+    }
+
+    /// Witness generation of a query, timed per operator and region
+    /// Nothing is committed, so this costs a fraction of a proof; use it to
+    /// find the operators that dominate before proving. Unsupported parts of
+    /// the query are not in the circuit and not in the profile.
+    pub fn dry_run(&self, sql: &str) -> PoneglyphResult<WitnessProfile> {
+        let _witness_generation = self.limits.as_ref().map(StageLimits::witness_generation);
+        let (query, compiled, _) = self.compile(sql)?;
+        let scan = self.table_scan(&query.from)?;
+        let db_commitment = scan.commitment();
+
+        // Public input values don't change the cost
+        let public_inputs = PublicInputs::new(db_commitment, Fr::from(0));
+        let circuit = compiled_circuit(compiled, scan, ThresholdMode::Fixed, &public_inputs);
+        WitnessProfile::measure(&circuit)
+            .map_err(|e| PoneglyphError::Synthesis(format!("Dry run failed: {:?}", e)))
+    }
+
+    /// Prove the query if it is fully supported, otherwise execute it
+    /// A failure while proving a supported query also degrades to an
+    /// unproved result (the failure is added to the report)
+    pub fn prove_or_execute(&mut self, sql: &str) -> PoneglyphResult<QueryOutcome> {
+        let witness_generation = self.limits.as_ref().map(StageLimits::witness_generation);
+        let (query, compiled, plan) = self.compile(sql)?;
+        let result = ReferenceExecutor::execute(&query, &self.table_data())
+            .map_err(PoneglyphError::InvalidInput)?;
+        let mut report = CapabilityReport::of(&query, &compiled);
+
+        // Only a single value fits the query result instance row
+        let query_result = match result.rows.as_slice() {
+            [row] if row.len() == 1 => row[0],
+            _ => {
+                report.unsupported.push(UnsupportedFeature {
+                    feature: "result".to_string(),
+                    reason: "only a single-value result is bound to the public inputs".to_string(),
+                });
+                None
+            }
+        };
+        if query_result.is_none() || !report.is_fully_supported() {
+            return Ok(QueryOutcome::Unproved { result, report });
+        }
+
+        if let Err(reason) = self.fit_params(&query, &compiled, &plan) {
+            report.unsupported.push(UnsupportedFeature {
+                feature: "size".to_string(),
+                reason,
+            });
+            return Ok(QueryOutcome::Unproved { result, report });
+        }
+
+        let scan = self.table_scan(&query.from)?;
+        let db_commitment = scan.commitment();
+        let public_inputs = result
+            .expected_public_inputs(db_commitment, sql)
+            .map_err(PoneglyphError::InvalidInput)?;
+        let circuit = compiled_circuit(compiled, scan, ThresholdMode::Fixed, &public_inputs);
+        drop(witness_generation);
+
+        match self.prove_circuit(&circuit, &public_inputs) {
+            Ok(proof) => Ok(QueryOutcome::Proved {
+                result,
+                proof,
+                public_inputs,
+            }),
+            Err(e) => {
+                report.unsupported.push(UnsupportedFeature {
+                    feature: "proof".to_string(),
+                    reason: format!("proof generation failed: {:?}", e),
+                });
+                Ok(QueryOutcome::Unproved { result, report })
+            }
+        }
+    }
+
+    /// Prove whether `predicate` holds for the result of `sql` without
+    /// revealing the result (selective disclosure, see
+    /// `PoneglyphCircuit::result_predicate`)
+    /// Unlike `prove_or_execute` there is no unproved fallback: the query must
+    /// be fully supported and have a single-value aggregation result.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// // "Revenue is above 1M", without the revenue
+    /// let disclosure = session.prove_predicate(
+    ///     "SELECT SUM(price) FROM orders",
+    ///     ResultPredicate::Above(1_000_000),
+    /// )?;
+    /// publish(disclosure.holds, disclosure.proof);
+    /// ```
+    pub fn prove_predicate(
+        &mut self,
+        sql: &str,
+        predicate: ResultPredicate,
+    ) -> PoneglyphResult<PredicateProof> {
+        let witness_generation = self.limits.as_ref().map(StageLimits::witness_generation);
+        let (query, compiled, plan) = self.compile(sql)?;
+        let result = ReferenceExecutor::execute(&query, &self.table_data())
+            .map_err(PoneglyphError::InvalidInput)?;
+        let report = CapabilityReport::of(&query, &compiled);
+        if !report.is_fully_supported() {
+            return Err(PoneglyphError::Validation(report.to_string()));
+        }
+        let value = result.bound_value().ok_or_else(|| {
+            PoneglyphError::InvalidInput(
+                "only a single non-NULL value can be compared with a predicate".to_string(),
+            )
+        })?;
+        if compiled.aggregations.is_empty() {
+            return Err(PoneglyphError::InvalidInput(
+                "only an aggregation result can be compared with a predicate".to_string(),
+            ));
+        }
+        self.fit_params(&query, &compiled, &plan)
+            .map_err(PoneglyphError::Configuration)?;
+
+        let scan = self.table_scan(&query.from)?;
+        let db_commitment = scan.commitment();
+        let public_inputs = result
+            .expected_predicate_inputs(db_commitment, sql, predicate)
+            .map_err(PoneglyphError::InvalidInput)?;
+        let mut circuit = compiled_circuit(compiled, scan, ThresholdMode::Fixed, &public_inputs);
+        circuit.result_predicate = Some(predicate);
+        drop(witness_generation);
+
+        let proof = self
+            .prove_circuit(&circuit, &public_inputs)
+            .map_err(|e| PoneglyphError::Synthesis(format!("Proof generation failed: {:?}", e)))?;
+        Ok(PredicateProof {
+            predicate,
+            holds: predicate.holds(value),
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Prove `sql` for a verifier in another process
+    /// The proof comes with a raw verifying key, so the verifier needs neither
+    /// the tables nor the query (see `verify_raw`). Like `prove_predicate`
+    /// there is no unproved fallback.
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if the query is not fully supported, `InvalidInput` if its
+    /// result is not a single value
+    pub fn prove_portable(&mut self, sql: &str) -> PoneglyphResult<PortableProof> {
+        let witness_generation = self.limits.as_ref().map(StageLimits::witness_generation);
+        let (query, compiled, plan) = self.compile(sql)?;
+        let result = ReferenceExecutor::execute(&query, &self.table_data())
+            .map_err(PoneglyphError::InvalidInput)?;
+        let report = CapabilityReport::of(&query, &compiled);
+        if !report.is_fully_supported() {
+            return Err(PoneglyphError::Validation(report.to_string()));
+        }
+        self.fit_params(&query, &compiled, &plan)
+            .map_err(PoneglyphError::Configuration)?;
+
+        let scan = self.table_scan(&query.from)?;
+        let db_commitment = scan.commitment();
+        let public_inputs = result
+            .expected_public_inputs(db_commitment, sql)
+            .map_err(PoneglyphError::InvalidInput)?;
+        let circuit = compiled_circuit(compiled, scan, ThresholdMode::Fixed, &public_inputs);
+        drop(witness_generation);
+
+        let synthesis =
+            |e: Error| PoneglyphError::Synthesis(format!("Proof generation failed: {:?}", e));
+        let bytes = self
+            .prove_circuit(&circuit, &public_inputs)
+            .map_err(synthesis)?;
+        let k = self.params.k();
+        // Cached by `prove_circuit`
+        let (pk, _) = self
+            .keys
+            .proving_key(&self.params, &circuit)
+            .map_err(synthesis)?;
+        let verifying_key = RawVerifyingKey::of(k, &circuit, pk.get_vk())?;
+        Ok(PortableProof {
+            result,
+            proof: Proof {
+                k,
+                circuit_version: vk_fingerprint(pk.get_vk()),
+                bytes,
+            },
+            verifying_key,
+            public_inputs,
+        })
+    }
+
+    /// Verifying key of query-private proofs of `sql` over this session's
+    /// tables, with a registry of `depth`
+    /// The registry authorizes `sql` with `vk_fingerprint` of this key, and
+    /// verifiers check proofs with it (`Verifier::verify_private_query`).
+    pub fn private_query_key(
+        &mut self,
+        sql: &str,
+        depth: usize,
+    ) -> PoneglyphResult<VerifyingKey<EqAffine>> {
+        let (query, compiled, plan) = self.compile(sql)?;
+        self.fit_params(&query, &compiled, &plan)
+            .map_err(PoneglyphError::Configuration)?;
+        let scan = self.table_scan(&query.from)?;
+        let public_inputs = PublicInputs::new(scan.commitment(), Fr::ZERO);
+        let circuit = compiled_circuit(compiled, scan, ThresholdMode::Fixed, &public_inputs);
+        keygen_vk(&self.params, &PrivateQueryCircuit::shape(circuit, depth))
+            .map_err(|e| PoneglyphError::Synthesis(format!("Key generation failed: {:?}", e)))
+    }
+
+    /// Prove `sql` without revealing it: the proof only shows that some query
+    /// authorized in `registry` was run (see `PrivateQueryCircuit`)
+    /// Like `prove_predicate` there is no unproved fallback.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let vk = session.private_query_key(sql, registry.depth())?;
+    /// registry.authorize(sql, vk_fingerprint(&vk))?;
+    /// let private = session.prove_private_query(sql, &registry)?;
+    /// Verifier::from_verifying_key(vk).verify_private_query(
+    ///     &params,
+    ///     &private.proof,
+    ///     &private.inputs.to_instance(),
+    ///     registry.root(),
+    /// )?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if the query is not fully supported or not authorized,
+    /// `InvalidInput` if its result is not a single value
+    pub fn prove_private_query(
+        &mut self,
+        sql: &str,
+        registry: &QueryRegistry,
+    ) -> PoneglyphResult<PrivateQueryProof> {
+        let witness_generation = self.limits.as_ref().map(StageLimits::witness_generation);
+        let (query, compiled, plan) = self.compile(sql)?;
+        let result = ReferenceExecutor::execute(&query, &self.table_data())
+            .map_err(PoneglyphError::InvalidInput)?;
+        let report = CapabilityReport::of(&query, &compiled);
+        if !report.is_fully_supported() {
+            return Err(PoneglyphError::Validation(report.to_string()));
+        }
+        self.fit_params(&query, &compiled, &plan)
+            .map_err(PoneglyphError::Configuration)?;
+
+        let scan = self.table_scan(&query.from)?;
+        let db_commitment = scan.commitment();
+        let public_inputs = result
+            .expected_public_inputs(db_commitment, sql)
+            .map_err(PoneglyphError::InvalidInput)?;
+        let circuit = compiled_circuit(compiled, scan, ThresholdMode::Fixed, &public_inputs);
+        drop(witness_generation);
+
+        let synthesis =
+            |e: Error| PoneglyphError::Synthesis(format!("Proof generation failed: {:?}", e));
+        let limits = self.limits.as_ref();
+        let faults = self.faults.as_deref();
+        inject(faults, PipelineStage::Keygen)?;
+        let prover = {
+            let _keygen = limits.map(StageLimits::keygen);
+            let shape = PrivateQueryCircuit::shape(circuit.clone(), registry.depth());
+            CycleProver::<EqAffine>::new(&self.params, &shape).map_err(synthesis)?
+        };
+        let circuit_fingerprint = vk_fingerprint(prover.vk());
+        let salt = Fr::random(OsRng);
+        let private = registry.private_circuit(sql, circuit, salt, circuit_fingerprint)?;
+        let inputs = PrivateQueryInputs {
+            public_inputs: public_inputs.with_query_hash(query_commitment(query_hash(sql), salt)),
+            registry_root: registry.root(),
+            circuit_fingerprint,
+        };
+
+        inject(faults, PipelineStage::Prove)?;
+        let _prove = limits.map(StageLimits::prove);
+        let proof = prover
+            .prove(&self.params, &private, &inputs.to_instance())
+            .map_err(synthesis)?;
+        Ok(PrivateQueryProof {
+            result,
+            proof,
+            inputs,
+        })
+    }
+
+    /// Rows of a registered table, as the circuit hashes them into its
+    /// commitment
+    fn table_scan(&self, table: &str) -> PoneglyphResult<TableScan> {
+        self.tables
+            .get(table)
+            .map(|t| {
+                let columns = t.columns.iter().map(|c| c.to_lowercase()).collect();
+                TableScan::new(columns, t.data.clone())
+            })
+            .ok_or_else(|| PoneglyphError::InvalidInput(format!("Table {} not found", table)))
+    }
+
+    /// Size the parameters for the planned circuit per `k_strategy`: with
+    /// max_k set, grow them to fit; with `minimal`, set them to the planned
+    /// size
+    /// Err with the reason if it needs more than 2^max_k rows
+    fn fit_params(
+        &mut self,
+        query: &SQLQuery,
+        compiled: &CompiledQuery,
+        plan: &ExecutionPlan,
+    ) -> Result<(), String> {
+        let strategy = self.settings.k_strategy;
+        if self.settings.max_k.is_none() && strategy == KStrategy::Grow {
+            return Ok(());
+        }
+        let k = QueryPlan::planned(query, compiled, plan).min_k();
+        if let Some(max_k) = self.settings.max_k {
+            if k > max_k {
+                return Err(format!(
+                    "circuit needs about 2^{} rows, above max_k = {}",
+                    k, max_k
+                ));
+            }
+        }
+        let resize = match strategy {
+            KStrategy::Grow => k > self.params.k(),
+            KStrategy::Minimal => k != self.params.k(),
+        };
+        if resize {
+            self.params = Params::new(k);
+        }
+        Ok(())
+    }
+
+    /// Keygen (or a cached key) and proof, under the stage limits and
+    /// injected faults
+    fn prove_circuit(
+        &mut self,
+        circuit: &PoneglyphCircuit,
+        public_inputs: &PublicInputs,
+    ) -> Result<Vec<u8>, halo2_proofs::plonk::Error> {
+        let limits = self.limits.as_ref();
+        let faults = self.faults.as_deref();
+        inject(faults, PipelineStage::Keygen)?;
+        let (pk, _) = {
+            let _keygen = limits.map(StageLimits::keygen);
+            self.keys.proving_key(&self.params, circuit)?
+        };
+        inject(faults, PipelineStage::Prove)?;
+        let _prove = limits.map(StageLimits::prove);
+        Prover::from_proving_key(pk.clone()).prove(
+            &self.params,
+            circuit,
+            &public_inputs.to_instance(),
+        )
+    }
+
+    /// Parse, plan and compile `sql` (in the planner's operator order)
+    fn compile(&self, sql: &str) -> PoneglyphResult<(SQLQuery, CompiledQuery, ExecutionPlan)> {
+        let faults = self.faults.as_deref();
+        inject(faults, PipelineStage::Parse)?;
+        let query = SQLParser::parse_with_mode(sql, self.settings.parse_mode)
+            .map_err(PoneglyphError::InvalidInput)?;
+        self.settings
+            .privacy_level
+            .check(&query)
+            .map_err(PoneglyphError::Validation)?;
+        let table_data = self.table_data();
+        self.settings
+            .lints
+            .check(Linter::new(&table_data).lint(&query))
+            .map_err(PoneglyphError::Validation)?;
+        inject(faults, PipelineStage::Plan)?;
+        let plan = Planner::new(&table_data)
+            .plan(&query)
+            .map_err(PoneglyphError::InvalidInput)?;
+        let compiled = SQLCompiler::compile_planned(&query, &table_data, &plan)
+            .map_err(PoneglyphError::InvalidInput)?;
+        Ok((query, compiled, plan))
+    }
+
+    fn table_data(&self) -> HashMap<String, HashMap<String, Vec<u64>>> {
+        self.tables
+            .iter()
+            .map(|(name, table)| {
+                let columns = table
+                    .table_data()
+                    .into_iter()
+                    .map(|(column, values)| (column.to_lowercase(), values))
+                    .collect();
+                (name.clone(), columns)
+            })
+            .collect()
+    }
+}
+
+/// Apply the injected fault of a stage, if any
+fn inject(faults: Option<&FaultInjector>, stage: PipelineStage) -> Result<(), InjectedFault> {
+    faults.map_or(Ok(()), |faults| faults.check(stage))
+}
+
+/// `PoneglyphCircuit` for the wired operations of a compiled query
+pub(crate) fn compiled_circuit(
+    compiled: CompiledQuery,
+    scan: TableScan,
+    threshold_mode: ThresholdMode,
+    public_inputs: &PublicInputs,
+) -> PoneglyphCircuit {
+    PoneglyphCircuit {
+        scan,
+        query_result: Value::known(public_inputs.query_result),
+        nonce: Value::known(public_inputs.nonce),
+        expiry: Value::known(public_inputs.expiry.to_field()),
+        query_hash: public_inputs.query_hash,
+        result_commitment: Value::known(public_inputs.result_commitment),
+        result_columns: compiled.result_columns,
+        result_predicate: None,
+        threshold_mode,
+        range_checks: compiled.range_checks,
+        sorts: compiled.sorts,
+        group_bys: compiled.group_bys,
+        joins: compiled.joins,
+        aggregations: compiled.aggregations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders() -> DatabaseTable {
+        let mut table = DatabaseTable::new(
+            "orders".to_string(),
+            vec!["id".to_string(), "price".to_string()],
+        );
+        table.insert(vec![1, 30]);
+        table.insert(vec![2, 70]);
+        table
+    }
+
+    #[test]
+    fn test_capability_report() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+
+        let report = session
+            .capabilities("SELECT id FROM orders WHERE price < 50 OR id IN (2, 3)")
+            .unwrap();
+        let features: Vec<_> = report
+            .unsupported
+            .iter()
+            .map(|u| u.feature.as_str())
+            .collect();
+        assert_eq!(features, vec!["OR / NOT", "IN", "result"]);
+        assert!(report.to_string().contains("not proven"));
+
+        // The aggregation is bound to the scanned rows, not to the filter
+        let report = session
+            .capabilities("SELECT SUM(price) FROM orders WHERE price < 50")
+            .unwrap();
+        assert_eq!(report.unsupported[0].feature, "WHERE");
+        assert!(session
+            .capabilities("SELECT SUM(price) FROM orders")
+            .unwrap()
+            .is_fully_supported());
+    }
+
+    #[test]
+    fn test_catalog_events() {
+        let mut session = Session::new(4);
+        let events = session.subscribe();
+        session.register_table(orders());
+        let mut updated = orders();
+        updated.insert(vec![3, 10]);
+        session.register_table(updated.clone());
+
+        let events: Vec<_> = events.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[0],
+            CatalogEvent::TableCommitted { version: 1, .. }
+        ));
+        assert_eq!(
+            events[1],
+            CatalogEvent::VersionBumped {
+                table: "orders".to_string(),
+                from: 1,
+                to: 2,
+            }
+        );
+        assert_eq!(
+            events[2],
+            CatalogEvent::TableCommitted {
+                table: "orders".to_string(),
+                version: 2,
+                commitment: updated.commit().commitment(),
+            }
+        );
+        assert_eq!(session.table_version("Orders"), Some(2));
+    }
+
+    #[test]
+    fn test_unsupported_query_is_executed_unproved() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+
+        let outcome = session
+            .prove_or_execute("SELECT id FROM orders WHERE price BETWEEN 10 AND 40")
+            .unwrap();
+        assert!(!outcome.is_proved());
+        assert_eq!(outcome.result().rows, vec![vec![Some(1)]]);
+        match outcome {
+            QueryOutcome::Unproved { report, .. } => {
+                assert!(report.unsupported.iter().any(|u| u.feature == "BETWEEN"))
+            }
+            QueryOutcome::Proved { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+
+        let profile = session
+            .dry_run("SELECT SUM(price) FROM orders WHERE price > 50")
+            .unwrap();
+        let names: Vec<_> = profile
+            .operators
+            .iter()
+            .map(|op| op.name.as_str())
+            .collect();
+        assert!(names.contains(&"range check"));
+        assert!(names.contains(&"aggregation"));
+        assert!(profile.counts().advice > 0);
+
+        assert!(session.dry_run("SELECT id FROM missing").is_err());
+    }
+
+    #[test]
+    fn test_prove_predicate_needs_a_proven_value() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+        let predicate = ResultPredicate::Above(50);
+
+        // No unproved fallback: the result would be revealed
+        assert!(matches!(
+            session.prove_predicate(
+                "SELECT SUM(price) FROM orders WHERE id IN (1, 2)",
+                predicate
+            ),
+            Err(PoneglyphError::Validation(_))
+        ));
+        assert!(matches!(
+            session.prove_predicate("SELECT price FROM orders", predicate),
+            Err(PoneglyphError::Validation(_))
+        ));
+
+        session.execute("SET max_k TO 4").unwrap();
+        assert!(matches!(
+            session.prove_predicate("SELECT SUM(price) FROM orders", predicate),
+            Err(PoneglyphError::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_prove_portable_needs_a_proven_value() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+        assert!(matches!(
+            session.prove_portable("SELECT SUM(price) FROM orders WHERE id IN (1, 2)"),
+            Err(PoneglyphError::Validation(_))
+        ));
+        assert!(matches!(
+            session.prove_portable("SELECT price FROM orders"),
+            Err(PoneglyphError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_prove_private_query_needs_a_proven_value() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+        let registry = QueryRegistry::new(2);
+
+        assert!(matches!(
+            session.prove_private_query(
+                "SELECT SUM(price) FROM orders WHERE id IN (1, 2)",
+                &registry
+            ),
+            Err(PoneglyphError::Validation(_))
+        ));
+        assert!(matches!(
+            session.prove_private_query("SELECT price FROM orders", &registry),
+            Err(PoneglyphError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_stage_limits_released() {
+        use crate::prover::ProverConfig;
+
+        let limits = StageLimits::new(
+            ProverConfig::new()
+                .with_max_witness_generations(1)
+                .with_max_keygens(1),
+        )
+        .unwrap();
+        let mut session = Session::new(4);
+        session.register_table(orders());
+        session.set_limits(limits.clone());
+
+        // Early (unproved) returns give the witness generation slot back
+        for _ in 0..2 {
+            let outcome = session
+                .prove_or_execute("SELECT id FROM orders WHERE price BETWEEN 10 AND 40")
+                .unwrap();
+            assert!(!outcome.is_proved());
+        }
+        let _witness_generation = limits.witness_generation();
+        let _keygen = limits.keygen();
+    }
+
+    #[test]
+    fn test_set_statements() {
+        let mut session = Session::new(4);
+        session.register_table(orders());
+
+        assert!(session
+            .execute("SET privacy_level = 'hide_row_counts'")
+            .unwrap()
+            .is_none());
+        assert!(session.execute("SELECT COUNT(*) FROM orders").is_err());
+        assert!(session.execute("SELECT id FROM orders").is_err());
+        assert!(session.execute("SET privacy_level = 'secret'").is_err());
+
+        session.execute("SET preset = 'cheap-verify'").unwrap();
+        assert_eq!(session.settings().k_strategy, KStrategy::Minimal);
+        assert!(session.execute("SET preset = 'tiny'").is_err());
+        session.execute("SET k_strategy = DEFAULT").unwrap();
+
+        // Too small a max_k leaves the query unproved
+        session.execute("SET max_k TO 4").unwrap();
+        assert_eq!(session.settings().max_k, Some(4));
+        match session.execute("SELECT SUM(price) FROM orders").unwrap() {
+            Some(QueryOutcome::Unproved { result, report }) => {
+                assert_eq!(result.rows, vec![vec![Some(100)]]);
+                assert!(report.unsupported.iter().any(|u| u.feature == "size"));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_lint_levels() {
+        use crate::sql::LintKind;
+
+        let mut session = Session::new(4);
+        session.register_table(orders());
+        let sql = "SELECT id FROM orders LIMIT 1";
+
+        let lints = session.lint(sql).unwrap();
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].kind, LintKind::UnorderedLimit);
+        assert!(session
+            .lint("SELECT SUM(price) FROM orders")
+            .unwrap()
+            .is_empty());
+
+        session.execute("SET lint_unordered_limit = deny").unwrap();
+        assert!(matches!(
+            session.lint(sql),
+            Err(PoneglyphError::Validation(_))
+        ));
+        assert!(matches!(
+            session.capabilities(sql),
+            Err(PoneglyphError::Validation(_))
+        ));
+        session.execute("SET lint_unordered_limit = allow").unwrap();
+        assert!(session.lint(sql).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_injected_faults() {
+        use crate::prover::{FaultKind, FaultTrigger};
+
+        let faults = Arc::new(
+            FaultInjector::new()
+                .inject(
+                    PipelineStage::Parse,
+                    FaultKind::Error,
+                    FaultTrigger::First(1),
+                )
+                .inject(
+                    PipelineStage::Keygen,
+                    FaultKind::Error,
+                    FaultTrigger::Always,
+                ),
+        );
+        let mut session = Session::new(4);
+        session.register_table(orders());
+        session.set_faults(faults.clone());
+
+        let sql = "SELECT SUM(price) FROM orders";
+        assert!(matches!(
+            session.capabilities(sql),
+            Err(PoneglyphError::InvalidInput(_))
+        ));
+        assert!(session.capabilities(sql).unwrap().is_fully_supported());
+
+        // A failed keygen degrades to an unproved result, like a real failure
+        match session.prove_or_execute(sql).unwrap() {
+            QueryOutcome::Unproved { result, report } => {
+                assert_eq!(result.rows, vec![vec![Some(100)]]);
+                assert!(report.unsupported.iter().any(|u| u.feature == "proof"));
+            }
+            QueryOutcome::Proved { .. } => unreachable!(),
+        }
+        assert_eq!(faults.calls(PipelineStage::Parse), 3);
+        assert_eq!(faults.calls(PipelineStage::Keygen), 1);
+        assert_eq!(faults.calls(PipelineStage::Prove), 0);
+        assert_eq!(faults.injected().len(), 2);
+    }
+}
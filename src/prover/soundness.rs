@@ -0,0 +1,392 @@
+// Negative witness corpus for soundness regression testing
+// Each case assigns an adversarial witness straight into a gate's rows
+// (bypassing the chip's honest witness generation) and must be rejected by
+// MockProver. A case that starts verifying after a refactor means a gate
+// lost a constraint.
+
+use ff::Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+
+use crate::circuit::{
+    signed_to_field, AggregationChip, AggregationConfig, GroupByChip, JoinChip, JoinConfig,
+    PoneglyphConfig, RangeCheckChip, RangeCheckConfig, SortChip, SortConfig,
+};
+
+/// Adversarial witness
+#[derive(Clone, Debug)]
+pub enum AdversarialWitness {
+    /// Sort output that is not in order (diffs are the true field differences)
+    UnsortedOutput { output: Vec<u64> },
+    /// Sort output with a diff cell that doesn't match the output rows
+    ForgedSortDiff { output: [u64; 2], diff: u64 },
+    /// Join row flagged as a match although the keys differ
+    ForgedJoinMatch { key1: u64, key2: u64 },
+    /// Join match flag outside {0, 1}
+    NonBooleanMatchFlag { key: u64, flag: u64 },
+    /// Running SUM of one group with a wrong final result
+    OffByOneSum { values: [u64; 2], claimed: u64 },
+    /// Running COUNT of one group with a wrong final result
+    OffByOneCount { claimed: u64 },
+    /// `lo <= x < hi` claimed true for x below lo
+    ForgedBetween { x: u64, lo: u64, hi: u64 },
+    /// Signed SUM whose second value cell is outside the i64 range
+    /// (the sum itself is consistent, only the signed range check rejects it)
+    SignedSumOutOfRange { value: i64, tampered: u64 },
+}
+
+/// One corpus entry
+#[derive(Clone, Debug)]
+pub struct SoundnessCase {
+    /// Unique name (stable across changes)
+    pub name: &'static str,
+    /// What the adversary tries to prove
+    pub description: &'static str,
+    pub witness: AdversarialWitness,
+}
+
+impl SoundnessCase {
+    /// Run the case; `Ok(reason)` if it is rejected, `Err` if it verifies
+    pub fn check(&self, k: u32) -> Result<String, String> {
+        let circuit = AdversarialCircuit {
+            witness: self.witness.clone(),
+        };
+        match MockProver::run(k, &circuit, vec![vec![]]) {
+            Err(e) => Ok(format!("synthesis failed: {:?}", e)),
+            Ok(prover) => match prover.verify() {
+                Err(failures) => Ok(format!("{} constraint failure(s)", failures.len())),
+                Ok(()) => Err(format!(
+                    "soundness case '{}' verified: {}",
+                    self.name, self.description
+                )),
+            },
+        }
+    }
+
+    /// Panic unless the case is rejected
+    pub fn assert_rejected(&self, k: u32) {
+        if let Err(msg) = self.check(k) {
+            panic!("{}", msg);
+        }
+    }
+}
+
+/// Curated corpus of adversarial witnesses
+pub fn soundness_corpus() -> Vec<SoundnessCase> {
+    vec![
+        SoundnessCase {
+            name: "sort_unsorted_output",
+            description: "Sorted output [1, 3, 2]",
+            witness: AdversarialWitness::UnsortedOutput {
+                output: vec![1, 3, 2],
+            },
+        },
+        SoundnessCase {
+            name: "sort_forged_diff",
+            description: "Diff 5 between sorted outputs 1 and 3",
+            witness: AdversarialWitness::ForgedSortDiff {
+                output: [1, 3],
+                diff: 5,
+            },
+        },
+        SoundnessCase {
+            name: "join_forged_match",
+            description: "Keys 4 and 5 flagged as a match",
+            witness: AdversarialWitness::ForgedJoinMatch { key1: 4, key2: 5 },
+        },
+        SoundnessCase {
+            name: "join_non_boolean_flag",
+            description: "Match flag 2 (double-counted match)",
+            witness: AdversarialWitness::NonBooleanMatchFlag { key: 7, flag: 2 },
+        },
+        SoundnessCase {
+            name: "sum_off_by_one",
+            description: "SUM(3, 4) = 8",
+            witness: AdversarialWitness::OffByOneSum {
+                values: [3, 4],
+                claimed: 8,
+            },
+        },
+        SoundnessCase {
+            name: "count_off_by_one",
+            description: "COUNT of a two-row group = 3",
+            witness: AdversarialWitness::OffByOneCount { claimed: 3 },
+        },
+        SoundnessCase {
+            name: "between_below_range",
+            description: "5 BETWEEN 10 AND 19",
+            witness: AdversarialWitness::ForgedBetween {
+                x: 5,
+                lo: 10,
+                hi: 20,
+            },
+        },
+        SoundnessCase {
+            name: "signed_sum_out_of_range",
+            description: "Signed SUM over the value cell 2^63 (i64::MAX + 1)",
+            witness: AdversarialWitness::SignedSumOutOfRange {
+                value: -4,
+                tampered: 1 << 63,
+            },
+        },
+    ]
+}
+
+/// Circuit assigning one adversarial witness
+#[derive(Clone, Debug)]
+struct AdversarialCircuit {
+    witness: AdversarialWitness,
+}
+
+#[derive(Clone, Debug)]
+struct AdversarialConfig {
+    poneglyph_config: PoneglyphConfig,
+    range_check_config: RangeCheckConfig,
+    sort_config: SortConfig,
+    join_config: JoinConfig,
+    aggregation_config: AggregationConfig,
+}
+
+impl Circuit<Fr> for AdversarialCircuit {
+    type Config = AdversarialConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let sort_config = SortChip::configure(meta, &poneglyph_config, &range_check_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let join_config =
+            JoinChip::configure(meta, &poneglyph_config, &range_check_config, &sort_config);
+        let aggregation_config = AggregationChip::configure(
+            meta,
+            &poneglyph_config,
+            &group_by_config,
+            &range_check_config,
+        );
+
+        AdversarialConfig {
+            poneglyph_config,
+            range_check_config,
+            sort_config,
+            join_config,
+            aggregation_config,
+        }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        let range_check_chip = RangeCheckChip::new(config.range_check_config.clone());
+        let known = |v: u64| Value::known(Fr::from(v));
+
+        match &self.witness {
+            AdversarialWitness::UnsortedOutput { output } => {
+                let sort = &config.sort_config;
+                let diffs = layouter.assign_region(
+                    || "unsorted output",
+                    |mut region| {
+                        let mut diffs = Vec::new();
+                        for (i, &v) in output.iter().enumerate() {
+                            region.assign_advice(|| "output", sort.output_column, i, || known(v))?;
+                            if i + 1 < output.len() {
+                                sort.sort_selector.enable(&mut region, i)?;
+                                let diff = Fr::from(output[i + 1]) - Fr::from(v);
+                                diffs.push(region.assign_advice(
+                                    || "diff",
+                                    sort.diff_column,
+                                    i,
+                                    || Value::known(diff),
+                                )?);
+                            }
+                        }
+                        Ok(diffs)
+                    },
+                )?;
+                for diff in diffs.iter() {
+                    range_check_chip.decompose_cell(layouter.namespace(|| "diff range"), diff)?;
+                }
+            }
+            AdversarialWitness::ForgedSortDiff { output, diff } => {
+                let sort = &config.sort_config;
+                layouter.assign_region(
+                    || "forged sort diff",
+                    |mut region| {
+                        sort.sort_selector.enable(&mut region, 0)?;
+                        region.assign_advice(|| "b0", sort.output_column, 0, || known(output[0]))?;
+                        region.assign_advice(|| "b1", sort.output_column, 1, || known(output[1]))?;
+                        region.assign_advice(|| "diff", sort.diff_column, 0, || known(*diff))
+                    },
+                )?;
+            }
+            AdversarialWitness::ForgedJoinMatch { key1, key2 } => {
+                self.assign_join_row(&mut layouter, &config.join_config, *key1, *key2, 1)?;
+            }
+            AdversarialWitness::NonBooleanMatchFlag { key, flag } => {
+                self.assign_join_row(&mut layouter, &config.join_config, *key, *key, *flag)?;
+            }
+            AdversarialWitness::OffByOneSum { values, claimed } => {
+                let agg = &config.aggregation_config;
+                let selector = agg.sum_selector;
+                self.assign_group(&mut layouter, agg, selector, *values, [values[0], *claimed])?;
+            }
+            AdversarialWitness::OffByOneCount { claimed } => {
+                let agg = &config.aggregation_config;
+                let selector = agg.count_selector;
+                self.assign_group(&mut layouter, agg, selector, [1, 1], [1, *claimed])?;
+            }
+            AdversarialWitness::ForgedBetween { x, lo, hi } => {
+                let rc = &config.range_check_config;
+                let (d_lo, d_hi) = layouter.assign_region(
+                    || "forged between",
+                    |mut region| {
+                        rc.between_selector.enable(&mut region, 0)?;
+                        region.assign_advice(|| "x", rc.x_column, 0, || known(*x))?;
+                        region.assign_fixed(|| "lo", rc.threshold_column, 0, || known(*lo))?;
+                        region.assign_fixed(|| "hi", rc.u_column, 0, || known(*hi))?;
+                        region.assign_advice(|| "out", rc.check_column, 0, || known(1))?;
+                        region.assign_advice(|| "ge", rc.check_column, 1, || known(1))?;
+                        region.assign_advice(|| "lt", rc.check_column, 2, || known(1))?;
+                        let d_lo = region.assign_advice(
+                            || "d_lo",
+                            rc.x_column,
+                            1,
+                            || Value::known(Fr::from(*x) - Fr::from(*lo)),
+                        )?;
+                        let d_hi = region.assign_advice(
+                            || "d_hi",
+                            rc.x_column,
+                            2,
+                            || Value::known(Fr::from(*hi) - Fr::ONE - Fr::from(*x)),
+                        )?;
+                        Ok((d_lo, d_hi))
+                    },
+                )?;
+                range_check_chip.decompose_cell(layouter.namespace(|| "d_lo range"), &d_lo)?;
+                range_check_chip.decompose_cell(layouter.namespace(|| "d_hi range"), &d_hi)?;
+            }
+            AdversarialWitness::SignedSumOutOfRange { value, tampered } => {
+                // The range check of `aggregate_signed_and_verify`, over the
+                // value cells of a group whose running sum is correct
+                let agg = &config.aggregation_config;
+                let first = signed_to_field(*value);
+                let cells = self.assign_group_cells(
+                    &mut layouter,
+                    agg,
+                    agg.sum_selector,
+                    [first, Fr::from(*tampered)],
+                    [first, first + Fr::from(*tampered)],
+                )?;
+                for cell in &cells {
+                    range_check_chip
+                        .decompose_signed_cell(layouter.namespace(|| "signed value range"), cell)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AdversarialCircuit {
+    /// One join row (key comparison and match flag gates)
+    fn assign_join_row(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        join: &JoinConfig,
+        key1: u64,
+        key2: u64,
+        flag: u64,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "join row",
+            |mut region| {
+                join.join_selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "key1", join.table1_key_column, 0, || {
+                    Value::known(Fr::from(key1))
+                })?;
+                region.assign_advice(|| "key2", join.table2_key_column, 0, || {
+                    Value::known(Fr::from(key2))
+                })?;
+                region.assign_advice(|| "match", join.match_column, 0, || {
+                    Value::known(Fr::from(flag))
+                })?;
+                Ok(())
+            },
+        )
+    }
+
+    /// Two rows of one group (boundary 1, 0) with the aggregation gate on row 1
+    fn assign_group(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        agg: &AggregationConfig,
+        selector: halo2_proofs::plonk::Selector,
+        values: [u64; 2],
+        results: [u64; 2],
+    ) -> Result<(), Error> {
+        self.assign_group_cells(
+            layouter,
+            agg,
+            selector,
+            values.map(Fr::from),
+            results.map(Fr::from),
+        )?;
+        Ok(())
+    }
+
+    /// `assign_group` over field elements, returning the value cells
+    fn assign_group_cells(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        agg: &AggregationConfig,
+        selector: halo2_proofs::plonk::Selector,
+        values: [Fr; 2],
+        results: [Fr; 2],
+    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+        layouter.assign_region(
+            || "aggregation group",
+            |mut region| {
+                selector.enable(&mut region, 1)?;
+                let mut value_cells = Vec::with_capacity(2);
+                for row in 0..2 {
+                    let boundary = (row == 0) as u64;
+                    region.assign_advice(
+                        || "boundary",
+                        agg.group_by_config.boundary_column,
+                        row,
+                        || Value::known(Fr::from(boundary)),
+                    )?;
+                    value_cells.push(region.assign_advice(|| "value", agg.value_column, row, || {
+                        Value::known(values[row])
+                    })?);
+                    region.assign_advice(|| "result", agg.result_column, row, || {
+                        Value::known(results[row])
+                    })?;
+                }
+                Ok(value_cells)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_names_unique() {
+        let corpus = soundness_corpus();
+        let mut names: Vec<_> = corpus.iter().map(|c| c.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), corpus.len());
+    }
+}
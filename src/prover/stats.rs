@@ -0,0 +1,370 @@
+// Circuit statistics for development reports
+// Per-column utilization (assigned cells vs. available rows), measured by
+// running the circuit's floor planner against a recording `Assignment`,
+// the same way `MockProver` synthesizes the circuit. Columns are numbered by
+// the circuit config's own allocation (`ConfigColumns`).
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, FloorPlanner,
+        Fixed, Instance, Selector,
+    },
+};
+use pasta_curves::pallas::Base as Fr;
+
+use crate::circuit::ConfigColumns;
+use crate::utils::{evcxr_html, html_escape, html_table, text_table};
+
+/// Columns of the tabular rendering (`Display`, `to_html`)
+const STATS_COLUMNS: [&str; 4] = ["column", "cells", "used", "max row"];
+
+/// Utilization of one column
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnUtilization {
+    /// Column position in the config's allocation (within its kind)
+    pub index: usize,
+    /// Number of distinct assigned cells
+    pub assigned_cells: usize,
+    /// Highest assigned row (None if the column is unused)
+    pub max_row: Option<usize>,
+}
+
+impl ColumnUtilization {
+    /// Fraction of usable rows that are assigned
+    pub fn ratio(&self, usable_rows: usize) -> f64 {
+        if usable_rows == 0 {
+            return 0.0;
+        }
+        self.assigned_cells as f64 / usable_rows as f64
+    }
+}
+
+/// Per-column utilization report
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// Circuit size (2^k rows)
+    pub k: u32,
+    /// Rows available for assignments (2^k minus blinding rows)
+    pub usable_rows: usize,
+    pub advice: Vec<ColumnUtilization>,
+    pub fixed: Vec<ColumnUtilization>,
+    /// Number of selectors enabled at least once, out of `num_selectors`
+    pub selectors_used: usize,
+    pub num_selectors: usize,
+}
+
+impl CircuitStats {
+    /// Measure column utilization of a circuit
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let stats = CircuitStats::measure(10, &circuit)?;
+    /// println!("{}", stats);
+    /// ```
+    pub fn measure<C>(k: u32, circuit: &C) -> Result<Self, Error>
+    where
+        C: Circuit<Fr>,
+        C::Config: ConfigColumns,
+    {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let advice_columns = config.advice_columns();
+        let fixed_columns = config.fixed_columns();
+        let constants = config.constant_columns();
+        let num_selectors = config.selectors().len();
+
+        let mut recorder = UsageRecorder {
+            advice: vec![BTreeSet::new(); advice_columns.len()],
+            fixed: vec![BTreeSet::new(); fixed_columns.len()],
+            advice_columns,
+            fixed_columns,
+            selectors: HashSet::new(),
+            usable_rows: (1usize << k).saturating_sub(cs.blinding_factors() + 1),
+        };
+        C::FloorPlanner::synthesize(&mut recorder, circuit, config, constants)?;
+
+        let summarize = |columns: &[BTreeSet<usize>]| -> Vec<ColumnUtilization> {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(index, rows)| ColumnUtilization {
+                    index,
+                    assigned_cells: rows.len(),
+                    max_row: rows.iter().next_back().copied(),
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            k,
+            usable_rows: recorder.usable_rows,
+            advice: summarize(&recorder.advice),
+            fixed: summarize(&recorder.fixed),
+            selectors_used: recorder.selectors.len(),
+            num_selectors,
+        })
+    }
+
+    /// Highest assigned row over all columns (None if nothing is assigned)
+    pub fn rows_used(&self) -> Option<usize> {
+        self.advice
+            .iter()
+            .chain(self.fixed.iter())
+            .filter_map(|c| c.max_row)
+            .max()
+    }
+
+    /// Advice columns whose utilization is below `threshold` (0.0 - 1.0)
+    pub fn sparse_advice_columns(&self, threshold: f64) -> Vec<usize> {
+        self.advice
+            .iter()
+            .filter(|c| c.ratio(self.usable_rows) < threshold)
+            .map(|c| c.index)
+            .collect()
+    }
+
+    /// Advice columns that are never assigned
+    pub fn unused_advice_columns(&self) -> Vec<usize> {
+        self.advice
+            .iter()
+            .filter(|c| c.assigned_cells == 0)
+            .map(|c| c.index)
+            .collect()
+    }
+
+    /// HTML table of the column utilization, for notebooks and web pages
+    pub fn to_html(&self) -> String {
+        let columns = STATS_COLUMNS.map(str::to_string);
+        format!(
+            "<p>{}</p>\n{}",
+            html_escape(&self.summary()),
+            html_table(&columns, &self.cells())
+        )
+    }
+
+    /// Rich output in evcxr (Jupyter) notebooks: the utilization as an HTML table
+    pub fn evcxr_display(&self) {
+        evcxr_html(&self.to_html());
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "k = {} ({} usable rows), selectors used: {}/{}",
+            self.k, self.usable_rows, self.selectors_used, self.num_selectors
+        )
+    }
+
+    /// One row per column: name, assigned cells, utilization, highest row
+    fn cells(&self) -> Vec<Vec<String>> {
+        let kinds = [("advice", &self.advice), ("fixed", &self.fixed)];
+        kinds
+            .into_iter()
+            .flat_map(|(kind, columns)| {
+                columns.iter().map(move |column| {
+                    vec![
+                        format!("{}[{}]", kind, column.index),
+                        column.assigned_cells.to_string(),
+                        format!("{:.1}%", column.ratio(self.usable_rows) * 100.0),
+                        column
+                            .max_row
+                            .map_or_else(|| "-".to_string(), |r| r.to_string()),
+                    ]
+                })
+            })
+            .collect()
+    }
+}
+
+/// Summary line, then one table row per advice and fixed column
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.summary())?;
+        let columns = STATS_COLUMNS.map(str::to_string);
+        write!(f, "{}", text_table(&columns, &self.cells()))
+    }
+}
+
+/// Records which cells are assigned (values are ignored)
+/// Lookup table columns aren't in the config's fixed columns and go unrecorded
+struct UsageRecorder {
+    advice: Vec<BTreeSet<usize>>,
+    fixed: Vec<BTreeSet<usize>>,
+    advice_columns: Vec<Column<Advice>>,
+    fixed_columns: Vec<Column<Fixed>>,
+    selectors: HashSet<Selector>,
+    usable_rows: usize,
+}
+
+impl UsageRecorder {
+    fn advice_rows(&mut self, column: Column<Advice>) -> Option<&mut BTreeSet<usize>> {
+        let position = self.advice_columns.iter().position(|c| *c == column)?;
+        self.advice.get_mut(position)
+    }
+
+    fn fixed_rows(&mut self, column: Column<Fixed>) -> Option<&mut BTreeSet<usize>> {
+        let position = self.fixed_columns.iter().position(|c| *c == column)?;
+        self.fixed.get_mut(position)
+    }
+}
+
+impl Assignment<Fr> for UsageRecorder {
+    fn enter_region<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(
+        &mut self,
+        _annotation: A,
+        selector: &Selector,
+        _row: usize,
+    ) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.selectors.insert(*selector);
+        Ok(())
+    }
+
+    fn query_instance(&self, _column: Column<Instance>, _row: usize) -> Result<Value<Fr>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Advice>,
+        row: usize,
+        _to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        if let Some(rows) = self.advice_rows(column) {
+            rows.insert(row);
+        }
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _annotation: A,
+        column: Column<Fixed>,
+        row: usize,
+        _to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fr>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        if let Some(rows) = self.fixed_rows(column) {
+            rows.insert(row);
+        }
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        _left_column: Column<Any>,
+        _left_row: usize,
+        _right_column: Column<Any>,
+        _right_row: usize,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        _to: Value<Assigned<Fr>>,
+    ) -> Result<(), Error> {
+        // Fills the rest of the column (lookup table padding)
+        let usable_rows = self.usable_rows;
+        if let Some(rows) = self.fixed_rows(column) {
+            rows.extend(row..usable_rows);
+        }
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _gadget_name: Option<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::{PoneglyphCircuit, RangeCheckOp, TableScan, ThresholdMode};
+
+    fn circuit(range_checks: Vec<RangeCheckOp>) -> PoneglyphCircuit {
+        PoneglyphCircuit {
+            scan: TableScan::default(),
+            query_result: Value::known(Fr::from(2)),
+            nonce: Value::known(Fr::from(0)),
+            expiry: Value::known(Fr::from(0)),
+            query_hash: Fr::from(0),
+            result_commitment: Value::known(Fr::from(0)),
+            result_columns: vec![],
+            result_predicate: None,
+            threshold_mode: ThresholdMode::Fixed,
+            range_checks,
+            sorts: vec![],
+            group_bys: vec![],
+            joins: vec![],
+            aggregations: vec![],
+        }
+    }
+
+    #[test]
+    fn test_column_utilization() {
+        let stats = CircuitStats::measure(10, &circuit(vec![])).unwrap();
+        assert_eq!(stats.advice.len(), 15);
+        // Threshold, u and the three Poseidon round constant columns
+        assert_eq!(stats.fixed.len(), 5);
+        assert_eq!(stats.num_selectors, 27);
+
+        // Only the public input bindings touch advice[0] (the database
+        // commitment is hashed in the Poseidon columns); range check columns
+        // are unused
+        assert_eq!(stats.advice[0].assigned_cells, 5);
+        assert!(stats.unused_advice_columns().contains(&9));
+        assert!(stats.sparse_advice_columns(0.01).len() >= 11);
+        assert!(stats.rows_used().is_some());
+        let text = stats.to_string();
+        assert!(text.lines().any(|line| line.starts_with("advice[9]  | 0 ")));
+        assert!(stats.to_html().contains("<td>advice[9]</td><td>0</td>"));
+    }
+
+    #[test]
+    fn test_utilization_grows_with_operations() {
+        let empty = CircuitStats::measure(10, &circuit(vec![])).unwrap();
+        let op = RangeCheckOp {
+            value: Value::known(5),
+            threshold: 10,
+            u: u64::MAX,
+        };
+        let stats = CircuitStats::measure(10, &circuit(vec![op.clone(), op])).unwrap();
+        assert!(stats.advice[9].assigned_cells > empty.advice[9].assigned_cells);
+    }
+}
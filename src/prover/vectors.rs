@@ -0,0 +1,298 @@
+// Cross-language test vectors
+// Instances, proofs and expected verification results as JSON, consumed by
+// the Solidity/WASM/Python verifiers so every integration checks against the
+// same bytes the Rust encoder produces.
+//
+// Encoding (instance ABI):
+// - Field elements: 32-byte canonical little-endian repr (`PrimeField::to_repr`), hex
+// - Proofs: Blake2b transcript bytes, hex
+// - Instance: one column, rows in `INSTANCE_LAYOUT` order
+
+use ff::PrimeField;
+use halo2_proofs::{circuit::Value, pasta::EqAffine, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+use serde::{Deserialize, Serialize};
+
+use super::{request_nonce, Prover, Verifier};
+use crate::circuit::{
+    AggregationOp, AggregationType, ExpiryBound, PoneglyphCircuit, PublicInputs, TableScan,
+    ThresholdMode, NUM_INSTANCE_ROWS,
+};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::sql::{query_hash, QueryResult};
+use crate::utils::{bytes_to_hex, hex_to_bytes};
+
+/// Version of the instance ABI; bump whenever the row layout or encoding changes
+pub const INSTANCE_ABI_VERSION: u32 = 3;
+
+/// Instance row names, in row order
+pub const INSTANCE_LAYOUT: [&str; NUM_INSTANCE_ROWS] = [
+    "db_commitment",
+    "query_result",
+    "nonce",
+    "expiry",
+    "query_hash",
+    "result_commitment",
+];
+
+/// One test vector
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// Unique name (stable across regenerations)
+    pub name: String,
+    /// What the vector exercises
+    pub description: String,
+    /// Instance columns, each row a hex field element
+    pub instance: Vec<Vec<String>>,
+    /// Proof bytes, hex
+    pub proof: String,
+    /// Whether the verifier must accept
+    pub expected_valid: bool,
+}
+
+/// Complete set of test vectors for one parameter size
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectorSet {
+    pub abi_version: u32,
+    /// Commitment scheme / curve / transcript description
+    pub scheme: String,
+    /// Circuit size (2^k rows)
+    pub k: u32,
+    /// Instance row names
+    pub instance_layout: Vec<String>,
+    /// Result revealed with the proofs (`QueryResult::to_bytes`), hex
+    pub result: String,
+    pub vectors: Vec<TestVector>,
+}
+
+/// Encode a field element as hex (canonical little-endian repr)
+pub fn field_to_hex(value: &Fr) -> String {
+    bytes_to_hex(value.to_repr().as_ref())
+}
+
+/// Decode a field element from hex (canonical little-endian repr)
+pub fn field_from_hex(hex: &str) -> PoneglyphResult<Fr> {
+    let bytes = hex_to_bytes(hex).map_err(PoneglyphError::Serialization)?;
+    let mut repr = <Fr as PrimeField>::Repr::default();
+    if bytes.len() != repr.as_ref().len() {
+        return Err(PoneglyphError::Serialization(format!(
+            "field element must be 32 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    repr.as_mut().copy_from_slice(&bytes);
+    Option::from(Fr::from_repr(repr))
+        .ok_or_else(|| PoneglyphError::Serialization("non-canonical field element".to_string()))
+}
+
+/// Encode instance columns as hex
+pub fn instance_to_hex(instance: &[Vec<Fr>]) -> Vec<Vec<String>> {
+    instance
+        .iter()
+        .map(|column| column.iter().map(field_to_hex).collect())
+        .collect()
+}
+
+/// Decode instance columns from hex
+pub fn instance_from_hex(instance: &[Vec<String>]) -> PoneglyphResult<Vec<Vec<Fr>>> {
+    instance
+        .iter()
+        .map(|column| column.iter().map(|v| field_from_hex(v)).collect())
+        .collect()
+}
+
+impl TestVector {
+    /// Decoded instance columns
+    pub fn instance_fields(&self) -> PoneglyphResult<Vec<Vec<Fr>>> {
+        instance_from_hex(&self.instance)
+    }
+
+    /// Decoded proof bytes
+    pub fn proof_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        hex_to_bytes(&self.proof).map_err(PoneglyphError::Serialization)
+    }
+}
+
+impl TestVectorSet {
+    /// Generate the standard vectors
+    ///
+    /// # Vectors
+    ///
+    /// - `valid`: honest proof with nonce, expiry, query hash and result commitment
+    /// - `wrong_db_commitment`: instance claims another database
+    /// - `wrong_query_result`: instance claims a different result
+    /// - `wrong_query_hash`: instance claims another query
+    /// - `wrong_result_commitment`: instance commits to another result
+    /// - `wrong_nonce`: proof replayed for another request
+    /// - `extended_expiry`: instance claims a later expiry bound
+    /// - `tampered_proof`: one proof byte flipped
+    pub fn generate(k: u32) -> PoneglyphResult<Self> {
+        let params = &Params::<EqAffine>::new(k);
+        let synthesis = |e: halo2_proofs::plonk::Error| PoneglyphError::Synthesis(format!("{:?}", e));
+
+        let nonce = request_nonce(b"test-vector-1")?;
+        let expiry = ExpiryBound::BlockHeight(1_000_000);
+        let result = QueryResult {
+            columns: vec!["sum(price)".to_string()],
+            rows: vec![vec![Some(100)]],
+        };
+        // SELECT SUM(price) FROM orders over two orders
+        let scan = TableScan::new(
+            vec!["id".to_string(), "price".to_string()],
+            vec![vec![1, 30], vec![2, 70]],
+        );
+        let public_inputs = PublicInputs::new(scan.commitment(), Fr::from(100))
+            .with_nonce(nonce)
+            .with_expiry(expiry)
+            .with_query_hash(query_hash("SELECT SUM(price) FROM orders"))
+            .with_result_commitment(result.commitment());
+
+        let circuit = PoneglyphCircuit {
+            scan,
+            query_result: Value::known(public_inputs.query_result),
+            nonce: Value::known(nonce),
+            expiry: Value::known(expiry.to_field()),
+            query_hash: public_inputs.query_hash,
+            result_commitment: Value::known(public_inputs.result_commitment),
+            result_columns: result.columns.clone(),
+            result_predicate: None,
+            threshold_mode: ThresholdMode::Fixed,
+            range_checks: vec![],
+            sorts: vec![],
+            group_bys: vec![],
+            joins: vec![],
+            aggregations: vec![AggregationOp {
+                group_keys: vec![0, 0],
+                values: vec![30, 70],
+                agg_type: AggregationType::Sum,
+                column: Some("price".to_string()),
+            }],
+        };
+
+        let prover = Prover::new(params, &circuit).map_err(synthesis)?;
+        let verifier = Verifier::new(params, &circuit).map_err(synthesis)?;
+        let instance = public_inputs.to_instance();
+        let proof = prover.prove(params, &circuit, &instance).map_err(synthesis)?;
+
+        let mut tampered = proof.clone();
+        if let Some(byte) = tampered.last_mut() {
+            *byte ^= 1;
+        }
+
+        let cases = vec![
+            ("valid", "Honest proof", instance.clone(), proof.clone()),
+            (
+                "wrong_db_commitment",
+                "Instance claims another database",
+                PublicInputs {
+                    db_commitment: Fr::from(43),
+                    ..public_inputs.clone()
+                }
+                .to_instance(),
+                proof.clone(),
+            ),
+            (
+                "wrong_query_result",
+                "Instance claims a different query result",
+                PublicInputs {
+                    query_result: Fr::from(101),
+                    ..public_inputs.clone()
+                }
+                .to_instance(),
+                proof.clone(),
+            ),
+            (
+                "wrong_query_hash",
+                "Instance claims another query",
+                public_inputs
+                    .clone()
+                    .with_query_hash(query_hash("SELECT COUNT(price) FROM orders"))
+                    .to_instance(),
+                proof.clone(),
+            ),
+            (
+                "wrong_result_commitment",
+                "Instance commits to another result",
+                public_inputs
+                    .clone()
+                    .with_result_commitment(
+                        QueryResult {
+                            rows: vec![vec![Some(101)]],
+                            ..result.clone()
+                        }
+                        .commitment(),
+                    )
+                    .to_instance(),
+                proof.clone(),
+            ),
+            (
+                "wrong_nonce",
+                "Proof replayed for another request",
+                public_inputs
+                    .clone()
+                    .with_nonce(request_nonce(b"test-vector-2")?)
+                    .to_instance(),
+                proof.clone(),
+            ),
+            (
+                "extended_expiry",
+                "Instance claims a later expiry bound",
+                public_inputs
+                    .clone()
+                    .with_expiry(ExpiryBound::BlockHeight(2_000_000))
+                    .to_instance(),
+                proof.clone(),
+            ),
+            ("tampered_proof", "One proof byte flipped", instance, tampered),
+        ];
+
+        // Expected results come from the Rust verifier itself
+        let vectors = cases
+            .into_iter()
+            .map(|(name, description, instance, proof)| TestVector {
+                name: name.to_string(),
+                description: description.to_string(),
+                expected_valid: verifier.verify(params, &proof, &instance).is_ok(),
+                instance: instance_to_hex(&instance),
+                proof: bytes_to_hex(&proof),
+            })
+            .collect();
+
+        Ok(Self {
+            abi_version: INSTANCE_ABI_VERSION,
+            scheme: "halo2-ipa/pasta-eqaffine/blake2b".to_string(),
+            k,
+            instance_layout: INSTANCE_LAYOUT.iter().map(|s| s.to_string()).collect(),
+            result: bytes_to_hex(&result.to_bytes()),
+            vectors,
+        })
+    }
+
+    /// Serialize to pretty JSON
+    pub fn to_json(&self) -> PoneglyphResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| PoneglyphError::Serialization(e.to_string()))
+    }
+
+    /// Parse from JSON
+    pub fn from_json(json: &str) -> PoneglyphResult<Self> {
+        serde_json::from_str(json).map_err(|e| PoneglyphError::Serialization(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_hex_roundtrip() {
+        for value in [Fr::from(0), Fr::from(1), Fr::from(u64::MAX), -Fr::from(1)] {
+            let hex = field_to_hex(&value);
+            assert_eq!(hex.len(), 64);
+            assert_eq!(field_from_hex(&hex).unwrap(), value);
+        }
+        // Little-endian: 1 is the first byte
+        assert!(field_to_hex(&Fr::from(1)).starts_with("01"));
+        assert!(field_from_hex("00").is_err());
+        assert!(field_from_hex(&"ff".repeat(32)).is_err());
+    }
+}
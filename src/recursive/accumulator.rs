@@ -0,0 +1,211 @@
+// Accumulator state persistence
+// An IVC chain over streaming inserts outlives any single prover process: the
+// accumulated proofs and public inputs are saved after each step and restored
+// when the prover restarts (`IncrementalProver::state` / `restore`).
+//
+// The state records a fingerprint of the verifying key it was built with, so a
+// redeployed prover whose circuit changed refuses to extend the chain instead
+// of producing proofs that can never be verified together.
+
+use std::fs;
+use std::path::Path;
+
+use ff::PrimeField;
+use halo2_proofs::{pasta::EqAffine, plonk::VerifyingKey};
+use pasta_curves::pallas::Base as Fr;
+
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+/// Format version of `AccumulatorState::to_bytes`
+pub const ACCUMULATOR_STATE_VERSION: u32 = 1;
+
+/// Leading bytes of a serialized accumulator state
+const MAGIC: &[u8; 4] = b"PGAC";
+
+/// Accumulated proofs and public inputs of an incremental proof chain
+#[derive(Clone, Debug, PartialEq, Eq, bincode::Encode, bincode::Decode)]
+pub struct AccumulatorState {
+    pub version: u32,
+    /// `vk_fingerprint` of the key the proofs were created with
+    pub vk_fingerprint: u64,
+    /// One proof per step
+    pub proofs: Vec<Vec<u8>>,
+    /// Accumulated public inputs (canonical little-endian reprs)
+    inputs: Vec<Vec<[u8; 32]>>,
+}
+
+impl AccumulatorState {
+    pub fn new(vk_fingerprint: u64, proofs: Vec<Vec<u8>>, inputs: &[Vec<Fr>]) -> Self {
+        let inputs = inputs
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|value| {
+                        let mut bytes = [0u8; 32];
+                        bytes.copy_from_slice(value.to_repr().as_ref());
+                        bytes
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            version: ACCUMULATOR_STATE_VERSION,
+            vk_fingerprint,
+            proofs,
+            inputs,
+        }
+    }
+
+    /// Number of accumulated steps
+    pub fn steps(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Accumulated public inputs
+    pub fn inputs(&self) -> PoneglyphResult<Vec<Vec<Fr>>> {
+        self.inputs
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|bytes| {
+                        let mut repr = <Fr as PrimeField>::Repr::default();
+                        repr.as_mut().copy_from_slice(bytes);
+                        Option::from(Fr::from_repr(repr)).ok_or_else(|| {
+                            PoneglyphError::Serialization("non-canonical field element".to_string())
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Fail unless the state was built with the key of this fingerprint
+    pub fn check_key(&self, vk_fingerprint: u64) -> PoneglyphResult<()> {
+        if self.vk_fingerprint != vk_fingerprint {
+            return Err(PoneglyphError::Validation(format!(
+                "accumulator was built with key {:016x}, prover has key {:016x}",
+                self.vk_fingerprint, vk_fingerprint
+            )));
+        }
+        Ok(())
+    }
+
+    /// Serialize for storage
+    pub fn to_bytes(&self) -> PoneglyphResult<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(
+            bincode::encode_to_vec(self, bincode::config::standard())
+                .map_err(|e| PoneglyphError::Serialization(e.to_string()))?,
+        );
+        Ok(bytes)
+    }
+
+    /// Deserialize from storage
+    pub fn from_bytes(bytes: &[u8]) -> PoneglyphResult<Self> {
+        let body = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or_else(|| PoneglyphError::Serialization("not an accumulator state".to_string()))?;
+        let (state, _): (Self, usize) =
+            bincode::decode_from_slice(body, bincode::config::standard())
+                .map_err(|e| PoneglyphError::Serialization(e.to_string()))?;
+        if state.version != ACCUMULATOR_STATE_VERSION {
+            return Err(PoneglyphError::Serialization(format!(
+                "accumulator state version {} is not supported (expected {})",
+                state.version, ACCUMULATOR_STATE_VERSION
+            )));
+        }
+        // Reject corrupt inputs on load rather than on the next step
+        state.inputs()?;
+        Ok(state)
+    }
+
+    /// Write to `path`
+    /// The state is written next to `path` and renamed into place, so a crash
+    /// mid-write leaves the previous state intact.
+    pub fn save(&self, path: impl AsRef<Path>) -> PoneglyphResult<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, self.to_bytes()?)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| PoneglyphError::Serialization(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Read from `path`
+    pub fn load(path: impl AsRef<Path>) -> PoneglyphResult<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .map_err(|e| PoneglyphError::Serialization(format!("{}: {}", path.display(), e)))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Stable fingerprint of a verifying key
+/// FNV-1a over the pinned key (domain, constraint system, fixed and
+/// permutation commitments), so it doesn't change between processes.
+pub fn vk_fingerprint(vk: &VerifyingKey<EqAffine>) -> u64 {
+    format!("{:?}", vk.pinned())
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+
+    fn state() -> AccumulatorState {
+        AccumulatorState::new(
+            7,
+            vec![vec![1, 2, 3], vec![4, 5]],
+            &[vec![Fr::from(9), -Fr::ONE], vec![Fr::ZERO]],
+        )
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let state = state();
+        let restored = AccumulatorState::from_bytes(&state.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored, state);
+        assert_eq!(restored.steps(), 2);
+        assert_eq!(
+            restored.inputs().unwrap(),
+            vec![vec![Fr::from(9), -Fr::ONE], vec![Fr::ZERO]]
+        );
+    }
+
+    #[test]
+    fn test_state_rejects_foreign_bytes() {
+        let bytes = state().to_bytes().unwrap();
+        assert!(AccumulatorState::from_bytes(&bytes[1..]).is_err());
+        assert!(AccumulatorState::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut newer = state();
+        newer.version += 1;
+        assert!(AccumulatorState::from_bytes(&newer.to_bytes().unwrap()).is_err());
+
+        let mut corrupt = state();
+        corrupt.inputs[0][0] = [0xff; 32];
+        assert!(corrupt.inputs().is_err());
+        assert!(AccumulatorState::from_bytes(&corrupt.to_bytes().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_key_check() {
+        assert!(state().check_key(7).is_ok());
+        assert!(state().check_key(8).is_err());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("poneglyph-acc-{}.bin", std::process::id()));
+        state().save(&path).unwrap();
+        assert_eq!(AccumulatorState::load(&path).unwrap(), state());
+        fs::remove_file(&path).unwrap();
+        assert!(AccumulatorState::load(&path).is_err());
+    }
+}
@@ -0,0 +1,498 @@
+// Chunked proving
+// A column too large for one circuit (see `MAX_CIRCUIT_SIZE`: a circuit of k
+// holds a fixed number of rows) is streamed in chunks. Each chunk is proven
+// on its own by `ChunkCircuit`, which publishes the chunk's commitment and
+// its partial aggregate; the chunk proofs are folded into one
+// `AggregatedProof`, and a small `CombineCircuit` proves that the result is
+// the merge of the published partials and that the table commitment is the
+// hash of the chunk commitments. The chunk and combine proofs are linked
+// through their instances: the verifier builds both from the same claim.
+//
+// Chunks have a fixed capacity so they share one verifying key; a short last
+// chunk is padded with empty slots, which the slot gate forces to the empty
+// summary.
+//
+// Chunks carry partial aggregates (SUM, COUNT, MIN, MAX and the dispersion
+// moments, see `PartialAggregate`); merging sorted runs across chunks is not
+// covered.
+
+use ff::{Field, PrimeField};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    pasta::EqAffine,
+    plonk::{keygen_vk, Circuit, ConstraintSystem, Error},
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::proof_aggregation::{AggregatedProof, RecursiveAggregator};
+use super::cycle::{verify_on_curve, CycleProver};
+use crate::circuit::{
+    AssignedPartialAggregate, PartialAggregate, PartialAggregateChip, PartialAggregateConfig,
+    PoneglyphConfig, PoseidonChip, PoseidonConfig, RangeCheckChip,
+};
+use crate::error::{PoneglyphError, PoneglyphResult, VerifyError};
+use crate::utils::poseidon_hash;
+
+/// Instance row of the chunk commitment
+pub const CHUNK_INSTANCE_COMMITMENT_ROW: usize = 0;
+/// First instance row of the chunk summary (sum, count, min, max,
+/// sum of squares)
+pub const CHUNK_INSTANCE_SUMMARY_ROW: usize = 1;
+
+/// Instance row of the table commitment (hash of the chunk commitments)
+pub const COMBINE_INSTANCE_TABLE_ROW: usize = 0;
+/// First instance row of the merged summary
+pub const COMBINE_INSTANCE_RESULT_ROW: usize = 1;
+/// First instance row of the chunks: commitment and summary, 6 rows each
+pub const COMBINE_INSTANCE_CHUNKS_ROW: usize = 6;
+
+/// Rows of one chunk slot: the slot row, one merge and one Poseidon
+/// permutation over advice[10-14] (the decompositions use other columns)
+const ROWS_PER_SLOT: usize = 80;
+/// Rows reserved for blinding and the unusable rows of a circuit
+const RESERVED_ROWS: usize = 80;
+
+/// Summary fields in instance order
+fn summary_fields(partial: &PartialAggregate) -> [Fr; 5] {
+    [
+        Fr::from_u128(partial.sum),
+        Fr::from(partial.count),
+        Fr::from(partial.min),
+        Fr::from(partial.max),
+        Fr::from_u128(partial.sum_of_squares),
+    ]
+}
+
+/// Commitment of a chunk: Poseidon over `(value, 1)` per row and `(0, 0)`
+/// per padding slot
+pub fn chunk_commitment(values: &[u64], capacity: usize) -> Fr {
+    let mut inputs = Vec::with_capacity(2 * capacity);
+    for slot in 0..capacity {
+        match values.get(slot) {
+            Some(&value) => inputs.extend([Fr::from(value), Fr::ONE]),
+            None => inputs.extend([Fr::ZERO, Fr::ZERO]),
+        }
+    }
+    poseidon_hash(&inputs)
+}
+
+/// Public part of one chunk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkSummary {
+    pub commitment: Fr,
+    pub partial: PartialAggregate,
+}
+
+impl ChunkSummary {
+    /// Summary of `values` in a chunk of `capacity` slots
+    /// None if a sum overflows
+    pub fn of(values: &[u64], capacity: usize) -> Option<Self> {
+        Some(Self {
+            commitment: chunk_commitment(values, capacity),
+            partial: PartialAggregate::of(values)?,
+        })
+    }
+
+    /// Instance of the chunk proof
+    pub fn instance(&self) -> Vec<Fr> {
+        let mut instance = vec![self.commitment];
+        instance.extend(summary_fields(&self.partial));
+        instance
+    }
+}
+
+/// Chunk Circuit
+/// Proves the commitment and the partial aggregate of one chunk
+///
+/// # Instance
+///
+/// - row 0: chunk commitment (`chunk_commitment`)
+/// - rows 1-5: chunk summary (sum, count, min, max, sum of squares)
+#[derive(Clone, Debug)]
+pub struct ChunkCircuit {
+    /// One entry per slot; `None` is padding
+    pub slots: Vec<Value<Option<u64>>>,
+}
+
+impl ChunkCircuit {
+    /// Circuit over `values`, padded to `capacity` slots
+    pub fn new(values: &[u64], capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity)
+                .map(|slot| Value::known(values.get(slot).copied()))
+                .collect(),
+        }
+    }
+
+    /// Circuit without witnesses (for key generation)
+    pub fn shape(capacity: usize) -> Self {
+        Self {
+            slots: vec![Value::unknown(); capacity],
+        }
+    }
+
+    /// Slots that fit in a circuit of 2^k rows
+    pub fn capacity_for(k: u32) -> usize {
+        (1usize << k).saturating_sub(RESERVED_ROWS) / ROWS_PER_SLOT
+    }
+}
+
+/// Config of `ChunkCircuit` and `CombineCircuit`
+#[derive(Clone, Debug)]
+pub struct ChunkConfig {
+    pub poneglyph_config: PoneglyphConfig,
+    pub poseidon_config: PoseidonConfig,
+    pub partial_config: PartialAggregateConfig,
+}
+
+impl ChunkConfig {
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let poseidon_config = PoseidonChip::configure(meta, &poneglyph_config);
+        let partial_config =
+            PartialAggregateChip::configure(meta, &poneglyph_config, &range_check_config);
+        Self {
+            poneglyph_config,
+            poseidon_config,
+            partial_config,
+        }
+    }
+
+    /// Bind a summary to five consecutive instance rows
+    fn constrain_summary(
+        &self,
+        layouter: &mut impl Layouter<Fr>,
+        partial: &AssignedPartialAggregate,
+        first_row: usize,
+    ) -> Result<(), Error> {
+        let cells = [
+            &partial.sum,
+            &partial.count,
+            &partial.min,
+            &partial.max,
+            &partial.sum_of_squares,
+        ];
+        for (offset, cell) in cells.into_iter().enumerate() {
+            layouter.constrain_instance(
+                cell.cell(),
+                self.poneglyph_config.instance,
+                first_row + offset,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Circuit<Fr> for ChunkCircuit {
+    type Config = ChunkConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::shape(self.slots.len())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        ChunkConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        let partial_chip = PartialAggregateChip::new(config.partial_config.clone());
+        let poseidon = PoseidonChip::new(config.poseidon_config.clone());
+
+        let mut partials = Vec::with_capacity(self.slots.len());
+        let mut inputs = Vec::with_capacity(2 * self.slots.len());
+        for (i, slot) in self.slots.iter().enumerate() {
+            let partial = partial_chip.slot(layouter.namespace(|| format!("slot {}", i)), *slot)?;
+            inputs.extend([partial.sum.clone(), partial.count.clone()]);
+            partials.push(partial);
+        }
+
+        let merged = partial_chip
+            .merge_all(layouter.namespace(|| "merge slots"), &partials)?
+            .ok_or(Error::Synthesis)?;
+        config.constrain_summary(&mut layouter, &merged, CHUNK_INSTANCE_SUMMARY_ROW)?;
+
+        let commitment = poseidon.hash(layouter.namespace(|| "chunk commitment"), &inputs)?;
+        layouter.constrain_instance(
+            commitment.cell(),
+            config.poneglyph_config.instance,
+            CHUNK_INSTANCE_COMMITMENT_ROW,
+        )
+    }
+}
+
+/// Combine Circuit
+/// Proves that the result is the merge of the published chunk summaries and
+/// that the table commitment is the hash of the chunk commitments. It has no
+/// witness: every value is copied from the instance.
+///
+/// # Instance
+///
+/// - row 0: table commitment
+/// - rows 1-5: merged summary
+/// - rows 6 + 6i ..: commitment and summary of chunk i
+#[derive(Clone, Debug)]
+pub struct CombineCircuit {
+    pub chunks: usize,
+}
+
+impl CombineCircuit {
+    pub fn new(chunks: usize) -> Self {
+        Self { chunks }
+    }
+}
+
+impl Circuit<Fr> for CombineCircuit {
+    type Config = ChunkConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        ChunkConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        let partial_chip = PartialAggregateChip::new(config.partial_config.clone());
+        let poseidon = PoseidonChip::new(config.poseidon_config.clone());
+        let instance = config.poneglyph_config.instance;
+
+        let mut partials = Vec::with_capacity(self.chunks);
+        let mut commitments = Vec::with_capacity(self.chunks);
+        for chunk in 0..self.chunks {
+            let first_row = COMBINE_INSTANCE_CHUNKS_ROW + 6 * chunk;
+            let (commitment, cells) = layouter.assign_region(
+                || format!("chunk {}", chunk),
+                |mut region| {
+                    let commitment = region.assign_advice_from_instance(
+                        || "commitment",
+                        instance,
+                        first_row,
+                        config.poneglyph_config.advice[0],
+                        0,
+                    )?;
+                    let cells = config
+                        .partial_config
+                        .columns
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, &column)| {
+                            region.assign_advice_from_instance(
+                                || "summary",
+                                instance,
+                                first_row + 1 + offset,
+                                column,
+                                0,
+                            )
+                        })
+                        .collect::<Result<Vec<AssignedCell<Fr, Fr>>, Error>>()?;
+                    Ok((commitment, cells))
+                },
+            )?;
+            let [sum, count, min, max, sum_of_squares]: [AssignedCell<Fr, Fr>; 5] =
+                cells.try_into().unwrap();
+            commitments.push(commitment);
+            partials.push(AssignedPartialAggregate {
+                sum,
+                count,
+                min,
+                max,
+                sum_of_squares,
+            });
+        }
+
+        let merged = partial_chip
+            .merge_all(layouter.namespace(|| "merge chunks"), &partials)?
+            .ok_or(Error::Synthesis)?;
+        config.constrain_summary(&mut layouter, &merged, COMBINE_INSTANCE_RESULT_ROW)?;
+
+        let table = poseidon.hash(layouter.namespace(|| "table commitment"), &commitments)?;
+        layouter.constrain_instance(table.cell(), instance, COMBINE_INSTANCE_TABLE_ROW)
+    }
+}
+
+/// Statement of a chunked proof: the merged summary of a table streamed in
+/// chunks of `capacity` rows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkedClaim {
+    pub capacity: usize,
+    /// Poseidon hash of the chunk commitments
+    pub table_commitment: Fr,
+    pub result: PartialAggregate,
+    pub chunks: Vec<ChunkSummary>,
+}
+
+impl ChunkedClaim {
+    /// Instance of the combine proof
+    pub fn combine_instance(&self) -> Vec<Fr> {
+        let mut instance = vec![self.table_commitment];
+        instance.extend(summary_fields(&self.result));
+        for chunk in &self.chunks {
+            instance.extend(chunk.instance());
+        }
+        instance
+    }
+
+    /// Verify the chunk proofs and the combine proof
+    ///
+    /// # Errors
+    ///
+    /// `InstanceMismatch` if the chunk proofs are for other chunks than the
+    /// claim's, otherwise as `AggregatedProof::verify` and `Verifier::verify`
+    pub fn verify(
+        &self,
+        params: &Params<EqAffine>,
+        proof: &ChunkedProof,
+    ) -> Result<(), VerifyError> {
+        let instances: Vec<Vec<Fr>> = self.chunks.iter().map(ChunkSummary::instance).collect();
+        if proof.chunks.instances != instances {
+            return Err(VerifyError::InstanceMismatch(
+                "chunk proofs are for other chunks than the claim".to_string(),
+            ));
+        }
+        let chunk_vk = keygen_vk(params, &ChunkCircuit::shape(self.capacity))?;
+        proof.chunks.verify(params, &chunk_vk)?;
+
+        let combine_vk = keygen_vk(params, &CombineCircuit::new(self.chunks.len()))?;
+        verify_on_curve(
+            params,
+            &combine_vk,
+            &proof.combine,
+            &[self.combine_instance()],
+        )?;
+        Ok(())
+    }
+}
+
+/// Chunk proofs (folded) and the combine proof
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkedProof {
+    pub chunks: AggregatedProof,
+    pub combine: Vec<u8>,
+}
+
+/// Chunked Prover
+/// Streams a column in chunks, proving each chunk as it fills, so memory is
+/// bounded by one chunk and the column is not capped by `k`
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let prover = ChunkedProver::for_params(&params)?;
+/// let (claim, proof) = prover.prove(&params, table.column("amount"))?;
+/// claim.verify(&params, &proof)?;
+/// let total = claim.result.evaluate(&AggregationType::Sum);
+/// ```
+///
+/// # Note
+///
+/// The combine circuit grows with the number of chunks (one merge and half a
+/// Poseidon permutation each), so `k` bounds the chunk count rather than the
+/// row count.
+pub struct ChunkedProver {
+    capacity: usize,
+    chunk: CycleProver<EqAffine>,
+    aggregator: RecursiveAggregator,
+}
+
+impl ChunkedProver {
+    /// Prover for chunks of `capacity` rows
+    pub fn new(params: &Params<EqAffine>, capacity: usize) -> Result<Self, Error> {
+        if capacity == 0 {
+            return Err(Error::Synthesis);
+        }
+        let chunk = CycleProver::<EqAffine>::new(params, &ChunkCircuit::shape(capacity))?;
+        let aggregator = RecursiveAggregator::new(chunk.vk().clone());
+        Ok(Self {
+            capacity,
+            chunk,
+            aggregator,
+        })
+    }
+
+    /// Prover for the largest chunks that fit in `params`
+    pub fn for_params(params: &Params<EqAffine>) -> Result<Self, Error> {
+        Self::new(params, ChunkCircuit::capacity_for(params.k()))
+    }
+
+    /// Rows per chunk
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Prove the merged summary of `values`
+    /// An empty column is one empty chunk
+    ///
+    /// # Errors
+    ///
+    /// `InvalidInput` if a sum overflows, `Synthesis` if a proof fails
+    pub fn prove(
+        &self,
+        params: &Params<EqAffine>,
+        values: impl IntoIterator<Item = u64>,
+    ) -> PoneglyphResult<(ChunkedClaim, ChunkedProof)> {
+        let synthesis = |e: Error| PoneglyphError::Synthesis(format!("{:?}", e));
+        let overflow = || PoneglyphError::InvalidInput("chunk sum overflows".to_string());
+
+        let mut chunks = Vec::new();
+        let mut proofs = Vec::new();
+        let mut buffer = Vec::with_capacity(self.capacity);
+        let mut values = values.into_iter().peekable();
+        while values.peek().is_some() || chunks.is_empty() {
+            buffer.clear();
+            buffer.extend(values.by_ref().take(self.capacity));
+
+            let summary = ChunkSummary::of(&buffer, self.capacity).ok_or_else(overflow)?;
+            let instance = summary.instance();
+            let circuit = ChunkCircuit::new(&buffer, self.capacity);
+            let proof = self
+                .chunk
+                .prove(params, &circuit, std::slice::from_ref(&instance))
+                .map_err(synthesis)?;
+            chunks.push(summary);
+            proofs.push((proof, instance));
+        }
+        let folded = self.aggregator.fold(params, &proofs)?;
+
+        let result = chunks
+            .iter()
+            .try_fold(PartialAggregate::default(), |acc, chunk| {
+                acc.merge(&chunk.partial)
+            })
+            .ok_or_else(overflow)?;
+        let commitments: Vec<Fr> = chunks.iter().map(|chunk| chunk.commitment).collect();
+        let claim = ChunkedClaim {
+            capacity: self.capacity,
+            table_commitment: poseidon_hash(&commitments),
+            result,
+            chunks,
+        };
+
+        let combine = CombineCircuit::new(claim.chunks.len());
+        let combine_proof = CycleProver::<EqAffine>::new(params, &combine)
+            .and_then(|prover| prover.prove(params, &combine, &[claim.combine_instance()]))
+            .map_err(synthesis)?;
+
+        Ok((
+            claim,
+            ChunkedProof {
+                chunks: folded,
+                combine: combine_proof,
+            },
+        ))
+    }
+}
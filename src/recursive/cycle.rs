@@ -0,0 +1,271 @@
+// Pasta curve cycle plumbing
+// Pallas and Vesta form a cycle: the scalar field of each curve is the base
+// field of the other. Circuits over pallas::Base (Fp) are committed with Vesta
+// points (`EqAffine`); those commitments have Fq coordinates, so the circuit
+// that checks them is over vesta::Base (Fq) and is committed with Pallas
+// points (`EpAffine`), and vice versa.
+//
+// Proofs are named by their circuit field, as in `RecursiveProof`: the
+// "Pallas proof" is over Fp with `EqAffine`, the "Vesta proof" over Fq with
+// `EpAffine`.
+//
+// Values crossing the cycle change field. p < q, so every Fp value is a valid
+// Fq value, but Fq values >= p are not valid Fp values; public inputs are
+// therefore carried in 64-bit limbs, which are canonical in both fields.
+
+use ff::{Field, FromUniformBytes, PrimeField};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    pasta::{EpAffine, EqAffine},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, ConstraintSystem, Error,
+        ProvingKey, SingleVerifier, VerifyingKey,
+    },
+    poly::commitment::Params,
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand_core::OsRng;
+
+use crate::circuit::{PoneglyphConfig, RangeCheckChip, RangeCheckConfig};
+
+/// Limbs per field element when carried across the cycle
+pub const CYCLE_LIMBS: usize = 4;
+
+/// Curve of the pasta cycle
+/// `Partner` is the other curve: its scalar field is this curve's base field
+/// and its base field is this curve's scalar field.
+pub trait CycleCurve: CurveAffine {
+    type Partner: CycleCurve<Partner = Self>
+        + CurveAffine<ScalarExt = Self::Base, Base = Self::ScalarExt>;
+
+    /// Name of the curve whose points are the commitments
+    const NAME: &'static str;
+}
+
+impl CycleCurve for EqAffine {
+    type Partner = EpAffine;
+    const NAME: &'static str = "vesta";
+}
+
+impl CycleCurve for EpAffine {
+    type Partner = EqAffine;
+    const NAME: &'static str = "pallas";
+}
+
+/// Carry a scalar of `C` into the partner's scalar field
+/// `None` when the value is not canonical there (Fq values >= p)
+pub fn to_partner_scalar<C: CycleCurve>(value: &C::Scalar) -> Option<C::Base> {
+    let mut repr = <C::Base as PrimeField>::Repr::default();
+    repr.as_mut().copy_from_slice(value.to_repr().as_ref());
+    Option::from(C::Base::from_repr(repr))
+}
+
+/// Little-endian 64-bit limbs of a scalar of `C`, as partner scalars
+pub fn to_partner_limbs<C: CycleCurve>(value: &C::Scalar) -> [C::Base; CYCLE_LIMBS] {
+    let repr = value.to_repr();
+    let mut limbs = [C::Base::ZERO; CYCLE_LIMBS];
+    for (limb, bytes) in limbs.iter_mut().zip(repr.as_ref().chunks(8)) {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(bytes);
+        *limb = C::Base::from(u64::from_le_bytes(word));
+    }
+    limbs
+}
+
+/// Inverse of `to_partner_limbs`
+/// `None` if a limb is not a 64-bit value or the limbs exceed the modulus
+pub fn from_partner_limbs<C: CycleCurve>(limbs: &[C::Base]) -> Option<C::Scalar> {
+    if limbs.len() != CYCLE_LIMBS {
+        return None;
+    }
+    let mut repr = <C::Scalar as PrimeField>::Repr::default();
+    for (bytes, limb) in repr.as_mut().chunks_mut(8).zip(limbs) {
+        let limb_repr = limb.to_repr();
+        if limb_repr.as_ref()[8..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        bytes.copy_from_slice(&limb_repr.as_ref()[..8]);
+    }
+    Option::from(C::Scalar::from_repr(repr))
+}
+
+/// Public inputs of a proof on `C`, as the instance of a partner circuit
+pub fn partner_instance<C: CycleCurve>(public_inputs: &[C::Scalar]) -> Vec<C::Base> {
+    public_inputs
+        .iter()
+        .flat_map(|value| to_partner_limbs::<C>(value))
+        .collect()
+}
+
+/// Partner-side circuit exposing the public inputs of a proof
+///
+/// Each limb is range checked to 64 bits and bound to its instance row, so
+/// the instance is the canonical limb encoding of the other side's inputs.
+#[derive(Clone, Debug)]
+pub struct CycleBindingCircuit<F: PrimeField> {
+    limbs: Vec<Value<F>>,
+}
+
+impl<F: PrimeField> CycleBindingCircuit<F> {
+    pub fn new(limbs: Vec<F>) -> Self {
+        Self {
+            limbs: limbs.into_iter().map(Value::known).collect(),
+        }
+    }
+
+    /// Circuit without witnesses for `num_limbs` limbs (key generation)
+    pub fn shape(num_limbs: usize) -> Self {
+        Self {
+            limbs: vec![Value::unknown(); num_limbs],
+        }
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for CycleBindingCircuit<F> {
+    type Config = (PoneglyphConfig, RangeCheckConfig);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::shape(self.limbs.len())
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &config);
+        (config, range_check_config)
+    }
+
+    fn synthesize(
+        &self,
+        (config, range_check_config): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_lookup_table(&mut layouter)?;
+        let range_check_chip = RangeCheckChip::new(range_check_config);
+        for (row, limb) in self.limbs.iter().enumerate() {
+            let cell = config.bind_public_input(&mut layouter, *limb, row)?;
+            range_check_chip
+                .decompose_cell(layouter.namespace(|| format!("limb {}", row)), &cell)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prover for circuits over the scalar field of `C`
+/// The same code proves on either side of the cycle.
+pub struct CycleProver<C: CycleCurve> {
+    pk: ProvingKey<C>,
+}
+
+impl<C> CycleProver<C>
+where
+    C: CycleCurve,
+    C::Scalar: FromUniformBytes<64>,
+{
+    pub fn new<ConcreteCircuit: Circuit<C::Scalar>>(
+        params: &Params<C>,
+        circuit: &ConcreteCircuit,
+    ) -> Result<Self, Error> {
+        let vk = keygen_vk(params, circuit)?;
+        let pk = keygen_pk(params, vk, circuit)?;
+        Ok(Self { pk })
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<C> {
+        self.pk.get_vk()
+    }
+
+    /// Prove one circuit; `instance` holds one vector per instance column
+    pub fn prove<ConcreteCircuit: Circuit<C::Scalar>>(
+        &self,
+        params: &Params<C>,
+        circuit: &ConcreteCircuit,
+        instance: &[Vec<C::Scalar>],
+    ) -> Result<Vec<u8>, Error> {
+        let columns: Vec<&[C::Scalar]> = instance.iter().map(|c| c.as_slice()).collect();
+        let mut transcript = Blake2bWrite::<Vec<u8>, C, Challenge255<C>>::init(vec![]);
+        create_proof(
+            params,
+            &self.pk,
+            std::slice::from_ref(circuit),
+            &[columns.as_slice()],
+            OsRng,
+            &mut transcript,
+        )?;
+        Ok(transcript.finalize())
+    }
+}
+
+/// Verify a proof made with `CycleProver<C>`
+pub fn verify_on_curve<C>(
+    params: &Params<C>,
+    vk: &VerifyingKey<C>,
+    proof: &[u8],
+    instance: &[Vec<C::Scalar>],
+) -> Result<(), Error>
+where
+    C: CycleCurve,
+    C::Scalar: FromUniformBytes<64>,
+{
+    let columns: Vec<&[C::Scalar]> = instance.iter().map(|c| c.as_slice()).collect();
+    let mut transcript = Blake2bRead::<&[u8], C, Challenge255<C>>::init(proof);
+    verify_proof(
+        params,
+        vk,
+        SingleVerifier::new(params),
+        &[columns.as_slice()],
+        &mut transcript,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ff::Field;
+    use halo2_proofs::dev::MockProver;
+    use halo2_proofs::pasta::{Fp, Fq};
+
+    #[test]
+    fn test_limbs_roundtrip_both_directions() {
+        for value in [Fp::ZERO, Fp::ONE, Fp::from(u64::MAX), -Fp::ONE] {
+            let limbs = to_partner_limbs::<EqAffine>(&value);
+            assert_eq!(from_partner_limbs::<EqAffine>(&limbs), Some(value));
+        }
+        for value in [Fq::ZERO, Fq::from(1 << 40), -Fq::ONE] {
+            let limbs = to_partner_limbs::<EpAffine>(&value);
+            assert_eq!(from_partner_limbs::<EpAffine>(&limbs), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_direct_carry_is_one_way() {
+        // p < q: every Fp value is an Fq value, not the other way around
+        assert!(to_partner_scalar::<EqAffine>(&-Fp::ONE).is_some());
+        assert!(to_partner_scalar::<EpAffine>(&-Fq::ONE).is_none());
+
+        let mut limbs = to_partner_limbs::<EpAffine>(&-Fq::ONE);
+        limbs[0] += Fp::from(u64::MAX);
+        assert_eq!(from_partner_limbs::<EpAffine>(&limbs), None);
+    }
+
+    #[test]
+    fn test_binding_circuit_on_both_fields() {
+        let k = 10;
+        let instance = partner_instance::<EqAffine>(&[Fp::from(7), -Fp::ONE]);
+        let circuit = CycleBindingCircuit::new(instance.clone());
+        let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // Instance that is not the limb encoding
+        let mut wrong = instance;
+        wrong[1] += Fq::ONE;
+        let prover = MockProver::run(k, &circuit, vec![wrong]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let instance = partner_instance::<EpAffine>(&[Fq::from(3)]);
+        let circuit = CycleBindingCircuit::new(instance.clone());
+        let prover = MockProver::run(k, &circuit, vec![instance]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -10,11 +10,12 @@
 // This implementation is fully compatible with the paper and simpler.
 
 use crate::circuit::PoneglyphCircuit;
+use crate::error::PoneglyphResult;
 use crate::prover::Prover;
 use pasta_curves::pallas::Base as Fr;
 
 use halo2_proofs::{
-    pasta::EqAffine,
+    pasta::{EpAffine, EqAffine},
     plonk::{
         create_proof, keygen_pk, keygen_vk, verify_proof, Error, ProvingKey, SingleVerifier,
         VerifyingKey,
@@ -23,6 +24,19 @@ use halo2_proofs::{
     transcript::{Blake2bRead, Blake2bWrite, Challenge255},
 };
 
+pub mod accumulator;
+pub mod chunk;
+pub mod cycle;
+pub mod proof_aggregation;
+pub mod shard;
+pub mod topology;
+pub use accumulator::*;
+pub use chunk::*;
+pub use cycle::*;
+pub use proof_aggregation::*;
+pub use shard::*;
+pub use topology::*;
+
 /// Halo2 Recursive Prover
 /// Paper Section 5: Recursive proof composition using cycle curves
 ///
@@ -53,6 +67,7 @@ pub struct RecursiveProof {
     /// Pallas curve proof (primary)
     pub proof_pallas: Vec<u8>,
     /// Vesta curve proof (verifier, recursive)
+    /// Circuit over vesta::Base committed with `EpAffine`; see `cycle`
     pub proof_vesta: Option<Vec<u8>>,
     /// Public inputs
     pub public_inputs: Vec<Vec<Fr>>,
@@ -114,9 +129,9 @@ impl Halo2RecursiveProver {
             create_proof(
                 params_pallas,
                 &self.pk_pallas,
-                &[circuit.clone()],
+                std::slice::from_ref(circuit),
                 &instances_refs,
-                rand::rngs::OsRng,
+                rand_core::OsRng,
                 &mut transcript,
             )?;
 
@@ -153,7 +168,7 @@ impl Halo2RecursiveProver {
         // Verify (for first circuit - simple implementation)
         // Note: Production should verify all circuits
         if let Some(first_inputs) = proof.public_inputs.first() {
-            let first_instances = vec![vec![first_inputs.as_slice()]];
+            let first_instances = [vec![first_inputs.as_slice()]];
             let first_instances_refs: Vec<&[&[Fr]]> =
                 first_instances.iter().map(|inst| inst.as_slice()).collect();
 
@@ -170,6 +185,57 @@ impl Halo2RecursiveProver {
 
         Ok(true)
     }
+
+    /// Create recursive proof including the Vesta side of the cycle
+    ///
+    /// The Vesta proof binds the public inputs of the first circuit, carried
+    /// into vesta::Base as 64-bit limbs (`partner_instance`).
+    ///
+    /// # Note
+    ///
+    /// The Vesta circuit does not verify the Pallas proof yet; it fixes the
+    /// cross-curve plumbing (fields, commitment curve, instance encoding) that
+    /// the in-circuit verifier will use.
+    pub fn prove_cycle(
+        &self,
+        params_pallas: &Params<EqAffine>,
+        params_vesta: &Params<EpAffine>,
+        circuits: &[PoneglyphCircuit],
+        public_inputs: &[Vec<Fr>],
+    ) -> Result<RecursiveProof, Error> {
+        let mut proof = self.prove_recursive(params_pallas, circuits, public_inputs)?;
+        let first_inputs = public_inputs.first().ok_or(Error::Synthesis)?;
+
+        let instance = partner_instance::<EqAffine>(first_inputs);
+        let circuit = CycleBindingCircuit::new(instance.clone());
+        let prover = CycleProver::<EpAffine>::new(params_vesta, &circuit)?;
+        proof.proof_vesta = Some(prover.prove(params_vesta, &circuit, &[instance])?);
+        Ok(proof)
+    }
+
+    /// Verify both sides of a proof from `prove_cycle`
+    /// The Vesta instance is re-derived from the Pallas public inputs, so a
+    /// Vesta proof for other inputs is rejected.
+    pub fn verify_cycle(
+        &self,
+        params_pallas: &Params<EqAffine>,
+        params_vesta: &Params<EpAffine>,
+        proof: &RecursiveProof,
+    ) -> Result<bool, Error> {
+        if !self.verify_recursive(params_pallas, proof)? {
+            return Ok(false);
+        }
+        let (Some(proof_vesta), Some(first_inputs)) =
+            (&proof.proof_vesta, proof.public_inputs.first())
+        else {
+            return Ok(false);
+        };
+
+        let instance = partner_instance::<EqAffine>(first_inputs);
+        let vk = keygen_vk(params_vesta, &CycleBindingCircuit::shape(instance.len()))?;
+        verify_on_curve(params_vesta, &vk, proof_vesta, &[instance])?;
+        Ok(true)
+    }
 }
 
 /// Incremental Proof Generation
@@ -224,6 +290,40 @@ impl IncrementalProver {
     pub fn accumulated_inputs(&self) -> &[Vec<Fr>] {
         &self.accumulated_inputs
     }
+
+    /// Snapshot of the accumulator for persistence
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// incremental.prove_incremental(&params, &circuit, &inputs)?;
+    /// incremental.state().save("chain.acc")?;
+    /// ```
+    pub fn state(&self) -> AccumulatorState {
+        AccumulatorState::new(
+            vk_fingerprint(self.prover.vk()),
+            self.accumulated_proofs.clone(),
+            &self.accumulated_inputs,
+        )
+    }
+
+    /// Continue a chain from a saved accumulator
+    /// Fails if `prover` has a different key than the chain was built with
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let state = AccumulatorState::load("chain.acc")?;
+    /// let mut incremental = IncrementalProver::restore(prover, &state)?;
+    /// ```
+    pub fn restore(prover: Prover, state: &AccumulatorState) -> PoneglyphResult<Self> {
+        state.check_key(vk_fingerprint(prover.vk()))?;
+        Ok(Self {
+            prover,
+            accumulated_proofs: state.proofs.clone(),
+            accumulated_inputs: state.inputs()?,
+        })
+    }
 }
 
 /// Batch Proof Processing
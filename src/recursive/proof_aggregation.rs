@@ -0,0 +1,331 @@
+// Proof aggregation
+// Halo-style accumulation of query proofs on the pasta cycle.
+//
+// Verifying an IPA opening has a cheap part, linear in k over the proof's
+// commitments, and an expensive part: checking that the prover's point G is
+// ⟨s(u), g⟩ for the round challenges u, an MSM over all 2^k generators. Halo
+// defers the expensive part: each proof leaves an accumulator (G, u), and
+// accumulators fold by a random linear combination whose single check covers
+// all of them:
+//
+//   Σ rⁱ·Gᵢ = ⟨Σ rⁱ·s(uᵢ), g⟩
+//
+// `RecursiveAggregator::fold` runs the cheap part of every proof and folds
+// their accumulators; `AggregatedProof::verify` re-runs the cheap parts
+// against the carried Gᵢ and checks the folded accumulator with one MSM, so N
+// proofs cost one generator MSM instead of N. r is derived after every Gᵢ is
+// fixed, so a wrong Gᵢ fails the folded check with overwhelming probability.
+//
+// The folded accumulator is constant size, but the aggregate still carries
+// the inner proofs: dropping them needs the Vesta-side circuit that runs the
+// cheap part in-circuit (see `Halo2RecursiveProver::prove_cycle`), which is
+// not implemented.
+
+use ff::Field;
+use group::{Curve, GroupEncoding};
+use halo2_proofs::{
+    arithmetic::best_multiexp,
+    pasta::EqAffine,
+    plonk::{verify_proof, Error, VerificationStrategy, VerifyingKey},
+    poly::commitment::{Guard, Params, MSM},
+    transcript::{Blake2bRead, Challenge255, EncodedChallenge},
+};
+use pasta_curves::pallas::Base as Fr;
+
+use super::accumulator::vk_fingerprint;
+use crate::error::VerifyError;
+use crate::utils::{poseidon_hash, poseidon_hash_bytes};
+
+/// IPA accumulator of one proof: the deferred point G and the round
+/// challenges it must be the commitment of
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IpaAccumulator {
+    pub g: EqAffine,
+    /// u₀ … u_{k-1}, in transcript order
+    pub challenges: Vec<Fr>,
+}
+
+impl IpaAccumulator {
+    /// Coefficients s(u) with G = ⟨s(u), g⟩ (halo2's `compute_s`)
+    pub fn coefficients(&self) -> Vec<Fr> {
+        let mut s = vec![Fr::ZERO; 1 << self.challenges.len()];
+        s[0] = Fr::ONE;
+        for (round, u) in self.challenges.iter().rev().enumerate() {
+            let len = 1 << round;
+            let (left, right) = s.split_at_mut(len);
+            for (r, l) in right[..len].iter_mut().zip(left.iter()) {
+                *r = *l * u;
+            }
+        }
+        s
+    }
+}
+
+/// Verification strategy that defers the generator MSM
+/// With `g` the caller supplies the deferred point (verifier side); without
+/// it the point is computed (aggregator side).
+struct DeferredVerifier<'params> {
+    msm: MSM<'params, EqAffine>,
+    g: Option<EqAffine>,
+}
+
+impl<'params> VerificationStrategy<'params, EqAffine> for DeferredVerifier<'params> {
+    type Output = IpaAccumulator;
+
+    fn process<E: EncodedChallenge<EqAffine>>(
+        self,
+        f: impl FnOnce(MSM<'params, EqAffine>) -> Result<Guard<'params, EqAffine, E>, Error>,
+    ) -> Result<IpaAccumulator, Error> {
+        let guard = f(self.msm)?;
+        let g = self.g.unwrap_or_else(|| guard.compute_g());
+        let (msm, accumulator) = guard.use_g(g);
+        if !msm.eval() {
+            return Err(Error::ConstraintSystemFailure);
+        }
+        Ok(IpaAccumulator {
+            g,
+            challenges: accumulator
+                .u_packed
+                .iter()
+                .map(|u| u.get_scalar())
+                .collect(),
+        })
+    }
+}
+
+/// Cheap part of verifying one proof
+fn defer(
+    params: &Params<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    proof: &[u8],
+    instance: &[Fr],
+    g: Option<EqAffine>,
+) -> Result<IpaAccumulator, Error> {
+    let strategy = DeferredVerifier {
+        msm: params.empty_msm(),
+        g,
+    };
+    let instances: &[&[&[Fr]]] = &[&[instance]];
+    let mut transcript = Blake2bRead::<&[u8], EqAffine, Challenge255<EqAffine>>::init(proof);
+    verify_proof(params, vk, strategy, instances, &mut transcript)
+}
+
+/// Folding challenge r over everything the accumulators depend on
+fn fold_challenge(
+    vk_fingerprint: u64,
+    proofs: &[Vec<u8>],
+    instances: &[Vec<Fr>],
+    deferred: &[EqAffine],
+) -> Fr {
+    let mut inputs = vec![Fr::from(vk_fingerprint)];
+    for ((proof, instance), g) in proofs.iter().zip(instances).zip(deferred) {
+        inputs.push(poseidon_hash_bytes(proof));
+        inputs.push(poseidon_hash(instance));
+        inputs.push(poseidon_hash_bytes(g.to_bytes().as_ref()));
+    }
+    poseidon_hash(&inputs)
+}
+
+/// r⁰ … r^{n-1}
+fn powers(r: Fr, n: usize) -> Vec<Fr> {
+    std::iter::successors(Some(Fr::ONE), |p| Some(*p * r))
+        .take(n)
+        .collect()
+}
+
+/// Proofs of one circuit, aggregated into a single deferred check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AggregatedProof {
+    /// `vk_fingerprint` of the key every proof was made with
+    pub vk_fingerprint: u64,
+    /// Inner proofs, in fold order
+    pub proofs: Vec<Vec<u8>>,
+    /// Public inputs of each proof (one instance column)
+    pub instances: Vec<Vec<Fr>>,
+    /// Deferred point Gᵢ of each proof
+    pub deferred: Vec<EqAffine>,
+    /// Folded accumulator Σ rⁱ·Gᵢ
+    pub accumulator: EqAffine,
+}
+
+impl AggregatedProof {
+    /// Number of aggregated proofs
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Verify every aggregated proof with one generator MSM
+    ///
+    /// # Errors
+    ///
+    /// `WrongCircuitVersion` if the proofs were made for another key,
+    /// `MalformedEnvelope` if the parts don't line up, otherwise as
+    /// `Verifier::verify`; a folded accumulator that doesn't open is
+    /// `InvalidProof`
+    pub fn verify(
+        &self,
+        params: &Params<EqAffine>,
+        vk: &VerifyingKey<EqAffine>,
+    ) -> Result<(), VerifyError> {
+        let expected = vk_fingerprint(vk);
+        if self.vk_fingerprint != expected {
+            return Err(VerifyError::WrongCircuitVersion {
+                expected,
+                found: self.vk_fingerprint,
+            });
+        }
+        if self.is_empty()
+            || self.instances.len() != self.len()
+            || self.deferred.len() != self.len()
+        {
+            return Err(VerifyError::MalformedEnvelope(format!(
+                "{} proofs, {} instances, {} deferred points",
+                self.len(),
+                self.instances.len(),
+                self.deferred.len()
+            )));
+        }
+
+        let accumulators = self
+            .proofs
+            .iter()
+            .zip(&self.instances)
+            .zip(&self.deferred)
+            .map(|((proof, instance), &g)| defer(params, vk, proof, instance, Some(g)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let r = fold_challenge(
+            self.vk_fingerprint,
+            &self.proofs,
+            &self.instances,
+            &self.deferred,
+        );
+        let powers = powers(r, self.len());
+        if best_multiexp(&powers, &self.deferred).to_affine() != self.accumulator {
+            return Err(VerifyError::InvalidProof(
+                "folded accumulator is not the fold of the deferred points".to_string(),
+            ));
+        }
+
+        // The one expensive check: Σ rⁱ·Gᵢ = ⟨Σ rⁱ·s(uᵢ), g⟩
+        let mut coeffs = vec![Fr::ZERO; 1 << params.k()];
+        for (accumulator, power) in accumulators.iter().zip(&powers) {
+            for (coeff, s) in coeffs.iter_mut().zip(accumulator.coefficients()) {
+                *coeff += s * power;
+            }
+        }
+        if best_multiexp(&coeffs, &params.get_g()).to_affine() != self.accumulator {
+            return Err(VerifyError::InvalidProof(
+                "folded accumulator does not open".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Recursive Aggregator
+/// Folds independent proofs of one circuit (e.g. query proofs from
+/// `Prover::prove`) into an `AggregatedProof`
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let aggregator = RecursiveAggregator::new(verifier_key);
+/// let aggregated = aggregator.fold(&params, &[(proof_a, inputs_a), (proof_b, inputs_b)])?;
+/// aggregator.verify(&params, &aggregated)?;
+/// ```
+pub struct RecursiveAggregator {
+    vk: VerifyingKey<EqAffine>,
+}
+
+impl RecursiveAggregator {
+    pub fn new(vk: VerifyingKey<EqAffine>) -> Self {
+        Self { vk }
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<EqAffine> {
+        &self.vk
+    }
+
+    /// Fold `(proof, instance)` pairs, in order
+    /// Every proof is checked up to its deferred point, so an invalid proof
+    /// is rejected here rather than only at verification
+    ///
+    /// # Errors
+    ///
+    /// `MalformedEnvelope` if there are no proofs, otherwise the first
+    /// failing proof's error, as in `Verifier::verify`
+    pub fn fold(
+        &self,
+        params: &Params<EqAffine>,
+        proofs: &[(Vec<u8>, Vec<Fr>)],
+    ) -> Result<AggregatedProof, VerifyError> {
+        if proofs.is_empty() {
+            return Err(VerifyError::MalformedEnvelope(
+                "no proofs to aggregate".to_string(),
+            ));
+        }
+        let deferred = proofs
+            .iter()
+            .map(|(proof, instance)| Ok(defer(params, &self.vk, proof, instance, None)?.g))
+            .collect::<Result<Vec<_>, VerifyError>>()?;
+        let (proofs, instances): (Vec<_>, Vec<_>) = proofs.iter().cloned().unzip();
+
+        let vk_fingerprint = vk_fingerprint(&self.vk);
+        let r = fold_challenge(vk_fingerprint, &proofs, &instances, &deferred);
+        let accumulator = best_multiexp(&powers(r, deferred.len()), &deferred).to_affine();
+        Ok(AggregatedProof {
+            vk_fingerprint,
+            proofs,
+            instances,
+            deferred,
+            accumulator,
+        })
+    }
+
+    /// Verify an aggregate made with this aggregator's key
+    pub fn verify(
+        &self,
+        params: &Params<EqAffine>,
+        aggregated: &AggregatedProof,
+    ) -> Result<(), VerifyError> {
+        aggregated.verify(params, &self.vk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coefficients_are_products_of_challenges() {
+        let u = [Fr::from(2), Fr::from(3), Fr::from(5)];
+        let accumulator = IpaAccumulator {
+            g: EqAffine::default(),
+            challenges: u.to_vec(),
+        };
+        let s = accumulator.coefficients();
+        assert_eq!(s.len(), 8);
+        // Bit j of the index (from the top) selects u_j
+        for (i, coeff) in s.iter().enumerate() {
+            let expected = u
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i >> (u.len() - 1 - j) & 1 == 1)
+                .fold(Fr::ONE, |acc, (_, u)| acc * u);
+            assert_eq!(*coeff, expected, "s[{}]", i);
+        }
+    }
+
+    #[test]
+    fn test_powers() {
+        assert_eq!(
+            powers(Fr::from(3), 4),
+            vec![Fr::ONE, Fr::from(3), Fr::from(9), Fr::from(27)]
+        );
+    }
+}
@@ -0,0 +1,413 @@
+// Parallel shard proving
+// A large query is split into shards (one `PoneglyphCircuit` each) that are
+// proven independently. `ShardedProver` spreads the shards over a pool of
+// worker threads: each worker starts with a contiguous block of shards and,
+// once its own block is drained, steals from the back of the busiest worker,
+// so a few slow shards don't leave the other workers idle.
+//
+// Proofs are folded in shard order, never in completion order, so the folded
+// proof is the same whatever the scheduling was.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use halo2_proofs::{pasta::EqAffine, plonk::Error, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+
+use crate::circuit::{PartialAggregate, PoneglyphCircuit};
+use crate::error::VerifyError;
+use crate::prover::{Prover, Verifier};
+use crate::utils::poseidon_hash_bytes;
+
+use super::FoldTopology;
+
+/// State change of a shard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShardStatus {
+    Started,
+    Proved,
+    Failed(String),
+}
+
+/// Progress report, sent once when a shard starts and once when it ends
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardProgress {
+    pub shard: usize,
+    /// Worker that runs the shard
+    pub worker: usize,
+    /// True if the shard was stolen from another worker's queue
+    pub stolen: bool,
+    pub status: ShardStatus,
+    /// Shards finished so far (including this one once it ends)
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// Per-worker shard queues with stealing
+struct ShardQueues {
+    queues: Vec<Mutex<VecDeque<usize>>>,
+}
+
+impl ShardQueues {
+    /// Split `0..num_shards` into contiguous blocks, one per worker
+    fn new(num_shards: usize, workers: usize) -> Self {
+        let block = num_shards.div_ceil(workers).max(1);
+        let queues = (0..workers)
+            .map(|w| Mutex::new((w * block..((w + 1) * block).min(num_shards)).collect()))
+            .collect();
+        Self { queues }
+    }
+
+    /// Next shard for `worker`: the front of its own queue, otherwise the
+    /// back of the longest other queue. `None` once every queue is empty.
+    fn next(&self, worker: usize) -> Option<(usize, bool)> {
+        if let Some(shard) = self.queues[worker].lock().unwrap().pop_front() {
+            return Some((shard, false));
+        }
+        loop {
+            let victim = (0..self.queues.len())
+                .filter(|&w| w != worker)
+                .max_by_key(|&w| self.queues[w].lock().unwrap().len())?;
+            let mut queue = self.queues[victim].lock().unwrap();
+            if let Some(shard) = queue.pop_back() {
+                return Some((shard, true));
+            }
+            drop(queue);
+            // The victim drained between the scan and the lock; rescan
+            if self.queues.iter().all(|q| q.lock().unwrap().is_empty()) {
+                return None;
+            }
+        }
+    }
+}
+
+/// Run `job` for every shard on `workers` threads with work stealing
+/// Results are returned in shard order. After a failure no new shards are
+/// started; the error of the lowest failed shard is returned.
+pub fn run_shards<T, E, J, P>(
+    num_shards: usize,
+    workers: usize,
+    job: J,
+    progress: P,
+) -> Result<Vec<T>, E>
+where
+    T: Send,
+    E: Send + std::fmt::Debug,
+    J: Fn(usize) -> Result<T, E> + Sync,
+    P: Fn(ShardProgress) + Sync,
+{
+    let workers = workers.clamp(1, num_shards.max(1));
+    let queues = ShardQueues::new(num_shards, workers);
+    let slots: Vec<Mutex<Option<Result<T, E>>>> =
+        (0..num_shards).map(|_| Mutex::new(None)).collect();
+    let completed = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for worker in 0..workers {
+            let (queues, slots, completed, failed) = (&queues, &slots, &completed, &failed);
+            let (job, progress) = (&job, &progress);
+            scope.spawn(move || {
+                while !failed.load(Ordering::SeqCst) {
+                    let Some((shard, stolen)) = queues.next(worker) else {
+                        break;
+                    };
+                    let report = |status, done| ShardProgress {
+                        shard,
+                        worker,
+                        stolen,
+                        status,
+                        completed: done,
+                        total: num_shards,
+                    };
+                    progress(report(
+                        ShardStatus::Started,
+                        completed.load(Ordering::SeqCst),
+                    ));
+
+                    let result = job(shard);
+                    let status = match &result {
+                        Ok(_) => ShardStatus::Proved,
+                        Err(e) => {
+                            failed.store(true, Ordering::SeqCst);
+                            ShardStatus::Failed(format!("{:?}", e))
+                        }
+                    };
+                    *slots[shard].lock().unwrap() = Some(result);
+                    progress(report(status, completed.fetch_add(1, Ordering::SeqCst) + 1));
+                }
+            });
+        }
+    });
+
+    // After a failure some shards never ran; report the lowest failure
+    let mut results = Vec::with_capacity(num_shards);
+    for slot in slots {
+        match slot.into_inner().unwrap() {
+            Some(Ok(value)) => results.push(value),
+            Some(Err(e)) => return Err(e),
+            None => {}
+        }
+    }
+    Ok(results)
+}
+
+/// Aggregate over all shards from their summaries, folded with `topology`
+/// None if a sum overflows; no shards give the empty summary
+pub fn merge_shard_partials(
+    partials: &[PartialAggregate],
+    topology: FoldTopology,
+) -> Option<PartialAggregate> {
+    topology
+        .fold(
+            partials.iter().map(|p| Some(*p)).collect(),
+            |left, right| left?.merge(&right?),
+        )
+        .unwrap_or(Some(PartialAggregate::default()))
+}
+
+/// Proofs of all shards, in shard order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardedProof {
+    pub proofs: Vec<Vec<u8>>,
+    /// Topology of `root`
+    pub topology: FoldTopology,
+}
+
+impl ShardedProof {
+    /// Folded proof: shard proofs concatenated in shard order
+    /// (the same encoding as `BatchProver::prove_batch`)
+    pub fn fold(&self) -> Vec<u8> {
+        self.proofs.concat()
+    }
+
+    /// Fold root of the shard proofs (Poseidon digests folded per `topology`)
+    /// `None` if there are no shards
+    pub fn root(&self) -> Option<Fr> {
+        let digests: Vec<Fr> = self
+            .proofs
+            .iter()
+            .map(|proof| poseidon_hash_bytes(proof))
+            .collect();
+        self.topology.fold_digests(&digests)
+    }
+
+    /// Verify every shard proof against its public inputs
+    ///
+    /// # Errors
+    ///
+    /// `InstanceMismatch` if there isn't one instance per shard, otherwise the
+    /// first shard's `Verifier::verify` error
+    pub fn verify(
+        &self,
+        params: &Params<EqAffine>,
+        verifier: &Verifier,
+        public_inputs: &[Vec<Vec<Fr>>],
+    ) -> Result<(), VerifyError> {
+        if self.proofs.len() != public_inputs.len() {
+            return Err(VerifyError::InstanceMismatch(format!(
+                "{} shard proofs, {} instances",
+                self.proofs.len(),
+                public_inputs.len()
+            )));
+        }
+        for (proof, inputs) in self.proofs.iter().zip(public_inputs) {
+            verifier.verify(params, proof, inputs)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sharded Prover
+/// Proves the shards of one query in parallel with a shared proving key
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let sharded = ShardedProver::new(prover, 8);
+/// let proof = sharded.prove_shards(&params, &shards, &inputs, |p| {
+///     eprintln!("shard {} {:?} ({}/{})", p.shard, p.status, p.completed, p.total)
+/// })?;
+/// publish(proof.fold());
+/// ```
+///
+/// # Note
+///
+/// Workers are threads of this process; proving across processes needs the
+/// shards and key to be shipped to them and is not handled here.
+pub struct ShardedProver {
+    prover: Prover,
+    workers: usize,
+    topology: FoldTopology,
+}
+
+impl ShardedProver {
+    /// Create sharded prover with `workers` threads (at least one)
+    pub fn new(prover: Prover, workers: usize) -> Self {
+        Self {
+            prover,
+            workers: workers.max(1),
+            topology: FoldTopology::default(),
+        }
+    }
+
+    /// Fold shard results with `topology` (default: `FoldTopology::Sequential`)
+    pub fn with_topology(mut self, topology: FoldTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Create sharded prover with one worker per available CPU
+    pub fn with_available_parallelism(prover: Prover) -> Self {
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::new(prover, workers)
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    pub fn topology(&self) -> FoldTopology {
+        self.topology
+    }
+
+    /// Prove all shards; `public_inputs[i]` is the instance of shard `i`
+    /// (missing entries are empty, as in `BatchProver::prove_batch`)
+    pub fn prove_shards(
+        &self,
+        params: &Params<EqAffine>,
+        circuits: &[PoneglyphCircuit],
+        public_inputs: &[Vec<Vec<Fr>>],
+        progress: impl Fn(ShardProgress) + Sync,
+    ) -> Result<ShardedProof, Error> {
+        let proofs = run_shards(
+            circuits.len(),
+            self.workers,
+            |shard| {
+                let inputs = public_inputs.get(shard).map_or(&[][..], |i| i.as_slice());
+                self.prover.prove(params, &circuits[shard], inputs)
+            },
+            progress,
+        )?;
+        Ok(ShardedProof {
+            proofs,
+            topology: self.topology,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_results_in_shard_order() {
+        // Early shards are slow, so later blocks finish first
+        let results = run_shards(
+            16,
+            4,
+            |shard| {
+                thread::sleep(Duration::from_millis(16 - shard as u64));
+                Ok::<_, String>(shard * 10)
+            },
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(results, (0..16).map(|s| s * 10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_idle_workers_steal() {
+        let reports = Mutex::new(Vec::new());
+        run_shards(
+            8,
+            2,
+            |shard| {
+                // Worker 0's block is slow
+                if shard < 4 {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Ok::<_, String>(())
+            },
+            |p| reports.lock().unwrap().push(p),
+        )
+        .unwrap();
+
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.len(), 16);
+        let finished: Vec<_> = reports
+            .iter()
+            .filter(|p| p.status == ShardStatus::Proved)
+            .collect();
+        assert_eq!(finished.len(), 8);
+        assert_eq!(finished.iter().map(|p| p.completed).max(), Some(8));
+        assert!(reports
+            .iter()
+            .any(|p| p.stolen && p.worker == 1 && p.shard < 4));
+    }
+
+    #[test]
+    fn test_lowest_failure_is_returned() {
+        let result = run_shards(
+            6,
+            3,
+            |shard| {
+                if shard % 2 == 1 {
+                    Err(shard)
+                } else {
+                    Ok(shard)
+                }
+            },
+            |_| {},
+        );
+        // Which odd shards run depends on scheduling; the lowest one that
+        // failed is reported
+        assert!(matches!(result, Err(1) | Err(3) | Err(5)));
+    }
+
+    #[test]
+    fn test_root_follows_topology() {
+        let proof = |topology| ShardedProof {
+            proofs: vec![vec![1], vec![2, 3], vec![4], vec![5]],
+            topology,
+        };
+        let seq = proof(FoldTopology::Sequential);
+        let tree = proof(FoldTopology::BinaryTree);
+        assert_eq!(seq.fold(), tree.fold());
+        assert_ne!(seq.root(), tree.root());
+        assert_eq!(
+            ShardedProof {
+                proofs: vec![],
+                topology: FoldTopology::BinaryTree
+            }
+            .root(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_merge_shard_partials() {
+        let shards = [vec![4u64, 8], vec![1], vec![], vec![6, 2, 9]];
+        let partials: Vec<_> = shards
+            .iter()
+            .map(|values| PartialAggregate::of(values).unwrap())
+            .collect();
+        let whole = PartialAggregate::of(&shards.concat()).unwrap();
+        for topology in [FoldTopology::Sequential, FoldTopology::BinaryTree] {
+            assert_eq!(merge_shard_partials(&partials, topology), Some(whole));
+        }
+        assert_eq!(
+            merge_shard_partials(&[], FoldTopology::BinaryTree),
+            Some(PartialAggregate::default())
+        );
+    }
+
+    #[test]
+    fn test_no_shards() {
+        let results = run_shards(0, 4, |_| Ok::<u8, String>(0), |_| {}).unwrap();
+        assert!(results.is_empty());
+    }
+}
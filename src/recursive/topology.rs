@@ -0,0 +1,147 @@
+// Fold topology
+// Shard results are folded pairwise into one accumulator. The shape of the
+// fold is a deployment choice:
+//
+// | Topology     | Fold steps | Rounds (latency)  | Live accumulators (memory) |
+// |--------------|------------|-------------------|----------------------------|
+// | `Sequential` | n - 1      | n - 1             | 2                          |
+// | `BinaryTree` | n - 1      | ceil(log2 n)      | n                          |
+//
+// Both do the same amount of work. The balanced tree folds each level in
+// parallel, so it finishes in logarithmic rounds but keeps a whole level
+// alive; the chain folds each shard into the accumulator as it arrives, so it
+// needs constant memory but one round per shard. `FoldTopology::cost` reports
+// these numbers for a given shard count; `benches/fold_topology.rs` measures
+// the wall-clock side.
+//
+// The two topologies produce different fold roots for the same leaves, so
+// prover and verifier must agree on it (it is part of `ShardedProof`).
+
+use pasta_curves::pallas::Base as Fr;
+
+use crate::utils::poseidon_hash;
+
+/// Shape of the recursion over shard results
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FoldTopology {
+    /// Linear chain: ((s0 · s1) · s2) · s3
+    #[default]
+    Sequential,
+    /// Balanced binary tree: (s0 · s1) · (s2 · s3); an odd node is carried up
+    BinaryTree,
+}
+
+/// Cost of folding a number of leaves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FoldCost {
+    /// Pairwise fold operations
+    pub steps: usize,
+    /// Rounds of folds that can't run in parallel
+    pub rounds: usize,
+    /// Peak number of accumulators held at once
+    pub peak_live: usize,
+}
+
+impl FoldTopology {
+    /// Cost of folding `leaves` results
+    pub fn cost(&self, leaves: usize) -> FoldCost {
+        let steps = leaves.saturating_sub(1);
+        match self {
+            FoldTopology::Sequential => FoldCost {
+                steps,
+                rounds: steps,
+                peak_live: leaves.min(2),
+            },
+            FoldTopology::BinaryTree => FoldCost {
+                steps,
+                rounds: leaves.next_power_of_two().trailing_zeros() as usize,
+                peak_live: leaves,
+            },
+        }
+    }
+
+    /// Fold `leaves` with `combine`; `None` if there are no leaves
+    pub fn fold<T>(&self, leaves: Vec<T>, mut combine: impl FnMut(T, T) -> T) -> Option<T> {
+        match self {
+            FoldTopology::Sequential => leaves.into_iter().reduce(combine),
+            FoldTopology::BinaryTree => {
+                let mut level = leaves;
+                while level.len() > 1 {
+                    let mut next = Vec::with_capacity(level.len().div_ceil(2));
+                    let mut nodes = level.into_iter();
+                    while let Some(left) = nodes.next() {
+                        next.push(match nodes.next() {
+                            Some(right) => combine(left, right),
+                            None => left,
+                        });
+                    }
+                    level = next;
+                }
+                level.pop()
+            }
+        }
+    }
+
+    /// Fold root of shard digests: each fold is `poseidon_hash([left, right])`
+    pub fn fold_digests(&self, digests: &[Fr]) -> Option<Fr> {
+        self.fold(digests.to_vec(), |left, right| {
+            poseidon_hash(&[left, right])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_order() {
+        let leaves: Vec<String> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let combine = |l: String, r: String| format!("({}{})", l, r);
+        assert_eq!(
+            FoldTopology::Sequential.fold(leaves.clone(), combine),
+            Some("((((ab)c)d)e)".to_string())
+        );
+        assert_eq!(
+            FoldTopology::BinaryTree.fold(leaves, combine),
+            Some("(((ab)(cd))e)".to_string())
+        );
+        assert_eq!(
+            FoldTopology::BinaryTree.fold(Vec::<u8>::new(), |l, _| l),
+            None
+        );
+        assert_eq!(FoldTopology::Sequential.fold(vec![7], |l, _| l), Some(7));
+    }
+
+    #[test]
+    fn test_cost() {
+        for n in [1, 2, 5, 8, 1000] {
+            let seq = FoldTopology::Sequential.cost(n);
+            let tree = FoldTopology::BinaryTree.cost(n);
+            assert_eq!(seq.steps, tree.steps);
+            assert!(tree.rounds <= seq.rounds);
+            assert!(seq.peak_live <= tree.peak_live);
+        }
+        assert_eq!(FoldTopology::BinaryTree.cost(1000).rounds, 10);
+        assert_eq!(FoldTopology::Sequential.cost(1000).peak_live, 2);
+        assert_eq!(FoldTopology::BinaryTree.cost(0).rounds, 0);
+    }
+
+    #[test]
+    fn test_digest_roots_differ() {
+        let digests: Vec<Fr> = (0..4u64).map(Fr::from).collect();
+        let seq = FoldTopology::Sequential.fold_digests(&digests).unwrap();
+        let tree = FoldTopology::BinaryTree.fold_digests(&digests).unwrap();
+        assert_ne!(seq, tree);
+        assert_eq!(
+            tree,
+            poseidon_hash(&[
+                poseidon_hash(&[digests[0], digests[1]]),
+                poseidon_hash(&[digests[2], digests[3]])
+            ])
+        );
+    }
+}
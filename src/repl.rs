@@ -0,0 +1,393 @@
+// Interactive shell
+// `poneglyphdb repl` (feature `cli`) reads SQL statements over several lines
+// until a `;`, and backslash commands on a line of their own:
+//
+// | Input               | Effect                                         |
+// |---------------------|------------------------------------------------|
+// | `SELECT ...;`       | prove and verify if supported, else execute    |
+// | `EXPLAIN ...;`      | cost report (`Session::explain`)               |
+// | `SET ...;`          | session setting                                |
+// | `\d` / `\d NAME`    | list tables / columns of a table               |
+// | `\load FILE [NAME]` | register a `.db` (committed), CSV or JSON file |
+// | `\c`                | discard the statement being typed              |
+// | `\?`, `\q`          | help, quit                                     |
+//
+// A proven query shows its proving and verification time and the proof size
+// next to the result; anything else shows why it isn't proven. `Repl` only
+// maps input lines to output text, the binary adds line editing and history.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::database::{Database, StoredTable, TypeInference};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::prover::{verify_raw, QueryOutcome, Session};
+use crate::utils::text_table;
+
+const HELP: &str = "\
+SQL statements end with ';' and may span lines.
+  SELECT ...;         prove and verify if supported, else execute
+  EXPLAIN SELECT ...; cost of proving the query
+  SET name = value;   session setting (parse_mode, privacy_level, max_k, lint_*)
+  \\d                  list tables
+  \\d NAME             columns of a table
+  \\load FILE [NAME]   register a committed .db, CSV or JSON file
+  \\c                  discard the current statement
+  \\?                  this help
+  \\q                  quit";
+
+/// What the caller does after a line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplStep {
+    /// Print the text (may be empty) and read the next line
+    Print(String),
+    Quit,
+}
+
+/// Line-oriented shell over a `Session`
+pub struct Repl {
+    session: Session,
+    /// Statement typed so far
+    buffer: String,
+}
+
+impl Repl {
+    pub fn new(session: Session) -> Self {
+        Self {
+            session,
+            buffer: String::new(),
+        }
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    pub fn session_mut(&mut self) -> &mut Session {
+        &mut self.session
+    }
+
+    /// Prompt for the next line (a continuation inside a statement)
+    pub fn prompt(&self) -> &'static str {
+        if self.buffer.is_empty() {
+            "poneglyph> "
+        } else {
+            "       ..> "
+        }
+    }
+
+    /// Handle one input line
+    pub fn feed(&mut self, line: &str) -> ReplStep {
+        let trimmed = line.trim();
+        if self.buffer.is_empty() {
+            if let Some(command) = trimmed.strip_prefix('\\') {
+                return self.command(command);
+            }
+            if trimmed.is_empty() {
+                return ReplStep::Print(String::new());
+            }
+        }
+
+        if !self.buffer.is_empty() {
+            self.buffer.push('\n');
+        }
+        self.buffer.push_str(line);
+        if !trimmed.ends_with(';') {
+            return ReplStep::Print(String::new());
+        }
+        let statement = std::mem::take(&mut self.buffer);
+        let statement = statement.trim().trim_end_matches(';').trim();
+        ReplStep::Print(match self.statement(statement) {
+            Ok(output) => output,
+            Err(e) => format!("error: {}", e),
+        })
+    }
+
+    fn command(&mut self, command: &str) -> ReplStep {
+        let mut words = command.split_whitespace();
+        let output = match (words.next(), words.next(), words.next()) {
+            (Some("q" | "quit"), None, None) => return ReplStep::Quit,
+            (Some("?" | "h" | "help"), None, None) => HELP.to_string(),
+            (Some("c"), None, None) => {
+                self.buffer.clear();
+                String::new()
+            }
+            (Some("d"), None, None) => self.tables(),
+            (Some("d"), Some(name), None) => self.columns(name),
+            (Some("load"), Some(path), name) => match self.load(Path::new(path), name) {
+                Ok(output) => output,
+                Err(e) => format!("error: {}", e),
+            },
+            _ => format!("error: unknown command \\{} (\\? for help)", command),
+        };
+        ReplStep::Print(output)
+    }
+
+    fn statement(&mut self, sql: &str) -> PoneglyphResult<String> {
+        let keyword = sql.split_whitespace().next().unwrap_or_default();
+        if keyword.eq_ignore_ascii_case("explain") {
+            let query = sql[keyword.len()..].trim_start();
+            return Ok(self.session.explain(query)?.to_string());
+        }
+        if keyword.eq_ignore_ascii_case("set") {
+            self.session.execute(sql)?;
+            return Ok("SET".to_string());
+        }
+        self.query(sql)
+    }
+
+    /// Prove and verify a supported query, execute any other
+    fn query(&mut self, sql: &str) -> PoneglyphResult<String> {
+        let mut output = String::new();
+        for lint in self.session.lint(sql)? {
+            let _ = writeln!(output, "warning: {}", lint);
+        }
+
+        let capabilities = self.session.capabilities(sql)?;
+        if capabilities.is_fully_supported() {
+            let started = Instant::now();
+            match self.session.prove_portable(sql) {
+                Ok(portable) => {
+                    let proving = started.elapsed();
+                    let started = Instant::now();
+                    let verified = verify_raw(
+                        &portable.verifying_key.to_bytes()?,
+                        &portable.public_inputs.to_bytes()?,
+                        &portable.proof.to_bytes()?,
+                    );
+                    let verifying = started.elapsed();
+                    let _ = writeln!(output, "{}", portable.result);
+                    let _ = write!(
+                        output,
+                        "proved in {} (k = {}, {} bytes), ",
+                        format_duration(proving),
+                        portable.proof.k,
+                        portable.proof.bytes.len()
+                    );
+                    match verified {
+                        Ok(()) => {
+                            let _ = write!(output, "verified in {}", format_duration(verifying));
+                        }
+                        Err(e) => {
+                            let _ = write!(output, "VERIFICATION FAILED: {}", e);
+                        }
+                    }
+                    return Ok(output);
+                }
+                // Not a single value: shown unproved below
+                Err(PoneglyphError::InvalidInput(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let started = Instant::now();
+        let outcome = self.session.prove_or_execute(sql)?;
+        let elapsed = started.elapsed();
+        let _ = writeln!(output, "{}", outcome.result());
+        let _ = write!(
+            output,
+            "executed in {}, not proven",
+            format_duration(elapsed)
+        );
+        if let QueryOutcome::Unproved { report, .. } = outcome {
+            for item in &report.unsupported {
+                let _ = write!(output, "\n  {}: {}", item.feature, item.reason);
+            }
+        }
+        Ok(output)
+    }
+
+    fn tables(&self) -> String {
+        let listing = self.session.catalog_listing();
+        if listing.tables.is_empty() {
+            return "no tables (\\load FILE to add one)".to_string();
+        }
+        let columns = ["table", "version", "rows", "columns"].map(str::to_string);
+        let rows: Vec<Vec<String>> = listing
+            .tables
+            .iter()
+            .map(|t| {
+                let names: Vec<&str> = t.columns.iter().map(|c| c.name.as_str()).collect();
+                vec![
+                    t.name.clone(),
+                    t.version.to_string(),
+                    t.rows.to_string(),
+                    names.join(", "),
+                ]
+            })
+            .collect();
+        format_table(&columns, &rows)
+    }
+
+    fn columns(&self, name: &str) -> String {
+        let listing = self.session.catalog_listing();
+        let table = match listing.table(name) {
+            Some(table) => table,
+            None => return format!("error: no table {}", name),
+        };
+        let columns = ["column", "scale", "min", "max", "distinct"].map(str::to_string);
+        let cell = |v: Option<u64>| v.map_or(String::new(), |v| v.to_string());
+        let rows: Vec<Vec<String>> = table
+            .columns
+            .iter()
+            .map(|c| {
+                vec![
+                    c.name.clone(),
+                    c.scale.to_string(),
+                    cell(c.statistics.min),
+                    cell(c.statistics.max),
+                    c.statistics.distinct.to_string(),
+                ]
+            })
+            .collect();
+        format!(
+            "{}commitment {}",
+            format_table(&columns, &rows),
+            table.commitment
+        )
+    }
+
+    /// Register a committed `.db` file, or load and commit a CSV / JSON file
+    fn load(&mut self, path: &Path, name: Option<&str>) -> PoneglyphResult<String> {
+        let table = match path.extension().and_then(|e| e.to_str()) {
+            Some("db") => {
+                let bytes = std::fs::read(path).map_err(|e| {
+                    PoneglyphError::Serialization(format!("{}: {}", path.display(), e))
+                })?;
+                let stored = StoredTable::from_bytes(&bytes)?;
+                let report = stored.verify_integrity();
+                if !report.is_intact() {
+                    return Err(PoneglyphError::Validation(report.to_string()));
+                }
+                let mut table = stored.table()?;
+                if let Some(name) = name {
+                    table.name = name.to_string();
+                }
+                table
+            }
+            extension => {
+                let name = match name.or_else(|| path.file_stem().and_then(|s| s.to_str())) {
+                    Some(name) => name.to_string(),
+                    None => {
+                        return Err(PoneglyphError::InvalidInput(format!(
+                            "{}: no table name",
+                            path.display()
+                        )))
+                    }
+                };
+                let inference = TypeInference::new();
+                let mut database = Database::new();
+                match extension {
+                    Some("json") => database.load_json_inferred(&name, path, &inference)?,
+                    _ => database.load_csv_inferred(&name, path, &inference)?,
+                };
+                database.table(&name).cloned().ok_or_else(|| {
+                    PoneglyphError::InvalidInput(format!("Table {} not found", name))
+                })?
+            }
+        };
+        let name = table.name.to_lowercase();
+        let rows = table.data.len();
+        self.session.register_table(table);
+        Ok(format!(
+            "table {}: {} rows (version {})",
+            name,
+            rows,
+            self.session.table_version(&name).unwrap_or(1)
+        ))
+    }
+}
+
+/// Aligned text table ending with a row count line
+fn format_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let plural = if rows.len() == 1 { "" } else { "s" };
+    format!(
+        "{}({} row{})\n",
+        text_table(columns, rows),
+        rows.len(),
+        plural
+    )
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{} ms", duration.as_millis())
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseTable;
+
+    fn repl() -> Repl {
+        let mut table = DatabaseTable::new(
+            "orders".to_string(),
+            vec!["id".to_string(), "price".to_string()],
+        );
+        table.insert(vec![1, 30]);
+        table.insert(vec![2, 70]);
+        let mut session = Session::new(4);
+        session.register_table(table);
+        Repl::new(session)
+    }
+
+    fn printed(step: ReplStep) -> String {
+        match step {
+            ReplStep::Print(text) => text,
+            ReplStep::Quit => panic!("unexpected quit"),
+        }
+    }
+
+    #[test]
+    fn test_multi_line_statements() {
+        let mut repl = repl();
+        assert_eq!(printed(repl.feed("SELECT id")), "");
+        assert_eq!(repl.prompt(), "       ..> ");
+        let output = printed(repl.feed("FROM orders;"));
+        assert!(output.contains("(2 rows)"), "{}", output);
+        assert!(output.contains("not proven"), "{}", output);
+        assert_eq!(repl.prompt(), "poneglyph> ");
+
+        // \c discards a partial statement only at the start of a line
+        repl.feed("SELECT price");
+        assert_eq!(printed(repl.feed("\\c")), "");
+        assert_eq!(repl.prompt(), "poneglyph> ");
+        assert!(printed(repl.feed("SELEC nonsense;")).starts_with("error: "));
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut repl = repl();
+        assert!(printed(repl.feed("\\d")).contains("orders | 1"));
+        let columns = printed(repl.feed("\\d orders"));
+        assert!(
+            columns.contains("price  | 0     | 30  | 70  | 2"),
+            "{}",
+            columns
+        );
+        assert!(printed(repl.feed("\\d users")).starts_with("error: "));
+        assert!(printed(repl.feed("\\?")).contains("\\load"));
+        assert!(printed(repl.feed("\\load missing.db")).starts_with("error: "));
+        assert!(printed(repl.feed("\\frobnicate")).starts_with("error: "));
+
+        assert_eq!(printed(repl.feed("SET max_k = 4;")), "SET");
+        assert_eq!(repl.session().settings().max_k, Some(4));
+        let explained = printed(repl.feed("EXPLAIN SELECT SUM(price) FROM orders;"));
+        assert!(explained.contains("proof size"), "{}", explained);
+        assert_eq!(repl.feed("\\q"), ReplStep::Quit);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let columns = ["a".to_string(), "bb".to_string()];
+        let rows = vec![vec!["100".to_string(), "2".to_string()]];
+        assert_eq!(
+            format_table(&columns, &rows),
+            "a   | bb\n----+---\n100 | 2\n(1 row)\n"
+        );
+    }
+}
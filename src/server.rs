@@ -0,0 +1,701 @@
+// Proving service
+// The prover as a long-running HTTP/JSON service (feature `server`):
+//
+// | Endpoint                   | Permission      | Body -> response                      |
+// |----------------------------|-----------------|---------------------------------------|
+// | `GET /tables`              | `Verify`        | `CatalogListing` JSON                 |
+// | `POST /tables`             | `MutateCatalog` | `StoredTable` bytes -> `TableListing` |
+// | `POST /queries`            | `Prove`         | `{"sql": ...}` -> `JobStatus` (202)   |
+// | `GET /jobs/{id}`           | `Prove`         | `JobStatus` JSON                      |
+// | `GET /jobs/{id}/proof`     | `Verify`        | `Proof` bytes (p.bin)                 |
+// | `GET /jobs/{id}/vk`        | `Verify`        | `RawVerifyingKey` bytes (vk.bin)      |
+// | `GET /jobs/{id}/instances` | `Verify`        | hex instance JSON (i.json)            |
+// | `GET /jobs/{id}/result`    | `Verify`        | result stream (NDJSON)                |
+//
+// The files match those of `poneglyphdb prove`, so `poneglyphdb verify`
+// checks a fetched proof. Result rows are not part of the job status: they
+// are streamed in batches (`?batch_rows=N`, see `sql::stream`) whose chain
+// ends in the result commitment of the proof's instances.
+//
+// Submitted queries go to a `MemoryQueue`; each worker thread claims jobs
+// and proves them with `Session::prove_portable` in its own session, whose
+// key cache persists across jobs (keygen happens once per worker and circuit
+// shape). Before each job a worker registers the tables replaced since its
+// last job. With a `ProofPreset`, the worker sessions take its settings and
+// share its stage limits.
+//
+// With `ApiKeys` configured, every request carries `Authorization: Bearer
+// <key>` and is checked against the table above. TLS is terminated in front
+// of the service.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use halo2_proofs::{pasta::EqAffine, poly::commitment::Params};
+use serde::{Deserialize, Serialize};
+
+use crate::database::{DatabaseTable, StoredTable};
+use crate::error::{PoneglyphError, PoneglyphResult};
+use crate::prover::{
+    instance_to_hex, AccessError, ApiKeys, CatalogListing, JobQueue, MemoryQueue, Permission,
+    PortableProof, ProofPreset, ProvingJob, Session, StageLimits, TableListing,
+};
+use crate::sql::{QueryResult, ResultStream, ResultStreamReader, SQLParser, DEFAULT_BATCH_ROWS};
+
+/// How long an idle worker waits before polling the queue again
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Largest request body `serve` reads (committed tables included)
+pub const MAX_BODY_BYTES: u64 = 256 << 20;
+
+/// Phase of a proving job
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPhase {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Job as reported by `GET /jobs/{id}`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobStatus {
+    pub id: String,
+    pub phase: JobPhase,
+    /// Why the job failed
+    pub error: Option<String>,
+    /// Result columns and row count, once done (rows: `GET /jobs/{id}/result`)
+    pub columns: Option<Vec<String>>,
+    pub row_count: Option<usize>,
+}
+
+#[derive(Debug)]
+enum JobRecord {
+    Queued,
+    Running,
+    /// The rows are kept once, outside the proof, and shared with streams
+    Done {
+        proof: Box<PortableProof>,
+        result: Arc<QueryResult>,
+    },
+    Failed(String),
+}
+
+impl JobRecord {
+    fn status(&self, id: &str) -> JobStatus {
+        let (phase, error, result) = match self {
+            JobRecord::Queued => (JobPhase::Queued, None, None),
+            JobRecord::Running => (JobPhase::Running, None, None),
+            JobRecord::Done { result, .. } => (JobPhase::Done, None, Some(result.as_ref())),
+            JobRecord::Failed(e) => (JobPhase::Failed, Some(e.clone()), None),
+        };
+        JobStatus {
+            id: id.to_string(),
+            phase,
+            error,
+            columns: result.map(|r| r.columns.clone()),
+            row_count: result.map(|r| r.rows.len()),
+        }
+    }
+}
+
+/// Body of `POST /queries`
+#[derive(Clone, Debug, Deserialize)]
+struct QueryRequest {
+    sql: String,
+}
+
+/// HTTP response of `ProvingService::handle`
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Body,
+}
+
+/// Body of a `Response`
+#[derive(Debug)]
+pub enum Body {
+    Bytes(Vec<u8>),
+    /// Encoded while it is sent, at the pace the client reads
+    Stream(ResultStreamReader),
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        match serde_json::to_vec_pretty(value) {
+            Ok(body) => Self {
+                status,
+                content_type: "application/json",
+                body: Body::Bytes(body),
+            },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    fn bytes(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body: Body::Bytes(body),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        let body = serde_json::json!({ "error": message })
+            .to_string()
+            .into_bytes();
+        Self {
+            status,
+            content_type: "application/json",
+            body: Body::Bytes(body),
+        }
+    }
+}
+
+/// Proving service: catalog, job queue and workers
+/// Transport-independent; `serve` puts it behind HTTP.
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let service = Arc::new(ProvingService::new(Params::new(12)).with_api_keys(keys));
+/// let workers = service.start(4);
+/// serve(service.clone(), "0.0.0.0:8080")?;
+/// ```
+pub struct ProvingService {
+    params: Params<EqAffine>,
+    max_k: Option<u32>,
+    preset: Option<ProofPreset>,
+    /// Shared by the worker sessions
+    limits: Option<StageLimits>,
+    api_keys: Option<ApiKeys>,
+    poll_interval: Duration,
+    /// Table name -> (table, version)
+    tables: RwLock<HashMap<String, (DatabaseTable, u64)>>,
+    queue: MemoryQueue,
+    jobs: Mutex<HashMap<String, JobRecord>>,
+    next_job: AtomicU64,
+    stopped: AtomicBool,
+}
+
+impl ProvingService {
+    pub fn new(params: Params<EqAffine>) -> Self {
+        Self {
+            params,
+            max_k: None,
+            preset: None,
+            limits: None,
+            api_keys: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            tables: RwLock::new(HashMap::new()),
+            queue: MemoryQueue::new(),
+            jobs: Mutex::new(HashMap::new()),
+            next_job: AtomicU64::new(1),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    /// Let workers grow their parameters up to 2^max_k rows (`SET max_k`)
+    pub fn with_max_k(mut self, max_k: u32) -> Self {
+        self.max_k = Some(max_k);
+        self
+    }
+
+    /// Prove with the settings and stage limits of a preset
+    pub fn with_preset(mut self, preset: ProofPreset) -> Self {
+        self.preset = Some(preset);
+        // Preset limits are never zero
+        self.limits = StageLimits::new(preset.prover_config()).ok();
+        self
+    }
+
+    /// Require an API key with the endpoint's permission on every request
+    pub fn with_api_keys(mut self, api_keys: ApiKeys) -> Self {
+        self.api_keys = Some(api_keys);
+        self
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Register (or replace) a committed table
+    ///
+    /// # Errors
+    ///
+    /// `Serialization` if the bytes don't decode, `Validation` if the table
+    /// doesn't match its stored digests or commitment
+    pub fn register(&self, stored_bytes: &[u8]) -> PoneglyphResult<TableListing> {
+        let stored = StoredTable::from_bytes(stored_bytes)?;
+        let report = stored.verify_integrity();
+        if !report.is_intact() {
+            return Err(PoneglyphError::Validation(report.to_string()));
+        }
+        let table = stored.table()?;
+        let name = table.name.to_lowercase();
+        let mut tables = self.tables.write().unwrap_or_else(|e| e.into_inner());
+        let version = tables.get(&name).map_or(1, |(_, version)| version + 1);
+        let listing = TableListing::of(&table, version);
+        tables.insert(name, (table, version));
+        Ok(listing)
+    }
+
+    /// Listing of the registered tables
+    pub fn catalog_listing(&self) -> CatalogListing {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        CatalogListing::of(
+            tables
+                .values()
+                .map(|(table, version)| TableListing::of(table, *version)),
+        )
+    }
+
+    /// Queue `sql` for proving
+    /// Returns the job id.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidInput` if the query doesn't parse
+    pub fn submit(&self, sql: &str) -> PoneglyphResult<String> {
+        SQLParser::parse(sql).map_err(PoneglyphError::InvalidInput)?;
+        let id = format!("job-{}", self.next_job.fetch_add(1, Ordering::Relaxed));
+        self.jobs().insert(id.clone(), JobRecord::Queued);
+        self.queue
+            .push(ProvingJob::new(id.clone(), sql))
+            .map_err(|e| PoneglyphError::Configuration(e.to_string()))?;
+        Ok(id)
+    }
+
+    /// Status of a job (None if unknown)
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        self.jobs().get(id).map(|record| record.status(id))
+    }
+
+    /// Proof of a finished job (None if unknown or not done)
+    pub fn proof(&self, id: &str) -> Option<PortableProof> {
+        match self.jobs().get(id) {
+            Some(JobRecord::Done { proof, result }) => Some(PortableProof {
+                result: result.as_ref().clone(),
+                ..proof.as_ref().clone()
+            }),
+            _ => None,
+        }
+    }
+
+    /// Result of a finished job in batches of `batch_rows` rows (None if
+    /// unknown or not done)
+    pub fn result_stream(&self, id: &str, batch_rows: usize) -> Option<ResultStream> {
+        // Sized outside the lock: that takes a pass over the rows
+        let result = match self.jobs().get(id) {
+            Some(JobRecord::Done { result, .. }) => Arc::clone(result),
+            _ => return None,
+        };
+        Some(ResultStream::new(result, batch_rows))
+    }
+
+    /// Start `workers` proving threads
+    /// They run until `shutdown`.
+    pub fn start(self: &Arc<Self>, workers: usize) -> Vec<JoinHandle<()>> {
+        (0..workers)
+            .map(|_| {
+                let service = Arc::clone(self);
+                thread::spawn(move || service.work())
+            })
+            .collect()
+    }
+
+    /// Stop the workers after their current job
+    pub fn shutdown(&self) {
+        self.stopped.store(true, Ordering::Release);
+    }
+
+    /// Route one request
+    /// `api_key` is the bearer token of the request, if any.
+    pub fn handle(&self, method: &str, path: &str, api_key: Option<&str>, body: &[u8]) -> Response {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let permission = match (method, segments.as_slice()) {
+            ("GET", ["tables"]) => Permission::Verify,
+            ("POST", ["tables"]) => Permission::MutateCatalog,
+            ("POST", ["queries"]) | ("GET", ["jobs", _]) => Permission::Prove,
+            ("GET", ["jobs", _, "proof" | "vk" | "instances" | "result"]) => Permission::Verify,
+            (_, ["tables"] | ["queries"] | ["jobs", ..]) => {
+                return Response::error(405, "method not allowed")
+            }
+            _ => return Response::error(404, "not found"),
+        };
+        if let Some(keys) = &self.api_keys {
+            let authorized = match api_key {
+                Some(key) => keys.authorize(key, permission),
+                None => Err(AccessError::UnknownKey),
+            };
+            match authorized {
+                Ok(_) => {}
+                Err(e @ AccessError::Forbidden { .. }) => {
+                    return Response::error(403, &e.to_string())
+                }
+                Err(e) => return Response::error(401, &e.to_string()),
+            }
+        }
+
+        match segments.as_slice() {
+            ["tables"] if method == "GET" => Response::json(200, &self.catalog_listing()),
+            ["tables"] => match self.register(body) {
+                Ok(listing) => Response::json(201, &listing),
+                Err(e) => Response::error(400, &e.to_string()),
+            },
+            ["queries"] => {
+                let request: QueryRequest = match serde_json::from_slice(body) {
+                    Ok(request) => request,
+                    Err(e) => return Response::error(400, &e.to_string()),
+                };
+                match self.submit(&request.sql) {
+                    Ok(id) => match self.status(&id) {
+                        Some(status) => Response::json(202, &status),
+                        None => Response::error(500, "job vanished"),
+                    },
+                    Err(e) => Response::error(400, &e.to_string()),
+                }
+            }
+            ["jobs", id] => match self.status(id) {
+                Some(status) => Response::json(200, &status),
+                None => Response::error(404, "unknown job"),
+            },
+            ["jobs", id, "result"] => {
+                let batch_rows = match query_parameter(query, "batch_rows") {
+                    None => DEFAULT_BATCH_ROWS,
+                    Some(rows) => match rows.parse::<usize>() {
+                        Ok(rows) if rows > 0 => rows,
+                        _ => return Response::error(400, "batch_rows must be a positive integer"),
+                    },
+                };
+                match (self.status(id), self.result_stream(id, batch_rows)) {
+                    (None, _) => Response::error(404, "unknown job"),
+                    (Some(_), None) => Response::error(409, "job is not done"),
+                    (Some(_), Some(stream)) => Response {
+                        status: 200,
+                        content_type: "application/x-ndjson",
+                        body: Body::Stream(stream.into_reader()),
+                    },
+                }
+            }
+            ["jobs", id, file] => {
+                let proof = match (self.status(id), self.proof(id)) {
+                    (None, _) => return Response::error(404, "unknown job"),
+                    (Some(_), None) => return Response::error(409, "job is not done"),
+                    (Some(_), Some(proof)) => proof,
+                };
+                let bytes = match *file {
+                    "proof" => proof.proof.to_bytes(),
+                    "vk" => proof.verifying_key.to_bytes(),
+                    _ => serde_json::to_vec_pretty(&instance_to_hex(
+                        &proof.public_inputs.to_instance(),
+                    ))
+                    .map_err(|e| PoneglyphError::Serialization(e.to_string())),
+                };
+                match bytes {
+                    Ok(bytes) if *file == "instances" => Response {
+                        status: 200,
+                        content_type: "application/json",
+                        body: Body::Bytes(bytes),
+                    },
+                    Ok(bytes) => Response::bytes(bytes),
+                    Err(e) => Response::error(500, &e.to_string()),
+                }
+            }
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn jobs(&self) -> MutexGuard<'_, HashMap<String, JobRecord>> {
+        // A panicking worker doesn't leave the job table itself inconsistent
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Worker loop: claim, sync tables, prove, record, ack
+    fn work(&self) {
+        let mut session = Session::with_params(self.params.clone());
+        if let Some(max_k) = self.max_k {
+            if let Err(e) = session.execute(&format!("SET max_k = {}", max_k)) {
+                // Invalid max_k: every job fails with the reason
+                self.drain(&e.to_string());
+                return;
+            }
+        }
+        if let Some(preset) = self.preset {
+            session.set_preset(preset);
+        }
+        if let Some(limits) = &self.limits {
+            session.set_limits(limits.clone());
+        }
+        let mut synced: HashMap<String, u64> = HashMap::new();
+        while !self.stopped.load(Ordering::Acquire) {
+            let job = match self.queue.claim() {
+                Ok(Some(job)) => job,
+                Ok(None) | Err(_) => {
+                    thread::sleep(self.poll_interval);
+                    continue;
+                }
+            };
+            self.jobs().insert(job.id.clone(), JobRecord::Running);
+            self.sync(&mut session, &mut synced);
+            let record = match session.prove_portable(&job.sql) {
+                Ok(mut proof) => {
+                    let rows = QueryResult {
+                        columns: proof.result.columns.clone(),
+                        rows: Vec::new(),
+                    };
+                    let result = Arc::new(std::mem::replace(&mut proof.result, rows));
+                    JobRecord::Done {
+                        proof: Box::new(proof),
+                        result,
+                    }
+                }
+                Err(e) => JobRecord::Failed(e.to_string()),
+            };
+            self.jobs().insert(job.id.clone(), record);
+            let _ = self.queue.ack(&job);
+        }
+    }
+
+    /// Register the tables replaced since the worker's last job
+    fn sync(&self, session: &mut Session, synced: &mut HashMap<String, u64>) {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        for (name, (table, version)) in tables.iter() {
+            if synced.get(name) != Some(version) {
+                session.register_table(table.clone());
+                synced.insert(name.clone(), *version);
+            }
+        }
+    }
+
+    /// Fail every queued job (until shutdown)
+    fn drain(&self, reason: &str) {
+        while !self.stopped.load(Ordering::Acquire) {
+            match self.queue.claim() {
+                Ok(Some(job)) => {
+                    self.jobs()
+                        .insert(job.id.clone(), JobRecord::Failed(reason.to_string()));
+                    let _ = self.queue.ack(&job);
+                }
+                Ok(None) | Err(_) => thread::sleep(self.poll_interval),
+            }
+        }
+    }
+}
+
+/// Serve `service` over HTTP at `address` (e.g. `127.0.0.1:8080`)
+/// Blocks for as long as the listener accepts connections.
+///
+/// # Errors
+///
+/// `Configuration` if the address can't be bound
+pub fn serve(service: Arc<ProvingService>, address: &str) -> PoneglyphResult<()> {
+    let server = tiny_http::Server::http(address)
+        .map_err(|e| PoneglyphError::Configuration(format!("{}: {}", address, e)))?;
+    for mut request in server.incoming_requests() {
+        let mut body = Vec::new();
+        let read = request
+            .as_reader()
+            .take(MAX_BODY_BYTES + 1)
+            .read_to_end(&mut body);
+        let response = match read {
+            Err(e) => Response::error(400, &e.to_string()),
+            Ok(_) if body.len() as u64 > MAX_BODY_BYTES => {
+                Response::error(413, "request body too large")
+            }
+            Ok(_) => {
+                let api_key = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+                    .map(|key| key.trim().to_string());
+                service.handle(
+                    request.method().as_str(),
+                    request.url(),
+                    api_key.as_deref(),
+                    &body,
+                )
+            }
+        };
+        let content_type =
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], response.content_type.as_bytes())
+                .expect("static content type");
+        let _ = match response.body {
+            Body::Bytes(body) => request.respond(
+                tiny_http::Response::from_data(body)
+                    .with_status_code(response.status)
+                    .with_header(content_type),
+            ),
+            // No length: sent chunked, each chunk read from the stream only
+            // once the socket has taken the previous one
+            Body::Stream(reader) => request.respond(tiny_http::Response::new(
+                tiny_http::StatusCode(response.status),
+                vec![content_type],
+                reader,
+                None,
+                None,
+            )),
+        };
+    }
+    Ok(())
+}
+
+/// Value of `name` in a query string (`a=1&b=2`)
+fn query_parameter<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::Role;
+
+    fn orders() -> Vec<u8> {
+        let mut table = DatabaseTable::new(
+            "orders".to_string(),
+            vec!["id".to_string(), "price".to_string()],
+        );
+        table.insert(vec![1, 30]);
+        table.insert(vec![2, 70]);
+        StoredTable::new(&table).to_bytes().unwrap()
+    }
+
+    fn bytes(response: &Response) -> &[u8] {
+        match &response.body {
+            Body::Bytes(bytes) => bytes,
+            Body::Stream(_) => panic!("streamed response"),
+        }
+    }
+
+    fn status(response: &Response) -> JobStatus {
+        serde_json::from_slice(bytes(response)).unwrap()
+    }
+
+    #[test]
+    fn test_routes() {
+        let service = ProvingService::new(Params::new(4));
+        let registered = service.handle("POST", "/tables", None, &orders());
+        assert_eq!(registered.status, 201);
+        assert_eq!(
+            service.handle("POST", "/tables", None, &orders()).status,
+            201
+        );
+        let listing = service.handle("GET", "/tables", None, &[]);
+        let listing = CatalogListing::from_json(std::str::from_utf8(bytes(&listing)).unwrap());
+        assert_eq!(listing.unwrap().table("orders").unwrap().version, 2);
+        assert_eq!(service.handle("POST", "/tables", None, b"junk").status, 400);
+
+        let submitted = service.handle(
+            "POST",
+            "/queries",
+            None,
+            br#"{"sql": "SELECT SUM(price) FROM orders"}"#,
+        );
+        assert_eq!(submitted.status, 202);
+        let job = status(&submitted);
+        assert_eq!(job.phase, JobPhase::Queued);
+        let path = format!("/jobs/{}", job.id);
+        assert_eq!(status(&service.handle("GET", &path, None, &[])), job);
+        let proof = format!("/jobs/{}/proof", job.id);
+        assert_eq!(service.handle("GET", &proof, None, &[]).status, 409);
+        let result = format!("/jobs/{}/result", job.id);
+        assert_eq!(service.handle("GET", &result, None, &[]).status, 409);
+        let batch_rows = format!("{}?batch_rows=0", result);
+        assert_eq!(service.handle("GET", &batch_rows, None, &[]).status, 400);
+        assert_eq!(
+            query_parameter("a=1&batch_rows=50", "batch_rows"),
+            Some("50")
+        );
+
+        let bad = service.handle("POST", "/queries", None, br#"{"sql": "DROP TABLE orders"}"#);
+        assert_eq!(bad.status, 400);
+        assert_eq!(service.handle("GET", "/jobs/job-99", None, &[]).status, 404);
+        assert_eq!(service.handle("DELETE", "/tables", None, &[]).status, 405);
+        assert_eq!(service.handle("GET", "/metrics", None, &[]).status, 404);
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let mut keys = ApiKeys::with_admin("root");
+        keys.issue("root", "etl", Role::Loader).unwrap();
+        keys.issue("root", "auditor", Role::Verifier).unwrap();
+        let service = ProvingService::new(Params::new(4)).with_api_keys(keys);
+
+        assert_eq!(service.handle("GET", "/tables", None, &[]).status, 401);
+        assert_eq!(
+            service.handle("GET", "/tables", Some("nope"), &[]).status,
+            401
+        );
+        assert_eq!(
+            service
+                .handle("GET", "/tables", Some("auditor"), &[])
+                .status,
+            200
+        );
+        assert_eq!(
+            service
+                .handle("POST", "/tables", Some("auditor"), &orders())
+                .status,
+            403
+        );
+        assert_eq!(
+            service
+                .handle("POST", "/tables", Some("etl"), &orders())
+                .status,
+            201
+        );
+        let query = br#"{"sql": "SELECT SUM(price) FROM orders"}"#;
+        assert_eq!(
+            service
+                .handle("POST", "/queries", Some("etl"), query)
+                .status,
+            403
+        );
+        assert_eq!(
+            service
+                .handle("POST", "/queries", Some("root"), query)
+                .status,
+            202
+        );
+    }
+
+    #[test]
+    fn test_workers_record_failures() {
+        let service = Arc::new(
+            ProvingService::new(Params::new(4)).with_poll_interval(Duration::from_millis(1)),
+        );
+        service.register(&orders()).unwrap();
+        // A plain column is not bound to the public inputs
+        let id = service.submit("SELECT id FROM orders").unwrap();
+        let workers = service.start(2);
+
+        let mut phase = JobPhase::Queued;
+        for _ in 0..1000 {
+            phase = service.status(&id).unwrap().phase;
+            if phase == JobPhase::Failed {
+                break;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        service.shutdown();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(phase, JobPhase::Failed);
+        assert!(service.status(&id).unwrap().error.is_some());
+        assert!(service.proof(&id).is_none());
+    }
+}
@@ -0,0 +1,735 @@
+// Reference executor
+// Evaluates a parsed query directly over the table data, with plain SQL
+// semantics and no circuit. Used as the oracle in differential tests (against
+// SQLite and against the compiled circuit operations).
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use pasta_curves::pallas::Base as Fr;
+
+use super::{
+    query_hash, AggregationFunction, CastClause, ComparisonOp, JoinType, OrderDirection,
+    PredicateExpr, SQLQuery, WhereClause,
+};
+use crate::circuit::{AggregationType, ArithExpr, LikePattern, PublicInputs, ResultPredicate};
+use crate::database::ColumnType;
+use crate::utils::{evcxr_html, html_table, text_table};
+
+/// Column prefixes the parser detects as aggregations
+const AGGREGATION_PREFIXES: [&str; 6] =
+    ["sum(", "count(", "max(", "min(", "median(", "percentile_disc("];
+
+/// Version byte of `QueryResult::to_bytes`
+pub const RESULT_ENCODING_VERSION: u8 = 1;
+
+/// Result of a query: one row per output row, `None` = SQL NULL
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<u64>>>,
+}
+
+impl QueryResult {
+    /// Value bound to the query result instance row: the only cell of a
+    /// one-row, one-column result (None for any other shape, or NULL)
+    pub fn bound_value(&self) -> Option<u64> {
+        match self.rows.as_slice() {
+            [row] if row.len() == 1 => row[0],
+            _ => None,
+        }
+    }
+
+    /// Canonical encoding, the bytes revealed next to a proof
+    ///
+    /// # Format
+    ///
+    /// - version byte (`RESULT_ENCODING_VERSION`)
+    /// - column count (u32 LE), then each name as length (u32 LE) and UTF-8
+    /// - row count (u32 LE), then each cell as 0 (NULL) or 1 and the value (u64 LE)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        encode_result_header(&self.columns, self.rows.len(), &mut bytes);
+        for row in &self.rows {
+            encode_result_row(row, &mut bytes);
+        }
+        bytes
+    }
+
+    /// Decode `to_bytes`; rejects anything that doesn't re-encode to the same bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut rest = bytes;
+        let rest = &mut rest;
+        if take::<1>(rest)? != [RESULT_ENCODING_VERSION] {
+            return Err("unknown result encoding version".to_string());
+        }
+        let column_count = u32::from_le_bytes(take(rest)?);
+        let mut columns = Vec::new();
+        for _ in 0..column_count {
+            let len = u32::from_le_bytes(take(rest)?) as usize;
+            if rest.len() < len {
+                return Err("result bytes are truncated".to_string());
+            }
+            let (name, tail) = rest.split_at(len);
+            *rest = tail;
+            let name =
+                std::str::from_utf8(name).map_err(|_| "column name is not UTF-8".to_string())?;
+            columns.push(name.to_string());
+        }
+        let row_count = u32::from_le_bytes(take(rest)?);
+        let mut rows = Vec::new();
+        for _ in 0..row_count {
+            let row = (0..column_count)
+                .map(|_| match take::<1>(rest)? {
+                    [0] => Ok(None),
+                    [1] => Ok(Some(u64::from_le_bytes(take(rest)?))),
+                    [tag] => Err(format!("invalid cell tag {}", tag)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+        if !rest.is_empty() {
+            return Err("trailing bytes after the result".to_string());
+        }
+        Ok(Self { columns, rows })
+    }
+
+    /// Commitment to the whole result (column names, rows, NULLs), bound to
+    /// the public inputs of its proof: Poseidon hash of `to_bytes`
+    pub fn commitment(&self) -> Fr {
+        crate::utils::poseidon_hash_bytes(&self.to_bytes())
+    }
+
+    /// Public inputs a `Session` proof of this result is checked against
+    /// `sql` is the query as sent (see `query_hash`), `db_commitment` the
+    /// published commitment of the queried table.
+    pub fn expected_public_inputs(
+        &self,
+        db_commitment: Fr,
+        sql: &str,
+    ) -> Result<PublicInputs, String> {
+        let value = self.bound_value().ok_or_else(|| {
+            "only a single non-NULL value is bound to the public inputs".to_string()
+        })?;
+        Ok(PublicInputs::new(db_commitment, Fr::from(value))
+            .with_query_hash(query_hash(sql))
+            .with_result_commitment(self.commitment()))
+    }
+
+    /// Instance vector of a `Session` proof of this result
+    /// Lets a party that received only the result rows verify the proof
+    /// alongside them without compiling the query.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let instances = result.expected_instances(db_commitment, sql)?;
+    /// verifier.verify(&params, &proof, &instances)?;
+    /// ```
+    pub fn expected_instances(&self, db_commitment: Fr, sql: &str) -> Result<Vec<Vec<Fr>>, String> {
+        Ok(self.expected_public_inputs(db_commitment, sql)?.to_instance())
+    }
+
+    /// Public inputs of a `Session::prove_predicate` proof over this result
+    /// Instance row 1 holds whether `predicate` holds instead of the value,
+    /// and no result commitment is bound: it would identify the result.
+    pub fn expected_predicate_inputs(
+        &self,
+        db_commitment: Fr,
+        sql: &str,
+        predicate: ResultPredicate,
+    ) -> Result<PublicInputs, String> {
+        let value = self.bound_value().ok_or_else(|| {
+            "only a single non-NULL value can be compared with a predicate".to_string()
+        })?;
+        let outcome = ResultPredicate::outcome(predicate.holds(value));
+        Ok(PublicInputs::new(db_commitment, outcome).with_query_hash(query_hash(sql)))
+    }
+
+    /// Cells as text, NULL spelled out
+    fn cells(&self) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or("NULL".to_string(), |v| v.to_string()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// HTML table of the rows, for notebooks and web pages
+    pub fn to_html(&self) -> String {
+        html_table(&self.columns, &self.cells())
+    }
+
+    /// Rich output in evcxr (Jupyter) notebooks: the rows as an HTML table
+    pub fn evcxr_display(&self) {
+        evcxr_html(&self.to_html());
+    }
+}
+
+/// Aligned table of the rows with a row count, as `psql` prints results
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", text_table(&self.columns, &self.cells()))?;
+        let plural = if self.rows.len() == 1 { "" } else { "s" };
+        write!(f, "({} row{})", self.rows.len(), plural)
+    }
+}
+
+/// `QueryResult::to_bytes` up to the cells: version, columns, row count
+pub(crate) fn encode_result_header(columns: &[String], row_count: usize, bytes: &mut Vec<u8>) {
+    bytes.push(RESULT_ENCODING_VERSION);
+    bytes.extend_from_slice(&(columns.len() as u32).to_le_bytes());
+    for column in columns {
+        bytes.extend_from_slice(&(column.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(column.as_bytes());
+    }
+    bytes.extend_from_slice(&(row_count as u32).to_le_bytes());
+}
+
+/// `QueryResult::to_bytes` of the cells of one row
+pub(crate) fn encode_result_row(row: &[Option<u64>], bytes: &mut Vec<u8>) {
+    for cell in row {
+        match cell {
+            None => bytes.push(0),
+            Some(value) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Next `N` bytes of `rest`
+fn take<const N: usize>(rest: &mut &[u8]) -> Result<[u8; N], String> {
+    if rest.len() < N {
+        return Err("result bytes are truncated".to_string());
+    }
+    let (head, tail) = rest.split_at(N);
+    *rest = tail;
+    Ok(head.try_into().expect("split at N"))
+}
+
+/// Reference Executor
+/// Out-of-circuit query evaluation over `table_data`
+pub struct ReferenceExecutor;
+
+type Row = HashMap<String, u64>;
+
+impl ReferenceExecutor {
+    /// Execute a parsed query
+    ///
+    /// Supported: WHERE predicates, computed columns, GROUP BY with
+    /// SUM/COUNT/MAX/MIN, window functions and ORDER BY. Groups are returned in
+    /// ascending key order unless ORDER BY says otherwise.
+    pub fn execute(
+        query: &SQLQuery,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<QueryResult, String> {
+        if query.joins.as_ref().is_some_and(|j| !j.is_empty()) {
+            return Err("JOIN is not supported by the reference executor".to_string());
+        }
+        if query.columns.iter().any(|c| c == "*") {
+            return Err("SELECT * is not supported by the reference executor".to_string());
+        }
+        if let Some(ctes) = &query.ctes {
+            // Materialize CTEs in order, later ones may read earlier ones
+            let mut tables = table_data.clone();
+            for cte in ctes {
+                let columns = cte.columns.clone().unwrap_or_else(|| cte.query.columns.clone());
+                let relation = Self::relation(&cte.query, &columns, &tables)?;
+                tables.insert(cte.name.clone(), relation);
+            }
+            let main = SQLQuery {
+                ctes: None,
+                ..query.clone()
+            };
+            return Self::execute(&main, &tables);
+        }
+        if let Some(union) = &query.union {
+            let left = Self::execute(
+                &SQLQuery {
+                    union: None,
+                    ..query.clone()
+                },
+                table_data,
+            )?;
+            let right = Self::execute(&union.query, table_data)?;
+            if left.columns.len() != right.columns.len() {
+                return Err("UNION inputs have different column counts".to_string());
+            }
+            let mut rows = left.rows;
+            rows.extend(right.rows);
+            if !union.all {
+                rows.sort();
+                rows.dedup();
+            }
+            return Ok(QueryResult {
+                columns: left.columns,
+                rows,
+            });
+        }
+
+        if let Some(function) = &query.table_function {
+            let main = SQLQuery {
+                table_function: None,
+                ..query.clone()
+            };
+            return Self::execute(&main, &function.with_relation(table_data)?);
+        }
+
+        let table = table_data
+            .get(&query.from)
+            .ok_or_else(|| format!("Table {} not found", query.from))?;
+        let num_rows = table.values().map(|v| v.len()).min().unwrap_or(0);
+        let mut rows: Vec<Row> = (0..num_rows)
+            .map(|i| {
+                table
+                    .iter()
+                    .map(|(name, values)| (name.clone(), values[i]))
+                    .collect()
+            })
+            .collect();
+
+        // WHERE (uncorrelated subqueries are evaluated once, up front)
+        if let Some(predicate) = &query.predicate {
+            let predicate = Self::resolve_predicate(predicate, table_data)?;
+            rows = Self::filter(rows, |row| Self::eval_predicate(&predicate, row))?;
+        } else if let Some(where_clause) = &query.where_clause {
+            let where_clause = Self::resolve_subqueries(where_clause, table_data)?;
+            rows = Self::filter(rows, |row| Self::eval_where(&where_clause, row))?;
+        }
+
+        let grouped = query.group_by.is_some() || query.aggregations.is_some();
+        let result_rows = if grouped {
+            Self::execute_grouped(query, &rows)?
+        } else {
+            // ORDER BY on input rows (stable, so ties keep table order)
+            if let Some(order_by) = &query.order_by {
+                for order in order_by.iter().rev() {
+                    let keys = rows
+                        .iter()
+                        .map(|row| Self::column_value(&order.column, row))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let mut indexed: Vec<(u64, Row)> = keys.into_iter().zip(rows).collect();
+                    match order.direction {
+                        OrderDirection::Asc => indexed.sort_by_key(|a| a.0),
+                        OrderDirection::Desc => indexed.sort_by_key(|a| std::cmp::Reverse(a.0)),
+                    }
+                    rows = indexed.into_iter().map(|(_, row)| row).collect();
+                }
+            }
+            Self::project(query, &rows)?
+        };
+
+        Ok(QueryResult {
+            columns: query.columns.clone(),
+            rows: result_rows,
+        })
+    }
+
+    /// Result of a query as a table (column name -> values)
+    /// Intermediate relations hold no NULLs (aggregates over empty input)
+    pub fn relation(
+        query: &SQLQuery,
+        columns: &[String],
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<HashMap<String, Vec<u64>>, String> {
+        let result = Self::execute(query, table_data)?;
+        if columns.len() != result.columns.len() {
+            return Err(format!(
+                "Relation has {} columns, {} names given",
+                result.columns.len(),
+                columns.len()
+            ));
+        }
+        let mut relation: HashMap<String, Vec<u64>> =
+            columns.iter().map(|c| (c.clone(), Vec::new())).collect();
+        if relation.len() != columns.len() {
+            return Err("Duplicate column name in relation".to_string());
+        }
+        for row in &result.rows {
+            for (column, value) in columns.iter().zip(row) {
+                let value = value.ok_or("NULL in intermediate relation")?;
+                relation.get_mut(column).unwrap().push(value);
+            }
+        }
+        Ok(relation)
+    }
+
+    fn filter(
+        rows: Vec<Row>,
+        predicate: impl Fn(&Row) -> Result<bool, String>,
+    ) -> Result<Vec<Row>, String> {
+        let mut kept = Vec::new();
+        for row in rows {
+            if predicate(&row)? {
+                kept.push(row);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Value of a column, computed expression or CAST in one row
+    /// (CASTs convert from integers: table data carries no types)
+    fn column_value(column: &str, row: &Row) -> Result<u64, String> {
+        if let Some(&value) = row.get(column) {
+            return Ok(value);
+        }
+        if let Some(cast) = CastClause::parse(column) {
+            let cast = cast?;
+            let value = Self::column_value(&cast.column, row)?;
+            return Ok(cast.apply(ColumnType::Integer, &[value])?[0]);
+        }
+        let expr = ArithExpr::parse(column).map_err(|_| format!("Column {} not found", column))?;
+        expr.evaluate(row)
+            .ok_or_else(|| format!("Arithmetic overflow or missing column in {}", column))
+    }
+
+    fn resolve_predicate(
+        predicate: &PredicateExpr,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<PredicateExpr, String> {
+        let resolve = |e: &PredicateExpr| Self::resolve_predicate(e, table_data).map(Box::new);
+        Ok(match predicate {
+            // An anti join keeps the rows outside the semi join
+            PredicateExpr::Compare(WhereClause::SemiJoin {
+                column,
+                subquery,
+                join_type: JoinType::Anti,
+            }) => {
+                let semi = WhereClause::SemiJoin {
+                    column: column.clone(),
+                    subquery: subquery.clone(),
+                    join_type: JoinType::Semi,
+                };
+                let semi = PredicateExpr::Compare(Self::resolve_subqueries(&semi, table_data)?);
+                PredicateExpr::Not(Box::new(semi))
+            }
+            PredicateExpr::Compare(clause) => {
+                PredicateExpr::Compare(Self::resolve_subqueries(clause, table_data)?)
+            }
+            PredicateExpr::And(l, r) => PredicateExpr::And(resolve(l)?, resolve(r)?),
+            PredicateExpr::Or(l, r) => PredicateExpr::Or(resolve(l)?, resolve(r)?),
+            PredicateExpr::Not(e) => PredicateExpr::Not(resolve(e)?),
+        })
+    }
+
+    /// Replace subqueries by their results (comparison literal / IN list)
+    fn resolve_subqueries(
+        clause: &WhereClause,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<WhereClause, String> {
+        let resolve = |c: &WhereClause| Self::resolve_subqueries(c, table_data).map(Box::new);
+        Ok(match clause {
+            WhereClause::CompareSubquery {
+                column,
+                operator,
+                subquery,
+            } => {
+                let result = Self::execute(subquery, table_data)?;
+                let value = match result.rows.as_slice() {
+                    [row] if row.len() == 1 => row[0].ok_or("Scalar subquery returned NULL")?,
+                    _ => return Err("Scalar subquery must return one value".to_string()),
+                };
+                let column = column.clone();
+                match operator {
+                    ComparisonOp::LessThan => WhereClause::LessThan { column, value },
+                    ComparisonOp::GreaterThan => WhereClause::GreaterThan { column, value },
+                    ComparisonOp::Equal => WhereClause::Equal { column, value },
+                }
+            }
+            // WhereClause has no NOT (see resolve_predicate)
+            WhereClause::SemiJoin {
+                join_type: JoinType::Anti,
+                ..
+            } => return Err("NOT IN / NOT EXISTS needs a predicate tree".to_string()),
+            WhereClause::InSubquery { column, subquery }
+            | WhereClause::SemiJoin {
+                column, subquery, ..
+            } => {
+                let result = Self::execute(subquery, table_data)?;
+                if result.columns.len() != 1 {
+                    return Err("IN subquery must select a single column".to_string());
+                }
+                // NULL never matches; an empty list selects nothing
+                WhereClause::In {
+                    column: column.clone(),
+                    values: result.rows.iter().filter_map(|row| row[0]).collect(),
+                }
+            }
+            WhereClause::And(l, r) => WhereClause::And(resolve(l)?, resolve(r)?),
+            WhereClause::Or(l, r) => WhereClause::Or(resolve(l)?, resolve(r)?),
+            other => other.clone(),
+        })
+    }
+
+    fn eval_predicate(predicate: &PredicateExpr, row: &Row) -> Result<bool, String> {
+        Ok(match predicate {
+            PredicateExpr::Compare(clause) => Self::eval_where(clause, row)?,
+            PredicateExpr::And(l, r) => {
+                Self::eval_predicate(l, row)? && Self::eval_predicate(r, row)?
+            }
+            PredicateExpr::Or(l, r) => {
+                Self::eval_predicate(l, row)? || Self::eval_predicate(r, row)?
+            }
+            PredicateExpr::Not(e) => !Self::eval_predicate(e, row)?,
+        })
+    }
+
+    fn eval_where(clause: &WhereClause, row: &Row) -> Result<bool, String> {
+        Ok(match clause {
+            WhereClause::LessThan { column, value } => Self::column_value(column, row)? < *value,
+            WhereClause::GreaterThan { column, value } => {
+                Self::column_value(column, row)? > *value
+            }
+            WhereClause::Equal { column, value } => Self::column_value(column, row)? == *value,
+            WhereClause::Between { column, low, high } => {
+                let x = Self::column_value(column, row)?;
+                *low <= x && x <= *high
+            }
+            WhereClause::In { column, values } => {
+                values.contains(&Self::column_value(column, row)?)
+            }
+            WhereClause::Like { column, pattern } => {
+                LikePattern::parse(pattern)?.matches(Self::column_value(column, row)?)
+            }
+            WhereClause::CompareSubquery { .. }
+            | WhereClause::InSubquery { .. }
+            | WhereClause::SemiJoin { .. } => return Err("Unresolved subquery".to_string()),
+            WhereClause::And(l, r) => Self::eval_where(l, row)? && Self::eval_where(r, row)?,
+            WhereClause::Or(l, r) => Self::eval_where(l, row)? || Self::eval_where(r, row)?,
+        })
+    }
+
+    /// Per-row projection (plain columns, computed columns, window functions)
+    fn project(query: &SQLQuery, rows: &[Row]) -> Result<Vec<Vec<Option<u64>>>, String> {
+        let mut columns: Vec<Vec<Option<u64>>> = Vec::new();
+        let mut windows = query.windows.iter().flatten();
+
+        for column in &query.columns {
+            if column.contains(" over ") {
+                let window = windows.next().ok_or("Window clause mismatch")?;
+                let key = |name: &Option<String>, row: &Row| match name {
+                    Some(name) => Self::column_value(name, row),
+                    None => Ok(0),
+                };
+                let mut keyed = Vec::with_capacity(rows.len());
+                for (i, row) in rows.iter().enumerate() {
+                    keyed.push((
+                        key(&window.partition_by, row)?,
+                        Self::column_value(&window.order_by, row)?,
+                        key(&window.column, row)?,
+                        i,
+                    ));
+                }
+                keyed.sort();
+
+                let partition_keys: Vec<u64> = keyed.iter().map(|k| k.0).collect();
+                let order_keys: Vec<u64> = keyed.iter().map(|k| k.1).collect();
+                let values: Vec<u64> = keyed.iter().map(|k| k.2).collect();
+                let results = window
+                    .function
+                    .evaluate(&partition_keys, &order_keys, &values)
+                    .ok_or("Window function overflow")?;
+
+                let mut out = vec![None; rows.len()];
+                for (k, result) in keyed.iter().zip(results) {
+                    out[k.3] = Some(result);
+                }
+                columns.push(out);
+            } else {
+                columns.push(
+                    rows.iter()
+                        .map(|row| Self::column_value(column, row).map(Some))
+                        .collect::<Result<_, _>>()?,
+                );
+            }
+        }
+
+        Ok((0..rows.len())
+            .map(|i| columns.iter().map(|c| c[i]).collect())
+            .collect())
+    }
+
+    /// GROUP BY / aggregation (one group without GROUP BY)
+    fn execute_grouped(query: &SQLQuery, rows: &[Row]) -> Result<Vec<Vec<Option<u64>>>, String> {
+        let group_by = query.group_by.clone().unwrap_or_default();
+        let mut groups: BTreeMap<Vec<u64>, Vec<&Row>> = BTreeMap::new();
+        for row in rows {
+            let key = group_by
+                .iter()
+                .map(|c| Self::column_value(c, row))
+                .collect::<Result<Vec<_>, _>>()?;
+            groups.entry(key).or_default().push(row);
+        }
+        // Aggregation without GROUP BY yields one row, even on empty input
+        if group_by.is_empty() && groups.is_empty() {
+            groups.insert(Vec::new(), Vec::new());
+        }
+
+        let mut out = Vec::new();
+        for (key, members) in &groups {
+            // Aggregations are parsed in column order
+            let mut aggregations = query.aggregations.iter().flatten();
+            let mut row = Vec::new();
+            for column in &query.columns {
+                if let Some(pos) = group_by.iter().position(|g| g == column) {
+                    row.push(Some(key[pos]));
+                    continue;
+                }
+                let is_aggregation = AGGREGATION_PREFIXES
+                    .iter()
+                    .any(|p| column.starts_with(p));
+                if !is_aggregation {
+                    return Err(format!("Column {} must appear in GROUP BY", column));
+                }
+                let agg = aggregations
+                    .next()
+                    .ok_or_else(|| format!("Unsupported aggregation {}", column))?;
+
+                let values = if agg.column == "*" {
+                    vec![1; members.len()]
+                } else {
+                    members
+                        .iter()
+                        .map(|r| Self::column_value(&agg.column, r))
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                row.push(match agg.function {
+                    AggregationFunction::Count => Some(values.len() as u64),
+                    AggregationFunction::Sum if values.is_empty() => None,
+                    AggregationFunction::Sum => Some(
+                        values
+                            .iter()
+                            .try_fold(0u64, |acc, &v| acc.checked_add(v))
+                            .ok_or("SUM overflow")?,
+                    ),
+                    AggregationFunction::Max => values.iter().max().copied(),
+                    AggregationFunction::Min => values.iter().min().copied(),
+                    AggregationFunction::Avg if values.is_empty() => None,
+                    AggregationFunction::Avg => {
+                        Some(values.iter().sum::<u64>() / values.len() as u64)
+                    }
+                    AggregationFunction::Median | AggregationFunction::Percentile(_) => {
+                        let agg_type = match agg.function {
+                            AggregationFunction::Percentile(p) => AggregationType::Percentile(p),
+                            _ => AggregationType::Median,
+                        };
+                        let mut sorted = values.clone();
+                        sorted.sort();
+                        agg_type.rank_index(sorted.len()).map(|i| sorted[i])
+                    }
+                });
+            }
+            out.push(row);
+        }
+
+        // ORDER BY over output columns (group keys or aggregates)
+        if let Some(order_by) = &query.order_by {
+            for order in order_by.iter().rev() {
+                let pos = query
+                    .columns
+                    .iter()
+                    .position(|c| *c == order.column)
+                    .ok_or_else(|| format!("ORDER BY {} is not an output column", order.column))?;
+                match order.direction {
+                    OrderDirection::Asc => out.sort_by(|a, b| a[pos].cmp(&b[pos])),
+                    OrderDirection::Desc => out.sort_by(|a, b| b[pos].cmp(&a[pos])),
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SQLParser;
+    use super::*;
+
+    fn table() -> HashMap<String, HashMap<String, Vec<u64>>> {
+        let mut t = HashMap::new();
+        t.insert("k".to_string(), vec![2, 1, 2, 1, 3]);
+        t.insert("v".to_string(), vec![10, 20, 30, 40, 50]);
+        let mut data = HashMap::new();
+        data.insert("t".to_string(), t);
+        data
+    }
+
+    #[test]
+    fn test_filter_and_order() {
+        let query = SQLParser::parse("SELECT v FROM t WHERE v > 15 ORDER BY v DESC").unwrap();
+        let result = ReferenceExecutor::execute(&query, &table()).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![Some(50)], vec![Some(40)], vec![Some(30)], vec![Some(20)]]
+        );
+    }
+
+    #[test]
+    fn test_result_display() {
+        let result = QueryResult {
+            columns: vec!["k".to_string(), "sum(v)".to_string()],
+            rows: vec![vec![Some(1), Some(60)], vec![Some(2), None]],
+        };
+        assert_eq!(
+            result.to_string(),
+            "k | sum(v)\n--+-------\n1 | 60\n2 | NULL\n(2 rows)"
+        );
+        assert!(result.to_html().contains("<th>sum(v)</th>"));
+        assert!(result.to_html().contains("<td>NULL</td>"));
+    }
+
+    #[test]
+    fn test_group_by_aggregates() {
+        let query = SQLParser::parse("SELECT k, SUM(v), COUNT(v) FROM t GROUP BY k").unwrap();
+        let result = ReferenceExecutor::execute(&query, &table()).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![Some(1), Some(60), Some(2)],
+                vec![Some(2), Some(40), Some(2)],
+                vec![Some(3), Some(50), Some(1)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_median_and_percentile() {
+        let query = SQLParser::parse(
+            "SELECT MEDIAN(v), PERCENTILE_DISC(0.9) WITHIN GROUP (ORDER BY v) FROM t",
+        )
+        .unwrap();
+        let result = ReferenceExecutor::execute(&query, &table()).unwrap();
+        assert_eq!(result.rows, vec![vec![Some(30), Some(50)]]);
+    }
+
+    #[test]
+    fn test_aggregate_over_empty_input_is_null() {
+        let query = SQLParser::parse("SELECT SUM(v), COUNT(v) FROM t WHERE v > 100").unwrap();
+        let result = ReferenceExecutor::execute(&query, &table()).unwrap();
+        assert_eq!(result.rows, vec![vec![None, Some(0)]]);
+    }
+
+    #[test]
+    fn test_expected_instances() {
+        let sql = "SELECT SUM(v) FROM t WHERE v > 15";
+        let query = SQLParser::parse(sql).unwrap();
+        let result = ReferenceExecutor::execute(&query, &table()).unwrap();
+        let instances = result.expected_instances(Fr::from(7), sql).unwrap();
+        let inputs = PublicInputs::from_instance(&instances).unwrap();
+        assert_eq!(inputs.db_commitment, Fr::from(7));
+        assert_eq!(inputs.query_result, Fr::from(140));
+        assert_eq!(inputs.query_hash, query_hash("select sum(v)  FROM t where v > 15"));
+        assert_eq!(inputs.result_commitment, result.commitment());
+        assert_eq!(QueryResult::from_bytes(&result.to_bytes()), Ok(result));
+
+        // Results that aren't a single value are never proved
+        let query = SQLParser::parse("SELECT v FROM t").unwrap();
+        let result = ReferenceExecutor::execute(&query, &table()).unwrap();
+        assert!(result.expected_instances(Fr::from(7), sql).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+// EXPLAIN for compiled queries
+// What proving a query would cost, without synthesizing or proving it: the
+// planner's operator order, the chips that get regions, estimated advice rows
+// per operator, and from those the circuit size k, the proof size and a
+// proving time estimate.
+//
+// The proof size is halo2's count of the IPA transcript of `PoneglyphCircuit`
+// (`dev::CircuitCost`: commitments and evaluations of 32 bytes each, plus 2k
+// points for the inner product argument), so it only depends on k. The
+// proving time is a single-threaded reference figure per committed cell of
+// the columns the circuit config allocates; measure on the target machine
+// before relying on it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use halo2_proofs::{
+    circuit::Value,
+    dev::CircuitCost,
+    plonk::{Circuit, ConstraintSystem},
+};
+use pasta_curves::{pallas::Base as Fr, Eq};
+
+use super::{SQLCompiler, SQLQuery};
+use crate::circuit::{ConfigColumns, PoneglyphCircuit, TableScan, ThresholdMode};
+use crate::optimization::{Planner, QueryPlan};
+
+/// Reference proving time per committed polynomial cell (2^k rows each)
+/// and log2 of the domain size (FFTs and MSMs are n log n)
+const PROVING_NANOS_PER_CELL: u64 = 500;
+
+/// Cost report of one query
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let query = SQLParser::parse("SELECT SUM(amount) FROM orders WHERE amount > 40")?;
+/// let report = explain(&query, &table_data)?;
+/// println!("{}", report);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExplainReport {
+    /// Operator tree in the planner's order, with rows per operator and the
+    /// planner's decisions
+    pub plan: QueryPlan,
+    /// Chips with at least one region, in plan order
+    pub chips: Vec<&'static str>,
+    /// Estimated advice rows of all operators
+    pub advice_rows: usize,
+    /// Circuit size (2^k rows)
+    pub k: u32,
+    /// Expected proof size in bytes
+    pub proof_size: usize,
+    /// Estimated single-threaded proving time
+    pub proving_time: Duration,
+}
+
+/// Plan and compile `query` over `table_data` and estimate its proving cost
+pub fn explain(
+    query: &SQLQuery,
+    table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+) -> Result<ExplainReport, String> {
+    let execution = Planner::new(table_data).plan(query)?;
+    let compiled = SQLCompiler::compile_planned(query, table_data, &execution)?;
+    let plan = QueryPlan::planned(query, &compiled, &execution);
+
+    let mut chips = Vec::new();
+    for chip in plan.nodes.iter().flat_map(|n| n.chips.iter()) {
+        if !chips.contains(chip) {
+            chips.push(*chip);
+        }
+    }
+    let k = plan.min_k();
+    let shape = CircuitShape::of_poneglyph();
+
+    Ok(ExplainReport {
+        chips,
+        advice_rows: plan.total_rows(),
+        k,
+        proof_size: proof_size(k),
+        proving_time: shape.proving_time(k),
+        plan,
+    })
+}
+
+impl fmt::Display for ExplainReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for decision in &self.plan.decisions {
+            writeln!(f, "plan: {}", decision)?;
+        }
+        for node in &self.plan.nodes {
+            write!(f, "{:<10}", node.operator.name())?;
+            if !node.detail.is_empty() {
+                write!(f, " {}", node.detail)?;
+            }
+            if !node.chips.is_empty() {
+                write!(f, " [{}]", node.chips.join(", "))?;
+            }
+            writeln!(f, " ~{} rows", node.rows)?;
+        }
+        writeln!(f, "chips: {}", self.chips.join(", "))?;
+        writeln!(
+            f,
+            "advice rows: ~{} (k = {}, {} rows)",
+            self.advice_rows,
+            self.k,
+            1usize << self.k
+        )?;
+        write!(
+            f,
+            "proof size: ~{} bytes, proving time: ~{:.1}s",
+            self.proof_size,
+            self.proving_time.as_secs_f64()
+        )
+    }
+}
+
+/// Proof size of `PoneglyphCircuit` at size k, as counted by halo2
+/// Columns, queries and lookups don't depend on the query, so a circuit
+/// without operations has the same transcript
+fn proof_size(k: u32) -> usize {
+    let circuit = PoneglyphCircuit {
+        scan: TableScan::default(),
+        query_result: Value::unknown(),
+        nonce: Value::unknown(),
+        expiry: Value::unknown(),
+        query_hash: Fr::from(0),
+        result_commitment: Value::unknown(),
+        result_columns: vec![],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: vec![],
+        aggregations: vec![],
+    };
+    CircuitCost::<Eq, PoneglyphCircuit>::measure(k, &circuit)
+        .proof_size(1)
+        .into()
+}
+
+/// Committed polynomials of `PoneglyphCircuit`, from its config's allocation
+struct CircuitShape {
+    advice: usize,
+    /// Fixed and lookup table columns
+    fixed: usize,
+    /// Selectors (each counted as a fixed column)
+    selectors: usize,
+    /// Lookup table columns (permuted input, permuted table and product
+    /// polynomials of the lookups into them)
+    table_columns: usize,
+    permutation_columns: usize,
+    degree: usize,
+}
+
+impl CircuitShape {
+    fn of_poneglyph() -> Self {
+        let mut cs = ConstraintSystem::<Fr>::default();
+        let config = PoneglyphCircuit::configure(&mut cs);
+        let table_columns = config.table_columns().len();
+
+        Self {
+            advice: config.advice_columns().len(),
+            fixed: config.fixed_columns().len() + table_columns,
+            selectors: config.selectors().len(),
+            table_columns,
+            permutation_columns: config.equality_columns().len(),
+            degree: cs.degree(),
+        }
+    }
+
+    /// Permutation product polynomials (columns are chunked by degree - 2)
+    fn permutation_chunks(&self) -> usize {
+        let chunk = self.degree.saturating_sub(2).max(1);
+        self.permutation_columns.div_ceil(chunk)
+    }
+
+    /// Proving time estimate at size k
+    fn proving_time(&self, k: u32) -> Duration {
+        let polynomials = self.advice
+            + self.fixed
+            + self.selectors
+            + 1 // instance column
+            + 3 * self.table_columns
+            + self.permutation_chunks()
+            + self.degree.saturating_sub(1);
+        let cells = (polynomials as u64) << k;
+        Duration::from_nanos(cells * k as u64 * PROVING_NANOS_PER_CELL)
+    }
+}
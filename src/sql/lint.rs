@@ -0,0 +1,347 @@
+// Query lints
+// Constructs that parse (and may prove) but whose result or proof means
+// something other than what the SQL seems to say:
+//
+// | Lint                 | Construct                                            |
+// |----------------------|------------------------------------------------------|
+// | `sum_overflow`       | SUM whose total may reach 2^64: the circuit adds in  |
+// |                      | the field and doesn't wrap, the executor stops at    |
+// |                      | u64, so the proven and the returned value disagree   |
+// | `unordered_limit`    | LIMIT / OFFSET without ORDER BY: which rows are kept |
+// |                      | depends on storage order, not on the query           |
+// | `fractional_literal` | `1.5` or `1e3` in an expression: values are integers |
+// |                      | (decimals are scaled) and the literal reads as a     |
+// |                      | column name                                          |
+//
+// Each lint has a level (`SET lint_<name> = allow | warn | deny`, default
+// `warn`). A session refuses queries with a denied lint and reports the
+// others from `Session::lint`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{AggregationFunction, SQLQuery, Unprovable, WhereClause};
+use crate::circuit::ArithExpr;
+
+/// Provability pitfall
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LintKind {
+    SumOverflow,
+    UnorderedLimit,
+    FractionalLiteral,
+}
+
+impl LintKind {
+    pub const ALL: [LintKind; 3] = [
+        LintKind::SumOverflow,
+        LintKind::UnorderedLimit,
+        LintKind::FractionalLiteral,
+    ];
+
+    /// Name in settings (`lint_<name>`) and messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::SumOverflow => "sum_overflow",
+            LintKind::UnorderedLimit => "unordered_limit",
+            LintKind::FractionalLiteral => "fractional_literal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// What a session does with a lint
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LintLevel {
+    /// Ignore it
+    Allow,
+    /// Report it (`Session::lint`)
+    #[default]
+    Warn,
+    /// Refuse the query
+    Deny,
+}
+
+impl LintLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [LintLevel::Allow, LintLevel::Warn, LintLevel::Deny]
+            .into_iter()
+            .find(|level| level.name() == name)
+    }
+}
+
+/// Lint found in a query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.name(), self.message)
+    }
+}
+
+/// Level of each lint
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LintLevels {
+    pub sum_overflow: LintLevel,
+    pub unordered_limit: LintLevel,
+    pub fractional_literal: LintLevel,
+}
+
+impl LintLevels {
+    pub fn level(&self, kind: LintKind) -> LintLevel {
+        match kind {
+            LintKind::SumOverflow => self.sum_overflow,
+            LintKind::UnorderedLimit => self.unordered_limit,
+            LintKind::FractionalLiteral => self.fractional_literal,
+        }
+    }
+
+    pub fn set(&mut self, kind: LintKind, level: LintLevel) {
+        match kind {
+            LintKind::SumOverflow => self.sum_overflow = level,
+            LintKind::UnorderedLimit => self.unordered_limit = level,
+            LintKind::FractionalLiteral => self.fractional_literal = level,
+        }
+    }
+
+    /// Apply the levels to the lints of a query
+    /// Returns the lints to report, or fails on the first denied one
+    pub fn check(&self, lints: Vec<Lint>) -> Result<Vec<Lint>, String> {
+        if let Some(denied) = lints
+            .iter()
+            .find(|lint| self.level(lint.kind) == LintLevel::Deny)
+        {
+            return Err(format!(
+                "{} (denied by lint_{})",
+                denied.message,
+                denied.kind.name()
+            ));
+        }
+        Ok(lints
+            .into_iter()
+            .filter(|lint| self.level(lint.kind) == LintLevel::Warn)
+            .collect())
+    }
+}
+
+/// Lint pass over a parsed query and the tables it reads
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let query = SQLParser::parse("SELECT amount FROM orders LIMIT 10")?;
+/// for lint in Linter::new(&table_data).lint(&query) {
+///     eprintln!("warning: {}", lint);
+/// }
+/// ```
+pub struct Linter<'a> {
+    table_data: &'a HashMap<String, HashMap<String, Vec<u64>>>,
+}
+
+impl<'a> Linter<'a> {
+    /// `table_data` bounds SUM totals; with tables missing from it (or none
+    /// at all) only the per-row width of SUM expressions is checked
+    pub fn new(table_data: &'a HashMap<String, HashMap<String, Vec<u64>>>) -> Self {
+        Self { table_data }
+    }
+
+    /// Lints of `query` and its nested queries (CTEs, UNION, subqueries)
+    pub fn lint(&self, query: &SQLQuery) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        self.lint_into(query, &mut lints);
+        lints
+    }
+
+    fn lint_into(&self, query: &SQLQuery, lints: &mut Vec<Lint>) {
+        for cte in query.ctes.iter().flatten() {
+            self.lint_into(&cte.query, lints);
+        }
+
+        if query.unprovable.contains(&Unprovable::Limit) && query.order_by.is_none() {
+            lints.push(Lint {
+                kind: LintKind::UnorderedLimit,
+                message: "LIMIT / OFFSET without ORDER BY keeps whichever rows come first \
+                          in storage order"
+                    .to_string(),
+            });
+        }
+
+        let aggregations = query.aggregations.iter().flatten();
+        for agg in aggregations.filter(|agg| matches!(agg.function, AggregationFunction::Sum)) {
+            if let Ok(expr) = ArithExpr::parse(&agg.column) {
+                if self.sum_bound(&query.from, &expr) > u64::MAX as u128 {
+                    lints.push(Lint {
+                        kind: LintKind::SumOverflow,
+                        message: format!(
+                            "SUM({}) may exceed 2^64; the proven total does not wrap",
+                            agg.column
+                        ),
+                    });
+                }
+            }
+        }
+
+        // Expressions of the select list, the aggregations and the predicate
+        let mut expressions: Vec<&str> = query
+            .columns
+            .iter()
+            .filter(|c| !c.contains(" over "))
+            .map(String::as_str)
+            .collect();
+        expressions.extend(
+            query
+                .aggregations
+                .iter()
+                .flatten()
+                .map(|a| a.column.as_str()),
+        );
+        let leaves = query.predicate.iter().flat_map(|p| p.leaves());
+        for leaf in leaves {
+            match leaf {
+                WhereClause::LessThan { column, .. }
+                | WhereClause::GreaterThan { column, .. }
+                | WhereClause::Equal { column, .. }
+                | WhereClause::Between { column, .. }
+                | WhereClause::In { column, .. }
+                | WhereClause::Like { column, .. } => expressions.push(column),
+                WhereClause::CompareSubquery {
+                    column, subquery, ..
+                }
+                | WhereClause::InSubquery { column, subquery }
+                | WhereClause::SemiJoin {
+                    column, subquery, ..
+                } => {
+                    expressions.push(column);
+                    self.lint_into(subquery, lints);
+                }
+                WhereClause::And(..) | WhereClause::Or(..) => {}
+            }
+        }
+        let mut seen = Vec::new();
+        let parsed: Vec<ArithExpr> = expressions
+            .into_iter()
+            .filter_map(|e| ArithExpr::parse(e).ok())
+            .collect();
+        for expr in &parsed {
+            for name in expr.columns() {
+                if is_fractional_literal(name) && !seen.contains(&name) {
+                    seen.push(name);
+                    lints.push(Lint {
+                        kind: LintKind::FractionalLiteral,
+                        message: format!(
+                            "{} is read as a column name; literals are integers \
+                             (decimal columns hold scaled values)",
+                            name
+                        ),
+                    });
+                }
+            }
+        }
+
+        if let Some(union) = &query.union {
+            self.lint_into(&union.query, lints);
+        }
+    }
+
+    /// Upper bound of the SUM of `expr` over `table`
+    /// Unknown columns count as u64::MAX and an unknown table as one row.
+    fn sum_bound(&self, table: &str, expr: &ArithExpr) -> u128 {
+        let columns = self.table_data.get(table);
+        let rows = columns
+            .and_then(|columns| columns.values().map(Vec::len).max())
+            .unwrap_or(1);
+        row_bound(expr, &|name| {
+            columns
+                .and_then(|columns| columns.get(name))
+                .map_or(u64::MAX, |values| values.iter().copied().max().unwrap_or(0))
+        })
+        .saturating_mul(rows as u128)
+    }
+}
+
+/// Upper bound of `expr` on one row, given each column's maximum
+fn row_bound(expr: &ArithExpr, max: &dyn Fn(&str) -> u64) -> u128 {
+    match expr {
+        ArithExpr::Column(name) => max(name) as u128,
+        ArithExpr::Const(value) => *value as u128,
+        ArithExpr::Add(l, r) => row_bound(l, max).saturating_add(row_bound(r, max)),
+        // Subtraction below zero fails the circuit, so the left side bounds it
+        ArithExpr::Sub(l, _) => row_bound(l, max),
+        ArithExpr::Mul(l, r) => row_bound(l, max).saturating_mul(row_bound(r, max)),
+    }
+}
+
+/// `1.5`, `.5`, `1e3`: a number that isn't an integer
+fn is_fractional_literal(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && name.parse::<u64>().is_err()
+        && name.parse::<f64>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_bound() {
+        let expr = ArithExpr::parse("price * qty + 5 - discount").unwrap();
+        let max = |name: &str| match name {
+            "price" => 100,
+            "qty" => 3,
+            _ => u64::MAX,
+        };
+        assert_eq!(row_bound(&expr, &max), 305);
+        let wide = ArithExpr::parse("a * b").unwrap();
+        assert!(row_bound(&wide, &|_| u64::MAX) > u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_is_fractional_literal() {
+        for name in ["1.5", ".5", "1e3", "2.0"] {
+            assert!(is_fractional_literal(name), "{}", name);
+        }
+        for name in ["price", "o.price", "15", "t1.a", "e1"] {
+            assert!(!is_fractional_literal(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_levels() {
+        let lint = |kind| Lint {
+            kind,
+            message: "m".to_string(),
+        };
+        let mut levels = LintLevels::default();
+        levels.set(LintKind::UnorderedLimit, LintLevel::Allow);
+        let reported = levels
+            .check(vec![
+                lint(LintKind::UnorderedLimit),
+                lint(LintKind::SumOverflow),
+            ])
+            .unwrap();
+        assert_eq!(reported, vec![lint(LintKind::SumOverflow)]);
+
+        levels.set(LintKind::SumOverflow, LintLevel::Deny);
+        let denied = levels.check(vec![lint(LintKind::SumOverflow)]).unwrap_err();
+        assert!(denied.contains("lint_sum_overflow"));
+        assert_eq!(
+            LintKind::from_name("fractional_literal"),
+            Some(LintKind::FractionalLiteral)
+        );
+        assert_eq!(LintLevel::from_name("deny"), Some(LintLevel::Deny));
+    }
+}
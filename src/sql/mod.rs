@@ -2,9 +2,32 @@
 // Paper Section 3: Compiling SQL queries to ZKP circuit
 
 use halo2_proofs::circuit::Value;
+use pasta_curves::pallas::Base as Fr;
 use std::collections::HashMap;
+use std::fmt;
 
-use crate::circuit::{AggregationOp, GroupByOp, JoinOp, RangeCheckOp, SortOp};
+pub mod executor;
+pub mod explain;
+pub mod lint;
+pub mod registry;
+pub mod settings;
+pub mod statement;
+pub mod stream;
+pub use executor::*;
+pub use explain::*;
+pub use lint::*;
+pub use registry::*;
+pub use settings::*;
+pub use statement::*;
+pub use stream::*;
+
+use crate::circuit::{
+    AggregationOp, AggregationType, ArithExpr, ArithmeticOp, BetweenOp, CastOp, GroupByOp, InListOp, JoinKind, JoinOp, LikeOp, LikePattern, PredicateTree,
+    RangeCheckOp, SeriesOp, SetOperation, SortOp, SubqueryFilter, SubqueryOp, UnionOp, WindowFunction, WindowOp,
+    COMPARISON_WINDOW, MAX_GROUP_KEY_COLUMNS,
+};
+use crate::database::{ColumnType, Database, DatabaseTable, ScanRange};
+use crate::optimization::ExecutionPlan;
 
 /// SQL Query AST (Abstract Syntax Tree)
 /// Paper Section 3: Used to compile SQL queries to circuit
@@ -12,12 +35,328 @@ use crate::circuit::{AggregationOp, GroupByOp, JoinOp, RangeCheckOp, SortOp};
 pub struct SQLQuery {
     pub columns: Vec<String>,
     pub from: String,
+    /// Alias of the FROM table (`FROM employees e`); columns qualified with
+    /// it are resolved to the FROM table at parse time
+    pub from_alias: Option<String>,
+    /// Table-valued function in FROM; `from` is its alias
+    pub table_function: Option<TableFunction>,
     pub where_clause: Option<WhereClause>,
+    /// Full WHERE predicate (with parentheses and NOT); `where_clause` is
+    /// None if the predicate uses NOT
+    pub predicate: Option<PredicateExpr>,
     pub group_by: Option<Vec<String>>,
     pub order_by: Option<Vec<OrderBy>>,
     pub having: Option<HavingClause>,
     pub joins: Option<Vec<JoinClause>>,
     pub aggregations: Option<Vec<AggregationClause>>,
+    pub windows: Option<Vec<WindowClause>>,
+    /// CAST columns of the select list
+    pub casts: Option<Vec<CastClause>>,
+    /// Common table expressions (WITH name AS (...)), in declaration order
+    pub ctes: Option<Vec<CommonTableExpr>>,
+    /// `UNION [ALL]` with a second query (this query is the left side)
+    pub union: Option<UnionClause>,
+    /// Constructs of this query the circuits don't prove (permissive mode);
+    /// nested queries carry their own
+    pub unprovable: Vec<Unprovable>,
+}
+
+impl SQLQuery {
+    /// Tables this query reads: FROM, JOINs and subqueries (CTE bodies excluded)
+    pub fn referenced_tables(&self) -> Vec<&str> {
+        let mut tables = Vec::new();
+        if self.table_function.is_none() {
+            tables.push(self.from.as_str());
+        }
+        tables.extend(self.joins.iter().flatten().map(|j| j.table.as_str()));
+        for leaf in self.predicate.iter().flat_map(|p| p.leaves()) {
+            if let WhereClause::CompareSubquery { subquery, .. }
+            | WhereClause::InSubquery { subquery, .. }
+            | WhereClause::SemiJoin { subquery, .. } = leaf
+            {
+                tables.extend(subquery.referenced_tables());
+            }
+        }
+        if let Some(union) = &self.union {
+            tables.extend(union.query.referenced_tables());
+        }
+        tables
+    }
+
+    /// Unprovable constructs of this query and every nested query
+    /// (CTE bodies, subqueries, UNION sides)
+    pub fn unprovable_constructs(&self) -> Vec<&Unprovable> {
+        let mut constructs: Vec<&Unprovable> = self.unprovable.iter().collect();
+        for cte in self.ctes.iter().flatten() {
+            constructs.extend(cte.query.unprovable_constructs());
+        }
+        for leaf in self.predicate.iter().flat_map(|p| p.leaves()) {
+            if let WhereClause::CompareSubquery { subquery, .. }
+            | WhereClause::InSubquery { subquery, .. }
+            | WhereClause::SemiJoin { subquery, .. } = leaf
+            {
+                constructs.extend(subquery.unprovable_constructs());
+            }
+        }
+        if let Some(union) = &self.union {
+            constructs.extend(union.query.unprovable_constructs());
+        }
+        constructs
+    }
+
+    /// Whether the whole query is in the provable subset
+    /// Otherwise it has to take the zkVM or plain execution path
+    pub fn is_provable(&self) -> bool {
+        self.unprovable_constructs().is_empty()
+    }
+
+    /// Resolve columns qualified with the FROM table (`e.salary` -> `salary`,
+    /// by alias if it has one); columns of joined tables keep their qualifier
+    fn strip_from_qualifier(&mut self) {
+        let qualifier = format!("{}.", self.from_alias.as_ref().unwrap_or(&self.from));
+        self.visit_identifiers(&mut |name| {
+            let mut stripped = String::with_capacity(name.len());
+            let mut last = 0;
+            for (idx, _) in name.match_indices(&qualifier) {
+                // Only whole qualifiers: `e.` but not `the.` or `x.e.`
+                let bounded = name[..idx]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '.'));
+                if bounded {
+                    stripped.push_str(&name[last..idx]);
+                    last = idx + qualifier.len();
+                }
+            }
+            stripped.push_str(&name[last..]);
+            *name = stripped;
+        });
+    }
+
+    /// Call `f` on every name and select expression, nested queries included
+    fn visit_identifiers(&mut self, f: &mut dyn FnMut(&mut String)) {
+        self.columns.iter_mut().for_each(&mut *f);
+        f(&mut self.from);
+        self.from_alias.iter_mut().for_each(&mut *f);
+        if let Some(function) = self.table_function.as_mut() {
+            f(&mut function.alias);
+            f(&mut function.column);
+        }
+        if let Some(clause) = self.where_clause.as_mut() {
+            clause.visit_identifiers(f);
+        }
+        if let Some(predicate) = self.predicate.as_mut() {
+            predicate.visit_identifiers(f);
+        }
+        self.group_by.iter_mut().flatten().for_each(&mut *f);
+        for order in self.order_by.iter_mut().flatten() {
+            f(&mut order.column);
+        }
+        if let Some(HavingClause::Compare { aggregation, .. }) = self.having.as_mut() {
+            f(aggregation);
+        }
+        for join in self.joins.iter_mut().flatten() {
+            f(&mut join.table);
+            join.alias.iter_mut().for_each(&mut *f);
+            f(&mut join.on.left_column);
+            f(&mut join.on.right_column);
+        }
+        for aggregation in self.aggregations.iter_mut().flatten() {
+            f(&mut aggregation.column);
+        }
+        for window in self.windows.iter_mut().flatten() {
+            window.column.iter_mut().for_each(&mut *f);
+            window.partition_by.iter_mut().for_each(&mut *f);
+            f(&mut window.order_by);
+        }
+        for cast in self.casts.iter_mut().flatten() {
+            f(&mut cast.column);
+        }
+        for cte in self.ctes.iter_mut().flatten() {
+            f(&mut cte.name);
+            cte.columns.iter_mut().flatten().for_each(&mut *f);
+            cte.query.visit_identifiers(f);
+        }
+        if let Some(union) = self.union.as_mut() {
+            union.query.visit_identifiers(f);
+        }
+        for construct in &mut self.unprovable {
+            if let Unprovable::Expression(expr) = construct {
+                f(expr);
+            }
+        }
+    }
+}
+
+/// Keywords that can't be used as unquoted names
+/// Quote them (`"order"`) to name a table or column
+pub const RESERVED_WORDS: &[&str] = &[
+    "all", "and", "as", "asc", "between", "by", "case", "create", "delete", "desc", "distinct",
+    "else", "end", "exists", "from", "full", "group", "having", "in", "inner", "insert", "into",
+    "is", "join", "left", "like", "limit", "not", "null", "offset", "on", "or", "order", "outer",
+    "over", "partition", "right", "select", "set", "table", "then", "union", "update", "values",
+    "when", "where", "with", "within",
+];
+
+/// Whether `word` (any case) is a reserved word
+pub fn is_reserved_word(word: &str) -> bool {
+    RESERVED_WORDS.contains(&word.to_lowercase().as_str())
+}
+
+/// Hash of a query, bound to the public inputs of its proof
+/// The text is normalized the way the parser reads it (the case of keywords
+/// and unquoted names and runs of whitespace don't matter), so a verifier
+/// hashes the SQL it asked for. Quoted text is hashed verbatim: `'Alice'`
+/// and `'alice'` are different queries.
+pub fn query_hash(sql: &str) -> Fr {
+    crate::utils::poseidon_hash_bytes(normalize_query(sql).as_bytes())
+}
+
+/// Text `query_hash` hashes: outside quotes, lowercase with runs of
+/// whitespace as one space; string literals and quoted names as written
+fn normalize_query(sql: &str) -> String {
+    let mut normalized = String::with_capacity(sql.len());
+    let mut quote = None;
+    let mut space = false;
+    for c in sql.trim().chars() {
+        match quote {
+            Some(q) => {
+                normalized.push(c);
+                // A doubled quote closes and reopens, leaving the text as is
+                if c == q {
+                    quote = None;
+                }
+            }
+            None if c.is_whitespace() => space = true,
+            None => {
+                if space {
+                    normalized.push(' ');
+                    space = false;
+                }
+                if c == '\'' || c == '"' {
+                    quote = Some(c);
+                }
+                normalized.extend(c.to_lowercase());
+            }
+        }
+    }
+    normalized
+}
+
+/// How the parser treats constructs outside the provable subset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Reject the query
+    Strict,
+    /// Accept the query and list the constructs in `SQLQuery::unprovable`
+    #[default]
+    Permissive,
+}
+
+/// Construct the parser accepts but the circuits don't prove
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Unprovable {
+    /// HAVING filter over groups
+    Having,
+    /// LIMIT / OFFSET
+    Limit,
+    /// SELECT DISTINCT
+    Distinct,
+    /// JOIN (or a comma-separated table list) in FROM
+    Join,
+    /// GROUP BY on more than `MAX_GROUP_KEY_COLUMNS` columns
+    MultiColumnGroupBy,
+    /// ORDER BY on several columns: each key is sorted on its own
+    MultiColumnOrderBy,
+    /// Function or expression in the select list with no gate (e.g. AVG)
+    Expression(String),
+}
+
+impl fmt::Display for Unprovable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unprovable::Having => write!(f, "HAVING"),
+            Unprovable::Limit => write!(f, "LIMIT / OFFSET"),
+            Unprovable::Distinct => write!(f, "SELECT DISTINCT"),
+            Unprovable::Join => write!(f, "JOIN"),
+            Unprovable::MultiColumnGroupBy => write!(
+                f,
+                "GROUP BY on more than {} columns",
+                MAX_GROUP_KEY_COLUMNS
+            ),
+            Unprovable::MultiColumnOrderBy => write!(f, "ORDER BY on several columns"),
+            Unprovable::Expression(expr) => write!(f, "expression '{}'", expr),
+        }
+    }
+}
+
+/// Table-valued function: `generate_series(start, stop[, step]) [AS] alias[(column)]`
+/// The alias names both the relation and its single column unless a column
+/// name is given (default `generate_series`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableFunction {
+    pub kind: TableFunctionKind,
+    pub alias: String,
+    pub column: String,
+}
+
+/// Supported table-valued functions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TableFunctionKind {
+    /// generate_series(start, stop[, step]), ascending, step >= 1
+    GenerateSeries(SeriesOp),
+}
+
+impl TableFunction {
+    /// Values of the single column
+    pub fn values(&self) -> Vec<u64> {
+        match &self.kind {
+            TableFunctionKind::GenerateSeries(op) => op.values(),
+        }
+    }
+
+    /// `table_data` with the function's relation added under its alias
+    pub fn with_relation(
+        &self,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<HashMap<String, HashMap<String, Vec<u64>>>, String> {
+        if table_data.contains_key(&self.alias) {
+            return Err(format!("Alias {} shadows an existing table", self.alias));
+        }
+        let mut tables = table_data.clone();
+        let relation = HashMap::from([(self.column.clone(), self.values())]);
+        tables.insert(self.alias.clone(), relation);
+        Ok(tables)
+    }
+}
+
+impl fmt::Display for TableFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TableFunctionKind::GenerateSeries(op) => write!(
+                f,
+                "generate_series({}, {}, {}) AS {}({})",
+                op.start, op.stop, op.step, self.alias, self.column
+            ),
+        }
+    }
+}
+
+/// Right side of a UNION / UNION ALL
+#[derive(Clone, Debug)]
+pub struct UnionClause {
+    /// UNION ALL (keep duplicates)
+    pub all: bool,
+    pub query: Box<SQLQuery>,
+}
+
+/// Common table expression: a named intermediate relation
+#[derive(Clone, Debug)]
+pub struct CommonTableExpr {
+    pub name: String,
+    /// Column names of the relation (default: the SELECT list of `query`)
+    pub columns: Option<Vec<String>>,
+    pub query: SQLQuery,
 }
 
 /// WHERE clause
@@ -29,16 +368,296 @@ pub enum WhereClause {
     GreaterThan { column: String, value: u64 },
     /// Range check: column = value
     Equal { column: String, value: u64 },
+    /// Range check: column BETWEEN low AND high (inclusive)
+    Between { column: String, low: u64, high: u64 },
+    /// Membership: column IN (v1, ..., vn)
+    In { column: String, values: Vec<u64> },
+    /// String match: column LIKE 'pattern' (column holds packed strings)
+    Like { column: String, pattern: String },
+    /// Comparison with an uncorrelated scalar subquery: column < (SELECT ...)
+    CompareSubquery {
+        column: String,
+        operator: ComparisonOp,
+        subquery: Box<SQLQuery>,
+    },
+    /// Membership in an uncorrelated subquery result: column IN (SELECT ...)
+    InSubquery {
+        column: String,
+        subquery: Box<SQLQuery>,
+    },
+    /// Semi / anti join with a subquery relation: rows whose column has
+    /// (`JoinType::Semi`) or has no (`JoinType::Anti`) match in the single
+    /// selected column
+    /// `[NOT] EXISTS` with one equality correlation and `NOT IN (SELECT ...)`
+    SemiJoin {
+        column: String,
+        subquery: Box<SQLQuery>,
+        join_type: JoinType,
+    },
     /// AND operation
     And(Box<WhereClause>, Box<WhereClause>),
     /// OR operation
     Or(Box<WhereClause>, Box<WhereClause>),
 }
 
+impl WhereClause {
+    fn visit_identifiers(&mut self, f: &mut dyn FnMut(&mut String)) {
+        match self {
+            WhereClause::LessThan { column, .. }
+            | WhereClause::GreaterThan { column, .. }
+            | WhereClause::Equal { column, .. }
+            | WhereClause::Between { column, .. }
+            | WhereClause::In { column, .. }
+            | WhereClause::Like { column, .. } => f(column),
+            WhereClause::CompareSubquery {
+                column, subquery, ..
+            }
+            | WhereClause::InSubquery { column, subquery }
+            | WhereClause::SemiJoin {
+                column, subquery, ..
+            } => {
+                f(column);
+                subquery.visit_identifiers(f);
+            }
+            WhereClause::And(left, right) | WhereClause::Or(left, right) => {
+                left.visit_identifiers(f);
+                right.visit_identifiers(f);
+            }
+        }
+    }
+
+    /// Range of `column` values a row needs to satisfy the clause, for a
+    /// pushed-down index scan
+    /// The range may be wider than the clause (the scan returns candidates,
+    /// the clause still filters them); None if the clause does not bound
+    /// `column`.
+    pub fn scan_range(&self, column: &str) -> Option<ScanRange> {
+        match self {
+            WhereClause::LessThan { column: c, value } if c == column => {
+                Some(ScanRange::less_than(*value))
+            }
+            WhereClause::GreaterThan { column: c, value } if c == column => {
+                Some(ScanRange::greater_than(*value))
+            }
+            WhereClause::Equal { column: c, value } if c == column => {
+                Some(ScanRange::equal(*value))
+            }
+            WhereClause::Between {
+                column: c,
+                low,
+                high,
+            } if c == column => Some(ScanRange::between(*low, *high)),
+            WhereClause::In { column: c, values } if c == column => {
+                let low = values.iter().min()?;
+                let high = values.iter().max()?;
+                Some(ScanRange::between(*low, *high))
+            }
+            WhereClause::And(left, right) => {
+                match (left.scan_range(column), right.scan_range(column)) {
+                    (Some(l), Some(r)) => Some(l.intersect(&r)),
+                    (l, r) => l.or(r),
+                }
+            }
+            WhereClause::Or(left, right) => {
+                Some(left.scan_range(column)?.hull(&right.scan_range(column)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// WHERE predicate tree
+/// Comparisons are the leaves; precedence is NOT > AND > OR
+#[derive(Clone, Debug)]
+pub enum PredicateExpr {
+    /// Single comparison (never `WhereClause::And` / `WhereClause::Or`)
+    Compare(WhereClause),
+    /// AND operation
+    And(Box<PredicateExpr>, Box<PredicateExpr>),
+    /// OR operation
+    Or(Box<PredicateExpr>, Box<PredicateExpr>),
+    /// NOT operation
+    Not(Box<PredicateExpr>),
+}
+
+impl PredicateExpr {
+    /// Parse a (lowercased) WHERE predicate
+    pub fn parse(where_part: &str) -> Result<Self, String> {
+        let where_part = where_part.trim();
+
+        if let Some(&or_idx) = Self::top_level_matches(where_part, " or ").first() {
+            let left = Self::parse(&where_part[..or_idx])?;
+            let right = Self::parse(&where_part[or_idx + 4..])?;
+            return Ok(PredicateExpr::Or(Box::new(left), Box::new(right)));
+        }
+
+        if let Some(and_idx) = Self::find_logical_and(where_part) {
+            let left = Self::parse(&where_part[..and_idx])?;
+            let right = Self::parse(&where_part[and_idx + 5..])?;
+            return Ok(PredicateExpr::And(Box::new(left), Box::new(right)));
+        }
+
+        if let Some(operand) = where_part.strip_prefix("not ").or_else(|| {
+            where_part
+                .strip_prefix("not")
+                .filter(|operand| operand.starts_with('('))
+        }) {
+            // NOT EXISTS is one comparison (an anti join), not NOT over EXISTS
+            if operand.trim_start().starts_with("exists") {
+                return SQLParser::parse_where_clause(where_part).map(PredicateExpr::Compare);
+            }
+            return Ok(PredicateExpr::Not(Box::new(Self::parse(operand)?)));
+        }
+
+        if let Some(inner) = Self::strip_parentheses(where_part) {
+            return Self::parse(inner);
+        }
+
+        SQLParser::parse_where_clause(where_part).map(PredicateExpr::Compare)
+    }
+
+    /// Comparisons in textual order (leaf `i` of `to_tree`)
+    pub fn leaves(&self) -> Vec<&WhereClause> {
+        match self {
+            PredicateExpr::Compare(clause) => vec![clause],
+            PredicateExpr::And(l, r) | PredicateExpr::Or(l, r) => {
+                let mut leaves = l.leaves();
+                leaves.extend(r.leaves());
+                leaves
+            }
+            PredicateExpr::Not(e) => e.leaves(),
+        }
+    }
+
+    /// Circuit-side tree, leaves numbered as in `leaves`
+    pub fn to_tree(&self) -> PredicateTree {
+        fn build(expr: &PredicateExpr, next: &mut usize) -> PredicateTree {
+            match expr {
+                PredicateExpr::Compare(_) => {
+                    *next += 1;
+                    PredicateTree::Leaf(*next - 1)
+                }
+                PredicateExpr::And(l, r) => {
+                    let l = build(l, next);
+                    PredicateTree::And(Box::new(l), Box::new(build(r, next)))
+                }
+                PredicateExpr::Or(l, r) => {
+                    let l = build(l, next);
+                    PredicateTree::Or(Box::new(l), Box::new(build(r, next)))
+                }
+                PredicateExpr::Not(e) => PredicateTree::Not(Box::new(build(e, next))),
+            }
+        }
+        build(self, &mut 0)
+    }
+
+    /// Equivalent `WhereClause` (None if the predicate uses NOT)
+    pub fn to_where_clause(&self) -> Option<WhereClause> {
+        match self {
+            PredicateExpr::Compare(clause) => Some(clause.clone()),
+            PredicateExpr::And(l, r) => Some(WhereClause::And(
+                Box::new(l.to_where_clause()?),
+                Box::new(r.to_where_clause()?),
+            )),
+            PredicateExpr::Or(l, r) => Some(WhereClause::Or(
+                Box::new(l.to_where_clause()?),
+                Box::new(r.to_where_clause()?),
+            )),
+            PredicateExpr::Not(_) => None,
+        }
+    }
+
+    /// Replace (lowercased) LIKE patterns with the original literals
+    fn restore_like_patterns(&mut self, literals: &mut impl Iterator<Item = String>) {
+        match self {
+            PredicateExpr::Compare(clause) => SQLParser::restore_like_patterns(clause, literals),
+            PredicateExpr::And(l, r) | PredicateExpr::Or(l, r) => {
+                l.restore_like_patterns(literals);
+                r.restore_like_patterns(literals);
+            }
+            PredicateExpr::Not(e) => e.restore_like_patterns(literals),
+        }
+    }
+
+    fn visit_identifiers(&mut self, f: &mut dyn FnMut(&mut String)) {
+        match self {
+            PredicateExpr::Compare(clause) => clause.visit_identifiers(f),
+            PredicateExpr::And(l, r) | PredicateExpr::Or(l, r) => {
+                l.visit_identifiers(f);
+                r.visit_identifiers(f);
+            }
+            PredicateExpr::Not(e) => e.visit_identifiers(f),
+        }
+    }
+
+    /// Positions of `keyword` outside parentheses and quoted literals
+    fn top_level_matches(s: &str, keyword: &str) -> Vec<usize> {
+        let mut depth = 0i32;
+        let mut quoted = false;
+        let mut positions = Vec::new();
+        for (idx, c) in s.char_indices() {
+            match c {
+                '\'' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 && !quoted && s[idx..].starts_with(keyword) {
+                positions.push(idx);
+            }
+        }
+        positions
+    }
+
+    /// Position of the first top-level " and " that is a logical AND
+    /// (the " and " of `x between a and b` belongs to BETWEEN)
+    fn find_logical_and(s: &str) -> Option<usize> {
+        let betweens = Self::top_level_matches(s, " between ");
+        let mut pending_between = 0;
+        let mut searched = 0;
+        for idx in Self::top_level_matches(s, " and ") {
+            pending_between += betweens
+                .iter()
+                .filter(|&&b| b >= searched && b < idx)
+                .count();
+            searched = idx;
+            if pending_between > 0 {
+                pending_between -= 1;
+            } else {
+                return Some(idx);
+            }
+        }
+        None
+    }
+
+    /// Inner part of `( ... )` if the parentheses enclose the whole string
+    fn strip_parentheses(s: &str) -> Option<&str> {
+        let inner = s.strip_prefix('(')?.strip_suffix(')')?;
+        let mut depth = 0i32;
+        let mut quoted = false;
+        for c in inner.chars() {
+            match c {
+                '\'' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return None;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(inner)
+    }
+}
+
 /// JOIN clause
 #[derive(Clone, Debug)]
 pub struct JoinClause {
     pub table: String,
+    /// Alias of the joined table (required when a table is joined with itself)
+    pub alias: Option<String>,
     pub on: JoinCondition,
     pub join_type: JoinType,
 }
@@ -57,6 +676,10 @@ pub enum JoinType {
     Left,
     Right,
     Full,
+    /// Left rows with a match (EXISTS); only the left columns are output
+    Semi,
+    /// Left rows without a match (NOT EXISTS, NOT IN)
+    Anti,
 }
 
 /// ORDER BY clause
@@ -107,8 +730,195 @@ pub enum AggregationFunction {
     Max,
     Min,
     Avg,
+    /// MEDIAN(column) (lower median)
+    Median,
+    /// PERCENTILE_DISC(p / 100) WITHIN GROUP (ORDER BY column)
+    Percentile(u8),
 }
 
+/// Window clause: `function() OVER (PARTITION BY column ORDER BY column)`
+#[derive(Clone, Debug)]
+pub struct WindowClause {
+    pub function: WindowFunction,
+    /// Function argument (SUM column)
+    pub column: Option<String>,
+    /// None = the whole table is one partition
+    pub partition_by: Option<String>,
+    pub order_by: String,
+}
+
+/// CAST clause: `CAST(column AS type)` in the select list
+/// `column` may also be an arithmetic expression over columns
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CastClause {
+    pub column: String,
+    pub target: CastType,
+}
+
+impl CastClause {
+    /// Parse a select column (None if it is not a CAST)
+    pub fn parse(col: &str) -> Option<Result<Self, String>> {
+        let inner = col.strip_prefix("cast(")?.strip_suffix(')')?;
+        let Some(&as_idx) = PredicateExpr::top_level_matches(inner, " as ").first() else {
+            return Some(Err(format!("Missing AS in {}", col)));
+        };
+        let column = inner[..as_idx].trim().to_string();
+        if column.is_empty() {
+            return Some(Err(format!("Missing CAST operand in {}", col)));
+        }
+        Some(CastType::parse(&inner[as_idx + 4..]).map(|target| CastClause { column, target }))
+    }
+
+    /// Cast of a column of type `source` (the operation's value is unknown)
+    /// Fails at plan time on casts the Cast Gate can't prove: text is stored
+    /// as a hash, so nothing converts from or to TEXT, and a boolean is only
+    /// cast from a boolean
+    pub fn plan(&self, source: ColumnType) -> Result<CastOp, String> {
+        let provable = match (source, self.target) {
+            (ColumnType::Text, target) => target == CastType::Text,
+            (_, CastType::Text) => false,
+            (source, CastType::Boolean) => source == ColumnType::Boolean,
+            _ => true,
+        };
+        if !provable {
+            return Err(format!(
+                "CAST({} AS {}) from {:?} can't be proven",
+                self.column, self.target, source
+            ));
+        }
+        let op = CastOp {
+            value: Value::unknown(),
+            from_scale: source.scale(),
+            to_scale: self.target.scale(),
+            max: self.target.max(),
+        };
+        if op.multiplier().is_none() || op.divisor().is_none() {
+            return Err(format!(
+                "CAST({} AS {}) rescales by more than 10^19",
+                self.column, self.target
+            ));
+        }
+        Ok(op)
+    }
+
+    /// Cast raw values of type `source`; a value that does not fit the
+    /// target type is an error, as in SQL
+    pub fn apply(&self, source: ColumnType, values: &[u64]) -> Result<Vec<u64>, String> {
+        let op = self.plan(source)?;
+        values
+            .iter()
+            .map(|&value| {
+                op.apply(value).ok_or_else(|| {
+                    format!("{} does not fit {} (CAST of {})", value, self.target, self.column)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Target type of a CAST
+/// Integers are unsigned, like every stored value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CastType {
+    /// Integer of the given width in bits
+    /// (TINYINT 8, SMALLINT 16, INT / INTEGER 32, BIGINT 64)
+    Integer(u32),
+    /// DECIMAL(precision, scale) / NUMERIC(precision, scale), at most 19
+    /// digits; plain DECIMAL is DECIMAL(19, 0)
+    Decimal { precision: u32, scale: u32 },
+    Boolean,
+    /// TEXT, VARCHAR(n), CHAR(n)
+    Text,
+}
+
+impl CastType {
+    /// Parse a (lowercased) type name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        let (base, args) = match name.split_once('(') {
+            Some((base, args)) => {
+                let args = args
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("Invalid type: {}", name))?;
+                let args = args
+                    .split(',')
+                    .map(|a| a.trim().parse::<u32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("Invalid type arguments: {}", name))?;
+                (base.trim(), args)
+            }
+            None => (name, Vec::new()),
+        };
+        let no_args = |ty: CastType| match args.is_empty() {
+            true => Ok(ty),
+            false => Err(format!("Type {} takes no arguments", base)),
+        };
+        match base {
+            "tinyint" => no_args(CastType::Integer(8)),
+            "smallint" => no_args(CastType::Integer(16)),
+            "int" | "integer" => no_args(CastType::Integer(32)),
+            "bigint" => no_args(CastType::Integer(64)),
+            "boolean" | "bool" => no_args(CastType::Boolean),
+            "text" => no_args(CastType::Text),
+            "varchar" | "char" if args.len() <= 1 => Ok(CastType::Text),
+            "decimal" | "numeric" => {
+                let (precision, scale) = match args.as_slice() {
+                    [] => (19, 0),
+                    [precision] => (*precision, 0),
+                    [precision, scale] => (*precision, *scale),
+                    _ => return Err(format!("Invalid type arguments: {}", name)),
+                };
+                if precision == 0 || precision > 19 || scale > precision {
+                    return Err(format!(
+                        "{} needs 1 <= precision <= 19 and scale <= precision",
+                        name
+                    ));
+                }
+                Ok(CastType::Decimal { precision, scale })
+            }
+            _ => Err(format!("Unsupported CAST type: {}", name)),
+        }
+    }
+
+    /// Fixed-point scale of the result (0 for non-decimal types)
+    pub fn scale(&self) -> u32 {
+        match self {
+            CastType::Decimal { scale, .. } => *scale,
+            _ => 0,
+        }
+    }
+
+    /// Largest raw value of the type
+    pub fn max(&self) -> u64 {
+        match self {
+            CastType::Integer(bits) if *bits < 64 => (1 << bits) - 1,
+            CastType::Decimal { precision, .. } => 10u64.pow(*precision) - 1,
+            CastType::Boolean => 1,
+            CastType::Integer(_) | CastType::Text => u64::MAX,
+        }
+    }
+}
+
+impl fmt::Display for CastType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CastType::Integer(8) => write!(f, "TINYINT"),
+            CastType::Integer(16) => write!(f, "SMALLINT"),
+            CastType::Integer(32) => write!(f, "INTEGER"),
+            CastType::Integer(64) => write!(f, "BIGINT"),
+            CastType::Integer(bits) => write!(f, "INTEGER({})", bits),
+            CastType::Decimal { precision, scale } => {
+                write!(f, "DECIMAL({}, {})", precision, scale)
+            }
+            CastType::Boolean => write!(f, "BOOLEAN"),
+            CastType::Text => write!(f, "TEXT"),
+        }
+    }
+}
+
+/// Prefix of the placeholders that stand for quoted identifiers while parsing
+const QUOTED_PLACEHOLDER: &str = "__quoted_";
+
 /// SQL Parser
 /// Converts SQL strings to AST
 pub struct SQLParser;
@@ -116,8 +926,111 @@ pub struct SQLParser;
 impl SQLParser {
     /// Parse SQL string
     /// Simple parser - production can use more advanced parser (e.g.: sqlparser-rs)
+    ///
+    /// Permissive: constructs outside the provable subset are accepted and
+    /// listed in `SQLQuery::unprovable` (see `parse_with_mode`)
+    ///
+    /// Identifiers: unquoted names fold to lowercase and must not be reserved
+    /// words (`RESERVED_WORDS`). Double-quoted names (`"Order"`, `"unit price"`,
+    /// `"a""b"` for `a"b`) may be reserved words or contain any character;
+    /// they fold to lowercase as well, since catalog names are
+    /// case-insensitive.
     pub fn parse(sql: &str) -> Result<SQLQuery, String> {
-        let sql = sql.trim().to_lowercase();
+        let (sql, quoted) = Self::quote_identifiers(sql)?;
+        let mut query = Self::parse_query(&sql)?;
+
+        let mut reserved = None;
+        query.visit_identifiers(&mut |name| {
+            if reserved.is_none() && is_reserved_word(name) {
+                reserved = Some(name.clone());
+            }
+            for (idx, quoted) in quoted.iter().enumerate() {
+                *name = name.replace(&Self::quoted_placeholder(idx), quoted);
+            }
+        });
+        match reserved {
+            Some(word) => Err(format!(
+                "'{}' is a reserved word; quote it (\"{}\") to use it as a name",
+                word, word
+            )),
+            None => Ok(query),
+        }
+    }
+
+    /// Replace double-quoted identifiers with placeholders
+    /// Placeholders are plain lowercase words, so clause splitting and
+    /// keyword search never see the quoted text. Returns the rewritten query
+    /// and the (folded) names in placeholder order.
+    fn quote_identifiers(sql: &str) -> Result<(String, Vec<String>), String> {
+        if sql.to_lowercase().contains(QUOTED_PLACEHOLDER) {
+            return Err(format!(
+                "Names starting with {} are reserved",
+                QUOTED_PLACEHOLDER
+            ));
+        }
+        let mut rewritten = String::with_capacity(sql.len());
+        let mut names = Vec::new();
+        let mut literal = false;
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    literal = !literal;
+                    rewritten.push(c);
+                }
+                '"' if !literal => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') if chars.peek() == Some(&'"') => {
+                                chars.next();
+                                name.push('"');
+                            }
+                            Some('"') => break,
+                            Some(c) => name.push(c),
+                            None => return Err("Unterminated quoted identifier".to_string()),
+                        }
+                    }
+                    if name.is_empty() {
+                        return Err("Empty quoted identifier".to_string());
+                    }
+                    rewritten.push_str(&Self::quoted_placeholder(names.len()));
+                    names.push(name.to_lowercase());
+                }
+                _ => rewritten.push(c),
+            }
+        }
+        Ok((rewritten, names))
+    }
+
+    fn quoted_placeholder(idx: usize) -> String {
+        format!("{}{}__", QUOTED_PLACEHOLDER, idx)
+    }
+
+    /// Parse a query whose quoted identifiers are replaced (see `parse`)
+    fn parse_query(sql: &str) -> Result<SQLQuery, String> {
+        let original = sql.trim();
+        if Self::starts_with_keyword(original, "with ") {
+            return Self::parse_with(original);
+        }
+        // UNION: split at the first top-level UNION, the rest is the right side
+        let lowered = original.to_ascii_lowercase();
+        if let Some(&union_idx) = PredicateExpr::top_level_matches(&lowered, " union ").first() {
+            let mut query = Self::parse_query(&original[..union_idx])?;
+            let right = original[union_idx + 7..].trim_start();
+            let all = Self::starts_with_keyword(right, "all ");
+            let right = Self::parse_query(if all { &right[4..] } else { right })?;
+            // Chains of one kind are associative; mixed chains are not
+            if right.union.as_ref().is_some_and(|u| u.all != all) {
+                return Err("Mixing UNION and UNION ALL is not supported".to_string());
+            }
+            query.union = Some(UnionClause {
+                all,
+                query: Box::new(right),
+            });
+            return Ok(query);
+        }
+        let sql = original.to_lowercase();
 
         // Simple SELECT parsing
         if !sql.starts_with("select") {
@@ -128,49 +1041,87 @@ impl SQLParser {
         let mut query = SQLQuery {
             columns: Vec::new(),
             from: String::new(),
+            from_alias: None,
+            table_function: None,
             where_clause: None,
+            predicate: None,
             group_by: None,
             order_by: None,
             having: None,
             joins: None,
             aggregations: None,
+            windows: None,
+            casts: None,
+            ctes: None,
+            union: None,
+            unprovable: Vec::new(),
         };
 
         // Find FROM clause
         let from_idx = sql.find(" from ").ok_or("Missing FROM clause")?;
         let select_part = &sql[6..from_idx].trim();
 
-        // Parse columns
-        query.columns = select_part
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .collect();
+        // Parse columns (commas inside parentheses belong to the column, e.g.
+        // CAST(x AS DECIMAL(10, 2)))
+        let mut start = 0;
+        for idx in PredicateExpr::top_level_matches(select_part, ",")
+            .into_iter()
+            .chain([select_part.len()])
+        {
+            query.columns.push(select_part[start..idx].trim().to_string());
+            start = idx + 1;
+        }
 
         // Parse after FROM
         let after_from = &sql[from_idx + 6..];
 
+        // Clause keywords inside subqueries belong to the subquery
+        let find = |s: &str, keyword: &str| {
+            PredicateExpr::top_level_matches(s, keyword).first().copied()
+        };
+
         // Find WHERE clause
-        if let Some(where_idx) = after_from.find(" where ") {
+        if let Some(where_idx) = find(after_from, " where ") {
             query.from = after_from[..where_idx].trim().to_string();
             let where_part = &after_from[where_idx + 7..];
+            let where_end = find(where_part, " group by ")
+                .or_else(|| find(where_part, " order by "))
+                .unwrap_or(where_part.len());
+            let where_part = &where_part[..where_end];
 
-            // Parse WHERE clause (simple: column < value, column > value, column = value)
-            query.where_clause = Some(Self::parse_where_clause(where_part)?);
+            // Parse WHERE clause (comparisons combined with AND/OR/NOT and parentheses)
+            let mut predicate = PredicateExpr::parse(where_part)?;
+
+            // LIKE patterns are case sensitive: take them from the original query
+            let mut literals = Self::quoted_literals(original).into_iter();
+            predicate.restore_like_patterns(&mut literals);
+            query.where_clause = predicate.to_where_clause();
+            query.predicate = Some(predicate);
         } else {
             // If no WHERE, take part until GROUP BY or ORDER BY as FROM
-            let end_idx = after_from
-                .find(" group by ")
-                .or_else(|| after_from.find(" order by "))
+            let end_idx = find(after_from, " group by ")
+                .or_else(|| find(after_from, " order by "))
                 .unwrap_or(after_from.len());
             query.from = after_from[..end_idx].trim().to_string();
         }
 
+        // Table-valued function in FROM: the query reads its alias
+        query.table_function = Self::parse_table_function(&query.from)?;
+        if let Some(function) = &query.table_function {
+            query.from = function.alias.clone();
+        } else if let Some((table, alias, joins)) = Self::parse_from(&query.from)? {
+            query.from = table;
+            query.from_alias = alias;
+            if !joins.is_empty() {
+                query.joins = Some(joins);
+            }
+        }
+
         // Find GROUP BY clause
-        if let Some(group_idx) = after_from.find(" group by ") {
+        if let Some(group_idx) = find(after_from, " group by ") {
             let group_part = &after_from[group_idx + 10..];
-            let end_idx = group_part
-                .find(" order by ")
-                .or_else(|| group_part.find(" having "))
+            let end_idx = find(group_part, " order by ")
+                .or_else(|| find(group_part, " having "))
                 .unwrap_or(group_part.len());
 
             query.group_by = Some(
@@ -183,18 +1134,20 @@ impl SQLParser {
         }
 
         // Find ORDER BY clause
-        if let Some(order_idx) = after_from.find(" order by ") {
+        if let Some(order_idx) = find(after_from, " order by ") {
             let order_part = &after_from[order_idx + 10..];
             query.order_by = Some(Self::parse_order_by(order_part)?);
         }
 
         // Detect aggregation functions
         let mut aggregations = Vec::new();
-        for col in &query.columns {
+        for col in query.columns.iter().filter(|c| !c.contains(" over ")) {
             if col.starts_with("sum(")
                 || col.starts_with("count(")
                 || col.starts_with("max(")
                 || col.starts_with("min(")
+                || col.starts_with("median(")
+                || col.starts_with("percentile_disc(")
             {
                 if let Some(agg) = Self::parse_aggregation(col) {
                     aggregations.push(agg);
@@ -205,24 +1158,136 @@ impl SQLParser {
             query.aggregations = Some(aggregations);
         }
 
+        // Detect window functions
+        let mut windows = Vec::new();
+        for col in &query.columns {
+            if let Some(window) = Self::parse_window(col) {
+                windows.push(window?);
+            }
+        }
+        if !windows.is_empty() {
+            query.windows = Some(windows);
+        }
+
+        // Detect CASTs
+        let mut casts = Vec::new();
+        for col in &query.columns {
+            if let Some(cast) = CastClause::parse(col) {
+                casts.push(cast?);
+            }
+        }
+        if !casts.is_empty() {
+            query.casts = Some(casts);
+        }
+
+        query.strip_from_qualifier();
+        query.unprovable = Self::unprovable(&query, after_from);
+        Ok(query)
+    }
+
+    /// Parse SQL string in the given mode
+    /// Strict mode fails on the first construct outside the provable subset,
+    /// including those of nested queries
+    pub fn parse_with_mode(sql: &str, mode: ParseMode) -> Result<SQLQuery, String> {
+        let query = Self::parse(sql)?;
+        if mode == ParseMode::Strict {
+            if let Some(construct) = query.unprovable_constructs().first() {
+                return Err(format!("{} is outside the provable subset", construct));
+            }
+        }
         Ok(query)
     }
 
-    /// Parse WHERE clause
+    /// Constructs of a parsed SELECT the circuits don't prove
+    /// `after_from` is the lowercased text after FROM
+    fn unprovable(query: &SQLQuery, after_from: &str) -> Vec<Unprovable> {
+        let has = |keyword: &str| !PredicateExpr::top_level_matches(after_from, keyword).is_empty();
+        let mut unprovable = Vec::new();
+        if has(" having ") {
+            unprovable.push(Unprovable::Having);
+        }
+        if has(" limit ") || has(" offset ") {
+            unprovable.push(Unprovable::Limit);
+        }
+        if query.columns.first().is_some_and(|c| c.starts_with("distinct ")) {
+            unprovable.push(Unprovable::Distinct);
+        }
+        let from = format!(" {} ", query.from);
+        if !PredicateExpr::top_level_matches(&from, " join ").is_empty() || from.contains(',') {
+            unprovable.push(Unprovable::Join);
+        }
+        if query
+            .group_by
+            .as_ref()
+            .is_some_and(|keys| keys.len() > MAX_GROUP_KEY_COLUMNS)
+        {
+            unprovable.push(Unprovable::MultiColumnGroupBy);
+        }
+        if query.order_by.as_ref().is_some_and(|keys| keys.len() > 1) {
+            unprovable.push(Unprovable::MultiColumnOrderBy);
+        }
+        // Calls that are neither aggregations, window functions, CASTs nor arithmetic
+        for column in query.columns.iter().filter(|c| c.contains('(')) {
+            if Self::parse_aggregation(column).is_none()
+                && Self::parse_window(column).is_none()
+                && CastClause::parse(column).is_none()
+                && ArithExpr::parse(column).is_err()
+            {
+                unprovable.push(Unprovable::Expression(column.clone()));
+            }
+        }
+        unprovable
+    }
+
+    /// Parse a single WHERE comparison (AND/OR/NOT are handled by `PredicateExpr`)
     fn parse_where_clause(where_part: &str) -> Result<WhereClause, String> {
         let where_part = where_part.trim();
 
-        // Check AND/OR operators
-        if let Some(and_idx) = where_part.find(" and ") {
-            let left = Self::parse_where_clause(&where_part[..and_idx])?;
-            let right = Self::parse_where_clause(&where_part[and_idx + 5..])?;
-            return Ok(WhereClause::And(Box::new(left), Box::new(right)));
+        // Subquery: column op (select ...) / column in (select ...)
+        if let Some(select_idx) = where_part.find("(select ") {
+            return Self::parse_subquery_clause(&where_part[..select_idx], &where_part[select_idx..]);
+        }
+
+        // Range check: column between low and high
+        if let Some(between_idx) = where_part.find(" between ") {
+            let column = where_part[..between_idx].trim().to_string();
+            let bounds = &where_part[between_idx + 9..];
+            let and_idx = bounds.find(" and ").ok_or("Missing AND in BETWEEN")?;
+            let parse_bound = |b: &str| {
+                b.trim()
+                    .parse::<u64>()
+                    .map_err(|_| "Invalid number in BETWEEN".to_string())
+            };
+            let low = parse_bound(&bounds[..and_idx])?;
+            let high = parse_bound(&bounds[and_idx + 5..])?;
+            return Ok(WhereClause::Between { column, low, high });
+        }
+
+        // Membership: column in (v1, ..., vn)
+        if let Some(in_idx) = where_part.find(" in (") {
+            let column = where_part[..in_idx].trim().to_string();
+            let list = where_part[in_idx + 5..]
+                .trim()
+                .strip_suffix(')')
+                .ok_or("Missing ')' in IN list")?;
+            let values = list
+                .split(',')
+                .map(|v| v.trim().parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| "Invalid number in IN list")?;
+            return Ok(WhereClause::In { column, values });
         }
 
-        if let Some(or_idx) = where_part.find(" or ") {
-            let left = Self::parse_where_clause(&where_part[..or_idx])?;
-            let right = Self::parse_where_clause(&where_part[or_idx + 4..])?;
-            return Ok(WhereClause::Or(Box::new(left), Box::new(right)));
+        // String match: column like 'pattern'
+        if let Some(like_idx) = where_part.find(" like ") {
+            let column = where_part[..like_idx].trim().to_string();
+            let literal = where_part[like_idx + 6..].trim();
+            let pattern = literal
+                .strip_prefix('\'')
+                .and_then(|p| p.strip_suffix('\''))
+                .ok_or("LIKE pattern must be a quoted string")?
+                .to_string();
+            return Ok(WhereClause::Like { column, pattern });
         }
 
         // Simple comparison: column < value, column > value, column = value
@@ -235,25 +1300,418 @@ impl SQLParser {
             return Ok(WhereClause::LessThan { column, value });
         }
 
-        if let Some(gt_idx) = where_part.find(" > ") {
-            let column = where_part[..gt_idx].trim().to_string();
-            let value = where_part[gt_idx + 3..]
-                .trim()
-                .parse::<u64>()
-                .map_err(|_| "Invalid number in WHERE clause")?;
-            return Ok(WhereClause::GreaterThan { column, value });
+        if let Some(gt_idx) = where_part.find(" > ") {
+            let column = where_part[..gt_idx].trim().to_string();
+            let value = where_part[gt_idx + 3..]
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "Invalid number in WHERE clause")?;
+            return Ok(WhereClause::GreaterThan { column, value });
+        }
+
+        if let Some(eq_idx) = where_part.find(" = ") {
+            let column = where_part[..eq_idx].trim().to_string();
+            let value = where_part[eq_idx + 3..]
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| "Invalid number in WHERE clause")?;
+            return Ok(WhereClause::Equal { column, value });
+        }
+
+        Err("Unsupported WHERE clause format".to_string())
+    }
+
+    /// Parse `WITH name [(columns)] AS (query), ... SELECT ...`
+    /// Works on the original text so LIKE patterns keep their case
+    fn parse_with(original: &str) -> Result<SQLQuery, String> {
+        let mut ctes: Vec<CommonTableExpr> = Vec::new();
+        let mut rest = original[5..].trim_start();
+        loop {
+            let as_idx = rest
+                .to_ascii_lowercase()
+                .find(" as ")
+                .ok_or("Missing AS in WITH clause")?;
+            let head = rest[..as_idx].trim().to_lowercase();
+            let (name, columns) = match head.split_once('(') {
+                Some((name, columns)) => {
+                    let columns = columns
+                        .strip_suffix(')')
+                        .ok_or("Unclosed CTE column list")?
+                        .split(',')
+                        .map(|c| c.trim().to_string())
+                        .collect();
+                    (name.trim().to_string(), Some(columns))
+                }
+                None => (head, None),
+            };
+            if name.is_empty() || ctes.iter().any(|cte| cte.name == name) {
+                return Err(format!("Invalid or duplicate CTE name '{}'", name));
+            }
+
+            let body = rest[as_idx + 4..].trim_start();
+            let close = Self::closing_parenthesis(body).ok_or("CTE body must be parenthesized")?;
+            let query = Self::parse_query(&body[1..close])?;
+            ctes.push(CommonTableExpr {
+                name,
+                columns,
+                query,
+            });
+
+            rest = body[close + 1..].trim_start();
+            match rest.strip_prefix(',') {
+                Some(next) => rest = next.trim_start(),
+                None => break,
+            }
+        }
+
+        if Self::starts_with_keyword(rest, "with ") {
+            return Err("Nested WITH is not supported".to_string());
+        }
+        let mut query = Self::parse_query(rest)?;
+        query.ctes = Some(ctes);
+        Ok(query)
+    }
+
+    /// Split a (lowercased) FROM clause into the table, its alias and inner
+    /// JOINs: `t [AS] a [[INNER] JOIN u [AS] b ON a.x = b.y]...`
+    /// Each ON compares a column of the FROM table with a column of the
+    /// joined table; columns are told apart by qualifier, so a table can be
+    /// joined with itself under two aliases.
+    ///
+    /// None for FROM clauses the join compiler doesn't take (outer joins,
+    /// comma lists, conditions on two joined tables); they stay unprovable.
+    ///
+    /// # Errors
+    ///
+    /// A table joined with itself without aliases, a repeated alias, or an
+    /// unqualified column in a self-join condition
+    #[allow(clippy::type_complexity)]
+    fn parse_from(from: &str) -> Result<Option<(String, Option<String>, Vec<JoinClause>)>, String> {
+        if from.contains(',') {
+            return Ok(None);
+        }
+        // `t`, `t a` or `t as a`
+        let table_ref = |spec: &str| -> Option<(String, Option<String>)> {
+            match spec.split_whitespace().collect::<Vec<_>>()[..] {
+                [table] => Some((table.to_string(), None)),
+                [table, alias] | [table, "as", alias] => {
+                    Some((table.to_string(), Some(alias.to_string())))
+                }
+                _ => None,
+            }
+        };
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for idx in PredicateExpr::top_level_matches(from, " join ") {
+            segments.push(&from[start..idx]);
+            start = idx + 6;
+        }
+        segments.push(&from[start..]);
+
+        // Every segment but the last ends with the next JOIN's type
+        let mut specs = Vec::with_capacity(segments.len());
+        for (i, segment) in segments.iter().enumerate() {
+            let mut segment = segment.trim();
+            if i + 1 < segments.len() {
+                match segment.rsplit_once(' ') {
+                    Some((rest, "inner")) => segment = rest.trim_end(),
+                    Some((_, "left" | "right" | "full" | "outer" | "cross" | "natural")) => {
+                        return Ok(None)
+                    }
+                    _ => {}
+                }
+            }
+            specs.push(segment);
+        }
+
+        let Some((table, alias)) = table_ref(specs[0]) else {
+            return Ok(None);
+        };
+        let from_name = alias.clone().unwrap_or_else(|| table.clone());
+        let mut names = vec![from_name.clone()];
+        let mut joins = Vec::new();
+        for spec in &specs[1..] {
+            let Some((join_spec, condition)) = spec.split_once(" on ") else {
+                return Ok(None);
+            };
+            let Some((join_table, join_alias)) = table_ref(join_spec) else {
+                return Ok(None);
+            };
+            let join_name = join_alias.clone().unwrap_or_else(|| join_table.clone());
+            if names.contains(&join_name) {
+                return Err(if join_alias.is_none() && join_table == table {
+                    format!(
+                        "Table {} is joined with itself; give each side an alias \
+                         (FROM {} a JOIN {} b ON ...)",
+                        join_table, join_table, join_table
+                    )
+                } else {
+                    format!("Table name {} is used twice in FROM", join_name)
+                });
+            }
+            names.push(join_name.clone());
+
+            if condition.contains(['<', '>', '!']) || condition.contains(" and ") {
+                return Ok(None);
+            }
+            let Some((lhs, rhs)) = condition.split_once('=') else {
+                return Ok(None);
+            };
+            // Side of each operand: true = FROM table, false = joined table
+            let side = |operand: &str, position: bool| -> Result<Option<(bool, String)>, String> {
+                match operand.trim().split_once('.') {
+                    Some((q, column)) if q == from_name => Ok(Some((true, column.to_string()))),
+                    Some((q, column)) if q == join_name => Ok(Some((false, column.to_string()))),
+                    Some(_) => Ok(None),
+                    None if join_table == table => Err(format!(
+                        "Column {} is ambiguous in a self-join; qualify it with an alias",
+                        operand.trim()
+                    )),
+                    None => Ok(Some((position, operand.trim().to_string()))),
+                }
+            };
+            let (Some(lhs), Some(rhs)) = (side(lhs, true)?, side(rhs, false)?) else {
+                return Ok(None);
+            };
+            let (left_column, right_column) = match (lhs, rhs) {
+                ((true, left), (false, right)) | ((false, right), (true, left)) => (left, right),
+                _ => return Ok(None),
+            };
+            joins.push(JoinClause {
+                table: join_table,
+                alias: join_alias,
+                on: JoinCondition {
+                    left_column,
+                    right_column,
+                },
+                join_type: JoinType::Inner,
+            });
+        }
+        Ok(Some((table, alias, joins)))
+    }
+
+    /// Parse a (lowercased) FROM clause that calls a table-valued function
+    /// (None if it names a table)
+    fn parse_table_function(from: &str) -> Result<Option<TableFunction>, String> {
+        let Some(call) = from
+            .strip_prefix("generate_series")
+            .map(str::trim_start)
+            .filter(|call| call.starts_with('('))
+        else {
+            return Ok(None);
+        };
+        let close = Self::closing_parenthesis(call).ok_or("Unclosed generate_series arguments")?;
+        let args = call[1..close]
+            .split(',')
+            .map(|arg| arg.trim().parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| "generate_series arguments must be integer constants")?;
+        let (start, stop, step) = match args[..] {
+            [start, stop] => (start, stop, 1),
+            [start, stop, step] => (start, stop, step),
+            _ => return Err("generate_series takes 2 or 3 arguments".to_string()),
+        };
+        if step == 0 {
+            return Err("generate_series step must be positive".to_string());
+        }
+
+        let alias = call[close + 1..].trim();
+        let alias = alias.strip_prefix("as ").unwrap_or(alias).trim();
+        let (alias, column) = match alias.split_once('(') {
+            _ if alias.is_empty() => ("generate_series", "generate_series"),
+            Some((alias, column)) => (
+                alias.trim(),
+                column
+                    .strip_suffix(')')
+                    .ok_or("Unclosed generate_series column alias")?
+                    .trim(),
+            ),
+            None => (alias, alias),
+        };
+        let valid = |name: &str| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        if !valid(alias) || !valid(column) {
+            return Err(format!("Unsupported alias after generate_series: {}", alias));
+        }
+
+        Ok(Some(TableFunction {
+            kind: TableFunctionKind::GenerateSeries(SeriesOp { start, stop, step }),
+            alias: alias.to_string(),
+            column: column.to_string(),
+        }))
+    }
+
+    fn starts_with_keyword(s: &str, keyword: &str) -> bool {
+        s.get(..keyword.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Index of the `)` matching the `(` that starts `s`
+    fn closing_parenthesis(s: &str) -> Option<usize> {
+        if !s.starts_with('(') {
+            return None;
+        }
+        let mut depth = 0i32;
+        let mut quoted = false;
+        for (idx, c) in s.char_indices() {
+            match c {
+                '\'' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(idx);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parse `column op` followed by a parenthesized subquery
+    fn parse_subquery_clause(lhs: &str, subquery: &str) -> Result<WhereClause, String> {
+        let inner = PredicateExpr::strip_parentheses(subquery.trim())
+            .ok_or("Subquery must be enclosed in parentheses")?;
+        let lhs = lhs.trim();
+
+        if lhs == "exists" || lhs == "not exists" {
+            let (column, subquery) = Self::decorrelate_exists(inner)?;
+            return Ok(WhereClause::SemiJoin {
+                column,
+                subquery: Box::new(subquery),
+                join_type: if lhs == "exists" {
+                    JoinType::Semi
+                } else {
+                    JoinType::Anti
+                },
+            });
+        }
+
+        let subquery = Box::new(Self::parse_query(inner)?);
+        if let Some(column) = lhs.strip_suffix(" not in") {
+            return Ok(WhereClause::SemiJoin {
+                column: column.trim().to_string(),
+                subquery,
+                join_type: JoinType::Anti,
+            });
+        }
+        if let Some(column) = lhs.strip_suffix(" in") {
+            return Ok(WhereClause::InSubquery {
+                column: column.trim().to_string(),
+                subquery,
+            });
+        }
+        let (column, operator) = if let Some(column) = lhs.strip_suffix('<') {
+            (column, ComparisonOp::LessThan)
+        } else if let Some(column) = lhs.strip_suffix('>') {
+            (column, ComparisonOp::GreaterThan)
+        } else if let Some(column) = lhs.strip_suffix('=') {
+            (column, ComparisonOp::Equal)
+        } else {
+            return Err("Unsupported subquery predicate".to_string());
+        };
+        Ok(WhereClause::CompareSubquery {
+            column: column.trim().to_string(),
+            operator,
+            subquery,
+        })
+    }
+
+    /// Rewrite the body of `EXISTS (SELECT ... FROM t WHERE t.a = b AND ...)`
+    /// as the outer column `b` and the subquery `SELECT a FROM t WHERE ...`
+    /// The correlation is the conjunct equating a column qualified with the
+    /// inner table (or its alias) to an outer column.
+    fn decorrelate_exists(body: &str) -> Result<(String, SQLQuery), String> {
+        const UNSUPPORTED: &str =
+            "EXISTS subquery must be SELECT ... FROM t WHERE t.column = column [AND ...]";
+        let where_idx = *PredicateExpr::top_level_matches(body, " where ")
+            .first()
+            .ok_or(UNSUPPORTED)?;
+        let (head, mut conditions) = (&body[..where_idx], &body[where_idx + 7..]);
+        let from_idx = head.find(" from ").ok_or(UNSUPPORTED)?;
+        let mut from = head[from_idx + 6..].split_whitespace();
+        let table = from.next().ok_or(UNSUPPORTED)?;
+        let alias = from.next().unwrap_or(table);
+        if from.next().is_some() {
+            return Err(UNSUPPORTED.to_string());
+        }
+        let qualifier = format!("{}.", alias);
+
+        let mut conjuncts = Vec::new();
+        while let Some(and_idx) = PredicateExpr::find_logical_and(conditions) {
+            conjuncts.push(&conditions[..and_idx]);
+            conditions = &conditions[and_idx + 5..];
         }
+        conjuncts.push(conditions);
 
-        if let Some(eq_idx) = where_part.find(" = ") {
-            let column = where_part[..eq_idx].trim().to_string();
-            let value = where_part[eq_idx + 3..]
-                .trim()
-                .parse::<u64>()
-                .map_err(|_| "Invalid number in WHERE clause")?;
-            return Ok(WhereClause::Equal { column, value });
+        // (inner column, outer column) of a `t.a = b` conjunct
+        let correlation = |conjunct: &str| -> Option<(String, String)> {
+            if conjunct.contains(['<', '>', '!']) {
+                return None;
+            }
+            let (left, right) = conjunct.split_once('=')?;
+            let (inner, outer) = match left.trim().strip_prefix(&qualifier) {
+                Some(inner) => (inner, right.trim()),
+                None => (right.trim().strip_prefix(&qualifier)?, left.trim()),
+            };
+            // The outer side may be qualified with the outer table
+            let outer = outer.rsplit('.').next().unwrap_or(outer);
+            let column = |c: &str| !c.is_empty() && c.parse::<u64>().is_err() && !c.contains(' ');
+            (column(inner) && column(outer)).then(|| (inner.to_string(), outer.to_string()))
+        };
+        let idx = conjuncts
+            .iter()
+            .position(|c| correlation(c).is_some())
+            .ok_or(UNSUPPORTED)?;
+        let (inner_column, outer_column) = correlation(conjuncts.remove(idx)).ok_or(UNSUPPORTED)?;
+
+        let mut sql = format!("select {} from {}", inner_column, table);
+        if !conjuncts.is_empty() {
+            sql.push_str(" where ");
+            sql.push_str(&conjuncts.join(" and ").replace(&qualifier, ""));
         }
+        Ok((outer_column, Self::parse_query(&sql)?))
+    }
 
-        Err("Unsupported WHERE clause format".to_string())
+    /// Single-quoted literals of a query, in order of appearance
+    fn quoted_literals(sql: &str) -> Vec<String> {
+        sql.split('\'')
+            .skip(1)
+            .step_by(2)
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    /// Replace (lowercased) LIKE patterns with the original literals
+    /// The clause tree is walked left to right, i.e. in textual order
+    fn restore_like_patterns(
+        clause: &mut WhereClause,
+        literals: &mut impl Iterator<Item = String>,
+    ) {
+        match clause {
+            WhereClause::Like { pattern, .. } => {
+                if let Some(literal) = literals.next() {
+                    *pattern = literal;
+                }
+            }
+            WhereClause::And(left, right) | WhereClause::Or(left, right) => {
+                Self::restore_like_patterns(left, literals);
+                Self::restore_like_patterns(right, literals);
+            }
+            // Subquery literals appear at the subquery's position
+            WhereClause::CompareSubquery { subquery, .. }
+            | WhereClause::InSubquery { subquery, .. }
+            | WhereClause::SemiJoin { subquery, .. } => {
+                if let Some(predicate) = subquery.predicate.as_mut() {
+                    predicate.restore_like_patterns(literals);
+                    subquery.where_clause = predicate.to_where_clause();
+                }
+            }
+            _ => {}
+        }
     }
 
     /// Parse ORDER BY clause
@@ -263,14 +1721,14 @@ impl SQLParser {
 
         for part in order_part.split(',') {
             let part = part.trim();
-            if part.ends_with(" desc") {
-                let column = part[..part.len() - 5].trim().to_string();
+            if let Some(column) = part.strip_suffix(" desc") {
+                let column = column.trim().to_string();
                 orders.push(OrderBy {
                     column,
                     direction: OrderDirection::Desc,
                 });
-            } else if part.ends_with(" asc") {
-                let column = part[..part.len() - 4].trim().to_string();
+            } else if let Some(column) = part.strip_suffix(" asc") {
+                let column = column.trim().to_string();
                 orders.push(OrderBy {
                     column,
                     direction: OrderDirection::Asc,
@@ -313,10 +1771,83 @@ impl SQLParser {
                 function: AggregationFunction::Min,
                 column,
             })
+        } else if col.starts_with("median(") && col.ends_with(")") {
+            let column = col[7..col.len() - 1].trim().to_string();
+            Some(AggregationClause {
+                function: AggregationFunction::Median,
+                column,
+            })
+        } else if col.starts_with("percentile_disc(") && col.ends_with(")") {
+            // percentile_disc(0.9) within group (order by column)
+            let close = col.find(')')?;
+            let fraction: f64 = col[16..close].trim().parse().ok()?;
+            let percent = (fraction * 100.0).round();
+            if !(0.0..=100.0).contains(&percent) || (fraction * 100.0 - percent).abs() > 1e-9 {
+                return None;
+            }
+            let column = col[close + 1..]
+                .trim()
+                .strip_prefix("within group")?
+                .trim()
+                .strip_prefix("(order by ")?
+                .strip_suffix(')')?
+                .trim()
+                .to_string();
+            Some(AggregationClause {
+                function: AggregationFunction::Percentile(percent as u8),
+                column,
+            })
         } else {
             None
         }
     }
+
+    /// Parse window function (None if the column has no OVER clause)
+    fn parse_window(col: &str) -> Option<Result<WindowClause, String>> {
+        let over_idx = col.find(" over ")?;
+        Some(Self::parse_window_clause(&col[..over_idx], &col[over_idx + 6..]))
+    }
+
+    fn parse_window_clause(function: &str, over: &str) -> Result<WindowClause, String> {
+        let function = function.trim().replace(' ', "");
+        let (function, column) = match function.as_str() {
+            "row_number()" => (WindowFunction::RowNumber, None),
+            "rank()" => (WindowFunction::Rank, None),
+            f if f.starts_with("sum(") && f.ends_with(')') && f.len() > 5 => {
+                (WindowFunction::Sum, Some(f[4..f.len() - 1].to_string()))
+            }
+            other => return Err(format!("Unsupported window function: {}", other)),
+        };
+
+        let spec = over
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or("Missing parentheses in OVER clause")?
+            .trim();
+        let order_idx = spec.find("order by ").ok_or("Missing ORDER BY in OVER clause")?;
+        let order_by = spec[order_idx + 9..].trim().to_string();
+        let partition_part = spec[..order_idx].trim();
+        let partition_by = if partition_part.is_empty() {
+            None
+        } else {
+            let column = partition_part
+                .strip_prefix("partition by ")
+                .ok_or("Invalid PARTITION BY in OVER clause")?
+                .trim();
+            Some(column.to_string())
+        };
+        if order_by.is_empty() || order_by.contains(' ') {
+            return Err("Invalid ORDER BY in OVER clause".to_string());
+        }
+
+        Ok(WindowClause {
+            function,
+            column,
+            partition_by,
+            order_by,
+        })
+    }
 }
 
 /// SQL Compiler
@@ -324,6 +1855,35 @@ impl SQLParser {
 pub struct SQLCompiler;
 
 impl SQLCompiler {
+    /// Compile a query against a catalog
+    /// Tables and columns are resolved by name through `Database`, so a
+    /// reference to an unknown table fails before any operation is compiled.
+    pub fn compile_in(query: &SQLQuery, database: &Database) -> Result<CompiledQuery, String> {
+        let ctes: Vec<&str> = query
+            .ctes
+            .iter()
+            .flatten()
+            .map(|cte| cte.name.as_str())
+            .collect();
+        for table in query.referenced_tables() {
+            if !ctes.contains(&table) && database.schema(table).is_none() {
+                return Err(format!("Table {} not found", table));
+            }
+        }
+        // Column types of the FROM table decide the scale CASTs convert from
+        let types = database
+            .schema(&query.from)
+            .map(|schema| {
+                schema
+                    .columns
+                    .iter()
+                    .map(|c| (c.name.clone(), c.ty))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::compile_typed(query, &database.table_data(), &types, None)
+    }
+
     /// Compile SQL query to circuit
     /// Paper Section 3: Compiling SQL queries to ZKP circuit
     ///
@@ -335,20 +1895,112 @@ impl SQLCompiler {
     /// # Returns
     ///
     /// Compiled query with circuit operations
+    ///
+    /// # Note
+    ///
+    /// `table_data` carries no types: CASTs treat every column as an
+    /// integer (use `compile_in` for typed columns)
     pub fn compile(
         query: &SQLQuery,
         table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
     ) -> Result<CompiledQuery, String> {
+        Self::compile_typed(query, table_data, &HashMap::new(), None)
+    }
+
+    /// `compile` in the operator order chosen by the planner
+    /// Joins are compiled in `plan.join_order`, each over the FROM rows the
+    /// plan feeds it, and sorts over `plan.sort_input`; filters and the other
+    /// operators are compiled as in `compile`
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let plan = Planner::new(&table_data).plan(&query)?;
+    /// let compiled = SQLCompiler::compile_planned(&query, &table_data, &plan)?;
+    /// let explained = QueryPlan::planned(&query, &compiled, &plan);
+    /// ```
+    pub fn compile_planned(
+        query: &SQLQuery,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+        plan: &ExecutionPlan,
+    ) -> Result<CompiledQuery, String> {
+        Self::compile_typed(query, table_data, &HashMap::new(), Some(plan))
+    }
+
+    /// `compile` with the types of the FROM table's columns (for CASTs;
+    /// missing columns are integers) and an optional operator order
+    fn compile_typed(
+        query: &SQLQuery,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+        types: &HashMap<String, ColumnType>,
+        plan: Option<&ExecutionPlan>,
+    ) -> Result<CompiledQuery, String> {
+        if let Some(ctes) = &query.ctes {
+            return Self::compile_with(query, ctes, table_data);
+        }
+        if let Some(union) = &query.union {
+            return Self::compile_union(query, union, table_data);
+        }
+        if let Some(function) = &query.table_function {
+            return Self::compile_table_function(query, function, table_data);
+        }
+
         let mut compiled = CompiledQuery {
             range_checks: Vec::new(),
             sorts: Vec::new(),
             group_bys: Vec::new(),
             joins: Vec::new(),
             aggregations: Vec::new(),
+            likes: Vec::new(),
+            in_lists: Vec::new(),
+            betweens: Vec::new(),
+            predicate: None,
+            arithmetic: Vec::new(),
+            windows: Vec::new(),
+            subqueries: Vec::new(),
+            semi_joins: Vec::new(),
+            ctes: Vec::new(),
+            unions: Vec::new(),
+            series: Vec::new(),
+            casts: Vec::new(),
+            scanned_cells: table_data
+                .get(&query.from)
+                .map_or(0, |t| t.values().map(Vec::len).sum()),
+            result_columns: query.columns.clone(),
         };
 
+        // Computed projections (e.g. SELECT price * qty - discount)
+        for column in query.columns.iter().filter(|c| !c.contains(" over ")) {
+            if let Ok(expr) = ArithExpr::parse(column) {
+                if expr.is_computed() {
+                    Self::compute_column(expr, table_data, &query.from, &mut compiled)?;
+                }
+            }
+        }
+
+        // CAST projections, one operation per row
+        for cast in query.casts.iter().flatten() {
+            let source = types
+                .get(&cast.column)
+                .copied()
+                .unwrap_or(ColumnType::Integer);
+            let op = cast.plan(source)?;
+            let values = Self::where_column(&cast.column, table_data, &query.from, &mut compiled)?;
+            cast.apply(source, &values)?;
+            compiled.casts.extend(values.into_iter().map(|value| CastOp {
+                value: Value::known(value),
+                ..op.clone()
+            }));
+        }
+
         // Convert WHERE clause to range check operations
-        if let Some(where_clause) = &query.where_clause {
+        // (with a predicate tree, each comparison is compiled once, in textual order)
+        if let Some(predicate) = &query.predicate {
+            for leaf in predicate.leaves() {
+                Self::compile_where_clause(leaf, table_data, &query.from, &mut compiled)?;
+            }
+            compiled.predicate = Some(predicate.to_tree());
+        } else if let Some(where_clause) = &query.where_clause {
             Self::compile_where_clause(where_clause, table_data, &query.from, &mut compiled)?;
         }
 
@@ -361,6 +2013,10 @@ impl SQLCompiler {
                     .ok_or_else(|| {
                         format!("Column {} not found in table {}", order.column, query.from)
                     })?;
+                let column_data = match plan.and_then(|p| p.sort_input.as_ref()) {
+                    Some(rows) => select_rows(column_data, rows),
+                    None => column_data.clone(),
+                };
 
                 let mut sorted = column_data.clone();
                 match order.direction {
@@ -378,68 +2034,138 @@ impl SQLCompiler {
             }
         }
 
-        // Convert GROUP BY clause to group_by operations
-        if let Some(group_by_cols) = &query.group_by {
-            for col in group_by_cols {
-                let column_data = table_data
-                    .get(&query.from)
-                    .and_then(|t| t.get(col))
-                    .ok_or_else(|| format!("Column {} not found in table {}", col, query.from))?;
-
-                // Extract group keys (unique values)
-                let mut group_keys: Vec<u64> = column_data.iter().copied().collect();
-                group_keys.sort();
-                group_keys.dedup();
-
-                compiled.group_bys.push(GroupByOp { group_keys });
+        // Convert GROUP BY clause to a group_by operation over the key tuples
+        let group_rows = Self::group_key_rows(query, table_data)?;
+        if let Some(rows) = &group_rows {
+            // Extract group keys (unique tuples, lexicographic order)
+            let mut group_keys = rows.clone();
+            group_keys.sort();
+            group_keys.dedup();
+            // Wider keys don't pack into a field element: their groups are
+            // checked by rank (the query is flagged MultiColumnGroupBy)
+            if group_keys.first().is_some_and(|key| key.len() > MAX_GROUP_KEY_COLUMNS) {
+                group_keys = (0..group_keys.len() as u64).map(|rank| vec![rank]).collect();
             }
+
+            compiled.group_bys.push(GroupByOp { group_keys });
         }
 
         // Compile aggregation operations
         if let Some(aggregations) = &query.aggregations {
             for agg in aggregations {
-                let column_data = table_data
+                let table = table_data
                     .get(&query.from)
-                    .and_then(|t| t.get(&agg.column))
-                    .ok_or_else(|| {
-                        format!("Column {} not found in table {}", agg.column, query.from)
-                    })?;
-
-                // Get group keys (if GROUP BY exists)
-                let group_keys = if let Some(group_by_cols) = &query.group_by {
-                    // Use first group by column
-                    if let Some(first_col) = group_by_cols.first() {
-                        table_data
-                            .get(&query.from)
-                            .and_then(|t| t.get(first_col))
-                            .map(|v| v.clone())
-                            .unwrap_or_default()
-                    } else {
-                        Vec::new()
-                    }
+                    .ok_or_else(|| format!("Table {} not found", query.from))?;
+                // COUNT(*) counts rows, whatever their values
+                let count_rows;
+                let column_data = if agg.column == "*" {
+                    count_rows = vec![1; table.values().next().map_or(0, Vec::len)];
+                    &count_rows
                 } else {
-                    Vec::new()
+                    table.get(&agg.column).ok_or_else(|| {
+                        format!("Column {} not found in table {}", agg.column, query.from)
+                    })?
                 };
 
+                // Get group keys (a single group without GROUP BY)
+                let group_keys = group_rows
+                    .as_deref()
+                    .map(Self::group_key_ranks)
+                    .unwrap_or_else(|| vec![0; column_data.len()]);
+
                 let agg_type = match agg.function {
-                    AggregationFunction::Sum => "sum",
-                    AggregationFunction::Count => "count",
-                    AggregationFunction::Max => "max",
-                    AggregationFunction::Min => "min",
-                    AggregationFunction::Avg => "sum", // Use SUM for AVG, then divide by COUNT
+                    AggregationFunction::Sum => AggregationType::Sum,
+                    AggregationFunction::Count => AggregationType::Count,
+                    AggregationFunction::Max => AggregationType::Max,
+                    AggregationFunction::Min => AggregationType::Min,
+                    AggregationFunction::Avg => AggregationType::Sum, // Use SUM for AVG, then divide by COUNT
+                    AggregationFunction::Median => AggregationType::Median,
+                    AggregationFunction::Percentile(p) => AggregationType::Percentile(p),
+                };
+
+                // Rank aggregations sort each group: rows ordered by group key
+                // (a single group without GROUP BY)
+                let (group_keys, values) = if agg_type.is_rank() {
+                    let mut rows: Vec<(u64, u64)> =
+                        group_keys.into_iter().zip(column_data.iter().copied()).collect();
+                    rows.sort();
+                    rows.into_iter().unzip()
+                } else {
+                    (group_keys, column_data.clone())
                 };
 
+                // Values in table order are bound to the scanned column
+                let column = (!agg_type.is_rank() && agg.column != "*").then(|| agg.column.clone());
                 compiled.aggregations.push(AggregationOp {
                     group_keys,
-                    values: column_data.clone(),
-                    agg_type: agg_type.to_string(),
+                    values,
+                    agg_type,
+                    column,
                 });
             }
         }
 
+        // Compile window functions (rows ordered by partition, then order key)
+        for window in query.windows.iter().flatten() {
+            let table = table_data
+                .get(&query.from)
+                .ok_or_else(|| format!("Table {} not found", query.from))?;
+            let column = |name: &String| {
+                table
+                    .get(name)
+                    .ok_or_else(|| format!("Column {} not found in table {}", name, query.from))
+            };
+            let order_keys = column(&window.order_by)?;
+            let partition_keys = match &window.partition_by {
+                Some(name) => column(name)?.clone(),
+                None => vec![0; order_keys.len()],
+            };
+            let values = match &window.column {
+                Some(name) => column(name)?.clone(),
+                None => vec![0; order_keys.len()],
+            };
+
+            let mut rows: Vec<(u64, u64, u64)> = partition_keys
+                .into_iter()
+                .zip(order_keys.iter().copied())
+                .zip(values)
+                .map(|((p, o), v)| (p, o, v))
+                .collect();
+            rows.sort();
+            let partition_keys: Vec<u64> = rows.iter().map(|&(p, _, _)| p).collect();
+            let order_keys: Vec<u64> = rows.iter().map(|&(_, o, _)| o).collect();
+            let values: Vec<u64> = match window.function {
+                WindowFunction::Sum => rows.iter().map(|&(_, _, v)| v).collect(),
+                _ => Vec::new(),
+            };
+            if window.function == WindowFunction::Sum
+                && window
+                    .function
+                    .evaluate(&partition_keys, &order_keys, &values)
+                    .is_none()
+            {
+                return Err("Running SUM overflow".to_string());
+            }
+
+            compiled.windows.push(WindowOp {
+                function: window.function,
+                partition_keys,
+                order_keys,
+                values,
+            });
+        }
+
         // Compile JOIN operations
+        // (in plan order, each over the FROM rows the plan feeds it)
         if let Some(joins) = &query.joins {
-            for join in joins {
+            let order: Vec<usize> = match plan {
+                Some(plan) => plan.join_order.clone(),
+                None => (0..joins.len()).collect(),
+            };
+            for (pos, &i) in order.iter().enumerate() {
+                let join = joins.get(i).ok_or("Join order out of range")?;
+                // A self-join reads both sides from the same committed table:
+                // the key columns are two views over one commitment
                 let left_table = table_data
                     .get(&query.from)
                     .ok_or_else(|| format!("Table {} not found", query.from))?;
@@ -469,12 +2195,29 @@ impl SQLCompiler {
                 // Use first column for values (simple implementation)
                 let left_values = left_table.values().next().cloned().unwrap_or_default();
                 let right_values = right_table.values().next().cloned().unwrap_or_default();
+                let selection = plan.and_then(|p| p.join_inputs.get(pos));
+                let (left_keys, left_values) = match selection {
+                    Some(rows) => (
+                        select_rows(&left_keys, rows),
+                        select_rows(&left_values, rows),
+                    ),
+                    None => (left_keys, left_values),
+                };
+                // All keys of the FROM table, in table order, are its scanned column
+                let table1_column = selection.is_none().then(|| join.on.left_column.clone());
 
+                let kind = match join.join_type {
+                    JoinType::Semi => JoinKind::Semi,
+                    JoinType::Anti => JoinKind::Anti,
+                    _ => JoinKind::Inner,
+                };
                 compiled.joins.push(JoinOp {
                     table1_keys: left_keys,
                     table1_values: left_values,
                     table2_keys: right_keys,
                     table2_values: right_values,
+                    kind,
+                    table1_column,
                 });
             }
         }
@@ -491,45 +2234,77 @@ impl SQLCompiler {
     ) -> Result<(), String> {
         match where_clause {
             WhereClause::LessThan { column, value } => {
-                let column_data = table_data
-                    .get(table_name)
-                    .and_then(|t| t.get(column))
-                    .ok_or_else(|| {
-                        format!("Column {} not found in table {}", column, table_name)
-                    })?;
+                let column_data =
+                    &Self::where_column(column, table_data, table_name, compiled)?;
 
                 for &val in column_data {
                     // Range check: val < value
-                    // u value: value - val (if val < value)
-                    let u = if val < *value { value - val } else { 0 };
                     compiled.range_checks.push(RangeCheckOp {
                         value: Value::known(val),
                         threshold: *value,
-                        u,
+                        u: COMPARISON_WINDOW,
                     });
                 }
             }
             WhereClause::GreaterThan { column, value } => {
-                let column_data = table_data
-                    .get(table_name)
-                    .and_then(|t| t.get(column))
-                    .ok_or_else(|| {
-                        format!("Column {} not found in table {}", column, table_name)
-                    })?;
+                let column_data =
+                    &Self::where_column(column, table_data, table_name, compiled)?;
 
                 for &val in column_data {
                     // For range check: val > value, can check val < MAX_VALUE - value
                     // Simple implementation: val >= value + 1 check
                     let threshold = value + 1;
-                    let u = if val >= threshold { val - threshold } else { 0 };
                     compiled.range_checks.push(RangeCheckOp {
                         value: Value::known(val),
                         threshold,
-                        u,
+                        u: COMPARISON_WINDOW,
                     });
                 }
             }
             WhereClause::Equal { column, value } => {
+                let column_data =
+                    &Self::where_column(column, table_data, table_name, compiled)?;
+
+                for &val in column_data {
+                    // Equality check: val == value
+                    // Range check ile: val < value + 1 && val >= value
+                    compiled.range_checks.push(RangeCheckOp {
+                        value: Value::known(val),
+                        threshold: value + 1,
+                        u: COMPARISON_WINDOW,
+                    });
+                }
+            }
+            WhereClause::Between { column, low, high } => {
+                let column_data =
+                    &Self::where_column(column, table_data, table_name, compiled)?;
+
+                // SQL BETWEEN is inclusive, the gate bound is exclusive
+                let high = high
+                    .checked_add(1)
+                    .ok_or("BETWEEN upper bound must be below u64::MAX")?;
+                for &val in column_data {
+                    compiled.betweens.push(BetweenOp {
+                        value: Value::known(val),
+                        low: *low,
+                        high,
+                    });
+                }
+            }
+            WhereClause::In { column, values } => {
+                let column_data = table_data
+                    .get(table_name)
+                    .and_then(|t| t.get(column))
+                    .ok_or_else(|| {
+                        format!("Column {} not found in table {}", column, table_name)
+                    })?;
+
+                compiled.in_lists.push(InListOp {
+                    values: column_data.clone(),
+                    list: values.clone(),
+                });
+            }
+            WhereClause::Like { column, pattern } => {
                 let column_data = table_data
                     .get(table_name)
                     .and_then(|t| t.get(column))
@@ -537,20 +2312,111 @@ impl SQLCompiler {
                         format!("Column {} not found in table {}", column, table_name)
                     })?;
 
+                let pattern = LikePattern::parse(pattern)?;
                 for &val in column_data {
-                    // Equality check: val == value
-                    // Range check ile: val < value + 1 && val >= value
-                    compiled.range_checks.push(RangeCheckOp {
-                        value: Value::known(val),
-                        threshold: value + 1,
-                        u: if val < value + 1 {
-                            (value + 1) - val
-                        } else {
-                            0
-                        },
+                    compiled.likes.push(LikeOp {
+                        value: val,
+                        pattern: pattern.clone(),
                     });
                 }
             }
+            WhereClause::CompareSubquery {
+                column,
+                operator,
+                subquery,
+            } => {
+                // The circuit wires the inner aggregate over all rows of its table
+                if subquery.columns.len() != 1
+                    || subquery.aggregations.as_ref().map_or(0, |a| a.len()) != 1
+                    || subquery.group_by.is_some()
+                    || subquery.predicate.is_some()
+                {
+                    return Err(
+                        "Scalar subquery must be a single aggregate without WHERE or GROUP BY"
+                            .to_string(),
+                    );
+                }
+                let values = Self::where_column(column, table_data, table_name, compiled)?;
+                let inner = Self::compile(subquery, table_data)?;
+                let result = ReferenceExecutor::execute(subquery, table_data)?;
+                let result = match result.rows.as_slice() {
+                    [row] => row[0].ok_or("Scalar subquery returned NULL")?,
+                    _ => return Err("Scalar subquery must return one row".to_string()),
+                };
+                let filter = match operator {
+                    ComparisonOp::LessThan => SubqueryFilter::LessThan,
+                    ComparisonOp::GreaterThan => SubqueryFilter::GreaterThan,
+                    ComparisonOp::Equal => SubqueryFilter::Equal,
+                };
+
+                compiled.subqueries.push(CompiledSubquery {
+                    inner,
+                    filter: SubqueryOp {
+                        filter,
+                        values,
+                        result: vec![result],
+                    },
+                });
+            }
+            WhereClause::InSubquery { column, subquery } => {
+                if subquery.columns.len() != 1 || subquery.aggregations.is_some() {
+                    return Err("IN subquery must select a single column".to_string());
+                }
+                let values = Self::where_column(column, table_data, table_name, compiled)?;
+                let inner = Self::compile(subquery, table_data)?;
+                let result = ReferenceExecutor::execute(subquery, table_data)?;
+                let mut items: Vec<u64> = result.rows.iter().filter_map(|row| row[0]).collect();
+                items.sort();
+                items.dedup();
+                if items.is_empty() {
+                    return Err("IN subquery returned no rows".to_string());
+                }
+
+                compiled.subqueries.push(CompiledSubquery {
+                    inner,
+                    filter: SubqueryOp {
+                        filter: SubqueryFilter::In,
+                        values,
+                        result: items,
+                    },
+                });
+            }
+            WhereClause::SemiJoin {
+                column,
+                subquery,
+                join_type,
+            } => {
+                if subquery.columns.len() != 1 || subquery.aggregations.is_some() {
+                    return Err("Semi join subquery must select a single column".to_string());
+                }
+                let kind = match join_type {
+                    JoinType::Semi => JoinKind::Semi,
+                    JoinType::Anti => JoinKind::Anti,
+                    _ => return Err("Subquery join must be a semi or anti join".to_string()),
+                };
+                let values = Self::where_column(column, table_data, table_name, compiled)?;
+                // A stored (not computed) column is scanned
+                let table1_column = table_data
+                    .get(table_name)
+                    .filter(|t| t.contains_key(column))
+                    .map(|_| column.clone());
+                let inner = Self::compile(subquery, table_data)?;
+                // NULL never matches
+                let result = ReferenceExecutor::execute(subquery, table_data)?;
+                let keys: Vec<u64> = result.rows.iter().filter_map(|row| row[0]).collect();
+
+                compiled.semi_joins.push(CompiledSemiJoin {
+                    inner,
+                    op: JoinOp {
+                        table1_keys: values.clone(),
+                        table1_values: values,
+                        table2_keys: keys.clone(),
+                        table2_values: keys,
+                        kind,
+                        table1_column,
+                    },
+                });
+            }
             WhereClause::And(left, right) => {
                 Self::compile_where_clause(left, table_data, table_name, compiled)?;
                 Self::compile_where_clause(right, table_data, table_name, compiled)?;
@@ -567,6 +2433,254 @@ impl SQLCompiler {
     }
 }
 
+impl SQLCompiler {
+    /// Compile a query with CTEs
+    /// Each referenced CTE is compiled exactly once and materialized as a
+    /// committed relation; downstream operators (the main query, later CTEs,
+    /// subqueries) read the relation instead of re-compiling the CTE body, so
+    /// a CTE referenced N times contributes its constraints once
+    fn compile_with(
+        query: &SQLQuery,
+        ctes: &[CommonTableExpr],
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<CompiledQuery, String> {
+        let main = SQLQuery {
+            ctes: None,
+            ..query.clone()
+        };
+
+        // References from the main query and from later CTEs that are used
+        let mut references = vec![0; ctes.len()];
+        for idx in (0..ctes.len()).rev() {
+            let count = |q: &SQLQuery| {
+                q.referenced_tables()
+                    .into_iter()
+                    .filter(|&table| table == ctes[idx].name)
+                    .count()
+            };
+            references[idx] = count(&main)
+                + ctes[idx + 1..]
+                    .iter()
+                    .zip(&references[idx + 1..])
+                    .filter(|(_, &used)| used > 0)
+                    .map(|(cte, _)| count(&cte.query))
+                    .sum::<usize>();
+        }
+
+        let mut tables = table_data.clone();
+        let mut compiled_ctes = Vec::new();
+        for (idx, cte) in ctes.iter().enumerate() {
+            if tables.contains_key(&cte.name) {
+                return Err(format!("CTE {} shadows an existing table", cte.name));
+            }
+            let references = references[idx];
+            if references == 0 {
+                // Unused CTEs add no constraints
+                continue;
+            }
+
+            let inner = Self::compile(&cte.query, &tables)?;
+            let columns = cte.columns.clone().unwrap_or_else(|| cte.query.columns.clone());
+            let relation = ReferenceExecutor::relation(&cte.query, &columns, &tables)?;
+            let commitment = Self::commit_relation(&cte.name, &columns, &relation);
+
+            tables.insert(cte.name.clone(), relation);
+            compiled_ctes.push(CompiledCte {
+                name: cte.name.clone(),
+                columns,
+                inner,
+                commitment,
+                references,
+            });
+        }
+
+        let mut compiled = Self::compile(&main, &tables)?;
+        compiled.ctes = compiled_ctes;
+        Ok(compiled)
+    }
+
+    /// Compile a query over a table-valued function
+    /// The function's relation is generated by its own gate (`SeriesChip` for
+    /// generate_series); the query then reads it like a table
+    fn compile_table_function(
+        query: &SQLQuery,
+        function: &TableFunction,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<CompiledQuery, String> {
+        let tables = function.with_relation(table_data)?;
+        let mut compiled = Self::compile(
+            &SQLQuery {
+                table_function: None,
+                ..query.clone()
+            },
+            &tables,
+        )?;
+        match &function.kind {
+            TableFunctionKind::GenerateSeries(op) => compiled.series.push(op.clone()),
+        }
+        Ok(compiled)
+    }
+
+    /// Compile `left UNION [ALL] right`: both sides compile as usual, their
+    /// results feed the Union Gate
+    fn compile_union(
+        query: &SQLQuery,
+        union: &UnionClause,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<CompiledQuery, String> {
+        let left = SQLQuery {
+            union: None,
+            ..query.clone()
+        };
+        let relation = |q: &SQLQuery| -> Result<Vec<u64>, String> {
+            if q.columns.len() != 1 {
+                return Err("UNION inputs must select a single column".to_string());
+            }
+            ReferenceExecutor::execute(q, table_data)?
+                .rows
+                .iter()
+                .map(|row| row[0].ok_or_else(|| "NULL in UNION input".to_string()))
+                .collect()
+        };
+
+        let mut compiled = Self::compile(&left, table_data)?;
+        let op = UnionOp {
+            operation: if union.all {
+                SetOperation::UnionAll
+            } else {
+                SetOperation::Union
+            },
+            left: relation(&left)?,
+            right: relation(&union.query)?,
+        };
+        compiled.unions.push(CompiledUnion {
+            inner: Self::compile(&union.query, table_data)?,
+            op,
+        });
+        Ok(compiled)
+    }
+
+    /// Commitment to an intermediate relation (same scheme as base tables)
+    fn commit_relation(
+        name: &str,
+        columns: &[String],
+        relation: &HashMap<String, Vec<u64>>,
+    ) -> Fr {
+        let mut table = DatabaseTable::new(name.to_string(), columns.to_vec());
+        let num_rows = relation.values().map(|v| v.len()).min().unwrap_or(0);
+        let values: Vec<&Vec<u64>> = columns.iter().map(|c| &relation[c]).collect();
+        for i in 0..num_rows {
+            table.insert(values.iter().map(|v| v[i]).collect());
+        }
+        table.commit().commitment()
+    }
+
+    /// GROUP BY key tuple of every row, one value per key column (None
+    /// without GROUP BY)
+    fn group_key_rows(
+        query: &SQLQuery,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+    ) -> Result<Option<Vec<Vec<u64>>>, String> {
+        let Some(group_by_cols) = query.group_by.as_ref().filter(|cols| !cols.is_empty()) else {
+            return Ok(None);
+        };
+        let columns = group_by_cols
+            .iter()
+            .map(|col| {
+                table_data
+                    .get(&query.from)
+                    .and_then(|t| t.get(col))
+                    .ok_or_else(|| format!("Column {} not found in table {}", col, query.from))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = columns.iter().map(|c| c.len()).min().unwrap_or(0);
+        Ok(Some(
+            (0..rows)
+                .map(|i| columns.iter().map(|c| c[i]).collect())
+                .collect(),
+        ))
+    }
+
+    /// Aggregation group key of every row
+    /// Single-column keys are the column values; composite keys are the
+    /// dense rank of the tuple, which keeps equality and lexicographic order
+    fn group_key_ranks(rows: &[Vec<u64>]) -> Vec<u64> {
+        if rows.iter().all(|row| row.len() == 1) {
+            return rows.iter().map(|row| row[0]).collect();
+        }
+        let mut distinct = rows.to_vec();
+        distinct.sort();
+        distinct.dedup();
+        rows.iter()
+            .map(|row| distinct.partition_point(|key| key < row) as u64)
+            .collect()
+    }
+
+    /// Values of a WHERE operand: a column, or a computed expression over
+    /// columns (then also compiled to an arithmetic operation)
+    fn where_column(
+        column: &str,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+        table_name: &str,
+        compiled: &mut CompiledQuery,
+    ) -> Result<Vec<u64>, String> {
+        if let Some(values) = table_data.get(table_name).and_then(|t| t.get(column)) {
+            return Ok(values.clone());
+        }
+        match ArithExpr::parse(column) {
+            Ok(expr) if expr.is_computed() => {
+                Self::compute_column(expr, table_data, table_name, compiled)
+            }
+            _ => Err(format!(
+                "Column {} not found in table {}",
+                column, table_name
+            )),
+        }
+    }
+
+    /// Evaluate a computed column per row and record the arithmetic operation
+    fn compute_column(
+        expr: ArithExpr,
+        table_data: &HashMap<String, HashMap<String, Vec<u64>>>,
+        table_name: &str,
+        compiled: &mut CompiledQuery,
+    ) -> Result<Vec<u64>, String> {
+        let table = table_data
+            .get(table_name)
+            .ok_or_else(|| format!("Table {} not found", table_name))?;
+        let inputs = expr
+            .columns()
+            .into_iter()
+            .map(|name| {
+                table
+                    .get(name)
+                    .map(|values| (name.to_string(), values.clone()))
+                    .ok_or_else(|| format!("Column {} not found in table {}", name, table_name))
+            })
+            .collect::<Result<HashMap<_, _>, String>>()?;
+
+        let num_rows = inputs.values().map(|v| v.len()).min().unwrap_or(0);
+        let values = (0..num_rows)
+            .map(|i| {
+                let row = inputs
+                    .iter()
+                    .map(|(name, values)| (name.clone(), values[i]))
+                    .collect();
+                expr.evaluate(&row)
+                    .ok_or_else(|| format!("Arithmetic overflow in row {}", i))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        compiled.arithmetic.push(ArithmeticOp { expr, inputs });
+        Ok(values)
+    }
+}
+
+/// Values of `column` at `rows`
+fn select_rows(column: &[u64], rows: &[usize]) -> Vec<u64> {
+    rows.iter().filter_map(|&r| column.get(r).copied()).collect()
+}
+
 /// Compiled SQL Query
 /// SQL query compiled to circuit
 #[derive(Clone, Debug)]
@@ -581,4 +2695,81 @@ pub struct CompiledQuery {
     pub joins: Vec<JoinOp>,
     /// Aggregation operations
     pub aggregations: Vec<AggregationOp>,
+    /// LIKE operations (one per row)
+    pub likes: Vec<LikeOp>,
+    /// IN-list operations (one per clause)
+    pub in_lists: Vec<InListOp>,
+    /// BETWEEN operations (one per row, exclusive upper bound)
+    pub betweens: Vec<BetweenOp>,
+    /// How the per-row comparison results combine (see `BooleanChip::evaluate`)
+    pub predicate: Option<PredicateTree>,
+    /// Computed columns (projections and WHERE operands)
+    pub arithmetic: Vec<ArithmeticOp>,
+    /// Window functions (see `WindowChip::rank_over` and `WindowChip::running_sum`)
+    pub windows: Vec<WindowOp>,
+    /// Subqueries, each compiled to its own segment (see `SubqueryChip`)
+    pub subqueries: Vec<CompiledSubquery>,
+    /// EXISTS / NOT EXISTS / NOT IN subqueries, proven as semi / anti joins
+    /// (see `JoinChip::semi_join_and_verify`)
+    pub semi_joins: Vec<CompiledSemiJoin>,
+    /// CTEs, compiled once each and in declaration order
+    pub ctes: Vec<CompiledCte>,
+    /// UNION / UNION ALL with the results of other queries (see `UnionChip`)
+    pub unions: Vec<CompiledUnion>,
+    /// Generated relations of table-valued functions (see `SeriesChip`)
+    pub series: Vec<SeriesOp>,
+    /// CAST projections, one per row (see `CastChip`)
+    pub casts: Vec<CastOp>,
+    /// Cells of the FROM table (rows × columns), hashed into the database
+    /// commitment in-circuit (see `TableScan`)
+    pub scanned_cells: usize,
+    /// Columns of the query result (see `PoneglyphCircuit::result_columns`)
+    pub result_columns: Vec<String>,
+}
+
+/// Right side of a UNION, compiled to its own segment
+#[derive(Clone, Debug)]
+pub struct CompiledUnion {
+    /// Operations of the right query
+    pub inner: CompiledQuery,
+    /// Union of the left (this query) and right results
+    pub op: UnionOp,
+}
+
+/// CTE compiled to a committed intermediate relation
+/// The operations of the main query that read `name` run over the relation
+/// rows, which are bound to `inner` through `commitment`
+#[derive(Clone, Debug)]
+pub struct CompiledCte {
+    pub name: String,
+    pub columns: Vec<String>,
+    /// Operations of the CTE body
+    pub inner: CompiledQuery,
+    /// Commitment to the relation rows (see `DatabaseTable::commit`)
+    pub commitment: Fr,
+    /// Number of references (the body is compiled once regardless)
+    pub references: usize,
+}
+
+/// Semi / anti join with a subquery relation
+/// The join keys of table 2 are the inner result rows; the inner query is
+/// compiled to its own segment
+#[derive(Clone, Debug)]
+pub struct CompiledSemiJoin {
+    /// Operations of the inner query
+    pub inner: CompiledQuery,
+    /// Outer column (table 1) against the inner result (table 2)
+    pub op: JoinOp,
+}
+
+/// Subquery compiled to its own segment
+/// The inner result is a constrained input of the outer filter: for scalar
+/// subqueries the result cell of `inner.aggregations[0]` is copied into the
+/// comparisons (`SubqueryChip::scalar_result`, `SubqueryChip::filter`)
+#[derive(Clone, Debug)]
+pub struct CompiledSubquery {
+    /// Operations of the inner query
+    pub inner: CompiledQuery,
+    /// Outer filter fed by the inner result
+    pub filter: SubqueryOp,
 }
@@ -0,0 +1,168 @@
+// Registry of authorized queries
+// The set of queries a prover may run without naming them (see
+// `circuit::PrivateQueryCircuit`). Each entry authorizes one query for one
+// circuit: the leaf is `registry_leaf(query_hash(sql), fingerprint)`, where
+// the fingerprint is `vk_fingerprint` of the private-query verifying key.
+// The circuit can't check that `sql` compiles to that key: whoever builds the
+// registry vouches for each pairing.
+// Verifiers only need the root and the keys of the authorized circuits.
+
+use halo2_proofs::circuit::Value;
+use pasta_curves::pallas::Base as Fr;
+
+use super::query_hash;
+use crate::circuit::{registry_leaf, PoneglyphCircuit, PrivateQueryCircuit};
+use crate::database::MerkleTree;
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+/// Default registry depth (up to 1024 authorized queries)
+pub const REGISTRY_DEPTH: usize = 10;
+
+/// Merkle tree of authorized (query, circuit) pairs
+#[derive(Clone, Debug)]
+pub struct QueryRegistry {
+    tree: MerkleTree,
+    /// Leaves in slot order
+    leaves: Vec<Fr>,
+}
+
+impl Default for QueryRegistry {
+    fn default() -> Self {
+        Self::new(REGISTRY_DEPTH)
+    }
+}
+
+impl QueryRegistry {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            tree: MerkleTree::new(depth),
+            leaves: Vec::new(),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.tree.depth()
+    }
+
+    pub fn root(&self) -> Fr {
+        self.tree.root()
+    }
+
+    /// Number of authorized entries
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Authorize `sql` for the circuit with `circuit_fingerprint`
+    /// Returns the slot of the entry; authorizing it again returns the same
+    /// slot and leaves the root unchanged.
+    pub fn authorize(&mut self, sql: &str, circuit_fingerprint: u64) -> PoneglyphResult<u64> {
+        if let Some(index) = self.index_of(sql, circuit_fingerprint) {
+            return Ok(index);
+        }
+        let leaf = registry_leaf(query_hash(sql), circuit_fingerprint);
+        let index = self.leaves.len() as u64;
+        self.tree.set(index, leaf)?;
+        self.leaves.push(leaf);
+        Ok(index)
+    }
+
+    /// Slot of an authorized entry
+    pub fn index_of(&self, sql: &str, circuit_fingerprint: u64) -> Option<u64> {
+        let leaf = registry_leaf(query_hash(sql), circuit_fingerprint);
+        self.leaves
+            .iter()
+            .position(|&l| l == leaf)
+            .map(|i| i as u64)
+    }
+
+    /// Siblings of a slot from the leaf level up
+    pub fn path(&self, index: u64) -> Vec<Fr> {
+        self.tree.path(index)
+    }
+
+    /// Private-query circuit proving `query` as the authorized `sql`
+    /// `query.query_hash` is set to `query_hash(sql)`; the caller picks a
+    /// fresh random `salt` per proof.
+    ///
+    /// # Errors
+    ///
+    /// `Validation` if `sql` is not authorized for `circuit_fingerprint`
+    pub fn private_circuit(
+        &self,
+        sql: &str,
+        mut query: PoneglyphCircuit,
+        salt: Fr,
+        circuit_fingerprint: u64,
+    ) -> PoneglyphResult<PrivateQueryCircuit> {
+        let index = self.index_of(sql, circuit_fingerprint).ok_or_else(|| {
+            PoneglyphError::Validation(format!(
+                "query is not authorized for circuit {:016x}",
+                circuit_fingerprint
+            ))
+        })?;
+        query.query_hash = query_hash(sql);
+        Ok(PrivateQueryCircuit {
+            query,
+            salt: Value::known(salt),
+            circuit_fingerprint: Value::known(Fr::from(circuit_fingerprint)),
+            registry_index: Value::known(index),
+            registry_path: self.path(index).into_iter().map(Value::known).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::merkle_root_from_path;
+
+    #[test]
+    fn test_authorize() {
+        let mut registry = QueryRegistry::new(3);
+        let empty = registry.root();
+        let sum = registry
+            .authorize("SELECT SUM(price) FROM orders", 7)
+            .unwrap();
+        let count = registry
+            .authorize("SELECT COUNT(*) FROM orders", 7)
+            .unwrap();
+        assert_eq!((sum, count), (0, 1));
+        assert_ne!(registry.root(), empty);
+
+        // Same query, any spelling the parser reads the same way
+        let root = registry.root();
+        assert_eq!(
+            registry
+                .authorize("select sum(price)  from orders", 7)
+                .unwrap(),
+            0
+        );
+        assert_eq!(registry.root(), root);
+        assert_eq!(registry.len(), 2);
+
+        // Another circuit is another entry
+        assert_eq!(registry.index_of("SELECT SUM(price) FROM orders", 8), None);
+        assert_eq!(
+            merkle_root_from_path(
+                registry_leaf(query_hash("SELECT COUNT(*) FROM orders"), 7),
+                count,
+                &registry.path(count)
+            ),
+            registry.root()
+        );
+    }
+
+    #[test]
+    fn test_registry_is_bounded() {
+        let mut registry = QueryRegistry::new(1);
+        registry.authorize("SELECT SUM(a) FROM t", 1).unwrap();
+        registry.authorize("SELECT SUM(b) FROM t", 1).unwrap();
+        assert!(registry.authorize("SELECT SUM(c) FROM t", 1).is_err());
+        assert_eq!(registry.len(), 2);
+    }
+}
@@ -0,0 +1,332 @@
+// Session settings
+// `SET name = value` (or `SET name TO value`) adjusts how a session parses,
+// plans and proves the queries that follow it, the way database sessions do:
+//
+// | Setting         | Values                           | Default      |
+// |-----------------|----------------------------------|--------------|
+// | `parse_mode`    | `strict`, `permissive`           | `permissive` |
+// | `privacy_level` | `none`, `hide_row_counts`        | `none`       |
+// | `max_k`         | 1 ..= 32, or `none` for no limit | `none`       |
+// | `k_strategy`    | `grow`, `minimal`                | `grow`       |
+// | `lint_<name>`   | `allow`, `warn`, `deny`          | `warn`       |
+//
+// The lints are listed in `lint.rs`. `SET preset = name` sets the proving
+// settings together (see `prover::ProofPreset`); a session handles it before
+// these settings.
+//
+// `SET name = DEFAULT` restores the default. Names and keyword values are
+// case-insensitive and values may be quoted.
+
+use std::fmt;
+
+use super::{AggregationFunction, LintKind, LintLevel, LintLevels, ParseMode, SQLParser, SQLQuery};
+
+/// What a session's results may disclose
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PrivacyLevel {
+    /// No restriction
+    #[default]
+    None,
+    /// Refuse queries whose answer is the number of qualifying rows: COUNT
+    /// aggregates, and row-returning queries (the length of the result is
+    /// the count)
+    HideRowCounts,
+}
+
+impl PrivacyLevel {
+    /// Fail if `query` would disclose what this level hides
+    pub fn check(&self, query: &SQLQuery) -> Result<(), String> {
+        if *self == PrivacyLevel::None {
+            return Ok(());
+        }
+        let aggregations = query.aggregations.as_deref().unwrap_or_default();
+        if aggregations
+            .iter()
+            .any(|agg| matches!(agg.function, AggregationFunction::Count))
+        {
+            return Err("COUNT discloses row counts (privacy_level = hide_row_counts)".to_string());
+        }
+        if aggregations.is_empty() && query.group_by.is_none() {
+            return Err(
+                "a row-returning query discloses its row count (privacy_level = hide_row_counts)"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// How a session sizes its parameters for a query
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KStrategy {
+    /// Grow the parameters when a query needs more rows (only with `max_k`
+    /// set) and never shrink them, so cached keys stay usable
+    #[default]
+    Grow,
+    /// Prove every query at its planned size, up to `max_k`: the smallest
+    /// proof and the cheapest verification, at the cost of new parameters
+    /// and keys whenever the size changes
+    Minimal,
+}
+
+/// `SET name = value`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetStatement {
+    /// Lowercased setting name
+    pub name: String,
+    /// Unquoted value; None for `DEFAULT`
+    pub value: Option<String>,
+}
+
+impl SetStatement {
+    /// Parse a SET statement (None if `sql` is not one)
+    pub fn parse(sql: &str) -> Option<Result<Self, String>> {
+        let sql = sql.trim().trim_end_matches(';').trim();
+        let keyword = sql.get(..4)?;
+        if !keyword.eq_ignore_ascii_case("set ") {
+            return None;
+        }
+        let rest = sql[4..].trim();
+        let (name, value) = match rest.split_once('=') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => match rest.split_once(char::is_whitespace) {
+                Some((name, value)) => match value.trim_start().get(..3) {
+                    Some(to) if to.eq_ignore_ascii_case("to ") => {
+                        (name, value.trim_start()[3..].trim())
+                    }
+                    _ => return Some(Err(format!("Expected = or TO in SET {}", rest))),
+                },
+                None => return Some(Err(format!("Missing value in SET {}", rest))),
+            },
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Some(Err(format!("Invalid setting name: {}", name)));
+        }
+        if value.is_empty() {
+            return Some(Err(format!("Missing value for {}", name)));
+        }
+
+        let unquoted = value
+            .strip_prefix('\'')
+            .and_then(|v| v.strip_suffix('\''))
+            .unwrap_or(value);
+        let value = if unquoted == value && value.eq_ignore_ascii_case("default") {
+            None
+        } else {
+            Some(unquoted.to_string())
+        };
+        Some(Ok(Self {
+            name: name.to_lowercase(),
+            value,
+        }))
+    }
+}
+
+/// Statement accepted by a session
+#[derive(Clone, Debug)]
+pub enum SQLStatement {
+    Query(Box<SQLQuery>),
+    Set(SetStatement),
+}
+
+impl SQLParser {
+    /// Parse a query or a SET statement
+    /// Queries are parsed in `mode` (see `parse_with_mode`)
+    pub fn parse_statement(sql: &str, mode: ParseMode) -> Result<SQLStatement, String> {
+        match SetStatement::parse(sql) {
+            Some(set) => set.map(SQLStatement::Set),
+            None => Self::parse_with_mode(sql, mode).map(|query| SQLStatement::Query(Box::new(query))),
+        }
+    }
+}
+
+/// Settings of one session
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SessionSettings {
+    pub parse_mode: ParseMode,
+    pub privacy_level: PrivacyLevel,
+    /// Largest circuit size (2^max_k rows) the session may prove with;
+    /// None = the session parameters only
+    pub max_k: Option<u32>,
+    pub k_strategy: KStrategy,
+    pub lints: LintLevels,
+}
+
+impl SessionSettings {
+    /// Apply a SET statement
+    /// Fails on unknown settings and invalid values, leaving the settings
+    /// unchanged
+    pub fn apply(&mut self, set: &SetStatement) -> Result<(), String> {
+        let defaults = Self::default();
+        let value = set.value.as_deref().map(str::to_lowercase);
+        let invalid = |value: &str| format!("Invalid value for {}: {}", set.name, value);
+        match set.name.as_str() {
+            "parse_mode" => {
+                self.parse_mode = match value.as_deref() {
+                    None => defaults.parse_mode,
+                    Some("strict") => ParseMode::Strict,
+                    Some("permissive") => ParseMode::Permissive,
+                    Some(other) => return Err(invalid(other)),
+                }
+            }
+            "privacy_level" => {
+                self.privacy_level = match value.as_deref() {
+                    None => defaults.privacy_level,
+                    Some("none") => PrivacyLevel::None,
+                    Some("hide_row_counts") => PrivacyLevel::HideRowCounts,
+                    Some(other) => return Err(invalid(other)),
+                }
+            }
+            "max_k" => {
+                self.max_k = match value.as_deref() {
+                    None => defaults.max_k,
+                    Some("none") => None,
+                    Some(k) => match k.parse::<u32>() {
+                        Ok(k) if (1..=32).contains(&k) => Some(k),
+                        _ => return Err(invalid(k)),
+                    },
+                }
+            }
+            "k_strategy" => {
+                self.k_strategy = match value.as_deref() {
+                    None => defaults.k_strategy,
+                    Some("grow") => KStrategy::Grow,
+                    Some("minimal") => KStrategy::Minimal,
+                    Some(other) => return Err(invalid(other)),
+                }
+            }
+            other => {
+                let kind = other
+                    .strip_prefix("lint_")
+                    .and_then(LintKind::from_name)
+                    .ok_or_else(|| format!("Unknown setting: {}", other))?;
+                let level = match value.as_deref() {
+                    None => defaults.lints.level(kind),
+                    Some(level) => LintLevel::from_name(level).ok_or_else(|| invalid(level))?,
+                };
+                self.lints.set(kind, level);
+            }
+        }
+        Ok(())
+    }
+
+    /// Current value of a setting, as `SET` accepts it
+    pub fn get(&self, name: &str) -> Option<String> {
+        let value = match name.to_lowercase().as_str() {
+            "parse_mode" => match self.parse_mode {
+                ParseMode::Strict => "strict".to_string(),
+                ParseMode::Permissive => "permissive".to_string(),
+            },
+            "privacy_level" => match self.privacy_level {
+                PrivacyLevel::None => "none".to_string(),
+                PrivacyLevel::HideRowCounts => "hide_row_counts".to_string(),
+            },
+            "max_k" => self.max_k.map_or("none".to_string(), |k| k.to_string()),
+            "k_strategy" => match self.k_strategy {
+                KStrategy::Grow => "grow".to_string(),
+                KStrategy::Minimal => "minimal".to_string(),
+            },
+            other => {
+                let kind = other.strip_prefix("lint_").and_then(LintKind::from_name)?;
+                self.lints.level(kind).name().to_string()
+            }
+        };
+        Some(value)
+    }
+}
+
+impl fmt::Display for SessionSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in ["parse_mode", "privacy_level", "max_k", "k_strategy"] {
+            writeln!(f, "{} = {}", name, self.get(name).unwrap_or_default())?;
+        }
+        for kind in LintKind::ALL {
+            writeln!(
+                f,
+                "lint_{} = {}",
+                kind.name(),
+                self.lints.level(kind).name()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(sql: &str) -> SetStatement {
+        SetStatement::parse(sql).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_set() {
+        assert_eq!(
+            set("SET privacy_level = 'hide_row_counts';"),
+            SetStatement {
+                name: "privacy_level".to_string(),
+                value: Some("hide_row_counts".to_string()),
+            }
+        );
+        assert_eq!(set("set MAX_K to 20").value.as_deref(), Some("20"));
+        assert_eq!(set("SET max_k = DEFAULT").value, None);
+        assert_eq!(
+            set("SET max_k = 'default'").value.as_deref(),
+            Some("default")
+        );
+
+        assert!(SetStatement::parse("SELECT a FROM t").is_none());
+        assert!(SetStatement::parse("SETTINGS").is_none());
+        assert!(SetStatement::parse("SET max_k").unwrap().is_err());
+        assert!(SetStatement::parse("SET max_k 20").unwrap().is_err());
+        assert!(SetStatement::parse("SET = 20").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_apply_settings() {
+        let mut settings = SessionSettings::default();
+        settings.apply(&set("SET max_k = 20")).unwrap();
+        settings.apply(&set("SET parse_mode = STRICT")).unwrap();
+        assert_eq!(settings.max_k, Some(20));
+        assert_eq!(settings.parse_mode, ParseMode::Strict);
+        assert_eq!(settings.get("max_k").as_deref(), Some("20"));
+
+        // Invalid values leave the setting unchanged
+        assert!(settings.apply(&set("SET max_k = 99")).is_err());
+        assert!(settings
+            .apply(&set("SET privacy_level = 'secret'"))
+            .is_err());
+        assert!(settings.apply(&set("SET unknown = 1")).is_err());
+        assert_eq!(settings.max_k, Some(20));
+
+        settings.apply(&set("SET k_strategy = minimal")).unwrap();
+        assert_eq!(settings.k_strategy, KStrategy::Minimal);
+        assert!(settings.apply(&set("SET k_strategy = tiny")).is_err());
+
+        settings.apply(&set("SET max_k = DEFAULT")).unwrap();
+        assert_eq!(settings.max_k, None);
+        assert!(settings.to_string().contains("parse_mode = strict"));
+    }
+
+    #[test]
+    fn test_lint_settings() {
+        let mut settings = SessionSettings::default();
+        settings
+            .apply(&set("SET lint_sum_overflow = DENY"))
+            .unwrap();
+        assert_eq!(settings.lints.sum_overflow, LintLevel::Deny);
+        assert_eq!(settings.get("lint_sum_overflow").as_deref(), Some("deny"));
+        assert!(settings.to_string().contains("lint_unordered_limit = warn"));
+
+        assert!(settings
+            .apply(&set("SET lint_sum_overflow = 'loud'"))
+            .is_err());
+        assert!(settings.apply(&set("SET lint_everything = deny")).is_err());
+        assert_eq!(settings.get("lint_everything"), None);
+        settings
+            .apply(&set("SET lint_sum_overflow = DEFAULT"))
+            .unwrap();
+        assert_eq!(settings.lints, LintLevels::default());
+    }
+}
@@ -0,0 +1,297 @@
+// Formal statement of a query proof
+// What a verified proof attests, in terms of the committed rows, the
+// predicate, the outputs and the public instance rows. Meant for security
+// review: the statement is derived from the same AST the compiler uses.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{
+    AggregationFunction, ComparisonOp, JoinType, OrderDirection, PredicateExpr, SQLQuery,
+    WhereClause,
+};
+use crate::circuit::{
+    INSTANCE_DB_COMMITMENT_ROW, INSTANCE_EXPIRY_ROW, INSTANCE_NONCE_ROW,
+    INSTANCE_QUERY_HASH_ROW, INSTANCE_QUERY_RESULT_ROW, INSTANCE_RESULT_COMMITMENT_ROW,
+};
+
+/// Statement attested by a query proof
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct QueryStatement {
+    /// Committed table the rows are drawn from
+    pub table: String,
+    /// Joined tables, as `<type> JOIN <table> ON <left> = <right>`
+    pub joins: Vec<String>,
+    /// Row predicate (None = every committed row)
+    pub predicate: Option<String>,
+    /// Grouping keys
+    pub group_by: Vec<String>,
+    /// Output columns and aggregates
+    pub outputs: Vec<String>,
+    /// Ordering of the output
+    pub order_by: Vec<String>,
+    /// Public instance rows, as `<row>: <meaning>`
+    pub public_inputs: Vec<String>,
+}
+
+impl QueryStatement {
+    /// Machine-readable form
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+impl SQLQuery {
+    /// Statement the proof of this query attests
+    ///
+    /// # Usage
+    ///
+    /// ```rust,ignore
+    /// let query = SQLParser::parse("SELECT SUM(price) FROM orders WHERE qty > 5")?;
+    /// println!("{}", query.statement());
+    /// ```
+    pub fn statement(&self) -> QueryStatement {
+        let joins = self
+            .joins
+            .iter()
+            .flatten()
+            .map(|join| {
+                let join_type = match join.join_type {
+                    JoinType::Inner => "INNER",
+                    JoinType::Left => "LEFT",
+                    JoinType::Right => "RIGHT",
+                    JoinType::Full => "FULL",
+                    JoinType::Semi => "SEMI",
+                    JoinType::Anti => "ANTI",
+                };
+                // Aliased sides are named by alias (both sides of a self-join
+                // are the same table)
+                let qualified = |name: Option<&String>, column: &str| match name {
+                    Some(name) => format!("{}.{}", name, column),
+                    None => column.to_string(),
+                };
+                let table = match &join.alias {
+                    Some(alias) => format!("{} {}", join.table, alias),
+                    None => join.table.clone(),
+                };
+                format!(
+                    "{} JOIN {} ON {} = {}",
+                    join_type,
+                    table,
+                    qualified(self.from_alias.as_ref(), &join.on.left_column),
+                    qualified(join.alias.as_ref(), &join.on.right_column)
+                )
+            })
+            .collect();
+
+        let predicate = self
+            .predicate
+            .as_ref()
+            .map(|p| p.to_string())
+            .or_else(|| self.where_clause.as_ref().map(|w| w.to_string()));
+
+        let outputs = match &self.aggregations {
+            Some(aggregations) => aggregations
+                .iter()
+                .map(|agg| {
+                    let function = match agg.function {
+                        AggregationFunction::Sum => "SUM",
+                        AggregationFunction::Count => "COUNT",
+                        AggregationFunction::Max => "MAX",
+                        AggregationFunction::Min => "MIN",
+                        AggregationFunction::Avg => "AVG",
+                        AggregationFunction::Median => "MEDIAN",
+                        AggregationFunction::Percentile(p) => {
+                            return format!(
+                                "{}th percentile (nearest rank) of {} over the selected rows",
+                                p, agg.column
+                            )
+                        }
+                    };
+                    format!("{}({}) over the selected rows", function, agg.column)
+                })
+                .collect(),
+            None => self.columns.clone(),
+        };
+
+        let order_by = self
+            .order_by
+            .iter()
+            .flatten()
+            .map(|order| {
+                let direction = match order.direction {
+                    OrderDirection::Asc => "ASC",
+                    OrderDirection::Desc => "DESC",
+                };
+                format!("{} {}", order.column, direction)
+            })
+            .collect();
+
+        QueryStatement {
+            table: self
+                .table_function
+                .as_ref()
+                .map_or_else(|| self.from.clone(), |function| function.to_string()),
+            joins,
+            predicate,
+            group_by: self.group_by.clone().unwrap_or_default(),
+            outputs,
+            order_by,
+            public_inputs: vec![
+                format!(
+                    "{}: commitment to table {}, hashed in-circuit from the scanned rows",
+                    INSTANCE_DB_COMMITMENT_ROW, self.from
+                ),
+                format!(
+                    "{}: query result, copied from the final aggregation result",
+                    INSTANCE_QUERY_RESULT_ROW
+                ),
+                format!("{}: request nonce", INSTANCE_NONCE_ROW),
+                format!("{}: expiry bound", INSTANCE_EXPIRY_ROW),
+                format!(
+                    "{}: query hash, a constant of the verifying key",
+                    INSTANCE_QUERY_HASH_ROW
+                ),
+                format!(
+                    "{}: result commitment, hashed in-circuit from the query result",
+                    INSTANCE_RESULT_COMMITMENT_ROW
+                ),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for QueryStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "For the table {} committed in instance row {}:",
+            self.table, INSTANCE_DB_COMMITMENT_ROW
+        )?;
+        for join in &self.joins {
+            writeln!(f, "  {}", join)?;
+        }
+        match &self.predicate {
+            Some(predicate) => writeln!(f, "  rows R = {{ r in {} | {} }}", self.table, predicate)?,
+            None => writeln!(f, "  rows R = all rows of {}", self.table)?,
+        }
+        if !self.group_by.is_empty() {
+            writeln!(f, "  grouped by {}", self.group_by.join(", "))?;
+        }
+        writeln!(f, "  outputs: {}", self.outputs.join(", "))?;
+        if !self.order_by.is_empty() {
+            writeln!(f, "  ordered by {}", self.order_by.join(", "))?;
+        }
+        writeln!(f, "Public inputs:")?;
+        for input in &self.public_inputs {
+            writeln!(f, "  {}", input)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WhereClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WhereClause::LessThan { column, value } => write!(f, "{} < {}", column, value),
+            WhereClause::GreaterThan { column, value } => write!(f, "{} > {}", column, value),
+            WhereClause::Equal { column, value } => write!(f, "{} = {}", column, value),
+            WhereClause::Between { column, low, high } => {
+                write!(f, "{} <= {} <= {}", low, column, high)
+            }
+            WhereClause::In { column, values } => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "{} IN {{{}}}", column, values.join(", "))
+            }
+            WhereClause::Like { column, pattern } => write!(f, "{} LIKE '{}'", column, pattern),
+            WhereClause::CompareSubquery {
+                column,
+                operator,
+                subquery,
+            } => {
+                let operator = match operator {
+                    ComparisonOp::LessThan => "<",
+                    ComparisonOp::GreaterThan => ">",
+                    ComparisonOp::Equal => "=",
+                };
+                write!(f, "{} {} ({})", column, operator, SubqueryText(subquery))
+            }
+            WhereClause::InSubquery { column, subquery } => {
+                write!(f, "{} IN ({})", column, SubqueryText(subquery))
+            }
+            WhereClause::SemiJoin {
+                column,
+                subquery,
+                join_type,
+            } => {
+                let not = match join_type {
+                    JoinType::Anti => "NOT ",
+                    _ => "",
+                };
+                write!(f, "{} {}IN ({})", column, not, SubqueryText(subquery))
+            }
+            WhereClause::And(l, r) => write!(f, "({} AND {})", l, r),
+            WhereClause::Or(l, r) => write!(f, "({} OR {})", l, r),
+        }
+    }
+}
+
+/// Compact `SELECT ... FROM ... [WHERE ...]` of a subquery
+struct SubqueryText<'a>(&'a SQLQuery);
+
+impl fmt::Display for SubqueryText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SELECT {} FROM {}", self.0.columns.join(", "), self.0.from)?;
+        if let Some(predicate) = &self.0.predicate {
+            write!(f, " WHERE {}", predicate)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PredicateExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PredicateExpr::Compare(clause) => write!(f, "{}", clause),
+            PredicateExpr::And(l, r) => write!(f, "({} AND {})", l, r),
+            PredicateExpr::Or(l, r) => write!(f, "({} OR {})", l, r),
+            PredicateExpr::Not(e) => write!(f, "NOT {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::SQLParser;
+
+    #[test]
+    fn test_statement_of_filtered_aggregate() {
+        let query =
+            SQLParser::parse("SELECT SUM(price) FROM orders WHERE (qty > 5 AND price < 100) OR NOT status = 3")
+                .unwrap();
+        let statement = query.statement();
+
+        assert_eq!(statement.table, "orders");
+        assert_eq!(
+            statement.predicate.as_deref(),
+            Some("((qty > 5 AND price < 100) OR NOT status = 3)")
+        );
+        assert_eq!(statement.outputs, vec!["SUM(price) over the selected rows"]);
+        assert_eq!(statement.public_inputs.len(), 6);
+
+        let text = statement.to_string();
+        assert!(text.contains("rows R = { r in orders |"));
+        assert!(statement.to_json().unwrap().contains("\"predicate\""));
+    }
+
+    #[test]
+    fn test_statement_without_predicate() {
+        let query = SQLParser::parse("SELECT a, b FROM t ORDER BY a DESC").unwrap();
+        let statement = query.statement();
+        assert_eq!(statement.predicate, None);
+        assert_eq!(statement.outputs, vec!["a", "b"]);
+        assert_eq!(statement.order_by, vec!["a DESC"]);
+        assert!(statement.to_string().contains("rows R = all rows of t"));
+    }
+}
@@ -0,0 +1,461 @@
+// Result streaming
+// Large results travel as a header and batches of rows instead of one body,
+// e.g. from the proving service (`GET /jobs/{id}/result`) as newline-delimited
+// JSON. Neither side holds the encoded result:
+//
+// | Line | Content                                                           |
+// |------|-------------------------------------------------------------------|
+// | 1    | `ResultStreamHeader`: columns, row count, encoded length          |
+// | 2..  | `ResultBatch`: index, rows, chain value after the batch           |
+//
+// The chain is the result commitment (`QueryResult::commitment`, Poseidon over
+// `QueryResult::to_bytes`) computed incrementally: the chain value of a batch
+// is the sponge over every byte sent so far, finalized. It covers all earlier
+// batches, so a lost, repeated or reordered batch is detected when it arrives,
+// and the chain value of the last batch IS the result commitment a proof binds
+// to instance row 5. Rows are only vouched for once that last value matches
+// the proof; a batch that keeps the chain consistent is not yet authentic.
+//
+// A `ResultStreamReader` encodes one batch at a time as the transport pulls
+// bytes, so a slow client holds back the producer instead of filling memory.
+
+use std::io::{self, BufRead, Read};
+use std::sync::Arc;
+
+use ff::PrimeField;
+use pasta_curves::pallas::Base as Fr;
+use serde::{Deserialize, Serialize};
+
+use super::executor::{encode_result_header, encode_result_row};
+use super::QueryResult;
+use crate::circuit::{pallas_poseidon_params, PoseidonSponge};
+use crate::utils::{bytes_to_hex, pack_chunk, PACK_CHUNK_BYTES};
+
+/// Rows per batch unless the client asks for another size
+pub const DEFAULT_BATCH_ROWS: usize = 1024;
+
+/// First line of a result stream
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultStreamHeader {
+    pub columns: Vec<String>,
+    /// Rows in the whole result
+    pub rows: usize,
+    /// Length of `QueryResult::to_bytes`, which fixes the sponge's input length
+    pub bytes: usize,
+}
+
+/// Consecutive rows of a result stream
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResultBatch {
+    /// Position in the stream, from 0
+    pub index: usize,
+    pub rows: Vec<Vec<Option<u64>>>,
+    /// Chain value after this batch, hex (the result commitment for the last)
+    pub chain: String,
+}
+
+/// Result commitment over bytes that arrive in pieces
+#[derive(Clone, Debug)]
+pub struct ResultChain {
+    sponge: PoseidonSponge<'static, Fr>,
+    /// Bytes of the chunk being filled (fewer than `PACK_CHUNK_BYTES`)
+    partial: Vec<u8>,
+    /// Bytes still expected
+    remaining: usize,
+}
+
+impl ResultChain {
+    /// Chain over an encoding of `len` bytes
+    pub fn new(len: usize) -> Self {
+        let elements = 1 + len.div_ceil(PACK_CHUNK_BYTES);
+        let mut sponge = PoseidonSponge::new(pallas_poseidon_params(), elements);
+        sponge.absorb(Fr::from(len as u64));
+        Self {
+            sponge,
+            partial: Vec::with_capacity(PACK_CHUNK_BYTES),
+            remaining: len,
+        }
+    }
+
+    /// Absorb the next bytes of the encoding
+    ///
+    /// # Errors
+    ///
+    /// If the bytes run past the announced length
+    pub fn absorb(&mut self, bytes: &[u8]) -> Result<(), String> {
+        if bytes.len() > self.remaining {
+            return Err("result stream is longer than its header announced".to_string());
+        }
+        self.remaining -= bytes.len();
+        for &byte in bytes {
+            self.partial.push(byte);
+            if self.partial.len() == PACK_CHUNK_BYTES {
+                self.sponge.absorb(pack_chunk(&self.partial));
+                self.partial.clear();
+            }
+        }
+        Ok(())
+    }
+
+    /// Chain value of the bytes so far
+    /// Equals `QueryResult::commitment` once every byte is absorbed.
+    pub fn value(&self) -> Fr {
+        let mut sponge = self.sponge.clone();
+        if !self.partial.is_empty() {
+            sponge.absorb(pack_chunk(&self.partial));
+        }
+        sponge.finish()
+    }
+
+    /// Bytes still expected
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+/// Batches of a result, each with its chain value
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let stream = ResultStream::new(Arc::new(result), 1000);
+/// send(&stream.header());
+/// for batch in stream {
+///     send(&batch);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ResultStream {
+    result: Arc<QueryResult>,
+    batch_rows: usize,
+    next_row: usize,
+    next_index: usize,
+    /// Length of the encoding
+    len: usize,
+    chain: ResultChain,
+}
+
+impl ResultStream {
+    /// Stream `result` in batches of `batch_rows` rows (at least one)
+    pub fn new(result: Arc<QueryResult>, batch_rows: usize) -> Self {
+        let mut header = Vec::new();
+        encode_result_header(&result.columns, result.rows.len(), &mut header);
+        let cell_bytes = |cell: &Option<u64>| if cell.is_some() { 9 } else { 1 };
+        let len = header.len() + result.rows.iter().flatten().map(cell_bytes).sum::<usize>();
+        let mut chain = ResultChain::new(len);
+        chain
+            .absorb(&header)
+            .expect("the header is part of the encoding");
+        Self {
+            result,
+            batch_rows: batch_rows.max(1),
+            next_row: 0,
+            next_index: 0,
+            len,
+            chain,
+        }
+    }
+
+    pub fn header(&self) -> ResultStreamHeader {
+        ResultStreamHeader {
+            columns: self.result.columns.clone(),
+            rows: self.result.rows.len(),
+            bytes: self.len,
+        }
+    }
+
+    /// Newline-delimited JSON: the header, then one line per batch
+    pub fn into_reader(self) -> ResultStreamReader {
+        let header = self.header();
+        ResultStreamReader {
+            stream: self,
+            header: Some(header),
+            line: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl Iterator for ResultStream {
+    type Item = ResultBatch;
+
+    /// Next batch; an empty result still has one (empty) batch, which
+    /// carries the result commitment
+    fn next(&mut self) -> Option<ResultBatch> {
+        let total = self.result.rows.len();
+        if self.next_index > 0 && self.next_row >= total {
+            return None;
+        }
+        let end = (self.next_row + self.batch_rows).min(total);
+        let rows = self.result.rows[self.next_row..end].to_vec();
+        let mut bytes = Vec::new();
+        for row in &rows {
+            encode_result_row(row, &mut bytes);
+        }
+        self.chain
+            .absorb(&bytes)
+            .expect("rows are part of the encoding");
+        let batch = ResultBatch {
+            index: self.next_index,
+            rows,
+            chain: field_hex(&self.chain.value()),
+        };
+        self.next_row = end;
+        self.next_index += 1;
+        Some(batch)
+    }
+}
+
+/// `io::Read` over the newline-delimited JSON of a `ResultStream`
+/// Encodes the next line only once the previous one has been read.
+#[derive(Debug)]
+pub struct ResultStreamReader {
+    stream: ResultStream,
+    header: Option<ResultStreamHeader>,
+    /// Current line and how much of it has been read
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl Read for ResultStreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.line.len() {
+            let line = match self.header.take() {
+                Some(header) => serde_json::to_vec(&header),
+                None => match self.stream.next() {
+                    Some(batch) => serde_json::to_vec(&batch),
+                    None => return Ok(0),
+                },
+            };
+            self.line = line.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.line.push(b'\n');
+            self.position = 0;
+        }
+        let n = buf.len().min(self.line.len() - self.position);
+        buf[..n].copy_from_slice(&self.line[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Checks batches against the chain as they arrive
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let mut verifier = ResultStreamVerifier::new(&header)?;
+/// for batch in batches {
+///     verifier.accept(&batch)?;
+///     stage(batch.rows); // consistent, not yet authentic
+/// }
+/// verifier.finish(public_inputs.result_commitment)?; // now authentic
+/// ```
+#[derive(Clone, Debug)]
+pub struct ResultStreamVerifier {
+    columns: usize,
+    rows: usize,
+    rows_seen: usize,
+    next_index: usize,
+    chain: ResultChain,
+}
+
+impl ResultStreamVerifier {
+    /// # Errors
+    ///
+    /// If the header's length can't hold its own columns and row count
+    pub fn new(header: &ResultStreamHeader) -> Result<Self, String> {
+        let mut bytes = Vec::new();
+        encode_result_header(&header.columns, header.rows, &mut bytes);
+        let mut chain = ResultChain::new(header.bytes);
+        chain.absorb(&bytes)?;
+        Ok(Self {
+            columns: header.columns.len(),
+            rows: header.rows,
+            rows_seen: 0,
+            next_index: 0,
+            chain,
+        })
+    }
+
+    /// Check the next batch
+    ///
+    /// # Errors
+    ///
+    /// If the batch is out of order, has rows of the wrong width or more rows
+    /// than announced, or breaks the chain
+    pub fn accept(&mut self, batch: &ResultBatch) -> Result<(), String> {
+        if batch.index != self.next_index {
+            return Err(format!(
+                "expected batch {}, got batch {}",
+                self.next_index, batch.index
+            ));
+        }
+        if batch.rows.iter().any(|row| row.len() != self.columns) {
+            return Err(format!("batch {} has rows of the wrong width", batch.index));
+        }
+        if self.rows_seen + batch.rows.len() > self.rows {
+            return Err(format!(
+                "batch {} runs past {} rows",
+                batch.index, self.rows
+            ));
+        }
+        let mut bytes = Vec::new();
+        for row in &batch.rows {
+            encode_result_row(row, &mut bytes);
+        }
+        self.chain.absorb(&bytes)?;
+        if field_hex(&self.chain.value()) != batch.chain {
+            return Err(format!("batch {} breaks the chain", batch.index));
+        }
+        self.rows_seen += batch.rows.len();
+        self.next_index += 1;
+        Ok(())
+    }
+
+    /// Check the complete stream against the result commitment of its proof
+    ///
+    /// # Errors
+    ///
+    /// If rows are missing or the stream is not the committed result
+    pub fn finish(self, result_commitment: Fr) -> Result<(), String> {
+        if self.rows_seen < self.rows || self.chain.remaining() > 0 || self.next_index == 0 {
+            return Err(format!(
+                "result stream ended after {} of {} rows",
+                self.rows_seen, self.rows
+            ));
+        }
+        if self.chain.value() != result_commitment {
+            return Err("result stream does not match the result commitment".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Read and check a newline-delimited JSON result stream
+/// `batch` sees each batch once it is consistent with the chain; the stream
+/// is authentic only if this returns Ok.
+///
+/// # Errors
+///
+/// If a line doesn't decode, as `ResultStreamVerifier::accept` and `finish`
+pub fn verify_result_stream<R: BufRead>(
+    reader: R,
+    result_commitment: Fr,
+    mut batch: impl FnMut(ResultBatch),
+) -> Result<ResultStreamHeader, String> {
+    let mut lines = reader.lines();
+    let mut next_line = || -> Result<Option<String>, String> {
+        lines.next().transpose().map_err(|e| e.to_string())
+    };
+    let header: ResultStreamHeader = match next_line()? {
+        Some(line) => serde_json::from_str(&line).map_err(|e| format!("header: {}", e))?,
+        None => return Err("empty result stream".to_string()),
+    };
+    let mut verifier = ResultStreamVerifier::new(&header)?;
+    while let Some(line) = next_line()? {
+        let next: ResultBatch = serde_json::from_str(&line).map_err(|e| format!("batch: {}", e))?;
+        verifier.accept(&next)?;
+        batch(next);
+    }
+    verifier.finish(result_commitment)?;
+    Ok(header)
+}
+
+/// Hex of a field element, as `prover::field_to_hex` (and i.json) encode it
+fn field_hex(value: &Fr) -> String {
+    bytes_to_hex(value.to_repr().as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(rows: usize) -> QueryResult {
+        QueryResult {
+            columns: vec!["id".to_string(), "price".to_string()],
+            rows: (0..rows as u64)
+                .map(|i| vec![Some(i), (i % 3 != 0).then_some(i * 10)])
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_chain_ends_in_commitment() {
+        for rows in [0, 1, 7, 100] {
+            let result = result(rows);
+            let stream = ResultStream::new(Arc::new(result.clone()), 8);
+            let header = stream.header();
+            assert_eq!(header.bytes, result.to_bytes().len());
+            let batches: Vec<ResultBatch> = stream.collect();
+            assert_eq!(batches.len(), rows.div_ceil(8).max(1));
+            assert_eq!(
+                batches.last().unwrap().chain,
+                field_hex(&result.commitment())
+            );
+
+            let mut verifier = ResultStreamVerifier::new(&header).unwrap();
+            for batch in &batches {
+                verifier.accept(batch).unwrap();
+            }
+            assert_eq!(verifier.finish(result.commitment()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let result = result(20);
+        let stream = ResultStream::new(Arc::new(result.clone()), 5);
+        let header = stream.header();
+        let batches: Vec<ResultBatch> = stream.collect();
+
+        // Reordered
+        let mut verifier = ResultStreamVerifier::new(&header).unwrap();
+        assert!(verifier.accept(&batches[1]).is_err());
+
+        // Altered row
+        let mut verifier = ResultStreamVerifier::new(&header).unwrap();
+        let mut altered = batches[0].clone();
+        altered.rows[2][1] = Some(999);
+        assert_eq!(
+            verifier.accept(&altered),
+            Err("batch 0 breaks the chain".to_string())
+        );
+
+        // Truncated
+        let mut verifier = ResultStreamVerifier::new(&header).unwrap();
+        verifier.accept(&batches[0]).unwrap();
+        assert!(verifier.finish(result.commitment()).is_err());
+
+        // Consistent, but another result than the proven one
+        let mut verifier = ResultStreamVerifier::new(&header).unwrap();
+        for batch in &batches {
+            verifier.accept(batch).unwrap();
+        }
+        assert!(verifier.finish(Fr::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_reader_round_trip() {
+        let result = result(50);
+        let mut reader = ResultStream::new(Arc::new(result.clone()), 16).into_reader();
+        // Small reads: lines are produced on demand
+        let mut ndjson = Vec::new();
+        let mut buf = [0u8; 7];
+        loop {
+            let n = reader.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            ndjson.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(ndjson.iter().filter(|&&b| b == b'\n').count(), 1 + 4);
+
+        let mut rows = Vec::new();
+        let header = verify_result_stream(&ndjson[..], result.commitment(), |batch| {
+            rows.extend(batch.rows)
+        })
+        .unwrap();
+        assert_eq!(header.columns, result.columns);
+        assert_eq!(rows, result.rows);
+    }
+}
@@ -1,4 +1,42 @@
-/// Test utilities for circuit testing
+//! Test utilities for circuit testing
+//! `fixtures` is shared with the integration tests, so it is built outside
+//! `cfg(test)`
+
+use std::collections::HashMap;
+
+/// Table data of SQL compiler tests: table -> column -> values
+pub type TableData = HashMap<String, HashMap<String, Vec<u64>>>;
+
+/// Tables shared by the SQL compiler tests
+pub mod fixtures {
+    use super::TableData;
+    use std::collections::HashMap;
+
+    /// `orders(customer, amount)`, five rows over three customers
+    pub fn orders() -> TableData {
+        let mut orders = HashMap::new();
+        orders.insert("customer".to_string(), vec![1, 2, 1, 3, 2]);
+        orders.insert("amount".to_string(), vec![50, 120, 30, 80, 200]);
+        let mut table_data = HashMap::new();
+        table_data.insert("orders".to_string(), orders);
+        table_data
+    }
+
+    /// `orders(customer, amount)` and `vip(id, tier)`: customers 2 and 3 are
+    /// VIPs, customer 1 is not
+    pub fn orders_and_vip() -> TableData {
+        let mut orders = HashMap::new();
+        orders.insert("customer".to_string(), vec![1, 2, 3, 2]);
+        orders.insert("amount".to_string(), vec![50, 120, 80, 200]);
+        let mut vip = HashMap::new();
+        vip.insert("id".to_string(), vec![2, 3]);
+        vip.insert("tier".to_string(), vec![1, 2]);
+        let mut table_data = HashMap::new();
+        table_data.insert("orders".to_string(), orders);
+        table_data.insert("vip".to_string(), vip);
+        table_data
+    }
+}
 
 #[cfg(test)]
 pub mod test_helpers {
@@ -22,8 +60,15 @@ pub mod test_helpers {
     /// Create a simple circuit for testing
     pub fn create_test_circuit() -> PoneglyphCircuit {
         PoneglyphCircuit {
-            db_commitment: Value::known(Fr::from(42)),
+            scan: TableScan::default(),
             query_result: Value::known(Fr::from(100)),
+            nonce: Value::known(Fr::from(0)),
+            expiry: Value::known(Fr::from(0)),
+            query_hash: Fr::from(0),
+            result_commitment: Value::known(Fr::from(0)),
+            result_columns: vec![],
+            result_predicate: None,
+            threshold_mode: ThresholdMode::Fixed,
             range_checks: vec![RangeCheckOp {
                 value: Value::known(10),
                 threshold: 20,
@@ -46,6 +91,7 @@ pub mod test_helpers {
             group_keys,
             values,
             agg_type,
+            column: None,
         }
     }
 }
@@ -0,0 +1,185 @@
+// TLS for network front ends
+// Server-side rustls configuration from PEM files: the server's certificate
+// chain and key, and optionally the CA that client certificates must chain to
+// (mutual TLS). Without a client CA any client may connect and is then
+// identified by its API key (see `ApiKeys`); with one, the TLS handshake
+// already rejects clients without a certificate from that CA.
+//
+// Only the ring provider and TLS 1.2 / 1.3 are compiled in (feature `tls`).
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+
+use crate::error::{PoneglyphError, PoneglyphResult};
+
+/// Client certificate requirement
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientAuth {
+    /// No client certificates
+    None,
+    /// Clients may present a certificate; if they do, it must chain to the CA
+    Optional { ca_pem: PathBuf },
+    /// Clients must present a certificate chaining to the CA
+    Required { ca_pem: PathBuf },
+}
+
+/// TLS settings of a listener
+///
+/// # Usage
+///
+/// ```rust,ignore
+/// let tls = TlsConfig::new("server.crt", "server.key")
+///     .with_client_auth(ClientAuth::Required { ca_pem: "clients.crt".into() });
+/// let server_config = tls.server_config()?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first
+    pub cert_chain_pem: PathBuf,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1)
+    pub private_key_pem: PathBuf,
+    pub client_auth: ClientAuth,
+    /// ALPN protocols to offer, in preference order (e.g. `h2`, `http/1.1`)
+    pub alpn_protocols: Vec<Vec<u8>>,
+}
+
+impl TlsConfig {
+    pub fn new(cert_chain_pem: impl Into<PathBuf>, private_key_pem: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_chain_pem: cert_chain_pem.into(),
+            private_key_pem: private_key_pem.into(),
+            client_auth: ClientAuth::None,
+            alpn_protocols: Vec::new(),
+        }
+    }
+
+    pub fn with_client_auth(mut self, client_auth: ClientAuth) -> Self {
+        self.client_auth = client_auth;
+        self
+    }
+
+    pub fn with_alpn(mut self, protocols: &[&str]) -> Self {
+        self.alpn_protocols = protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+        self
+    }
+
+    /// rustls server configuration
+    ///
+    /// # Errors
+    ///
+    /// `Configuration` if a file can't be read or parsed, or if the key
+    /// doesn't match the certificate
+    pub fn server_config(&self) -> PoneglyphResult<Arc<ServerConfig>> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?;
+
+        let builder = match &self.client_auth {
+            ClientAuth::None => builder.with_no_client_auth(),
+            ClientAuth::Optional { ca_pem } | ClientAuth::Required { ca_pem } => {
+                let mut roots = RootCertStore::empty();
+                for cert in read_certs(ca_pem)? {
+                    roots.add(cert).map_err(tls_error)?;
+                }
+                let verifier =
+                    WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+                let verifier = match self.client_auth {
+                    ClientAuth::Optional { .. } => verifier.allow_unauthenticated(),
+                    _ => verifier,
+                };
+                builder.with_client_cert_verifier(verifier.build().map_err(tls_error)?)
+            }
+        };
+
+        let key = PrivateKeyDer::from_pem_file(&self.private_key_pem).map_err(|e| {
+            PoneglyphError::Configuration(format!(
+                "Can't read private key {}: {}",
+                self.private_key_pem.display(),
+                e
+            ))
+        })?;
+        let mut config = builder
+            .with_single_cert(read_certs(&self.cert_chain_pem)?, key)
+            .map_err(tls_error)?;
+        config.alpn_protocols = self.alpn_protocols.clone();
+        Ok(Arc::new(config))
+    }
+}
+
+/// Every certificate of a PEM file (at least one)
+fn read_certs(path: &Path) -> PoneglyphResult<Vec<CertificateDer<'static>>> {
+    let read_error = |e: rustls::pki_types::pem::Error| {
+        PoneglyphError::Configuration(format!("Can't read certificates {}: {}", path.display(), e))
+    };
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(read_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(read_error)?;
+    if certs.is_empty() {
+        return Err(PoneglyphError::Configuration(format!(
+            "No certificates in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn tls_error(e: impl std::fmt::Display) -> PoneglyphError {
+    PoneglyphError::Configuration(format!("TLS: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/tls")
+            .join(name)
+    }
+
+    #[test]
+    fn test_server_config() {
+        let tls = TlsConfig::new(fixture("server.crt"), fixture("server.key")).with_alpn(&["h2"]);
+        let config = tls.server_config().unwrap();
+        assert_eq!(config.alpn_protocols, vec![b"h2".to_vec()]);
+
+        for client_auth in [
+            ClientAuth::Optional {
+                ca_pem: fixture("client_ca.crt"),
+            },
+            ClientAuth::Required {
+                ca_pem: fixture("client_ca.crt"),
+            },
+        ] {
+            assert!(tls
+                .clone()
+                .with_client_auth(client_auth)
+                .server_config()
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        // Key file in place of the certificate chain
+        let tls = TlsConfig::new(fixture("server.key"), fixture("server.key"));
+        assert!(matches!(
+            tls.server_config(),
+            Err(PoneglyphError::Configuration(_))
+        ));
+
+        let tls = TlsConfig::new(fixture("server.crt"), fixture("missing.key"));
+        assert!(tls.server_config().is_err());
+
+        // Key of another certificate
+        let tls = TlsConfig::new(fixture("client_ca.crt"), fixture("server.key"));
+        assert!(tls.server_config().is_err());
+    }
+}
@@ -1,4 +1,8 @@
-/// Utility functions for common operations
+//! Utility functions for common operations
+
+use std::fmt::Write;
+
+use pasta_curves::pallas::Base as Fr;
 
 /// Convert bytes to hex string representation
 pub fn bytes_to_hex(bytes: &[u8]) -> String {
@@ -9,7 +13,7 @@ pub fn bytes_to_hex(bytes: &[u8]) -> String {
 
 /// Parse hex string to bytes
 pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return Err("Hex string must have even length".to_string());
     }
     
@@ -21,13 +25,51 @@ pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
 }
 
 /// Calculate simple hash for a slice of bytes
+/// Low 64 bits of `poseidon_hash_bytes`, so it can be recomputed in-circuit
 pub fn simple_hash(data: &[u8]) -> u64 {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    
-    let mut hasher = DefaultHasher::new();
-    data.hash(&mut hasher);
-    hasher.finish()
+    use ff::PrimeField;
+
+    let repr = poseidon_hash_bytes(data).to_repr();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&repr.as_ref()[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Poseidon hash of field elements
+/// Matches `PoseidonChip::hash` bit-for-bit (same parameters and sponge)
+pub fn poseidon_hash(inputs: &[Fr]) -> Fr {
+    crate::circuit::pallas_poseidon_params().hash(inputs)
+}
+
+/// Poseidon hash of a row of column values
+pub fn poseidon_hash_row(row: &[u64]) -> Fr {
+    let inputs: Vec<Fr> = row.iter().map(|&v| Fr::from(v)).collect();
+    poseidon_hash(&inputs)
+}
+
+/// Bytes per field element in `pack_bytes` (every chunk is below the modulus)
+pub const PACK_CHUNK_BYTES: usize = 31;
+
+/// Field elements hashed by `poseidon_hash_bytes`: the byte length, then the
+/// bytes in 31-byte little-endian chunks
+pub fn pack_bytes(data: &[u8]) -> Vec<Fr> {
+    let mut elements = vec![Fr::from(data.len() as u64)];
+    elements.extend(data.chunks(PACK_CHUNK_BYTES).map(pack_chunk));
+    elements
+}
+
+/// One chunk of `pack_bytes` (at most `PACK_CHUNK_BYTES`), little-endian
+pub fn pack_chunk(chunk: &[u8]) -> Fr {
+    use ff::PrimeField;
+
+    let mut repr = <Fr as PrimeField>::Repr::default();
+    repr.as_mut()[..chunk.len()].copy_from_slice(chunk);
+    Fr::from_repr(repr).unwrap()
+}
+
+/// Poseidon hash of a byte string (e.g. string column values)
+pub fn poseidon_hash_bytes(data: &[u8]) -> Fr {
+    poseidon_hash(&pack_bytes(data))
 }
 
 /// Pad bytes to a specific length
@@ -50,10 +92,69 @@ pub fn truncate_or_pad(bytes: &[u8], length: usize, pad_value: u8) -> Vec<u8> {
 
 /// Check if a string is a valid hex string
 pub fn is_valid_hex(hex: &str) -> bool {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return false;
     }
-    hex.chars().all(|c| c.is_digit(16))
+    hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Render rows as an aligned plain-text table: header, rule, one line per row
+pub fn text_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        padded.join(" | ").trim_end().to_string()
+    };
+    let mut output = String::new();
+    let _ = writeln!(output, "{}", line(columns));
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let _ = writeln!(output, "{}", rule.join("-+-"));
+    for row in rows {
+        let _ = writeln!(output, "{}", line(row));
+    }
+    output
+}
+
+/// Render rows as an HTML table, every cell escaped
+pub fn html_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut output = String::from("<table>\n<thead><tr>");
+    for column in columns {
+        let _ = write!(output, "<th>{}</th>", html_escape(column));
+    }
+    output.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        output.push_str("<tr>");
+        for cell in row {
+            let _ = write!(output, "<td>{}</td>", html_escape(cell));
+        }
+        output.push_str("</tr>\n");
+    }
+    output.push_str("</tbody>\n</table>");
+    output
+}
+
+/// Escape text for HTML content and attribute values
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Print HTML as the rich output of an evcxr (Jupyter) cell
+/// evcxr calls a value's `evcxr_display` method, if it has one, instead of
+/// printing its `Debug` form.
+pub fn evcxr_html(html: &str) {
+    println!("EVCXR_BEGIN_CONTENT text/html\n{}\nEVCXR_END_CONTENT", html);
 }
 
 #[cfg(test)]
@@ -90,6 +191,16 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_poseidon_hash_bytes() {
+        assert_eq!(pack_bytes(b"").len(), 1);
+        assert_eq!(pack_bytes(&[7u8; 32]).len(), 3);
+        // Trailing zero bytes change the length element
+        assert_ne!(poseidon_hash_bytes(b"ab"), poseidon_hash_bytes(b"ab\0"));
+        assert_ne!(simple_hash(b"ab"), simple_hash(b"ba"));
+        assert_eq!(poseidon_hash_row(&[1, 2]), poseidon_hash(&[Fr::from(1), Fr::from(2)]));
+    }
+
     #[test]
     fn test_pad_bytes() {
         let bytes = b"test";
@@ -111,6 +222,19 @@ mod tests {
         assert!(!super::is_valid_hex("123"));
         assert!(!super::is_valid_hex("zz"));
     }
+
+    #[test]
+    fn test_text_and_html_tables() {
+        let columns = ["a".to_string(), "bb".to_string()];
+        let rows = vec![vec!["100".to_string(), "<2>".to_string()]];
+        assert_eq!(
+            text_table(&columns, &rows),
+            "a   | bb\n----+----\n100 | <2>\n"
+        );
+        let html = html_table(&columns, &rows);
+        assert!(html.contains("<th>bb</th>"));
+        assert!(html.contains("<td>&lt;2&gt;</td>"));
+    }
 }
 
 /// Mock SHA256 helper
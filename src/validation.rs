@@ -1,4 +1,4 @@
-/// Validation helper functions for circuit operations
+//! Validation helper functions for circuit operations
 
 use crate::error::{PoneglyphError, PoneglyphResult};
 
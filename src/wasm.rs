@@ -0,0 +1,127 @@
+// WebAssembly bindings
+// The verifier for browsers and JavaScript backends (feature `wasm`, target
+// wasm32-unknown-unknown). It takes the three files `poneglyphdb prove`
+// writes (p.bin, vk.bin, i.json), so a page or a Node service verifies a
+// proof without the tables, the query or a Rust toolchain:
+//
+//   import init, { verifyProof, queryHash } from "./pkg/poneglyphdb.js";
+//   await init();
+//   verifyProof(vkBytes, instancesJson, proofBytes); // throws if invalid
+//
+// `queryHash` and `queryStatement` bring the parser along, so a client can
+// check that instance row 4 is the hash of the SQL it asked for and show what
+// the proof attests. Nothing here proves.
+//
+// Build without halo2's thread pool, which wasm32-unknown-unknown lacks:
+//
+//   wasm-pack build --target web -- --no-default-features --features wasm
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::circuit::PublicInputs;
+use crate::prover::{field_to_hex, instance_from_hex, verify_raw};
+use crate::sql::{query_hash, SQLParser};
+
+/// Public inputs with every field element in hex (see `field_to_hex`)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct PublicInputsView {
+    db_commitment: String,
+    query_result: String,
+    nonce: String,
+    expiry: String,
+    query_hash: String,
+    result_commitment: String,
+    thresholds: Vec<u64>,
+}
+
+/// Verify a proof
+/// `vk` and `proof` are the bytes of vk.bin and p.bin, `instances` the text
+/// of i.json. Returns normally if the proof is valid and throws otherwise,
+/// with the reason (malformed input, wrong circuit, invalid proof).
+#[wasm_bindgen(js_name = verifyProof)]
+pub fn verify_proof(vk: &[u8], instances: &str, proof: &[u8]) -> Result<(), JsError> {
+    verify(vk, instances, proof).map_err(|e| JsError::new(&e))
+}
+
+/// Public inputs of i.json as JSON (hex field elements)
+#[wasm_bindgen(js_name = publicInputs)]
+pub fn public_inputs(instances: &str) -> Result<String, JsError> {
+    public_inputs_json(instances).map_err(|e| JsError::new(&e))
+}
+
+/// Hash of a query as bound to instance row 4, in hex
+#[wasm_bindgen(js_name = queryHash)]
+pub fn query_hash_hex(sql: &str) -> String {
+    field_to_hex(&query_hash(sql))
+}
+
+/// Statement a proof of `sql` attests, as JSON (see `QueryStatement`)
+#[wasm_bindgen(js_name = queryStatement)]
+pub fn query_statement(sql: &str) -> Result<String, JsError> {
+    statement_json(sql).map_err(|e| JsError::new(&e))
+}
+
+fn decode(instances: &str) -> Result<PublicInputs, String> {
+    let instances: Vec<Vec<String>> =
+        serde_json::from_str(instances).map_err(|e| format!("instances: {}", e))?;
+    let instance = instance_from_hex(&instances).map_err(|e| e.to_string())?;
+    PublicInputs::from_instance(&instance).ok_or_else(|| "instances do not decode".to_string())
+}
+
+fn verify(vk: &[u8], instances: &str, proof: &[u8]) -> Result<(), String> {
+    let inputs = decode(instances)?;
+    let instance_bytes = inputs.to_bytes().map_err(|e| e.to_string())?;
+    verify_raw(vk, &instance_bytes, proof).map_err(|e| e.to_string())
+}
+
+fn public_inputs_json(instances: &str) -> Result<String, String> {
+    let inputs = decode(instances)?;
+    let view = PublicInputsView {
+        db_commitment: field_to_hex(&inputs.db_commitment),
+        query_result: field_to_hex(&inputs.query_result),
+        nonce: field_to_hex(&inputs.nonce),
+        expiry: field_to_hex(&inputs.expiry.to_field()),
+        query_hash: field_to_hex(&inputs.query_hash),
+        result_commitment: field_to_hex(&inputs.result_commitment),
+        thresholds: inputs.thresholds,
+    };
+    serde_json::to_string_pretty(&view).map_err(|e| e.to_string())
+}
+
+fn statement_json(sql: &str) -> Result<String, String> {
+    SQLParser::parse(sql)?.statement().to_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::instance_to_hex;
+    use pasta_curves::pallas::Base as Fr;
+
+    fn instances(inputs: &PublicInputs) -> String {
+        serde_json::to_string(&instance_to_hex(&inputs.to_instance())).unwrap()
+    }
+
+    #[test]
+    fn test_public_inputs_json() {
+        let sql = "SELECT SUM(price) FROM orders";
+        let inputs =
+            PublicInputs::new(Fr::from(42), Fr::from(100)).with_query_hash(query_hash(sql));
+        let json = public_inputs_json(&instances(&inputs)).unwrap();
+        assert!(json.contains(&query_hash_hex(sql)));
+        assert!(json.contains(&field_to_hex(&Fr::from(100))));
+        assert!(public_inputs_json("[[\"zz\"]]").is_err());
+    }
+
+    #[test]
+    fn test_malformed_inputs_rejected() {
+        let inputs = PublicInputs::new(Fr::from(42), Fr::from(100));
+        assert!(verify(b"not a key", &instances(&inputs), b"not a proof").is_err());
+        assert!(verify(b"not a key", "{}", b"not a proof").is_err());
+        assert!(statement_json("SELECT SUM(price) FROM orders")
+            .unwrap()
+            .contains("orders"));
+        assert!(statement_json("DROP TABLE orders").is_err());
+    }
+}
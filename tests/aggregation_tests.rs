@@ -18,8 +18,6 @@ struct AggregationTestCircuit {
 #[derive(Clone)]
 struct TestConfig {
     poneglyph_config: PoneglyphConfig,
-    range_check_config: RangeCheckConfig,
-    group_by_config: GroupByConfig,
     aggregation_config: AggregationConfig,
 }
 
@@ -43,8 +41,6 @@ impl Circuit<Fr> for AggregationTestCircuit {
         
         TestConfig {
             poneglyph_config,
-            range_check_config,
-            group_by_config,
             aggregation_config,
         }
     }
@@ -64,13 +60,14 @@ impl Circuit<Fr> for AggregationTestCircuit {
         
         // Create aggregation chip
         let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        let agg_type = AggregationType::from_str(&self.agg_type).ok_or(Error::Synthesis)?;
         
         // Aggregate and verify
         let _results = aggregation_chip.aggregate_and_verify(
             layouter.namespace(|| "aggregate and verify"),
             &sorted_keys,
             &self.values,
-            &self.agg_type,
+            &agg_type,
         )?;
         
         Ok(())
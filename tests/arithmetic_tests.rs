@@ -0,0 +1,142 @@
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// Arithmetic Gate test circuit
+#[derive(Clone)]
+struct ArithmeticTestCircuit {
+    expr: ArithExpr,
+    rows: Vec<HashMap<String, u64>>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    arithmetic_config: ArithmeticConfig,
+}
+
+impl Circuit<Fr> for ArithmeticTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let arithmetic_config =
+            ArithmeticChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            arithmetic_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let arithmetic_chip = ArithmeticChip::new(config.arithmetic_config);
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut inputs = HashMap::new();
+            for (name, &value) in row {
+                let cell = arithmetic_chip.assign_input(
+                    layouter.namespace(|| format!("row {} {}", i, name)),
+                    Value::known(value),
+                )?;
+                inputs.insert(name.clone(), cell);
+            }
+
+            let result = arithmetic_chip.evaluate(
+                layouter.namespace(|| format!("row {}", i)),
+                &self.expr,
+                &inputs,
+            )?;
+            let expected = Fr::from(self.expr.evaluate(row).unwrap());
+            result.value().assert_if_known(|v| **v == expected);
+        }
+
+        Ok(())
+    }
+}
+
+fn row(values: &[(&str, u64)]) -> HashMap<String, u64> {
+    values.iter().map(|&(k, v)| (k.to_string(), v)).collect()
+}
+
+#[test]
+fn test_computed_column() {
+    // Test: SELECT price * qty - discount
+    let k = 12;
+    let circuit = ArithmeticTestCircuit {
+        expr: ArithExpr::parse("price * qty - discount").unwrap(),
+        rows: vec![
+            row(&[("price", 10), ("qty", 3), ("discount", 5)]),
+            row(&[("price", 7), ("qty", 0), ("discount", 0)]),
+        ],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_constant_multiplication() {
+    // Test: (price + tax) * 100
+    let k = 12;
+    let circuit = ArithmeticTestCircuit {
+        expr: ArithExpr::parse("(price + tax) * 100").unwrap(),
+        rows: vec![row(&[("price", 12), ("tax", 3)])],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_negative_result_rejected() {
+    // Test: discount > price * qty has no 64-bit witness
+    let k = 12;
+    let circuit = ArithmeticTestCircuit {
+        expr: ArithExpr::parse("price * qty - discount").unwrap(),
+        rows: vec![row(&[("price", 1), ("qty", 1), ("discount", 5)])],
+    };
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+}
+
+#[test]
+fn test_sql_computed_predicate() {
+    // Test: WHERE on a computed expression compiles to an arithmetic operation
+    let query = SQLParser::parse(
+        "SELECT price * qty FROM orders WHERE price * qty - discount < 20",
+    )
+    .unwrap();
+
+    let mut orders = HashMap::new();
+    orders.insert("price".to_string(), vec![10, 2]);
+    orders.insert("qty".to_string(), vec![3, 4]);
+    orders.insert("discount".to_string(), vec![5, 1]);
+    let mut table_data = HashMap::new();
+    table_data.insert("orders".to_string(), orders);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.arithmetic.len(), 2);
+    let thresholds: Vec<_> = compiled
+        .range_checks
+        .iter()
+        .map(|op| op.threshold)
+        .collect();
+    assert_eq!(thresholds, vec![20, 20]);
+}
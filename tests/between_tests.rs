@@ -0,0 +1,128 @@
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// BETWEEN Gate test circuit
+#[derive(Clone)]
+struct BetweenTestCircuit {
+    values: Vec<u64>,
+    low: u64,
+    high: u64,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    range_check_config: RangeCheckConfig,
+}
+
+impl Circuit<Fr> for BetweenTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            range_check_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::new(config.range_check_config);
+        for (i, &value) in self.values.iter().enumerate() {
+            let result = range_check_chip.check_between(
+                layouter.namespace(|| format!("between {}", i)),
+                Value::known(value),
+                self.low,
+                self.high,
+            )?;
+            let expected = Fr::from((self.low <= value && value < self.high) as u64);
+            result.value().assert_if_known(|v| **v == expected);
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_between_bounds() {
+    // Test: 10 <= x < 20, including both edges
+    let k = 10;
+    let circuit = BetweenTestCircuit {
+        values: vec![9, 10, 15, 19, 20, 0],
+        low: 10,
+        high: 20,
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_between_large_values() {
+    // Test: bounds near u64::MAX (differences use the full 64-bit range)
+    let k = 10;
+    let circuit = BetweenTestCircuit {
+        values: vec![u64::MAX - 1, u64::MAX, 0, 1 << 40],
+        low: 1 << 40,
+        high: u64::MAX,
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_between_compiles() {
+    // Test: BETWEEN is inclusive and its AND is not a logical AND
+    let query =
+        SQLParser::parse("SELECT price FROM orders WHERE price BETWEEN 10 AND 20").unwrap();
+    match &query.where_clause {
+        Some(WhereClause::Between { column, low, high }) => {
+            assert_eq!(column, "price");
+            assert_eq!((*low, *high), (10, 20));
+        }
+        other => panic!("unexpected WHERE clause: {:?}", other),
+    }
+
+    let mut orders = HashMap::new();
+    orders.insert("price".to_string(), vec![10, 20, 21]);
+    let mut table_data = HashMap::new();
+    table_data.insert("orders".to_string(), orders);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.betweens.len(), 3);
+    assert_eq!(compiled.betweens[0].low, 10);
+    assert_eq!(compiled.betweens[0].high, 21);
+
+    let query = SQLParser::parse(
+        "SELECT price FROM orders WHERE price BETWEEN 10 AND 20 AND qty > 5",
+    )
+    .unwrap();
+    match &query.where_clause {
+        Some(WhereClause::And(left, right)) => {
+            assert!(matches!(**left, WhereClause::Between { .. }));
+            assert!(matches!(**right, WhereClause::GreaterThan { .. }));
+        }
+        other => panic!("unexpected WHERE clause: {:?}", other),
+    }
+}
@@ -0,0 +1,154 @@
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// Boolean Gate test circuit
+/// Leaves are `low <= value < high` comparisons, combined by `tree`
+#[derive(Clone)]
+struct BooleanTestCircuit {
+    rows: Vec<Vec<u64>>,
+    bounds: Vec<(u64, u64)>,
+    tree: PredicateTree,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    range_check_config: RangeCheckConfig,
+    boolean_config: BooleanConfig,
+}
+
+impl Circuit<Fr> for BooleanTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let boolean_config = BooleanChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            range_check_config,
+            boolean_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::new(config.range_check_config);
+        let boolean_chip = BooleanChip::new(config.boolean_config);
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut leaves = Vec::new();
+            let mut bits = Vec::new();
+            for (j, (&value, &(low, high))) in row.iter().zip(self.bounds.iter()).enumerate() {
+                leaves.push(range_check_chip.check_between(
+                    layouter.namespace(|| format!("row {} leaf {}", i, j)),
+                    Value::known(value),
+                    low,
+                    high,
+                )?);
+                bits.push(low <= value && value < high);
+            }
+
+            let result = boolean_chip.evaluate(
+                layouter.namespace(|| format!("row {} predicate", i)),
+                &self.tree,
+                &leaves,
+            )?;
+            let expected = Fr::from(self.tree.evaluate(&bits).unwrap() as u64);
+            result.value().assert_if_known(|v| **v == expected);
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_boolean_predicate_tree() {
+    // Test: WHERE (a < 10 AND b = 3) OR c > 100
+    let query =
+        SQLParser::parse("SELECT a FROM t WHERE (a < 10 AND b = 3) OR c > 100").unwrap();
+    let tree = query.predicate.unwrap().to_tree();
+    assert_eq!(
+        tree,
+        PredicateTree::Or(
+            Box::new(PredicateTree::And(
+                Box::new(PredicateTree::Leaf(0)),
+                Box::new(PredicateTree::Leaf(1)),
+            )),
+            Box::new(PredicateTree::Leaf(2)),
+        )
+    );
+
+    let k = 12;
+    let circuit = BooleanTestCircuit {
+        rows: vec![vec![5, 3, 0], vec![5, 4, 0], vec![50, 3, 101], vec![10, 3, 100]],
+        bounds: vec![(0, 10), (3, 4), (101, u64::MAX)],
+        tree,
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_boolean_not() {
+    // Test: WHERE NOT (a < 10 OR b = 3)
+    let query = SQLParser::parse("SELECT a FROM t WHERE NOT (a < 10 OR b = 3)").unwrap();
+    assert!(query.where_clause.is_none());
+    let tree = query.predicate.unwrap().to_tree();
+
+    let k = 12;
+    let circuit = BooleanTestCircuit {
+        rows: vec![vec![5, 0], vec![20, 3], vec![20, 4]],
+        bounds: vec![(0, 10), (3, 4)],
+        tree,
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_predicate_precedence_and_compile() {
+    // Test: AND binds tighter than OR, leaves compile in textual order
+    let query = SQLParser::parse(
+        "SELECT a FROM t WHERE a < 10 OR b BETWEEN 1 AND 5 AND NOT c = 3",
+    )
+    .unwrap();
+    match &query.predicate {
+        Some(PredicateExpr::Or(left, right)) => {
+            assert!(matches!(**left, PredicateExpr::Compare(WhereClause::LessThan { .. })));
+            assert!(matches!(**right, PredicateExpr::And(_, _)));
+        }
+        other => panic!("unexpected predicate: {:?}", other),
+    }
+
+    let mut t = HashMap::new();
+    t.insert("a".to_string(), vec![1, 20]);
+    t.insert("b".to_string(), vec![2, 2]);
+    t.insert("c".to_string(), vec![3, 4]);
+    let mut table_data = HashMap::new();
+    table_data.insert("t".to_string(), t);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.betweens.len(), 2);
+    assert_eq!(compiled.range_checks.len(), 4);
+    assert_eq!(compiled.predicate.unwrap().num_leaves(), 3);
+}
@@ -0,0 +1,273 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::database::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+// CAST tests
+// Cast Gate (MockProver) and CAST(x AS type) in SQL
+
+/// Cast test circuit
+/// `output` overrides the honest result (with its remainder) to check the
+/// gate rejects it
+#[derive(Clone)]
+struct CastTestCircuit {
+    ops: Vec<CastOp>,
+    output: Option<(u64, u64)>,
+}
+
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    cast_config: CastConfig,
+}
+
+impl Circuit<Fr> for CastTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let cast_config = CastChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            cast_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let Some((out, r)) = self.output else {
+            let chip = CastChip::new(config.cast_config);
+            for (i, op) in self.ops.iter().enumerate() {
+                chip.cast(layouter.namespace(|| format!("cast {}", i)), op)?;
+            }
+            return Ok(());
+        };
+
+        // Same layout as `CastChip::cast`, with the given result
+        let c = &config.cast_config;
+        let op = &self.ops[0];
+        let divisor = op.divisor().unwrap();
+        let value = op.value;
+        let cells = layouter.assign_region(
+            || "tampered cast",
+            |mut region| {
+                c.cast_selector.enable(&mut region, 0)?;
+                let fixed = [
+                    (c.scale_column, op.multiplier().unwrap()),
+                    (c.bound_column, divisor),
+                ];
+                for (column, constant) in fixed {
+                    region.assign_fixed(
+                        || "constant",
+                        column,
+                        0,
+                        || Value::known(Fr::from(constant)),
+                    )?;
+                }
+                let constants = [(c.input_column, divisor / 2), (c.output_column, op.max)];
+                for (column, constant) in constants {
+                    region.assign_advice_from_constant(
+                        || "constant",
+                        column,
+                        1,
+                        Fr::from(constant),
+                    )?;
+                }
+                region.assign_advice(|| "x", c.input_column, 0, || value.map(Fr::from))?;
+                let field = |v: u64| Value::known(Fr::from(v));
+                let headroom = Value::known(Fr::from(op.max) - Fr::from(out));
+                let slack = Value::known(Fr::from(divisor) - Fr::from(1) - Fr::from(r));
+                Ok([
+                    region.assign_advice(|| "y", c.output_column, 0, || field(out))?,
+                    region.assign_advice(|| "r", c.remainder_column, 0, || field(r))?,
+                    region.assign_advice(|| "slack", c.slack_column, 0, || slack)?,
+                    region.assign_advice(|| "headroom", c.headroom_column, 0, || headroom)?,
+                ])
+            },
+        )?;
+        let range_check = RangeCheckChip::new(c.range_check_config.clone());
+        for cell in &cells {
+            range_check.decompose_cell(layouter.namespace(|| "bound"), cell)?;
+        }
+        Ok(())
+    }
+}
+
+fn cast(value: u64, from_scale: u32, to_scale: u32, max: u64) -> CastOp {
+    CastOp {
+        value: Value::known(value),
+        from_scale,
+        to_scale,
+        max,
+    }
+}
+
+fn run(
+    ops: Vec<CastOp>,
+    output: Option<(u64, u64)>,
+) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+    let circuit = CastTestCircuit { ops, output };
+    MockProver::run(10, &circuit, vec![vec![]])
+        .unwrap()
+        .verify()
+}
+
+#[test]
+fn test_cast_gate() {
+    // Test: Widening, narrowing with rounding and width bounds pass
+    let ops = vec![
+        cast(1_234, 2, 4, u64::MAX),
+        cast(12_345, 3, 2, 99_999),
+        cast(12_499, 3, 0, u64::MAX),
+        cast(255, 0, 0, 255),
+        cast(u64::MAX, 0, 0, u64::MAX),
+    ];
+    assert_eq!(run(ops, None), Ok(()));
+}
+
+#[test]
+fn test_cast_gate_rejects_tampering() {
+    // Test: Truncation instead of rounding and results above max fail
+    let op = cast(12_345, 3, 2, 99_999);
+    assert_eq!(op.apply(12_345), Some(1_235));
+    assert_eq!(run(vec![op.clone()], Some((1_235, 0))), Ok(()));
+    assert!(run(vec![op.clone()], Some((1_234, 10))).is_err());
+    assert!(run(vec![op], Some((1_236, 0))).is_err());
+
+    // 300 as TINYINT: the honest chip refuses, a forged witness fails
+    let op = cast(300, 0, 0, 255);
+    assert!(MockProver::run(
+        10,
+        &CastTestCircuit {
+            ops: vec![op.clone()],
+            output: None,
+        },
+        vec![vec![]],
+    )
+    .is_err());
+    assert!(run(vec![op], Some((300, 0))).is_err());
+}
+
+#[test]
+fn test_cast_types() {
+    // Test: Type names, widths and precisions
+    assert_eq!(CastType::parse("smallint"), Ok(CastType::Integer(16)));
+    assert_eq!(CastType::Integer(16).max(), 65_535);
+    assert_eq!(
+        CastType::parse("numeric(5, 2)"),
+        Ok(CastType::Decimal {
+            precision: 5,
+            scale: 2
+        })
+    );
+    assert_eq!(CastType::parse("decimal(5, 2)").unwrap().max(), 99_999);
+    assert_eq!(CastType::parse("varchar(10)"), Ok(CastType::Text));
+    assert!(CastType::parse("decimal(2, 3)").is_err());
+    assert!(CastType::parse("decimal(20)").is_err());
+    assert!(CastType::parse("int(4)").is_err());
+    assert!(CastType::parse("blob").is_err());
+}
+
+fn database() -> Database {
+    let mut db = Database::new();
+    db.create_table(
+        "items",
+        &[
+            ("qty", ColumnType::Integer),
+            ("price", ColumnType::Decimal(3)),
+            ("name", ColumnType::Text),
+            ("active", ColumnType::Boolean),
+        ],
+    )
+    .unwrap();
+    for (qty, price, name, active) in [(3, "1.005", "a", true), (200, "12.344", "b", false)] {
+        db.insert(
+            "items",
+            &[
+                Datum::Integer(qty),
+                Datum::Decimal(Decimal::parse(price, 3).unwrap()),
+                Datum::Text(name.to_string()),
+                Datum::Boolean(active),
+            ],
+        )
+        .unwrap();
+    }
+    db
+}
+
+#[test]
+fn test_cast_in_sql() {
+    // Test: CASTs compile to one operation per row, at the catalog scales
+    let db = database();
+    let query = SQLParser::parse(
+        "SELECT qty, CAST(price AS DECIMAL(10, 2)), CAST(qty AS TINYINT) FROM items",
+    )
+    .unwrap();
+    assert_eq!(query.columns.len(), 3);
+    assert!(query.is_provable());
+    let casts = query.casts.as_ref().unwrap();
+    assert_eq!(casts[0].column, "price");
+    assert_eq!(
+        casts[0].target,
+        CastType::Decimal {
+            precision: 10,
+            scale: 2
+        }
+    );
+
+    let compiled = SQLCompiler::compile_in(&query, &db).unwrap();
+    assert_eq!(compiled.casts.len(), 4);
+    let price = &compiled.casts[0];
+    assert_eq!(price.apply(1_005), Some(101));
+    assert_eq!(price.apply(12_344), Some(1_234));
+    assert_eq!(
+        (compiled.casts[0].from_scale, compiled.casts[0].to_scale),
+        (3, 2)
+    );
+
+    // Untyped table data: columns are integers
+    let mut items = HashMap::new();
+    items.insert("qty".to_string(), vec![3, 200]);
+    let tables = HashMap::from([("items".to_string(), items)]);
+    let query = SQLParser::parse("SELECT CAST(qty * 2 AS DECIMAL(6, 2)) FROM items").unwrap();
+    let result = ReferenceExecutor::execute(&query, &tables).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(600)], vec![Some(40_000)]]);
+}
+
+#[test]
+fn test_unprovable_casts_fail_at_plan_time() {
+    // Test: Text, boolean targets and values that don't fit are rejected
+    let db = database();
+    for sql in [
+        "SELECT CAST(name AS INT) FROM items",
+        "SELECT CAST(qty AS TEXT) FROM items",
+        "SELECT CAST(qty AS BOOLEAN) FROM items",
+        "SELECT CAST(price AS DECIMAL(3, 2)) FROM items",
+    ] {
+        let query = SQLParser::parse(sql).unwrap();
+        assert!(SQLCompiler::compile_in(&query, &db).is_err(), "{}", sql);
+    }
+    let query = SQLParser::parse("SELECT CAST(active AS INT) FROM items").unwrap();
+    assert_eq!(SQLCompiler::compile_in(&query, &db).unwrap().casts.len(), 2);
+
+    assert!(SQLParser::parse("SELECT CAST(qty) FROM items").is_err());
+    assert!(SQLParser::parse("SELECT CAST(qty AS float) FROM items").is_err());
+}
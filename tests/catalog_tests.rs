@@ -0,0 +1,95 @@
+use poneglyphdb::circuit::Decimal;
+use poneglyphdb::database::*;
+use poneglyphdb::sql::*;
+
+fn database() -> Database {
+    let mut db = Database::new();
+    db.create_table(
+        "orders",
+        &[
+            ("customer", ColumnType::Integer),
+            ("amount", ColumnType::Decimal(2)),
+            ("shipped", ColumnType::Boolean),
+        ],
+    )
+    .unwrap();
+    db.create_table(
+        "customers",
+        &[("id", ColumnType::Integer), ("name", ColumnType::Text)],
+    )
+    .unwrap();
+
+    for (customer, amount, shipped) in [(1, "50", true), (2, "1.20", false), (1, "0.3", true)] {
+        db.insert(
+            "orders",
+            &[
+                Datum::Integer(customer),
+                Datum::Decimal(Decimal::parse(amount, 2).unwrap()),
+                Datum::Boolean(shipped),
+            ],
+        )
+        .unwrap();
+    }
+    for (id, name) in [(1, "alice"), (2, "bob")] {
+        db.insert_named(
+            "customers",
+            &[
+                ("name", Datum::Text(name.to_string())),
+                ("id", Datum::Integer(id)),
+            ],
+        )
+        .unwrap();
+    }
+    db
+}
+
+#[test]
+fn test_columns_resolved_by_name() {
+    let db = database();
+    let data = db.table_data();
+    assert_eq!(data["orders"]["amount"], vec![5000, 120, 30]);
+    assert_eq!(data["orders"]["shipped"], vec![1, 0, 1]);
+    assert_eq!(data["customers"]["id"], vec![1, 2]);
+    assert_eq!(
+        db.resolve("orders", "AMOUNT").unwrap().ty,
+        ColumnType::Decimal(2)
+    );
+}
+
+#[test]
+fn test_compile_in_catalog() {
+    let db = database();
+    let query = SQLParser::parse(
+        "SELECT customer, SUM(amount) FROM orders WHERE amount < 1000 GROUP BY customer",
+    )
+    .unwrap();
+    let compiled = SQLCompiler::compile_in(&query, &db).unwrap();
+    assert!(!compiled.range_checks.is_empty());
+
+    let result = ReferenceExecutor::execute(&query, &db.table_data()).unwrap();
+    assert_eq!(
+        result.rows,
+        vec![vec![Some(1), Some(30)], vec![Some(2), Some(120)]]
+    );
+}
+
+#[test]
+fn test_compile_in_catalog_with_cte() {
+    let db = database();
+    let query = SQLParser::parse(
+        "WITH big AS (SELECT customer FROM orders WHERE amount > 100) SELECT customer FROM big",
+    )
+    .unwrap();
+    assert!(SQLCompiler::compile_in(&query, &db).is_ok());
+}
+
+#[test]
+fn test_compile_in_unknown_table() {
+    let db = database();
+    let query = SQLParser::parse("SELECT id FROM suppliers WHERE id < 10").unwrap();
+    let err = SQLCompiler::compile_in(&query, &db).unwrap_err();
+    assert!(err.contains("suppliers"));
+
+    let query = SQLParser::parse("SELECT customer FROM orders WHERE total < 10").unwrap();
+    assert!(SQLCompiler::compile_in(&query, &db).is_err());
+}
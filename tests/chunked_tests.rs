@@ -0,0 +1,98 @@
+use ff::Field;
+use halo2_proofs::{dev::MockProver, pasta::EqAffine, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::error::VerifyError;
+use poneglyphdb::recursive::*;
+
+// Chunked proving tests
+// Columns streamed in fixed-capacity chunks, each proven on its own and
+// merged by the combine circuit
+
+const K: u32 = 10;
+const CAPACITY: usize = 3;
+
+fn accepts<C: halo2_proofs::plonk::Circuit<Fr>>(circuit: &C, instance: Vec<Fr>) -> bool {
+    MockProver::run(K, circuit, vec![instance]).is_ok_and(|prover| prover.verify().is_ok())
+}
+
+#[test]
+fn test_chunk_circuit() {
+    // Test: A full and a padded chunk prove their commitment and summary
+    assert!(ChunkCircuit::capacity_for(K) >= CAPACITY);
+    for values in [vec![7, 2, 9], vec![4]] {
+        let summary = ChunkSummary::of(&values, CAPACITY).unwrap();
+        let circuit = ChunkCircuit::new(&values, CAPACITY);
+        assert!(accepts(&circuit, summary.instance()));
+
+        // Another summary for the same rows
+        let mut wrong = summary;
+        wrong.partial.count += 1;
+        assert!(!accepts(&circuit, wrong.instance()));
+    }
+
+    // Padding is not a zero row
+    let padded = ChunkSummary::of(&[4], CAPACITY).unwrap();
+    let zero = ChunkSummary::of(&[4, 0], CAPACITY).unwrap();
+    assert_ne!(padded.commitment, zero.commitment);
+    assert_eq!(padded.partial.min, 4);
+    assert_eq!(zero.partial.min, 0);
+}
+
+#[test]
+fn test_combine_circuit() {
+    // Test: The result must be the merge of the chunk summaries
+    let chunks: Vec<ChunkSummary> = [vec![7, 2, 9], vec![1, 8, 3], vec![5]]
+        .iter()
+        .map(|values| ChunkSummary::of(values, CAPACITY).unwrap())
+        .collect();
+    let commitments: Vec<Fr> = chunks.iter().map(|c| c.commitment).collect();
+    let claim = ChunkedClaim {
+        capacity: CAPACITY,
+        table_commitment: poneglyphdb::utils::poseidon_hash(&commitments),
+        result: PartialAggregate::of(&[7, 2, 9, 1, 8, 3, 5]).unwrap(),
+        chunks,
+    };
+    let circuit = CombineCircuit::new(claim.chunks.len());
+    assert!(accepts(&circuit, claim.combine_instance()));
+
+    let mut wrong = claim.clone();
+    wrong.result.max = 8;
+    assert!(!accepts(&circuit, wrong.combine_instance()));
+
+    let mut wrong = claim;
+    wrong.table_commitment += Fr::ONE;
+    assert!(!accepts(&circuit, wrong.combine_instance()));
+}
+
+#[test]
+fn test_chunked_prove_and_verify() {
+    // Test: A column of several chunks proves its merged summary
+    let params = Params::<EqAffine>::new(K);
+    let prover = ChunkedProver::new(&params, CAPACITY).unwrap();
+    let values = vec![12u64, 5, 40, 7, 7, 19, 3];
+    let (claim, proof) = prover.prove(&params, values.iter().copied()).unwrap();
+
+    assert_eq!(claim.chunks.len(), 3);
+    assert_eq!(claim.result, PartialAggregate::of(&values).unwrap());
+    assert_eq!(claim.result.evaluate(&AggregationType::Sum), Some(93));
+    assert_eq!(claim.verify(&params, &proof), Ok(()));
+
+    // A claim about other rows
+    let mut wrong = claim.clone();
+    wrong.result.sum += 1;
+    assert!(wrong.verify(&params, &proof).is_err());
+
+    let mut wrong = claim;
+    wrong.chunks.swap(0, 1);
+    assert!(matches!(
+        wrong.verify(&params, &proof),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+
+    // An empty column is one empty chunk
+    let (claim, proof) = prover.prove(&params, []).unwrap();
+    assert_eq!(claim.chunks.len(), 1);
+    assert_eq!(claim.result, PartialAggregate::default());
+    assert_eq!(claim.verify(&params, &proof), Ok(()));
+}
@@ -0,0 +1,95 @@
+use poneglyphdb::sql::*;
+use poneglyphdb::test_utils::fixtures::orders;
+
+#[test]
+fn test_parse_cte() {
+    let query = SQLParser::parse(
+        "WITH totals(customer, total) AS (SELECT customer, SUM(amount) FROM orders GROUP BY customer), \
+         big AS (SELECT customer FROM totals WHERE total > 100) \
+         SELECT customer FROM big",
+    )
+    .unwrap();
+    let ctes = query.ctes.as_ref().unwrap();
+    assert_eq!(ctes.len(), 2);
+    assert_eq!(ctes[0].name, "totals");
+    assert_eq!(
+        ctes[0].columns,
+        Some(vec!["customer".to_string(), "total".to_string()])
+    );
+    assert_eq!(ctes[0].query.from, "orders");
+    assert_eq!(ctes[1].columns, None);
+    assert_eq!(query.from, "big");
+
+    assert!(SQLParser::parse(
+        "WITH t AS (SELECT a FROM x), t AS (SELECT a FROM x) SELECT a FROM t"
+    )
+    .is_err());
+    assert!(SQLParser::parse("WITH t AS SELECT a FROM x SELECT a FROM t").is_err());
+}
+
+#[test]
+fn test_cte_execution() {
+    let query = SQLParser::parse(
+        "WITH totals(customer, total) AS (SELECT customer, SUM(amount) FROM orders GROUP BY customer) \
+         SELECT customer FROM totals WHERE total > 100 ORDER BY customer",
+    )
+    .unwrap();
+    let result = ReferenceExecutor::execute(&query, &orders()).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(2)]]);
+}
+
+#[test]
+fn test_cte_compiled_once() {
+    // `totals` is read by the main query and by its subquery
+    let query = SQLParser::parse(
+        "WITH totals(customer, total) AS (SELECT customer, SUM(amount) FROM orders GROUP BY customer), \
+         unused AS (SELECT amount FROM orders) \
+         SELECT customer FROM totals WHERE total = (SELECT MAX(total) FROM totals)",
+    )
+    .unwrap();
+    let compiled = SQLCompiler::compile(&query, &orders()).unwrap();
+
+    assert_eq!(compiled.ctes.len(), 1);
+    let totals = &compiled.ctes[0];
+    assert_eq!(totals.name, "totals");
+    assert_eq!(totals.references, 2);
+    assert_eq!(totals.inner.aggregations.len(), 1);
+
+    // The subquery consumes the relation instead of re-compiling the CTE body
+    assert_eq!(compiled.subqueries.len(), 1);
+    assert!(compiled.subqueries[0].inner.ctes.is_empty());
+    assert_eq!(compiled.subqueries[0].filter.result, vec![320]);
+
+    let result = ReferenceExecutor::execute(&query, &orders()).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(2)]]);
+}
+
+#[test]
+fn test_cte_commitment_binds_relation() {
+    let sql = |threshold: u64| {
+        format!(
+            "WITH small AS (SELECT customer, amount FROM orders WHERE amount < {}) SELECT customer FROM small",
+            threshold
+        )
+    };
+    let compile = |threshold| {
+        let query = SQLParser::parse(&sql(threshold)).unwrap();
+        SQLCompiler::compile(&query, &orders()).unwrap()
+    };
+    assert_eq!(
+        compile(100).ctes[0].commitment,
+        compile(100).ctes[0].commitment
+    );
+    assert_ne!(
+        compile(100).ctes[0].commitment,
+        compile(60).ctes[0].commitment
+    );
+}
+
+#[test]
+fn test_cte_shadowing_table_is_rejected() {
+    let query =
+        SQLParser::parse("WITH orders AS (SELECT amount FROM orders) SELECT amount FROM orders")
+            .unwrap();
+    assert!(SQLCompiler::compile(&query, &orders()).is_err());
+}
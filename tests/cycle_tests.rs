@@ -0,0 +1,61 @@
+use std::any::TypeId;
+
+use ff::Field;
+use halo2_proofs::{
+    pasta::{EpAffine, EqAffine, Fp, Fq},
+    poly::commitment::Params,
+};
+use poneglyphdb::recursive::*;
+
+fn partner_of_partner<C: CycleCurve>() -> TypeId {
+    TypeId::of::<<C::Partner as CycleCurve>::Partner>()
+}
+
+#[test]
+fn test_cycle_closes() {
+    assert_eq!(partner_of_partner::<EqAffine>(), TypeId::of::<EqAffine>());
+    assert_eq!(partner_of_partner::<EpAffine>(), TypeId::of::<EpAffine>());
+    assert_eq!(<EqAffine as CycleCurve>::NAME, "vesta");
+    assert_eq!(
+        <<EqAffine as CycleCurve>::Partner as CycleCurve>::NAME,
+        "pallas"
+    );
+}
+
+#[test]
+fn test_pallas_proof_inputs_verified_over_vesta() {
+    // Public inputs of a proof over Fp, bound by a proof over Fq
+    let k = 10;
+    let params: Params<EpAffine> = Params::new(k);
+    let inputs = [Fp::from(42), -Fp::ONE];
+    let instance = partner_instance::<EqAffine>(&inputs);
+
+    let circuit = CycleBindingCircuit::new(instance.clone());
+    let prover = CycleProver::<EpAffine>::new(&params, &circuit).unwrap();
+    let proof = prover
+        .prove(&params, &circuit, std::slice::from_ref(&instance))
+        .unwrap();
+    assert!(verify_on_curve(&params, prover.vk(), &proof, &[instance]).is_ok());
+
+    // Different Pallas-side inputs give a different Vesta instance
+    let other = partner_instance::<EqAffine>(&[Fp::from(43), -Fp::ONE]);
+    assert!(verify_on_curve(&params, prover.vk(), &proof, &[other]).is_err());
+}
+
+#[test]
+fn test_vesta_proof_inputs_verified_over_pallas() {
+    // Fq values >= p only cross the cycle as limbs
+    let k = 10;
+    let params: Params<EqAffine> = Params::new(k);
+    let inputs = [-Fq::ONE];
+    assert!(to_partner_scalar::<EpAffine>(&inputs[0]).is_none());
+    let instance = partner_instance::<EpAffine>(&inputs);
+    assert_eq!(from_partner_limbs::<EpAffine>(&instance), Some(inputs[0]));
+
+    let circuit = CycleBindingCircuit::new(instance.clone());
+    let prover = CycleProver::<EqAffine>::new(&params, &circuit).unwrap();
+    let proof = prover
+        .prove(&params, &circuit, std::slice::from_ref(&instance))
+        .unwrap();
+    assert!(verify_on_curve(&params, prover.vk(), &proof, &[instance]).is_ok());
+}
@@ -0,0 +1,117 @@
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+/// Decimal Gate test circuit
+/// SUM(price * quantity) with 2-decimal precision
+#[derive(Clone)]
+struct DecimalTestCircuit {
+    group_keys: Vec<u64>,
+    prices: Vec<u64>,
+    quantities: Vec<u64>,
+    divisor: u64,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    decimal_config: DecimalConfig,
+    aggregation_config: AggregationConfig,
+}
+
+impl Circuit<Fr> for DecimalTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            group_keys: vec![],
+            prices: vec![],
+            quantities: vec![],
+            divisor: self.divisor,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let decimal_config = DecimalChip::configure(meta, &poneglyph_config, &range_check_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let aggregation_config = AggregationChip::configure(meta, &poneglyph_config, &group_by_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            decimal_config,
+            aggregation_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        // Decimal comparison: price < 10.00
+        let decimal_chip = DecimalChip::new(config.decimal_config.clone());
+        for (i, &price) in self.prices.iter().enumerate() {
+            decimal_chip.check_less_than(
+                layouter.namespace(|| format!("price filter {}", i)),
+                Value::known(price),
+                2,
+                Decimal::new(10, 0),
+                u64::MAX,
+            )?;
+        }
+
+        // SUM(price * quantity)
+        let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        aggregation_chip.aggregate_decimal_products_and_verify(
+            layouter.namespace(|| "sum of products"),
+            &config.decimal_config,
+            &self.group_keys,
+            &self.prices,
+            &self.quantities,
+            self.divisor,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_decimal_sum_price_times_quantity() {
+    // Test: price (scale 2) * quantity (scale 0) summed at scale 2
+    let k = 12;
+    let circuit = DecimalTestCircuit {
+        group_keys: vec![1, 1, 2],
+        prices: vec![1999, 250, 1000],
+        quantities: vec![3, 4, 2],
+        divisor: Decimal::product_divisor(2, 0, 2).unwrap(),
+    };
+    let public_inputs = vec![vec![]];
+    let prover = MockProver::run(k, &circuit, public_inputs).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_decimal_sum_with_rounding() {
+    // Test: price (scale 2) * rate (scale 2) rounded back to scale 2
+    let k = 12;
+    let circuit = DecimalTestCircuit {
+        group_keys: vec![1, 1, 1],
+        prices: vec![125, 5, 999],
+        quantities: vec![125, 10, 50],
+        divisor: Decimal::product_divisor(2, 2, 2).unwrap(),
+    };
+    let public_inputs = vec![vec![]];
+    let prover = MockProver::run(k, &circuit, public_inputs).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
@@ -0,0 +1,135 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+/// VARIANCE / STDDEV test circuit
+#[derive(Clone)]
+struct DispersionTestCircuit {
+    group_keys: Vec<u64>,
+    values: Vec<u64>,
+    agg_type: AggregationType,
+    /// Expected result per group
+    expected: Vec<u64>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    aggregation_config: AggregationConfig,
+}
+
+impl Circuit<Fr> for DispersionTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let aggregation_config = AggregationChip::configure(
+            meta,
+            &poneglyph_config,
+            &group_by_config,
+            &range_check_config,
+        );
+
+        TestConfig {
+            poneglyph_config,
+            aggregation_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        let results = aggregation_chip.aggregate_dispersion_and_verify(
+            layouter.namespace(|| "dispersion aggregation"),
+            &self.group_keys,
+            &self.values,
+            &self.agg_type,
+        )?;
+
+        assert_eq!(results.len(), self.expected.len());
+        for (cell, &expected) in results.iter().zip(self.expected.iter()) {
+            cell.value().assert_if_known(|v| **v == Fr::from(expected));
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_dispersion_formula() {
+    // {2, 4, 4, 4, 5, 5, 7, 9}: mean 5, variance 4, standard deviation 2
+    let values = [2, 4, 4, 4, 5, 5, 7, 9];
+    assert_eq!(AggregationType::Variance(0).dispersion(&values), Some(4));
+    assert_eq!(AggregationType::StdDev(0).dispersion(&values), Some(2));
+
+    // {1, 2}: variance 0.25, standard deviation 0.5
+    assert_eq!(AggregationType::Variance(2).dispersion(&[1, 2]), Some(25));
+    assert_eq!(AggregationType::StdDev(2).dispersion(&[1, 2]), Some(50));
+    assert_eq!(AggregationType::Variance(0).dispersion(&[1, 2]), Some(0));
+
+    assert_eq!(AggregationType::Variance(0).dispersion(&[]), None);
+    assert_eq!(AggregationType::Sum.dispersion(&values), None);
+    assert_eq!(
+        AggregationType::from_str("stddev(3)"),
+        Some(AggregationType::StdDev(3))
+    );
+}
+
+#[test]
+fn test_variance_per_group() {
+    // Test: group 1 = {2, 4, 4, 4, 5, 5, 7, 9} -> 4.00, group 2 = {1, 2} -> 0.25
+    let k = 12;
+    let circuit = DispersionTestCircuit {
+        group_keys: vec![1, 1, 1, 1, 1, 1, 1, 1, 2, 2],
+        values: vec![2, 4, 4, 4, 5, 5, 7, 9, 1, 2],
+        agg_type: AggregationType::Variance(2),
+        expected: vec![400, 25],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_stddev() {
+    // Test: standard deviation of {1, 2, 3, 4} is √1.25 = 1.118...
+    let k = 12;
+    let circuit = DispersionTestCircuit {
+        group_keys: vec![0; 4],
+        values: vec![3, 1, 4, 2],
+        agg_type: AggregationType::StdDev(3),
+        expected: vec![1118],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_single_value_group() {
+    // Test: a single value has zero spread
+    let k = 12;
+    let circuit = DispersionTestCircuit {
+        group_keys: vec![7],
+        values: vec![42],
+        agg_type: AggregationType::StdDev(0),
+        expected: vec![0],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
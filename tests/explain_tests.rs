@@ -0,0 +1,74 @@
+use poneglyphdb::optimization::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+// EXPLAIN tests
+// Cost reports of compiled queries: plan, chips, rows per operator, circuit
+// size, proof size and proving time
+
+fn tables(rows: u64) -> HashMap<String, HashMap<String, Vec<u64>>> {
+    let mut orders = HashMap::new();
+    orders.insert("customer".to_string(), (0..rows).map(|i| i % 7).collect());
+    orders.insert(
+        "amount".to_string(),
+        (0..rows).map(|i| i * 13 % 250).collect(),
+    );
+    let mut table_data = HashMap::new();
+    table_data.insert("orders".to_string(), orders);
+    table_data
+}
+
+#[test]
+fn test_explain_report() {
+    // Test: The report lists the planned operators, their chips and rows
+    let table_data = tables(8);
+    let query = SQLParser::parse(
+        "SELECT customer, SUM(amount) FROM orders WHERE amount > 40 \
+         GROUP BY customer ORDER BY customer",
+    )
+    .unwrap();
+    let report = explain(&query, &table_data).unwrap();
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    let plan = QueryPlan::of(&query, &compiled);
+    assert_eq!(report.advice_rows, report.plan.total_rows());
+    assert!(report.advice_rows <= plan.total_rows());
+    assert_eq!(report.k, report.plan.min_k());
+    for node in &report.plan.nodes {
+        for chip in &node.chips {
+            assert!(report.chips.contains(chip));
+        }
+    }
+    assert!(report.chips.contains(&"sort"));
+    assert!(report.proof_size > 0);
+
+    let text = report.to_string();
+    assert!(text.contains("GroupBy"));
+    assert!(text.contains(&format!("k = {}", report.k)));
+    assert!(text.contains("proof size"));
+}
+
+#[test]
+fn test_explain_scales_with_rows() {
+    // Test: More rows need a larger circuit, a larger proof and more time
+    let query =
+        SQLParser::parse("SELECT amount FROM orders WHERE amount > 40 ORDER BY amount").unwrap();
+    let small = explain(&query, &tables(16)).unwrap();
+    let large = explain(&query, &tables(4096)).unwrap();
+
+    assert!(large.advice_rows > small.advice_rows);
+    assert!(large.k > small.k);
+    // Two IPA round commitments per extra k
+    assert_eq!(
+        large.proof_size - small.proof_size,
+        64 * (large.k - small.k) as usize
+    );
+    assert!(large.proving_time > small.proving_time);
+}
+
+#[test]
+fn test_explain_errors() {
+    // Test: Unknown tables are reported instead of estimated
+    let query = SQLParser::parse("SELECT amount FROM missing").unwrap();
+    assert!(explain(&query, &tables(4)).is_err());
+}
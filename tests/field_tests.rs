@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+
+use ff::PrimeField;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::{pallas, vesta};
+use poneglyphdb::circuit::*;
+
+/// Chips instantiated over an arbitrary prime field
+#[derive(Clone)]
+struct FieldTestCircuit<F: PrimeField> {
+    values: Vec<u64>,
+    threshold: u64,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    range_check_config: RangeCheckConfig,
+    sort_config: SortConfig,
+    group_by_config: GroupByConfig,
+}
+
+impl<F: PrimeField> Circuit<F> for FieldTestCircuit<F> {
+    type Config = TestConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let sort_config = SortChip::configure(meta, &poneglyph_config, &range_check_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            range_check_config,
+            sort_config,
+            group_by_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::new(config.range_check_config);
+        for &value in &self.values {
+            range_check_chip.check_less_than(
+                layouter.namespace(|| "range check"),
+                Value::known(value),
+                self.threshold,
+                self.threshold + 1000,
+            )?;
+        }
+
+        let mut sorted = self.values.clone();
+        sorted.sort_unstable();
+        let sort_chip = SortChip::new(config.sort_config);
+        let output = sort_chip.sort_and_verify(
+            layouter.namespace(|| "sort"),
+            self.values.iter().map(|&v| Value::known(v)).collect(),
+            sorted.clone(),
+        )?;
+        for (cell, &expected) in output.iter().zip(sorted.iter()) {
+            cell.value().assert_if_known(|v| **v == F::from(expected));
+        }
+
+        let group_by_chip = GroupByChip::new(config.group_by_config);
+        group_by_chip.group_and_verify(layouter.namespace(|| "group by"), &sorted)?;
+
+        Ok(())
+    }
+}
+
+fn circuit<F: PrimeField>(values: Vec<u64>, threshold: u64) -> FieldTestCircuit<F> {
+    FieldTestCircuit {
+        values,
+        threshold,
+        _marker: PhantomData,
+    }
+}
+
+#[test]
+fn test_chips_over_pallas_base() {
+    let k = 10;
+    let circuit = circuit::<pallas::Base>(vec![7, 3, 7, 1], 10);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_chips_over_vesta_base() {
+    let k = 10;
+    let circuit = circuit::<vesta::Base>(vec![7, 3, 7, 1], 10);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_constraint_system_is_field_independent() {
+    let mut pallas_meta = ConstraintSystem::<pallas::Base>::default();
+    let pallas_config = FieldTestCircuit::<pallas::Base>::configure(&mut pallas_meta);
+    let mut vesta_meta = ConstraintSystem::<vesta::Base>::default();
+    let vesta_config = FieldTestCircuit::<vesta::Base>::configure(&mut vesta_meta);
+
+    assert_eq!(pallas_meta.degree(), vesta_meta.degree());
+    assert_eq!(pallas_meta.minimum_rows(), vesta_meta.minimum_rows());
+    let (pallas_config, vesta_config) = (
+        &pallas_config.poneglyph_config,
+        &vesta_config.poneglyph_config,
+    );
+    assert_eq!(
+        pallas_config.advice_columns().len(),
+        vesta_config.advice_columns().len()
+    );
+    assert_eq!(
+        pallas_config.selectors().len(),
+        vesta_config.selectors().len()
+    );
+    assert_eq!(
+        pallas_config.table_columns().len(),
+        vesta_config.table_columns().len()
+    );
+}
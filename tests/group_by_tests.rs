@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
+use ff::Field;
 use halo2_proofs::{
     dev::MockProver,
     plonk::{Circuit, ConstraintSystem, Error},
 };
 use pasta_curves::pallas::Base as Fr;
 use poneglyphdb::circuit::*;
+use poneglyphdb::sql::{SQLCompiler, SQLParser};
 
 /// Group-By Gate test circuit
 /// According to Paper Section 4.3: Group verification with Boundary Check
@@ -16,7 +20,6 @@ struct GroupByTestCircuit {
 #[derive(Clone)]
 struct TestConfig {
     poneglyph_config: PoneglyphConfig,
-    range_check_config: RangeCheckConfig,
     group_by_config: GroupByConfig,
 }
 
@@ -37,7 +40,6 @@ impl Circuit<Fr> for GroupByTestCircuit {
         
         TestConfig {
             poneglyph_config,
-            range_check_config,
             group_by_config,
         }
     }
@@ -159,3 +161,112 @@ fn test_group_by_mixed_sizes() {
     assert_eq!(prover.verify(), Ok(()));
 }
 
+
+/// Composite key test circuit: `keys` are (region, year)-like tuples,
+/// `boundaries` the expected flag of each consecutive pair (1 = same group)
+#[derive(Clone)]
+struct CompositeGroupByCircuit {
+    keys: Vec<Vec<u64>>,
+    boundaries: Vec<u64>,
+}
+
+impl Circuit<Fr> for CompositeGroupByCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        GroupByTestCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let group_by_chip = GroupByChip::new(config.group_by_config);
+        let boundaries = group_by_chip.group_composite_and_verify(
+            layouter.namespace(|| "composite group by"),
+            &self.keys,
+        )?;
+        for (cell, &expected) in boundaries.iter().zip(&self.boundaries) {
+            cell.value().map(|b| assert_eq!(*b, Fr::from(expected)));
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_group_by_composite_keys() {
+    // Test: (region, year) groups split whenever either column changes
+    let circuit = CompositeGroupByCircuit {
+        keys: vec![
+            vec![1, 2020],
+            vec![1, 2020],
+            vec![1, 2021],
+            vec![2, 2020],
+            vec![2, 2020],
+        ],
+        boundaries: vec![1, 0, 0, 1],
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_group_by_composite_key_width() {
+    // Test: tuples must share one width of 1 to MAX_GROUP_KEY_COLUMNS columns
+    for keys in [
+        vec![vec![1, 2], vec![1]],
+        vec![vec![1, 2, 3, 4]],
+        vec![vec![]],
+    ] {
+        let circuit = CompositeGroupByCircuit {
+            keys,
+            boundaries: vec![],
+        };
+        assert!(MockProver::run(10, &circuit, vec![vec![]]).is_err());
+    }
+}
+
+#[test]
+fn test_composite_key_encoding() {
+    // Test: packing keeps lexicographic order and equality
+    let a: Fr = composite_key(&[1, u64::MAX]);
+    let b: Fr = composite_key(&[2, 0]);
+    assert_eq!(b - a, Fr::ONE);
+    assert_eq!(composite_key::<Fr>(&[7]), Fr::from(7));
+    assert_ne!(composite_key::<Fr>(&[0, 1]), composite_key::<Fr>(&[1, 0]));
+}
+
+#[test]
+fn test_compile_multi_column_group_by() {
+    // Test: GROUP BY region, year compiles to one group-by over key tuples
+    let mut sales = HashMap::new();
+    sales.insert("region".to_string(), vec![2, 1, 1, 2, 1]);
+    sales.insert("year".to_string(), vec![2020, 2021, 2020, 2020, 2021]);
+    sales.insert("amount".to_string(), vec![10, 20, 30, 40, 50]);
+    let mut tables = HashMap::new();
+    tables.insert("sales".to_string(), sales);
+
+    let query =
+        SQLParser::parse("SELECT region, year, SUM(amount) FROM sales GROUP BY region, year")
+            .unwrap();
+    assert!(query.is_provable());
+    let compiled = SQLCompiler::compile(&query, &tables).unwrap();
+    assert_eq!(compiled.group_bys.len(), 1);
+    assert_eq!(
+        compiled.group_bys[0].group_keys,
+        vec![vec![1, 2020], vec![1, 2021], vec![2, 2020]]
+    );
+
+    // Aggregation keys are the tuple ranks
+    let sum = &compiled.aggregations[0];
+    assert_eq!(sum.group_keys, vec![2, 1, 0, 2, 1]);
+}
@@ -0,0 +1,82 @@
+use poneglyphdb::database::*;
+use poneglyphdb::sql::*;
+
+// Identifier tests
+// Quoted identifiers, case folding and reserved words
+
+fn database() -> Database {
+    let mut db = Database::new();
+    db.create_table(
+        "Sales",
+        &[
+            ("Order", ColumnType::Integer),
+            ("UnitPrice", ColumnType::Integer),
+            ("unit price", ColumnType::Integer),
+        ],
+    )
+    .unwrap();
+    for (order, price) in [(1, 30), (2, 10), (3, 20)] {
+        db.insert(
+            "sales",
+            &[
+                Datum::Integer(order),
+                Datum::Integer(price),
+                Datum::Integer(price),
+            ],
+        )
+        .unwrap();
+    }
+    db
+}
+
+#[test]
+fn test_quoted_reserved_words() {
+    // Test: Quoted reserved words are names, unquoted ones are rejected
+    let query =
+        SQLParser::parse("SELECT \"Order\", SUM(\"unit price\") FROM \"Sales\" WHERE \"Order\" > 1 ORDER BY \"Order\" DESC")
+            .unwrap();
+    assert_eq!(query.columns, vec!["order", "sum(unit price)"]);
+    assert_eq!(query.from, "sales");
+    assert_eq!(query.order_by.as_ref().unwrap()[0].column, "order");
+    assert_eq!(query.aggregations.as_ref().unwrap()[0].column, "unit price");
+    assert!(matches!(
+        query.where_clause,
+        Some(WhereClause::GreaterThan { ref column, value: 1 }) if column == "order"
+    ));
+
+    let err = SQLParser::parse("SELECT order FROM sales").unwrap_err();
+    assert!(err.contains("'order' is a reserved word"), "{}", err);
+    assert!(SQLParser::parse("SELECT id FROM sales ORDER BY order").is_err());
+    assert!(SQLParser::parse("SELECT id FROM t WHERE t.id IN (SELECT id FROM \"group\")").is_ok());
+}
+
+#[test]
+fn test_case_folding() {
+    // Test: Unquoted and quoted names fold to the case-insensitive catalog
+    let db = database();
+    for sql in [
+        "SELECT UnitPrice FROM Sales ORDER BY UNITPRICE",
+        "SELECT \"UnitPrice\" FROM \"SALES\" ORDER BY \"unitprice\"",
+    ] {
+        let query = SQLParser::parse(sql).unwrap();
+        assert_eq!(query.columns, vec!["unitprice"]);
+        assert_eq!(query.from, "sales");
+        let compiled = SQLCompiler::compile_in(&query, &db).unwrap();
+        assert_eq!(compiled.sorts.len(), 1);
+    }
+}
+
+#[test]
+fn test_quoting_edge_cases() {
+    // Test: Escaped quotes, quotes inside literals and malformed names
+    let query = SQLParser::parse("SELECT \"a\"\"b\" FROM t WHERE name LIKE '\"Bo%'").unwrap();
+    assert_eq!(query.columns, vec!["a\"b"]);
+    assert!(matches!(
+        query.where_clause,
+        Some(WhereClause::Like { ref pattern, .. }) if pattern == "\"Bo%"
+    ));
+
+    assert!(SQLParser::parse("SELECT \"order FROM t").is_err());
+    assert!(SQLParser::parse("SELECT \"\" FROM t").is_err());
+    assert!(SQLParser::parse("SELECT __quoted_0__ FROM t").is_err());
+}
@@ -0,0 +1,111 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// IN-list Gate test circuit
+#[derive(Clone)]
+struct InListTestCircuit {
+    values: Vec<u64>,
+    list: Vec<u64>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    in_list_config: InListConfig,
+}
+
+impl Circuit<Fr> for InListTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let in_list_config = InListChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            in_list_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup tables
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        let in_list_chip = InListChip::new(config.in_list_config);
+        in_list_chip.load_list(&mut layouter, &self.list)?;
+
+        let selected = in_list_chip.filter_in(
+            layouter.namespace(|| "in list"),
+            &self.values,
+            &self.list,
+        )?;
+        for (cell, value) in selected.iter().zip(self.values.iter()) {
+            let expected = Fr::from(self.list.contains(value) as u64);
+            cell.value().assert_if_known(|v| **v == expected);
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_in_list_filter() {
+    // Test: WHERE status IN (2, 5, 7)
+    let k = 10;
+    let circuit = InListTestCircuit {
+        values: vec![5, 1, 7, 0, 2, 9],
+        list: vec![2, 5, 7],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_in_list_with_zero() {
+    // Test: 0 is only a member if it is listed
+    let k = 10;
+    let circuit = InListTestCircuit {
+        values: vec![0, 3, 4],
+        list: vec![0, 3],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_in_list_compiles() {
+    // Test: IN list is parsed and compiled into one operation
+    let query = SQLParser::parse("SELECT status FROM orders WHERE status IN (2, 5, 7)").unwrap();
+    match &query.where_clause {
+        Some(WhereClause::In { column, values }) => {
+            assert_eq!(column, "status");
+            assert_eq!(values, &vec![2, 5, 7]);
+        }
+        other => panic!("unexpected WHERE clause: {:?}", other),
+    }
+
+    let mut orders = HashMap::new();
+    orders.insert("status".to_string(), vec![5, 1, 7]);
+    let mut table_data = HashMap::new();
+    table_data.insert("orders".to_string(), orders);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.in_lists.len(), 1);
+    assert_eq!(compiled.in_lists[0].values, vec![5, 1, 7]);
+    assert_eq!(compiled.in_lists[0].list, vec![2, 5, 7]);
+}
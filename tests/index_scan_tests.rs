@@ -0,0 +1,113 @@
+use halo2_proofs::dev::MockProver;
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::database::*;
+use poneglyphdb::sql::*;
+
+// Index scan tests
+// Pushed-down scans over a sorted index are proven complete with
+// `IndexScanCircuit` (MockProver)
+
+const K: u32 = 11;
+const DEPTH: usize = 4;
+
+fn table() -> DatabaseTable {
+    let mut table = DatabaseTable::new(
+        "orders".to_string(),
+        vec!["id".to_string(), "amount".to_string()],
+    );
+    table.data = [70, 15, 42, 99, 42, 8, 63, 30]
+        .iter()
+        .enumerate()
+        .map(|(id, &amount)| vec![id as u64, amount])
+        .collect();
+    table
+}
+
+fn accepts(scan: &IndexScan, instance: Vec<Fr>) -> bool {
+    MockProver::run(K, &scan.circuit(), vec![instance]).is_ok_and(|prover| prover.verify().is_ok())
+}
+
+#[test]
+fn test_scan_returns_qualifying_rows() {
+    // Test: The candidates are exactly the qualifying rows and the proof verifies
+    let table = table();
+    let index = SortedIndex::build(&table, "amount", DEPTH).unwrap();
+    let commitment = index.commitment();
+    assert!(commitment.matches(&table.data.iter().map(|r| r[1]).collect::<Vec<_>>()));
+
+    let range = ScanRange::between(30, 65);
+    let scan = index.scan(range);
+    let expected: Vec<u64> = table
+        .data
+        .iter()
+        .filter(|row| range.contains(row[1]))
+        .map(|row| row[0])
+        .collect();
+    assert_eq!(scan.rows(), expected);
+    assert_eq!(scan.table(&table).data.len(), 4);
+    assert!(scan.candidates.iter().all(|c| c.verify(commitment.root)));
+    assert!(accepts(&scan, scan.claim().instance()));
+
+    // One side unbounded: only the other boundary is opened
+    let scan = index.scan(ScanRange::greater_than(60));
+    assert!(scan.upper.is_none());
+    assert_eq!(scan.rows(), vec![0, 3, 6]);
+    assert!(accepts(&scan, scan.claim().instance()));
+}
+
+#[test]
+fn test_scan_rejects_skipped_qualifying_rows() {
+    // Test: A claim that drops a qualifying row, or another root, fails
+    let table = table();
+    let index = SortedIndex::build(&table, "amount", DEPTH).unwrap();
+    let scan = index.scan(ScanRange::between(30, 65));
+
+    // Shrink the run: the new upper boundary (63) is in range
+    let mut short = scan.clone();
+    short.upper = short.candidates.pop();
+    short.positions.end -= 1;
+    assert!(!accepts(&short, short.claim().instance()));
+
+    // Honest witness, claim with a wider range
+    let mut claim = scan.claim();
+    claim.range.high = Some(70);
+    assert!(!accepts(&scan, claim.instance()));
+
+    // Honest witness, claim with the upper boundary moved
+    let mut claim = scan.claim();
+    claim.positions.end += 1;
+    assert!(!accepts(&scan, claim.instance()));
+
+    // Another index
+    let mut claim = scan.claim();
+    claim.commitment.root = Fr::from(1);
+    assert!(!accepts(&scan, claim.instance()));
+}
+
+#[test]
+fn test_scan_range_from_where() {
+    // Test: WHERE clauses on the indexed column become scan ranges
+    let range = |sql: &str| {
+        SQLParser::parse(sql)
+            .unwrap()
+            .where_clause
+            .and_then(|clause| clause.scan_range("amount"))
+    };
+    assert_eq!(
+        range("SELECT id FROM orders WHERE amount BETWEEN 30 AND 65"),
+        Some(ScanRange::between(30, 65))
+    );
+    assert_eq!(
+        range("SELECT id FROM orders WHERE amount > 10 AND amount < 50 AND id > 2"),
+        Some(ScanRange::between(11, 49))
+    );
+    assert_eq!(
+        range("SELECT id FROM orders WHERE amount = 8 OR amount = 42"),
+        Some(ScanRange::between(8, 42))
+    );
+    assert_eq!(
+        range("SELECT id FROM orders WHERE amount = 8 OR id = 3"),
+        None
+    );
+    assert_eq!(range("SELECT id FROM orders WHERE id > 3"), None);
+}
@@ -19,8 +19,6 @@ struct JoinTestCircuit {
 #[derive(Clone)]
 struct TestConfig {
     poneglyph_config: PoneglyphConfig,
-    range_check_config: RangeCheckConfig,
-    sort_config: SortConfig,
     join_config: JoinConfig,
 }
 
@@ -45,8 +43,6 @@ impl Circuit<Fr> for JoinTestCircuit {
         
         TestConfig {
             poneglyph_config,
-            range_check_config,
-            sort_config,
             join_config,
         }
     }
@@ -0,0 +1,148 @@
+use halo2_proofs::{
+    circuit::Value,
+    dev::MockProver,
+    pasta::EqAffine,
+    plonk::{keygen_vk, Circuit, ConstraintSystem, Error},
+    poly::commitment::Params,
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::recursive::vk_fingerprint;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// LIKE Gate test circuit
+#[derive(Clone)]
+struct LikeTestCircuit {
+    values: Vec<u64>,
+    pattern: LikePattern,
+    expected: Vec<bool>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    like_config: LikeConfig,
+}
+
+impl Circuit<Fr> for LikeTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let like_config = LikeChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            like_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let like_chip = LikeChip::new(config.like_config);
+        let column = config.poneglyph_config.advice[9];
+        for (i, &value) in self.values.iter().enumerate() {
+            // Scanned column value
+            let cell = layouter.assign_region(
+                || format!("value {}", i),
+                |mut region| {
+                    region.assign_advice(|| "value", column, 0, || Value::known(Fr::from(value)))
+                },
+            )?;
+            let result = like_chip.check_like(
+                layouter.namespace(|| format!("like {}", i)),
+                &cell,
+                &self.pattern,
+            )?;
+            let expected = Fr::from(self.expected[i] as u64);
+            result.value().assert_if_known(|v| **v == expected);
+        }
+
+        Ok(())
+    }
+}
+
+fn like_circuit(pattern: &str, values: &[&str], expected: &[bool]) -> LikeTestCircuit {
+    LikeTestCircuit {
+        values: values
+            .iter()
+            .map(|v| encode_fixed_string(v).unwrap())
+            .collect(),
+        pattern: LikePattern::parse(pattern).unwrap(),
+        expected: expected.to_vec(),
+    }
+}
+
+#[test]
+fn test_like_prefix() {
+    // Test: WHERE name LIKE 'abc%'
+    let k = 10;
+    let circuit = like_circuit("abc%", &["abcdef", "abc", "abd", "ab"], &[true, true, false, false]);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_like_suffix_and_wildcard() {
+    // Test: WHERE name LIKE '%_ef' (suffix with single-byte wildcard)
+    let k = 10;
+    let circuit = like_circuit("%_ef", &["abcdef", "xef", "ef", "abcdeg"], &[true, true, false, false]);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_like_layout_independent_of_length() {
+    // Test: the verifying key doesn't reveal the length of the private string
+    let k = 10;
+    let params = Params::<EqAffine>::new(k);
+    let short = like_circuit("%ef", &["ef"], &[true]);
+    let long = like_circuit("%ef", &["abcdef"], &[true]);
+    let short_vk = keygen_vk(&params, &short).unwrap();
+    let long_vk = keygen_vk(&params, &long).unwrap();
+    assert_eq!(vk_fingerprint(&short_vk), vk_fingerprint(&long_vk));
+}
+
+#[test]
+fn test_sql_like_compiles() {
+    // Test: LIKE is parsed case-sensitively and compiled per row
+    let query = SQLParser::parse("SELECT name FROM users WHERE name LIKE 'Ab%'").unwrap();
+    match &query.where_clause {
+        Some(WhereClause::Like { column, pattern }) => {
+            assert_eq!(column, "name");
+            assert_eq!(pattern, "Ab%");
+        }
+        other => panic!("unexpected WHERE clause: {:?}", other),
+    }
+
+    let names: Vec<u64> = ["Abe", "abe", "Al"]
+        .iter()
+        .map(|v| encode_fixed_string(v).unwrap())
+        .collect();
+    let mut users = HashMap::new();
+    users.insert("name".to_string(), names);
+    let mut table_data = HashMap::new();
+    table_data.insert("users".to_string(), users);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    let matches: Vec<bool> = compiled
+        .likes
+        .iter()
+        .map(|op| op.pattern.matches(op.value))
+        .collect();
+    assert_eq!(matches, vec![true, false, false]);
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use poneglyphdb::sql::*;
+
+// Query lint tests
+// Constructs with surprising proof semantics are reported, with or without
+// the table data
+
+fn lint(sql: &str, table_data: &HashMap<String, HashMap<String, Vec<u64>>>) -> Vec<LintKind> {
+    let query = SQLParser::parse(sql).unwrap();
+    Linter::new(table_data)
+        .lint(&query)
+        .into_iter()
+        .map(|lint| lint.kind)
+        .collect()
+}
+
+fn orders(price: Vec<u64>, qty: Vec<u64>) -> HashMap<String, HashMap<String, Vec<u64>>> {
+    let columns = HashMap::from([("price".to_string(), price), ("qty".to_string(), qty)]);
+    HashMap::from([("orders".to_string(), columns)])
+}
+
+#[test]
+fn test_clean_queries() {
+    // Test: Ordinary provable queries have no lints
+    let data = orders(vec![10, 20], vec![1, 2]);
+    for sql in [
+        "SELECT SUM(price) FROM orders WHERE qty > 1",
+        "SELECT SUM(price * qty) FROM orders",
+        "SELECT price FROM orders ORDER BY price LIMIT 1",
+        "SELECT price * 2 FROM orders",
+    ] {
+        assert_eq!(lint(sql, &data), vec![], "{}", sql);
+    }
+}
+
+#[test]
+fn test_sum_overflow() {
+    // Test: A SUM whose bound reaches 2^64 is reported
+    let large = orders(vec![u64::MAX / 2, u64::MAX / 2, 1], vec![1, 1, 1]);
+    assert_eq!(
+        lint("SELECT SUM(price) FROM orders", &large),
+        vec![LintKind::SumOverflow]
+    );
+    assert_eq!(lint("SELECT SUM(qty) FROM orders", &large), vec![]);
+
+    // Without data only the per-row width of the expression is known
+    let none = HashMap::new();
+    assert_eq!(lint("SELECT SUM(price) FROM orders", &none), vec![]);
+    assert_eq!(
+        lint("SELECT SUM(price * qty) FROM orders", &none),
+        vec![LintKind::SumOverflow]
+    );
+}
+
+#[test]
+fn test_unordered_limit() {
+    // Test: LIMIT or OFFSET without ORDER BY is reported, nested queries too
+    let none = HashMap::new();
+    assert_eq!(
+        lint("SELECT price FROM orders LIMIT 5", &none),
+        vec![LintKind::UnorderedLimit]
+    );
+    assert_eq!(
+        lint(
+            "SELECT price FROM orders UNION SELECT price FROM archive LIMIT 5",
+            &none
+        ),
+        vec![LintKind::UnorderedLimit]
+    );
+}
+
+#[test]
+fn test_fractional_literal() {
+    // Test: A fractional literal reads as a column name and is reported once
+    let data = orders(vec![10, 20], vec![1, 2]);
+    assert_eq!(
+        lint("SELECT price * 1.5 FROM orders", &data),
+        vec![LintKind::FractionalLiteral]
+    );
+    assert_eq!(
+        lint("SELECT qty * 2.5, price + 2.5 FROM orders", &data),
+        vec![LintKind::FractionalLiteral]
+    );
+    // PERCENTILE_DISC takes its fraction as an argument, not an expression
+    assert_eq!(
+        lint(
+            "SELECT PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY price) FROM orders",
+            &data
+        ),
+        vec![]
+    );
+}
@@ -0,0 +1,124 @@
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+// Lookup table manager tests
+// Tables registered through `PoneglyphConfig::lookup_tables`: sharing of
+// identical tables between chips and additional range tables
+
+const NIBBLE_TABLE: LookupTableSpec = LookupTableSpec::Range { bits: 4 };
+
+/// Looks every value up in a 4-bit range table
+#[derive(Clone)]
+struct NibbleCircuit {
+    values: Vec<u64>,
+}
+
+#[derive(Clone)]
+struct NibbleConfig {
+    poneglyph_config: PoneglyphConfig,
+    value: Column<Advice>,
+    selector: Selector,
+}
+
+impl Circuit<Fr> for NibbleCircuit {
+    type Config = NibbleConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let table = poneglyph_config
+            .lookup_tables
+            .register(meta, "nibble", NIBBLE_TABLE)[0];
+        let value = meta.advice_column();
+        let selector = meta.complex_selector();
+        meta.lookup(|meta| {
+            let s = meta.query_selector(selector);
+            let v = meta.query_advice(value, Rotation::cur());
+            vec![(s * v, table)]
+        });
+
+        NibbleConfig {
+            poneglyph_config,
+            value,
+            selector,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Loads the 8-bit and the 4-bit table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+        layouter.assign_region(
+            || "nibbles",
+            |mut region| {
+                for (i, &v) in self.values.iter().enumerate() {
+                    config.selector.enable(&mut region, i)?;
+                    region.assign_advice(
+                        || format!("value_{}", i),
+                        config.value,
+                        i,
+                        || Value::known(Fr::from(v)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[test]
+fn test_identical_tables_are_shared() {
+    // Test: Chips registering the same table get the same columns and no new fixed columns
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let config = PoneglyphConfig::configure(&mut meta);
+    assert_eq!(
+        config
+            .lookup_tables
+            .register(&mut meta, "like", LookupTableSpec::Range { bits: 8 }),
+        vec![config.lookup_table]
+    );
+
+    let first = InListChip::configure(&mut meta, &config);
+    let table_columns = config.table_columns().len();
+    let second = InListChip::configure(&mut meta, &config);
+    assert_eq!(config.table_columns().len(), table_columns);
+    assert_eq!(
+        (first.tag_table, first.list_table),
+        (second.tag_table, second.list_table)
+    );
+
+    let tables = config.lookup_tables.tables_registered();
+    assert_eq!(tables.len(), 2);
+    assert_eq!(tables[0].users, vec!["range_check", "like"]);
+    assert_eq!(tables[1].spec, IN_LIST_TABLE);
+}
+
+#[test]
+fn test_additional_range_table() {
+    // Test: A 4-bit table next to the 8-bit one accepts 0-15 only
+    let k = 10;
+    let circuit = NibbleCircuit {
+        values: vec![0, 7, 15],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let circuit = NibbleCircuit {
+        values: vec![3, 16],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert!(prover.verify().is_err());
+}
@@ -0,0 +1,149 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// MEDIAN / PERCENTILE test circuit
+#[derive(Clone)]
+struct RankAggregationTestCircuit {
+    group_keys: Vec<u64>,
+    values: Vec<u64>,
+    agg_type: AggregationType,
+    /// Expected result per group
+    expected: Vec<u64>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    sort_config: SortConfig,
+    aggregation_config: AggregationConfig,
+}
+
+impl Circuit<Fr> for RankAggregationTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let sort_config = SortChip::configure(meta, &poneglyph_config, &range_check_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let aggregation_config = AggregationChip::configure(
+            meta,
+            &poneglyph_config,
+            &group_by_config,
+            &range_check_config,
+        );
+
+        TestConfig {
+            poneglyph_config,
+            sort_config,
+            aggregation_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let sort_chip = SortChip::new(config.sort_config);
+        let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        let results = aggregation_chip.aggregate_rank_and_verify(
+            layouter.namespace(|| "rank aggregation"),
+            &sort_chip,
+            &self.group_keys,
+            &self.values,
+            &self.agg_type,
+        )?;
+
+        assert_eq!(results.len(), self.expected.len());
+        for (cell, &expected) in results.iter().zip(self.expected.iter()) {
+            cell.value().assert_if_known(|v| **v == Fr::from(expected));
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_rank_index() {
+    assert_eq!(AggregationType::Median.rank_index(5), Some(2));
+    assert_eq!(AggregationType::Median.rank_index(4), Some(1));
+    assert_eq!(AggregationType::Percentile(90).rank_index(10), Some(8));
+    assert_eq!(AggregationType::Percentile(0).rank_index(3), Some(0));
+    assert_eq!(AggregationType::Percentile(100).rank_index(3), Some(2));
+    assert_eq!(AggregationType::Median.rank_index(0), None);
+    assert_eq!(AggregationType::Sum.rank_index(3), None);
+    assert_eq!(
+        AggregationType::from_str("percentile(95)"),
+        Some(AggregationType::Percentile(95))
+    );
+}
+
+#[test]
+fn test_median_per_group() {
+    // Test: group 1 = {40, 10, 30} -> 30, group 2 = {7, 3, 9, 5} -> 5 (lower median)
+    let k = 12;
+    let circuit = RankAggregationTestCircuit {
+        group_keys: vec![1, 1, 1, 2, 2, 2, 2],
+        values: vec![40, 10, 30, 7, 3, 9, 5],
+        agg_type: AggregationType::Median,
+        expected: vec![30, 5],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_percentile() {
+    // Test: 90th percentile of 1..=10 is 9 (nearest rank)
+    let k = 12;
+    let circuit = RankAggregationTestCircuit {
+        group_keys: vec![0; 10],
+        values: vec![10, 2, 8, 4, 6, 1, 9, 3, 7, 5],
+        agg_type: AggregationType::Percentile(90),
+        expected: vec![9],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_median() {
+    let query = SQLParser::parse(
+        "SELECT region, MEDIAN(amount), PERCENTILE_DISC(0.75) WITHIN GROUP (ORDER BY amount) FROM sales GROUP BY region",
+    )
+    .unwrap();
+    let aggregations = query.aggregations.as_ref().unwrap();
+    assert!(matches!(aggregations[0].function, AggregationFunction::Median));
+    assert!(matches!(
+        aggregations[1].function,
+        AggregationFunction::Percentile(75)
+    ));
+    assert_eq!(aggregations[1].column, "amount");
+
+    let mut sales = HashMap::new();
+    sales.insert("region".to_string(), vec![2, 1, 2, 1]);
+    sales.insert("amount".to_string(), vec![7, 3, 5, 1]);
+    let mut table_data = HashMap::new();
+    table_data.insert("sales".to_string(), sales);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.aggregations[0].agg_type, AggregationType::Median);
+    assert_eq!(compiled.aggregations[0].group_keys, vec![1, 1, 2, 2]);
+    assert_eq!(compiled.aggregations[0].values, vec![1, 3, 5, 7]);
+}
@@ -0,0 +1,138 @@
+use halo2_proofs::dev::MockProver;
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::database::*;
+
+// Commitment delta tests
+// Catalog changes are proven with `CommitmentDeltaCircuit` (MockProver)
+
+const K: u32 = 11;
+const DEPTH: usize = 4;
+
+fn database() -> Database {
+    let mut db = Database::new().with_merkle_depth(DEPTH);
+    db.create_table(
+        "accounts",
+        &[
+            ("id", ColumnType::Integer),
+            ("balance", ColumnType::Integer),
+        ],
+    )
+    .unwrap();
+    for (id, balance) in [(1, 100), (2, 250), (3, 75)] {
+        db.insert("accounts", &[Datum::Integer(id), Datum::Integer(balance)])
+            .unwrap();
+    }
+    db
+}
+
+fn rebuilt_root(db: &Database) -> Fr {
+    let leaves = db
+        .table("accounts")
+        .unwrap()
+        .data
+        .iter()
+        .map(|row| merkle_leaf(Some(row.as_slice())))
+        .collect();
+    MerkleTree::from_leaves(DEPTH, leaves).unwrap().root()
+}
+
+fn run(
+    delta: &CommitmentDelta,
+    instance: Vec<Fr>,
+) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+    MockProver::run(K, &delta.circuit(), vec![instance])
+        .unwrap()
+        .verify()
+}
+
+#[test]
+fn test_incremental_root_matches_rebuild() {
+    // Test: Inserts, updates and deletes keep the root equal to a full rebuild
+    let mut db = database();
+    assert_eq!(db.root("accounts"), Some(rebuilt_root(&db)));
+
+    db.update("accounts", 1, &[Datum::Integer(2), Datum::Integer(300)])
+        .unwrap();
+    assert_eq!(db.root("accounts"), Some(rebuilt_root(&db)));
+
+    db.delete("accounts", 0).unwrap();
+    assert_eq!(db.root("accounts"), Some(rebuilt_root(&db)));
+    assert_eq!(db.table("accounts").unwrap().data[0], vec![3, 75]);
+
+    assert!(db
+        .update("accounts", 2, &[Datum::Integer(9), Datum::Integer(9)])
+        .is_err());
+    assert!(db.delete("accounts", 5).is_err());
+}
+
+#[test]
+fn test_delta_chains_roots() {
+    // Test: A delta starts at the root of the last delta and ends at the current root
+    let mut db = database();
+    let inserted = db.take_delta("accounts").unwrap();
+    assert_eq!(inserted.updates.len(), 3);
+    assert_eq!(inserted.old_root, MerkleTree::new(DEPTH).root());
+    assert_eq!(inserted.new_root(), db.root("accounts").unwrap());
+
+    let before = db.root("accounts").unwrap();
+    db.delete("accounts", 0).unwrap();
+    let deleted = db.take_delta("accounts").unwrap();
+    let claim = deleted.claim();
+    assert_eq!(claim.old_root, before);
+    assert_eq!(claim.new_root, db.root("accounts").unwrap());
+    assert_eq!(
+        claim.changes,
+        vec![
+            RowChange {
+                index: 0,
+                old_leaf: merkle_leaf(Some(&[1, 100][..])),
+                new_leaf: merkle_leaf(Some(&[3, 75][..])),
+            },
+            RowChange {
+                index: 2,
+                old_leaf: merkle_leaf(Some(&[3, 75][..])),
+                new_leaf: merkle_leaf(None),
+            },
+        ]
+    );
+
+    let empty = db.take_delta("accounts").unwrap();
+    assert!(empty.updates.is_empty());
+    assert_eq!(empty.claim().old_root, empty.claim().new_root);
+}
+
+#[test]
+fn test_delta_circuit_accepts_claimed_changes() {
+    // Test: The delta circuit proves the claim of an update and a delete
+    let mut db = database();
+    db.take_delta("accounts").unwrap();
+    db.update("accounts", 1, &[Datum::Integer(2), Datum::Integer(300)])
+        .unwrap();
+    db.delete("accounts", 0).unwrap();
+
+    let delta = db.take_delta("accounts").unwrap();
+    assert_eq!(delta.updates.len(), 3);
+    assert_eq!(run(&delta, delta.claim().instance()), Ok(()));
+}
+
+#[test]
+fn test_delta_circuit_rejects_wrong_claim() {
+    // Test: A different new root or an unclaimed leaf value fails
+    let mut db = database();
+    db.take_delta("accounts").unwrap();
+    db.update("accounts", 2, &[Datum::Integer(3), Datum::Integer(80)])
+        .unwrap();
+    let delta = db.take_delta("accounts").unwrap();
+
+    let mut wrong_root = delta.claim();
+    wrong_root.new_root += Fr::from(1);
+    assert!(run(&delta, wrong_root.instance()).is_err());
+
+    let mut wrong_leaf = delta.claim();
+    wrong_leaf.changes[0].new_leaf = merkle_leaf(Some(&[3, 8000][..]));
+    assert!(run(&delta, wrong_leaf.instance()).is_err());
+
+    let mut wrong_index = delta.claim();
+    wrong_index.changes[0].index = 1;
+    assert!(run(&delta, wrong_index.instance()).is_err());
+}
@@ -0,0 +1,159 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+/// Several aggregations over the same groups in one pass
+#[derive(Clone)]
+struct MultiMeasureCircuit {
+    group_keys: Vec<u64>,
+    measures: Vec<(Vec<u64>, AggregationType)>,
+    /// Expected running results per measure
+    expected: Vec<Vec<u64>>,
+}
+
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    aggregation_config: AggregationConfig,
+}
+
+impl Circuit<Fr> for MultiMeasureCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let aggregation_config = AggregationChip::configure(
+            meta,
+            &poneglyph_config,
+            &group_by_config,
+            &range_check_config,
+        );
+
+        TestConfig {
+            poneglyph_config,
+            aggregation_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        let measures: Vec<(&[u64], &AggregationType)> = self
+            .measures
+            .iter()
+            .map(|(values, agg_type)| (values.as_slice(), agg_type))
+            .collect();
+        let results = aggregation_chip.aggregate_many_and_verify(
+            layouter.namespace(|| "multi-measure aggregation"),
+            &self.group_keys,
+            &measures,
+        )?;
+
+        assert_eq!(results.len(), self.expected.len());
+        for (cells, expected) in results.iter().zip(self.expected.iter()) {
+            assert_eq!(cells.len(), expected.len());
+            for (cell, &expected) in cells.iter().zip(expected.iter()) {
+                cell.value().assert_if_known(|v| **v == Fr::from(expected));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_sum_max_count_one_pass() {
+    // Test: SELECT SUM(a), MAX(b), COUNT(*) ... GROUP BY k
+    let circuit = MultiMeasureCircuit {
+        group_keys: vec![1, 1, 2, 2, 2, 3],
+        measures: vec![
+            (vec![10, 20, 30, 40, 50, 60], AggregationType::Sum),
+            (vec![7, 3, 1, 9, 4, 2], AggregationType::Max),
+            (vec![0; 6], AggregationType::Count),
+        ],
+        expected: vec![
+            vec![10, 30, 30, 70, 120, 60],
+            vec![7, 7, 1, 9, 9, 2],
+            vec![1, 2, 1, 2, 3, 1],
+        ],
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_min_and_max_share_diffs() {
+    // Test: MIN and MAX diffs of several measures are range checked together
+    let circuit = MultiMeasureCircuit {
+        group_keys: vec![5, 5, 5, 8],
+        measures: vec![
+            (vec![4, 2, 6, 1], AggregationType::Min),
+            (vec![4, 2, 6, 1], AggregationType::Max),
+            (vec![u64::MAX, 0, 1, 3], AggregationType::Max),
+        ],
+        expected: vec![
+            vec![4, 2, 2, 1],
+            vec![4, 4, 6, 1],
+            vec![u64::MAX, u64::MAX, u64::MAX, 3],
+        ],
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_single_measure_matches_aggregate() {
+    // Test: one measure is the same as aggregate_and_verify
+    let circuit = MultiMeasureCircuit {
+        group_keys: vec![1],
+        measures: vec![(vec![42], AggregationType::Sum)],
+        expected: vec![vec![42]],
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let empty = MultiMeasureCircuit {
+        group_keys: vec![],
+        measures: vec![
+            (vec![], AggregationType::Sum),
+            (vec![], AggregationType::Max),
+        ],
+        expected: vec![vec![], vec![]],
+    };
+    let prover = MockProver::run(10, &empty, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_multi_measure_rejects() {
+    // Test: measures of another length, rank and dispersion measures are rejected
+    let rejected = [
+        (vec![1, 2], AggregationType::Sum),
+        (vec![1, 2, 3], AggregationType::Median),
+        (vec![1, 2, 3], AggregationType::Variance(0)),
+    ];
+    for measure in rejected {
+        let circuit = MultiMeasureCircuit {
+            group_keys: vec![1, 1, 2],
+            measures: vec![(vec![1, 2, 3], AggregationType::Sum), measure],
+            expected: vec![],
+        };
+        assert!(MockProver::run(10, &circuit, vec![vec![]]).is_err());
+    }
+}
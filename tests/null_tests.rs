@@ -0,0 +1,172 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+/// NULL semantics test circuit
+/// Filter, group-by, aggregation and join over nullable columns
+#[derive(Clone)]
+struct NullTestCircuit {
+    group_keys: Vec<u64>,
+    values: Vec<Option<u64>>,
+    agg_type: AggregationType,
+    join_keys1: Vec<Option<u64>>,
+    join_keys2: Vec<Option<u64>>,
+    null_safe: bool,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    null_config: NullConfig,
+    group_by_config: GroupByConfig,
+    aggregation_config: AggregationConfig,
+    join_config: JoinConfig,
+}
+
+impl Circuit<Fr> for NullTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            group_keys: vec![],
+            values: vec![],
+            agg_type: self.agg_type.clone(),
+            join_keys1: vec![],
+            join_keys2: vec![],
+            null_safe: self.null_safe,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let null_config = NullChip::configure(meta, &poneglyph_config, &range_check_config);
+        let sort_config = SortChip::configure(meta, &poneglyph_config, &range_check_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let aggregation_config = AggregationChip::configure(meta, &poneglyph_config, &group_by_config, &range_check_config);
+        let join_config = JoinChip::configure(meta, &poneglyph_config, &range_check_config, &sort_config);
+
+        TestConfig {
+            poneglyph_config,
+            null_config,
+            group_by_config,
+            aggregation_config,
+            join_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        // WHERE value < 50 (NULL rows are UNKNOWN and filtered out)
+        let null_chip = NullChip::new(config.null_config.clone());
+        for (i, value) in self.values.iter().enumerate() {
+            null_chip.filter_less_than(
+                layouter.namespace(|| format!("filter {}", i)),
+                *value,
+                50,
+                u64::MAX,
+            )?;
+        }
+
+        // GROUP BY over the nullable values (NULLs form one group)
+        let mut sorted_values = self.values.clone();
+        sorted_values.sort();
+        let group_by_chip = GroupByChip::new(config.group_by_config);
+        group_by_chip.group_nullable_and_verify(
+            layouter.namespace(|| "group nullable"),
+            &sorted_values,
+        )?;
+
+        // Aggregation over the nullable column
+        let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        aggregation_chip.aggregate_nullable_and_verify(
+            layouter.namespace(|| "aggregate nullable"),
+            &config.null_config,
+            &self.group_keys,
+            &self.values,
+            &self.agg_type,
+        )?;
+
+        // Join over nullable keys
+        if !self.join_keys1.is_empty() {
+            let join_chip = JoinChip::new(config.join_config);
+            let values1 = vec![0; self.join_keys1.len()];
+            let values2 = vec![0; self.join_keys2.len()];
+            join_chip.join_nullable_and_verify(
+                layouter.namespace(|| "join nullable"),
+                &self.join_keys1,
+                &values1,
+                &self.join_keys2,
+                &values2,
+                self.null_safe,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn circuit(values: Vec<Option<u64>>, agg_type: AggregationType) -> NullTestCircuit {
+    NullTestCircuit {
+        group_keys: vec![1; values.len()],
+        values,
+        agg_type,
+        join_keys1: vec![],
+        join_keys2: vec![],
+        null_safe: false,
+    }
+}
+
+#[test]
+fn test_null_excluded_from_sum() {
+    // Test: SUM(col) ignores NULL rows
+    let k = 12;
+    let circuit = circuit(vec![Some(10), None, Some(20), None], AggregationType::Sum);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_null_excluded_from_count() {
+    // Test: COUNT(col) counts only non-NULL rows
+    let k = 12;
+    let circuit = circuit(vec![None, Some(7), Some(0), None], AggregationType::Count);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_null_skipped_by_max() {
+    // Test: MAX(col) over non-NULL rows only
+    let k = 12;
+    let circuit = circuit(vec![Some(3), None, Some(9), None], AggregationType::Max);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_null_join_semantics() {
+    // Test: NULL = NULL is a miss, NULL <=> NULL is a match
+    let k = 12;
+    for null_safe in [false, true] {
+        let circuit = NullTestCircuit {
+            join_keys1: vec![Some(1), None, Some(3)],
+            join_keys2: vec![Some(1), None, Some(4)],
+            null_safe,
+            ..circuit(vec![Some(1)], AggregationType::Sum)
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -0,0 +1,86 @@
+use poneglyphdb::sql::*;
+
+/// Parse mode tests
+/// Strict mode rejects constructs outside the provable subset, permissive
+/// mode accepts and annotates them
+
+#[test]
+fn test_provable_query_in_both_modes() {
+    // Test: A query of the provable subset has no annotations
+    let sql = "SELECT customer, SUM(amount), price * qty FROM orders \
+               WHERE qty > 5 GROUP BY customer ORDER BY customer";
+    for mode in [ParseMode::Strict, ParseMode::Permissive] {
+        let query = SQLParser::parse_with_mode(sql, mode).unwrap();
+        assert!(query.unprovable.is_empty());
+        assert!(query.is_provable());
+    }
+}
+
+#[test]
+fn test_permissive_annotates() {
+    // Test: Each unprovable construct is listed once
+    let cases = [
+        (
+            "SELECT customer, SUM(amount) FROM orders GROUP BY customer HAVING SUM(amount) > 10",
+            Unprovable::Having,
+        ),
+        (
+            "SELECT amount FROM orders ORDER BY amount LIMIT 10",
+            Unprovable::Limit,
+        ),
+        ("SELECT DISTINCT customer FROM orders", Unprovable::Distinct),
+        (
+            "SELECT amount FROM orders LEFT JOIN customers ON orders.customer = customers.id",
+            Unprovable::Join,
+        ),
+        (
+            "SELECT SUM(amount) FROM orders GROUP BY customer, region, year, month",
+            Unprovable::MultiColumnGroupBy,
+        ),
+        (
+            "SELECT amount FROM orders ORDER BY customer, amount DESC",
+            Unprovable::MultiColumnOrderBy,
+        ),
+        (
+            "SELECT AVG(amount) FROM orders",
+            Unprovable::Expression("avg(amount)".to_string()),
+        ),
+    ];
+    for (sql, construct) in cases {
+        let query = SQLParser::parse_with_mode(sql, ParseMode::Permissive).unwrap();
+        assert_eq!(query.unprovable, vec![construct], "{}", sql);
+        assert!(!query.is_provable());
+        assert_eq!(
+            SQLParser::parse(sql).unwrap().unprovable,
+            query.unprovable,
+            "parse is permissive"
+        );
+    }
+}
+
+#[test]
+fn test_strict_rejects() {
+    // Test: Strict mode names the construct, also inside nested queries
+    let err = SQLParser::parse_with_mode("SELECT AVG(amount) FROM orders", ParseMode::Strict)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "expression 'avg(amount)' is outside the provable subset"
+    );
+
+    let nested = [
+        "WITH t AS (SELECT DISTINCT customer FROM orders) SELECT customer FROM t",
+        "SELECT amount FROM orders WHERE customer IN (SELECT id FROM customers LIMIT 1)",
+        "SELECT amount FROM orders UNION SELECT amount FROM archive ORDER BY amount, customer",
+    ];
+    for sql in nested {
+        let query = SQLParser::parse(sql).unwrap();
+        assert!(query.unprovable.is_empty(), "{}", sql);
+        assert!(!query.is_provable(), "{}", sql);
+        assert!(
+            SQLParser::parse_with_mode(sql, ParseMode::Strict).is_err(),
+            "{}",
+            sql
+        );
+    }
+}
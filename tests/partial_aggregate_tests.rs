@@ -0,0 +1,139 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use ff::PrimeField;
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+/// Partial aggregate merge test circuit
+#[derive(Clone)]
+struct MergeTestCircuit {
+    /// Rows of each shard
+    shards: Vec<Vec<u64>>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    partial_config: PartialAggregateConfig,
+}
+
+impl Circuit<Fr> for MergeTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let partial_config =
+            PartialAggregateChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            partial_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let chip = PartialAggregateChip::new(config.partial_config);
+        let partials = self
+            .shards
+            .iter()
+            .map(|rows| {
+                chip.assign(
+                    layouter.namespace(|| "shard"),
+                    &PartialAggregate::of(rows).unwrap(),
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let merged = chip
+            .merge_all(layouter.namespace(|| "merge"), &partials)?
+            .unwrap();
+
+        let expected = PartialAggregate::of(&self.shards.concat()).unwrap();
+        merged
+            .sum
+            .value()
+            .assert_if_known(|v| **v == Fr::from_u128(expected.sum));
+        merged
+            .count
+            .value()
+            .assert_if_known(|v| **v == Fr::from(expected.count));
+        merged
+            .min
+            .value()
+            .assert_if_known(|v| **v == Fr::from(expected.min));
+        merged
+            .max
+            .value()
+            .assert_if_known(|v| **v == Fr::from(expected.max));
+        merged
+            .sum_of_squares
+            .value()
+            .assert_if_known(|v| **v == Fr::from_u128(expected.sum_of_squares));
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_merge_shards() {
+    let k = 10;
+    let circuit = MergeTestCircuit {
+        shards: vec![vec![7, 3], vec![12, 1, 5], vec![4]],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_merge_with_empty_shard() {
+    // The empty summary (min = u64::MAX, max = 0) is the merge identity
+    let k = 10;
+    let circuit = MergeTestCircuit {
+        shards: vec![vec![], vec![u64::MAX - 1, 2], vec![]],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_merged_matches_aggregation_types() {
+    let shards = [vec![2u64, 4], vec![4, 4, 5], vec![5, 7, 9]];
+    let merged = shards
+        .iter()
+        .map(|rows| PartialAggregate::of(rows).unwrap())
+        .try_fold(PartialAggregate::default(), |acc, p| acc.merge(&p))
+        .unwrap();
+    let all = shards.concat();
+    for agg in [
+        AggregationType::Sum,
+        AggregationType::Count,
+        AggregationType::Min,
+        AggregationType::Max,
+        AggregationType::Variance(2),
+        AggregationType::StdDev(2),
+    ] {
+        let expected = match agg {
+            AggregationType::Sum => Some(all.iter().sum()),
+            AggregationType::Count => Some(all.len() as u64),
+            AggregationType::Min => all.iter().min().copied(),
+            AggregationType::Max => all.iter().max().copied(),
+            _ => agg.dispersion(&all),
+        };
+        assert_eq!(merged.evaluate(&agg), expected, "{:?}", agg);
+    }
+}
@@ -0,0 +1,249 @@
+use halo2_proofs::{circuit::Value, pasta::EqAffine, plonk::VerifyingKey};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::error::{PoneglyphError, VerifyError};
+use poneglyphdb::prover::*;
+
+// Proof pipeline tests
+// Real proofs (create_proof / verify_proof, IPA on pasta curves) from
+// `Prover::setup` keys, verified from the proof, the key and the inputs only
+
+const K: u32 = 10;
+
+fn scan() -> TableScan {
+    TableScan::new(
+        vec!["id".to_string(), "price".to_string()],
+        vec![vec![1, 30], vec![2, 70]],
+    )
+}
+
+fn circuit(inputs: Option<&PublicInputs>) -> PoneglyphCircuit {
+    let known =
+        |f: fn(&PublicInputs) -> Fr| inputs.map_or(Value::unknown(), |i| Value::known(f(i)));
+    PoneglyphCircuit {
+        scan: scan(),
+        query_result: known(|i| i.query_result),
+        nonce: known(|i| i.nonce),
+        expiry: known(|i| i.expiry.to_field()),
+        query_hash: Fr::from(0),
+        result_commitment: known(|i| i.result_commitment),
+        result_columns: vec![],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: vec![],
+        aggregations: vec![],
+    }
+}
+
+fn inputs() -> PublicInputs {
+    PublicInputs::new(scan().commitment(), Fr::from(100))
+        .with_nonce(request_nonce(b"pipeline").unwrap())
+        .with_expiry(ExpiryBound::BlockHeight(1_000))
+}
+
+#[test]
+fn test_setup_prove_verify() {
+    // Test: A proof from setup keys verifies with the verifying key alone
+    let (pk, vk) = Prover::setup(K, &circuit(None)).unwrap();
+    let inputs = inputs();
+    let proof = Prover::from_setup(K, pk)
+        .create_proof(&circuit(Some(&inputs)), &inputs)
+        .unwrap();
+    assert_eq!(proof.k, K);
+    assert_eq!(
+        proof.circuit_version,
+        poneglyphdb::recursive::vk_fingerprint(&vk)
+    );
+    assert!(!proof.bytes.is_empty());
+
+    assert_eq!(Verifier::verify_proof(&proof, &vk, &inputs), Ok(()));
+}
+
+#[test]
+fn test_verify_proof_rejects() {
+    // Test: Other inputs, tampered bytes and another circuit version fail
+    let (pk, vk) = Prover::setup(K, &circuit(None)).unwrap();
+    let inputs = inputs();
+    let proof = Prover::from_setup(K, pk)
+        .create_proof(&circuit(Some(&inputs)), &inputs)
+        .unwrap();
+
+    let other = PublicInputs {
+        query_result: Fr::from(101),
+        ..inputs.clone()
+    };
+    assert!(matches!(
+        Verifier::verify_proof(&proof, &vk, &other),
+        Err(VerifyError::InvalidProof(_))
+    ));
+
+    let mut tampered = proof.clone();
+    *tampered.bytes.last_mut().unwrap() ^= 1;
+    assert!(Verifier::verify_proof(&tampered, &vk, &inputs).is_err());
+
+    let relabeled = Proof {
+        circuit_version: proof.circuit_version ^ 1,
+        ..proof
+    };
+    assert!(matches!(
+        Verifier::verify_proof(&relabeled, &vk, &inputs),
+        Err(VerifyError::WrongCircuitVersion { .. })
+    ));
+}
+
+#[test]
+fn test_create_proof_needs_setup_size() {
+    // Test: A prover over caller-held parameters can't make a transferable proof
+    let (pk, _) = Prover::setup(K, &circuit(None)).unwrap();
+    let inputs = inputs();
+    assert!(matches!(
+        Prover::from_proving_key(pk).create_proof(&circuit(Some(&inputs)), &inputs),
+        Err(PoneglyphError::Configuration(_))
+    ));
+}
+
+#[test]
+fn test_verify_from_bytes() {
+    // Test: Proof and key sent as bytes verify in a separate verifier
+    let (pk, vk) = Prover::setup(K, &circuit(None)).unwrap();
+    let inputs = inputs();
+    let proof = Prover::from_setup(K, pk)
+        .create_proof(&circuit(Some(&inputs)), &inputs)
+        .unwrap();
+    let proof_bytes = proof.to_bytes().unwrap();
+    let key_bytes = vk.to_bytes(K).unwrap();
+
+    // Receiving side: only bytes and the circuit shape
+    let proof = Proof::from_bytes(&proof_bytes).unwrap();
+    let vk = VerifyingKey::<EqAffine>::from_bytes(&key_bytes, &circuit(None)).unwrap();
+    assert_eq!(Verifier::verify_proof(&proof, &vk, &inputs), Ok(()));
+
+    // A key for another circuit shape is not accepted
+    let mut other = circuit(None);
+    other.range_checks.push(RangeCheckOp {
+        value: Value::unknown(),
+        threshold: 10,
+        u: 1 << 8,
+    });
+    assert!(matches!(
+        VerifyingKey::<EqAffine>::from_bytes(&key_bytes, &other),
+        Err(PoneglyphError::Validation(_))
+    ));
+    assert!(VerifyingKey::<EqAffine>::from_bytes(&proof_bytes, &circuit(None)).is_err());
+}
+
+#[test]
+fn test_verify_raw() {
+    // Test: A raw key, the inputs and the proof as bytes verify without the circuit
+    let (pk, vk) = Prover::setup(K, &circuit(None)).unwrap();
+    let inputs = inputs();
+    let proof = Prover::from_setup(K, pk)
+        .create_proof(&circuit(Some(&inputs)), &inputs)
+        .unwrap();
+    let vk_bytes = RawVerifyingKey::of(K, &circuit(None), &vk)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    let instance_bytes = inputs.to_bytes().unwrap();
+    let proof_bytes = proof.to_bytes().unwrap();
+    assert_eq!(verify_raw(&vk_bytes, &instance_bytes, &proof_bytes), Ok(()));
+
+    let other = PublicInputs {
+        query_result: Fr::from(101),
+        ..inputs.clone()
+    };
+    assert!(matches!(
+        verify_raw(&vk_bytes, &other.to_bytes().unwrap(), &proof_bytes),
+        Err(VerifyError::InvalidProof(_))
+    ));
+    let relabeled = Proof {
+        circuit_version: proof.circuit_version ^ 1,
+        ..proof
+    };
+    assert!(matches!(
+        verify_raw(&vk_bytes, &instance_bytes, &relabeled.to_bytes().unwrap()),
+        Err(VerifyError::WrongCircuitVersion { .. })
+    ));
+    assert!(matches!(
+        verify_raw(&proof_bytes, &instance_bytes, &proof_bytes),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+}
+
+#[test]
+fn test_precheck_stages() {
+    // Test: Structural failures are reported before the cryptographic check
+    let (pk, vk) = Prover::setup(K, &circuit(None)).unwrap();
+    let inputs = inputs();
+    let proof = Prover::from_setup(K, pk)
+        .create_proof(&circuit(Some(&inputs)), &inputs)
+        .unwrap();
+    let proof_bytes = proof.to_bytes().unwrap();
+    let instance_bytes = inputs.to_bytes().unwrap();
+    let anchors = vec![inputs.db_commitment];
+    let precheck = Precheck::new(&vk).with_anchors(&anchors);
+    assert_eq!(
+        precheck.verify(&proof_bytes, &instance_bytes),
+        Ok(inputs.clone())
+    );
+
+    // Envelope: truncated bytes, or k above the accepted bound
+    assert!(matches!(
+        precheck.check(&proof_bytes[..10], &instance_bytes),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+    assert!(matches!(
+        Precheck::new(&vk)
+            .with_max_k(K - 1)
+            .check(&proof_bytes, &instance_bytes),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+
+    // Version
+    let relabeled = Proof {
+        circuit_version: proof.circuit_version ^ 1,
+        ..proof.clone()
+    };
+    assert!(matches!(
+        precheck.check(&relabeled.to_bytes().unwrap(), &instance_bytes),
+        Err(VerifyError::WrongCircuitVersion { .. })
+    ));
+
+    // Instance arity
+    let with_threshold = inputs.clone().with_thresholds(vec![10]);
+    assert!(matches!(
+        precheck
+            .with_thresholds(0)
+            .check(&proof_bytes, &with_threshold.to_bytes().unwrap()),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+
+    // Anchor
+    let other_db = PublicInputs {
+        db_commitment: Fr::from(43),
+        ..inputs.clone()
+    };
+    assert_eq!(
+        Precheck::new(&vk)
+            .with_anchors(&anchors)
+            .check(&proof_bytes, &other_db.to_bytes().unwrap()),
+        Err(VerifyError::UnknownCommitment(Fr::from(43)))
+    );
+
+    // Passing the structural checks doesn't make a wrong result verify
+    let other_result = PublicInputs {
+        query_result: Fr::from(101),
+        ..inputs
+    };
+    let precheck = Precheck::new(&vk).with_anchors(&anchors);
+    assert!(precheck
+        .check(&proof_bytes, &other_result.to_bytes().unwrap())
+        .is_ok());
+    assert!(matches!(
+        precheck.verify(&proof_bytes, &other_result.to_bytes().unwrap()),
+        Err(VerifyError::InvalidProof(_))
+    ));
+}
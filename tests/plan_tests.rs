@@ -0,0 +1,111 @@
+use poneglyphdb::optimization::*;
+use poneglyphdb::sql::*;
+use poneglyphdb::test_utils::fixtures::orders;
+
+// Query plan tests
+// Operator trees of compiled queries and their DOT / JSON graphs
+
+fn plan(sql: &str) -> QueryPlan {
+    let query = SQLParser::parse(sql).unwrap();
+    let compiled = SQLCompiler::compile(&query, &orders()).unwrap();
+    QueryPlan::of(&query, &compiled)
+}
+
+#[test]
+fn test_plan_operators() {
+    // Test: Operators chain scan → filter → group → aggregate → sort
+    let plan = plan(
+        "SELECT customer, SUM(amount) FROM orders WHERE amount > 40 \
+         GROUP BY customer ORDER BY customer",
+    );
+    let operators: Vec<PlanOperator> = plan.nodes.iter().map(|n| n.operator).collect();
+    assert_eq!(
+        operators,
+        vec![
+            PlanOperator::Scan,
+            PlanOperator::Filter,
+            PlanOperator::GroupBy,
+            PlanOperator::Aggregate,
+            PlanOperator::Sort,
+        ]
+    );
+    assert_eq!(plan.root, 4);
+    for node in &plan.nodes[1..] {
+        assert_eq!(node.inputs, vec![node.id - 1]);
+    }
+    assert_eq!(plan.nodes[0].detail, "orders");
+    assert!(plan.nodes[1].chips.contains(&"range_check"));
+    assert!(plan.nodes[1].rows > 0);
+    assert_eq!(
+        plan.total_rows(),
+        plan.nodes.iter().map(|n| n.rows).sum::<usize>()
+    );
+}
+
+#[test]
+fn test_plan_cte_feeds_scan() {
+    // Test: A referenced CTE is a subplan feeding the scan of its name
+    let plan = plan(
+        "WITH totals(customer, total) AS (SELECT customer, SUM(amount) FROM orders GROUP BY customer), \
+         unused AS (SELECT amount FROM orders) \
+         SELECT customer FROM totals WHERE total > 100",
+    );
+    let ctes: Vec<&PlanNode> = plan
+        .nodes
+        .iter()
+        .filter(|n| n.operator == PlanOperator::Cte)
+        .collect();
+    assert_eq!(ctes.len(), 1);
+    assert_eq!(ctes[0].detail, "totals");
+
+    let scan = plan
+        .nodes
+        .iter()
+        .find(|n| n.operator == PlanOperator::Scan && n.detail == "totals")
+        .unwrap();
+    assert_eq!(scan.inputs, vec![ctes[0].id]);
+    assert_eq!(plan.nodes[plan.root].operator, PlanOperator::Filter);
+}
+
+#[test]
+fn test_plan_graph_export() {
+    // Test: DOT and JSON carry every node, edge and cost annotation
+    let plan = plan("SELECT customer FROM orders WHERE amount > 40 ORDER BY customer");
+    let graph = plan.to_graph();
+    assert_eq!(graph.nodes.len(), 3);
+    assert_eq!(graph.edges.len(), 2);
+    assert_eq!(graph.total_rows, plan.total_rows());
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph plan {"));
+    assert!(dot.contains("n0 -> n1;"));
+    assert!(dot.contains("n1 -> n2;"));
+    assert!(dot.contains("Filter\\namount > 40"));
+    assert!(dot.contains(&format!("~{} rows", graph.nodes[2].rows)));
+
+    let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+    assert_eq!(json["nodes"][1]["operator"], "filter");
+    assert_eq!(json["edges"][0]["from"], 0);
+    assert_eq!(json["root"], 2);
+}
+
+#[test]
+fn test_plan_display() {
+    // Test: Text and HTML tables list the operators root first, inputs indented
+    let plan = plan("SELECT customer FROM orders WHERE amount > 40 ORDER BY customer");
+    let text = plan.to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[0].starts_with("operator"), "{}", text);
+    assert!(lines[2].starts_with("Sort "), "{}", text);
+    assert!(lines[3].starts_with("  Filter"), "{}", text);
+    assert!(lines[3].contains("amount > 40"), "{}", text);
+    assert!(lines[4].starts_with("    Scan"), "{}", text);
+    assert_eq!(
+        lines[5],
+        format!("~{} advice rows, k >= {}", plan.total_rows(), plan.min_k())
+    );
+
+    let html = plan.to_html();
+    assert!(html.contains("<td>\u{a0}\u{a0}Filter</td><td>amount &gt; 40</td>"));
+    assert!(html.contains(&format!("<p>~{} advice rows", plan.total_rows())));
+}
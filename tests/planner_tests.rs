@@ -0,0 +1,142 @@
+use poneglyphdb::optimization::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+// Query planner tests
+// Filter pushdown and join ordering chosen from table contents, and the
+// compiled operations that follow the chosen order
+
+fn tables() -> HashMap<String, HashMap<String, Vec<u64>>> {
+    let mut orders = HashMap::new();
+    orders.insert("customer".to_string(), vec![1, 2, 1, 3, 2, 4, 1, 3]);
+    orders.insert("amount".to_string(), vec![50, 120, 30, 80, 200, 10, 90, 45]);
+    let mut customers = HashMap::new();
+    customers.insert("id".to_string(), vec![1, 2, 3, 4]);
+    let mut vip = HashMap::new();
+    vip.insert("id".to_string(), vec![2]);
+
+    let mut table_data = HashMap::new();
+    table_data.insert("orders".to_string(), orders);
+    table_data.insert("customers".to_string(), customers);
+    table_data.insert("vip".to_string(), vip);
+    table_data
+}
+
+/// Parsed query with inner joins of `orders.customer` to each table's `id`
+/// (in the given order)
+fn query(sql: &str, joined: &[&str]) -> SQLQuery {
+    let mut query = SQLParser::parse(sql).unwrap();
+    if !joined.is_empty() {
+        query.joins = Some(
+            joined
+                .iter()
+                .map(|table| JoinClause {
+                    table: table.to_string(),
+                    alias: None,
+                    on: JoinCondition {
+                        left_column: "customer".to_string(),
+                        right_column: "id".to_string(),
+                    },
+                    join_type: JoinType::Inner,
+                })
+                .collect(),
+        );
+    }
+    query
+}
+
+#[test]
+fn test_filter_pushdown() {
+    // Test: Sorts only see the rows the filter keeps; the filter still covers every row
+    let table_data = tables();
+    let query = query(
+        "SELECT amount FROM orders WHERE amount > 40 ORDER BY amount",
+        &[],
+    );
+    let plan = Planner::new(&table_data).plan(&query).unwrap();
+    assert_eq!(
+        plan.decisions,
+        vec![PlanDecision::PushFilter {
+            filter: "amount > 40".to_string(),
+            rows_before: 8,
+            rows_after: 6,
+        }]
+    );
+    assert!(plan.planned_rows < plan.written_rows);
+
+    let compiled = SQLCompiler::compile_planned(&query, &table_data, &plan).unwrap();
+    assert_eq!(
+        compiled.sorts[0].sorted_output,
+        vec![45, 50, 80, 90, 120, 200]
+    );
+    let written = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.range_checks.len(), written.range_checks.len());
+
+    // The filter runs below the sort
+    let operators: Vec<PlanOperator> = QueryPlan::planned(&query, &compiled, &plan)
+        .nodes
+        .iter()
+        .map(|n| n.operator)
+        .collect();
+    assert_eq!(
+        operators,
+        vec![PlanOperator::Scan, PlanOperator::Filter, PlanOperator::Sort]
+    );
+}
+
+#[test]
+fn test_join_reordering() {
+    // Test: The join keeping the fewest rows runs first and shrinks the next one
+    let table_data = tables();
+    let query = query(
+        "SELECT amount FROM orders WHERE amount > 40",
+        &["customers", "vip"],
+    );
+    let plan = Planner::new(&table_data).plan(&query).unwrap();
+    assert_eq!(plan.join_order, vec![1, 0]);
+    assert!(plan.decisions.contains(&PlanDecision::ReorderJoins {
+        written: vec!["customers".to_string(), "vip".to_string()],
+        chosen: vec!["vip".to_string(), "customers".to_string()],
+    }));
+    assert!(plan.explain().contains("join vip, customers"));
+
+    // vip sees the 6 filtered rows, customers only the 2 vip orders
+    let compiled = SQLCompiler::compile_planned(&query, &table_data, &plan).unwrap();
+    assert_eq!(compiled.joins[0].table1_keys, vec![1, 2, 3, 2, 1, 3]);
+    assert_eq!(compiled.joins[0].table2_keys, vec![2]);
+    assert_eq!(compiled.joins[1].table1_keys, vec![2, 2]);
+    assert_eq!(compiled.joins[1].table2_keys, vec![1, 2, 3, 4]);
+
+    let explained = QueryPlan::planned(&query, &compiled, &plan);
+    let joins: Vec<&str> = explained
+        .nodes
+        .iter()
+        .filter(|n| n.operator == PlanOperator::Join)
+        .map(|n| n.detail.as_str())
+        .collect();
+    assert!(joins[0].contains("vip") && joins[1].contains("customers"));
+    assert_eq!(explained.decisions, plan.decisions);
+    let written = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert!(explained.total_rows() < QueryPlan::of(&query, &written).total_rows());
+}
+
+#[test]
+fn test_plan_without_choices() {
+    // Test: A query with nothing to reorder compiles exactly as written
+    let table_data = tables();
+    let query = query(
+        "SELECT SUM(amount) FROM orders WHERE amount > 40 OR customer = 4",
+        &[],
+    );
+    let plan = Planner::new(&table_data).plan(&query).unwrap();
+    assert!(plan.decisions.is_empty());
+    assert_eq!(plan.planned_rows, plan.written_rows);
+    assert!(plan.explain().starts_with("as written"));
+
+    let compiled = SQLCompiler::compile_planned(&query, &table_data, &plan).unwrap();
+    let written = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(
+        QueryPlan::planned(&query, &compiled, &plan).nodes,
+        QueryPlan::of(&query, &written).nodes
+    );
+}
@@ -0,0 +1,101 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::utils::{pack_bytes, poseidon_hash, poseidon_hash_bytes};
+
+/// Poseidon test circuit
+/// Hashes `inputs` in-circuit and exposes the digest as instance row 0
+#[derive(Clone)]
+struct PoseidonTestCircuit {
+    inputs: Vec<Fr>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    poseidon_config: PoseidonConfig,
+}
+
+impl Circuit<Fr> for PoseidonTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let poseidon_config = PoseidonChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            poseidon_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let poseidon_chip = PoseidonChip::new(config.poseidon_config);
+        let inputs = poseidon_chip.assign_inputs(layouter.namespace(|| "inputs"), &self.inputs)?;
+        let digest = poseidon_chip.hash(layouter.namespace(|| "hash"), &inputs)?;
+        layouter.constrain_instance(digest.cell(), config.poneglyph_config.instance, 0)?;
+
+        Ok(())
+    }
+}
+
+fn run(inputs: Vec<Fr>, digest: Fr) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+    let k = 10;
+    let circuit = PoseidonTestCircuit { inputs };
+    MockProver::run(k, &circuit, vec![vec![digest]])
+        .unwrap()
+        .verify()
+}
+
+#[test]
+fn test_chip_matches_native_hash() {
+    for len in [0u64, 1, 2, 3, 5] {
+        let inputs: Vec<Fr> = (0..len).map(|i| Fr::from(i * 7 + 1)).collect();
+        assert_eq!(
+            run(inputs.clone(), poseidon_hash(&inputs)),
+            Ok(()),
+            "length {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn test_chip_rejects_wrong_digest() {
+    let inputs = vec![Fr::from(1), Fr::from(2), Fr::from(3)];
+    let wrong = poseidon_hash(&[Fr::from(1), Fr::from(2)]);
+    assert!(run(inputs, wrong).is_err());
+}
+
+#[test]
+fn test_string_hash_in_circuit() {
+    let data = b"ORDER-2024-000123 shipped to warehouse 7";
+    assert_eq!(run(pack_bytes(data), poseidon_hash_bytes(data)), Ok(()));
+}
+
+#[test]
+fn test_database_commitment_uses_poseidon() {
+    let commitment = poneglyphdb::DatabaseCommitment::new(&[(1, 10), (2, 20)]);
+    assert_eq!(
+        commitment.commitment(),
+        poseidon_hash(&[Fr::from(1), Fr::from(10), Fr::from(2), Fr::from(20)])
+    );
+    assert!(commitment.verify(&[(1, 10), (2, 20)]));
+    assert!(!commitment.verify(&[(2, 20), (1, 10)]));
+}
@@ -0,0 +1,177 @@
+use halo2_proofs::{circuit::Value, dev::MockProver, pasta::EqAffine, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::error::VerifyError;
+use poneglyphdb::prover::Verifier;
+use poneglyphdb::recursive::{vk_fingerprint, CycleProver};
+use poneglyphdb::sql::{query_hash, QueryRegistry};
+
+// Query privacy tests
+// Instance row 4 holds a salted commitment to the query hash, rows 6-7 the
+// registry root and circuit fingerprint
+
+const K: u32 = 10;
+const DEPTH: usize = 3;
+const SUM: &str = "SELECT SUM(price) FROM orders";
+const COUNT: &str = "SELECT COUNT(*) FROM orders";
+const FINGERPRINT: u64 = 0xfeed;
+
+fn scan() -> TableScan {
+    TableScan::new(vec!["price".to_string()], vec![vec![30], vec![70]])
+}
+
+fn query(result: u64) -> PoneglyphCircuit {
+    PoneglyphCircuit {
+        scan: scan(),
+        query_result: Value::known(Fr::from(result)),
+        nonce: Value::known(Fr::from(0)),
+        expiry: Value::known(ExpiryBound::Never.to_field()),
+        query_hash: Fr::from(0),
+        result_commitment: Value::known(Fr::from(0)),
+        result_columns: vec![],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: vec![],
+        aggregations: vec![],
+    }
+}
+
+fn registry(fingerprint: u64) -> QueryRegistry {
+    let mut registry = QueryRegistry::new(DEPTH);
+    registry.authorize(COUNT, fingerprint).unwrap();
+    registry.authorize(SUM, fingerprint).unwrap();
+    registry
+}
+
+fn inputs(sql: &str, salt: Fr, registry_root: Fr, circuit_fingerprint: u64) -> PrivateQueryInputs {
+    PrivateQueryInputs {
+        public_inputs: PublicInputs::new(scan().commitment(), Fr::from(100))
+            .with_query_hash(query_commitment(query_hash(sql), salt)),
+        registry_root,
+        circuit_fingerprint,
+    }
+}
+
+fn mock_verify(circuit: &PrivateQueryCircuit, inputs: &PrivateQueryInputs) -> bool {
+    let prover = MockProver::run(K, circuit, inputs.to_instance()).unwrap();
+    prover.verify().is_ok()
+}
+
+#[test]
+fn test_authorized_query_verifies() {
+    // Test: An authorized query verifies against its commitment and the root
+    let registry = registry(FINGERPRINT);
+    let salt = Fr::from(7);
+    let circuit = registry
+        .private_circuit(SUM, query(100), salt, FINGERPRINT)
+        .unwrap();
+    let public = inputs(SUM, salt, registry.root(), FINGERPRINT);
+    assert!(mock_verify(&circuit, &public));
+
+    // The plain query hash is not the public value
+    let mut named = public.clone();
+    named.public_inputs.query_hash = query_hash(SUM);
+    assert!(!mock_verify(&circuit, &named));
+    // Nor is the commitment of another query, or with another salt
+    assert!(!mock_verify(
+        &circuit,
+        &inputs(COUNT, salt, registry.root(), FINGERPRINT)
+    ));
+    assert!(!mock_verify(
+        &circuit,
+        &inputs(SUM, Fr::from(8), registry.root(), FINGERPRINT)
+    ));
+}
+
+#[test]
+fn test_registry_membership() {
+    // Test: The proof is bound to the registry root and the circuit fingerprint
+    let registry = registry(FINGERPRINT);
+    let salt = Fr::from(7);
+    let circuit = registry
+        .private_circuit(SUM, query(100), salt, FINGERPRINT)
+        .unwrap();
+
+    let other = QueryRegistry::new(DEPTH);
+    assert!(!mock_verify(
+        &circuit,
+        &inputs(SUM, salt, other.root(), FINGERPRINT)
+    ));
+    assert!(!mock_verify(
+        &circuit,
+        &inputs(SUM, salt, registry.root(), FINGERPRINT + 1)
+    ));
+
+    // An unauthorized query can't borrow the path of an authorized one
+    let mut forged = circuit.clone();
+    forged.query.query_hash = query_hash("SELECT MAX(price) FROM orders");
+    assert!(!mock_verify(
+        &forged,
+        &inputs(
+            "SELECT MAX(price) FROM orders",
+            salt,
+            registry.root(),
+            FINGERPRINT
+        )
+    ));
+    assert!(registry
+        .private_circuit(
+            "SELECT MAX(price) FROM orders",
+            query(100),
+            salt,
+            FINGERPRINT
+        )
+        .is_err());
+}
+
+#[test]
+fn test_instance_thresholds_rejected() {
+    // Test: Public thresholds would reveal the query's constants
+    let registry = registry(FINGERPRINT);
+    let mut circuit = registry
+        .private_circuit(SUM, query(100), Fr::from(7), FINGERPRINT)
+        .unwrap();
+    circuit.query.threshold_mode = ThresholdMode::Instance;
+    let public = inputs(SUM, Fr::from(7), registry.root(), FINGERPRINT);
+    assert!(MockProver::run(K, &circuit, public.to_instance()).is_err());
+}
+
+#[test]
+fn test_verify_private_query() {
+    // Test: A real proof verifies with the registry root and the matching key
+    let params = Params::<EqAffine>::new(K);
+    let shape = PrivateQueryCircuit::shape(query(100), DEPTH);
+    let prover = CycleProver::<EqAffine>::new(&params, &shape).unwrap();
+    let fingerprint = vk_fingerprint(prover.vk());
+    let verifier = Verifier::from_verifying_key(prover.vk().clone());
+    assert_eq!(verifier.circuit_version(), fingerprint);
+
+    let registry = registry(fingerprint);
+    let salt = Fr::from(1234);
+    let circuit = registry
+        .private_circuit(SUM, query(100), salt, fingerprint)
+        .unwrap();
+    let public = inputs(SUM, salt, registry.root(), fingerprint).to_instance();
+    let proof = prover.prove(&params, &circuit, &public).unwrap();
+
+    assert_eq!(
+        verifier.verify_private_query(&params, &proof, &public, registry.root()),
+        Ok(())
+    );
+    assert!(matches!(
+        verifier.verify_private_query(&params, &proof, &public, QueryRegistry::new(DEPTH).root()),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+    let relabeled = inputs(SUM, salt, registry.root(), fingerprint + 1).to_instance();
+    assert!(matches!(
+        verifier.verify_private_query(&params, &proof, &relabeled, registry.root()),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+    let other_salt = inputs(SUM, Fr::from(1), registry.root(), fingerprint).to_instance();
+    assert!(verifier
+        .verify_private_query(&params, &proof, &other_salt, registry.root())
+        .is_err());
+}
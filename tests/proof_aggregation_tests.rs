@@ -0,0 +1,104 @@
+use ff::Field;
+use halo2_proofs::{pasta::EqAffine, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::error::VerifyError;
+use poneglyphdb::recursive::*;
+
+// Proof aggregation tests
+// Independent proofs of one circuit folded into a single deferred check
+// (`RecursiveAggregator`, real IPA proofs)
+
+const K: u32 = 10;
+const LIMBS: usize = 3;
+
+fn proofs(
+    params: &Params<EqAffine>,
+    prover: &CycleProver<EqAffine>,
+    n: u64,
+) -> Vec<(Vec<u8>, Vec<Fr>)> {
+    (0..n)
+        .map(|i| {
+            let instance: Vec<Fr> = (0..LIMBS as u64).map(|j| Fr::from(10 * i + j)).collect();
+            let circuit = CycleBindingCircuit::new(instance.clone());
+            let proof = prover.prove(params, &circuit, std::slice::from_ref(&instance)).unwrap();
+            (proof, instance)
+        })
+        .collect()
+}
+
+#[test]
+fn test_fold_and_verify() {
+    // Test: Folded proofs verify with the aggregator's key
+    let params = Params::<EqAffine>::new(K);
+    let prover = CycleProver::<EqAffine>::new(&params, &CycleBindingCircuit::shape(LIMBS)).unwrap();
+    let aggregator = RecursiveAggregator::new(prover.vk().clone());
+
+    let aggregated = aggregator
+        .fold(&params, &proofs(&params, &prover, 3))
+        .unwrap();
+    assert_eq!(aggregated.len(), 3);
+    assert_eq!(aggregator.verify(&params, &aggregated), Ok(()));
+
+    // A single proof aggregates too
+    let single = aggregator
+        .fold(&params, &proofs(&params, &prover, 1))
+        .unwrap();
+    assert_eq!(aggregator.verify(&params, &single), Ok(()));
+
+    assert!(matches!(
+        aggregator.fold(&params, &[]),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+}
+
+#[test]
+fn test_fold_rejects_invalid_proof() {
+    // Test: A proof for other inputs is rejected while folding
+    let params = Params::<EqAffine>::new(K);
+    let prover = CycleProver::<EqAffine>::new(&params, &CycleBindingCircuit::shape(LIMBS)).unwrap();
+    let aggregator = RecursiveAggregator::new(prover.vk().clone());
+
+    let mut proofs = proofs(&params, &prover, 2);
+    proofs[1].1[0] += Fr::ONE;
+    assert!(aggregator.fold(&params, &proofs).is_err());
+}
+
+#[test]
+fn test_verify_rejects_tampered_aggregate() {
+    // Test: Swapped deferred points, a moved accumulator and changed inputs fail
+    let params = Params::<EqAffine>::new(K);
+    let prover = CycleProver::<EqAffine>::new(&params, &CycleBindingCircuit::shape(LIMBS)).unwrap();
+    let aggregator = RecursiveAggregator::new(prover.vk().clone());
+    let aggregated = aggregator
+        .fold(&params, &proofs(&params, &prover, 2))
+        .unwrap();
+
+    let mut swapped = aggregated.clone();
+    swapped.deferred.swap(0, 1);
+    assert!(aggregator.verify(&params, &swapped).is_err());
+
+    let mut moved = aggregated.clone();
+    moved.accumulator = aggregated.deferred[0];
+    assert!(matches!(
+        aggregator.verify(&params, &moved),
+        Err(VerifyError::InvalidProof(_))
+    ));
+
+    let mut inputs = aggregated.clone();
+    inputs.instances[0][1] += Fr::ONE;
+    assert!(aggregator.verify(&params, &inputs).is_err());
+
+    let mut short = aggregated.clone();
+    short.deferred.pop();
+    assert!(matches!(
+        aggregator.verify(&params, &short),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+
+    let mut other_key = aggregated;
+    other_key.vk_fingerprint ^= 1;
+    assert!(matches!(
+        aggregator.verify(&params, &other_key),
+        Err(VerifyError::WrongCircuitVersion { .. })
+    ));
+}
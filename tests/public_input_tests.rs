@@ -0,0 +1,271 @@
+use halo2_proofs::{circuit::Value, dev::MockProver};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::prover::request_nonce;
+use poneglyphdb::sql::{query_hash, QueryResult};
+
+// Public input binding tests
+// Paper Section 5.1: Public data is exposed in the instance column
+
+/// Scanned rows of `SELECT SUM(price) FROM orders`
+fn scan() -> TableScan {
+    TableScan::new(vec!["price".to_string()], vec![vec![30], vec![70]])
+}
+
+fn circuit_with_nonce(nonce: Fr) -> PoneglyphCircuit {
+    circuit_with(nonce, ExpiryBound::Never)
+}
+
+fn circuit_with(nonce: Fr, expiry: ExpiryBound) -> PoneglyphCircuit {
+    PoneglyphCircuit {
+        scan: scan(),
+        query_result: Value::known(Fr::from(100)),
+        nonce: Value::known(nonce),
+        expiry: Value::known(expiry.to_field()),
+        query_hash: Fr::from(0),
+        result_commitment: Value::known(Fr::from(0)),
+        result_columns: vec![],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: vec![],
+        aggregations: vec![AggregationOp {
+            group_keys: vec![0, 0],
+            values: vec![30, 70],
+            agg_type: AggregationType::Sum,
+            column: Some("price".to_string()),
+        }],
+    }
+}
+
+#[test]
+fn test_nonce_bound_to_instance() {
+    // Test: Proof verifies with the nonce it was generated for
+    let k = 10;
+    let nonce = request_nonce(b"request-1").unwrap();
+    let circuit = circuit_with_nonce(nonce);
+    let public_inputs = PoneglyphConfig::get_public_input_layout_with_nonce(
+        scan().commitment(),
+        Fr::from(100),
+        nonce,
+    );
+    let prover = MockProver::run(k, &circuit, public_inputs).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_nonce_replay_rejected() {
+    // Test: Same proof presented as the answer to another request fails
+    let k = 10;
+    let nonce = request_nonce(b"request-1").unwrap();
+    let other_nonce = request_nonce(b"request-2").unwrap();
+    let circuit = circuit_with_nonce(nonce);
+    let public_inputs = PoneglyphConfig::get_public_input_layout_with_nonce(
+        scan().commitment(),
+        Fr::from(100),
+        other_nonce,
+    );
+    let prover = MockProver::run(k, &circuit, public_inputs).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_request_nonce_is_injective() {
+    // Test: Distinct request ids give distinct nonces
+    assert_ne!(request_nonce(b"a").unwrap(), request_nonce(b"a\0").unwrap());
+    assert_ne!(
+        request_nonce(b"abc").unwrap(),
+        request_nonce(b"abd").unwrap()
+    );
+    assert!(request_nonce(&[0u8; 32]).is_err());
+}
+
+#[test]
+fn test_expiry_bound_to_instance() {
+    // Test: Expiry bound is part of the statement
+    let k = 10;
+    let expiry = ExpiryBound::BlockHeight(1_000);
+    let circuit = circuit_with(Fr::from(0), expiry);
+
+    let public_inputs = PublicInputs::new(scan().commitment(), Fr::from(100))
+        .with_expiry(expiry)
+        .to_instance();
+    let prover = MockProver::run(k, &circuit, public_inputs).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // Extending the bound after proving is not possible
+    let extended = PublicInputs::new(scan().commitment(), Fr::from(100))
+        .with_expiry(ExpiryBound::BlockHeight(2_000))
+        .to_instance();
+    let prover = MockProver::run(k, &circuit, extended).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_expiry_bound_encoding() {
+    // Test: Encoding roundtrip and freshness rules
+    for bound in [
+        ExpiryBound::Never,
+        ExpiryBound::BlockHeight(0),
+        ExpiryBound::BlockHeight(u64::MAX),
+        ExpiryBound::Timestamp(1_700_000_000),
+    ] {
+        assert_eq!(ExpiryBound::from_field(bound.to_field()), Some(bound));
+    }
+    assert_eq!(ExpiryBound::from_field(Fr::from(5)), None);
+
+    let bound = ExpiryBound::BlockHeight(100);
+    assert!(bound.is_fresh_at(ExpiryBound::BlockHeight(100)));
+    assert!(!bound.is_fresh_at(ExpiryBound::BlockHeight(101)));
+    assert!(!bound.is_fresh_at(ExpiryBound::Timestamp(50)));
+    assert!(ExpiryBound::Never.is_fresh_at(ExpiryBound::Timestamp(u64::MAX)));
+}
+
+#[test]
+fn test_thresholds_in_instance() {
+    // Test: Range check thresholds are public inputs in ThresholdMode::Instance
+    let k = 10;
+    let mut circuit = circuit_with(Fr::from(0), ExpiryBound::Never);
+    circuit.threshold_mode = ThresholdMode::Instance;
+    circuit.range_checks = vec![
+        RangeCheckOp {
+            value: Value::known(5),
+            threshold: 10,
+            u: 0,
+        },
+        RangeCheckOp {
+            value: Value::known(50),
+            threshold: 10,
+            u: 0,
+        },
+    ];
+
+    let public_inputs =
+        PublicInputs::new(scan().commitment(), Fr::from(100)).with_thresholds(vec![10, 10]);
+    assert_eq!(
+        PublicInputs::from_instance(&public_inputs.to_instance()),
+        Some(public_inputs.clone())
+    );
+    let prover = MockProver::run(k, &circuit, public_inputs.to_instance()).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // The verifier's threshold must match the one used by the prover
+    let other = PublicInputs::new(scan().commitment(), Fr::from(100)).with_thresholds(vec![10, 60]);
+    let prover = MockProver::run(k, &circuit, other.to_instance()).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn test_statement_bound_to_instance() {
+    // Test: Database commitment, query result and query hash are all checked
+    let k = 10;
+    let sql = "SELECT SUM(price) FROM orders";
+    let mut circuit = circuit_with(Fr::from(0), ExpiryBound::Never);
+    circuit.query_hash = query_hash(sql);
+
+    let inputs =
+        PublicInputs::new(scan().commitment(), Fr::from(100)).with_query_hash(query_hash(sql));
+    let prover = MockProver::run(k, &circuit, inputs.to_instance()).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    assert_eq!(
+        PublicInputs::from_instance(&inputs.to_instance()),
+        Some(inputs.clone())
+    );
+
+    for forged in [
+        PublicInputs {
+            db_commitment: Fr::from(43),
+            ..inputs.clone()
+        },
+        PublicInputs {
+            query_result: Fr::from(101),
+            ..inputs.clone()
+        },
+        inputs
+            .clone()
+            .with_query_hash(query_hash("SELECT SUM(price) FROM orders WHERE price > 10")),
+    ] {
+        let prover = MockProver::run(k, &circuit, forged.to_instance()).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // Case and whitespace don't change the hash
+    assert_eq!(
+        query_hash(sql),
+        query_hash("select  sum(price)\n from orders")
+    );
+
+    // Quoted text does: literals and quoted names are compared as written
+    assert_ne!(
+        query_hash("SELECT COUNT(*) FROM users WHERE name = 'Alice'"),
+        query_hash("SELECT COUNT(*) FROM users WHERE name = 'alice'")
+    );
+    assert_ne!(
+        query_hash("SELECT COUNT(*) FROM users WHERE name = 'a  b'"),
+        query_hash("SELECT COUNT(*) FROM users WHERE name = 'a b'")
+    );
+    assert_eq!(
+        query_hash("SELECT COUNT(*) FROM users WHERE name = 'it''s'"),
+        query_hash("select count(*)  from users where NAME = 'it''s'")
+    );
+}
+
+#[test]
+fn test_result_commitment_bound_to_instance() {
+    // Test: The result commitment row is hashed in-circuit from the result
+    let k = 10;
+    let result = QueryResult {
+        columns: vec!["sum(price)".to_string()],
+        rows: vec![vec![Some(100)]],
+    };
+    let mut circuit = circuit_with(Fr::from(0), ExpiryBound::Never);
+    circuit.result_columns = result.columns.clone();
+
+    let inputs = PublicInputs::new(scan().commitment(), Fr::from(100))
+        .with_result_commitment(result.commitment());
+    let prover = MockProver::run(k, &circuit, inputs.to_instance()).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    assert_eq!(
+        PublicInputs::from_instance(&inputs.to_instance()),
+        Some(inputs.clone())
+    );
+
+    // Test: An honest proof paired with another result fails, even with that
+    // result's commitment as the witness: another value, another column
+    // name, more rows
+    let rows = QueryResult {
+        columns: vec!["id".to_string(), "price".to_string()],
+        rows: vec![vec![Some(1), Some(30)], vec![Some(2), None]],
+    };
+    let forgeries = [
+        QueryResult {
+            columns: vec!["sum(price)".to_string()],
+            rows: vec![vec![Some(101)]],
+        },
+        QueryResult {
+            columns: vec!["total".to_string()],
+            rows: vec![vec![Some(100)]],
+        },
+        rows.clone(),
+    ];
+    for other in forgeries {
+        let mut forged_circuit = circuit.clone();
+        forged_circuit.result_commitment = Value::known(other.commitment());
+        let forged = inputs.clone().with_result_commitment(other.commitment());
+        let prover = MockProver::run(k, &forged_circuit, forged.to_instance()).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // Canonical encoding
+    let bytes = rows.to_bytes();
+    assert_eq!(QueryResult::from_bytes(&bytes), Ok(rows));
+    for malformed in [
+        &bytes[..bytes.len() - 1],
+        &[bytes.as_slice(), &[0u8][..]].concat()[..],
+        &[&[2u8][..], &bytes[1..]].concat()[..],
+    ] {
+        assert!(QueryResult::from_bytes(malformed).is_err());
+    }
+}
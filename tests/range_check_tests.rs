@@ -62,8 +62,9 @@ impl Circuit<Fr> for RangeCheckTestCircuit {
             value,
         )?;
         
-        // x < t check (u value must be greater than threshold)
-        let u = self.threshold + 1000; // u > threshold must hold
+        // x < t check, with a window wide enough for x (|x - t| < u), as
+        // the SQL compiler picks it
+        let u = self.value.abs_diff(self.threshold).saturating_add(1);
         let _check = range_check_chip.check_less_than(
             layouter.namespace(|| "check less than"),
             value,
@@ -155,3 +156,266 @@ fn test_range_check_large_value() {
     assert_eq!(prover.verify(), Ok(()));
 }
 
+
+/// x < t test circuit with an explicit comparison window u
+#[derive(Clone)]
+struct LessThanWindowCircuit {
+    value: u64,
+    threshold: u64,
+    u: u64,
+}
+
+impl Circuit<Fr> for LessThanWindowCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: 0,
+            threshold: self.threshold,
+            u: self.u,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        RangeCheckTestCircuit::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::new(config.range_check_config);
+        let check = range_check_chip.check_less_than(
+            layouter.namespace(|| "check less than"),
+            Value::known(self.value),
+            self.threshold,
+            self.u,
+        )?;
+        check
+            .value()
+            .assert_if_known(|v| **v == Fr::from((self.value < self.threshold) as u64));
+
+        Ok(())
+    }
+}
+
+fn verify_window(value: u64, threshold: u64, u: u64) -> bool {
+    let circuit = LessThanWindowCircuit {
+        value,
+        threshold,
+        u,
+    };
+    MockProver::run(10, &circuit, vec![vec![]])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+#[test]
+fn test_range_check_large_window() {
+    // Test: u >= 256 is decomposed, x anywhere in [t - u, t + u) verifies
+    assert!(verify_window(500, 1000, 1000));
+    assert!(verify_window(1999, 1000, 1000));
+    assert!(verify_window(0, u64::MAX, u64::MAX));
+    assert!(verify_window(u64::MAX - 1, 0, u64::MAX));
+    assert!(verify_window(1 << 40, 1 << 41, 1 << 41));
+}
+
+#[test]
+fn test_range_check_large_window_enforced() {
+    // Test: u >= 256 no longer leaves diff unconstrained, x outside the window fails
+    assert!(!verify_window(2000, 1000, 1000));
+    assert!(!verify_window(5000, 1000, 256));
+    assert!(!verify_window(0, 1001, 1000));
+}
+
+#[test]
+fn test_range_check_small_window() {
+    // Test: u < 256 uses the lookup table directly, with the same window
+    assert!(verify_window(990, 1000, 20));
+    assert!(verify_window(1019, 1000, 20));
+    assert!(!verify_window(1020, 1000, 20));
+    assert!(!verify_window(979, 1000, 20));
+}
+
+/// decompose_bits test circuit, chunk width fixed at configuration
+#[derive(Clone)]
+struct DecomposeBitsCircuit<const CHUNK_BITS: u32> {
+    value: u128,
+    n_bits: u32,
+}
+
+impl<const CHUNK_BITS: u32> Circuit<Fr> for DecomposeBitsCircuit<CHUNK_BITS> {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: 0,
+            n_bits: self.n_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let params = DecompositionParams::new(CHUNK_BITS, (128 / CHUNK_BITS) as usize);
+        let poneglyph_config = PoneglyphConfig::configure_with(meta, params);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            range_check_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::new(config.range_check_config);
+        let chunks = range_check_chip.decompose_bits(
+            layouter.namespace(|| "decompose bits"),
+            Value::known(self.value),
+            self.n_bits,
+        )?;
+        assert_eq!(chunks.len(), (self.n_bits / CHUNK_BITS) as usize);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_decompose_128bit() {
+    // Test: 128-bit value (e.g. 1000 ETH in wei) in 16 chunks of 8 bits
+    let wei = 1000 * 10u128.pow(18);
+    for value in [wei, u128::MAX, 0] {
+        let circuit = DecomposeBitsCircuit::<8> { value, n_bits: 128 };
+        let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
+
+#[test]
+fn test_decompose_bits_out_of_range() {
+    // Test: a value wider than n_bits doesn't verify
+    let circuit = DecomposeBitsCircuit::<8> {
+        value: 1 << 72,
+        n_bits: 72,
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert!(prover.verify().is_err());
+
+    let circuit = DecomposeBitsCircuit::<8> {
+        value: (1 << 72) - 1,
+        n_bits: 72,
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_decompose_bits_invalid_width() {
+    // Test: widths that aren't whole chunks, or exceed max_chunks, are rejected
+    for n_bits in [0, 12, 136] {
+        let circuit = DecomposeBitsCircuit::<8> { value: 0, n_bits };
+        assert!(MockProver::run(10, &circuit, vec![vec![]]).is_err());
+    }
+}
+
+#[test]
+fn test_decompose_16bit_chunks() {
+    // Test: 8 chunks of 16 bits against the 2^16-row table (k >= 17)
+    let circuit = DecomposeBitsCircuit::<16> {
+        value: u128::MAX - 1,
+        n_bits: 128,
+    };
+    let prover = MockProver::run(17, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+/// decompose_many test circuit; `per_value` uses one decompose_64bit region per value
+#[derive(Clone)]
+struct DecomposeManyCircuit {
+    values: Vec<u64>,
+    per_value: bool,
+}
+
+impl Circuit<Fr> for DecomposeManyCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![0; self.values.len()],
+            per_value: self.per_value,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            range_check_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let range_check_chip = RangeCheckChip::new(config.range_check_config);
+        let values: Vec<Value<u64>> = self.values.iter().map(|&v| Value::known(v)).collect();
+        if self.per_value {
+            for (i, &value) in values.iter().enumerate() {
+                range_check_chip
+                    .decompose_64bit(layouter.namespace(|| format!("value {}", i)), value)?;
+            }
+        } else {
+            let chunks =
+                range_check_chip.decompose_many(layouter.namespace(|| "values"), &values)?;
+            assert_eq!(chunks.len(), values.len());
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_decompose_many() {
+    // Test: values packed one per row verify, including 0 and u64::MAX
+    let circuit = DecomposeManyCircuit {
+        values: vec![0, 1, 255, 256, 1 << 40, u64::MAX],
+        per_value: false,
+    };
+    let prover = MockProver::run(10, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_decompose_many_rows() {
+    // Test: 300 values fit in 2^9 rows shared, but not in per-value regions
+    let values: Vec<u64> = (0..300).map(|i| i * 1_000_003).collect();
+    let circuit = DecomposeManyCircuit {
+        values: values.clone(),
+        per_value: false,
+    };
+    let prover = MockProver::run(9, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let circuit = DecomposeManyCircuit {
+        values,
+        per_value: true,
+    };
+    assert!(MockProver::run(9, &circuit, vec![vec![]]).is_err());
+}
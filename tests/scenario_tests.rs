@@ -0,0 +1,32 @@
+use poneglyphdb::examples_lib::*;
+
+/// End-to-end scenario tests
+/// The library-driven demos: file ingestion, commitment, SQL, real proofs and
+/// verification from the published bytes alone
+
+#[test]
+fn test_payroll_demo() {
+    // Test: Every payroll query is proven and verified with its claimed value
+    let report = run_payroll_demo().unwrap();
+    assert_eq!(report.table, "payroll");
+    assert_eq!(report.rows, 8);
+    assert_eq!(report.queries.len(), 2);
+    assert_eq!(report.value("SELECT SUM(salary) FROM payroll"), Some(44600));
+    assert_eq!(report.value("SELECT COUNT(*) FROM payroll"), Some(8));
+    assert!(report.queries.iter().all(|q| q.proof_bytes > 0));
+    assert!(report.to_string().contains("verified"));
+}
+
+#[test]
+fn test_reserves_demo() {
+    // Test: The liabilities of a JSON export are proven over its commitment
+    let report = run_reserves_demo().unwrap();
+    assert_eq!(report.table, "balances");
+    assert_eq!(report.rows, 6);
+    assert_eq!(
+        report.value("SELECT SUM(balance) FROM balances"),
+        Some(71750)
+    );
+    assert_eq!(report.value("SELECT COUNT(*) FROM balances"), Some(6));
+    assert_eq!(report.commitment.len(), 64);
+}
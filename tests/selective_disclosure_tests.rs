@@ -0,0 +1,182 @@
+use halo2_proofs::{circuit::Value, dev::MockProver, pasta::EqAffine, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::error::VerifyError;
+use poneglyphdb::prover::{Prover, Verifier};
+use poneglyphdb::sql::{query_hash, QueryResult};
+
+// Selective disclosure tests
+// The query result stays private; instance row 1 only holds whether a
+// public predicate over it holds
+
+const K: u32 = 10;
+const SQL: &str = "SELECT SUM(price) FROM orders";
+
+/// Scanned rows: one order of `total`, so `SUM(price)` is `total`
+fn scan(total: u64) -> TableScan {
+    TableScan::new(vec!["price".to_string()], vec![vec![total]])
+}
+
+fn circuit(total: u64, predicate: ResultPredicate) -> PoneglyphCircuit {
+    PoneglyphCircuit {
+        scan: scan(total),
+        query_result: Value::unknown(),
+        nonce: Value::known(Fr::from(0)),
+        expiry: Value::known(ExpiryBound::Never.to_field()),
+        query_hash: query_hash(SQL),
+        result_commitment: Value::known(Fr::from(0)),
+        result_columns: vec![],
+        result_predicate: Some(predicate),
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: vec![],
+        aggregations: vec![AggregationOp {
+            group_keys: vec![0],
+            values: vec![total],
+            agg_type: AggregationType::Sum,
+            column: Some("price".to_string()),
+        }],
+    }
+}
+
+fn instance(total: u64, outcome: Fr) -> Vec<Vec<Fr>> {
+    PublicInputs::new(scan(total).commitment(), outcome)
+        .with_query_hash(query_hash(SQL))
+        .to_instance()
+}
+
+fn mock_verify(result: u64, predicate: ResultPredicate, outcome: Fr) -> bool {
+    let circuit = circuit(result, predicate);
+    let prover = MockProver::run(K, &circuit, instance(result, outcome)).unwrap();
+    prover.verify().is_ok()
+}
+
+#[test]
+fn test_predicate_outcome_is_public() {
+    // Test: Only the true outcome of the predicate verifies
+    let cases = [
+        (ResultPredicate::Above(1_000_000), 1_500_000, true),
+        (ResultPredicate::Above(1_000_000), 1_000_000, false),
+        (ResultPredicate::Above(0), 0, false),
+        (ResultPredicate::Below(300), 299, true),
+        (ResultPredicate::Below(300), 300, false),
+        (ResultPredicate::Below(0), 0, false),
+        (ResultPredicate::Above(u64::MAX - 1), u64::MAX, true),
+    ];
+    for (predicate, result, holds) in cases {
+        assert_eq!(
+            predicate.holds(result),
+            holds,
+            "{} for {}",
+            predicate,
+            result
+        );
+        assert!(
+            mock_verify(result, predicate, ResultPredicate::outcome(holds)),
+            "{} for {}",
+            predicate,
+            result
+        );
+        assert!(
+            !mock_verify(result, predicate, ResultPredicate::outcome(!holds)),
+            "{} for {}",
+            predicate,
+            result
+        );
+    }
+}
+
+#[test]
+fn test_result_not_in_instance() {
+    // Test: The instance with the result itself (the public mode) fails
+    let predicate = ResultPredicate::Above(1_000_000);
+    assert!(!mock_verify(1_500_000, predicate, Fr::from(1_500_000)));
+    assert!(!mock_verify(1, predicate, Fr::from(2)));
+}
+
+#[test]
+fn test_predicate_over_aggregation_result() {
+    // Test: The predicate compares the aggregation result, not a claimed one
+    let predicate = ResultPredicate::Above(1_000_000);
+    let mut circuit = circuit(500, predicate);
+    circuit.query_result = Value::known(Fr::from(1_500_000));
+    let prover =
+        MockProver::run(K, &circuit, instance(500, ResultPredicate::outcome(true))).unwrap();
+    assert!(prover.verify().is_err());
+
+    // Test: Without an aggregation there is no result to compare
+    circuit.aggregations.clear();
+    assert!(MockProver::run(K, &circuit, instance(500, ResultPredicate::outcome(false))).is_err());
+}
+
+#[test]
+fn test_expected_predicate_inputs() {
+    // Test: The prover's expected inputs carry the outcome and no result commitment
+    let result = QueryResult {
+        columns: vec!["sum(price)".to_string()],
+        rows: vec![vec![Some(1_500_000)]],
+    };
+    let inputs = result
+        .expected_predicate_inputs(
+            scan(1_500_000).commitment(),
+            SQL,
+            ResultPredicate::Above(1_000_000),
+        )
+        .unwrap();
+    assert_eq!(
+        inputs.to_instance(),
+        instance(1_500_000, ResultPredicate::outcome(true))
+    );
+    assert_eq!(inputs.result_commitment, Fr::from(0));
+
+    let rows = QueryResult {
+        rows: vec![vec![Some(1)], vec![Some(2)]],
+        ..result
+    };
+    assert!(rows
+        .expected_predicate_inputs(scan(3).commitment(), SQL, ResultPredicate::Below(3))
+        .is_err());
+}
+
+#[test]
+fn test_verify_predicate() {
+    // Test: A real proof tells the verifier the outcome, for this query only
+    let params = Params::<EqAffine>::new(K);
+    let predicate = ResultPredicate::Above(1_000_000);
+    let shape = circuit(0, predicate);
+    let prover = Prover::new(&params, &shape).unwrap();
+    let verifier = Verifier::new(&params, &shape).unwrap();
+
+    let public = instance(1_500_000, ResultPredicate::outcome(true));
+    let proof = prover
+        .prove(&params, &circuit(1_500_000, predicate), &public)
+        .unwrap();
+    assert_eq!(
+        verifier.verify_predicate(&params, &proof, &public, SQL),
+        Ok(true)
+    );
+
+    assert!(verifier
+        .verify_predicate(
+            &params,
+            &proof,
+            &instance(1_500_000, ResultPredicate::outcome(false)),
+            SQL
+        )
+        .is_err());
+    assert!(matches!(
+        verifier.verify_predicate(
+            &params,
+            &proof,
+            &instance(1_500_000, Fr::from(1_500_000)),
+            SQL
+        ),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+    assert!(matches!(
+        verifier.verify_predicate(&params, &proof, &public, "SELECT SUM(price) FROM refunds"),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+}
@@ -0,0 +1,143 @@
+use halo2_proofs::{circuit::Value, dev::MockProver};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+// Self-join tests
+// Both sides of `FROM t a JOIN t b` are views over the same committed
+// table; aliases decide which side each column belongs to
+
+fn tables() -> HashMap<String, HashMap<String, Vec<u64>>> {
+    let mut employees = HashMap::new();
+    employees.insert("id".to_string(), vec![1, 2, 3, 4, 5]);
+    employees.insert("mgr".to_string(), vec![0, 1, 1, 2, 2]);
+    employees.insert("salary".to_string(), vec![300, 200, 150, 120, 90]);
+    let mut table_data = HashMap::new();
+    table_data.insert("employees".to_string(), employees);
+    table_data
+}
+
+#[test]
+fn test_parse_self_join() {
+    // Test: Aliases resolve each ON column to its side, in either order
+    for sql in [
+        "SELECT e.salary FROM employees e JOIN employees m ON e.mgr = m.id",
+        "SELECT e.salary FROM employees AS e INNER JOIN employees AS m ON m.id = e.mgr",
+    ] {
+        let query = SQLParser::parse(sql).unwrap();
+        assert_eq!(query.from, "employees");
+        assert_eq!(query.from_alias.as_deref(), Some("e"));
+        assert_eq!(query.columns, vec!["salary".to_string()]);
+
+        let joins = query.joins.as_ref().unwrap();
+        assert_eq!(joins.len(), 1);
+        assert_eq!(joins[0].table, "employees");
+        assert_eq!(joins[0].alias.as_deref(), Some("m"));
+        assert_eq!(joins[0].on.left_column, "mgr");
+        assert_eq!(joins[0].on.right_column, "id");
+        assert!(query.is_provable(), "{}", sql);
+    }
+}
+
+#[test]
+fn test_from_alias_qualifiers() {
+    // Test: Columns qualified with the FROM alias resolve to the FROM table
+    let query = SQLParser::parse(
+        "SELECT e.id, SUM(e.salary) FROM employees e WHERE e.salary > 100 GROUP BY e.id ORDER BY e.id",
+    )
+    .unwrap();
+    assert_eq!(query.from, "employees");
+    assert!(query.joins.is_none());
+    assert_eq!(
+        query.columns,
+        vec!["id".to_string(), "sum(salary)".to_string()]
+    );
+    assert_eq!(query.group_by, Some(vec!["id".to_string()]));
+    assert_eq!(query.order_by.as_ref().unwrap()[0].column, "id");
+    assert!(matches!(
+        &query.where_clause,
+        Some(WhereClause::GreaterThan { column, value: 100 }) if column == "salary"
+    ));
+
+    let result = ReferenceExecutor::execute(&query, &tables()).unwrap();
+    assert_eq!(result.rows.len(), 4);
+}
+
+#[test]
+fn test_compile_self_join() {
+    // Test: The join witness takes both key columns from the one table
+    let query = SQLParser::parse(
+        "SELECT e.salary FROM employees e JOIN employees m ON e.mgr = m.id WHERE e.salary > 100",
+    )
+    .unwrap();
+    let compiled = SQLCompiler::compile(&query, &tables()).unwrap();
+    assert_eq!(compiled.joins.len(), 1);
+    assert_eq!(compiled.joins[0].table1_keys, vec![0, 1, 1, 2, 2]);
+    assert_eq!(compiled.joins[0].table2_keys, vec![1, 2, 3, 4, 5]);
+    assert_eq!(compiled.joins[0].kind, JoinKind::Inner);
+    assert_eq!(compiled.range_checks.len(), 5);
+
+    let circuit = PoneglyphCircuit {
+        scan: TableScan::default(),
+        query_result: Value::known(Fr::from(100)),
+        nonce: Value::known(Fr::from(0)),
+        expiry: Value::known(ExpiryBound::Never.to_field()),
+        query_hash: Fr::from(0),
+        result_commitment: Value::known(Fr::from(0)),
+        result_columns: vec![],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: compiled.joins,
+        aggregations: vec![],
+    };
+    let public_inputs =
+        PoneglyphConfig::get_public_input_layout(TableScan::default().commitment(), Fr::from(100));
+    let prover = MockProver::run(12, &circuit, public_inputs).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_self_join_statement() {
+    // Test: The statement names each side by alias
+    let query =
+        SQLParser::parse("SELECT e.salary FROM employees e JOIN employees m ON m.id = e.mgr")
+            .unwrap();
+    assert_eq!(
+        query.statement().joins,
+        vec!["INNER JOIN employees m ON e.mgr = m.id".to_string()]
+    );
+}
+
+#[test]
+fn test_self_join_errors() {
+    // Test: Self-joins need aliases and qualified columns
+    let errors = [
+        (
+            "SELECT salary FROM employees JOIN employees ON mgr = id",
+            "joined with itself",
+        ),
+        (
+            "SELECT e.salary FROM employees e JOIN employees m ON mgr = id",
+            "ambiguous",
+        ),
+        (
+            "SELECT e.salary FROM employees e JOIN employees e ON e.mgr = e.id",
+            "used twice",
+        ),
+    ];
+    for (sql, message) in errors {
+        let err = SQLParser::parse(sql).unwrap_err();
+        assert!(err.contains(message), "{}: {}", sql, err);
+    }
+
+    // Outer joins stay outside the provable subset
+    let query =
+        SQLParser::parse("SELECT e.salary FROM employees e LEFT JOIN employees m ON e.mgr = m.id")
+            .unwrap();
+    assert!(query.joins.is_none());
+    assert_eq!(query.unprovable, vec![Unprovable::Join]);
+}
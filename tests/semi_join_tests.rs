@@ -0,0 +1,211 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use poneglyphdb::test_utils::fixtures::orders_and_vip;
+
+/// Semi / anti join test circuit
+/// `cell_keys` are the values of the table 1 key cells, `table1_keys` unless
+/// a test forges them
+#[derive(Clone)]
+struct SemiJoinTestCircuit {
+    table1_keys: Vec<u64>,
+    cell_keys: Vec<u64>,
+    table2_keys: Vec<u64>,
+    kind: JoinKind,
+}
+
+impl SemiJoinTestCircuit {
+    fn new(table1_keys: Vec<u64>, table2_keys: Vec<u64>, kind: JoinKind) -> Self {
+        Self {
+            cell_keys: table1_keys.clone(),
+            table1_keys,
+            table2_keys,
+            kind,
+        }
+    }
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    join_config: JoinConfig,
+}
+
+impl Circuit<Fr> for SemiJoinTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let sort_config = SortChip::configure(meta, &poneglyph_config, &range_check_config);
+        let join_config =
+            JoinChip::configure(meta, &poneglyph_config, &range_check_config, &sort_config);
+
+        TestConfig {
+            poneglyph_config,
+            join_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let join_chip = JoinChip::new(config.join_config);
+        let key_cells = join_chip.assign_keys(layouter.namespace(|| "keys"), &self.cell_keys)?;
+        let selected = join_chip.semi_join_and_verify(
+            layouter.namespace(|| "semi join"),
+            &self.table1_keys,
+            &key_cells,
+            &self.table2_keys,
+            self.kind,
+        )?;
+
+        assert_eq!(selected.len(), self.table1_keys.len());
+        for (cell, &key) in selected.iter().zip(self.table1_keys.iter()) {
+            let expected = self.kind.keeps(key, &self.table2_keys);
+            cell.value()
+                .assert_if_known(|v| **v == Fr::from(expected as u64));
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_semi_join() {
+    // Test: table 1 rows with a match (EXISTS)
+    let circuit = SemiJoinTestCircuit::new(vec![1, 2, 3, 2, 7], vec![3, 2, 5], JoinKind::Semi);
+    let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_anti_join() {
+    // Test: table 1 rows without a match (NOT EXISTS), keys outside table 2's range
+    let circuit =
+        SemiJoinTestCircuit::new(vec![0, 4, 9, u64::MAX, 5], vec![5, 1, 8], JoinKind::Anti);
+    let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_semi_join_empty_table2() {
+    // Test: nothing matches an empty table 2
+    for kind in [JoinKind::Semi, JoinKind::Anti] {
+        let circuit = SemiJoinTestCircuit::new(vec![0, 3, u64::MAX], vec![], kind);
+        let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
+
+#[test]
+fn test_semi_join_rejects_inner() {
+    // Test: inner joins go through join_and_verify
+    let circuit = SemiJoinTestCircuit::new(vec![1], vec![1], JoinKind::Inner);
+    assert!(MockProver::run(11, &circuit, vec![vec![]]).is_err());
+}
+
+#[test]
+fn test_semi_join_binds_key_cells() {
+    // Test: the keys are the table 1 cells, not the laid-out keys; a key cell
+    // of 5 cannot be shown to miss table 2 from the gap (3, 5) of key 4
+    let mut circuit = SemiJoinTestCircuit::new(vec![1, 4], vec![3, 5], JoinKind::Anti);
+    circuit.cell_keys = vec![1, 5];
+    let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+    assert!(prover.verify().is_err());
+
+    // Test: a key cell with no key to lay it out
+    circuit.cell_keys = vec![1];
+    assert!(MockProver::run(11, &circuit, vec![vec![]]).is_err());
+}
+
+#[test]
+fn test_sql_not_in() {
+    let query =
+        SQLParser::parse("SELECT amount FROM orders WHERE customer NOT IN (SELECT id FROM vip)")
+            .unwrap();
+    assert!(matches!(
+        query.where_clause,
+        Some(WhereClause::SemiJoin {
+            join_type: JoinType::Anti,
+            ..
+        })
+    ));
+
+    let compiled = SQLCompiler::compile(&query, &orders_and_vip()).unwrap();
+    assert_eq!(compiled.semi_joins.len(), 1);
+    let semi_join = &compiled.semi_joins[0];
+    assert_eq!(semi_join.op.kind, JoinKind::Anti);
+    assert_eq!(semi_join.op.table1_keys, vec![1, 2, 3, 2]);
+    assert_eq!(semi_join.op.table2_keys, vec![2, 3]);
+    // The keys are copied from the scanned column
+    assert_eq!(semi_join.op.table1_column.as_deref(), Some("customer"));
+
+    let result = ReferenceExecutor::execute(&query, &orders_and_vip()).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(50)]]);
+}
+
+#[test]
+fn test_sql_exists() {
+    // Test: correlated EXISTS is decorrelated into a semi join
+    let query = SQLParser::parse(
+        "SELECT amount FROM orders WHERE EXISTS (SELECT * FROM vip v WHERE v.id = customer AND v.tier > 1)",
+    )
+    .unwrap();
+    match &query.where_clause {
+        Some(WhereClause::SemiJoin {
+            column,
+            subquery,
+            join_type,
+        }) => {
+            assert_eq!(column, "customer");
+            assert_eq!(subquery.from, "vip");
+            assert_eq!(subquery.columns, vec!["id".to_string()]);
+            assert!(matches!(join_type, JoinType::Semi));
+        }
+        other => panic!("unexpected clause {:?}", other),
+    }
+
+    let compiled = SQLCompiler::compile(&query, &orders_and_vip()).unwrap();
+    assert_eq!(compiled.semi_joins[0].op.kind, JoinKind::Semi);
+    assert_eq!(compiled.semi_joins[0].op.table2_keys, vec![3]);
+
+    let result = ReferenceExecutor::execute(&query, &orders_and_vip()).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(80)]]);
+}
+
+#[test]
+fn test_sql_not_exists() {
+    let query = SQLParser::parse(
+        "SELECT amount FROM orders WHERE NOT EXISTS (SELECT id FROM vip WHERE vip.id = orders.customer)",
+    )
+    .unwrap();
+    let compiled = SQLCompiler::compile(&query, &orders_and_vip()).unwrap();
+    assert_eq!(compiled.semi_joins[0].op.kind, JoinKind::Anti);
+
+    let result = ReferenceExecutor::execute(&query, &orders_and_vip()).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(50)]]);
+}
+
+#[test]
+fn test_sql_exists_errors() {
+    // Uncorrelated EXISTS has no join key
+    assert!(
+        SQLParser::parse("SELECT amount FROM orders WHERE EXISTS (SELECT id FROM vip)").is_err()
+    );
+}
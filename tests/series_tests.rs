@@ -0,0 +1,217 @@
+use ff::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+// generate_series tests
+// Series Gate (MockProver) and table-valued functions in SQL
+
+/// Series test circuit
+/// `values` overrides the honest series to check the gate rejects it
+#[derive(Clone)]
+struct SeriesTestCircuit {
+    op: SeriesOp,
+    values: Option<Vec<u64>>,
+}
+
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    series_config: SeriesConfig,
+}
+
+impl Circuit<Fr> for SeriesTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let series_config = SeriesChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            series_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let Some(values) = &self.values else {
+            let cells = SeriesChip::new(config.series_config)
+                .assign(layouter.namespace(|| "series"), &self.op)?;
+            assert_eq!(cells.len(), self.op.values().len());
+            return Ok(());
+        };
+
+        // Same layout as `SeriesChip::assign`, with the given values
+        let c = &config.series_config;
+        let op = &self.op;
+        let last = *values.last().unwrap();
+        let (below, above) = layouter.assign_region(
+            || "tampered series",
+            |mut region| {
+                for (row, &value) in values.iter().enumerate() {
+                    let constant = if row == 0 {
+                        c.start_selector.enable(&mut region, row)?;
+                        op.start
+                    } else {
+                        c.step_selector.enable(&mut region, row)?;
+                        op.step
+                    };
+                    let constant = Value::known(Fr::from(constant));
+                    region.assign_fixed(|| "constant", c.constant_column, row, || constant)?;
+                    let value = Value::known(Fr::from(value));
+                    region.assign_advice(|| "value", c.value_column, row, || value)?;
+                }
+                let row = values.len();
+                c.bound_selector.enable(&mut region, row)?;
+                let stop = Value::known(Fr::from(op.stop));
+                let step = Value::known(Fr::from(op.step));
+                region.assign_fixed(|| "stop", c.constant_column, row, || stop)?;
+                region.assign_fixed(|| "step", c.bound_step_column, row, || step)?;
+                let below = Fr::from(op.stop) - Fr::from(last);
+                let above = Fr::from(last) + Fr::from(op.step) - Fr::ONE - Fr::from(op.stop);
+                let below = region.assign_advice(
+                    || "below",
+                    c.below_column,
+                    row,
+                    || Value::known(below),
+                )?;
+                let above = region.assign_advice(
+                    || "above",
+                    c.above_column,
+                    row,
+                    || Value::known(above),
+                )?;
+                Ok((below, above))
+            },
+        )?;
+        let range_check = RangeCheckChip::new(c.range_check_config.clone());
+        range_check.decompose_cell(layouter.namespace(|| "below"), &below)?;
+        range_check.decompose_cell(layouter.namespace(|| "above"), &above)?;
+        Ok(())
+    }
+}
+
+fn run(
+    op: SeriesOp,
+    values: Option<Vec<u64>>,
+) -> Result<(), Vec<halo2_proofs::dev::VerifyFailure>> {
+    let circuit = SeriesTestCircuit { op, values };
+    MockProver::run(10, &circuit, vec![vec![]])
+        .unwrap()
+        .verify()
+}
+
+#[test]
+fn test_series_values() {
+    // Test: Bounds are inclusive, the last value may fall short of stop
+    let op = |start, stop, step| SeriesOp { start, stop, step };
+    assert_eq!(op(1, 5, 1).values(), vec![1, 2, 3, 4, 5]);
+    assert_eq!(op(0, 10, 4).values(), vec![0, 4, 8]);
+    assert_eq!(op(7, 7, 3).values(), vec![7]);
+    assert!(op(5, 1, 1).values().is_empty());
+    assert_eq!(op(u64::MAX - 1, u64::MAX, 5).values(), vec![u64::MAX - 1]);
+}
+
+#[test]
+fn test_series_gate() {
+    // Test: Honest series of several shapes pass
+    for (start, stop, step) in [(1, 5, 1), (0, 10, 4), (7, 7, 3), (5, 1, 1)] {
+        assert_eq!(run(SeriesOp { start, stop, step }, None), Ok(()));
+    }
+}
+
+#[test]
+fn test_series_gate_rejects_tampering() {
+    // Test: A skipped value, a wrong start and a missing last row fail
+    let op = SeriesOp {
+        start: 1,
+        stop: 4,
+        step: 1,
+    };
+    assert_eq!(run(op.clone(), Some(vec![1, 2, 3, 4])), Ok(()));
+    assert!(run(op.clone(), Some(vec![1, 2, 4, 5])).is_err());
+    assert!(run(op.clone(), Some(vec![2, 3, 4])).is_err());
+    assert!(run(op, Some(vec![1, 2, 3])).is_err());
+}
+
+fn sales() -> HashMap<String, HashMap<String, Vec<u64>>> {
+    let mut sales = HashMap::new();
+    sales.insert("day".to_string(), vec![1, 2, 4, 4, 7]);
+    sales.insert("amount".to_string(), vec![10, 20, 5, 5, 30]);
+    HashMap::from([("sales".to_string(), sales)])
+}
+
+#[test]
+fn test_generate_series_in_sql() {
+    // Test: FROM generate_series is parsed, compiled and executed as a relation
+    let query =
+        SQLParser::parse("SELECT d FROM generate_series(1, 7) AS days(d) WHERE d > 3").unwrap();
+    assert_eq!(query.from, "days");
+    assert_eq!(
+        query.table_function.as_ref().unwrap().to_string(),
+        "generate_series(1, 7, 1) AS days(d)"
+    );
+    assert!(query.is_provable());
+    assert!(query.referenced_tables().is_empty());
+
+    let compiled = SQLCompiler::compile(&query, &sales()).unwrap();
+    assert_eq!(
+        compiled.series,
+        vec![SeriesOp {
+            start: 1,
+            stop: 7,
+            step: 1
+        }]
+    );
+    let result = ReferenceExecutor::execute(&query, &sales()).unwrap();
+    let days: Vec<_> = result.rows.iter().map(|row| row[0].unwrap()).collect();
+    assert_eq!(days, vec![4, 5, 6, 7]);
+
+    // Default names and step
+    let query = SQLParser::parse("SELECT generate_series FROM generate_series(0, 6, 3)").unwrap();
+    let result = ReferenceExecutor::execute(&query, &sales()).unwrap();
+    assert_eq!(
+        result.rows,
+        vec![vec![Some(0)], vec![Some(3)], vec![Some(6)]]
+    );
+}
+
+#[test]
+fn test_gap_filling() {
+    // Test: Days without sales, from a series and a subquery
+    let query = SQLParser::parse(
+        "SELECT d FROM generate_series(1, 7) d WHERE NOT d IN (SELECT day FROM sales)",
+    )
+    .unwrap();
+    let result = ReferenceExecutor::execute(&query, &sales()).unwrap();
+    let gaps: Vec<_> = result.rows.iter().map(|row| row[0].unwrap()).collect();
+    assert_eq!(gaps, vec![3, 5, 6]);
+}
+
+#[test]
+fn test_generate_series_errors() {
+    // Test: Non-constant arguments, zero step and shadowing aliases fail
+    assert!(SQLParser::parse("SELECT x FROM generate_series(1) x").is_err());
+    assert!(SQLParser::parse("SELECT x FROM generate_series(1, day) x").is_err());
+    assert!(SQLParser::parse("SELECT x FROM generate_series(1, 5, 0) x").is_err());
+    let query = SQLParser::parse("SELECT day FROM generate_series(1, 3) sales(day)").unwrap();
+    assert!(SQLCompiler::compile(&query, &sales()).is_err());
+}
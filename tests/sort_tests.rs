@@ -17,7 +17,6 @@ struct SortTestCircuit {
 #[derive(Clone)]
 struct TestConfig {
     poneglyph_config: PoneglyphConfig,
-    range_check_config: RangeCheckConfig,
     sort_config: SortConfig,
 }
 
@@ -38,7 +37,6 @@ impl Circuit<Fr> for SortTestCircuit {
         
         TestConfig {
             poneglyph_config,
-            range_check_config,
             sort_config,
         }
     }
@@ -0,0 +1,26 @@
+use poneglyphdb::prover::*;
+
+/// Soundness regression tests
+/// Every adversarial witness in the corpus must be rejected
+
+#[test]
+fn test_soundness_corpus_rejected() {
+    let k = 10;
+    let corpus = soundness_corpus();
+    assert!(!corpus.is_empty());
+    for case in &corpus {
+        case.assert_rejected(k);
+    }
+}
+
+#[test]
+fn test_soundness_case_reports_reason() {
+    // Test: a rejected case explains how it was rejected
+    let k = 10;
+    let case = soundness_corpus()
+        .into_iter()
+        .find(|c| c.name == "join_forged_match")
+        .unwrap();
+    let reason = case.check(k).unwrap();
+    assert!(reason.contains("constraint failure"));
+}
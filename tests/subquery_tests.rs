@@ -0,0 +1,215 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use poneglyphdb::test_utils::fixtures::orders_and_vip;
+
+/// Subquery test circuit
+#[derive(Clone)]
+struct SubqueryTestCircuit {
+    /// Inner aggregation of a scalar subquery (None for IN)
+    inner: Option<AggregationOp>,
+    op: SubqueryOp,
+    /// Expected selected flag per outer row
+    expected: Vec<bool>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    aggregation_config: AggregationConfig,
+    subquery_config: SubqueryConfig,
+}
+
+impl Circuit<Fr> for SubqueryTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let aggregation_config = AggregationChip::configure(
+            meta,
+            &poneglyph_config,
+            &group_by_config,
+            &range_check_config,
+        );
+        let boolean_config = BooleanChip::configure(meta, &poneglyph_config);
+        let subquery_config = SubqueryChip::configure(
+            meta,
+            &poneglyph_config,
+            &range_check_config,
+            &boolean_config,
+        );
+
+        TestConfig {
+            poneglyph_config,
+            aggregation_config,
+            subquery_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let aggregation_chip = AggregationChip::new(config.aggregation_config);
+        let subquery_chip = SubqueryChip::new(config.subquery_config);
+
+        let result = match &self.inner {
+            Some(inner) => vec![subquery_chip.scalar_result(
+                layouter.namespace(|| "inner query"),
+                &aggregation_chip,
+                inner,
+            )?],
+            None => {
+                subquery_chip.assign_list(layouter.namespace(|| "inner query"), &self.op.result)?
+            }
+        };
+        let selected =
+            subquery_chip.filter(layouter.namespace(|| "outer filter"), &self.op, &result)?;
+
+        assert_eq!(selected.len(), self.expected.len());
+        for (cell, &expected) in selected.iter().zip(self.expected.iter()) {
+            cell.value()
+                .assert_if_known(|v| **v == Fr::from(expected as u64));
+        }
+
+        Ok(())
+    }
+}
+
+fn scalar_circuit(
+    filter: SubqueryFilter,
+    values: Vec<u64>,
+    inner: Vec<u64>,
+) -> SubqueryTestCircuit {
+    let max = *inner.iter().max().unwrap();
+    let expected = values
+        .iter()
+        .map(|&v| filter.evaluate(v, &[max]).unwrap())
+        .collect();
+    SubqueryTestCircuit {
+        inner: Some(AggregationOp {
+            group_keys: vec![],
+            values: inner,
+            agg_type: AggregationType::Max,
+            column: None,
+        }),
+        op: SubqueryOp {
+            filter,
+            values,
+            result: vec![max],
+        },
+        expected,
+    }
+}
+
+#[test]
+fn test_scalar_subquery_filters() {
+    // Test: value op (SELECT MAX(x) FROM t) with MAX = 30
+    let k = 12;
+    for filter in [
+        SubqueryFilter::LessThan,
+        SubqueryFilter::GreaterThan,
+        SubqueryFilter::Equal,
+    ] {
+        let circuit = scalar_circuit(filter, vec![10, 30, 45], vec![20, 30, 5]);
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
+
+#[test]
+fn test_in_subquery_filter() {
+    // Test: value IN (3, 7, 9)
+    let k = 12;
+    let circuit = SubqueryTestCircuit {
+        inner: None,
+        op: SubqueryOp {
+            filter: SubqueryFilter::In,
+            values: vec![7, 4, 9, 0],
+            result: vec![3, 7, 9],
+        },
+        expected: vec![true, false, true, false],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_scalar_subquery() {
+    let query = SQLParser::parse(
+        "SELECT customer FROM orders WHERE amount < (SELECT MAX(amount) FROM orders) ORDER BY customer",
+    )
+    .unwrap();
+    assert_eq!(query.from, "orders");
+    assert!(query.order_by.is_some());
+    match &query.where_clause {
+        Some(WhereClause::CompareSubquery {
+            column, subquery, ..
+        }) => {
+            assert_eq!(column, "amount");
+            assert_eq!(subquery.columns, vec!["max(amount)".to_string()]);
+        }
+        other => panic!("unexpected clause {:?}", other),
+    }
+
+    let compiled = SQLCompiler::compile(&query, &orders_and_vip()).unwrap();
+    assert_eq!(compiled.subqueries.len(), 1);
+    let subquery = &compiled.subqueries[0];
+    assert_eq!(subquery.filter.filter, SubqueryFilter::LessThan);
+    assert_eq!(subquery.filter.result, vec![200]);
+    assert_eq!(
+        subquery.inner.aggregations[0].agg_type,
+        AggregationType::Max
+    );
+
+    let result = ReferenceExecutor::execute(&query, &orders_and_vip()).unwrap();
+    assert_eq!(
+        result.rows,
+        vec![vec![Some(1)], vec![Some(2)], vec![Some(3)]]
+    );
+}
+
+#[test]
+fn test_sql_in_subquery() {
+    let query = SQLParser::parse(
+        "SELECT amount FROM orders WHERE customer IN (SELECT id FROM vip WHERE tier > 1)",
+    )
+    .unwrap();
+    assert!(matches!(
+        query.where_clause,
+        Some(WhereClause::InSubquery { .. })
+    ));
+
+    let compiled = SQLCompiler::compile(&query, &orders_and_vip()).unwrap();
+    assert_eq!(compiled.subqueries[0].filter.filter, SubqueryFilter::In);
+    assert_eq!(compiled.subqueries[0].filter.result, vec![3]);
+    assert_eq!(compiled.subqueries[0].inner.range_checks.len(), 2);
+
+    let result = ReferenceExecutor::execute(&query, &orders_and_vip()).unwrap();
+    assert_eq!(result.rows, vec![vec![Some(80)]]);
+}
+
+#[test]
+fn test_sql_subquery_errors() {
+    // Scalar subqueries must be a single ungrouped aggregate
+    let query =
+        SQLParser::parse("SELECT amount FROM orders WHERE amount > (SELECT id FROM vip)").unwrap();
+    assert!(SQLCompiler::compile(&query, &orders_and_vip()).is_err());
+    assert!(ReferenceExecutor::execute(&query, &orders_and_vip()).is_err());
+}
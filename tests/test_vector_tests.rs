@@ -0,0 +1,191 @@
+use halo2_proofs::{circuit::Value, pasta::EqAffine, poly::commitment::Params};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::error::VerifyError;
+use poneglyphdb::prover::*;
+use poneglyphdb::sql::{query_hash, QueryResult};
+use poneglyphdb::utils::hex_to_bytes;
+
+// Cross-language test vector tests
+// The JSON emitted here is what the Solidity/WASM/Python verifiers consume.
+// Set `PONEGLYPH_TEST_VECTORS=<path>` to write the generated file.
+
+const K: u32 = 10;
+
+#[test]
+fn test_vectors_expected_results() {
+    // Test: Only the honest vector verifies
+    let set = TestVectorSet::generate(K).unwrap();
+    assert_eq!(set.abi_version, INSTANCE_ABI_VERSION);
+    assert_eq!(set.instance_layout.len(), NUM_INSTANCE_ROWS);
+
+    for vector in &set.vectors {
+        assert_eq!(vector.expected_valid, vector.name == "valid", "{}", vector.name);
+    }
+
+    if let Ok(path) = std::env::var("PONEGLYPH_TEST_VECTORS") {
+        std::fs::write(path, set.to_json().unwrap()).unwrap();
+    }
+}
+
+#[test]
+fn test_vectors_roundtrip_through_json() {
+    // Test: Decoded vectors reproduce the expected results
+    let set = TestVectorSet::generate(K).unwrap();
+    let parsed = TestVectorSet::from_json(&set.to_json().unwrap()).unwrap();
+    assert_eq!(parsed, set);
+
+    let params = Params::<EqAffine>::new(K);
+    let verifier = Verifier::new(&params, &shape()).unwrap();
+    for vector in &parsed.vectors {
+        let instance = vector.instance_fields().unwrap();
+        let proof = vector.proof_bytes().unwrap();
+        let valid = verifier.verify(&params, &proof, &instance).is_ok();
+        assert_eq!(valid, vector.expected_valid, "{}", vector.name);
+    }
+}
+
+#[test]
+fn test_verify_error_outcomes() {
+    // Test: Each rejection is reported as its own VerifyError variant
+    let set = TestVectorSet::generate(K).unwrap();
+    let params = Params::<EqAffine>::new(K);
+    let verifier = Verifier::new(&params, &shape()).unwrap();
+    let valid = set.vectors.iter().find(|v| v.name == "valid").unwrap();
+    let instance = valid.instance_fields().unwrap();
+    let proof = valid.proof_bytes().unwrap();
+    let inputs = PublicInputs::from_instance(&instance).unwrap();
+
+    assert_eq!(verifier.verify(&params, &proof, &instance), Ok(()));
+
+    let tampered = set.vectors.iter().find(|v| v.name == "tampered_proof").unwrap();
+    assert!(matches!(
+        verifier.verify(&params, &tampered.proof_bytes().unwrap(), &instance),
+        Err(VerifyError::InvalidProof(_))
+    ));
+
+    assert!(matches!(
+        verifier.verify(&params, &proof[..proof.len() / 2], &instance),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+
+    assert!(matches!(
+        verifier.verify_for_request(&params, &proof, &instance, Fr::from(7)),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+    assert!(matches!(
+        verifier.verify_fresh(&params, &proof, &instance, ExpiryBound::BlockHeight(2_000_000)),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+    assert_eq!(
+        verifier.verify_fresh(&params, &proof, &instance, ExpiryBound::BlockHeight(1)),
+        Ok(())
+    );
+
+    assert_eq!(
+        verifier.verify_against_commitment(&params, &proof, &instance, Fr::from(43)),
+        Err(VerifyError::CommitmentMismatch {
+            expected: Fr::from(43),
+            found: inputs.db_commitment,
+        })
+    );
+
+    assert!(matches!(
+        verifier.verify_for_query(&params, &proof, &instance, "SELECT COUNT(price) FROM orders"),
+        Err(VerifyError::InstanceMismatch(_))
+    ));
+    assert_eq!(
+        verifier.verify_for_query(&params, &proof, &instance, "select sum(price)\n  from orders"),
+        Ok(())
+    );
+
+    let version = verifier.circuit_version();
+    assert_eq!(verifier.check_circuit_version(version), Ok(()));
+    assert_eq!(
+        verifier.check_circuit_version(version ^ 1),
+        Err(VerifyError::WrongCircuitVersion {
+            expected: version,
+            found: version ^ 1,
+        })
+    );
+}
+
+#[test]
+fn test_verify_and_extract() {
+    // Test: The revealed result is returned only if it is the committed one
+    let set = TestVectorSet::generate(K).unwrap();
+    let params = Params::<EqAffine>::new(K);
+    let verifier = Verifier::new(&params, &shape()).unwrap();
+    let valid = set.vectors.iter().find(|v| v.name == "valid").unwrap();
+    let instance = valid.instance_fields().unwrap();
+    let proof = valid.proof_bytes().unwrap();
+    let result_bytes = hex_to_bytes(&set.result).unwrap();
+
+    let result = verifier
+        .verify_and_extract(&params, &proof, &instance, &result_bytes)
+        .unwrap();
+    assert_eq!(result.columns, vec!["sum(price)".to_string()]);
+    assert_eq!(result.rows, vec![vec![Some(100)]]);
+
+    // Another result, with the same value under another column name
+    let other = QueryResult {
+        rows: vec![vec![Some(101)]],
+        ..result.clone()
+    };
+    let renamed = QueryResult {
+        columns: vec!["total".to_string()],
+        ..result.clone()
+    };
+    for forged in [other, renamed] {
+        assert!(matches!(
+            verifier.verify_and_extract(&params, &proof, &instance, &forged.to_bytes()),
+            Err(VerifyError::InstanceMismatch(_))
+        ));
+    }
+    assert!(matches!(
+        verifier.verify_and_extract(&params, &proof, &instance, &result_bytes[1..]),
+        Err(VerifyError::MalformedEnvelope(_))
+    ));
+
+    // The committed result with a forged instance still fails the proof
+    let forged = set
+        .vectors
+        .iter()
+        .find(|v| v.name == "wrong_result_commitment")
+        .unwrap();
+    assert!(verifier
+        .verify_and_extract(
+            &params,
+            &proof,
+            &forged.instance_fields().unwrap(),
+            &result_bytes
+        )
+        .is_err());
+}
+
+/// Circuit of `TestVectorSet::generate` without its values: the key depends
+/// on the row counts and the query hash
+fn shape() -> PoneglyphCircuit {
+    let columns = vec!["id".to_string(), "price".to_string()];
+    PoneglyphCircuit {
+        scan: TableScan::new(columns, vec![vec![0, 0]; 2]),
+        query_result: Value::unknown(),
+        nonce: Value::unknown(),
+        expiry: Value::unknown(),
+        query_hash: query_hash("SELECT SUM(price) FROM orders"),
+        result_commitment: Value::unknown(),
+        result_columns: vec!["sum(price)".to_string()],
+        result_predicate: None,
+        threshold_mode: ThresholdMode::Fixed,
+        range_checks: vec![],
+        sorts: vec![],
+        group_bys: vec![],
+        joins: vec![],
+        aggregations: vec![AggregationOp {
+            group_keys: vec![0, 0],
+            values: vec![0, 0],
+            agg_type: AggregationType::Sum,
+            column: Some("price".to_string()),
+        }],
+    }
+}
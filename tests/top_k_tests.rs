@@ -0,0 +1,145 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+
+/// Top-K Gate test circuit
+#[derive(Clone)]
+struct TopKTestCircuit {
+    group_keys: Vec<u64>,
+    values: Vec<u64>,
+    k: u64,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    sort_config: SortConfig,
+    group_by_config: GroupByConfig,
+    top_k_config: TopKConfig,
+}
+
+impl Circuit<Fr> for TopKTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let sort_config = SortChip::configure(meta, &poneglyph_config, &range_check_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let top_k_config = TopKChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            sort_config,
+            group_by_config,
+            top_k_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let sort_chip = SortChip::new(config.sort_config);
+        let group_by_chip = GroupByChip::new(config.group_by_config);
+        let top_k_chip = TopKChip::new(config.top_k_config);
+        let selected = top_k_chip.top_k(
+            layouter.namespace(|| "top-k"),
+            &group_by_chip,
+            &sort_chip,
+            &self.group_keys,
+            &self.values,
+            self.k,
+        )?;
+
+        let expected = top_k_per_group(&self.group_keys, &self.values, self.k);
+        assert_eq!(selected.len(), expected.len());
+        for (cells, expected) in selected.iter().zip(expected.iter()) {
+            assert_eq!(cells.len(), expected.len());
+            for (cell, &value) in cells.iter().zip(expected.iter()) {
+                cell.value().assert_if_known(|v| **v == Fr::from(value));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_top_k_reference() {
+    // Test: reference top-k per group, largest first
+    let group_keys = [1, 1, 1, 1, 2, 2, 3];
+    let values = [5, 9, 1, 7, 4, 8, 6];
+    assert_eq!(
+        top_k_per_group(&group_keys, &values, 3),
+        vec![vec![9, 7, 5], vec![8, 4], vec![6]]
+    );
+    assert_eq!(
+        top_k_per_group(&group_keys, &values, 1),
+        vec![vec![9], vec![8], vec![6]]
+    );
+    assert!(top_k_per_group(&[], &[], 2).is_empty());
+}
+
+#[test]
+fn test_top_3_per_category() {
+    // Test: top 3 products per category
+    let circuit = TopKTestCircuit {
+        group_keys: vec![1, 1, 1, 1, 1, 2, 2, 3],
+        values: vec![50, 20, 90, 70, 10, 40, 60, 30],
+        k: 3,
+    };
+    let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_top_k_ties_and_large_k() {
+    // Test: tied values and k larger than every group
+    let circuit = TopKTestCircuit {
+        group_keys: vec![4, 4, 4, 9, 9],
+        values: vec![7, 7, 3, u64::MAX, 0],
+        k: 2,
+    };
+    let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let circuit = TopKTestCircuit {
+        group_keys: vec![4, 4, 4, 9, 9],
+        values: vec![7, 7, 3, u64::MAX, 0],
+        k: 10,
+    };
+    let prover = MockProver::run(11, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_top_k_rejects() {
+    // Test: k = 0 and mismatched lengths are rejected
+    let zero = TopKTestCircuit {
+        group_keys: vec![1, 1],
+        values: vec![1, 2],
+        k: 0,
+    };
+    assert!(MockProver::run(11, &zero, vec![vec![]]).is_err());
+
+    let mismatched = TopKTestCircuit {
+        group_keys: vec![1, 1, 2],
+        values: vec![1, 2],
+        k: 1,
+    };
+    assert!(MockProver::run(11, &mismatched, vec![vec![]]).is_err());
+}
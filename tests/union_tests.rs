@@ -0,0 +1,157 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// UNION / UNION ALL test circuit
+#[derive(Clone)]
+struct UnionTestCircuit {
+    op: UnionOp,
+    /// Expected output relation
+    expected: Vec<u64>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    union_config: UnionConfig,
+}
+
+impl Circuit<Fr> for UnionTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let union_config = UnionChip::configure(meta, &poneglyph_config, &range_check_config);
+
+        TestConfig {
+            poneglyph_config,
+            union_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let union_chip = UnionChip::new(config.union_config);
+        let left = union_chip.assign_relation(layouter.namespace(|| "left"), &self.op.left)?;
+        let right = union_chip.assign_relation(layouter.namespace(|| "right"), &self.op.right)?;
+        let output =
+            union_chip.union_and_verify(layouter.namespace(|| "union"), &self.op, &left, &right)?;
+
+        assert_eq!(output.len(), self.expected.len());
+        for (cell, &expected) in output.iter().zip(self.expected.iter()) {
+            cell.value().assert_if_known(|v| **v == Fr::from(expected));
+        }
+
+        Ok(())
+    }
+}
+
+fn union_circuit(operation: SetOperation, left: Vec<u64>, right: Vec<u64>) -> UnionTestCircuit {
+    let expected = operation.evaluate(&left, &right);
+    UnionTestCircuit {
+        op: UnionOp {
+            operation,
+            left,
+            right,
+        },
+        expected,
+    }
+}
+
+#[test]
+fn test_union_all() {
+    // Test: {5, 1, 5} ++ {3, 1} = {1, 1, 3, 5, 5}
+    let k = 10;
+    let circuit = union_circuit(SetOperation::UnionAll, vec![5, 1, 5], vec![3, 1]);
+    assert_eq!(circuit.expected, vec![1, 1, 3, 5, 5]);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_union_distinct() {
+    // Test: {5, 1, 5} ∪ {3, 1} = {1, 3, 5}
+    let k = 10;
+    let circuit = union_circuit(SetOperation::Union, vec![5, 1, 5], vec![3, 1]);
+    assert_eq!(circuit.expected, vec![1, 3, 5]);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_union_with_empty_side() {
+    let k = 10;
+    let circuit = union_circuit(SetOperation::Union, vec![], vec![2, 2]);
+    assert_eq!(circuit.expected, vec![2]);
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+fn tables() -> HashMap<String, HashMap<String, Vec<u64>>> {
+    let mut current = HashMap::new();
+    current.insert("customer".to_string(), vec![1, 2, 3]);
+    let mut archived = HashMap::new();
+    archived.insert("customer".to_string(), vec![3, 4]);
+    let mut table_data = HashMap::new();
+    table_data.insert("current".to_string(), current);
+    table_data.insert("archived".to_string(), archived);
+    table_data
+}
+
+#[test]
+fn test_sql_union() {
+    let query =
+        SQLParser::parse("SELECT customer FROM current UNION SELECT customer FROM archived")
+            .unwrap();
+    assert_eq!(query.from, "current");
+    let union = query.union.as_ref().unwrap();
+    assert!(!union.all);
+    assert_eq!(union.query.from, "archived");
+
+    let compiled = SQLCompiler::compile(&query, &tables()).unwrap();
+    assert_eq!(compiled.unions.len(), 1);
+    assert_eq!(compiled.unions[0].op.operation, SetOperation::Union);
+    assert_eq!(compiled.unions[0].op.left, vec![1, 2, 3]);
+    assert_eq!(compiled.unions[0].op.right, vec![3, 4]);
+
+    let result = ReferenceExecutor::execute(&query, &tables()).unwrap();
+    let rows: Vec<u64> = result.rows.iter().map(|row| row[0].unwrap()).collect();
+    assert_eq!(rows, SetOperation::Union.evaluate(&[1, 2, 3], &[3, 4]));
+}
+
+#[test]
+fn test_sql_union_all_chain() {
+    let query = SQLParser::parse(
+        "SELECT customer FROM current UNION ALL SELECT customer FROM archived WHERE customer < 4 UNION ALL SELECT customer FROM current",
+    )
+    .unwrap();
+    let union = query.union.as_ref().unwrap();
+    assert!(union.all);
+    assert!(union.query.union.as_ref().unwrap().all);
+
+    let result = ReferenceExecutor::execute(&query, &tables()).unwrap();
+    assert_eq!(result.rows.len(), 7);
+
+    assert!(SQLParser::parse(
+        "SELECT customer FROM current UNION SELECT customer FROM archived UNION ALL SELECT customer FROM current"
+    )
+    .is_err());
+}
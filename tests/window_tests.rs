@@ -0,0 +1,209 @@
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use pasta_curves::pallas::Base as Fr;
+use poneglyphdb::circuit::*;
+use poneglyphdb::sql::*;
+use std::collections::HashMap;
+
+/// Window Gate test circuit
+#[derive(Clone)]
+struct WindowTestCircuit {
+    partition_keys: Vec<u64>,
+    order_keys: Vec<u64>,
+    /// SUM argument (empty = no running SUM)
+    values: Vec<u64>,
+}
+
+/// Config for test circuit
+#[derive(Clone)]
+struct TestConfig {
+    poneglyph_config: PoneglyphConfig,
+    group_by_config: GroupByConfig,
+    window_config: WindowConfig,
+}
+
+impl Circuit<Fr> for WindowTestCircuit {
+    type Config = TestConfig;
+    type FloorPlanner = halo2_proofs::circuit::SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let poneglyph_config = PoneglyphConfig::configure(meta);
+        let range_check_config = RangeCheckChip::configure(meta, &poneglyph_config);
+        let group_by_config = GroupByChip::configure(meta, &poneglyph_config, &range_check_config);
+        let window_config = WindowChip::configure(meta, &poneglyph_config);
+
+        TestConfig {
+            poneglyph_config,
+            group_by_config,
+            window_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl halo2_proofs::circuit::Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // Load lookup table
+        config.poneglyph_config.load_lookup_table(&mut layouter)?;
+
+        let group_by_chip = GroupByChip::new(config.group_by_config);
+        let window_chip = WindowChip::new(config.window_config);
+        let window = window_chip.rank_over(
+            layouter.namespace(|| "window"),
+            &group_by_chip,
+            &self.partition_keys,
+            &self.order_keys,
+        )?;
+
+        let expected = WindowFunction::Rank
+            .evaluate(&self.partition_keys, &self.order_keys, &[])
+            .unwrap();
+        for (cell, &rank) in window.ranks.iter().zip(expected.iter()) {
+            cell.value().assert_if_known(|v| **v == Fr::from(rank));
+        }
+
+        if !self.values.is_empty() {
+            let sums = window_chip.running_sum(
+                layouter.namespace(|| "running sum"),
+                &group_by_chip,
+                &self.partition_keys,
+                &self.values,
+            )?;
+            let expected = WindowFunction::Sum
+                .evaluate(&self.partition_keys, &self.order_keys, &self.values)
+                .unwrap();
+            for (cell, &sum) in sums.iter().zip(expected.iter()) {
+                cell.value().assert_if_known(|v| **v == Fr::from(sum));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_reference_values() {
+    let partition_keys = [1, 1, 1, 1, 2, 2];
+    let order_keys = [10, 20, 20, 30, 5, 5];
+    assert_eq!(
+        WindowFunction::RowNumber.evaluate(&partition_keys, &order_keys, &[]),
+        Some(vec![1, 2, 3, 4, 1, 2])
+    );
+    assert_eq!(
+        WindowFunction::Rank.evaluate(&partition_keys, &order_keys, &[]),
+        Some(vec![1, 2, 2, 4, 1, 1])
+    );
+    assert_eq!(
+        WindowFunction::Sum.evaluate(&partition_keys, &order_keys, &[1, 2, 3, 4, 5, 6]),
+        Some(vec![1, 3, 6, 10, 5, 11])
+    );
+    assert_eq!(
+        WindowFunction::Sum.evaluate(&[1, 1], &[1, 2], &[u64::MAX, 1]),
+        None
+    );
+}
+
+#[test]
+fn test_rank_over_partitions() {
+    // Test: counters reset at partition boundaries, ties share a rank
+    let k = 10;
+    let circuit = WindowTestCircuit {
+        partition_keys: vec![1, 1, 1, 1, 2, 2, 3],
+        order_keys: vec![10, 20, 20, 30, 5, 5, 7],
+        values: vec![],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_single_row() {
+    let k = 10;
+    let circuit = WindowTestCircuit {
+        partition_keys: vec![4],
+        order_keys: vec![9],
+        values: vec![],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_window_clause() {
+    // Test: OVER (PARTITION BY ... ORDER BY ...) compiles to a window operation
+    let query = SQLParser::parse(
+        "SELECT region, RANK() OVER (PARTITION BY region ORDER BY amount) FROM sales",
+    )
+    .unwrap();
+    let windows = query.windows.as_ref().unwrap();
+    assert_eq!(windows.len(), 1);
+    assert_eq!(windows[0].function, WindowFunction::Rank);
+    assert_eq!(windows[0].partition_by.as_deref(), Some("region"));
+    assert_eq!(windows[0].order_by, "amount");
+
+    let mut sales = HashMap::new();
+    sales.insert("region".to_string(), vec![2, 1, 2, 1]);
+    sales.insert("amount".to_string(), vec![7, 3, 5, 3]);
+    let mut table_data = HashMap::new();
+    table_data.insert("sales".to_string(), sales);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    assert_eq!(compiled.windows.len(), 1);
+    assert_eq!(compiled.windows[0].partition_keys, vec![1, 1, 2, 2]);
+    assert_eq!(compiled.windows[0].order_keys, vec![3, 3, 5, 7]);
+    assert!(compiled.arithmetic.is_empty());
+}
+
+#[test]
+fn test_sql_row_number_without_partition() {
+    let query = SQLParser::parse("SELECT ROW_NUMBER() OVER (ORDER BY id) FROM t").unwrap();
+    let window = &query.windows.unwrap()[0];
+    assert_eq!(window.function, WindowFunction::RowNumber);
+    assert_eq!(window.partition_by, None);
+
+    assert!(SQLParser::parse("SELECT ROW_NUMBER() OVER (PARTITION BY a) FROM t").is_err());
+}
+
+#[test]
+fn test_running_sum_resets_at_boundaries() {
+    // Test: SUM(amount) OVER (PARTITION BY account ORDER BY day)
+    let k = 10;
+    let circuit = WindowTestCircuit {
+        partition_keys: vec![1, 1, 1, 2, 2, 3],
+        order_keys: vec![1, 2, 3, 1, 2, 1],
+        values: vec![100, 50, 25, 7, 8, 42],
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
+
+#[test]
+fn test_sql_running_sum() {
+    let query = SQLParser::parse(
+        "SELECT account, SUM(amount) OVER (PARTITION BY account ORDER BY day) FROM ledger",
+    )
+    .unwrap();
+    assert!(query.aggregations.is_none());
+    let window = &query.windows.as_ref().unwrap()[0];
+    assert_eq!(window.function, WindowFunction::Sum);
+    assert_eq!(window.column.as_deref(), Some("amount"));
+
+    let mut ledger = HashMap::new();
+    ledger.insert("account".to_string(), vec![2, 1, 1]);
+    ledger.insert("day".to_string(), vec![1, 2, 1]);
+    ledger.insert("amount".to_string(), vec![9, 5, 3]);
+    let mut table_data = HashMap::new();
+    table_data.insert("ledger".to_string(), ledger);
+
+    let compiled = SQLCompiler::compile(&query, &table_data).unwrap();
+    let op = &compiled.windows[0];
+    assert_eq!(op.partition_keys, vec![1, 1, 2]);
+    assert_eq!(op.values, vec![3, 5, 9]);
+}